@@ -122,6 +122,7 @@ fn configure_validation(builder: Builder) -> Builder {
             ("CreateCollection.optimizers_config", ""),
             ("CreateCollection.vectors_config", ""),
             ("CreateCollection.quantization_config", ""),
+            ("CreateCollection.replica_placement", ""),
             ("UpdateCollection.collection_name", "length(min = 1, max = 255)"),
             ("UpdateCollection.optimizers_config", ""),
             ("UpdateCollection.params", ""),
@@ -136,6 +137,8 @@ fn configure_validation(builder: Builder) -> Builder {
             ("CollectionConfig.optimizers_config", ""),
             ("CollectionConfig.quantization_config", ""),
             ("CollectionParams.vectors_config", ""),
+            ("CollectionParams.replica_placement", ""),
+            ("ReplicaPlacement.spread_by_label", "length(min = 1)"),
             ("ChangeAliases.timeout", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
             ("ListCollectionAliasesRequest.collection_name", "length(min = 1, max = 255)"),
             ("HnswConfigDiff.ef_construct", "custom = \"crate::grpc::validate::validate_u64_range_min_4\""),
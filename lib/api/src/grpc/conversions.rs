@@ -16,8 +16,9 @@ use uuid::Uuid;
 use super::qdrant::raw_query::RawContextPair;
 use super::qdrant::{
     raw_query, start_from, BinaryQuantization, CompressionRatio, DatetimeRange, Direction,
-    GeoLineString, GroupId, MultiVectorComparator, MultiVectorConfig, OrderBy, OrderValue, Range,
-    RawVector, RecommendStrategy, ShardKeySelector, SparseIndices, StartFrom,
+    GeoLineString, GroupId, MultiVectorCentroidsConfig, MultiVectorComparator, MultiVectorConfig,
+    MultiVectorPoolingConfig, OrderBy, OrderValue, Range, RawVector, RecommendStrategy,
+    ShardKeySelector, SparseIndices, StartFrom,
 };
 use crate::grpc::models::{CollectionsResponse, VersionInfo};
 use crate::grpc::qdrant::condition::ConditionOneOf;
@@ -882,9 +883,69 @@ impl TryFrom<QuantizationConfig> for segment::types::QuantizationConfig {
 
 impl From<segment::types::MultiVectorConfig> for MultiVectorConfig {
     fn from(value: segment::types::MultiVectorConfig) -> Self {
+        let segment::types::MultiVectorConfig {
+            comparator,
+            pooling,
+            centroids,
+        } = value;
+        Self {
+            comparator: MultiVectorComparator::from(comparator) as i32,
+            pooling: pooling.map(MultiVectorPoolingConfig::from),
+            centroids: centroids.map(MultiVectorCentroidsConfig::from),
+        }
+    }
+}
+
+impl From<segment::types::MultiVectorPoolingConfig> for MultiVectorPoolingConfig {
+    fn from(value: segment::types::MultiVectorPoolingConfig) -> Self {
         Self {
-            comparator: MultiVectorComparator::from(value.comparator) as i32,
+            max_vectors: value.max_vectors as u64,
+        }
+    }
+}
+
+impl TryFrom<MultiVectorPoolingConfig> for segment::types::MultiVectorPoolingConfig {
+    type Error = Status;
+
+    fn try_from(value: MultiVectorPoolingConfig) -> Result<Self, Self::Error> {
+        if value.max_vectors == 0 {
+            return Err(Status::invalid_argument(
+                "Multivector pooling max_vectors must be at least 1",
+            ));
         }
+        Ok(Self {
+            max_vectors: value.max_vectors as usize,
+        })
+    }
+}
+
+impl From<segment::types::MultiVectorCentroidsConfig> for MultiVectorCentroidsConfig {
+    fn from(value: segment::types::MultiVectorCentroidsConfig) -> Self {
+        Self {
+            num_centroids: value.num_centroids as u64,
+            candidates_factor: value.candidates_factor.map(|factor| factor as u64),
+        }
+    }
+}
+
+impl TryFrom<MultiVectorCentroidsConfig> for segment::types::MultiVectorCentroidsConfig {
+    type Error = Status;
+
+    fn try_from(value: MultiVectorCentroidsConfig) -> Result<Self, Self::Error> {
+        if !(1..=65536).contains(&value.num_centroids) {
+            return Err(Status::invalid_argument(
+                "Multivector num_centroids must be in range [1, 65536]",
+            ));
+        }
+        if value.candidates_factor == Some(0) {
+            return Err(Status::invalid_argument(
+                "Multivector candidates_factor must be at least 1",
+            ));
+        }
+        Ok(Self {
+            num_centroids: value.num_centroids as usize,
+            candidates_factor: value.candidates_factor.map(|factor| factor as usize),
+        })
     }
 }
 
@@ -904,6 +965,8 @@ impl TryFrom<MultiVectorConfig> for segment::types::MultiVectorConfig {
             .ok_or_else(|| Status::invalid_argument("Unknown multi vector comparator"))?;
         Ok(segment::types::MultiVectorConfig {
             comparator: segment::types::MultiVectorComparator::from(comparator),
+            pooling: value.pooling.map(TryInto::try_into).transpose()?,
+            centroids: value.centroids.map(TryInto::try_into).transpose()?,
         })
     }
 }
@@ -118,6 +118,9 @@ pub struct SparseVectorParams {
     /// If set - apply modifier to the vector values
     #[prost(enumeration = "Modifier", optional, tag = "2")]
     pub modifier: ::core::option::Option<i32>,
+    /// Type of storage for sparse vectors
+    #[prost(enumeration = "SparseVectorStorageType", optional, tag = "3")]
+    pub storage_type: ::core::option::Option<i32>,
 }
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -132,10 +135,35 @@ pub struct SparseVectorConfig {
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MultiVectorPoolingConfig {
+    /// Maximal number of vectors to keep per multivector
+    #[prost(uint64, tag = "1")]
+    pub max_vectors: u64,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MultiVectorCentroidsConfig {
+    /// Number of centroids to cluster vectors around
+    #[prost(uint64, tag = "1")]
+    pub num_centroids: u64,
+    /// Number of candidates compared exactly, as a multiple of the requested limit. Default: 8
+    #[prost(uint64, optional, tag = "2")]
+    pub candidates_factor: ::core::option::Option<u64>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MultiVectorConfig {
     /// Comparator for multi-vector search
     #[prost(enumeration = "MultiVectorComparator", tag = "1")]
     pub comparator: i32,
+    /// Pool similar vectors of each multivector together on ingestion
+    #[prost(message, optional, tag = "2")]
+    pub pooling: ::core::option::Option<MultiVectorPoolingConfig>,
+    /// Build centroid index to select candidates before exact comparison
+    #[prost(message, optional, tag = "3")]
+    pub centroids: ::core::option::Option<MultiVectorCentroidsConfig>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -495,6 +523,16 @@ pub struct CreateCollection {
     /// Configuration for sparse vectors
     #[prost(message, optional, tag = "16")]
     pub sparse_vectors_config: ::core::option::Option<SparseVectorConfig>,
+    /// Type of storage for point payloads, must match `on_disk_payload` if both are set
+    #[prost(enumeration = "PayloadStorage", optional, tag = "17")]
+    pub payload_storage: ::core::option::Option<i32>,
+    /// Defines how optimized segments are persisted
+    #[prost(enumeration = "SegmentStorageFormat", optional, tag = "18")]
+    pub storage_format: ::core::option::Option<i32>,
+    /// Constraint on the placement of shard replicas across peers
+    #[prost(message, optional, tag = "19")]
+    #[validate]
+    pub replica_placement: ::core::option::Option<ReplicaPlacement>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -588,6 +626,26 @@ pub struct CollectionParams {
     /// Configuration for sparse vectors
     #[prost(message, optional, tag = "10")]
     pub sparse_vectors_config: ::core::option::Option<SparseVectorConfig>,
+    /// Type of storage for point payloads, if not set - defined by `on_disk_payload`
+    #[prost(enumeration = "PayloadStorage", optional, tag = "11")]
+    pub payload_storage: ::core::option::Option<i32>,
+    /// Defines how optimized segments are persisted
+    #[prost(enumeration = "SegmentStorageFormat", optional, tag = "12")]
+    pub storage_format: ::core::option::Option<i32>,
+    /// Constraint on the placement of shard replicas across peers
+    #[prost(message, optional, tag = "13")]
+    #[validate]
+    pub replica_placement: ::core::option::Option<ReplicaPlacement>,
+}
+#[derive(validator::Validate)]
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReplicaPlacement {
+    /// Spread replicas of each shard across peers with different values of this peer label
+    #[prost(string, tag = "1")]
+    #[validate(length(min = 1))]
+    pub spread_by_label: ::prost::alloc::string::String,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -606,6 +664,9 @@ pub struct CollectionParamsDiff {
     /// Fan-out every read request to these many additional remote nodes (and return first available response)
     #[prost(uint32, optional, tag = "4")]
     pub read_fan_out_factor: ::core::option::Option<u32>,
+    /// Type of storage for point payloads, existing segments are rebuilt with it
+    #[prost(enumeration = "PayloadStorage", optional, tag = "5")]
+    pub payload_storage: ::core::option::Option<i32>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -1170,6 +1231,35 @@ impl Modifier {
 #[derive(serde::Serialize)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+pub enum SparseVectorStorageType {
+    /// Store sparse vectors in RocksDB
+    OnDisk = 0,
+    /// Store sparse vectors in appendable mmap files
+    Mmap = 1,
+}
+impl SparseVectorStorageType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            SparseVectorStorageType::OnDisk => "OnDisk",
+            SparseVectorStorageType::Mmap => "Mmap",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "OnDisk" => Some(Self::OnDisk),
+            "Mmap" => Some(Self::Mmap),
+            _ => None,
+        }
+    }
+}
+#[derive(serde::Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
 pub enum MultiVectorComparator {
     MaxSim = 0,
 }
@@ -1407,6 +1497,68 @@ impl ShardingMethod {
 #[derive(serde::Serialize)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+pub enum PayloadStorage {
+    /// Payload is stored in RocksDB and cached in memory
+    InMemory = 0,
+    /// Payload is stored in RocksDB and read from disk every time it is requested
+    OnDisk = 1,
+    /// Payload is stored in append-only memory mapped pages
+    Mmap = 2,
+}
+impl PayloadStorage {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            PayloadStorage::InMemory => "InMemory",
+            PayloadStorage::OnDisk => "OnDisk",
+            PayloadStorage::Mmap => "Mmap",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "InMemory" => Some(Self::InMemory),
+            "OnDisk" => Some(Self::OnDisk),
+            "Mmap" => Some(Self::Mmap),
+            _ => None,
+        }
+    }
+}
+#[derive(serde::Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum SegmentStorageFormat {
+    /// Segment components are stored in RocksDB
+    RocksDb = 0,
+    /// Segment components are stored in flat files, only applies to non-appendable segments
+    Flat = 1,
+}
+impl SegmentStorageFormat {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            SegmentStorageFormat::RocksDb => "RocksDb",
+            SegmentStorageFormat::Flat => "Flat",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "RocksDb" => Some(Self::RocksDb),
+            "Flat" => Some(Self::Flat),
+            _ => None,
+        }
+    }
+}
+#[derive(serde::Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
 pub enum TokenizerType {
     Unknown = 0,
     Prefix = 1,
@@ -9946,6 +10098,35 @@ pub struct WaitOnConsensusCommitResponse {
     #[prost(bool, tag = "1")]
     pub ok: bool,
 }
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetPeerLoadRequest {}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ShardLoad {
+    /// Name of the collection
+    #[prost(string, tag = "1")]
+    pub collection_name: ::prost::alloc::string::String,
+    /// Id of the local shard
+    #[prost(uint32, tag = "2")]
+    pub shard_id: u32,
+    /// Disk usage of the local shard in bytes
+    #[prost(uint64, tag = "3")]
+    pub size_bytes: u64,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetPeerLoadResponse {
+    /// Free disk space in the storage directory, if known
+    #[prost(uint64, optional, tag = "1")]
+    pub free_disk_bytes: ::core::option::Option<u64>,
+    /// Local shards of the target node
+    #[prost(message, repeated, tag = "2")]
+    pub shards: ::prost::alloc::vec::Vec<ShardLoad>,
+}
 /// Generated client implementations.
 pub mod qdrant_internal_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -10087,6 +10268,33 @@ pub mod qdrant_internal_client {
                 );
             self.inner.unary(req, path, codec).await
         }
+        ///
+        /// Get free disk space and local shard sizes of the target node, used for rebalancing shards.
+        pub async fn get_peer_load(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetPeerLoadRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetPeerLoadResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.QdrantInternal/GetPeerLoad",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.QdrantInternal", "GetPeerLoad"));
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -10114,6 +10322,15 @@ pub mod qdrant_internal_server {
             tonic::Response<super::WaitOnConsensusCommitResponse>,
             tonic::Status,
         >;
+        ///
+        /// Get free disk space and local shard sizes of the target node, used for rebalancing shards.
+        async fn get_peer_load(
+            &self,
+            request: tonic::Request<super::GetPeerLoadRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetPeerLoadResponse>,
+            tonic::Status,
+        >;
     }
     #[derive(Debug)]
     pub struct QdrantInternalServer<T: QdrantInternal> {
@@ -10291,6 +10508,53 @@ pub mod qdrant_internal_server {
                     };
                     Box::pin(fut)
                 }
+                "/qdrant.QdrantInternal/GetPeerLoad" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetPeerLoadSvc<T: QdrantInternal>(pub Arc<T>);
+                    impl<
+                        T: QdrantInternal,
+                    > tonic::server::UnaryService<super::GetPeerLoadRequest>
+                    for GetPeerLoadSvc<T> {
+                        type Response = super::GetPeerLoadResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetPeerLoadRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as QdrantInternal>::get_peer_load(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetPeerLoadSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
@@ -11764,6 +12028,9 @@ pub struct RecoverShardSnapshotRequest {
     /// Optional API key used when fetching the snapshot from a remote URL
     #[prost(string, optional, tag = "6")]
     pub api_key: ::core::option::Option<::prost::alloc::string::String>,
+    /// Replay operations from the WAL archive on top of the snapshot, up to this time
+    #[prost(message, optional, tag = "7")]
+    pub recover_until: ::core::option::Option<::prost_wkt_types::Timestamp>,
 }
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
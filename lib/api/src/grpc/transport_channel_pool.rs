@@ -73,6 +73,9 @@ enum RequestFailure {
 }
 
 /// Intercepts gRPC requests and adds a default timeout if it wasn't already set.
+///
+/// With the `opentelemetry` feature, the trace context of the current span is propagated to the
+/// peer as well.
 pub struct AddTimeout {
     default_timeout: Duration,
 }
@@ -88,10 +91,36 @@ impl Interceptor for AddTimeout {
         if request.metadata().get("grpc-timeout").is_none() {
             request.set_timeout(self.default_timeout);
         }
+        #[cfg(feature = "opentelemetry")]
+        inject_trace_context(request.metadata_mut());
         Ok(request)
     }
 }
 
+/// Write the trace context of the current span into W3C `traceparent` and `tracestate` metadata
+#[cfg(feature = "opentelemetry")]
+fn inject_trace_context(metadata: &mut tonic::metadata::MetadataMap) {
+    use tracing_opentelemetry::OpenTelemetrySpanExt as _;
+
+    let context = tracing::Span::current().context();
+    opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut MetadataInjector(metadata))
+    });
+}
+
+#[cfg(feature = "opentelemetry")]
+struct MetadataInjector<'a>(&'a mut tonic::metadata::MetadataMap);
+
+#[cfg(feature = "opentelemetry")]
+impl opentelemetry::propagation::Injector for MetadataInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        let key = tonic::metadata::MetadataKey::from_bytes(key.as_bytes());
+        if let (Ok(key), Ok(value)) = (key, value.parse()) {
+            self.0.insert(key, value);
+        }
+    }
+}
+
 /// Holds a pool of channels established for a set of URIs.
 /// Channel are shared by cloning them.
 /// Make the `pool_size` larger to increase throughput.
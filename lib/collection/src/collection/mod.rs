@@ -26,6 +26,7 @@ use self::resharding::ReshardingState;
 use crate::collection::payload_index_schema::PayloadIndexSchema;
 use crate::collection_state::{ShardInfo, State};
 use crate::common::is_ready::IsReady;
+use crate::common::stoppable_task_async::CancellableAsyncTaskHandle;
 use crate::config::CollectionConfig;
 use crate::operations::shared_storage_config::SharedStorageConfig;
 use crate::operations::types::{CollectionError, CollectionResult, NodeType};
@@ -54,6 +55,7 @@ pub struct Collection {
     pub(crate) shared_storage_config: Arc<SharedStorageConfig>,
     pub(crate) payload_index_schema: SaveOnDisk<PayloadIndexSchema>,
     resharding_state: SaveOnDisk<Option<ReshardingState>>,
+    resharding_task: Mutex<Option<CancellableAsyncTaskHandle<bool>>>,
     this_peer_id: PeerId,
     path: PathBuf,
     snapshots_path: PathBuf,
@@ -146,6 +148,7 @@ impl Collection {
             payload_index_schema,
             shared_storage_config,
             resharding_state,
+            resharding_task: Default::default(),
             this_peer_id,
             path: path.to_owned(),
             snapshots_path: snapshots_path.to_owned(),
@@ -212,11 +215,8 @@ impl Collection {
         let resharding_state = Self::load_resharding_state(path)
             .expect("Can't load or initialize resharding progress");
 
-        let mut shard_holder = ShardHolder::new(
-            path,
-            resharding_state.read().as_ref().map(|state| state.shard_id),
-        )
-        .expect("Can not create shard holder");
+        let mut shard_holder = ShardHolder::new(path, resharding_state.read().clone())
+            .expect("Can not create shard holder");
 
         let shared_collection_config = Arc::new(RwLock::new(collection_config.clone()));
 
@@ -248,6 +248,7 @@ impl Collection {
             payload_index_schema,
             shared_storage_config,
             resharding_state,
+            resharding_task: Default::default(),
             this_peer_id,
             path: path.to_owned(),
             snapshots_path: snapshots_path.to_owned(),
@@ -657,105 +658,6 @@ impl Collection {
         Ok(())
     }
 
-    pub fn resharding_state(&self) -> Option<ReshardingState> {
-        self.resharding_state.read().clone()
-    }
-
-    pub async fn start_resharding(
-        &self,
-        peer_id: PeerId,
-        shard_id: ShardId,
-        shard_key: Option<ShardKey>,
-    ) -> CollectionResult<()> {
-        // TODO(resharding): Improve error handling?
-
-        let mut shard_holder = self.shards_holder.write().await;
-
-        if let Some(state) = self.resharding_state.read().deref() {
-            return Err(CollectionError::bad_request(format!(
-                "resharding of collection {} is already in progress: {state:?}",
-                self.id
-            )));
-        }
-
-        if shard_holder.get_shard(&shard_id).is_some() {
-            return Err(CollectionError::bad_shard_selection(format!(
-                "shard {shard_id} already exists in collection {}",
-                self.id
-            )));
-        }
-
-        let replica_set = self
-            .create_replica_set(shard_id, &[peer_id], Some(ReplicaState::Resharding))
-            .await?;
-
-        shard_holder.start_resharding(shard_id, replica_set, shard_key.clone())?;
-
-        self.resharding_state.write(|state| {
-            debug_assert!(
-                state.is_none(),
-                "resharding of collection {} is already in progress: {state:?}",
-                self.id
-            );
-
-            *state = Some(ReshardingState::new(peer_id, shard_id, shard_key));
-        })?;
-
-        Ok(())
-    }
-
-    pub async fn abort_resharding(
-        &self,
-        peer_id: PeerId,
-        shard_id: ShardId,
-        shard_key: Option<ShardKey>,
-    ) -> CollectionResult<()> {
-        let mut shard_holder = self.shards_holder.write().await;
-
-        let is_in_progress = if let Some(state) = self.resharding_state.read().deref() {
-            let is_in_progress = state.peer_id == peer_id
-                && state.shard_id == shard_id
-                && state.shard_key == shard_key;
-
-            if !is_in_progress {
-                return Err(CollectionError::bad_request(format!(
-                    "resharding of collection {} is not in progress",
-                    self.id,
-                )));
-            }
-
-            is_in_progress
-        } else {
-            log::warn!(
-                "aborting resharding of collection {} ({peer_id}/{shard_id}/{shard_key:?}),\
-                 but resharding is not in progress",
-                self.id,
-            );
-
-            false
-        };
-
-        if shard_holder.get_shard(&shard_id).is_none() {
-            log::warn!(
-                "aborting resharding of collection {} ({peer_id}/{shard_id}/{shard_key:?}), \
-                 but shard {shard_id} does not exist in collection",
-                self.id,
-            );
-        }
-
-        // TODO(resharding): Contextualize errors? 🤔
-        shard_holder
-            .abort_resharding(shard_id, peer_id, shard_key.clone(), is_in_progress)
-            .await?;
-
-        // TODO(resharding): Contextualize errors? 🤔
-        self.resharding_state.write(|state| {
-            *state = None;
-        })?;
-
-        Ok(())
-    }
-
     pub async fn get_telemetry_data(&self, detail: TelemetryDetail) -> CollectionTelemetry {
         let (shards_telemetry, transfers) = {
             let mut shards_telemetry = Vec::new();
@@ -6,7 +6,7 @@ use segment::types::{PayloadFieldSchema, PayloadKeyType};
 use serde::{Deserialize, Serialize};
 
 use crate::collection::Collection;
-use crate::operations::types::{CollectionResult, UpdateResult};
+use crate::operations::types::{CollectionError, CollectionResult, UpdateResult};
 use crate::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
 use crate::save_on_disk::SaveOnDisk;
 
@@ -47,6 +47,15 @@ impl Collection {
         field_schema: PayloadFieldSchema,
         wait: bool,
     ) -> CollectionResult<Option<UpdateResult>> {
+        // Field indexes are stored in RocksDB, which segments in flat format don't have
+        let storage_format = self.collection_config.read().await.params.storage_format;
+        if storage_format.unwrap_or_default().is_flat() {
+            return Err(CollectionError::bad_request(format!(
+                "Can't create payload index on {field_name}, \
+                 payload indexes are not supported with flat storage format",
+            )));
+        }
+
         self.payload_index_schema.write(|schema| {
             schema
                 .schema
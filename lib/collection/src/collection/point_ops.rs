@@ -1,20 +1,28 @@
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Instant;
 
 use futures::stream::FuturesUnordered;
-use futures::{future, StreamExt as _, TryFutureExt, TryStreamExt as _};
+use futures::{future, FutureExt as _, StreamExt as _, TryFutureExt, TryStreamExt as _};
 use itertools::Itertools;
 use segment::data_types::order_by::{Direction, OrderBy};
-use segment::types::{ShardKey, WithPayload, WithPayloadInterface};
+use segment::types::{ShardKey, WithPayload, WithPayloadInterface, WithVector};
 use validator::Validate as _;
 
 use super::Collection;
+use crate::hash_ring::HashRing;
 use crate::operations::consistency_params::ReadConsistency;
 use crate::operations::point_ops::WriteOrdering;
+use crate::operations::query_explain::{QueryExplain, ShardExplain};
 use crate::operations::shard_selector_internal::ShardSelectorInternal;
 use crate::operations::types::*;
 use crate::operations::{CollectionUpdateOperations, OperationWithClockTag};
+use crate::shards::replica_set::ShardReplicaSet;
 use crate::shards::shard::ShardId;
+use crate::slow_query_log::{QueryTimings, SlowQueryOperation};
+
+/// Number of point IDs scrolled at once, when counting points in shards being resharded
+const COUNT_SCROLL_PAGE_SIZE: usize = 10_000;
 
 impl Collection {
     /// Apply collection update operation to all local shards.
@@ -211,6 +219,35 @@ impl Collection {
         read_consistency: Option<ReadConsistency>,
         shard_selection: &ShardSelectorInternal,
     ) -> CollectionResult<ScrollResult> {
+        let (result, _) = self
+            .do_scroll_by(request, read_consistency, shard_selection, false)
+            .await?;
+        Ok(result)
+    }
+
+    /// Scroll, and explain how the request was executed in each shard and segment
+    pub async fn scroll_by_with_explain(
+        &self,
+        request: ScrollRequestInternal,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: &ShardSelectorInternal,
+    ) -> CollectionResult<(ScrollResult, QueryExplain)> {
+        let (result, explain) = self
+            .do_scroll_by(request, read_consistency, shard_selection, true)
+            .await?;
+        Ok((result, explain.unwrap_or_default()))
+    }
+
+    async fn do_scroll_by(
+        &self,
+        request: ScrollRequestInternal,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: &ShardSelectorInternal,
+        explain: bool,
+    ) -> CollectionResult<(ScrollResult, Option<QueryExplain>)> {
+        let instant = Instant::now();
+        let timings = QueryTimings::new(shard_selection);
+
         let default_request = ScrollRequestInternal::default();
 
         let id_offset = request.offset;
@@ -221,9 +258,9 @@ impl Collection {
             .with_payload
             .clone()
             .unwrap_or_else(|| default_request.with_payload.clone().unwrap());
-        let with_vector = request.with_vector;
+        let with_vector = request.with_vector.clone();
 
-        let order_by = request.order_by.map(OrderBy::from);
+        let order_by = request.order_by.clone().map(OrderBy::from);
 
         // Handle case of order_by
         if let Some(order_by) = &order_by {
@@ -262,36 +299,71 @@ impl Collection {
 
         let local_only = shard_selection.is_shard_id();
 
-        let retrieved_points: Vec<_> = {
+        let (retrieved_points, shard_explains): (Vec<_>, Vec<_>) = {
             let shards_holder = self.shards_holder.read().await;
             let target_shards = shards_holder.select_shards(shard_selection)?;
+            let timings = &timings;
             let scroll_futures = target_shards.into_iter().map(|(shard, shard_key)| {
                 let shard_key = shard_key.cloned();
-                shard
-                    .scroll_by(
-                        id_offset,
-                        limit,
-                        &with_payload_interface,
-                        &with_vector,
-                        request.filter.as_ref(),
-                        read_consistency,
-                        local_only,
-                        order_by.as_ref(),
-                    )
-                    .and_then(move |mut records| async move {
-                        if shard_key.is_none() {
-                            return Ok(records);
-                        }
-                        for point in &mut records {
-                            point.shard_key.clone_from(&shard_key);
-                        }
-                        Ok(records)
-                    })
+                let shard_id = shard.shard_id;
+                let shard_started = Instant::now();
+                let scroll = if explain {
+                    shard
+                        .explain_scroll_by(
+                            id_offset,
+                            limit,
+                            &with_payload_interface,
+                            &with_vector,
+                            request.filter.as_ref(),
+                            read_consistency,
+                            local_only,
+                            order_by.as_ref(),
+                        )
+                        .map_ok(|(records, explain)| (records, Some(explain)))
+                        .left_future()
+                } else {
+                    shard
+                        .scroll_by(
+                            id_offset,
+                            limit,
+                            &with_payload_interface,
+                            &with_vector,
+                            request.filter.as_ref(),
+                            read_consistency,
+                            local_only,
+                            order_by.as_ref(),
+                        )
+                        .map_ok(|records| (records, None))
+                        .right_future()
+                };
+                scroll.and_then(move |(mut records, mut explain)| async move {
+                    timings.add_shard(
+                        "scroll",
+                        shard_id,
+                        shard_key.clone(),
+                        shard_started.elapsed(),
+                    );
+                    if shard_key.is_none() {
+                        return Ok((records, explain));
+                    }
+                    for point in &mut records {
+                        point.shard_key.clone_from(&shard_key);
+                    }
+                    if let Some(explain) = &mut explain {
+                        explain.shard_key = shard_key;
+                    }
+                    Ok((records, explain))
+                })
             });
 
-            future::try_join_all(scroll_futures).await?
+            future::try_join_all(scroll_futures)
+                .await?
+                .into_iter()
+                .unzip()
         };
+        timings.add_stage("scroll", instant.elapsed());
 
+        let merge_started = Instant::now();
         let retrieved_iter = retrieved_points.into_iter();
 
         let mut points = match &order_by {
@@ -343,10 +415,27 @@ impl Collection {
             // remove extra point, it would be a first point of the next page
             Some(points.pop().unwrap().id)
         };
-        Ok(ScrollResult {
-            points,
-            next_page_offset,
-        })
+        timings.add_stage("merge", merge_started.elapsed());
+
+        timings.finish(
+            &self.id,
+            SlowQueryOperation::Scroll,
+            || serde_json::to_value(&request).unwrap_or_default(),
+            points.len(),
+        );
+
+        let explain = explain.then(|| QueryExplain {
+            shards: shard_explains.into_iter().flatten().collect(),
+            duration_micros: instant.elapsed().as_micros() as u64,
+        });
+
+        Ok((
+            ScrollResult {
+                points,
+                next_page_offset,
+            },
+            explain,
+        ))
     }
 
     pub async fn count(
@@ -355,29 +444,190 @@ impl Collection {
         read_consistency: Option<ReadConsistency>,
         shard_selection: &ShardSelectorInternal,
     ) -> CollectionResult<CountResult> {
+        let (result, _) = self
+            .do_count(request, read_consistency, shard_selection, false)
+            .await?;
+        Ok(result)
+    }
+
+    /// Count, and explain how the request was executed in each shard and segment
+    pub async fn count_with_explain(
+        &self,
+        request: CountRequestInternal,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: &ShardSelectorInternal,
+    ) -> CollectionResult<(CountResult, QueryExplain)> {
+        let (result, explain) = self
+            .do_count(request, read_consistency, shard_selection, true)
+            .await?;
+        Ok((result, explain.unwrap_or_default()))
+    }
+
+    async fn do_count(
+        &self,
+        request: CountRequestInternal,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: &ShardSelectorInternal,
+        explain: bool,
+    ) -> CollectionResult<(CountResult, Option<QueryExplain>)> {
+        let instant = Instant::now();
+        let timings = QueryTimings::new(shard_selection);
+
         let shards_holder = self.shards_holder.read().await;
         let shards = shards_holder.select_shards(shard_selection)?;
+        let local_only = shard_selection.is_shard_id();
+
+        // During resharding, points are copied between shards of the resharded hashring. In these
+        // shards, only count points placed in them by the hashring, to count each point only once.
+        // Internal requests always count a single shard, deduplication is done by the caller.
+        let resharding = shards_holder
+            .resharding_state()
+            .filter(|_| !local_only)
+            .and_then(|state| {
+                let ring = shards_holder.rings.get(&state.shard_key)?;
+                Some((state.shard_key.clone(), ring))
+            });
 
         let request = Arc::new(request);
         let mut requests: futures::stream::FuturesUnordered<_> = shards
             .into_iter()
             // `count` requests received through internal gRPC *always* have `shard_selection`
-            .map(|(shard, _shard_key)| {
-                shard.count(
-                    request.clone(),
-                    read_consistency,
-                    shard_selection.is_shard_id(),
-                )
+            .map(|(shard, shard_key)| {
+                let timings = &timings;
+                let resharding_ring = resharding
+                    .as_ref()
+                    .filter(|(resharding_key, _)| resharding_key.as_ref() == shard_key)
+                    .map(|(_, ring)| *ring);
+                let shard_key = shard_key.cloned();
+                let shard_id = shard.shard_id;
+                let shard_started = Instant::now();
+                let count = if let Some(ring) = resharding_ring {
+                    self.count_owned_in_shard(shard, ring, &request, read_consistency, explain)
+                        .left_future()
+                } else if explain {
+                    shard
+                        .explain_count(request.clone(), read_consistency, local_only)
+                        .map_ok(|(count, explain)| (count.count, Some(explain)))
+                        .left_future()
+                        .right_future()
+                } else {
+                    shard
+                        .count(request.clone(), read_consistency, local_only)
+                        .map_ok(|count| (count.count, None))
+                        .right_future()
+                        .right_future()
+                };
+                count.map_ok(move |(count, mut explain)| {
+                    timings.add_shard(
+                        "count",
+                        shard_id,
+                        shard_key.clone(),
+                        shard_started.elapsed(),
+                    );
+                    if let Some(explain) = &mut explain {
+                        explain.shard_key = shard_key;
+                    }
+                    (count, explain)
+                })
             })
             .collect();
 
         let mut count = 0;
+        let mut shard_explains = Vec::new();
+
+        while let Some((shard_count, shard_explain)) = requests.try_next().await? {
+            count += shard_count;
+            shard_explains.extend(shard_explain);
+        }
+        // Release the borrow of `timings`
+        drop(requests);
+        timings.add_stage("count", instant.elapsed());
+
+        timings.finish(
+            &self.id,
+            SlowQueryOperation::Count,
+            || serde_json::to_value(&*request).unwrap_or_default(),
+            count,
+        );
+
+        let explain = explain.then(|| QueryExplain {
+            shards: shard_explains,
+            duration_micros: instant.elapsed().as_micros() as u64,
+        });
+
+        Ok((CountResult { count }, explain))
+    }
 
-        while let Some(response) = requests.try_next().await? {
-            count += response.count;
+    /// Count points matching the count request in the given shard, which the hashring places in it
+    ///
+    /// IDs are scrolled in pages, to not hold all IDs of the shard in memory.
+    async fn count_owned_in_shard(
+        &self,
+        shard: &ShardReplicaSet,
+        ring: &HashRing,
+        request: &CountRequestInternal,
+        read_consistency: Option<ReadConsistency>,
+        explain: bool,
+    ) -> CollectionResult<(usize, Option<ShardExplain>)> {
+        let with_payload = WithPayloadInterface::Bool(false);
+        let with_vector = WithVector::Bool(false);
+
+        let mut count = 0;
+        let mut offset = None;
+        let mut shard_explain = None;
+
+        loop {
+            // Scroll one more point, as the offset of the next page
+            let limit = COUNT_SCROLL_PAGE_SIZE + 1;
+
+            // Only explain the first page, the others are scrolled the same way
+            let mut records = if explain && shard_explain.is_none() {
+                let (records, page_explain) = shard
+                    .explain_scroll_by(
+                        offset,
+                        limit,
+                        &with_payload,
+                        &with_vector,
+                        request.filter.as_ref(),
+                        read_consistency,
+                        false,
+                        None,
+                    )
+                    .await?;
+                shard_explain = Some(page_explain);
+                records
+            } else {
+                shard
+                    .scroll_by(
+                        offset,
+                        limit,
+                        &with_payload,
+                        &with_vector,
+                        request.filter.as_ref(),
+                        read_consistency,
+                        false,
+                        None,
+                    )
+                    .await?
+            };
+
+            offset = if records.len() > COUNT_SCROLL_PAGE_SIZE {
+                records.pop().map(|record| record.id)
+            } else {
+                None
+            };
+
+            count += records
+                .iter()
+                .filter(|record| ring.owner(&record.id) == Some(shard.shard_id))
+                .count();
+
+            if offset.is_none() {
+                break;
+            }
         }
 
-        Ok(CountResult { count })
+        Ok((count, shard_explain))
     }
 
     pub async fn retrieve(
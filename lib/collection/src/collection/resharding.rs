@@ -1,22 +1,374 @@
+use std::fmt;
+
+use schemars::JsonSchema;
 use segment::types::ShardKey;
 use serde::{Deserialize, Serialize};
 
+use super::Collection;
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::shards::replica_set::ReplicaState;
+use crate::shards::resharding::driver;
 use crate::shards::shard::{PeerId, ShardId};
+use crate::shards::transfer::ShardTransferConsensus;
 
 #[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 pub struct ReshardingState {
     pub peer_id: PeerId,
     pub shard_id: ShardId,
     pub shard_key: Option<ShardKey>,
+    #[serde(default)]
+    pub direction: ReshardingDirection,
+    #[serde(default)]
+    pub stage: ReshardingStage,
 }
 
 impl ReshardingState {
-    #[allow(dead_code)]
-    pub fn new(peer_id: PeerId, shard_id: ShardId, shard_key: Option<ShardKey>) -> Self {
+    pub fn new(key: ReshardKey) -> Self {
         Self {
-            peer_id,
-            shard_id,
-            shard_key,
+            peer_id: key.peer_id,
+            shard_id: key.shard_id,
+            shard_key: key.shard_key,
+            direction: key.direction,
+            stage: ReshardingStage::default(),
+        }
+    }
+
+    pub fn key(&self) -> ReshardKey {
+        ReshardKey {
+            direction: self.direction,
+            peer_id: self.peer_id,
+            shard_id: self.shard_id,
+            shard_key: self.shard_key.clone(),
+        }
+    }
+
+    pub fn matches(&self, key: &ReshardKey) -> bool {
+        self.direction == key.direction
+            && self.peer_id == key.peer_id
+            && self.shard_id == key.shard_id
+            && self.shard_key == key.shard_key
+    }
+}
+
+/// Unique identifier of a resharding operation
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct ReshardKey {
+    #[serde(default)]
+    pub direction: ReshardingDirection,
+    /// Peer driving the resharding process.
+    /// When resharding up, this peer also holds the new shard.
+    pub peer_id: PeerId,
+    /// Shard being added when resharding up, or removed when resharding down
+    pub shard_id: ShardId,
+    pub shard_key: Option<ShardKey>,
+}
+
+impl fmt::Display for ReshardKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} {}/{}/{:?}",
+            self.direction, self.peer_id, self.shard_id, self.shard_key,
+        )
+    }
+}
+
+/// Direction of resharding
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReshardingDirection {
+    /// Scale up, add a new shard
+    #[default]
+    Up,
+    /// Scale down, remove a shard
+    Down,
+}
+
+/// Stage of resharding
+///
+/// Stages are applied in order through consensus. Until `WriteHashRingCommitted`, resharding can
+/// be aborted without losing any updates.
+#[derive(
+    Copy, Clone, Debug, Default, Eq, PartialEq, PartialOrd, Ord, Hash, Deserialize, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ReshardingStage {
+    /// Points are migrated into their new shards, updates are applied to both hashrings.
+    /// Reads are served with the old hashring.
+    #[default]
+    MigratingPoints,
+    /// Reads are served with the new hashring, updates are still applied to both hashrings.
+    ReadHashRingCommitted,
+    /// Reads and updates are served with the new hashring, migrated points are being deleted
+    /// from their old shards.
+    WriteHashRingCommitted,
+}
+
+impl Collection {
+    pub fn resharding_state(&self) -> Option<ReshardingState> {
+        self.resharding_state.read().clone()
+    }
+
+    pub async fn start_resharding(
+        &self,
+        key: ReshardKey,
+        consensus: Option<Box<dyn ShardTransferConsensus>>,
+    ) -> CollectionResult<()> {
+        {
+            let mut shard_holder = self.shards_holder.write().await;
+
+            if let Some(state) = self.resharding_state.read().as_ref() {
+                return Err(CollectionError::bad_request(format!(
+                    "resharding of collection {} is already in progress: {state:?}",
+                    self.id
+                )));
+            }
+
+            let replica_set = match key.direction {
+                ReshardingDirection::Up => {
+                    if shard_holder.get_shard(&key.shard_id).is_some() {
+                        return Err(CollectionError::bad_shard_selection(format!(
+                            "shard {} already exists in collection {}",
+                            key.shard_id, self.id,
+                        )));
+                    }
+
+                    let replica_set = self
+                        .create_replica_set(
+                            key.shard_id,
+                            &[key.peer_id],
+                            Some(ReplicaState::Resharding),
+                        )
+                        .await?;
+
+                    Some(replica_set)
+                }
+
+                ReshardingDirection::Down => None,
+            };
+
+            let state = ReshardingState::new(key.clone());
+            shard_holder.start_resharding(state.clone(), replica_set)?;
+
+            self.resharding_state.write(|resharding_state| {
+                debug_assert!(
+                    resharding_state.is_none(),
+                    "resharding of collection {} is already in progress: {resharding_state:?}",
+                    self.id
+                );
+
+                *resharding_state = Some(state);
+            })?;
+        }
+
+        if let Some(consensus) = consensus {
+            self.spawn_resharding_driver(key, consensus).await;
+        }
+
+        Ok(())
+    }
+
+    /// Resume driving resharding on this peer, if it is in progress and driven by this peer
+    ///
+    /// Used after restart, the driver task does not survive it.
+    pub async fn resume_resharding(
+        &self,
+        consensus: Box<dyn ShardTransferConsensus>,
+    ) -> CollectionResult<()> {
+        let Some(state) = self.resharding_state() else {
+            return Ok(());
+        };
+
+        if state.peer_id != self.this_peer_id {
+            return Ok(());
+        }
+
+        log::info!(
+            "Resuming resharding {} of collection {} from {:?} stage",
+            state.key(),
+            self.id,
+            state.stage,
+        );
+
+        self.spawn_resharding_driver(state.key(), consensus).await;
+        Ok(())
+    }
+
+    async fn spawn_resharding_driver(
+        &self,
+        key: ReshardKey,
+        consensus: Box<dyn ShardTransferConsensus>,
+    ) {
+        if key.peer_id != self.this_peer_id {
+            return;
+        }
+
+        let mut resharding_task = self.resharding_task.lock().await;
+
+        if let Some(task) = resharding_task.take() {
+            if !task.is_finished() {
+                log::warn!(
+                    "Stopping previous resharding driver of collection {}",
+                    self.id
+                );
+                task.ask_to_cancel();
+            }
+        }
+
+        *resharding_task = Some(driver::spawn_resharding_task(
+            key,
+            self.shards_holder.clone(),
+            consensus,
+            self.id.clone(),
+            self.this_peer_id,
+            self.channel_service.clone(),
+        ));
+    }
+
+    async fn stop_resharding_driver(&self) {
+        if let Some(task) = self.resharding_task.lock().await.take() {
+            if !task.is_finished() {
+                task.ask_to_cancel();
+            }
+        }
+    }
+
+    /// Switch reads to the new hashring
+    pub async fn commit_read_hashring(&self, key: ReshardKey) -> CollectionResult<()> {
+        self.advance_resharding_stage(key, ReshardingStage::ReadHashRingCommitted)
+            .await
+    }
+
+    /// Switch updates to the new hashring
+    pub async fn commit_write_hashring(&self, key: ReshardKey) -> CollectionResult<()> {
+        self.advance_resharding_stage(key, ReshardingStage::WriteHashRingCommitted)
+            .await
+    }
+
+    async fn advance_resharding_stage(
+        &self,
+        key: ReshardKey,
+        stage: ReshardingStage,
+    ) -> CollectionResult<()> {
+        let mut shard_holder = self.shards_holder.write().await;
+
+        let state = self.checked_resharding_state(&key)?;
+
+        if state.stage >= stage {
+            log::warn!(
+                "resharding {key} of collection {} is already in {:?} stage",
+                self.id,
+                state.stage,
+            );
+            return Ok(());
+        }
+
+        match stage {
+            ReshardingStage::MigratingPoints => unreachable!(),
+            ReshardingStage::ReadHashRingCommitted => shard_holder.commit_read_hashring()?,
+            ReshardingStage::WriteHashRingCommitted => shard_holder.commit_write_hashring()?,
+        }
+
+        self.resharding_state.write(|resharding_state| {
+            if let Some(resharding_state) = resharding_state {
+                resharding_state.stage = stage;
+            }
+        })?;
+
+        Ok(())
+    }
+
+    /// Complete resharding, update shard count of the collection
+    pub async fn finish_resharding(&self, key: ReshardKey) -> CollectionResult<()> {
+        {
+            let mut shard_holder = self.shards_holder.write().await;
+
+            let state = self.checked_resharding_state(&key)?;
+
+            if state.stage < ReshardingStage::WriteHashRingCommitted {
+                return Err(CollectionError::bad_request(format!(
+                    "resharding {key} of collection {} cannot be finished in {:?} stage",
+                    self.id, state.stage,
+                )));
+            }
+
+            shard_holder.finish_resharding().await?;
+
+            if key.shard_key.is_none() {
+                let mut config = self.collection_config.write().await;
+                let shard_number = config.params.shard_number.get();
+                let shard_number = match key.direction {
+                    ReshardingDirection::Up => shard_number + 1,
+                    ReshardingDirection::Down => shard_number.saturating_sub(1),
+                };
+                config.params.shard_number = shard_number.try_into().map_err(|_| {
+                    CollectionError::service_error(format!(
+                        "resharding {key} of collection {} results in zero shards",
+                        self.id,
+                    ))
+                })?;
+                config.save(&self.path)?;
+            }
+
+            self.resharding_state.write(|resharding_state| {
+                *resharding_state = None;
+            })?;
+        }
+
+        self.stop_resharding_driver().await;
+
+        Ok(())
+    }
+
+    pub async fn abort_resharding(&self, key: ReshardKey) -> CollectionResult<()> {
+        {
+            let mut shard_holder = self.shards_holder.write().await;
+
+            let is_in_progress = match self.resharding_state.read().as_ref() {
+                Some(state) if state.matches(&key) => {
+                    if state.stage >= ReshardingStage::WriteHashRingCommitted {
+                        return Err(CollectionError::bad_request(format!(
+                            "resharding {key} of collection {} cannot be aborted in {:?} stage",
+                            self.id, state.stage,
+                        )));
+                    }
+                    true
+                }
+                Some(_) => {
+                    return Err(CollectionError::bad_request(format!(
+                        "resharding {key} of collection {} is not in progress",
+                        self.id,
+                    )));
+                }
+                None => {
+                    log::warn!(
+                        "aborting resharding {key} of collection {}, \
+                         but resharding is not in progress",
+                        self.id,
+                    );
+                    false
+                }
+            };
+
+            shard_holder.abort_resharding(key, is_in_progress).await?;
+
+            self.resharding_state.write(|resharding_state| {
+                *resharding_state = None;
+            })?;
+        }
+
+        self.stop_resharding_driver().await;
+
+        Ok(())
+    }
+
+    fn checked_resharding_state(&self, key: &ReshardKey) -> CollectionResult<ReshardingState> {
+        match self.resharding_state.read().as_ref() {
+            Some(state) if state.matches(key) => Ok(state.clone()),
+            _ => Err(CollectionError::bad_request(format!(
+                "resharding {key} of collection {} is not in progress",
+                self.id,
+            ))),
         }
     }
 }
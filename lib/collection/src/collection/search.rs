@@ -3,7 +3,7 @@ use std::mem;
 use std::sync::Arc;
 use std::time::Duration;
 
-use futures::{future, TryFutureExt};
+use futures::{future, FutureExt as _, TryFutureExt};
 use itertools::{Either, Itertools};
 use segment::data_types::vectors::VectorStruct;
 use segment::types::{
@@ -14,8 +14,10 @@ use tokio::time::Instant;
 use super::Collection;
 use crate::events::SlowQueryEvent;
 use crate::operations::consistency_params::ReadConsistency;
+use crate::operations::query_explain::QueryExplain;
 use crate::operations::shard_selector_internal::ShardSelectorInternal;
 use crate::operations::types::*;
+use crate::slow_query_log::{self, QueryTimings, SlowQueryOperation};
 
 impl Collection {
     pub async fn search(
@@ -29,12 +31,20 @@ impl Collection {
             return Ok(vec![]);
         }
         // search is a special case of search_batch with a single batch
-        let request_batch = CoreSearchRequestBatch {
+        let request_batch = Arc::new(CoreSearchRequestBatch {
             searches: vec![request],
-        };
+        });
+        let timings = QueryTimings::new(shard_selection);
         let results = self
-            .do_core_search_batch(request_batch, read_consistency, shard_selection, timeout)
+            .do_core_search_batch(
+                Arc::clone(&request_batch),
+                read_consistency,
+                shard_selection,
+                timeout,
+                &timings,
+            )
             .await?;
+        self.finish_search_timings(timings, &request_batch, &results);
         Ok(results.into_iter().next().unwrap())
     }
 
@@ -49,6 +59,8 @@ impl Collection {
         if request.searches.iter().all(|s| s.limit == 0) {
             return Ok(vec![]);
         }
+        let request = Arc::new(request);
+        let timings = QueryTimings::new(&shard_selection);
         // A factor which determines if we need to use the 2-step search or not
         // Should be adjusted based on usage statistics.
         const PAYLOAD_TRANSFERS_FACTOR_THRESHOLD: usize = 10;
@@ -96,85 +108,189 @@ impl Collection {
             };
             let without_payload_results = self
                 .do_core_search_batch(
-                    without_payload_batch,
+                    Arc::new(without_payload_batch),
                     read_consistency,
                     &shard_selection,
                     timeout,
+                    &timings,
                 )
                 .await?;
+            let fill_started = Instant::now();
             let filled_results = without_payload_results
                 .into_iter()
-                .zip(request.clone().searches.into_iter())
+                .zip(request.searches.iter())
                 .map(|(without_payload_result, req)| {
                     self.fill_search_result_with_payload(
                         without_payload_result,
                         req.with_payload.clone(),
-                        req.with_vector.unwrap_or_default(),
+                        req.with_vector.clone().unwrap_or_default(),
                         read_consistency,
                         &shard_selection,
                     )
                 });
-            future::try_join_all(filled_results).await
+            let result = future::try_join_all(filled_results).await?;
+            timings.add_stage("fill_payload", fill_started.elapsed());
+            self.finish_search_timings(timings, &request, &result);
+            Ok(result)
         } else {
             let result = self
-                .do_core_search_batch(request, read_consistency, &shard_selection, timeout)
+                .do_core_search_batch(
+                    Arc::clone(&request),
+                    read_consistency,
+                    &shard_selection,
+                    timeout,
+                    &timings,
+                )
                 .await?;
+            self.finish_search_timings(timings, &request, &result);
             Ok(result)
         }
     }
 
     async fn do_core_search_batch(
         &self,
-        request: CoreSearchRequestBatch,
+        request: Arc<CoreSearchRequestBatch>,
         read_consistency: Option<ReadConsistency>,
         shard_selection: &ShardSelectorInternal,
         timeout: Option<Duration>,
+        timings: &QueryTimings,
     ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
-        let request = Arc::new(request);
+        let (result, _) = self
+            .do_core_search_batch_with_explain(
+                request,
+                read_consistency,
+                shard_selection,
+                timeout,
+                timings,
+                false,
+            )
+            .await?;
+        Ok(result)
+    }
+
+    /// Search, and explain how the request was executed in each shard and segment.
+    ///
+    /// Payload and vectors are fetched by the search itself, so the explained request
+    /// is always executed in a single step.
+    pub async fn search_with_explain(
+        &self,
+        request: CoreSearchRequest,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: &ShardSelectorInternal,
+        timeout: Option<Duration>,
+    ) -> CollectionResult<(Vec<ScoredPoint>, QueryExplain)> {
+        if request.limit == 0 {
+            return Ok((vec![], QueryExplain::default()));
+        }
+        let request_batch = Arc::new(CoreSearchRequestBatch {
+            searches: vec![request],
+        });
+        let timings = QueryTimings::new(shard_selection);
+        let (results, explain) = self
+            .do_core_search_batch_with_explain(
+                Arc::clone(&request_batch),
+                read_consistency,
+                shard_selection,
+                timeout,
+                &timings,
+                true,
+            )
+            .await?;
+        self.finish_search_timings(timings, &request_batch, &results);
+        Ok((
+            results.into_iter().next().unwrap(),
+            explain.unwrap_or_default(),
+        ))
+    }
 
+    async fn do_core_search_batch_with_explain(
+        &self,
+        request: Arc<CoreSearchRequestBatch>,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: &ShardSelectorInternal,
+        timeout: Option<Duration>,
+        timings: &QueryTimings,
+        explain: bool,
+    ) -> CollectionResult<(Vec<Vec<ScoredPoint>>, Option<QueryExplain>)> {
         let instant = Instant::now();
 
         // query all shards concurrently
-        let all_searches_res = {
+        let (all_searches_res, shard_explains): (Vec<_>, Vec<_>) = {
             let shard_holder = self.shards_holder.read().await;
             let target_shards = shard_holder.select_shards(shard_selection)?;
             let all_searches = target_shards.iter().map(|(shard, shard_key)| {
                 let shard_key = shard_key.cloned();
-                shard
-                    .core_search(
-                        Arc::clone(&request),
-                        read_consistency,
-                        shard_selection.is_shard_id(),
-                        timeout,
-                    )
-                    .and_then(move |mut records| async move {
-                        if shard_key.is_none() {
-                            return Ok(records);
-                        }
-                        for batch in &mut records {
-                            for point in batch {
-                                point.shard_key.clone_from(&shard_key);
-                            }
+                let shard_id = shard.shard_id;
+                let shard_started = Instant::now();
+                let search = if explain {
+                    shard
+                        .explain_core_search(
+                            Arc::clone(&request),
+                            read_consistency,
+                            shard_selection.is_shard_id(),
+                            timeout,
+                        )
+                        .map_ok(|(records, explain)| (records, Some(explain)))
+                        .left_future()
+                } else {
+                    shard
+                        .core_search(
+                            Arc::clone(&request),
+                            read_consistency,
+                            shard_selection.is_shard_id(),
+                            timeout,
+                        )
+                        .map_ok(|records| (records, None))
+                        .right_future()
+                };
+                search.and_then(move |(mut records, mut explain)| async move {
+                    timings.add_shard(
+                        "search",
+                        shard_id,
+                        shard_key.clone(),
+                        shard_started.elapsed(),
+                    );
+                    if shard_key.is_none() {
+                        return Ok((records, explain));
+                    }
+                    for batch in &mut records {
+                        for point in batch {
+                            point.shard_key.clone_from(&shard_key);
                         }
-                        Ok(records)
-                    })
+                    }
+                    if let Some(explain) = &mut explain {
+                        explain.shard_key = shard_key;
+                    }
+                    Ok((records, explain))
+                })
             });
-            future::try_join_all(all_searches).await?
+            future::try_join_all(all_searches)
+                .await?
+                .into_iter()
+                .unzip()
         };
+        timings.add_stage("search", instant.elapsed());
 
+        let merge_started = Instant::now();
         let result = self
             .merge_from_shards(
                 all_searches_res,
                 Arc::clone(&request),
                 !shard_selection.is_shard_id(),
             )
-            .await;
+            .await?;
+        timings.add_stage("merge", merge_started.elapsed());
 
         let filters_refs = request.searches.iter().map(|req| req.filter.as_ref());
 
         self.post_process_if_slow_request(instant.elapsed(), filters_refs);
 
-        result
+        let explain = explain.then(|| QueryExplain {
+            shards: shard_explains.into_iter().flatten().collect(),
+            duration_micros: instant.elapsed().as_micros() as u64,
+        });
+
+        Ok((result, explain))
     }
 
     pub(crate) async fn fill_search_result_with_payload(
@@ -281,6 +397,20 @@ impl Collection {
         Ok(top_results)
     }
 
+    fn finish_search_timings(
+        &self,
+        timings: QueryTimings,
+        request: &CoreSearchRequestBatch,
+        result: &[Vec<ScoredPoint>],
+    ) {
+        timings.finish(
+            &self.id,
+            SlowQueryOperation::Search,
+            || slow_query_log::search_batch_request(&request.searches),
+            result.iter().map(Vec::len).sum(),
+        );
+    }
+
     fn post_process_if_slow_request<'a>(
         &self,
         duration: Duration,
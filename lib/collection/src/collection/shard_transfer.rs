@@ -79,35 +79,39 @@ impl Collection {
             let is_receiver = replica_set.this_peer_id() == shard_transfer.to;
             let is_sender = replica_set.this_peer_id() == shard_transfer.from;
 
+            // Resharding transfers target a different shard, which is already set up by the
+            // resharding process, so replicas of this shard are left untouched
             let initial_state = match shard_transfer.method.unwrap_or_default() {
-                ShardTransferMethod::StreamRecords => ReplicaState::Partial,
+                ShardTransferMethod::StreamRecords => Some(ReplicaState::Partial),
                 ShardTransferMethod::Snapshot | ShardTransferMethod::WalDelta => {
-                    ReplicaState::Recovery
+                    Some(ReplicaState::Recovery)
                 }
-                ShardTransferMethod::ReshardingStreamRecords => ReplicaState::Resharding,
+                ShardTransferMethod::ReshardingStreamRecords => None,
             };
 
             // Create local shard if it does not exist on receiver, or simply set replica state otherwise
             // (on all peers, regardless if shard is local or remote on that peer).
             //
             // This should disable queries to receiver replica even if it was active before.
-            if !is_local && is_receiver {
-                let shard = LocalShard::build(
-                    shard_id,
-                    self.name(),
-                    &replica_set.shard_path,
-                    self.collection_config.clone(),
-                    self.shared_storage_config.clone(),
-                    self.update_runtime.clone(),
-                    self.optimizer_cpu_budget.clone(),
-                )
-                .await?;
-
-                replica_set.set_local(shard, Some(initial_state)).await?;
-            } else {
-                replica_set
-                    .ensure_replica_with_state(&shard_transfer.to, initial_state)
+            if let Some(initial_state) = initial_state {
+                if !is_local && is_receiver {
+                    let shard = LocalShard::build(
+                        shard_id,
+                        self.name(),
+                        &replica_set.shard_path,
+                        self.collection_config.clone(),
+                        self.shared_storage_config.clone(),
+                        self.update_runtime.clone(),
+                        self.optimizer_cpu_budget.clone(),
+                    )
                     .await?;
+
+                    replica_set.set_local(shard, Some(initial_state)).await?;
+                } else {
+                    replica_set
+                        .ensure_replica_with_state(&shard_transfer.to, initial_state)
+                        .await?;
+                }
             }
 
             is_local && is_sender
@@ -195,6 +199,21 @@ impl Collection {
             None => shard_holder_guard.insert(self.shards_holder.read().await),
         };
 
+        // Resharding transfers only move points into a different shard, replicas stay as they are.
+        // Just unwrap forward proxy into local shard on the transfer side.
+        if transfer.is_resharding() {
+            if self.this_peer_id == transfer.from {
+                let proxy_reverted =
+                    transfer::driver::revert_proxy_shard_to_local(shard_holder, transfer.shard_id)
+                        .await?;
+                log::debug!("proxy_reverted: {proxy_reverted}");
+            }
+
+            let finish_was_registered = shard_holder.register_finish_transfer(&transfer.key())?;
+            log::debug!("finish_was_registered: {finish_was_registered}");
+            return Ok(());
+        }
+
         // Should happen on transfer side
         // Unwrap forward proxy into local shard, or replace it with remote shard
         // depending on the `sync` flag.
@@ -277,7 +296,10 @@ impl Collection {
             return Ok(());
         };
 
-        if replica_set.peer_state(&transfer.to).is_some() {
+        // Resharding transfers don't touch the target replica, it belongs to a different shard
+        if transfer.is_resharding() {
+            shard_holder.register_aborted_resharding_transfer(&transfer);
+        } else if replica_set.peer_state(&transfer.to).is_some() {
             if transfer.sync {
                 replica_set.set_replica_state(&transfer.to, ReplicaState::Dead)?;
             } else {
@@ -335,15 +357,27 @@ impl Collection {
                 // and `shards_holder` is holding the lock.
                 // This is a workaround for lifetime checker.
                 let replica_set = shards_holder.get_shard(&shard_id).unwrap();
+                let mut is_resharding = false;
                 let shard_transfer_registered = shards_holder.shard_transfers.wait_for(
                     |shard_transfers| {
                         shard_transfers.iter().any(|shard_transfer| {
-                            shard_transfer.shard_id == shard_id && shard_transfer.to == this_peer_id
+                            let is_target = shard_transfer
+                                .to_shard_id
+                                .unwrap_or(shard_transfer.shard_id)
+                                == shard_id
+                                && shard_transfer.to == this_peer_id;
+                            is_resharding = is_target && shard_transfer.is_resharding();
+                            is_target
                         })
                     },
                     Duration::from_secs(60),
                 );
 
+                // Resharding does not change the state of the target replica
+                if shard_transfer_registered && is_resharding {
+                    return true;
+                }
+
                 // It is not enough to check for shard_transfer_registered,
                 // because it is registered before the state of the shard is changed.
                 shard_transfer_registered
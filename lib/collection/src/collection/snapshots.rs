@@ -17,8 +17,9 @@ use crate::shards::remote_shard::RemoteShard;
 use crate::shards::replica_set::ShardReplicaSet;
 use crate::shards::shard::{PeerId, ShardId};
 use crate::shards::shard_config::{self, ShardConfig};
-use crate::shards::shard_holder::{ShardKeyMapping, SHARD_KEY_MAPPING_FILE};
+use crate::shards::shard_holder::{shard_not_found_error, ShardKeyMapping, SHARD_KEY_MAPPING_FILE};
 use crate::shards::shard_versioning;
+use crate::wal_archive::RecoveryTarget;
 
 impl Collection {
     pub fn get_snapshots_storage_manager(&self) -> CollectionResult<SnapshotStorageManager> {
@@ -48,8 +49,23 @@ impl Collection {
         global_temp_dir: &Path,
         this_peer_id: PeerId,
     ) -> CollectionResult<SnapshotDescription> {
+        self.create_tagged_snapshot(global_temp_dir, this_peer_id, None)
+            .await
+    }
+
+    /// Creates a snapshot of the collection, with an optional `tag` in its name.
+    ///
+    /// Tagged snapshots are named `<collection>-<peer_id>-<tag>-<time>.snapshot`, which allows
+    /// to tell them apart from snapshots created on request.
+    pub async fn create_tagged_snapshot(
+        &self,
+        global_temp_dir: &Path,
+        this_peer_id: PeerId,
+        tag: Option<&str>,
+    ) -> CollectionResult<SnapshotDescription> {
+        let tag = tag.map(|tag| format!("{tag}-")).unwrap_or_default();
         let snapshot_name = format!(
-            "{}-{this_peer_id}-{}.snapshot",
+            "{}-{this_peer_id}-{tag}{}.snapshot",
             self.name(),
             chrono::Utc::now().format("%Y-%m-%d-%H-%M-%S"),
         );
@@ -111,6 +127,7 @@ impl Collection {
                         snapshot_temp_temp_dir.path(),
                         &shard_snapshot_path,
                         save_wal,
+                        None,
                     )
                     .await
                     .map_err(|err| {
@@ -286,15 +303,39 @@ impl Collection {
             .await
     }
 
+    /// Check that the shard snapshot is not the base of any incremental snapshot of the shard
+    pub async fn check_shard_snapshot_unreferenced(
+        &self,
+        shard_id: ShardId,
+        snapshot_file_name: &str,
+        temp_dir: &Path,
+    ) -> CollectionResult<()> {
+        self.shards_holder
+            .read()
+            .await
+            .check_shard_snapshot_unreferenced(
+                &self.snapshots_path,
+                shard_id,
+                snapshot_file_name,
+                temp_dir,
+            )
+            .await
+    }
+
+    /// Create snapshot of a shard
+    ///
+    /// If `base` snapshot of the shard is given, the snapshot is incremental and only includes
+    /// segments changed since the base snapshot.
     pub async fn create_shard_snapshot(
         &self,
         shard_id: ShardId,
+        base: Option<&str>,
         temp_dir: &Path,
     ) -> CollectionResult<SnapshotDescription> {
         self.shards_holder
             .read()
             .await
-            .create_shard_snapshot(&self.snapshots_path, &self.name(), shard_id, temp_dir)
+            .create_shard_snapshot(&self.snapshots_path, &self.name(), shard_id, base, temp_dir)
             .await
     }
 
@@ -321,6 +362,7 @@ impl Collection {
             .await
             .restore_shard_snapshot(
                 snapshot_path,
+                &self.snapshots_path,
                 &self.name(),
                 shard_id,
                 this_peer_id,
@@ -331,6 +373,25 @@ impl Collection {
             .await
     }
 
+    /// Replay archived WAL operations on a local shard restored from a snapshot, up to the given
+    /// recovery target
+    ///
+    /// Returns the number of replayed operations.
+    pub async fn replay_wal_archive(
+        &self,
+        shard_id: ShardId,
+        target: &RecoveryTarget,
+        temp_dir: &Path,
+    ) -> CollectionResult<usize> {
+        let shard_holder = self.shards_holder.read().await;
+
+        let replica_set = shard_holder
+            .get_shard(&shard_id)
+            .ok_or_else(|| shard_not_found_error(shard_id))?;
+
+        replica_set.replay_wal_archive(target, temp_dir).await
+    }
+
     pub async fn assert_shard_exists(&self, shard_id: ShardId) -> CollectionResult<()> {
         self.shards_holder
             .read()
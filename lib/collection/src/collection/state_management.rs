@@ -1,6 +1,7 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::collection::payload_index_schema::PayloadIndexSchema;
+use crate::collection::resharding::ReshardingState;
 use crate::collection::Collection;
 use crate::collection_state::{ShardInfo, State};
 use crate::config::CollectionConfig;
@@ -20,6 +21,7 @@ impl Collection {
         self.apply_config(state.config).await?;
         self.apply_shard_transfers(state.transfers, this_peer_id, abort_transfer)
             .await?;
+        self.apply_resharding_state(state.resharding).await?;
         self.apply_shard_info(state.shards, state.shards_key_mapping)
             .await?;
         self.apply_payload_index_schema(state.payload_index_schema)
@@ -60,11 +62,13 @@ impl Collection {
         self.update_optimizer_params(new_config.optimizer_config)
             .await?;
 
-        // Update replication factor
+        // Update replication factor and shard number, the latter changes with resharding
         {
             let mut config = self.collection_config.write().await;
             config.params.replication_factor = new_config.params.replication_factor;
             config.params.write_consistency_factor = new_config.params.write_consistency_factor;
+            config.params.shard_number = new_config.params.shard_number;
+            config.save(&self.path)?;
         }
 
         self.recreate_optimizers_blocking().await?;
@@ -72,6 +76,23 @@ impl Collection {
         Ok(())
     }
 
+    async fn apply_resharding_state(
+        &self,
+        resharding_state: Option<ReshardingState>,
+    ) -> CollectionResult<()> {
+        let mut shard_holder = self.shards_holder.write().await;
+
+        if self.resharding_state.read().as_ref() == resharding_state.as_ref() {
+            return Ok(());
+        }
+
+        shard_holder.set_resharding_state(resharding_state.clone());
+        self.resharding_state
+            .write(|state| *state = resharding_state)?;
+
+        Ok(())
+    }
+
     async fn apply_shard_info(
         &self,
         shards: HashMap<ShardId, ShardInfo>,
@@ -1530,6 +1530,7 @@ mod tests {
             ]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            storage_format: Default::default(),
         };
         let mut original_segment = build_segment(dir.path(), &config, true).unwrap();
         let write_segment = build_segment(dir.path(), &config, true).unwrap();
@@ -755,25 +755,32 @@ impl<'s> SegmentHolder {
     ) -> OperationResult<LockedSegment> {
         let config = match collection_params {
             // Base config on collection params
-            Some(collection_params) => SegmentConfig {
-                vector_data: collection_params
-                    .to_base_vector_data()
-                    .map_err(|err| OperationError::service_error(format!("Failed to source dense vector configuration from collection parameters: {err:?}")))?,
-                sparse_vector_data: collection_params
-                    .to_sparse_vector_data()
-                    .map_err(|err| OperationError::service_error(format!("Failed to source sparse vector configuration from collection parameters: {err:?}")))?,
-                payload_storage_type: collection_params.payload_storage_type(),
-            },
-            // Fall back: base config on existing appendable segment
-            None => {
-                self
-                    .random_appendable_segment()
-                    .ok_or_else(|| OperationError::service_error("No existing segment to source temporary segment configuration from"))?
-                    .get()
-                    .read()
-                    .config()
-                    .clone()
+            Some(collection_params) => {
+                let mut config = SegmentConfig {
+                    vector_data: collection_params
+                        .to_base_vector_data()
+                        .map_err(|err| OperationError::service_error(format!("Failed to source dense vector configuration from collection parameters: {err:?}")))?,
+                    sparse_vector_data: collection_params
+                        .to_sparse_vector_data()
+                        .map_err(|err| OperationError::service_error(format!("Failed to source sparse vector configuration from collection parameters: {err:?}")))?,
+                    payload_storage_type: collection_params.payload_storage_type(),
+                    storage_format: Default::default(),
+                };
+                config.storage_format = collection_params.segment_storage_format(&config, false);
+                config
             }
+            // Fall back: base config on existing appendable segment
+            None => self
+                .random_appendable_segment()
+                .ok_or_else(|| {
+                    OperationError::service_error(
+                        "No existing segment to source temporary segment configuration from",
+                    )
+                })?
+                .get()
+                .read()
+                .config()
+                .clone(),
         };
 
         Ok(LockedSegment::new(build_segment(
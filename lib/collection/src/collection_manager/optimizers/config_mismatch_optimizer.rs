@@ -6,7 +6,10 @@ use std::sync::Arc;
 use parking_lot::Mutex;
 use segment::common::operation_time_statistics::OperationDurationsAggregator;
 use segment::index::sparse_index::sparse_index_config::SparseIndexType;
-use segment::types::{HnswConfig, Indexes, QuantizationConfig, SegmentType, VECTOR_ELEMENT_SIZE};
+use segment::types::{
+    HnswConfig, Indexes, QuantizationConfig, SegmentType, SparseVectorStorageType,
+    VECTOR_ELEMENT_SIZE,
+};
 
 use crate::collection_manager::holders::segment_holder::{LockedSegmentHolder, SegmentId};
 use crate::collection_manager::optimizers::segment_optimizer::{
@@ -69,6 +72,18 @@ impl ConfigMismatchOptimizer {
             .and_then(|index| index.on_disk)
     }
 
+    /// Check which storage type is explicitly configured for the given sparse vector
+    fn get_required_sparse_vector_storage_type(
+        &self,
+        vector_name: &str,
+    ) -> Option<SparseVectorStorageType> {
+        self.collection_params
+            .sparse_vectors
+            .as_ref()
+            .and_then(|vector_params| vector_params.get(vector_name))
+            .and_then(|params| params.storage_type)
+    }
+
     /// Calculates and HNSW config that should be used for a given vector
     /// with current configuration.
     ///
@@ -126,12 +141,21 @@ impl ConfigMismatchOptimizer {
                     return None; // Never optimize already optimized segment
                 }
 
-                if self.collection_params.on_disk_payload
-                    != segment_config.payload_storage_type.is_on_disk()
+                if self.collection_params.payload_storage_type()
+                    != segment_config.payload_storage_type
                 {
                     return Some((*idx, vector_size)); // Skip segments with payload mismatch
                 }
 
+                let has_payload_index = !read_segment.get_indexed_fields().is_empty();
+                if self
+                    .collection_params
+                    .segment_storage_format(segment_config, has_payload_index)
+                    != segment_config.storage_format
+                {
+                    return Some((*idx, vector_size)); // Skip segments with storage format mismatch
+                }
+
                 // Determine whether dense data in segment has mismatch
                 let dense_has_mismatch =
                     segment_config
@@ -192,6 +216,15 @@ impl ConfigMismatchOptimizer {
                         .sparse_vector_data
                         .iter()
                         .any(|(vector_name, vector_data)| {
+                            // Migrate vectors if storage type has changed
+                            if let Some(required_storage_type) =
+                                self.get_required_sparse_vector_storage_type(vector_name)
+                            {
+                                if required_storage_type != vector_data.storage_type {
+                                    return true;
+                                }
+                            }
+
                             let Some(is_required_on_disk) =
                                 self.check_if_sparse_vectors_index_on_disk(vector_name)
                             else {
@@ -270,8 +303,8 @@ mod tests {
     use segment::entry::entry_point::SegmentEntry;
     use segment::index::hnsw_index::num_rayon_threads;
     use segment::types::{
-        CompressionRatio, Distance, ProductQuantization, ProductQuantizationConfig,
-        ScalarQuantizationConfig, ScalarType,
+        CompressionRatio, Distance, PayloadStorageType, ProductQuantization,
+        ProductQuantizationConfig, ScalarQuantizationConfig, ScalarType,
     };
     use tempfile::Builder;
 
@@ -279,6 +312,7 @@ mod tests {
     use crate::collection_manager::fixtures::{random_multi_vec_segment, random_segment};
     use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentHolder};
     use crate::collection_manager::optimizers::indexing_optimizer::IndexingOptimizer;
+    use crate::config::PayloadStorage;
     use crate::operations::config_diff::HnswConfigDiff;
     use crate::operations::types::VectorsConfig;
     use crate::operations::vector_params_builder::VectorParamsBuilder;
@@ -754,4 +788,90 @@ mod tests {
                 );
             });
     }
+
+    /// This test the config mismatch optimizer for a changed payload storage
+    ///
+    /// It tests whether existing segments are rebuilt with the payload storage configured for the
+    /// collection, which is how segments are migrated to it.
+    #[test]
+    fn test_payload_storage_mismatch() {
+        // Collection configuration
+        let (point_count, dim) = (1000, 10);
+        let thresholds_config = OptimizerThresholds {
+            max_segment_size: usize::MAX,
+            memmap_threshold: usize::MAX,
+            indexing_threshold: usize::MAX,
+        };
+        let mut collection_params = CollectionParams {
+            vectors: VectorsConfig::Single(
+                VectorParamsBuilder::new(dim as u64, Distance::Dot).build(),
+            ),
+            ..CollectionParams::empty()
+        };
+
+        // Base segment, payload is stored in memory
+        let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let mut holder = SegmentHolder::default();
+
+        let segment = random_segment(dir.path(), 100, point_count, dim as usize);
+        assert_eq!(
+            segment.config().payload_storage_type,
+            PayloadStorageType::InMemory,
+        );
+
+        holder.add(segment);
+        let locked_holder: Arc<RwLock<_>> = Arc::new(RwLock::new(holder));
+
+        let mut config_mismatch_optimizer = ConfigMismatchOptimizer::new(
+            thresholds_config,
+            dir.path().to_owned(),
+            temp_dir.path().to_owned(),
+            collection_params.clone(),
+            Default::default(),
+            Default::default(),
+        );
+
+        // Mismatch optimizer should not optimize yet, payload storage is not changed yet
+        let suggested_to_optimize =
+            config_mismatch_optimizer.check_condition(locked_holder.clone(), &Default::default());
+        assert_eq!(suggested_to_optimize.len(), 0);
+
+        // Change payload storage of the collection
+        collection_params.payload_storage = Some(PayloadStorage::Mmap);
+        collection_params.on_disk_payload = true;
+        config_mismatch_optimizer.collection_params = collection_params;
+
+        // Run mismatch optimizer again, make sure it optimizes now
+        let permit = CpuPermit::dummy(1);
+        let suggested_to_optimize =
+            config_mismatch_optimizer.check_condition(locked_holder.clone(), &Default::default());
+        assert_eq!(suggested_to_optimize.len(), 1);
+        let changed = config_mismatch_optimizer
+            .optimize(
+                locked_holder.clone(),
+                suggested_to_optimize,
+                permit,
+                &false.into(),
+            )
+            .unwrap();
+        assert!(changed, "optimizer should have rebuilt this segment");
+
+        // Ensure new segment stores payload in mmap storage
+        locked_holder
+            .read()
+            .iter()
+            .map(|(_, segment)| match segment {
+                LockedSegment::Original(s) => s.read(),
+                LockedSegment::Proxy(_) => unreachable!(),
+            })
+            .filter(|segment| segment.total_point_count() > 0)
+            .for_each(|segment| {
+                assert_eq!(
+                    segment.config().payload_storage_type,
+                    PayloadStorageType::Mmap,
+                    "segment must be optimized with changed payload storage",
+                );
+            });
+    }
 }
@@ -305,7 +305,9 @@ mod tests {
     use segment::fixtures::index_fixtures::random_vector;
     use segment::index::hnsw_index::num_rayon_threads;
     use segment::json_path::JsonPath;
-    use segment::types::{Distance, Payload, PayloadSchemaType};
+    use segment::types::{
+        Distance, Payload, PayloadSchemaType, PayloadStorageType, SegmentStorageFormat,
+    };
     use serde_json::json;
     use tempfile::Builder;
 
@@ -316,6 +318,7 @@ mod tests {
     use crate::collection_manager::segments_updater::{
         process_field_index_operation, process_point_operation,
     };
+    use crate::config::PayloadStorage;
     use crate::operations::point_ops::{Batch, PointOperations};
     use crate::operations::types::{VectorParams, VectorsConfig};
     use crate::operations::vector_params_builder::VectorParamsBuilder;
@@ -990,4 +993,136 @@ mod tests {
                 );
             });
     }
+
+    #[test]
+    fn test_indexing_optimizer_flat_storage_format() {
+        check_indexing_optimizer_flat_storage_format(false);
+    }
+
+    #[test]
+    fn test_indexing_optimizer_flat_storage_format_with_payload_index() {
+        check_indexing_optimizer_flat_storage_format(true);
+    }
+
+    fn check_indexing_optimizer_flat_storage_format(with_payload_index: bool) {
+        // Collection configuration
+        let (point_count, dim) = (1000, 10);
+        let thresholds_config = OptimizerThresholds {
+            max_segment_size: usize::MAX,
+            memmap_threshold: 10,
+            indexing_threshold: usize::MAX,
+        };
+        let collection_params = CollectionParams {
+            vectors: VectorsConfig::Single(
+                VectorParamsBuilder::new(dim as u64, Distance::Dot).build(),
+            ),
+            payload_storage: Some(PayloadStorage::Mmap),
+            storage_format: Some(SegmentStorageFormat::Flat),
+            ..CollectionParams::empty()
+        };
+
+        // Base segment
+        let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let mut holder = SegmentHolder::default();
+
+        let mut segment = random_segment(dir.path(), 100, point_count, dim as usize);
+        if with_payload_index {
+            segment
+                .create_field_index(
+                    101,
+                    &"number".parse().unwrap(),
+                    Some(&PayloadSchemaType::Integer.into()),
+                )
+                .unwrap();
+        }
+
+        let segment_id = holder.add(segment);
+        let locked_holder: Arc<parking_lot::RwLock<_>> = Arc::new(RwLock::new(holder));
+
+        let hnsw_config = HnswConfig {
+            m: 16,
+            ef_construct: 100,
+            full_scan_threshold: 10,
+            max_indexing_threads: 0,
+            on_disk: None,
+            payload_m: None,
+        };
+
+        // Optimizers used in test
+        let index_optimizer = IndexingOptimizer::new(
+            2,
+            thresholds_config.clone(),
+            dir.path().to_owned(),
+            temp_dir.path().to_owned(),
+            collection_params.clone(),
+            hnsw_config.clone(),
+            Default::default(),
+        );
+        let config_mismatch_optimizer = ConfigMismatchOptimizer::new(
+            thresholds_config,
+            dir.path().to_owned(),
+            temp_dir.path().to_owned(),
+            collection_params,
+            hnsw_config,
+            Default::default(),
+        );
+
+        let permit_cpu_count = num_rayon_threads(0);
+        let permit = CpuPermit::dummy(permit_cpu_count as u32);
+
+        // Use indexing optimizer to build mmap segment
+        let changed = index_optimizer
+            .optimize(
+                locked_holder.clone(),
+                vec![segment_id],
+                permit,
+                &false.into(),
+            )
+            .unwrap();
+        assert!(changed, "optimizer should have rebuilt this segment");
+        assert_eq!(locked_holder.read().len(), 2, "mmap segment must be built");
+
+        // Optimized segment is stored in flat format, unless it has payload indexes which are
+        // stored in RocksDB. New appendable segment keeps RocksDB
+        locked_holder
+            .read()
+            .iter()
+            .map(|(_, segment)| match segment {
+                LockedSegment::Original(s) => s.read(),
+                LockedSegment::Proxy(_) => unreachable!(),
+            })
+            .for_each(|segment| {
+                let config = segment.config();
+                if segment.total_point_count() > 0 && with_payload_index {
+                    assert_eq!(config.payload_storage_type, PayloadStorageType::Mmap);
+                    assert!(
+                        config.storage_format.is_rocks_db(),
+                        "optimized segment with payload index must use RocksDB",
+                    );
+                    assert_eq!(segment.get_indexed_fields().len(), 1);
+                } else if segment.total_point_count() > 0 {
+                    assert_eq!(config.payload_storage_type, PayloadStorageType::Mmap);
+                    assert!(
+                        config.storage_format.is_flat(),
+                        "optimized segment must be flat",
+                    );
+                    assert!(
+                        !segment.current_path.join("CURRENT").exists(),
+                        "flat segment must not contain RocksDB files",
+                    );
+                } else {
+                    assert!(config.is_appendable());
+                    assert!(
+                        config.storage_format.is_rocks_db(),
+                        "appendable segment must use RocksDB",
+                    );
+                }
+            });
+
+        // Segments match collection config, nothing to re-optimize
+        let suggested_to_optimize =
+            config_mismatch_optimizer.check_condition(locked_holder.clone(), &Default::default());
+        assert_eq!(suggested_to_optimize.len(), 0);
+    }
 }
@@ -23,6 +23,8 @@ const KEEP_LAST_TRACKERS: usize = 16;
 #[derive(Default, Clone, Debug)]
 pub struct TrackerLog {
     descriptions: VecDeque<Tracker>,
+    /// Number of points in segments optimized successfully, never truncated
+    optimized_points: usize,
 }
 
 impl TrackerLog {
@@ -32,6 +34,16 @@ impl TrackerLog {
         self.truncate();
     }
 
+    /// Count points of segments optimized successfully
+    pub fn add_optimized_points(&mut self, points: usize) {
+        self.optimized_points += points;
+    }
+
+    /// Number of points in segments optimized successfully since start
+    pub fn optimized_points(&self) -> usize {
+        self.optimized_points
+    }
+
     /// Truncate and forget old trackers for successful/cancelled optimizations
     ///
     /// Will never remove older trackers with failed or still ongoing optimizations.
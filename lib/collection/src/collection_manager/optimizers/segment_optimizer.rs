@@ -17,8 +17,8 @@ use segment::segment::{Segment, SegmentVersion};
 use segment::segment_constructor::build_segment;
 use segment::segment_constructor::segment_builder::SegmentBuilder;
 use segment::types::{
-    HnswConfig, Indexes, PayloadFieldSchema, PayloadKeyType, PayloadStorageType, PointIdType,
-    QuantizationConfig, SegmentConfig, VectorStorageType, VECTOR_ELEMENT_SIZE,
+    HnswConfig, Indexes, PayloadFieldSchema, PayloadKeyType, PointIdType, QuantizationConfig,
+    SegmentConfig, VectorStorageType, VECTOR_ELEMENT_SIZE,
 };
 
 use crate::collection_manager::holders::proxy_segment::ProxySegment;
@@ -80,15 +80,13 @@ pub trait SegmentOptimizer {
     /// Build temp segment
     fn temp_segment(&self, save_version: bool) -> CollectionResult<LockedSegment> {
         let collection_params = self.collection_params();
-        let config = SegmentConfig {
+        let mut config = SegmentConfig {
             vector_data: collection_params.to_base_vector_data()?,
             sparse_vector_data: collection_params.to_sparse_vector_data()?,
-            payload_storage_type: if collection_params.on_disk_payload {
-                PayloadStorageType::OnDisk
-            } else {
-                PayloadStorageType::InMemory
-            },
+            payload_storage_type: collection_params.payload_storage_type(),
+            storage_format: Default::default(),
         };
+        config.storage_format = collection_params.segment_storage_format(&config, false);
         Ok(LockedSegment::new(build_segment(
             self.segments_path(),
             &config,
@@ -234,15 +232,15 @@ pub trait SegmentOptimizer {
                 }
             });
 
-        let optimized_config = SegmentConfig {
+        let mut optimized_config = SegmentConfig {
             vector_data,
             sparse_vector_data,
-            payload_storage_type: if collection_params.on_disk_payload {
-                PayloadStorageType::OnDisk
-            } else {
-                PayloadStorageType::InMemory
-            },
+            payload_storage_type: collection_params.payload_storage_type(),
+            storage_format: Default::default(),
         };
+        // Payload indexes are not known yet, the builder keeps RocksDB format if there are any
+        optimized_config.storage_format =
+            collection_params.segment_storage_format(&optimized_config, false);
 
         Ok(SegmentBuilder::new(
             self.segments_path(),
@@ -75,10 +75,15 @@ impl VacuumOptimizer {
                 // Calculate littered ratio for segment and named vectors
                 let littered_ratio_segment = self.littered_ratio_segment(segment);
                 let littered_ratio_vectors = self.littered_vectors_index_ratio(segment);
-                [littered_ratio_segment, littered_ratio_vectors]
-                    .into_iter()
-                    .flatten()
-                    .map(|ratio| (*idx, ratio))
+                let littered_ratio_payload = self.littered_ratio_payload_storage(segment);
+                [
+                    littered_ratio_segment,
+                    littered_ratio_vectors,
+                    littered_ratio_payload,
+                ]
+                .into_iter()
+                .flatten()
+                .map(|ratio| (*idx, ratio))
             })
             .max_by_key(|(_, ratio)| OrderedFloat(*ratio))
             .map(|(idx, _)| (idx, segments_read_guard.get(idx).unwrap().clone()))
@@ -102,6 +107,30 @@ impl VacuumOptimizer {
         (is_big && is_littered).then_some(littered_ratio)
     }
 
+    /// Calculate littered ratio for segment on payload storage level
+    ///
+    /// Append-only payload storages leave old values behind on every update. Rebuilding the
+    /// segment writes all payloads compactly, which reclaims that space.
+    ///
+    /// Returns `None` if the storage does not accumulate stale data, or if littered ratio did not
+    /// reach vacuum thresholds.
+    fn littered_ratio_payload_storage(&self, segment: &LockedSegment) -> Option<f64> {
+        let segment_entry = match segment {
+            LockedSegment::Original(segment) => segment,
+            LockedSegment::Proxy(_) => return None,
+        };
+        let read_segment = segment_entry.read();
+
+        let littered_ratio = read_segment
+            .payload_index
+            .borrow()
+            .payload_storage_littered_ratio()?;
+        let is_big = read_segment.total_point_count() >= self.min_vectors_number;
+        let is_littered = littered_ratio > self.deleted_threshold;
+
+        (is_big && is_littered).then_some(littered_ratio)
+    }
+
     /// Calculate littered ratio for segment on vector index level
     ///
     /// If a segment has multiple named vectors, it checks each one.
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
 use common::types::ScoreType;
 use futures::future::try_join_all;
@@ -9,6 +10,7 @@ use parking_lot::RwLock;
 use segment::common::operation_error::OperationError;
 use segment::data_types::named_vectors::NamedVectors;
 use segment::data_types::query_context::QueryContext;
+use segment::data_types::query_explain::{SearchExplainCollector, SegmentExplain};
 use segment::data_types::vectors::{QueryVector, VectorStruct};
 use segment::types::{
     Filter, Indexes, PointIdType, ScoredPoint, SearchParams, SegmentConfig, SeqNumberType,
@@ -581,7 +583,14 @@ fn execute_batch_search(
     };
 
     let vectors_batch = &vectors_batch.iter().collect_vec();
-    let segment_query_context = query_context.get_segment_query_context();
+    let started = Instant::now();
+    let search_explain = query_context
+        .explain()
+        .map(|_| SearchExplainCollector::default());
+    let mut segment_query_context = query_context.get_segment_query_context();
+    if let Some(search_explain) = &search_explain {
+        segment_query_context = segment_query_context.with_explain(search_explain);
+    }
     let res = read_segment.search_batch(
         search_params.vector_name,
         vectors_batch,
@@ -593,6 +602,13 @@ fn execute_batch_search(
         segment_query_context,
     )?;
 
+    if let (Some(explain), Some(search_explain)) = (query_context.explain(), search_explain) {
+        let mut segment_explain = SegmentExplain::new(&*read_segment, search_params.filter);
+        segment_explain.searches = search_explain.into_inner();
+        segment_explain.duration_micros = started.elapsed().as_micros() as u64;
+        explain.lock().push(segment_explain);
+    }
+
     let further_results = res
         .iter()
         .map(|batch_result| batch_result.len() == top)
@@ -0,0 +1,460 @@
+//! Authenticated encryption of snapshot files at rest.
+//!
+//! Files are encrypted with AES-256-GCM, using the STREAM construction over chunks of
+//! [`CHUNK_SIZE`] bytes, so that large snapshots never have to be held in memory. Encrypted files
+//! start with a header holding the ID of the key they are encrypted with, which allows to keep
+//! previous keys around for decryption when rotating keys.
+//!
+//! Layout of an encrypted file:
+//!
+//! ```text
+//! | magic (8) | version (1) | key ID (8) | nonce prefix (7) | chunk | ... | last chunk |
+//! ```
+//!
+//! Every chunk is followed by its authentication tag, the header is authenticated as associated
+//! data of every chunk.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use aes_gcm::aead::generic_array::GenericArray;
+use aes_gcm::aead::stream::{DecryptorBE32, EncryptorBE32};
+use aes_gcm::aead::Payload;
+use aes_gcm::{Aes256Gcm, Key, KeyInit};
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine as _;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tempfile::TempPath;
+
+use crate::operations::types::{CollectionError, CollectionResult};
+
+const MAGIC: &[u8; 8] = b"QDRNTENC";
+const VERSION: u8 = 1;
+const KEY_SIZE: usize = 32;
+const KEY_ID_SIZE: usize = 8;
+const NONCE_PREFIX_SIZE: usize = 7;
+const HEADER_SIZE: usize = MAGIC.len() + 1 + KEY_ID_SIZE + NONCE_PREFIX_SIZE;
+const CHUNK_SIZE: usize = 64 * 1024;
+const TAG_SIZE: usize = 16;
+
+#[derive(Clone, Deserialize, Debug, Default)]
+pub struct SnapshotEncryptionConfig {
+    /// Base64 encoded 256-bit key used to encrypt new snapshots
+    pub key: Option<String>,
+    /// Path to a file holding the base64 encoded key used to encrypt new snapshots
+    pub key_file: Option<String>,
+    /// Previous base64 encoded keys, only used to decrypt existing snapshots
+    #[serde(default)]
+    pub decryption_keys: Vec<String>,
+    /// Paths to files holding previous keys, only used to decrypt existing snapshots
+    #[serde(default)]
+    pub decryption_key_files: Vec<String>,
+}
+
+#[derive(Clone)]
+struct EncryptionKey {
+    id: [u8; KEY_ID_SIZE],
+    cipher: Aes256Gcm,
+}
+
+impl EncryptionKey {
+    fn parse(encoded: &str) -> CollectionResult<Self> {
+        let key = BASE64_STANDARD.decode(encoded.trim()).map_err(|err| {
+            CollectionError::service_error(format!("Invalid snapshot encryption key: {err}"))
+        })?;
+
+        if key.len() != KEY_SIZE {
+            return Err(CollectionError::service_error(format!(
+                "Invalid snapshot encryption key: expected {KEY_SIZE} bytes, got {}",
+                key.len(),
+            )));
+        }
+
+        let mut id = [0; KEY_ID_SIZE];
+        id.copy_from_slice(&Sha256::digest(&key)[..KEY_ID_SIZE]);
+
+        Ok(Self {
+            id,
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)),
+        })
+    }
+
+    fn read(path: &str) -> CollectionResult<Self> {
+        let encoded = std::fs::read_to_string(path).map_err(|err| {
+            CollectionError::service_error(format!(
+                "Failed to read snapshot encryption key file {path}: {err}"
+            ))
+        })?;
+        Self::parse(&encoded)
+    }
+}
+
+/// Keys to encrypt and decrypt snapshot files with
+#[derive(Clone)]
+pub struct SnapshotEncryption {
+    /// Key to encrypt new files with, if encryption is enabled
+    encryption_key: Option<EncryptionKey>,
+    /// All keys files may be encrypted with, including the encryption key
+    decryption_keys: Vec<EncryptionKey>,
+}
+
+impl SnapshotEncryption {
+    pub fn new(config: &SnapshotEncryptionConfig) -> CollectionResult<Self> {
+        let encryption_key = match (&config.key, &config.key_file) {
+            (Some(_), Some(_)) => {
+                return Err(CollectionError::service_error(
+                    "Snapshot encryption accepts either `key` or `key_file`, not both",
+                ));
+            }
+            (Some(key), None) => Some(EncryptionKey::parse(key)?),
+            (None, Some(key_file)) => Some(EncryptionKey::read(key_file)?),
+            (None, None) => None,
+        };
+
+        let mut decryption_keys: Vec<_> = encryption_key.iter().cloned().collect();
+        for key in &config.decryption_keys {
+            decryption_keys.push(EncryptionKey::parse(key)?);
+        }
+        for key_file in &config.decryption_key_files {
+            decryption_keys.push(EncryptionKey::read(key_file)?);
+        }
+
+        Ok(Self {
+            encryption_key,
+            decryption_keys,
+        })
+    }
+
+    /// Whether new files are encrypted, or decryption keys are only kept for existing files
+    pub fn is_enabled(&self) -> bool {
+        self.encryption_key.is_some()
+    }
+
+    /// Encrypt `source` file into `target` file
+    ///
+    /// This method performs blocking IO.
+    pub fn encrypt_file(&self, source: &Path, target: &Path) -> CollectionResult<()> {
+        let Some(key) = &self.encryption_key else {
+            return Err(CollectionError::service_error(
+                "Snapshot encryption key is not configured",
+            ));
+        };
+
+        let nonce_prefix: [u8; NONCE_PREFIX_SIZE] = rand::random();
+
+        let mut header = Vec::with_capacity(HEADER_SIZE);
+        header.extend_from_slice(MAGIC);
+        header.push(VERSION);
+        header.extend_from_slice(&key.id);
+        header.extend_from_slice(&nonce_prefix);
+
+        let mut reader = BufReader::new(File::open(source)?);
+        let mut writer = BufWriter::new(File::create(target)?);
+        writer.write_all(&header)?;
+
+        let mut encryptor =
+            EncryptorBE32::from_aead(key.cipher.clone(), GenericArray::from_slice(&nonce_prefix));
+
+        let mut chunk = vec![0; CHUNK_SIZE];
+        let mut next_chunk = vec![0; CHUNK_SIZE];
+        let mut len = read_chunk(&mut reader, &mut chunk)?;
+
+        loop {
+            // Last chunk must be marked as such, so we look ahead
+            let next_len = if len == CHUNK_SIZE {
+                read_chunk(&mut reader, &mut next_chunk)?
+            } else {
+                0
+            };
+
+            let payload = Payload {
+                msg: &chunk[..len],
+                aad: &header,
+            };
+
+            if next_len == 0 {
+                let ciphertext = encryptor
+                    .encrypt_last(payload)
+                    .map_err(|_| CollectionError::service_error("Failed to encrypt snapshot"))?;
+                writer.write_all(&ciphertext)?;
+                break;
+            }
+
+            let ciphertext = encryptor
+                .encrypt_next(payload)
+                .map_err(|_| CollectionError::service_error("Failed to encrypt snapshot"))?;
+            writer.write_all(&ciphertext)?;
+
+            std::mem::swap(&mut chunk, &mut next_chunk);
+            len = next_len;
+        }
+
+        writer
+            .into_inner()
+            .map_err(|err| err.into_error())?
+            .sync_all()?;
+
+        Ok(())
+    }
+
+    /// Decrypt `source` file into `target` file
+    ///
+    /// This method performs blocking IO.
+    pub fn decrypt_file(&self, source: &Path, target: &Path) -> CollectionResult<()> {
+        let mut reader = BufReader::new(File::open(source)?);
+
+        let mut header = [0; HEADER_SIZE];
+        reader
+            .read_exact(&mut header)
+            .map_err(|_| CollectionError::bad_input("Encrypted snapshot is truncated"))?;
+
+        let (magic, rest) = header.split_at(MAGIC.len());
+        let (version, rest) = rest.split_at(1);
+        let (key_id, nonce_prefix) = rest.split_at(KEY_ID_SIZE);
+
+        if magic != MAGIC {
+            return Err(CollectionError::bad_input("Snapshot is not encrypted"));
+        }
+
+        if version[0] != VERSION {
+            return Err(CollectionError::bad_input(format!(
+                "Unsupported snapshot encryption version {}",
+                version[0],
+            )));
+        }
+
+        let Some(key) = self
+            .decryption_keys
+            .iter()
+            .find(|key| key.id.as_slice() == key_id)
+        else {
+            return Err(CollectionError::bad_input(
+                "Snapshot is encrypted with an unknown key, add it to the snapshot decryption keys",
+            ));
+        };
+
+        let mut writer = BufWriter::new(File::create(target)?);
+
+        let mut decryptor =
+            DecryptorBE32::from_aead(key.cipher.clone(), GenericArray::from_slice(nonce_prefix));
+
+        let mut chunk = vec![0; CHUNK_SIZE + TAG_SIZE];
+        let mut next_chunk = vec![0; CHUNK_SIZE + TAG_SIZE];
+        let mut len = read_chunk(&mut reader, &mut chunk)?;
+
+        let decryption_error = || {
+            CollectionError::bad_input(
+                "Failed to decrypt snapshot, it is corrupted or was tampered with",
+            )
+        };
+
+        loop {
+            let next_len = if len == chunk.len() {
+                read_chunk(&mut reader, &mut next_chunk)?
+            } else {
+                0
+            };
+
+            let payload = Payload {
+                msg: &chunk[..len],
+                aad: &header,
+            };
+
+            if next_len == 0 {
+                let plaintext = decryptor
+                    .decrypt_last(payload)
+                    .map_err(|_| decryption_error())?;
+                writer.write_all(&plaintext)?;
+                break;
+            }
+
+            let plaintext = decryptor
+                .decrypt_next(payload)
+                .map_err(|_| decryption_error())?;
+            writer.write_all(&plaintext)?;
+
+            std::mem::swap(&mut chunk, &mut next_chunk);
+            len = next_len;
+        }
+
+        writer
+            .into_inner()
+            .map_err(|err| err.into_error())?
+            .sync_all()?;
+
+        Ok(())
+    }
+}
+
+/// Check whether the file is an encrypted snapshot file
+///
+/// This function performs blocking IO.
+pub fn is_encrypted(path: &Path) -> CollectionResult<bool> {
+    let mut magic = [0; MAGIC.len()];
+    let len = read_chunk(&mut File::open(path)?, &mut magic)?;
+    Ok(len == magic.len() && &magic == MAGIC)
+}
+
+/// Decrypt snapshot file into `target_dir`, if it is encrypted
+///
+/// Returns the path of the decrypted file, which is deleted once dropped, or `None` if the
+/// snapshot is not encrypted and can be used as is.
+pub async fn decrypt_snapshot(
+    encryption: Option<&SnapshotEncryption>,
+    snapshot_path: &Path,
+    target_dir: &Path,
+) -> CollectionResult<Option<TempPath>> {
+    let snapshot_path = snapshot_path.to_path_buf();
+    let decrypted_path = target_dir.join(format!("{}.decrypted", uuid::Uuid::new_v4()));
+    let encryption = encryption.cloned();
+
+    tokio::task::spawn_blocking(move || {
+        if !is_encrypted(&snapshot_path)? {
+            return Ok(None);
+        }
+
+        let Some(encryption) = encryption else {
+            return Err(CollectionError::bad_input(
+                "Snapshot is encrypted, but no snapshot encryption keys are configured",
+            ));
+        };
+
+        let decrypted_path = TempPath::from_path(decrypted_path);
+        encryption.decrypt_file(&snapshot_path, &decrypted_path)?;
+        Ok(Some(decrypted_path))
+    })
+    .await?
+}
+
+/// Decrypt the file in place, if it is encrypted
+pub async fn decrypt_file_in_place(
+    encryption: Option<&SnapshotEncryption>,
+    path: &Path,
+) -> CollectionResult<()> {
+    let Some(target_dir) = path.parent() else {
+        return Ok(());
+    };
+
+    if let Some(decrypted_path) = decrypt_snapshot(encryption, path, target_dir).await? {
+        tokio::fs::rename(&decrypted_path, path).await?;
+    }
+
+    Ok(())
+}
+
+/// Read from `reader` until `buffer` is full or the end is reached, returns number of bytes read
+fn read_chunk(reader: &mut impl Read, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut len = 0;
+
+    while len < buffer.len() {
+        match reader.read(&mut buffer[len..]) {
+            Ok(0) => break,
+            Ok(read) => len += read,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::Builder;
+
+    use super::*;
+
+    fn config(key: &[u8; KEY_SIZE]) -> SnapshotEncryptionConfig {
+        SnapshotEncryptionConfig {
+            key: Some(BASE64_STANDARD.encode(key)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let dir = Builder::new()
+            .prefix("snapshot_encryption")
+            .tempdir()
+            .unwrap();
+        let encryption = SnapshotEncryption::new(&config(&[1; KEY_SIZE])).unwrap();
+
+        for size in [0, 1, CHUNK_SIZE, 3 * CHUNK_SIZE + 17] {
+            let plaintext: Vec<u8> = (0..size).map(|i| i as u8).collect();
+            let source = dir.path().join("source");
+            let encrypted = dir.path().join("encrypted");
+            let decrypted = dir.path().join("decrypted");
+            std::fs::write(&source, &plaintext).unwrap();
+
+            encryption.encrypt_file(&source, &encrypted).unwrap();
+            assert!(is_encrypted(&encrypted).unwrap());
+            assert!(!is_encrypted(&source).unwrap());
+
+            encryption.decrypt_file(&encrypted, &decrypted).unwrap();
+            assert_eq!(std::fs::read(&decrypted).unwrap(), plaintext);
+        }
+    }
+
+    #[test]
+    fn test_key_rotation() {
+        let dir = Builder::new()
+            .prefix("snapshot_encryption")
+            .tempdir()
+            .unwrap();
+        let source = dir.path().join("source");
+        let encrypted = dir.path().join("encrypted");
+        let decrypted = dir.path().join("decrypted");
+        std::fs::write(&source, b"snapshot").unwrap();
+
+        let old_key = [1; KEY_SIZE];
+        let new_key = [2; KEY_SIZE];
+
+        SnapshotEncryption::new(&config(&old_key))
+            .unwrap()
+            .encrypt_file(&source, &encrypted)
+            .unwrap();
+
+        // New key alone can't decrypt
+        let rotated = SnapshotEncryption::new(&config(&new_key)).unwrap();
+        assert!(rotated.decrypt_file(&encrypted, &decrypted).is_err());
+
+        // Old key kept for decryption
+        let rotated = SnapshotEncryption::new(&SnapshotEncryptionConfig {
+            decryption_keys: vec![BASE64_STANDARD.encode(old_key)],
+            ..config(&new_key)
+        })
+        .unwrap();
+        rotated.decrypt_file(&encrypted, &decrypted).unwrap();
+        assert_eq!(std::fs::read(&decrypted).unwrap(), b"snapshot");
+    }
+
+    #[test]
+    fn test_tampered_file() {
+        let dir = Builder::new()
+            .prefix("snapshot_encryption")
+            .tempdir()
+            .unwrap();
+        let encryption = SnapshotEncryption::new(&config(&[1; KEY_SIZE])).unwrap();
+
+        let source = dir.path().join("source");
+        let encrypted = dir.path().join("encrypted");
+        let decrypted = dir.path().join("decrypted");
+        std::fs::write(&source, vec![7; 2 * CHUNK_SIZE]).unwrap();
+        encryption.encrypt_file(&source, &encrypted).unwrap();
+
+        // Flip a byte
+        let mut ciphertext = std::fs::read(&encrypted).unwrap();
+        ciphertext[HEADER_SIZE + 10] ^= 1;
+        std::fs::write(&encrypted, &ciphertext).unwrap();
+        assert!(encryption.decrypt_file(&encrypted, &decrypted).is_err());
+
+        // Drop the last chunk
+        let mut ciphertext = std::fs::read(&encrypted).unwrap();
+        ciphertext[HEADER_SIZE + 10] ^= 1;
+        ciphertext.truncate(HEADER_SIZE + CHUNK_SIZE + TAG_SIZE);
+        std::fs::write(&encrypted, &ciphertext).unwrap();
+        assert!(encryption.decrypt_file(&encrypted, &decrypted).is_err());
+    }
+}
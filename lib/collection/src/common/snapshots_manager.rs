@@ -2,18 +2,28 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use actix_web::HttpRequest;
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine as _;
 use object_store::aws::AmazonS3Builder;
+use object_store::azure::MicrosoftAzureBuilder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::http::HttpBuilder;
+use object_store::ClientOptions;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
 use serde::Deserialize;
 use tempfile::TempPath;
 use tokio::io::AsyncWriteExt;
 
+use super::snapshot_encryption::{
+    decrypt_file_in_place, SnapshotEncryption, SnapshotEncryptionConfig,
+};
 use super::snapshot_stream::{SnapShotStreamCloudStrage, SnapShotStreamLocalFS, SnapshotStream};
 use crate::common::file_utils::move_file;
 use crate::common::sha_256::hash_file;
 use crate::operations::snapshot_ops::{
-    get_checksum_path, get_snapshot_description, SnapshotDescription,
+    get_checksum_path, get_manifest_path, get_snapshot_description, SnapshotDescription,
 };
-use crate::operations::snapshot_storage_ops::{self};
+use crate::operations::snapshot_storage_ops::{self, HttpUploader};
 use crate::operations::types::{CollectionError, CollectionResult};
 use crate::shards::shard::ShardId;
 use crate::shards::shard_holder::LockedShardHolder;
@@ -22,13 +32,25 @@ use crate::shards::shard_holder::LockedShardHolder;
 pub struct SnapShotsConfig {
     pub snapshots_storage: SnapshotsStorageConfig,
     pub s3_config: Option<S3Config>,
+    pub gcs_config: Option<GcsConfig>,
+    pub azure_config: Option<AzureConfig>,
+    pub http_config: Option<HttpConfig>,
+    /// Archive WAL records into the snapshot storage, to allow point-in-time recovery
+    #[serde(default)]
+    pub wal_archiving: bool,
+    /// Encrypt snapshots and archived WAL records at rest
+    pub encryption: Option<SnapshotEncryptionConfig>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SnapshotsStorageConfig {
     #[default]
     Local,
     S3,
+    Gcs,
+    Azure,
+    Http,
 }
 
 #[derive(Clone, Deserialize, Debug, Default)]
@@ -40,27 +62,86 @@ pub struct S3Config {
     pub endpoint_url: Option<String>,
 }
 
+/// Google Cloud Storage configuration
+///
+/// Without explicit credentials, they are discovered from the environment, such as the metadata
+/// server on GCP instances.
+#[derive(Clone, Deserialize, Debug, Default)]
+pub struct GcsConfig {
+    pub bucket: String,
+    /// Path to a service account JSON key file
+    pub service_account_path: Option<String>,
+    /// Service account JSON key.
+    /// Set `gcs_base_url` and `disable_oauth` in the key to use an emulator.
+    pub service_account_key: Option<String>,
+    /// Path to an application default credentials JSON file
+    pub application_credentials: Option<String>,
+}
+
+/// Azure Blob Storage configuration
+#[derive(Clone, Deserialize, Debug, Default)]
+pub struct AzureConfig {
+    pub account: String,
+    pub container: String,
+    pub access_key: Option<String>,
+    /// Shared access signature, such as `sv=...&sig=...`
+    pub sas_token: Option<String>,
+    pub endpoint_url: Option<String>,
+    /// Use the Azurite storage emulator, with its well-known account and key
+    #[serde(default)]
+    pub use_emulator: bool,
+}
+
+/// Plain HTTP or WebDAV server configuration
+///
+/// The server must support `PUT`, `GET` and `DELETE`, and WebDAV `MKCOL` and `PROPFIND` to
+/// create directories and list snapshots.
+#[derive(Clone, Deserialize, Debug, Default)]
+pub struct HttpConfig {
+    /// Base URL to store snapshots under
+    pub url: String,
+    /// Username for basic authentication
+    pub username: Option<String>,
+    /// Password for basic authentication
+    pub password: Option<String>,
+    /// Token for bearer authentication
+    pub bearer_token: Option<String>,
+}
+
 #[allow(dead_code)]
 pub struct SnapshotStorageCloud {
     client: Box<dyn object_store::ObjectStore>,
+    /// Uploads files in a single streaming request, for stores without multipart uploads
+    http_uploader: Option<HttpUploader>,
+    encryption: Option<SnapshotEncryption>,
 }
 
-pub struct SnapshotStorageLocalFS;
+pub struct SnapshotStorageLocalFS {
+    encryption: Option<SnapshotEncryption>,
+}
 
 pub enum SnapshotStorageManager {
     LocalFS(SnapshotStorageLocalFS),
     // Assuming that we can have common operations for all cloud storages
     S3(SnapshotStorageCloud),
-    // <TODO> : Implement other cloud storage
-    // GCS(SnapshotStorageCloud),
-    // AZURE(SnapshotStorageCloud),
+    Gcs(SnapshotStorageCloud),
+    Azure(SnapshotStorageCloud),
+    Http(SnapshotStorageCloud),
 }
 
 impl SnapshotStorageManager {
     pub fn new(snapshots_config: SnapShotsConfig) -> CollectionResult<Self> {
-        match snapshots_config.clone().snapshots_storage {
+        let encryption = snapshots_config
+            .encryption
+            .as_ref()
+            .map(SnapshotEncryption::new)
+            .transpose()?;
+
+        match snapshots_config.snapshots_storage {
             SnapshotsStorageConfig::Local => {
-                Ok(SnapshotStorageManager::LocalFS(SnapshotStorageLocalFS))
+                Ok(SnapshotStorageManager::LocalFS(SnapshotStorageLocalFS {
+                    encryption,
+                }))
             }
             SnapshotsStorageConfig::S3 => {
                 let mut builder = AmazonS3Builder::new();
@@ -88,7 +169,123 @@ impl SnapshotStorageManager {
                         CollectionError::service_error(format!("Failed to create S3 client: {}", e))
                     })?);
 
-                Ok(SnapshotStorageManager::S3(SnapshotStorageCloud { client }))
+                Ok(SnapshotStorageManager::S3(SnapshotStorageCloud {
+                    client,
+                    http_uploader: None,
+                    encryption,
+                }))
+            }
+            SnapshotsStorageConfig::Gcs => {
+                let gcs_config = snapshots_config.gcs_config.as_ref().ok_or_else(|| {
+                    CollectionError::service_error("Missing `gcs_config` for GCS snapshots storage")
+                })?;
+
+                let mut builder =
+                    GoogleCloudStorageBuilder::new().with_bucket_name(&gcs_config.bucket);
+
+                if let Some(service_account_path) = &gcs_config.service_account_path {
+                    builder = builder.with_service_account_path(service_account_path);
+                }
+                if let Some(service_account_key) = &gcs_config.service_account_key {
+                    builder = builder.with_service_account_key(service_account_key);
+                }
+                if let Some(application_credentials) = &gcs_config.application_credentials {
+                    builder = builder.with_application_credentials(application_credentials);
+                }
+
+                let client: Box<dyn object_store::ObjectStore> =
+                    Box::new(builder.build().map_err(|e| {
+                        CollectionError::service_error(format!(
+                            "Failed to create GCS client: {}",
+                            e
+                        ))
+                    })?);
+
+                Ok(SnapshotStorageManager::Gcs(SnapshotStorageCloud {
+                    client,
+                    http_uploader: None,
+                    encryption,
+                }))
+            }
+            SnapshotsStorageConfig::Azure => {
+                let azure_config = snapshots_config.azure_config.as_ref().ok_or_else(|| {
+                    CollectionError::service_error(
+                        "Missing `azure_config` for Azure snapshots storage",
+                    )
+                })?;
+
+                let mut builder = MicrosoftAzureBuilder::new()
+                    .with_container_name(&azure_config.container)
+                    .with_use_emulator(azure_config.use_emulator);
+
+                // Emulator provides its own well-known account
+                if !azure_config.use_emulator {
+                    builder = builder.with_account(&azure_config.account);
+                }
+                if let Some(access_key) = &azure_config.access_key {
+                    builder = builder.with_access_key(access_key);
+                }
+                if let Some(sas_token) = &azure_config.sas_token {
+                    let query_pairs =
+                        url::form_urlencoded::parse(sas_token.trim_start_matches('?').as_bytes())
+                            .into_owned()
+                            .collect::<Vec<_>>();
+                    builder = builder.with_sas_authorization(query_pairs);
+                }
+                if let Some(endpoint_url) = &azure_config.endpoint_url {
+                    builder = builder.with_endpoint(endpoint_url.clone());
+                    if endpoint_url.starts_with("http://") {
+                        builder = builder.with_allow_http(true);
+                    }
+                }
+
+                let client: Box<dyn object_store::ObjectStore> =
+                    Box::new(builder.build().map_err(|e| {
+                        CollectionError::service_error(format!(
+                            "Failed to create Azure client: {}",
+                            e
+                        ))
+                    })?);
+
+                Ok(SnapshotStorageManager::Azure(SnapshotStorageCloud {
+                    client,
+                    http_uploader: None,
+                    encryption,
+                }))
+            }
+            SnapshotsStorageConfig::Http => {
+                let http_config = snapshots_config.http_config.as_ref().ok_or_else(|| {
+                    CollectionError::service_error(
+                        "Missing `http_config` for HTTP snapshots storage",
+                    )
+                })?;
+
+                let headers = http_auth_headers(http_config)?;
+
+                let client_options = ClientOptions::new()
+                    .with_default_headers(headers.clone())
+                    .with_allow_http(http_config.url.starts_with("http://"));
+
+                let client: Box<dyn object_store::ObjectStore> = Box::new(
+                    HttpBuilder::new()
+                        .with_url(&http_config.url)
+                        .with_client_options(client_options)
+                        .build()
+                        .map_err(|e| {
+                            CollectionError::service_error(format!(
+                                "Failed to create HTTP client: {}",
+                                e
+                            ))
+                        })?,
+                );
+
+                let http_uploader = HttpUploader::new(&http_config.url, headers)?;
+
+                Ok(SnapshotStorageManager::Http(SnapshotStorageCloud {
+                    client,
+                    http_uploader: Some(http_uploader),
+                    encryption,
+                }))
             }
         }
     }
@@ -98,7 +295,10 @@ impl SnapshotStorageManager {
             SnapshotStorageManager::LocalFS(storage_impl) => {
                 storage_impl.delete_snapshot(snapshot_name).await
             }
-            SnapshotStorageManager::S3(storage_impl) => {
+            SnapshotStorageManager::S3(storage_impl)
+            | SnapshotStorageManager::Gcs(storage_impl)
+            | SnapshotStorageManager::Azure(storage_impl)
+            | SnapshotStorageManager::Http(storage_impl) => {
                 storage_impl.delete_snapshot(snapshot_name).await
             }
         }
@@ -111,26 +311,65 @@ impl SnapshotStorageManager {
             SnapshotStorageManager::LocalFS(storage_impl) => {
                 storage_impl.list_snapshots(directory).await
             }
-            SnapshotStorageManager::S3(storage_impl) => {
+            SnapshotStorageManager::S3(storage_impl)
+            | SnapshotStorageManager::Gcs(storage_impl)
+            | SnapshotStorageManager::Azure(storage_impl)
+            | SnapshotStorageManager::Http(storage_impl) => {
                 storage_impl.list_snapshots(directory).await
             }
         }
     }
+    /// Keys to encrypt and decrypt stored files with, if configured
+    pub fn encryption(&self) -> Option<&SnapshotEncryption> {
+        match self {
+            SnapshotStorageManager::LocalFS(storage_impl) => storage_impl.encryption.as_ref(),
+            SnapshotStorageManager::S3(storage_impl)
+            | SnapshotStorageManager::Gcs(storage_impl)
+            | SnapshotStorageManager::Azure(storage_impl)
+            | SnapshotStorageManager::Http(storage_impl) => storage_impl.encryption.as_ref(),
+        }
+    }
+
+    /// Store the file, encrypting it if encryption is enabled
+    ///
+    /// Checksum of an encrypted file covers the ciphertext.
     pub async fn store_file(
         &self,
         source_path: &Path,
         target_path: &Path,
     ) -> CollectionResult<SnapshotDescription> {
+        let encryption = self
+            .encryption()
+            .filter(|encryption| encryption.is_enabled());
+
+        let encrypted_path = match encryption {
+            Some(encryption) => {
+                let encryption = encryption.clone();
+                let source_path = source_path.to_path_buf();
+                let encrypted_path = TempPath::from_path(source_path.with_extension("encrypted"));
+                let target = encrypted_path.to_path_buf();
+                tokio::task::spawn_blocking(move || encryption.encrypt_file(&source_path, &target))
+                    .await??;
+                Some(encrypted_path)
+            }
+            None => None,
+        };
+        let source_path = encrypted_path.as_deref().unwrap_or(source_path);
+
         match self {
             SnapshotStorageManager::LocalFS(storage_impl) => {
                 storage_impl.store_file(source_path, target_path).await
             }
-            SnapshotStorageManager::S3(storage_impl) => {
+            SnapshotStorageManager::S3(storage_impl)
+            | SnapshotStorageManager::Gcs(storage_impl)
+            | SnapshotStorageManager::Azure(storage_impl)
+            | SnapshotStorageManager::Http(storage_impl) => {
                 storage_impl.store_file(source_path, target_path).await
             }
         }
     }
 
+    /// Get the stored file at a local path, decrypting it if it is encrypted
     pub async fn get_stored_file(
         &self,
         storage_path: &Path,
@@ -138,12 +377,59 @@ impl SnapshotStorageManager {
     ) -> CollectionResult<()> {
         match self {
             SnapshotStorageManager::LocalFS(storage_impl) => {
-                storage_impl.get_stored_file(storage_path, local_path).await
+                storage_impl
+                    .get_stored_file(storage_path, local_path)
+                    .await?
             }
-            SnapshotStorageManager::S3(storage_impl) => {
-                storage_impl.get_stored_file(storage_path, local_path).await
+            SnapshotStorageManager::S3(storage_impl)
+            | SnapshotStorageManager::Gcs(storage_impl)
+            | SnapshotStorageManager::Azure(storage_impl)
+            | SnapshotStorageManager::Http(storage_impl) => {
+                storage_impl
+                    .get_stored_file(storage_path, local_path)
+                    .await?
             }
         }
+
+        decrypt_file_in_place(self.encryption(), local_path).await
+    }
+
+    /// List names of all files in the given directory
+    pub async fn list_files(&self, directory: &Path) -> CollectionResult<Vec<String>> {
+        match self {
+            SnapshotStorageManager::LocalFS(storage_impl) => {
+                storage_impl.list_files(directory).await
+            }
+            SnapshotStorageManager::S3(storage_impl)
+            | SnapshotStorageManager::Gcs(storage_impl)
+            | SnapshotStorageManager::Azure(storage_impl)
+            | SnapshotStorageManager::Http(storage_impl) => {
+                storage_impl.list_files(directory).await
+            }
+        }
+    }
+
+    /// Copy stored file to a local path, keeping it in the storage
+    ///
+    /// The local copy is decrypted if the stored file is encrypted.
+    pub async fn download_file(
+        &self,
+        storage_path: &Path,
+        local_path: &Path,
+    ) -> CollectionResult<()> {
+        match self {
+            SnapshotStorageManager::LocalFS(storage_impl) => {
+                storage_impl.download_file(storage_path, local_path).await?
+            }
+            SnapshotStorageManager::S3(storage_impl)
+            | SnapshotStorageManager::Gcs(storage_impl)
+            | SnapshotStorageManager::Azure(storage_impl)
+            | SnapshotStorageManager::Http(storage_impl) => {
+                storage_impl.download_file(storage_path, local_path).await?
+            }
+        }
+
+        decrypt_file_in_place(self.encryption(), local_path).await
     }
 
     pub async fn get_snapshot_path(
@@ -157,7 +443,10 @@ impl SnapshotStorageManager {
                     .get_snapshot_path(snapshots_path, snapshot_name)
                     .await
             }
-            SnapshotStorageManager::S3(storage_impl) => {
+            SnapshotStorageManager::S3(storage_impl)
+            | SnapshotStorageManager::Gcs(storage_impl)
+            | SnapshotStorageManager::Azure(storage_impl)
+            | SnapshotStorageManager::Http(storage_impl) => {
                 storage_impl
                     .get_snapshot_path(snapshots_path, snapshot_name)
                     .await
@@ -176,7 +465,10 @@ impl SnapshotStorageManager {
                     .get_full_snapshot_path(snapshots_path, snapshot_name)
                     .await
             }
-            SnapshotStorageManager::S3(storage_impl) => {
+            SnapshotStorageManager::S3(storage_impl)
+            | SnapshotStorageManager::Gcs(storage_impl)
+            | SnapshotStorageManager::Azure(storage_impl)
+            | SnapshotStorageManager::Http(storage_impl) => {
                 storage_impl
                     .get_full_snapshot_path(snapshots_path, snapshot_name)
                     .await
@@ -202,7 +494,10 @@ impl SnapshotStorageManager {
                     )
                     .await
             }
-            SnapshotStorageManager::S3(storage_impl) => {
+            SnapshotStorageManager::S3(storage_impl)
+            | SnapshotStorageManager::Gcs(storage_impl)
+            | SnapshotStorageManager::Azure(storage_impl)
+            | SnapshotStorageManager::Http(storage_impl) => {
                 storage_impl
                     .get_shard_snapshot_path(
                         shards_holder,
@@ -224,19 +519,56 @@ impl SnapshotStorageManager {
             SnapshotStorageManager::LocalFS(storage_impl) => {
                 storage_impl.get_snapshot_stream(req, snapshot_path).await
             }
-            SnapshotStorageManager::S3(storage_impl) => {
+            SnapshotStorageManager::S3(storage_impl)
+            | SnapshotStorageManager::Gcs(storage_impl)
+            | SnapshotStorageManager::Azure(storage_impl)
+            | SnapshotStorageManager::Http(storage_impl) => {
                 storage_impl.get_snapshot_stream(snapshot_path).await
             }
         }
     }
 }
 
+/// Build authentication headers for the HTTP snapshots storage
+fn http_auth_headers(http_config: &HttpConfig) -> CollectionResult<HeaderMap> {
+    let authorization = match (&http_config.username, &http_config.bearer_token) {
+        (Some(_), Some(_)) => {
+            return Err(CollectionError::service_error(
+                "HTTP snapshots storage accepts either `username` or `bearer_token`, not both",
+            ));
+        }
+        (Some(username), None) => {
+            let password = http_config.password.as_deref().unwrap_or_default();
+            let credentials = BASE64_STANDARD.encode(format!("{username}:{password}"));
+            Some(format!("Basic {credentials}"))
+        }
+        (None, Some(bearer_token)) => Some(format!("Bearer {bearer_token}")),
+        (None, None) => None,
+    };
+
+    let mut headers = HeaderMap::new();
+
+    if let Some(authorization) = authorization {
+        let mut value = HeaderValue::from_str(&authorization).map_err(|e| {
+            CollectionError::service_error(format!(
+                "Invalid HTTP snapshots storage credentials: {e}"
+            ))
+        })?;
+        value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, value);
+    }
+
+    Ok(headers)
+}
+
 impl SnapshotStorageLocalFS {
     async fn delete_snapshot(&self, snapshot_path: &Path) -> CollectionResult<bool> {
         let checksum_path = get_checksum_path(snapshot_path);
-        let (delete_snapshot, delete_checksum) = tokio::join!(
+        let manifest_path = get_manifest_path(snapshot_path);
+        let (delete_snapshot, delete_checksum, delete_manifest) = tokio::join!(
             tokio::fs::remove_file(snapshot_path),
             tokio::fs::remove_file(checksum_path),
+            tokio::fs::remove_file(&manifest_path),
         );
 
         delete_snapshot?;
@@ -246,6 +578,11 @@ impl SnapshotStorageLocalFS {
             log::warn!("Failed to delete checksum file for snapshot, ignoring: {err}");
         }
 
+        // Only shard snapshots have a manifest
+        if delete_manifest.is_ok() {
+            let _ = tokio::fs::remove_file(get_checksum_path(manifest_path)).await;
+        }
+
         Ok(true)
     }
 
@@ -325,6 +662,34 @@ impl SnapshotStorageLocalFS {
         Ok(())
     }
 
+    async fn list_files(&self, directory: &Path) -> CollectionResult<Vec<String>> {
+        if !directory.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut entries = tokio::fs::read_dir(directory).await?;
+        let mut files = Vec::new();
+
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.file_type().await?.is_file() {
+                files.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+
+        Ok(files)
+    }
+
+    async fn download_file(&self, storage_path: &Path, local_path: &Path) -> CollectionResult<()> {
+        if let Some(target_dir) = local_path.parent() {
+            if !target_dir.exists() {
+                std::fs::create_dir_all(target_dir)?;
+            }
+        }
+
+        tokio::fs::copy(storage_path, local_path).await?;
+        Ok(())
+    }
+
     /// Get absolute file path for a full snapshot by name
     ///
     /// This enforces the file to be inside the snapshots directory
@@ -417,7 +782,29 @@ impl SnapshotStorageLocalFS {
 
 impl SnapshotStorageCloud {
     async fn delete_snapshot(&self, snapshot_path: &Path) -> CollectionResult<bool> {
-        snapshot_storage_ops::delete_snapshot(&self.client, snapshot_path).await
+        let deleted = snapshot_storage_ops::delete_snapshot(&self.client, snapshot_path).await?;
+
+        // Only shard snapshots have a manifest, ignore deletion errors
+        let manifest_path = get_manifest_path(snapshot_path);
+        if let Err(err) = snapshot_storage_ops::delete_snapshot(&self.client, &manifest_path).await
+        {
+            log::debug!("Failed to delete manifest file for snapshot, ignoring: {err}");
+        }
+
+        Ok(deleted)
+    }
+
+    async fn list_files(&self, directory: &Path) -> CollectionResult<Vec<String>> {
+        let files = snapshot_storage_ops::list_snapshot_descriptions(&self.client, directory)
+            .await?
+            .into_iter()
+            .map(|description| description.name)
+            .collect();
+        Ok(files)
+    }
+
+    async fn download_file(&self, storage_path: &Path, local_path: &Path) -> CollectionResult<()> {
+        snapshot_storage_ops::download_snapshot(&self.client, storage_path, local_path).await
     }
 
     async fn list_snapshots(&self, directory: &Path) -> CollectionResult<Vec<SnapshotDescription>> {
@@ -429,7 +816,13 @@ impl SnapshotStorageCloud {
         source_path: &Path,
         target_path: &Path,
     ) -> CollectionResult<SnapshotDescription> {
-        snapshot_storage_ops::multipart_upload(&self.client, source_path, target_path).await?;
+        match &self.http_uploader {
+            Some(http_uploader) => http_uploader.upload(source_path, target_path).await?,
+            None => {
+                snapshot_storage_ops::multipart_upload(&self.client, source_path, target_path)
+                    .await?
+            }
+        }
         snapshot_storage_ops::get_snapshot_description(&self.client, target_path).await
     }
 
@@ -498,3 +891,44 @@ impl SnapshotStorageCloud {
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_snapshots_storage() {
+        let config: SnapShotsConfig = serde_json::from_value(serde_json::json!({
+            "snapshots_storage": "gcs",
+            "gcs_config": { "bucket": "snapshots" },
+        }))
+        .unwrap();
+
+        assert!(matches!(
+            config.snapshots_storage,
+            SnapshotsStorageConfig::Gcs,
+        ));
+        assert_eq!(config.gcs_config.unwrap().bucket, "snapshots");
+    }
+
+    #[test]
+    fn test_http_auth_headers() {
+        let mut config = HttpConfig {
+            url: "http://localhost:8080/snapshots".to_string(),
+            ..Default::default()
+        };
+        assert!(http_auth_headers(&config).unwrap().is_empty());
+
+        config.username = Some("user".to_string());
+        config.password = Some("pass".to_string());
+        let headers = http_auth_headers(&config).unwrap();
+        assert_eq!(headers[AUTHORIZATION], "Basic dXNlcjpwYXNz");
+
+        config.bearer_token = Some("token".to_string());
+        assert!(http_auth_headers(&config).is_err());
+
+        config.username = None;
+        let headers = http_auth_headers(&config).unwrap();
+        assert_eq!(headers[AUTHORIZATION], "Bearer token");
+    }
+}
@@ -10,11 +10,12 @@ use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
 use segment::data_types::vectors::DEFAULT_VECTOR_NAME;
 use segment::index::sparse_index::sparse_index_config::{SparseIndexConfig, SparseIndexType};
+use segment::segment_constructor::check_flat_segment_config;
 use segment::types::{
     default_replication_factor_const, default_shard_number_const,
     default_write_consistency_factor_const, Distance, HnswConfig, Indexes, PayloadStorageType,
-    QuantizationConfig, SparseVectorDataConfig, VectorDataConfig, VectorStorageDatatype,
-    VectorStorageType,
+    QuantizationConfig, SegmentConfig, SegmentStorageFormat, SparseVectorDataConfig,
+    VectorDataConfig, VectorStorageDatatype, VectorStorageType,
 };
 use serde::{Deserialize, Serialize};
 use validator::Validate;
@@ -65,6 +66,58 @@ pub enum ShardingMethod {
     Custom,
 }
 
+/// Type of storage for point payloads
+#[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq, Hash, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadStorage {
+    /// Payload is stored in RocksDB and cached in memory
+    InMemory,
+    /// Payload is stored in RocksDB and read from disk every time it is requested
+    OnDisk,
+    /// Payload is stored in append-only memory mapped pages, read every time it is requested
+    Mmap,
+}
+
+impl PayloadStorage {
+    pub fn is_on_disk(&self) -> bool {
+        PayloadStorageType::from(*self).is_on_disk()
+    }
+
+    /// Check that `on_disk_payload`, if specified alongside, doesn't contradict this storage
+    pub fn check_on_disk_payload(&self, on_disk_payload: Option<bool>) -> CollectionResult<()> {
+        match on_disk_payload {
+            Some(on_disk_payload) if on_disk_payload != self.is_on_disk() => {
+                Err(CollectionError::BadInput {
+                    description: format!(
+                        "`on_disk_payload: {on_disk_payload}` conflicts with `payload_storage: {self:?}`",
+                    ),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl From<PayloadStorage> for PayloadStorageType {
+    fn from(value: PayloadStorage) -> Self {
+        match value {
+            PayloadStorage::InMemory => PayloadStorageType::InMemory,
+            PayloadStorage::OnDisk => PayloadStorageType::OnDisk,
+            PayloadStorage::Mmap => PayloadStorageType::Mmap,
+        }
+    }
+}
+
+/// Constraint on the placement of shard replicas across peers
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub struct ReplicaPlacement {
+    /// Spread replicas of each shard across peers with different values of this peer label,
+    /// for example `zone`. Peers without this label are treated as having the same empty value.
+    #[validate(length(min = 1))]
+    pub spread_by_label: String,
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub struct CollectionParams {
@@ -84,6 +137,10 @@ pub struct CollectionParams {
     /// Number of replicas for each shard
     #[serde(default = "default_replication_factor")]
     pub replication_factor: NonZeroU32,
+    /// Constraint on the placement of shard replicas across peers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate]
+    pub replica_placement: Option<ReplicaPlacement>,
     /// Defines how many replicas should apply the operation for us to consider it successful.
     /// Increasing this number will make the collection more resilient to inconsistencies, but will
     /// also make it fail if not enough replicas are available.
@@ -102,6 +159,17 @@ pub struct CollectionParams {
     /// Note: those payload values that are involved in filtering and are indexed - remain in RAM.
     #[serde(default = "default_on_disk_payload")]
     pub on_disk_payload: bool,
+    /// Type of storage for point payloads.
+    /// If set, `on_disk_payload` always matches it. If not set, defined by `on_disk_payload`.
+    /// Changing it rebuilds existing segments with the new storage during optimization.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload_storage: Option<PayloadStorage>,
+    /// Defines how optimized segments are persisted.
+    /// Flat format stores all segment components in plain files without RocksDB.
+    /// It is only applied to non-appendable segments with mmap payload and on-disk vector storages,
+    /// other segments keep using RocksDB. Payload indexes can't be created with flat format.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_format: Option<SegmentStorageFormat>,
     /// Configuration of the sparse vector storage
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[validate]
@@ -110,10 +178,30 @@ pub struct CollectionParams {
 
 impl CollectionParams {
     pub fn payload_storage_type(&self) -> PayloadStorageType {
-        if self.on_disk_payload {
-            PayloadStorageType::OnDisk
-        } else {
-            PayloadStorageType::InMemory
+        match self.payload_storage {
+            Some(payload_storage) => payload_storage.into(),
+            None if self.on_disk_payload => PayloadStorageType::OnDisk,
+            None => PayloadStorageType::InMemory,
+        }
+    }
+
+    /// Storage format to use for a segment with the given config.
+    /// Falls back to RocksDB if the segment doesn't support the configured format,
+    /// e.g. appendable segments and segments with payload indexes are always stored in RocksDB.
+    pub fn segment_storage_format(
+        &self,
+        segment_config: &SegmentConfig,
+        has_payload_index: bool,
+    ) -> SegmentStorageFormat {
+        match self.storage_format.unwrap_or_default() {
+            SegmentStorageFormat::Flat
+                if !has_payload_index && check_flat_segment_config(segment_config).is_ok() =>
+            {
+                SegmentStorageFormat::Flat
+            }
+            SegmentStorageFormat::Flat | SegmentStorageFormat::RocksDb => {
+                SegmentStorageFormat::RocksDb
+            }
         }
     }
 }
@@ -125,9 +213,12 @@ impl Anonymize for CollectionParams {
             shard_number: self.shard_number,
             sharding_method: self.sharding_method,
             replication_factor: self.replication_factor,
+            replica_placement: self.replica_placement.clone(),
             write_consistency_factor: self.write_consistency_factor,
             read_fan_out_factor: self.read_fan_out_factor,
             on_disk_payload: self.on_disk_payload,
+            payload_storage: self.payload_storage,
+            storage_format: self.storage_format,
             sparse_vectors: self.sparse_vectors.anonymize(),
         }
     }
@@ -202,9 +293,12 @@ impl CollectionParams {
             shard_number: default_shard_number(),
             sharding_method: None,
             replication_factor: default_replication_factor(),
+            replica_placement: None,
             write_consistency_factor: default_write_consistency_factor(),
             read_fan_out_factor: None,
             on_disk_payload: default_on_disk_payload(),
+            payload_storage: None,
+            storage_format: None,
             sparse_vectors: None,
         }
     }
@@ -318,12 +412,20 @@ impl CollectionParams {
     ) -> CollectionResult<()> {
         for (vector_name, update_params) in update_vectors.0.iter() {
             let sparse_vector_params = self.get_sparse_vector_params_mut(vector_name)?;
-            let SparseVectorParams { index, modifier } = update_params.clone();
+            let SparseVectorParams {
+                index,
+                modifier,
+                storage_type,
+            } = update_params.clone();
 
             if let Some(modifier) = modifier {
                 sparse_vector_params.modifier = Some(modifier);
             }
 
+            if let Some(storage_type) = storage_type {
+                sparse_vector_params.storage_type = Some(storage_type);
+            }
+
             if let Some(index) = index {
                 if let Some(existing_index) = &mut sparse_vector_params.index {
                     existing_index.update_from_other(&index);
@@ -387,6 +489,7 @@ impl CollectionParams {
                                     .and_then(|index| index.full_scan_threshold),
                                 index_type: SparseIndexType::MutableRam,
                             },
+                            storage_type: params.storage_type.unwrap_or_default(),
                         },
                     )
                 })
@@ -1,8 +1,10 @@
 use std::fmt;
 use std::hash::Hash;
 
+use itertools::Itertools;
 use smallvec::SmallVec;
 
+use crate::collection::resharding::ReshardingDirection;
 use crate::shards::shard::ShardId;
 
 const HASH_RING_SHARD_SCALE: u32 = 100;
@@ -25,20 +27,6 @@ impl<T: Hash + Copy + PartialEq> HashRing<T> {
         Self::Single(Inner::fair(HASH_RING_SHARD_SCALE))
     }
 
-    /// Create a new resharding hashring, with resharding shard already added into `new` hashring.
-    ///
-    /// The hashring is created with a fair distribution of points and `HASH_RING_SHARD_SCALE` scale.
-    pub fn resharding(shard: T) -> Self {
-        let mut ring = Self::Resharding {
-            old: Inner::fair(HASH_RING_SHARD_SCALE),
-            new: Inner::fair(HASH_RING_SHARD_SCALE),
-        };
-
-        ring.add_resharding(shard);
-
-        ring
-    }
-
     pub fn is_empty(&self) -> bool {
         match self {
             Self::Single(ring) => ring.is_empty(),
@@ -75,6 +63,65 @@ impl<T: Hash + Copy + PartialEq> HashRing<T> {
         new.add(shard);
     }
 
+    /// Start resharding in the given direction.
+    ///
+    /// When resharding up, the shard is added into the `new` hashring. When resharding down, the
+    /// shard is removed from the `new` hashring.
+    pub fn start_resharding(&mut self, shard: T, direction: ReshardingDirection) {
+        match direction {
+            ReshardingDirection::Up => self.add_resharding(shard),
+            ReshardingDirection::Down => {
+                if let Self::Single(ring) = self {
+                    let (old, new) = (ring.clone(), ring.clone());
+                    *self = Self::Resharding { old, new };
+                }
+
+                let Self::Resharding { new, .. } = self else {
+                    unreachable!();
+                };
+
+                new.remove(&shard);
+            }
+        }
+    }
+
+    /// Switch to the `new` hashring, completing resharding.
+    ///
+    /// Returns `false` if the hashring is not in resharding mode.
+    pub fn commit_resharding(&mut self) -> bool {
+        let Self::Resharding { new, .. } = self else {
+            log::warn!("committing resharding, but hashring is not in resharding mode");
+            return false;
+        };
+
+        *self = Self::Single(new.clone());
+        true
+    }
+
+    /// Revert to the `old` hashring, aborting resharding in the given direction.
+    ///
+    /// Returns `true` if the hashring was in resharding mode for the given shard.
+    pub fn abort_resharding(&mut self, shard: T, direction: ReshardingDirection) -> bool
+    where
+        T: fmt::Display,
+    {
+        match direction {
+            ReshardingDirection::Up => self.remove_resharding(shard),
+            ReshardingDirection::Down => {
+                let Self::Resharding { old, .. } = self else {
+                    log::warn!(
+                        "aborting resharding of shard {shard}, \
+                         but hashring is not in resharding mode"
+                    );
+                    return false;
+                };
+
+                *self = Self::Single(old.clone());
+                true
+            }
+        }
+    }
+
     pub fn remove_resharding(&mut self, shard: T) -> bool
     where
         T: fmt::Display,
@@ -140,17 +187,14 @@ impl<T: Hash + Copy + PartialEq> HashRing<T> {
     pub fn get<U: Hash>(&self, key: &U) -> ShardIds<T> {
         match self {
             Self::Single(ring) => ring.get(key).into_iter().cloned().collect(),
-            // TODO(resharding): just use the old hash ring for now, never route to two shards
-            // TODO(resharding): switch to both as commented below once read folding is implemented
-            Self::Resharding { old, new: _ } => old.get(key).into_iter().cloned().collect(),
-            // Self::Resharding { old, new } => old
-            //     .get(key)
-            //     .into_iter()
-            //     .chain(new.get(key))
-            //     // Both hash rings may return the same shard ID, take it once
-            //     .dedup()
-            //     .cloned()
-            //     .collect(),
+            Self::Resharding { old, new } => old
+                .get(key)
+                .into_iter()
+                .chain(new.get(key))
+                // Both hash rings may return the same shard ID, take it once
+                .dedup()
+                .cloned()
+                .collect(),
         }
     }
 
@@ -167,6 +211,16 @@ impl<T: Hash + Copy + PartialEq> HashRing<T> {
             Self::Resharding { old, new } => old.get(key) != new.get(key),
         }
     }
+
+    /// Get the shard holding the up to date copy of the given point
+    ///
+    /// While resharding, this is the shard in the old hashring: updates are applied to it until
+    /// the new hashring is committed for writes, which replaces this resharding hashring.
+    pub fn owner<U: Hash>(&self, key: &U) -> Option<T> {
+        match self {
+            Self::Single(ring) | Self::Resharding { old: ring, .. } => ring.get(key).copied(),
+        }
+    }
 }
 
 /// List type for shard IDs
@@ -296,4 +350,61 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_resharding_up() {
+        let mut ring = HashRing::single();
+        for shard in 0..3 {
+            ring.add(shard);
+        }
+        let initial = ring.clone();
+
+        ring.start_resharding(3, ReshardingDirection::Up);
+        assert!(ring.is_resharding());
+
+        for i in 0..1000 {
+            let shards = ring.get(&i);
+            if ring.has_moved(&i) {
+                assert_eq!(shards.len(), 2);
+                assert_eq!(shards[1], 3);
+            } else {
+                assert_eq!(shards.as_slice(), initial.get(&i).as_slice());
+            }
+
+            // Points are owned by their old shard until resharding is committed
+            assert_eq!(ring.owner(&i), initial.owner(&i));
+        }
+
+        let mut aborted = ring.clone();
+        assert!(aborted.abort_resharding(3, ReshardingDirection::Up));
+        assert_eq!(aborted, initial);
+
+        assert!(ring.commit_resharding());
+        assert!(!ring.is_resharding());
+        assert!((0..1000).any(|i| ring.get(&i).as_slice() == [3]));
+        assert!((0..1000).any(|i| ring.owner(&i) == Some(3)));
+    }
+
+    #[test]
+    fn test_resharding_down() {
+        let mut ring = HashRing::single();
+        for shard in 0..3 {
+            ring.add(shard);
+        }
+        let initial = ring.clone();
+
+        ring.start_resharding(2, ReshardingDirection::Down);
+
+        for i in 0..1000 {
+            let was_in_removed_shard = initial.get(&i).as_slice() == [2];
+            assert_eq!(ring.has_moved(&i), was_in_removed_shard);
+        }
+
+        let mut aborted = ring.clone();
+        assert!(aborted.abort_resharding(2, ReshardingDirection::Down));
+        assert_eq!(aborted, initial);
+
+        assert!(ring.commit_resharding());
+        assert!((0..1000).all(|i| ring.get(&i).as_slice() != [2]));
+    }
 }
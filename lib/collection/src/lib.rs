@@ -12,9 +12,11 @@ pub mod optimizers_builder;
 pub mod recommendations;
 pub mod save_on_disk;
 pub mod shards;
+pub mod slow_query_log;
 pub mod telemetry;
 mod update_handler;
 pub mod wal;
+pub mod wal_archive;
 pub mod wal_delta;
 
 pub mod events;
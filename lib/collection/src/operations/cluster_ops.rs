@@ -6,6 +6,7 @@ use segment::types::ShardKey;
 use serde::{Deserialize, Serialize};
 use validator::{Validate, ValidationErrors};
 
+use crate::collection::resharding::ReshardingDirection;
 use crate::shards::shard::{PeerId, ShardId};
 use crate::shards::transfer::ShardTransferMethod;
 
@@ -207,6 +208,11 @@ pub struct AbortShardTransfer {
 
 #[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, Validate)]
 pub struct StartResharding {
+    /// Add a new shard (`up`, default) or remove the last shard (`down`)
+    #[serde(default)]
+    pub direction: ReshardingDirection,
+    /// Peer to drive resharding, and to hold the new shard when resharding up.
+    /// If not specified, the peer holding the least shards is selected.
     pub peer_id: Option<PeerId>,
     pub shard_key: Option<ShardKey>,
 }
@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use validator::{Validate, ValidationErrors};
 
-use crate::config::{CollectionParams, WalConfig};
+use crate::config::{CollectionParams, PayloadStorage, WalConfig};
 use crate::operations::types::CollectionResult;
 use crate::optimizers_builder::OptimizersConfig;
 
@@ -110,6 +110,11 @@ pub struct CollectionParamsDiff {
     /// Note: those payload values that are involved in filtering and are indexed - remain in RAM.
     #[serde(default)]
     pub on_disk_payload: Option<bool>,
+    /// Type of storage for point payloads.
+    /// Existing segments are rebuilt with the new storage during optimization.
+    /// Changing only `on_disk_payload` switches back to RocksDB based storage.
+    #[serde(default)]
+    pub payload_storage: Option<PayloadStorage>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, Merge)]
@@ -201,7 +206,26 @@ impl DiffConfig<OptimizersConfig> for OptimizersConfigDiff {}
 
 impl DiffConfig<WalConfig> for WalConfigDiff {}
 
-impl DiffConfig<CollectionParams> for CollectionParamsDiff {}
+impl DiffConfig<CollectionParams> for CollectionParamsDiff {
+    /// Keeps `on_disk_payload` consistent with the payload storage
+    fn update(self, config: &CollectionParams) -> CollectionResult<CollectionParams> {
+        let payload_storage = match (self.payload_storage, self.on_disk_payload) {
+            (Some(payload_storage), on_disk_payload) => {
+                payload_storage.check_on_disk_payload(on_disk_payload)?;
+                Some(payload_storage)
+            }
+            (None, Some(_)) => None,
+            (None, None) => config.payload_storage,
+        };
+
+        let mut params: CollectionParams = update_config(config, self)?;
+        params.payload_storage = payload_storage;
+        if let Some(payload_storage) = payload_storage {
+            params.on_disk_payload = payload_storage.is_on_disk();
+        }
+        Ok(params)
+    }
+}
 
 impl From<HnswConfig> for HnswConfigDiff {
     fn from(config: HnswConfig) -> Self {
@@ -328,7 +352,7 @@ impl Validate for QuantizationConfigDiff {
 
 #[cfg(test)]
 mod tests {
-    use segment::types::{Distance, HnswConfig};
+    use segment::types::{Distance, HnswConfig, PayloadStorageType};
 
     use super::*;
     use crate::operations::vector_params_builder::VectorParamsBuilder;
@@ -348,6 +372,7 @@ mod tests {
             write_consistency_factor: Some(NonZeroU32::new(2).unwrap()),
             read_fan_out_factor: None,
             on_disk_payload: None,
+            payload_storage: None,
         };
 
         let new_params = diff.update(&params).unwrap();
@@ -357,6 +382,40 @@ mod tests {
         assert!(!new_params.on_disk_payload);
     }
 
+    #[test]
+    fn test_update_collection_payload_storage() {
+        let params = CollectionParams::empty();
+        let mmap_diff: CollectionParamsDiff =
+            serde_json::from_str(r#"{ "payload_storage": "mmap" }"#).unwrap();
+
+        let new_params = mmap_diff.update(&params).unwrap();
+        assert_eq!(new_params.payload_storage, Some(PayloadStorage::Mmap));
+        assert!(new_params.on_disk_payload);
+        assert_eq!(new_params.payload_storage_type(), PayloadStorageType::Mmap);
+
+        // Other params keep the payload storage
+        let diff: CollectionParamsDiff =
+            serde_json::from_str(r#"{ "write_consistency_factor": 2 }"#).unwrap();
+        let new_params = diff.update(&new_params).unwrap();
+        assert_eq!(new_params.payload_storage, Some(PayloadStorage::Mmap));
+
+        // Changing only `on_disk_payload` switches back to RocksDB
+        let diff: CollectionParamsDiff =
+            serde_json::from_str(r#"{ "on_disk_payload": true }"#).unwrap();
+        let new_params = diff.update(&new_params).unwrap();
+        assert_eq!(new_params.payload_storage, None);
+        assert_eq!(
+            new_params.payload_storage_type(),
+            PayloadStorageType::OnDisk
+        );
+
+        // Contradicting values are rejected
+        let diff: CollectionParamsDiff =
+            serde_json::from_str(r#"{ "payload_storage": "mmap", "on_disk_payload": false }"#)
+                .unwrap();
+        assert!(diff.update(&params).is_err());
+    }
+
     #[test]
     fn test_hnsw_update() {
         let base_config = HnswConfig::default();
@@ -18,7 +18,10 @@ use segment::data_types::vectors::{
     BatchVectorStruct, Named, NamedQuery, NamedVectorStruct, Vector, VectorStruct,
     DEFAULT_VECTOR_NAME,
 };
-use segment::types::{Distance, MultiVectorConfig, QuantizationConfig, ScoredPoint};
+use segment::types::{
+    Distance, MultiVectorConfig, QuantizationConfig, ScoredPoint, SegmentStorageFormat,
+    SparseVectorStorageType,
+};
 use segment::vector_storage::query::{ContextPair, ContextQuery, DiscoveryQuery, RecoQuery};
 use sparse::common::sparse_vector::{validate_sparse_vector_impl, SparseVector};
 use tonic::Status;
@@ -32,7 +35,7 @@ use super::types::{
 };
 use crate::config::{
     default_replication_factor, default_write_consistency_factor, CollectionConfig,
-    CollectionParams, ShardingMethod, WalConfig,
+    CollectionParams, PayloadStorage, ReplicaPlacement, ShardingMethod, WalConfig,
 };
 use crate::lookup::types::WithLookupInterface;
 use crate::lookup::WithLookup;
@@ -81,6 +84,64 @@ pub fn sharding_method_from_proto(sharding_method: i32) -> Result<ShardingMethod
     }
 }
 
+pub fn storage_format_to_proto(storage_format: SegmentStorageFormat) -> i32 {
+    match storage_format {
+        SegmentStorageFormat::RocksDb => api::grpc::qdrant::SegmentStorageFormat::RocksDb as i32,
+        SegmentStorageFormat::Flat => api::grpc::qdrant::SegmentStorageFormat::Flat as i32,
+    }
+}
+
+pub fn storage_format_from_proto(storage_format: i32) -> Result<SegmentStorageFormat, Status> {
+    match storage_format {
+        x if x == api::grpc::qdrant::SegmentStorageFormat::RocksDb as i32 => {
+            Ok(SegmentStorageFormat::RocksDb)
+        }
+        x if x == api::grpc::qdrant::SegmentStorageFormat::Flat as i32 => {
+            Ok(SegmentStorageFormat::Flat)
+        }
+        _ => Err(Status::invalid_argument(format!(
+            "Cannot convert storage format: {}",
+            storage_format
+        ))),
+    }
+}
+
+pub fn payload_storage_to_proto(payload_storage: PayloadStorage) -> i32 {
+    match payload_storage {
+        PayloadStorage::InMemory => api::grpc::qdrant::PayloadStorage::InMemory as i32,
+        PayloadStorage::OnDisk => api::grpc::qdrant::PayloadStorage::OnDisk as i32,
+        PayloadStorage::Mmap => api::grpc::qdrant::PayloadStorage::Mmap as i32,
+    }
+}
+
+pub fn payload_storage_from_proto(payload_storage: i32) -> Result<PayloadStorage, Status> {
+    match payload_storage {
+        x if x == api::grpc::qdrant::PayloadStorage::InMemory as i32 => {
+            Ok(PayloadStorage::InMemory)
+        }
+        x if x == api::grpc::qdrant::PayloadStorage::OnDisk as i32 => Ok(PayloadStorage::OnDisk),
+        x if x == api::grpc::qdrant::PayloadStorage::Mmap as i32 => Ok(PayloadStorage::Mmap),
+        _ => Err(Status::invalid_argument(format!(
+            "Cannot convert payload storage: {}",
+            payload_storage
+        ))),
+    }
+}
+
+impl From<ReplicaPlacement> for api::grpc::qdrant::ReplicaPlacement {
+    fn from(value: ReplicaPlacement) -> Self {
+        let ReplicaPlacement { spread_by_label } = value;
+        Self { spread_by_label }
+    }
+}
+
+impl From<api::grpc::qdrant::ReplicaPlacement> for ReplicaPlacement {
+    fn from(value: api::grpc::qdrant::ReplicaPlacement) -> Self {
+        let api::grpc::qdrant::ReplicaPlacement { spread_by_label } = value;
+        Self { spread_by_label }
+    }
+}
+
 pub fn write_ordering_to_proto(ordering: WriteOrdering) -> api::grpc::qdrant::WriteOrdering {
     api::grpc::qdrant::WriteOrdering {
         r#type: match ordering {
@@ -282,6 +343,10 @@ impl TryFrom<api::grpc::qdrant::CollectionParamsDiff> for CollectionParamsDiff {
                 .transpose()?,
             read_fan_out_factor: value.read_fan_out_factor,
             on_disk_payload: value.on_disk_payload,
+            payload_storage: value
+                .payload_storage
+                .map(payload_storage_from_proto)
+                .transpose()?,
         })
     }
 }
@@ -379,7 +444,10 @@ impl From<CollectionInfo> for api::grpc::qdrant::CollectionInfo {
                     },
                     shard_number: config.params.shard_number.get(),
                     replication_factor: Some(config.params.replication_factor.get()),
+                    replica_placement: config.params.replica_placement.map(Into::into),
                     on_disk_payload: config.params.on_disk_payload,
+                    payload_storage: config.params.payload_storage.map(payload_storage_to_proto),
+                    storage_format: config.params.storage_format.map(storage_format_to_proto),
                     write_consistency_factor: Some(config.params.write_consistency_factor.get()),
                     read_fan_out_factor: config.params.read_fan_out_factor,
                     sharding_method: config.params.sharding_method.map(sharding_method_to_proto),
@@ -614,6 +682,10 @@ impl From<api::grpc::qdrant::SparseVectorParams> for SparseVectorParams {
                 .modifier
                 .and_then(api::grpc::qdrant::Modifier::from_i32)
                 .map(Modifier::from),
+            storage_type: sparse_vector_params
+                .storage_type
+                .and_then(api::grpc::qdrant::SparseVectorStorageType::from_i32)
+                .map(SparseVectorStorageType::from),
         }
     }
 }
@@ -639,6 +711,27 @@ impl From<SparseVectorParams> for api::grpc::qdrant::SparseVectorParams {
             modifier: sparse_vector_params
                 .modifier
                 .map(|modifier| api::grpc::qdrant::Modifier::from(modifier) as i32),
+            storage_type: sparse_vector_params.storage_type.map(|storage_type| {
+                api::grpc::qdrant::SparseVectorStorageType::from(storage_type) as i32
+            }),
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::SparseVectorStorageType> for SparseVectorStorageType {
+    fn from(value: api::grpc::qdrant::SparseVectorStorageType) -> Self {
+        match value {
+            api::grpc::qdrant::SparseVectorStorageType::OnDisk => SparseVectorStorageType::OnDisk,
+            api::grpc::qdrant::SparseVectorStorageType::Mmap => SparseVectorStorageType::Mmap,
+        }
+    }
+}
+
+impl From<SparseVectorStorageType> for api::grpc::qdrant::SparseVectorStorageType {
+    fn from(value: SparseVectorStorageType) -> Self {
+        match value {
+            SparseVectorStorageType::OnDisk => api::grpc::qdrant::SparseVectorStorageType::OnDisk,
+            SparseVectorStorageType::Mmap => api::grpc::qdrant::SparseVectorStorageType::Mmap,
         }
     }
 }
@@ -706,6 +799,14 @@ impl TryFrom<api::grpc::qdrant::CollectionConfig> for CollectionConfig {
                     shard_number: NonZeroU32::new(params.shard_number)
                         .ok_or_else(|| Status::invalid_argument("`shard_number` cannot be zero"))?,
                     on_disk_payload: params.on_disk_payload,
+                    payload_storage: params
+                        .payload_storage
+                        .map(payload_storage_from_proto)
+                        .transpose()?,
+                    storage_format: params
+                        .storage_format
+                        .map(storage_format_from_proto)
+                        .transpose()?,
                     replication_factor: NonZeroU32::new(
                         params
                             .replication_factor
@@ -714,6 +815,7 @@ impl TryFrom<api::grpc::qdrant::CollectionConfig> for CollectionConfig {
                     .ok_or_else(|| {
                         Status::invalid_argument("`replication_factor` cannot be zero")
                     })?,
+                    replica_placement: params.replica_placement.map(Into::into),
                     write_consistency_factor: NonZeroU32::new(
                         params
                             .write_consistency_factor
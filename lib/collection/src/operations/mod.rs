@@ -7,6 +7,7 @@ pub mod operation_effect;
 pub mod payload_ops;
 pub mod point_ops;
 pub mod query_enum;
+pub mod query_explain;
 pub mod shard_selector_internal;
 pub mod shared_storage_config;
 pub mod snapshot_ops;
@@ -54,6 +55,11 @@ pub struct OperationWithClockTag {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub clock_tag: Option<ClockTag>,
+
+    /// Unix timestamp in milliseconds when the operation was received.
+    /// Used to replay archived operations up to a point in time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
 }
 
 impl OperationWithClockTag {
@@ -64,6 +70,7 @@ impl OperationWithClockTag {
         Self {
             operation: operation.into(),
             clock_tag,
+            timestamp: Some(chrono::Utc::now().timestamp_millis().max(0) as u64),
         }
     }
 }
@@ -213,6 +213,14 @@ impl Validate for PointInsertOperations {
 }
 
 impl PointInsertOperations {
+    /// Number of points to insert
+    pub fn points_count(&self) -> usize {
+        match self {
+            PointInsertOperations::PointsBatch(batch) => batch.batch.ids.len(),
+            PointInsertOperations::PointsList(list) => list.points.len(),
+        }
+    }
+
     pub fn decompose(self) -> (Option<ShardKeySelector>, PointInsertOperationsInternal) {
         match self {
             PointInsertOperations::PointsBatch(batch) => (batch.shard_key, batch.batch.into()),
@@ -0,0 +1,43 @@
+use schemars::JsonSchema;
+use segment::data_types::query_explain::SegmentExplain;
+use segment::types::ShardKey;
+use serde::Serialize;
+
+use crate::shards::shard::ShardId;
+
+/// Breakdown of how a read request was executed in each shard and segment
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct QueryExplain {
+    pub shards: Vec<ShardExplain>,
+    pub duration_micros: u64,
+}
+
+/// How a read request was executed in a single shard
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ShardExplain {
+    pub shard_id: ShardId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shard_key: Option<ShardKey>,
+    /// Whether the request was served by the local replica, and broken down into segments.
+    /// Otherwise the request is only timed, see `not_explained` for the reason.
+    pub local: bool,
+    /// Why the request to this shard is not broken down into segments
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not_explained: Option<NotExplainedReason>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<SegmentExplain>,
+    pub duration_micros: u64,
+}
+
+/// Why a shard request could not be explained by the local replica
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotExplainedReason {
+    /// There is no active local replica, the request was served by a remote replica
+    RemoteReplica,
+    /// Shard transfer of the local replica is in progress, the request was served through
+    /// the transfer proxy
+    ShardTransfer,
+    /// Read consistency requires results of multiple replicas
+    ReadConsistency,
+}
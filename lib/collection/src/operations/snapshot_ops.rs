@@ -9,6 +9,7 @@ use url::Url;
 use validator::Validate;
 
 use crate::operations::types::CollectionResult;
+use crate::wal_archive::RecoveryTarget;
 
 /// Defines source of truth for snapshot recovery:
 /// `NoSync` means - restore snapshot without *any* additional synchronization.
@@ -136,6 +137,13 @@ pub fn get_checksum_path(snapshot_path: impl Into<PathBuf>) -> PathBuf {
     checksum_path.into()
 }
 
+/// Path of the manifest stored next to a shard snapshot, used as base for incremental snapshots
+pub fn get_manifest_path(snapshot_path: impl Into<PathBuf>) -> PathBuf {
+    let mut manifest_path = snapshot_path.into().into_os_string();
+    manifest_path.push(".manifest");
+    manifest_path.into()
+}
+
 pub async fn list_snapshots_in_directory(
     directory: &Path,
 ) -> CollectionResult<Vec<SnapshotDescription>> {
@@ -168,6 +176,13 @@ pub struct ShardSnapshotRecover {
     /// Optional API key used when fetching the snapshot from a remote URL.
     #[serde(default)]
     pub api_key: Option<String>,
+
+    /// Replay operations from the WAL archive on top of the snapshot, up to this point.
+    /// Requires WAL archiving to be enabled when the snapshot was created.
+    /// Other replicas of the shard are marked dead and synchronized from the recovered one,
+    /// so it can't be used with `replica` priority.
+    #[serde(default)]
+    pub recover_until: Option<RecoveryTarget>,
 }
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize, schemars::JsonSchema)]
@@ -2,13 +2,20 @@ use std::fs::File;
 use std::io::{BufReader, Read};
 use std::path::{Path, PathBuf};
 
+use bytes::BytesMut;
 use futures::StreamExt;
+use object_store::path::PathPart;
 use object_store::WriteMultipart;
-use tokio::io::AsyncWriteExt;
+use reqwest::header::{HeaderMap, CONTENT_LENGTH};
+use reqwest::{Method, StatusCode};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use url::Url;
 
 use super::snapshot_ops::SnapshotDescription;
 use super::types::{CollectionError, CollectionResult};
 
+const HTTP_UPLOAD_CHUNK_SIZE: usize = 1024 * 1024;
+
 pub(crate) fn trim_dot_slash(path: &Path) -> CollectionResult<object_store::path::Path> {
     // Get file name by trimming the path.
     // if the path is ./path/to/file.txt, the key should be path/to/file.txt
@@ -130,6 +137,95 @@ pub async fn multipart_upload(
     Ok(())
 }
 
+/// Uploads files to a plain HTTP or WebDAV server
+///
+/// WebDAV has no multipart uploads, so each file is streamed in a single `PUT` request instead of
+/// buffering it in memory, as `object_store` does for non-multipart uploads.
+pub struct HttpUploader {
+    client: reqwest::Client,
+    base_url: Url,
+}
+
+impl HttpUploader {
+    pub fn new(base_url: &str, headers: HeaderMap) -> CollectionResult<Self> {
+        let base_url = Url::parse(base_url).map_err(|e| {
+            CollectionError::service_error(format!("Invalid HTTP snapshots storage URL: {}", e))
+        })?;
+
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .map_err(|e| {
+                CollectionError::service_error(format!("Failed to create HTTP client: {}", e))
+            })?;
+
+        Ok(Self { client, base_url })
+    }
+
+    fn url<'a>(&self, parts: impl IntoIterator<Item = PathPart<'a>>) -> CollectionResult<Url> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .map_err(|_| {
+                CollectionError::service_error(format!(
+                    "Invalid HTTP snapshots storage URL: {}",
+                    self.base_url
+                ))
+            })?
+            .pop_if_empty()
+            .extend(parts);
+        Ok(url)
+    }
+
+    pub async fn upload(&self, source_path: &Path, target_path: &Path) -> CollectionResult<()> {
+        let key = trim_dot_slash(target_path)?;
+        let parts: Vec<_> = key.parts().collect();
+
+        // Create parent directories, servers respond with `405 Method Not Allowed` to existing ones
+        let mkcol = Method::from_bytes(b"MKCOL").unwrap();
+        for depth in 1..parts.len() {
+            let mut url = self.url(parts[..depth].iter().cloned())?;
+            url.path_segments_mut().unwrap().push("");
+
+            let response = self
+                .client
+                .request(mkcol.clone(), url)
+                .send()
+                .await
+                .map_err(|e| {
+                    CollectionError::service_error(format!("Failed to create directory: {}", e))
+                })?;
+
+            let status = response.status();
+            if !status.is_success() && status != StatusCode::METHOD_NOT_ALLOWED {
+                return Err(CollectionError::service_error(format!(
+                    "Failed to create directory for {}: {}",
+                    key, status
+                )));
+            }
+        }
+
+        let file = tokio::fs::File::open(source_path).await?;
+        let file_size = file.metadata().await?.len();
+
+        let chunks = futures::stream::try_unfold(file, |mut file| async move {
+            let mut buffer = BytesMut::with_capacity(HTTP_UPLOAD_CHUNK_SIZE);
+            let bytes_read = file.read_buf(&mut buffer).await?;
+            Ok::<_, std::io::Error>((bytes_read > 0).then(|| (buffer.freeze(), file)))
+        });
+
+        self.client
+            .put(self.url(parts)?)
+            .header(CONTENT_LENGTH, file_size)
+            .body(reqwest::Body::wrap_stream(chunks))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| CollectionError::service_error(format!("Failed to upload: {}", e)))?;
+
+        Ok(())
+    }
+}
+
 pub async fn list_snapshot_descriptions(
     client: &dyn object_store::ObjectStore,
     directory: &Path,
@@ -24,12 +24,11 @@ use segment::data_types::vectors::{
 use segment::json_path::{JsonPath, JsonPathInterface};
 use segment::types::{
     Distance, Filter, MultiVectorConfig, Payload, PayloadIndexInfo, PayloadKeyType, PointIdType,
-    QuantizationConfig, SearchParams, SeqNumberType, ShardKey, VectorStorageDatatype,
-    WithPayloadInterface, WithVector,
+    QuantizationConfig, SearchParams, SeqNumberType, ShardKey, SparseVectorStorageType,
+    VectorStorageDatatype, WithPayloadInterface, WithVector,
 };
 use semver::Version;
-use serde;
-use serde::{Deserialize, Serialize};
+use serde::{self, Deserialize, Serialize};
 use serde_json::Error as JsonError;
 use sparse::common::sparse_vector::SparseVector;
 use thiserror::Error;
@@ -49,6 +48,7 @@ use crate::save_on_disk;
 use crate::shards::replica_set::ReplicaState;
 use crate::shards::shard::{PeerId, ShardId};
 use crate::shards::transfer::ShardTransferMethod;
+use crate::slow_query_log::SlowQuery;
 use crate::wal::WalError;
 
 /// Current state of the collection.
@@ -288,6 +288,10 @@ pub struct ScrollRequest {
     /// Specify in which shards to look for the points, if not specified - look in all shards
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub shard_key: Option<ShardKeySelector>,
+    /// If true, the response contains a breakdown of how the request was executed in each
+    /// shard and segment. Default: false
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explain: Option<bool>,
 }
 
 /// Scroll request - paginate over all points which matches given condition
@@ -348,6 +352,10 @@ pub struct SearchRequest {
     /// Specify in which shards to look for the points, if not specified - look in all shards
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub shard_key: Option<ShardKeySelector>,
+    /// If true, the response contains a breakdown of how the request was executed in each
+    /// shard and segment. Default: false
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explain: Option<bool>,
 }
 
 /// Search request.
@@ -801,6 +809,10 @@ pub struct CountRequest {
     /// Specify in which shards to look for the points, if not specified - look in all shards
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub shard_key: Option<ShardKeySelector>,
+    /// If true, the response contains a breakdown of how the request was executed in each
+    /// shard and segment. Default: false
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explain: Option<bool>,
 }
 
 /// Count Request
@@ -1280,6 +1292,7 @@ pub struct VectorParams {
     pub datatype: Option<Datatype>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate]
     pub multivec_config: Option<MultiVectorConfig>,
 }
 
@@ -1327,6 +1340,12 @@ pub struct SparseVectorParams {
     /// Default: none
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub modifier: Option<Modifier>,
+
+    /// Type of storage for sparse vectors. If set to `mmap`, vectors are stored in appendable
+    /// mmap files instead of RocksDB. Existing segments are migrated by the optimizer.
+    /// Default: on_disk
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_type: Option<SparseVectorStorageType>,
 }
 
 impl Anonymize for SparseVectorParams {
@@ -1334,6 +1353,7 @@ impl Anonymize for SparseVectorParams {
         Self {
             index: self.index.anonymize(),
             modifier: self.modifier.clone(),
+            storage_type: self.storage_type,
         }
     }
 }
@@ -1793,17 +1813,30 @@ pub struct IssuesReport {
     pub issues: Vec<IssueRecord>,
 }
 
+/// Latest requests which took longer than the slow query threshold, from oldest to newest
+#[derive(Serialize, JsonSchema, Debug)]
+pub struct SlowQueriesReport {
+    pub queries: Vec<SlowQuery>,
+}
+
+/// Peer label holding the availability zone of a peer
+pub const ZONE_LABEL: &str = "zone";
+
 /// Metadata describing extra properties for each peer
 #[derive(Debug, Hash, Serialize, Deserialize, Clone, Eq, PartialEq)]
 pub struct PeerMetadata {
     /// Peer Qdrant version
     pub(crate) version: Version,
+    /// Labels describing the peer, such as its availability zone
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
 }
 
 impl PeerMetadata {
-    pub fn current() -> Self {
+    pub fn current(labels: BTreeMap<String, String>) -> Self {
         Self {
             version: defaults::QDRANT_VERSION.clone(),
+            labels,
         }
     }
 
@@ -1811,4 +1844,9 @@ impl PeerMetadata {
     pub fn is_different_version(&self) -> bool {
         self.version != *defaults::QDRANT_VERSION
     }
+
+    /// Availability zone of the peer, see [`ZONE_LABEL`]
+    pub fn zone(&self) -> Option<&str> {
+        self.labels.get(ZONE_LABEL).map(String::as_str)
+    }
 }
@@ -15,6 +15,7 @@ use crate::operations::types::{
 };
 use crate::operations::universal_query::shard_query::{ShardQueryRequest, ShardQueryResponse};
 use crate::operations::OperationWithClockTag;
+use crate::shards::local_shard::snapshot_manifest::SnapshotManifest;
 use crate::shards::shard_trait::ShardOperation;
 use crate::shards::telemetry::LocalShardTelemetry;
 
@@ -35,6 +36,7 @@ impl DummyShard {
         _temp_path: &Path,
         _target_path: &Path,
         _save_wal: bool,
+        _base: Option<&SnapshotManifest>,
     ) -> CollectionResult<()> {
         self.dummy()
     }
@@ -47,6 +49,8 @@ impl DummyShard {
         LocalShardTelemetry {
             variant_name: Some("dummy shard".into()),
             segments: vec![],
+            searches: Default::default(),
+            pending_wal_operations: 0,
             optimizations: Default::default(),
         }
     }
@@ -21,8 +21,10 @@ use crate::operations::types::{
 };
 use crate::operations::universal_query::shard_query::{ShardQueryRequest, ShardQueryResponse};
 use crate::operations::{
-    CollectionUpdateOperations, CreateIndex, FieldIndexOperations, OperationWithClockTag,
+    CollectionUpdateOperations, CreateIndex, FieldIndexOperations, OperationToShard,
+    OperationWithClockTag, SplitByShard as _,
 };
+use crate::shards::local_shard::snapshot_manifest::SnapshotManifest;
 use crate::shards::local_shard::LocalShard;
 use crate::shards::remote_shard::RemoteShard;
 use crate::shards::shard_trait::ShardOperation;
@@ -37,16 +39,26 @@ use crate::shards::telemetry::LocalShardTelemetry;
 pub struct ForwardProxyShard {
     pub(crate) wrapped_shard: LocalShard,
     pub(crate) remote_shard: RemoteShard,
+    /// Hashring to filter points and operations with when resharding
+    ///
+    /// If set, only the points and operations routed to the remote shard by this hashring are
+    /// transferred and forwarded. Other ones are kept local.
+    resharding_hashring: Option<HashRing>,
     /// Lock required to protect transfer-in-progress updates.
     /// It should block data updating operations while the batch is being transferred.
     update_lock: Mutex<()>,
 }
 
 impl ForwardProxyShard {
-    pub fn new(wrapped_shard: LocalShard, remote_shard: RemoteShard) -> Self {
+    pub fn new(
+        wrapped_shard: LocalShard,
+        remote_shard: RemoteShard,
+        resharding_hashring: Option<HashRing>,
+    ) -> Self {
         Self {
             wrapped_shard,
             remote_shard,
+            resharding_hashring,
             update_lock: Mutex::new(()),
         }
     }
@@ -86,7 +98,6 @@ impl ForwardProxyShard {
         &self,
         offset: Option<PointIdType>,
         batch_size: usize,
-        hashring_filter: Option<&HashRing>,
         runtime_handle: &Handle,
     ) -> CollectionResult<Option<PointIdType>> {
         debug_assert!(batch_size > 0);
@@ -114,12 +125,8 @@ impl ForwardProxyShard {
 
         let points: Result<Vec<PointStruct>, String> = batch
             .into_iter()
-            // If using a hashring filter, only transfer points that moved, otherwise transfer all
-            .filter(|point| {
-                hashring_filter
-                    .map(|hashring| hashring.has_moved(&point.id))
-                    .unwrap_or(true)
-            })
+            // If resharding, only transfer points that go into the remote shard
+            .filter(|point| self.is_routed_to_remote(&point.id))
             .map(|point| point.try_into())
             .collect();
 
@@ -147,6 +154,37 @@ impl ForwardProxyShard {
         Ok(next_page_offset)
     }
 
+    /// Whether the given point is routed to the remote shard
+    ///
+    /// Always true if not resharding.
+    fn is_routed_to_remote(&self, point_id: &PointIdType) -> bool {
+        self.resharding_hashring.as_ref().map_or(true, |hashring| {
+            hashring.get(point_id).contains(&self.remote_shard.id)
+        })
+    }
+
+    /// Select the part of the given operation to forward to the remote shard
+    ///
+    /// When resharding, the remote is a different shard. We only forward the part of the
+    /// operation that is routed to it, without clock tag, because the remote shard has its own
+    /// clocks.
+    fn operation_for_remote(
+        &self,
+        operation: OperationWithClockTag,
+    ) -> Option<OperationWithClockTag> {
+        let Some(hashring) = &self.resharding_hashring else {
+            return Some(operation);
+        };
+
+        match operation.operation.split_by_shard(hashring) {
+            OperationToShard::ByShard(by_shard) => by_shard
+                .into_iter()
+                .find(|(shard_id, _)| *shard_id == self.remote_shard.id)
+                .map(|(_, operation)| OperationWithClockTag::from(operation)),
+            OperationToShard::ToAll(operation) => Some(OperationWithClockTag::from(operation)),
+        }
+    }
+
     pub fn deconstruct(self) -> (LocalShard, RemoteShard) {
         (self.wrapped_shard, self.remote_shard)
     }
@@ -157,9 +195,10 @@ impl ForwardProxyShard {
         temp_path: &Path,
         target_path: &Path,
         save_wal: bool,
+        base: Option<&SnapshotManifest>,
     ) -> CollectionResult<()> {
         self.wrapped_shard
-            .create_snapshot(temp_path, target_path, save_wal)
+            .create_snapshot(temp_path, target_path, save_wal, base)
             .await
     }
 
@@ -201,9 +240,13 @@ impl ShardOperation for ForwardProxyShard {
         // the transfer needs to have access to the latest version of points.
         let mut result = self.wrapped_shard.update(operation.clone(), true).await?;
 
+        let Some(remote_operation) = self.operation_for_remote(operation) else {
+            return Ok(result);
+        };
+
         let remote_result = self
             .remote_shard
-            .update(operation, false)
+            .update(remote_operation, false)
             .await
             .map_err(|err| CollectionError::forward_proxy_error(self.remote_shard.peer_id, err))?;
 
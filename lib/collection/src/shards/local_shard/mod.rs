@@ -4,6 +4,7 @@ pub(super) mod query;
 pub(super) mod scroll;
 pub(super) mod search;
 pub(super) mod shard_ops;
+pub mod snapshot_manifest;
 
 use std::collections::{BTreeSet, HashMap};
 use std::mem::size_of;
@@ -21,14 +22,16 @@ use common::types::TelemetryDetail;
 use indicatif::{ProgressBar, ProgressStyle};
 use itertools::Itertools;
 use parking_lot::{Mutex as ParkingMutex, RwLock};
+use segment::common::operation_time_statistics::OperationDurationsAggregator;
+use segment::data_types::query_explain::{SegmentExplain, SegmentsExplainCollector};
 use segment::data_types::vectors::VectorElementType;
 use segment::entry::entry_point::SegmentEntry as _;
 use segment::index::field_index::CardinalityEstimation;
 use segment::segment::Segment;
 use segment::segment_constructor::{build_segment, load_segment};
 use segment::types::{
-    CompressionRatio, Filter, PayloadIndexInfo, PayloadKeyType, PayloadStorageType, PointIdType,
-    QuantizationConfig, SegmentConfig, SegmentType,
+    CompressionRatio, Filter, PayloadIndexInfo, PayloadKeyType, PointIdType, QuantizationConfig,
+    SegmentConfig, SegmentType,
 };
 use segment::utils::mem::Mem;
 use tokio::fs::{copy, create_dir_all, remove_dir_all, remove_file};
@@ -39,6 +42,7 @@ use wal::{Wal, WalOptions};
 
 use self::clock_map::{ClockMap, RecoveryPoint};
 use self::disk_usage_watcher::DiskUsageWatcher;
+use self::snapshot_manifest::SnapshotManifest;
 use super::update_tracker::UpdateTracker;
 use crate::collection_manager::collection_updater::CollectionUpdater;
 use crate::collection_manager::holders::segment_holder::{
@@ -50,16 +54,18 @@ use crate::config::CollectionConfig;
 use crate::operations::shared_storage_config::SharedStorageConfig;
 use crate::operations::types::{
     check_sparse_compatible_with_segment_config, CollectionError, CollectionInfoInternal,
-    CollectionResult, CollectionStatus, OptimizersStatus,
+    CollectionResult, CollectionStatus, CountRequestInternal, CountResult, OptimizersStatus,
 };
 use crate::operations::OperationWithClockTag;
 use crate::optimizers_builder::{build_optimizers, clear_temp_segments};
 use crate::shards::shard::ShardId;
 use crate::shards::shard_config::{ShardConfig, SHARD_CONFIG_FILE};
+use crate::shards::shard_trait::ShardOperation as _;
 use crate::shards::telemetry::{LocalShardTelemetry, OptimizerTelemetry};
 use crate::shards::CollectionId;
 use crate::update_handler::{Optimizer, UpdateHandler, UpdateSignal};
 use crate::wal::SerdeWal;
+use crate::wal_archive::{RecoveryTarget, WalArchiveState, WalArchiver};
 use crate::wal_delta::{LockedWal, RecoverableWal};
 
 /// If rendering WAL load progression in basic text form, report progression every 60 seconds.
@@ -83,6 +89,9 @@ pub struct LocalShard {
     pub(super) optimizers_log: Arc<ParkingMutex<TrackerLog>>,
     update_runtime: Handle,
     disk_usage_watcher: DiskUsageWatcher,
+    /// Archives WAL records into the snapshot storage, if enabled
+    wal_archiver: Option<Arc<Mutex<WalArchiver>>>,
+    pub(super) telemetry_search_durations: Arc<ParkingMutex<OperationDurationsAggregator>>,
 }
 
 /// Shard holds information about segments and WAL.
@@ -97,6 +106,7 @@ impl LocalShard {
         move_dir(segments_from, segments_to).await?;
 
         LocalShardClocks::move_data(from, to).await?;
+        WalArchiveState::move_data(from, to).await?;
 
         Ok(())
     }
@@ -125,6 +135,7 @@ impl LocalShard {
         }
 
         LocalShardClocks::delete_data(shard_path).await?;
+        WalArchiveState::delete_data(shard_path).await?;
 
         Ok(())
     }
@@ -139,9 +150,11 @@ impl LocalShard {
         optimizer_cpu_budget: CpuBudget,
         shard_path: &Path,
         clocks: LocalShardClocks,
+        wal_archiver: Option<WalArchiver>,
         update_runtime: Handle,
     ) -> Self {
         let segment_holder = Arc::new(RwLock::new(segment_holder));
+        let wal_archiver = wal_archiver.map(|archiver| Arc::new(Mutex::new(archiver)));
         let config = collection_config.read().await;
         let locked_wal = Arc::new(ParkingMutex::new(wal));
         let optimizers_log = Arc::new(ParkingMutex::new(Default::default()));
@@ -167,6 +180,7 @@ impl LocalShard {
             config.optimizer_config.flush_interval_sec,
             config.optimizer_config.max_optimization_threads,
             clocks.clone(),
+            wal_archiver.clone(),
             shard_path.into(),
         );
 
@@ -191,6 +205,8 @@ impl LocalShard {
             optimizers,
             optimizers_log,
             disk_usage_watcher,
+            wal_archiver,
+            telemetry_search_durations: OperationDurationsAggregator::new(),
         }
     }
 
@@ -310,6 +326,9 @@ impl LocalShard {
 
         let clocks = LocalShardClocks::load(shard_path)?;
 
+        let wal_archiver =
+            WalArchiver::new(&shared_storage_config, &collection_id, id, shard_path, &wal)?;
+
         // Always make sure we have any appendable segments, needed for update operations
         if !segment_holder.has_appendable_segment() {
             debug_assert!(
@@ -331,6 +350,7 @@ impl LocalShard {
             optimizer_cpu_budget,
             shard_path,
             clocks,
+            wal_archiver,
             update_runtime,
         )
         .await;
@@ -435,15 +455,14 @@ impl LocalShard {
 
         for _sid in 0..segment_number {
             let path_clone = segments_path.clone();
-            let segment_config = SegmentConfig {
+            let mut segment_config = SegmentConfig {
                 vector_data: vector_params.clone(),
                 sparse_vector_data: sparse_vector_params.clone(),
-                payload_storage_type: if config.params.on_disk_payload {
-                    PayloadStorageType::OnDisk
-                } else {
-                    PayloadStorageType::InMemory
-                },
+                payload_storage_type: config.params.payload_storage_type(),
+                storage_format: Default::default(),
             };
+            segment_config.storage_format =
+                config.params.segment_storage_format(&segment_config, false);
             let segment = thread::Builder::new()
                 .name(format!("shard-build-{collection_id}-{id}"))
                 .spawn(move || build_segment(&path_clone, &segment_config, true))
@@ -472,6 +491,9 @@ impl LocalShard {
         let wal: SerdeWal<OperationWithClockTag> =
             SerdeWal::new(wal_path.to_str().unwrap(), (&config.wal_config).into())?;
 
+        let wal_archiver =
+            WalArchiver::new(&shared_storage_config, &collection_id, id, shard_path, &wal)?;
+
         let optimizers = build_optimizers(
             shard_path,
             &config.params,
@@ -491,6 +513,7 @@ impl LocalShard {
             optimizer_cpu_budget,
             shard_path,
             LocalShardClocks::default(),
+            wal_archiver,
             update_runtime,
         )
         .await;
@@ -508,6 +531,36 @@ impl LocalShard {
         update_handler.wait_workers_stops().await
     }
 
+    /// Replay operations archived after the snapshot this shard was restored from, up to the
+    /// given recovery target
+    ///
+    /// Returns the number of replayed operations.
+    pub async fn replay_wal_archive(
+        &self,
+        target: &RecoveryTarget,
+        temp_dir: &Path,
+    ) -> CollectionResult<usize> {
+        let Some(wal_archiver) = &self.wal_archiver else {
+            return Err(CollectionError::bad_request(
+                "WAL archiving is not enabled".to_string(),
+            ));
+        };
+
+        let operations = wal_archiver
+            .lock()
+            .await
+            .read_restored_operations(temp_dir)
+            .await?;
+        let operations = target.take_operations(operations);
+        let replayed = operations.len();
+
+        for operation in operations {
+            self.update(operation, true).await?;
+        }
+
+        Ok(replayed)
+    }
+
     /// Loads latest collection operations from WAL
     pub async fn load_from_wal(&self, collection_id: CollectionId) -> CollectionResult<()> {
         let mut newest_clocks = self.wal.newest_clocks.lock().await;
@@ -664,6 +717,12 @@ impl LocalShard {
     pub fn restore_snapshot(snapshot_path: &Path) -> CollectionResult<()> {
         // recover segments
         let segments_path = LocalShard::segments_path(snapshot_path);
+
+        // segments referenced from other snapshots must have been extracted by now
+        if let Some(manifest) = SnapshotManifest::load(snapshot_path)? {
+            manifest.check_resolved(&segments_path)?;
+        }
+
         // iterate over segments directory and recover each segment
         for entry in std::fs::read_dir(segments_path)? {
             let entry_path = entry?.path();
@@ -681,15 +740,23 @@ impl LocalShard {
                 std::fs::remove_file(&entry_path)?;
             }
         }
+
+        // Updates to the restored shard must not be archived into the history of the snapshot
+        WalArchiveState::fork(snapshot_path)?;
+
         Ok(())
     }
 
     /// Create snapshot for local shard into `target_path`
+    ///
+    /// If `base` manifest is given, segments unchanged since the base snapshot are referenced
+    /// instead of archived.
     pub async fn create_snapshot(
         &self,
         temp_path: &Path,
         target_path: &Path,
         save_wal: bool,
+        base: Option<&SnapshotManifest>,
     ) -> CollectionResult<()> {
         let snapshot_shard_path = target_path;
 
@@ -714,6 +781,7 @@ impl LocalShard {
         let segments_path = Self::segments_path(&self.path);
         let collection_params = self.collection_config.read().await.params.clone();
         let temp_path = temp_path.to_owned();
+        let base = base.cloned();
 
         tokio::task::spawn_blocking(move || {
             // Do not change segments while snapshotting
@@ -725,6 +793,9 @@ impl LocalShard {
                 &snapshot_segments_shard_path,
             )?;
 
+            SnapshotManifest::create(&snapshot_segments_shard_path, base.as_ref())?
+                .save(&snapshot_shard_path_owned)?;
+
             if save_wal {
                 // snapshot all shard's WAL
                 Self::snapshot_wal(wal, &snapshot_shard_path_owned)
@@ -735,6 +806,7 @@ impl LocalShard {
         .await??;
 
         LocalShardClocks::copy_data(&self.path, snapshot_shard_path).await?;
+        WalArchiveState::copy_data(&self.path, snapshot_shard_path).await?;
 
         // copy shard's config
         let shard_config_path = ShardConfig::get_config_path(&self.path);
@@ -816,6 +888,43 @@ impl LocalShard {
         Ok(cardinality)
     }
 
+    /// Count points, recording how each segment was read into `explain`
+    pub fn count_with_explain(
+        &self,
+        request: &CountRequestInternal,
+        explain: Option<&SegmentsExplainCollector>,
+    ) -> CollectionResult<CountResult> {
+        let filter = request.filter.as_ref();
+        let segments = self.segments().read();
+
+        let count = if request.exact {
+            let all_points: BTreeSet<_> = segments
+                .non_appendable_then_appendable_segments()
+                .flat_map(|segment| {
+                    let segment = segment.get();
+                    let segment = segment.read();
+                    SegmentExplain::measure(explain, &*segment, filter, || {
+                        segment.read_filtered(None, None, filter)
+                    })
+                })
+                .collect();
+            all_points.len()
+        } else {
+            segments
+                .iter()
+                .map(|(_id, segment)| {
+                    let segment = segment.get();
+                    let segment = segment.read();
+                    SegmentExplain::measure(explain, &*segment, filter, || {
+                        segment.estimate_point_count(filter).exp
+                    })
+                })
+                .sum()
+        };
+
+        Ok(CountResult { count })
+    }
+
     pub fn read_filtered<'a>(
         &'a self,
         filter: Option<&'a Filter>,
@@ -851,13 +960,24 @@ impl LocalShard {
             })
             .fold(Default::default(), |acc, x| acc + x);
 
+        let pending_wal_operations = self.wal.wal.lock().unacknowledged_len() as usize;
+
         LocalShardTelemetry {
             variant_name: None,
             segments,
-            optimizations: OptimizerTelemetry {
-                status: optimizer_status,
-                optimizations,
-                log: self.optimizers_log.lock().to_telemetry(),
+            searches: self
+                .telemetry_search_durations
+                .lock()
+                .get_statistics(detail),
+            pending_wal_operations,
+            optimizations: {
+                let optimizers_log = self.optimizers_log.lock();
+                OptimizerTelemetry {
+                    status: optimizer_status,
+                    optimizations,
+                    optimized_points: optimizers_log.optimized_points(),
+                    log: optimizers_log.to_telemetry(),
+                }
             },
         }
     }
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use futures::future::try_join_all;
 use itertools::Itertools as _;
 use segment::data_types::order_by::{Direction, OrderBy, OrderValue};
+use segment::data_types::query_explain::{SegmentExplain, SegmentsExplainCollector};
 use segment::types::{
     ExtendedPointId, Filter, ScoredPoint, WithPayload, WithPayloadInterface, WithVector,
 };
@@ -60,6 +61,7 @@ impl LocalShard {
                     with_vector,
                     filter.as_ref(),
                     search_runtime_handle,
+                    None,
                 )
                 .await
                 .map(|records| {
@@ -85,6 +87,7 @@ impl LocalShard {
                         filter.as_ref(),
                         search_runtime_handle,
                         &order_by,
+                        None,
                     )
                     .await?;
 
@@ -107,6 +110,59 @@ impl LocalShard {
         }
     }
 
+    /// Scroll, recording how each segment was read into `explain`
+    #[allow(clippy::too_many_arguments)]
+    pub async fn scroll_by_with_explain(
+        &self,
+        offset: Option<ExtendedPointId>,
+        limit: usize,
+        with_payload_interface: &WithPayloadInterface,
+        with_vector: &WithVector,
+        filter: Option<&Filter>,
+        search_runtime_handle: &Handle,
+        order_by: Option<&OrderBy>,
+        explain: Option<&Arc<SegmentsExplainCollector>>,
+    ) -> CollectionResult<Vec<Record>> {
+        match order_by {
+            None => {
+                self.scroll_by_id(
+                    offset,
+                    limit,
+                    with_payload_interface,
+                    with_vector,
+                    filter,
+                    search_runtime_handle,
+                    explain,
+                )
+                .await
+            }
+            Some(order_by) => {
+                let (mut records, values) = self
+                    .scroll_by_field(
+                        limit,
+                        with_payload_interface,
+                        with_vector,
+                        filter,
+                        search_runtime_handle,
+                        order_by,
+                        explain,
+                    )
+                    .await?;
+
+                records.iter_mut().zip(values).for_each(|(record, value)| {
+                    // Add order_by value to the payload. It will be removed in the next step, after crossing the shard boundary.
+                    let new_payload =
+                        OrderBy::insert_order_value_in_payload(record.payload.take(), value);
+
+                    record.payload = Some(new_payload);
+                });
+
+                Ok(records)
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn scroll_by_id(
         &self,
         offset: Option<ExtendedPointId>,
@@ -115,6 +171,7 @@ impl LocalShard {
         with_vector: &WithVector,
         filter: Option<&Filter>,
         search_runtime_handle: &Handle,
+        explain: Option<&Arc<SegmentsExplainCollector>>,
     ) -> CollectionResult<Vec<Record>> {
         let segments = self.segments();
 
@@ -122,12 +179,14 @@ impl LocalShard {
 
         let read_filtered = |segment: LockedSegment| {
             let filter = filter.cloned();
+            let explain = explain.cloned();
 
             search_runtime_handle.spawn_blocking(move || {
-                segment
-                    .get()
-                    .read()
-                    .read_filtered(offset, Some(limit), filter.as_ref())
+                let segment = segment.get();
+                let segment = segment.read();
+                SegmentExplain::measure(explain.as_deref(), &*segment, filter.as_ref(), || {
+                    segment.read_filtered(offset, Some(limit), filter.as_ref())
+                })
             })
         };
 
@@ -152,6 +211,7 @@ impl LocalShard {
         Ok(points)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn scroll_by_field(
         &self,
         limit: usize,
@@ -160,6 +220,7 @@ impl LocalShard {
         filter: Option<&Filter>,
         search_runtime_handle: &Handle,
         order_by: &OrderBy,
+        explain: Option<&Arc<SegmentsExplainCollector>>,
     ) -> CollectionResult<(Vec<Record>, Vec<OrderValue>)> {
         let segments = self.segments();
 
@@ -168,12 +229,14 @@ impl LocalShard {
         let read_ordered_filtered = |segment: LockedSegment| {
             let filter = filter.cloned();
             let order_by = order_by.clone();
+            let explain = explain.cloned();
 
             search_runtime_handle.spawn_blocking(move || {
-                segment
-                    .get()
-                    .read()
-                    .read_ordered_filtered(Some(limit), filter.as_ref(), &order_by)
+                let segment = segment.get();
+                let segment = segment.read();
+                SegmentExplain::measure(explain.as_deref(), &*segment, filter.as_ref(), || {
+                    segment.read_ordered_filtered(Some(limit), filter.as_ref(), &order_by)
+                })
             })
         };
 
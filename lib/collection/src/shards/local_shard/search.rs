@@ -1,6 +1,8 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use segment::common::operation_time_statistics::ScopeDurationMeasurer;
+use segment::data_types::query_explain::SegmentsExplainCollector;
 use segment::types::ScoredPoint;
 use tokio::runtime::Handle;
 
@@ -11,11 +13,42 @@ use crate::operations::query_enum::QueryEnum;
 use crate::operations::types::{CollectionError, CollectionResult, CoreSearchRequestBatch};
 
 impl LocalShard {
+    /// Search with telemetry, recording how the search was executed in each segment into `explain`
+    pub async fn core_search_with_explain(
+        &self,
+        request: Arc<CoreSearchRequestBatch>,
+        search_runtime_handle: &Handle,
+        timeout: Option<Duration>,
+        explain: Option<Arc<SegmentsExplainCollector>>,
+    ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+        let mut timer = ScopeDurationMeasurer::new(&self.telemetry_search_durations);
+        timer.set_success(false);
+
+        let result = self
+            .do_search_with_explain(request, search_runtime_handle, timeout, explain)
+            .await;
+
+        timer.set_success(result.is_ok());
+        result
+    }
+
     pub async fn do_search(
         &self,
         core_request: Arc<CoreSearchRequestBatch>,
         search_runtime_handle: &Handle,
         timeout: Option<Duration>,
+    ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+        self.do_search_with_explain(core_request, search_runtime_handle, timeout, None)
+            .await
+    }
+
+    /// Search, recording how the search was executed in each segment into `explain`
+    pub async fn do_search_with_explain(
+        &self,
+        core_request: Arc<CoreSearchRequestBatch>,
+        search_runtime_handle: &Handle,
+        timeout: Option<Duration>,
+        explain: Option<Arc<SegmentsExplainCollector>>,
     ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
         let is_stopped_guard = StoppingGuard::new();
 
@@ -35,6 +68,11 @@ impl LocalShard {
                 return Ok(vec![]);
             };
 
+            let query_context = match explain {
+                Some(explain) => query_context.with_explain(explain),
+                None => query_context,
+            };
+
             (query_context, collection_config.params.clone())
         };
 
@@ -114,41 +114,17 @@ impl ShardOperation for LocalShard {
         search_runtime_handle: &Handle,
         order_by: Option<&OrderBy>,
     ) -> CollectionResult<Vec<Record>> {
-        match order_by {
-            None => {
-                self.scroll_by_id(
-                    offset,
-                    limit,
-                    with_payload_interface,
-                    with_vector,
-                    filter,
-                    search_runtime_handle,
-                )
-                .await
-            }
-            Some(order_by) => {
-                let (mut records, values) = self
-                    .scroll_by_field(
-                        limit,
-                        with_payload_interface,
-                        with_vector,
-                        filter,
-                        search_runtime_handle,
-                        order_by,
-                    )
-                    .await?;
-
-                records.iter_mut().zip(values).for_each(|(record, value)| {
-                    // Add order_by value to the payload. It will be removed in the next step, after crossing the shard boundary.
-                    let new_payload =
-                        OrderBy::insert_order_value_in_payload(record.payload.take(), value);
-
-                    record.payload = Some(new_payload);
-                });
-
-                Ok(records)
-            }
-        }
+        self.scroll_by_with_explain(
+            offset,
+            limit,
+            with_payload_interface,
+            with_vector,
+            filter,
+            search_runtime_handle,
+            order_by,
+            None,
+        )
+        .await
     }
 
     /// Collect overview information about the shard
@@ -162,18 +138,12 @@ impl ShardOperation for LocalShard {
         search_runtime_handle: &Handle,
         timeout: Option<Duration>,
     ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
-        self.do_search(request, search_runtime_handle, timeout)
+        self.core_search_with_explain(request, search_runtime_handle, timeout, None)
             .await
     }
 
     async fn count(&self, request: Arc<CountRequestInternal>) -> CollectionResult<CountResult> {
-        let total_count = if request.exact {
-            let all_points = self.read_filtered(request.filter.as_ref())?;
-            all_points.len()
-        } else {
-            self.estimate_cardinality(request.filter.as_ref())?.exp
-        };
-        Ok(CountResult { count: total_count })
+        self.count_with_explain(&request, None)
     }
 
     async fn retrieve(
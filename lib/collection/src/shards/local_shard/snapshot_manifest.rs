@@ -0,0 +1,403 @@
+//! Manifest of segment archives in a local shard snapshot, used for incremental snapshots.
+//!
+//! An incremental snapshot only includes archives of segments that changed since its base
+//! snapshot. Unchanged segments are referenced by the content hash of their archive and the name
+//! of the snapshot that actually holds the archive. References are carried over from base to base,
+//! so restoring an incremental snapshot only needs the snapshots it references directly, no matter
+//! how long the chain of incremental snapshots is.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use io::file_operations::{atomic_save_json, read_json};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::operations::types::{CollectionError, CollectionResult};
+
+pub const SNAPSHOT_MANIFEST_FILE: &str = "snapshot_manifest.json";
+
+const SEGMENT_ARCHIVE_EXTENSION: &str = "tar";
+
+/// RocksDB backups in segment archives, which are left out of the segment hash
+///
+/// Backups are not reproducible byte for byte. Any change to the data in RocksDB bumps the segment
+/// version, which is stored in the hashed segment state file, so the hash still changes with it.
+const ROCKSDB_BACKUP_PATHS: [&str; 2] = ["snapshot/db_backup", "snapshot/payload_index_db_backup"];
+
+/// Segment archives of a local shard snapshot
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SnapshotManifest {
+    /// Segment archives by segment ID
+    segments: HashMap<String, SegmentArchive>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+struct SegmentArchive {
+    /// SHA-256 hash of the files in the segment archive
+    hash: String,
+    /// Name of the snapshot holding the segment archive, if not included in this snapshot
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    snapshot: Option<String>,
+}
+
+impl SnapshotManifest {
+    fn path(snapshot_path: &Path) -> PathBuf {
+        snapshot_path.join(SNAPSHOT_MANIFEST_FILE)
+    }
+
+    pub fn load(snapshot_path: &Path) -> CollectionResult<Option<Self>> {
+        let path = Self::path(snapshot_path);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        Ok(Some(read_json(&path)?))
+    }
+
+    pub fn save(&self, snapshot_path: &Path) -> CollectionResult<()> {
+        atomic_save_json(&Self::path(snapshot_path), self)?;
+        Ok(())
+    }
+
+    /// Build manifest of the segment archives in `segments_path`
+    ///
+    /// Archives of segments that are unchanged since the `base` snapshot are removed from
+    /// `segments_path`, and referenced instead.
+    pub fn create(segments_path: &Path, base: Option<&SnapshotManifest>) -> CollectionResult<Self> {
+        let mut segments = HashMap::new();
+
+        for entry in std::fs::read_dir(segments_path)? {
+            let archive_path = entry?.path();
+
+            let Some(segment_id) = segment_archive_id(&archive_path) else {
+                continue;
+            };
+
+            let hash = hash_segment_archive(&archive_path)?;
+
+            let base_snapshot = base
+                .and_then(|base| base.segments.get(&segment_id))
+                .filter(|base_archive| base_archive.hash == hash)
+                .and_then(|base_archive| base_archive.snapshot.clone());
+
+            if base_snapshot.is_some() {
+                std::fs::remove_file(&archive_path)?;
+            }
+
+            segments.insert(
+                segment_id,
+                SegmentArchive {
+                    hash,
+                    snapshot: base_snapshot,
+                },
+            );
+        }
+
+        let manifest = Self { segments };
+
+        if base.is_some()
+            && !manifest.segments.is_empty()
+            && manifest.referenced_snapshots().is_empty()
+        {
+            log::warn!(
+                "Incremental snapshot does not reuse any segment of its base snapshot, all \
+                 segments changed since",
+            );
+        }
+
+        Ok(manifest)
+    }
+
+    /// Use manifest of the stored snapshot `snapshot_name` as base for an incremental snapshot
+    ///
+    /// Segment archives included in the snapshot become references to it.
+    pub fn into_base(mut self, snapshot_name: &str) -> Self {
+        for archive in self.segments.values_mut() {
+            archive
+                .snapshot
+                .get_or_insert_with(|| snapshot_name.to_string());
+        }
+
+        self
+    }
+
+    /// Names of the snapshots holding segment archives referenced by this snapshot
+    pub fn referenced_snapshots(&self) -> BTreeSet<&str> {
+        self.segments
+            .values()
+            .filter_map(|archive| archive.snapshot.as_deref())
+            .collect()
+    }
+
+    /// Extract segment archives referenced from the snapshot `snapshot_name` into `segments_path`
+    ///
+    /// `archive_path` is the shard snapshot archive of `snapshot_name`. Extracted segment archives
+    /// are verified against their hashes in this manifest.
+    ///
+    /// This method performs blocking IO.
+    pub fn extract_referenced(
+        &self,
+        snapshot_name: &str,
+        archive_path: &Path,
+        segments_path: &Path,
+    ) -> CollectionResult<()> {
+        let mut missing: HashMap<_, _> = self
+            .segments
+            .iter()
+            .filter(|(_, archive)| archive.snapshot.as_deref() == Some(snapshot_name))
+            .map(|(segment_id, archive)| (segment_id.as_str(), archive.hash.as_str()))
+            .collect();
+
+        let mut archive = tar::Archive::new(BufReader::new(File::open(archive_path)?));
+
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+
+            let entry_path = entry.path()?.into_owned();
+            let entry_path = entry_path.strip_prefix(".").unwrap_or(&entry_path);
+
+            if entry_path.parent() != Some(Path::new("segments")) {
+                continue;
+            }
+
+            let Some(segment_id) = segment_archive_id(entry_path) else {
+                continue;
+            };
+
+            let Some(hash) = missing.remove(segment_id.as_str()) else {
+                continue;
+            };
+
+            let segment_archive_path = segments_path.join(entry_path.file_name().unwrap());
+            entry.unpack(&segment_archive_path)?;
+
+            if hash_segment_archive(&segment_archive_path)? != hash {
+                return Err(CollectionError::service_error(format!(
+                    "Archive of segment {segment_id} in snapshot {snapshot_name} does not match \
+                     its hash",
+                )));
+            }
+
+            if missing.is_empty() {
+                break;
+            }
+        }
+
+        if !missing.is_empty() {
+            return Err(CollectionError::service_error(format!(
+                "Snapshot {snapshot_name} is missing archives of segments {}",
+                missing.keys().copied().collect::<Vec<_>>().join(", "),
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Check that all segment archives referenced by this snapshot were extracted into
+    /// `segments_path`
+    pub fn check_resolved(&self, segments_path: &Path) -> CollectionResult<()> {
+        for (segment_id, archive) in &self.segments {
+            let Some(snapshot_name) = &archive.snapshot else {
+                continue;
+            };
+
+            let archive_path =
+                segments_path.join(format!("{segment_id}.{SEGMENT_ARCHIVE_EXTENSION}"));
+
+            if !archive_path.exists() {
+                return Err(CollectionError::bad_input(format!(
+                    "Incremental snapshot references segment {segment_id} of snapshot \
+                     {snapshot_name}, which can only be restored as a shard snapshot",
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Get segment ID from the path of a segment archive
+fn segment_archive_id(path: &Path) -> Option<String> {
+    if path.extension()? != SEGMENT_ARCHIVE_EXTENSION {
+        return None;
+    }
+
+    path.file_stem()?.to_str().map(str::to_string)
+}
+
+/// Compute hash of the paths and contents of files in a segment archive
+///
+/// Unlike the hash of the archive itself, it does not depend on file metadata, such as
+/// modification times of the directories created while taking the segment snapshot.
+/// RocksDB backups are skipped, see [`ROCKSDB_BACKUP_PATHS`].
+fn hash_segment_archive(path: &Path) -> CollectionResult<String> {
+    let mut archive = tar::Archive::new(BufReader::new(File::open(path)?));
+    let mut sha = Sha256::new();
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let entry_path = entry.path()?;
+        if ROCKSDB_BACKUP_PATHS
+            .iter()
+            .any(|backup_path| entry_path.starts_with(backup_path))
+        {
+            continue;
+        }
+
+        sha.update(entry.path_bytes());
+        sha.update([0]);
+        sha.update(entry.size().to_le_bytes());
+        std::io::copy(&mut entry, &mut sha)?;
+    }
+
+    Ok(format!("{:x}", sha.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::Builder;
+
+    use super::*;
+
+    fn write_segment_archive(segments_path: &Path, segment_id: &str, contents: &[u8]) {
+        write_segment_archive_files(segments_path, segment_id, &[("files/data", contents)]);
+    }
+
+    fn write_segment_archive_files(
+        segments_path: &Path,
+        segment_id: &str,
+        files: &[(&str, &[u8])],
+    ) {
+        let file = File::create(segments_path.join(format!("{segment_id}.tar"))).unwrap();
+        let mut builder = tar::Builder::new(file);
+
+        for (path, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(rand::random::<u32>().into());
+            header.set_cksum();
+            builder
+                .append_data(&mut header, format!("snapshot/{path}"), *contents)
+                .unwrap();
+        }
+        builder.finish().unwrap();
+    }
+
+    #[test]
+    fn test_rocksdb_backups_are_not_hashed() {
+        let dir = Builder::new()
+            .prefix("snapshot_manifest")
+            .tempdir()
+            .unwrap();
+
+        let base_segments = dir.path().join("base");
+        std::fs::create_dir_all(&base_segments).unwrap();
+        write_segment_archive_files(
+            &base_segments,
+            "a",
+            &[
+                ("db_backup/meta/1", b"backup 1"),
+                ("files/segment.json", b"version 1"),
+            ],
+        );
+        let base = SnapshotManifest::create(&base_segments, None).unwrap();
+
+        // Same segment version, backed up again
+        let segments = dir.path().join("incremental");
+        std::fs::create_dir_all(&segments).unwrap();
+        write_segment_archive_files(
+            &segments,
+            "a",
+            &[
+                ("db_backup/meta/1", b"backup 2"),
+                ("files/segment.json", b"version 1"),
+            ],
+        );
+        let incremental =
+            SnapshotManifest::create(&segments, Some(&base.clone().into_base("base.tar"))).unwrap();
+        assert_eq!(
+            incremental.referenced_snapshots(),
+            BTreeSet::from(["base.tar"])
+        );
+
+        // Segment changed
+        write_segment_archive_files(
+            &segments,
+            "a",
+            &[
+                ("db_backup/meta/1", b"backup 3"),
+                ("files/segment.json", b"version 2"),
+            ],
+        );
+        let incremental =
+            SnapshotManifest::create(&segments, Some(&base.into_base("base.tar"))).unwrap();
+        assert!(incremental.referenced_snapshots().is_empty());
+    }
+
+    #[test]
+    fn test_incremental_snapshot_chain() {
+        let dir = Builder::new()
+            .prefix("snapshot_manifest")
+            .tempdir()
+            .unwrap();
+
+        // Full snapshot
+        let full_path = dir.path().join("full");
+        let full_segments = full_path.join("segments");
+        std::fs::create_dir_all(&full_segments).unwrap();
+        write_segment_archive(&full_segments, "a", b"immutable");
+        write_segment_archive(&full_segments, "b", b"appendable");
+
+        let full = SnapshotManifest::create(&full_segments, None).unwrap();
+        assert!(full.referenced_snapshots().is_empty());
+
+        let mut full_archive =
+            tar::Builder::new(File::create(dir.path().join("full.tar")).unwrap());
+        full_archive.append_dir_all(".", &full_path).unwrap();
+        full_archive.finish().unwrap();
+        drop(full_archive);
+
+        // First incremental snapshot, only `b` changed
+        let first_segments = dir.path().join("first");
+        std::fs::create_dir_all(&first_segments).unwrap();
+        write_segment_archive(&first_segments, "a", b"immutable");
+        write_segment_archive(&first_segments, "b", b"appendable, changed");
+
+        let first =
+            SnapshotManifest::create(&first_segments, Some(&full.into_base("full.tar"))).unwrap();
+        assert_eq!(first.referenced_snapshots(), BTreeSet::from(["full.tar"]));
+        assert!(!first_segments.join("a.tar").exists());
+        assert!(first_segments.join("b.tar").exists());
+
+        // Second incremental snapshot still references `a` in the full snapshot
+        let second_segments = dir.path().join("second");
+        std::fs::create_dir_all(&second_segments).unwrap();
+        write_segment_archive(&second_segments, "a", b"immutable");
+        write_segment_archive(&second_segments, "c", b"new");
+
+        let second =
+            SnapshotManifest::create(&second_segments, Some(&first.into_base("first.tar")))
+                .unwrap();
+        assert_eq!(second.referenced_snapshots(), BTreeSet::from(["full.tar"]));
+        assert!(second.check_resolved(&second_segments).is_err());
+
+        // Restore the chain
+        second
+            .extract_referenced("full.tar", &dir.path().join("full.tar"), &second_segments)
+            .unwrap();
+        second.check_resolved(&second_segments).unwrap();
+
+        let restored = SnapshotManifest::create(&second_segments, None).unwrap();
+        assert_eq!(restored.segments["a"].hash, second.segments["a"].hash);
+    }
+}
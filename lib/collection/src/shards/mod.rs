@@ -4,10 +4,12 @@ mod conversions;
 pub mod dummy_shard;
 pub mod forward_proxy_shard;
 pub mod local_shard;
+pub mod peer_groups;
 pub mod proxy_shard;
 pub mod queue_proxy_shard;
 pub mod remote_shard;
 pub mod replica_set;
+pub mod resharding;
 pub mod resolve;
 pub mod shard;
 pub mod shard_config;
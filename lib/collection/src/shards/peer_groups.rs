@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use crate::config::ReplicaPlacement;
+use crate::operations::types::PeerMetadata;
+use crate::shards::shard::PeerId;
+
+/// Peers grouped by the label a collection spreads its shard replicas by
+///
+/// See [`ReplicaPlacement`]. Without a placement constraint all peers are in the same group, so
+/// replicas can be placed on any peer.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PeerGroups {
+    /// Label value of each peer having the label. All other peers form a group of their own.
+    groups: HashMap<PeerId, String>,
+}
+
+impl PeerGroups {
+    pub fn new(
+        placement: Option<&ReplicaPlacement>,
+        peer_metadata: &HashMap<PeerId, PeerMetadata>,
+    ) -> Self {
+        let Some(placement) = placement else {
+            return Self::default();
+        };
+
+        let groups = peer_metadata
+            .iter()
+            .filter_map(|(peer_id, metadata)| {
+                let value = metadata.labels.get(&placement.spread_by_label)?;
+                Some((*peer_id, value.clone()))
+            })
+            .collect();
+
+        Self { groups }
+    }
+
+    /// Group peers by the given label values directly
+    pub fn from_groups(groups: HashMap<PeerId, String>) -> Self {
+        Self { groups }
+    }
+
+    /// Group of the given peer, `None` for peers without the label
+    pub fn group(&self, peer_id: PeerId) -> Option<&str> {
+        self.groups.get(&peer_id).map(String::as_str)
+    }
+
+    /// Number of the given replicas placed in the same group as the given peer
+    pub fn replicas_in_group(&self, replicas: &[PeerId], peer_id: PeerId) -> usize {
+        let group = self.group(peer_id);
+        replicas
+            .iter()
+            .filter(|replica| self.group(**replica) == group)
+            .count()
+    }
+
+    /// Whether a new replica of a shard placed on `replicas` may be added on the `candidate` peer
+    ///
+    /// This is the case if no other peer in `peers`, not holding a replica yet, is in a group
+    /// holding less replicas of the shard.
+    pub fn allows_replica(&self, replicas: &[PeerId], candidate: PeerId, peers: &[PeerId]) -> bool {
+        let candidate_replicas = self.replicas_in_group(replicas, candidate);
+
+        peers
+            .iter()
+            .filter(|peer_id| !replicas.contains(peer_id))
+            .all(|peer_id| self.replicas_in_group(replicas, *peer_id) >= candidate_replicas)
+    }
+
+    /// Whether a replica of a shard placed on `replicas` may be moved from `from` to `to`,
+    /// without spreading the replicas any worse
+    pub fn allows_move(
+        &self,
+        replicas: &[PeerId],
+        from: PeerId,
+        to: PeerId,
+        peers: &[PeerId],
+    ) -> bool {
+        if self.group(from) == self.group(to) {
+            return true;
+        }
+
+        let remaining: Vec<_> = replicas
+            .iter()
+            .copied()
+            .filter(|peer_id| *peer_id != from)
+            .collect();
+
+        self.allows_replica(&remaining, to, peers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Peers 1, 2 in zone `a`, peers 3, 4 in zone `b`, peer 5 in zone `c`, peer 6 unlabeled
+    fn zones() -> PeerGroups {
+        PeerGroups::from_groups(HashMap::from([
+            (1, "a".to_string()),
+            (2, "a".to_string()),
+            (3, "b".to_string()),
+            (4, "b".to_string()),
+            (5, "c".to_string()),
+        ]))
+    }
+
+    #[test]
+    fn test_unconstrained_placement() {
+        let groups = PeerGroups::default();
+        assert!(groups.allows_replica(&[1], 2, &[1, 2, 3]));
+        assert!(groups.allows_move(&[1, 2], 1, 3, &[1, 2, 3]));
+    }
+
+    #[test]
+    fn test_allows_replica() {
+        let groups = zones();
+        let peers = [1, 2, 3, 4, 5, 6];
+
+        assert!(!groups.allows_replica(&[1], 2, &peers));
+        assert!(groups.allows_replica(&[1], 3, &peers));
+        assert!(groups.allows_replica(&[1], 6, &peers));
+
+        // Once every group holds a replica, any group may hold a second one
+        assert!(groups.allows_replica(&[1, 3, 5, 6], 2, &peers));
+
+        // Without peers in other groups, replicas stay in the same group
+        assert!(groups.allows_replica(&[1], 2, &[1, 2]));
+    }
+
+    #[test]
+    fn test_allows_move() {
+        let groups = zones();
+        let peers = [1, 2, 3, 4, 5];
+
+        // Within the same group
+        assert!(groups.allows_move(&[1, 3], 1, 2, &peers));
+        // Into an empty group
+        assert!(groups.allows_move(&[1, 2, 3], 2, 5, &peers));
+        // Into a group already holding a replica, while another group holds none
+        assert!(!groups.allows_move(&[1, 3], 1, 4, &peers));
+    }
+}
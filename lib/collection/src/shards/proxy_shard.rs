@@ -25,6 +25,7 @@ use crate::operations::types::{
 };
 use crate::operations::universal_query::shard_query::{ShardQueryRequest, ShardQueryResponse};
 use crate::operations::OperationWithClockTag;
+use crate::shards::local_shard::snapshot_manifest::SnapshotManifest;
 use crate::shards::local_shard::LocalShard;
 use crate::shards::shard_trait::ShardOperation;
 use crate::shards::telemetry::LocalShardTelemetry;
@@ -71,9 +72,10 @@ impl ProxyShard {
         temp_path: &Path,
         target_path: &Path,
         save_wal: bool,
+        base: Option<&SnapshotManifest>,
     ) -> CollectionResult<()> {
         self.wrapped_shard
-            .create_snapshot(temp_path, target_path, save_wal)
+            .create_snapshot(temp_path, target_path, save_wal, base)
             .await
     }
 
@@ -112,6 +112,14 @@ impl RemoteShard {
         }
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "info",
+            skip_all,
+            fields(collection = %self.collection_id, peer_id = self.peer_id, shard_id = self.id),
+        )
+    )]
     async fn with_points_client<T, O: Future<Output = Result<T, Status>>>(
         &self,
         f: impl Fn(PointsInternalClient<InterceptedService<Channel, AddTimeout>>) -> O,
@@ -128,6 +136,14 @@ impl RemoteShard {
             .map_err(|err| err.into())
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "info",
+            skip_all,
+            fields(collection = %self.collection_id, peer_id = self.peer_id, shard_id = self.id),
+        )
+    )]
     async fn with_collections_client<T, O: Future<Output = Result<T, Status>>>(
         &self,
         f: impl Fn(CollectionsInternalClient<InterceptedService<Channel, AddTimeout>>) -> O,
@@ -144,6 +160,14 @@ impl RemoteShard {
             .map_err(|err| err.into())
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "info",
+            skip_all,
+            fields(collection = %self.collection_id, peer_id = self.peer_id, shard_id = self.id),
+        )
+    )]
     async fn with_shard_snapshots_client_timeout<T, O: Future<Output = Result<T, Status>>>(
         &self,
         f: impl Fn(ShardSnapshotsClient<InterceptedService<Channel, AddTimeout>>) -> O,
@@ -167,6 +191,14 @@ impl RemoteShard {
             .map_err(|err| err.into())
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "info",
+            skip_all,
+            fields(collection = %self.collection_id, peer_id = self.peer_id, shard_id = self.id),
+        )
+    )]
     async fn with_qdrant_client<T, Fut: Future<Output = Result<T, Status>>>(
         &self,
         f: impl Fn(QdrantClient<InterceptedService<Channel, AddTimeout>>) -> Fut,
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::fmt::Write as _;
 use std::ops::Deref as _;
 
@@ -8,16 +9,17 @@ use rand::seq::SliceRandom as _;
 
 use super::ShardReplicaSet;
 use crate::operations::consistency_params::{ReadConsistency, ReadConsistencyType};
-use crate::operations::types::{CollectionError, CollectionResult};
+use crate::operations::types::{CollectionError, CollectionResult, PeerMetadata};
 use crate::shards::remote_shard::RemoteShard;
 use crate::shards::resolve::{Resolve, ResolveCondition};
-use crate::shards::shard::Shard;
+use crate::shards::shard::{PeerId, Shard};
 use crate::shards::shard_trait::ShardOperation;
 
 impl ShardReplicaSet {
     /// Execute read op. on replica set:
     /// 1 - Prefer local replica
-    /// 2 - Otherwise uses `read_fan_out_ratio` to compute list of active remote shards.
+    /// 2 - Otherwise uses `read_fan_out_ratio` to compute list of active remote shards,
+    ///     preferring remote shards in the same zone.
     /// 3 - Fallbacks to all remaining shards if the optimisations fails.
     /// It does not report failing peer_ids to the consensus.
     pub async fn execute_read_operation<Res, F>(
@@ -186,6 +188,14 @@ impl ShardReplicaSet {
 
         active_remotes.shuffle(&mut rand::thread_rng());
 
+        // Prefer remotes in the same zone, other zones are only queried on failure or if more
+        // replicas are required
+        prefer_same_zone(
+            &mut active_remotes,
+            self.this_peer_id(),
+            &self.channel_service.id_to_metadata.read(),
+        );
+
         let remote_operations = active_remotes.into_iter().map(|remote| {
             read_operation(remote)
                 .map(|result| (result, false))
@@ -308,3 +318,76 @@ impl ShardReplicaSet {
         }
     }
 }
+
+/// Move remotes in the same zone as this peer to the front, keeping the order otherwise.
+/// Does nothing if this peer has no zone label.
+fn prefer_same_zone(
+    remotes: &mut [&RemoteShard],
+    this_peer_id: PeerId,
+    peer_metadata: &HashMap<PeerId, PeerMetadata>,
+) {
+    let Some(this_zone) = peer_metadata
+        .get(&this_peer_id)
+        .and_then(PeerMetadata::zone)
+    else {
+        return;
+    };
+
+    remotes.sort_by_key(|remote| {
+        let zone = peer_metadata
+            .get(&remote.peer_id)
+            .and_then(PeerMetadata::zone);
+        zone != Some(this_zone)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::operations::types::ZONE_LABEL;
+    use crate::shards::channel_service::ChannelService;
+
+    fn remote(peer_id: PeerId) -> RemoteShard {
+        RemoteShard::new(0, "test".to_string(), peer_id, ChannelService::default())
+    }
+
+    fn metadata(zone: Option<&str>) -> PeerMetadata {
+        let labels = zone
+            .map(|zone| BTreeMap::from([(ZONE_LABEL.to_string(), zone.to_string())]))
+            .unwrap_or_default();
+        PeerMetadata::current(labels)
+    }
+
+    #[test]
+    fn test_prefer_same_zone() {
+        let remotes: Vec<_> = (2..=6).map(remote).collect();
+        let peer_ids = |remotes: &[&RemoteShard]| -> Vec<PeerId> {
+            remotes.iter().map(|remote| remote.peer_id).collect()
+        };
+
+        let peer_metadata = HashMap::from([
+            (1, metadata(Some("a"))),
+            (2, metadata(Some("b"))),
+            (3, metadata(Some("a"))),
+            (4, metadata(None)),
+            (5, metadata(Some("a"))),
+        ]);
+
+        // Same zone replicas first, the rest keeps its order
+        let mut ordered: Vec<_> = remotes.iter().collect();
+        prefer_same_zone(&mut ordered, 1, &peer_metadata);
+        assert_eq!(peer_ids(&ordered), vec![3, 5, 2, 4, 6]);
+
+        // Order is not changed if this peer has no zone label
+        let mut ordered: Vec<_> = remotes.iter().collect();
+        prefer_same_zone(&mut ordered, 4, &peer_metadata);
+        assert_eq!(peer_ids(&ordered), vec![2, 3, 4, 5, 6]);
+
+        // Or if no peer has labels
+        let mut ordered: Vec<_> = remotes.iter().collect();
+        prefer_same_zone(&mut ordered, 1, &HashMap::new());
+        assert_eq!(peer_ids(&ordered), vec![2, 3, 4, 5, 6]);
+    }
+}
@@ -1,14 +1,22 @@
+use std::future::Future;
+use std::mem;
+use std::ops::Deref as _;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use futures::future::BoxFuture;
 use futures::FutureExt as _;
 use segment::data_types::order_by::OrderBy;
+use segment::data_types::query_explain::SegmentsExplainCollector;
 use segment::types::*;
 
 use super::ShardReplicaSet;
 use crate::operations::consistency_params::ReadConsistency;
+use crate::operations::query_explain::{NotExplainedReason, ShardExplain};
 use crate::operations::types::*;
 use crate::operations::universal_query::shard_query::ShardQueryRequest;
+use crate::shards::local_shard::LocalShard;
+use crate::shards::shard::Shard;
 
 impl ShardReplicaSet {
     #[allow(clippy::too_many_arguments)]
@@ -157,3 +165,299 @@ impl ShardReplicaSet {
         .await
     }
 }
+
+impl ShardReplicaSet {
+    /// Scroll, and explain how each segment of the local replica was read
+    #[allow(clippy::too_many_arguments)]
+    pub async fn explain_scroll_by(
+        &self,
+        offset: Option<ExtendedPointId>,
+        limit: usize,
+        with_payload_interface: &WithPayloadInterface,
+        with_vector: &WithVector,
+        filter: Option<&Filter>,
+        read_consistency: Option<ReadConsistency>,
+        local_only: bool,
+        order_by: Option<&OrderBy>,
+    ) -> CollectionResult<(Vec<Record>, ShardExplain)> {
+        self.execute_explained_read_operation(
+            |local_shard, explain| {
+                let with_payload_interface = with_payload_interface.clone();
+                let with_vector = with_vector.clone();
+                let filter = filter.cloned();
+                let search_runtime = self.search_runtime.clone();
+                let order_by = order_by.cloned();
+
+                async move {
+                    local_shard
+                        .scroll_by_with_explain(
+                            offset,
+                            limit,
+                            &with_payload_interface,
+                            &with_vector,
+                            filter.as_ref(),
+                            &search_runtime,
+                            order_by.as_ref(),
+                            Some(&explain),
+                        )
+                        .await
+                }
+                .boxed()
+            },
+            self.scroll_by(
+                offset,
+                limit,
+                with_payload_interface,
+                with_vector,
+                filter,
+                read_consistency,
+                local_only,
+                order_by,
+            ),
+            read_consistency,
+            local_only,
+        )
+        .await
+    }
+
+    /// Search, and explain how each segment of the local replica was searched
+    pub async fn explain_core_search(
+        &self,
+        request: Arc<CoreSearchRequestBatch>,
+        read_consistency: Option<ReadConsistency>,
+        local_only: bool,
+        timeout: Option<Duration>,
+    ) -> CollectionResult<(Vec<Vec<ScoredPoint>>, ShardExplain)> {
+        self.execute_explained_read_operation(
+            |local_shard, explain| {
+                let request = Arc::clone(&request);
+                let search_runtime = self.search_runtime.clone();
+
+                async move {
+                    local_shard
+                        .core_search_with_explain(request, &search_runtime, timeout, Some(explain))
+                        .await
+                }
+                .boxed()
+            },
+            self.core_search(Arc::clone(&request), read_consistency, local_only, timeout),
+            read_consistency,
+            local_only,
+        )
+        .await
+    }
+
+    /// Count, and explain how each segment of the local replica was read
+    pub async fn explain_count(
+        &self,
+        request: Arc<CountRequestInternal>,
+        read_consistency: Option<ReadConsistency>,
+        local_only: bool,
+    ) -> CollectionResult<(CountResult, ShardExplain)> {
+        self.execute_explained_read_operation(
+            |local_shard, explain| {
+                let request = Arc::clone(&request);
+                async move { local_shard.count_with_explain(&request, Some(&*explain)) }.boxed()
+            },
+            self.count(Arc::clone(&request), read_consistency, local_only),
+            read_consistency,
+            local_only,
+        )
+        .await
+    }
+
+    /// Execute the read operation on the local replica, and collect how it was executed in each
+    /// segment. Falls back to `read_operation`, if the request can't be served by a plain local
+    /// shard alone.
+    async fn execute_explained_read_operation<Res, L>(
+        &self,
+        local_read_operation: L,
+        read_operation: impl Future<Output = CollectionResult<Res>>,
+        read_consistency: Option<ReadConsistency>,
+        local_only: bool,
+    ) -> CollectionResult<(Res, ShardExplain)>
+    where
+        L: for<'a> FnOnce(
+            &'a LocalShard,
+            Arc<SegmentsExplainCollector>,
+        ) -> BoxFuture<'a, CollectionResult<Res>>,
+    {
+        let started = Instant::now();
+
+        let local_result = {
+            let local = self.local.read().await;
+
+            match self.not_explained_reason(local.as_ref(), read_consistency, local_only) {
+                None => {
+                    let Some(Shard::Local(local_shard)) = local.deref() else {
+                        unreachable!("only plain local shard can be explained");
+                    };
+
+                    let explain = Arc::new(SegmentsExplainCollector::default());
+                    let result = local_read_operation(local_shard, Arc::clone(&explain)).await;
+                    let segments = mem::take(&mut *explain.lock());
+                    Ok((result, segments))
+                }
+                Some(reason) => Err(reason),
+            }
+        };
+
+        let (result, not_explained, segments) = match local_result {
+            Ok((result, segments)) => (result?, None, segments),
+            Err(reason) => (read_operation.await?, Some(reason), Vec::new()),
+        };
+
+        let explain = ShardExplain {
+            shard_id: self.shard_id,
+            shard_key: None,
+            local: not_explained.is_none(),
+            not_explained,
+            segments,
+            duration_micros: started.elapsed().as_micros() as u64,
+        };
+
+        Ok((result, explain))
+    }
+
+    /// Why the request can't be served by the plain local replica alone, if so
+    fn not_explained_reason(
+        &self,
+        local: Option<&Shard>,
+        read_consistency: Option<ReadConsistency>,
+        local_only: bool,
+    ) -> Option<NotExplainedReason> {
+        if !matches!(
+            read_consistency.unwrap_or_default(),
+            ReadConsistency::Factor(1),
+        ) {
+            return Some(NotExplainedReason::ReadConsistency);
+        }
+
+        if !local_only && !self.peer_is_active(&self.this_peer_id()) {
+            return Some(NotExplainedReason::RemoteReplica);
+        }
+
+        match local {
+            Some(Shard::Local(_)) => None,
+            Some(Shard::Proxy(_) | Shard::ForwardProxy(_) | Shard::QueueProxy(_)) => {
+                Some(NotExplainedReason::ShardTransfer)
+            }
+            Some(Shard::Dummy(_)) | None => Some(NotExplainedReason::RemoteReplica),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::num::NonZeroU32;
+
+    use common::cpu::CpuBudget;
+    use tempfile::Builder;
+    use tokio::runtime::Handle;
+    use tokio::sync::RwLock;
+
+    use super::*;
+    use crate::config::{CollectionConfig, CollectionParams, WalConfig};
+    use crate::operations::consistency_params::ReadConsistencyType;
+    use crate::operations::vector_params_builder::VectorParamsBuilder;
+    use crate::optimizers_builder::OptimizersConfig;
+    use crate::shards::channel_service::ChannelService;
+    use crate::shards::remote_shard::RemoteShard;
+    use crate::shards::replica_set::ReplicaState;
+
+    #[tokio::test]
+    async fn test_explain_fallback() {
+        let collection_dir = Builder::new().prefix("test_collection").tempdir().unwrap();
+
+        let config = CollectionConfig {
+            params: CollectionParams {
+                vectors: VectorsConfig::Single(VectorParamsBuilder::new(4, Distance::Dot).build()),
+                shard_number: NonZeroU32::new(1).unwrap(),
+                ..CollectionParams::empty()
+            },
+            optimizer_config: OptimizersConfig::fixture(),
+            wal_config: WalConfig {
+                wal_capacity_mb: 1,
+                wal_segments_ahead: 0,
+            },
+            hnsw_config: Default::default(),
+            quantization_config: None,
+        };
+
+        let rs = ShardReplicaSet::build(
+            0,
+            "test_collection".to_string(),
+            1,
+            true,
+            HashSet::new(),
+            Arc::new(|_peer_id, _shard_id| {}),
+            Arc::new(|_shard_transfer, _reason| {}),
+            collection_dir.path(),
+            Arc::new(RwLock::new(config)),
+            Default::default(),
+            Default::default(),
+            Handle::current(),
+            Handle::current(),
+            CpuBudget::default(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let request = Arc::new(CountRequestInternal {
+            filter: None,
+            exact: true,
+        });
+
+        // Local replica is not active, request would be served by a remote replica
+        {
+            let local = rs.local.read().await;
+            assert_eq!(
+                rs.not_explained_reason(local.as_ref(), None, false),
+                Some(NotExplainedReason::RemoteReplica),
+            );
+        }
+
+        rs.set_replica_state(&1, ReplicaState::Active).unwrap();
+
+        let (_, explain) = rs
+            .explain_count(Arc::clone(&request), None, false)
+            .await
+            .unwrap();
+        assert!(explain.local);
+        assert_eq!(explain.not_explained, None);
+
+        // Results of all replicas are required
+        let (_, explain) = rs
+            .explain_count(
+                Arc::clone(&request),
+                Some(ReadConsistency::Type(ReadConsistencyType::All)),
+                false,
+            )
+            .await
+            .unwrap();
+        assert!(!explain.local);
+        assert_eq!(
+            explain.not_explained,
+            Some(NotExplainedReason::ReadConsistency),
+        );
+
+        // Local replica is transferred to another peer
+        let remote = RemoteShard::new(
+            0,
+            "test_collection".to_string(),
+            2,
+            ChannelService::default(),
+        );
+        rs.proxify_local(remote, None).await.unwrap();
+
+        let (_, explain) = rs.explain_count(request, None, false).await.unwrap();
+        assert!(!explain.local);
+        assert_eq!(
+            explain.not_explained,
+            Some(NotExplainedReason::ShardTransfer)
+        );
+        assert!(explain.segments.is_empty());
+    }
+}
@@ -17,10 +17,17 @@ use crate::shards::transfer::transfer_tasks_pool::TransferTaskProgress;
 impl ShardReplicaSet {
     /// Convert `Local` shard into `ForwardProxy`.
     ///
+    /// When resharding, a hashring may be given. Only points and updates routed to the remote shard
+    /// by it are then transferred and forwarded.
+    ///
     /// # Cancel safety
     ///
     /// This method is cancel safe.
-    pub async fn proxify_local(&self, remote_shard: RemoteShard) -> CollectionResult<()> {
+    pub async fn proxify_local(
+        &self,
+        remote_shard: RemoteShard,
+        resharding_hashring: Option<HashRing>,
+    ) -> CollectionResult<()> {
         let mut local = self.local.write().await;
 
         match local.deref() {
@@ -29,7 +36,8 @@ impl ShardReplicaSet {
 
             // If a forward proxy to same remote, return early
             Some(Shard::ForwardProxy(proxy))
-                if proxy.remote_shard.peer_id == remote_shard.peer_id =>
+                if proxy.remote_shard.peer_id == remote_shard.peer_id
+                    && proxy.remote_shard.id == remote_shard.id =>
             {
                 return Ok(())
             }
@@ -75,7 +83,7 @@ impl ShardReplicaSet {
             _ => unreachable!(),
         };
 
-        let proxy_shard = ForwardProxyShard::new(local_shard, remote_shard);
+        let proxy_shard = ForwardProxyShard::new(local_shard, remote_shard, resharding_hashring);
         let _ = local.insert(Shard::ForwardProxy(proxy_shard));
 
         Ok(())
@@ -332,7 +340,6 @@ impl ShardReplicaSet {
         &self,
         offset: Option<PointIdType>,
         batch_size: usize,
-        hashring_filter: Option<&HashRing>,
     ) -> CollectionResult<Option<PointIdType>> {
         let local = self.local.read().await;
 
@@ -344,7 +351,7 @@ impl ShardReplicaSet {
         };
 
         proxy
-            .transfer_batch(offset, batch_size, hashring_filter, &self.search_runtime)
+            .transfer_batch(offset, batch_size, &self.search_runtime)
             .await
     }
 
@@ -452,7 +459,7 @@ impl ShardReplicaSet {
         };
 
         let (local_shard, remote_shard) = queue_proxy.forget_updates_and_finalize();
-        let forward_proxy = ForwardProxyShard::new(local_shard, remote_shard);
+        let forward_proxy = ForwardProxyShard::new(local_shard, remote_shard, None);
         let _ = local.insert(Shard::ForwardProxy(forward_proxy));
 
         Ok(())
@@ -5,9 +5,11 @@ use super::{ReplicaSetState, ReplicaState, ShardReplicaSet, REPLICA_STATE_FILE};
 use crate::operations::types::{CollectionError, CollectionResult};
 use crate::save_on_disk::SaveOnDisk;
 use crate::shards::dummy_shard::DummyShard;
+use crate::shards::local_shard::snapshot_manifest::SnapshotManifest;
 use crate::shards::local_shard::LocalShard;
 use crate::shards::shard::{PeerId, Shard};
 use crate::shards::shard_config::ShardConfig;
+use crate::wal_archive::RecoveryTarget;
 
 impl ShardReplicaSet {
     pub async fn create_snapshot(
@@ -15,12 +17,13 @@ impl ShardReplicaSet {
         temp_path: &Path,
         target_path: &Path,
         save_wal: bool,
+        base: Option<&SnapshotManifest>,
     ) -> CollectionResult<()> {
         let local_read = self.local.read().await;
 
         if let Some(local) = &*local_read {
             local
-                .create_snapshot(temp_path, target_path, save_wal)
+                .create_snapshot(temp_path, target_path, save_wal, base)
                 .await?
         }
 
@@ -70,6 +73,26 @@ impl ShardReplicaSet {
         Ok(())
     }
 
+    /// Replay archived WAL operations on the local replica restored from a snapshot
+    ///
+    /// See [`LocalShard::replay_wal_archive`].
+    pub async fn replay_wal_archive(
+        &self,
+        target: &RecoveryTarget,
+        temp_dir: &Path,
+    ) -> CollectionResult<usize> {
+        let local = self.local.read().await;
+
+        let Some(Shard::Local(local)) = local.deref() else {
+            return Err(CollectionError::bad_request(format!(
+                "shard {} has no local replica to replay WAL archive on",
+                self.shard_id,
+            )));
+        };
+
+        local.replay_wal_archive(target, temp_dir).await
+    }
+
     /// # Cancel safety
     ///
     /// This method is *not* cancel safe.
@@ -0,0 +1,404 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use segment::types::{PointIdType, WithPayloadInterface};
+use tokio::time::sleep;
+
+use crate::collection::resharding::{ReshardKey, ReshardingDirection, ReshardingStage};
+use crate::common::stoppable_task_async::{spawn_async_cancellable, CancellableAsyncTaskHandle};
+use crate::operations::point_ops::{PointOperations, WriteOrdering};
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::operations::CollectionUpdateOperations;
+use crate::shards::channel_service::ChannelService;
+use crate::shards::replica_set::ReplicaState;
+use crate::shards::shard::{PeerId, ShardId};
+use crate::shards::shard_holder::{LockedShardHolder, ShardHolder};
+use crate::shards::transfer::{
+    await_consensus_sync, ShardTransfer, ShardTransferConsensus, ShardTransferMethod,
+};
+use crate::shards::CollectionId;
+
+/// Interval for polling the state of resharding transfers
+const AWAIT_TRANSFER_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Batch size for deleting migrated points from their old shards
+const DELETE_BATCH_SIZE: usize = 500;
+
+/// Drive the resharding process on the driving peer
+///
+/// Resharding goes through these stages, each one is committed through consensus:
+/// 1. migrate points into their new shards with resharding transfers
+/// 2. commit read hashring, reads are now served by the new hashring
+/// 3. commit write hashring, updates are now only applied with the new hashring
+/// 4. when resharding up, delete migrated points from their old shards
+/// 5. finish resharding
+///
+/// The process continues from the current stage, which allows resuming it after restart.
+///
+/// # Cancel safety
+///
+/// This function is cancel safe.
+pub async fn drive_resharding(
+    key: ReshardKey,
+    shard_holder: Arc<LockedShardHolder>,
+    consensus: &dyn ShardTransferConsensus,
+    collection_id: CollectionId,
+    this_peer_id: PeerId,
+    channel_service: ChannelService,
+) -> CollectionResult<()> {
+    let stage = current_stage(&key, &*shard_holder.read().await)?;
+
+    if stage < ReshardingStage::ReadHashRingCommitted {
+        log::debug!("Migrating points of {collection_id} for resharding {key}");
+        migrate_points(&key, &shard_holder, consensus, &collection_id).await?;
+
+        log::debug!("Committing read hashring of {collection_id} for resharding {key}");
+        consensus
+            .commit_read_hashring(collection_id.clone(), key.clone())
+            .await?;
+
+        // All peers must read with the new hashring before old shards stop receiving updates
+        await_consensus_sync(consensus, &channel_service, this_peer_id).await;
+    }
+
+    if stage < ReshardingStage::WriteHashRingCommitted {
+        log::debug!("Committing write hashring of {collection_id} for resharding {key}");
+        consensus
+            .commit_write_hashring(collection_id.clone(), key.clone())
+            .await?;
+
+        // All peers must route updates with the new hashring before we clean up old shards
+        await_consensus_sync(consensus, &channel_service, this_peer_id).await;
+    }
+
+    if key.direction == ReshardingDirection::Up {
+        log::debug!("Deleting migrated points of {collection_id} for resharding {key}");
+        delete_migrated_points(&key, &shard_holder).await?;
+    }
+
+    log::debug!("Finishing resharding {key} of {collection_id}");
+    consensus.finish_resharding(collection_id, key).await?;
+
+    Ok(())
+}
+
+/// Spawn task driving resharding, see [`drive_resharding`]
+///
+/// If driving resharding fails before the write hashring is committed, resharding is aborted
+/// through consensus. After that point it can only be resumed.
+pub fn spawn_resharding_task(
+    key: ReshardKey,
+    shard_holder: Arc<LockedShardHolder>,
+    consensus: Box<dyn ShardTransferConsensus>,
+    collection_id: CollectionId,
+    this_peer_id: PeerId,
+    channel_service: ChannelService,
+) -> CancellableAsyncTaskHandle<bool> {
+    spawn_async_cancellable(move |cancel| async move {
+        let future = drive_resharding(
+            key.clone(),
+            shard_holder.clone(),
+            consensus.as_ref(),
+            collection_id.clone(),
+            this_peer_id,
+            channel_service,
+        );
+
+        let result = cancel::future::cancel_on_token(cancel, future).await;
+
+        let err = match result {
+            Ok(Ok(())) => return true,
+            Ok(Err(err)) => err,
+            // Task was cancelled, resharding was finished or aborted through consensus
+            Err(_) => return false,
+        };
+
+        log::error!("Failed to drive resharding {key} of {collection_id}: {err}");
+
+        let stage = current_stage(&key, &*shard_holder.read().await);
+        if matches!(stage, Ok(stage) if stage < ReshardingStage::WriteHashRingCommitted) {
+            if let Err(err) = consensus
+                .abort_resharding(collection_id.clone(), key.clone(), &err.to_string())
+                .await
+            {
+                log::error!("Failed to abort resharding {key} of {collection_id}: {err}");
+            }
+        }
+
+        false
+    })
+}
+
+fn current_stage(
+    key: &ReshardKey,
+    shard_holder: &ShardHolder,
+) -> CollectionResult<ReshardingStage> {
+    match shard_holder.resharding_state() {
+        Some(state) if state.matches(key) => Ok(state.stage),
+        _ => Err(CollectionError::service_error(format!(
+            "resharding {key} is not in progress"
+        ))),
+    }
+}
+
+/// Migrate all points that move into a different shard, one resharding transfer at a time
+///
+/// When resharding up, points are moved from each existing shard into the new shard. When
+/// resharding down, points are moved from the removed shard into each replica of the remaining
+/// shards.
+async fn migrate_points(
+    key: &ReshardKey,
+    shard_holder: &Arc<LockedShardHolder>,
+    consensus: &dyn ShardTransferConsensus,
+    collection_id: &CollectionId,
+) -> CollectionResult<()> {
+    let transfers = {
+        let shard_holder = shard_holder.read().await;
+        resharding_transfers(key, &shard_holder)?
+    };
+
+    for transfer in transfers {
+        log::debug!(
+            "Resharding {key} of {collection_id}, transferring points from shard {}/{} into shard {:?}/{}",
+            transfer.shard_id,
+            transfer.from,
+            transfer.to_shard_id,
+            transfer.to,
+        );
+
+        shard_holder
+            .read()
+            .await
+            .take_aborted_resharding_transfer(&transfer.key());
+
+        consensus
+            .start_shard_transfer(transfer.clone(), collection_id.clone())
+            .await?;
+
+        await_transfer(key, &transfer, shard_holder).await?;
+    }
+
+    Ok(())
+}
+
+/// List resharding transfers required to migrate all points
+fn resharding_transfers(
+    key: &ReshardKey,
+    shard_holder: &ShardHolder,
+) -> CollectionResult<Vec<ShardTransfer>> {
+    let shard_ids_in_ring = shard_holder
+        .shard_ids_in_ring(&key.shard_key)
+        .filter(|shard_id| *shard_id != key.shard_id);
+
+    let mut transfers = Vec::new();
+
+    for shard_id in shard_ids_in_ring {
+        match key.direction {
+            ReshardingDirection::Up => {
+                let from = active_replica(shard_holder, shard_id, Some(key.peer_id))?;
+                transfers.push(resharding_transfer(
+                    shard_id,
+                    from,
+                    key.peer_id,
+                    key.shard_id,
+                ));
+            }
+
+            ReshardingDirection::Down => {
+                let target = shard_holder.get_shard(&shard_id).ok_or_else(|| {
+                    CollectionError::service_error(format!("shard {shard_id} not found"))
+                })?;
+
+                for (to, state) in target.peers() {
+                    if state != ReplicaState::Active {
+                        continue;
+                    }
+
+                    let from = active_replica(shard_holder, key.shard_id, Some(to))?;
+                    transfers.push(resharding_transfer(key.shard_id, from, to, shard_id));
+                }
+            }
+        }
+    }
+
+    Ok(transfers)
+}
+
+fn resharding_transfer(
+    shard_id: ShardId,
+    from: PeerId,
+    to: PeerId,
+    to_shard_id: ShardId,
+) -> ShardTransfer {
+    ShardTransfer {
+        shard_id,
+        from,
+        to,
+        sync: true,
+        method: Some(ShardTransferMethod::ReshardingStreamRecords),
+        to_shard_id: Some(to_shard_id),
+    }
+}
+
+/// Select a peer with an active replica of the given shard, prefer the given peer
+fn active_replica(
+    shard_holder: &ShardHolder,
+    shard_id: ShardId,
+    prefer: Option<PeerId>,
+) -> CollectionResult<PeerId> {
+    let shard = shard_holder
+        .get_shard(&shard_id)
+        .ok_or_else(|| CollectionError::service_error(format!("shard {shard_id} not found")))?;
+
+    let active_peers: Vec<_> = shard
+        .peers()
+        .into_iter()
+        .filter(|(_, state)| *state == ReplicaState::Active)
+        .map(|(peer_id, _)| peer_id)
+        .collect();
+
+    prefer
+        .filter(|peer_id| active_peers.contains(peer_id))
+        .or_else(|| active_peers.iter().min().copied())
+        .ok_or_else(|| {
+            CollectionError::service_error(format!("shard {shard_id} has no active replica"))
+        })
+}
+
+/// Wait for the given resharding transfer to be finished through consensus
+///
+/// # Errors
+///
+/// Errors if the transfer is aborted, or if resharding is not in progress anymore.
+///
+/// # Cancel safety
+///
+/// This function is cancel safe.
+async fn await_transfer(
+    key: &ReshardKey,
+    transfer: &ShardTransfer,
+    shard_holder: &Arc<LockedShardHolder>,
+) -> CollectionResult<()> {
+    let transfer_key = transfer.key();
+
+    loop {
+        {
+            let shard_holder = shard_holder.read().await;
+
+            current_stage(key, &shard_holder)?;
+
+            // Aborted transfers are registered before they are removed, check in this order
+            if !shard_holder.check_transfer_exists(&transfer_key) {
+                if shard_holder.take_aborted_resharding_transfer(&transfer_key) {
+                    return Err(CollectionError::service_error(format!(
+                        "resharding transfer of shard {} from {} to {} was aborted",
+                        transfer_key.shard_id, transfer_key.from, transfer_key.to,
+                    )));
+                }
+
+                return Ok(());
+            }
+        }
+
+        sleep(AWAIT_TRANSFER_INTERVAL).await;
+    }
+}
+
+/// Delete points that moved into the new shard from all other shards
+///
+/// Must only be called after the write hashring is committed, so no update routes these points
+/// into their old shards anymore.
+///
+/// # Cancel safety
+///
+/// This function is *not* cancel safe. It can be safely repeated though, as deleting points is
+/// idempotent.
+async fn delete_migrated_points(
+    key: &ReshardKey,
+    shard_holder: &Arc<LockedShardHolder>,
+) -> CollectionResult<()> {
+    let (hashring, shard_ids) = {
+        let shard_holder = shard_holder.read().await;
+
+        let hashring = shard_holder
+            .rings
+            .get(&key.shard_key)
+            .cloned()
+            .ok_or_else(|| {
+                CollectionError::service_error(format!(
+                    "hashring for shard key {:?} not found",
+                    key.shard_key,
+                ))
+            })?;
+
+        let shard_ids: Vec<_> = shard_holder
+            .shard_ids_in_ring(&key.shard_key)
+            .filter(|shard_id| *shard_id != key.shard_id)
+            .collect();
+
+        (hashring, shard_ids)
+    };
+
+    for shard_id in shard_ids {
+        let mut offset = None;
+
+        loop {
+            let shard_holder = shard_holder.read().await;
+
+            let Some(replica_set) = shard_holder.get_shard(&shard_id) else {
+                return Err(CollectionError::service_error(format!(
+                    "shard {shard_id} not found"
+                )));
+            };
+
+            let limit = DELETE_BATCH_SIZE + 1;
+            let mut batch = replica_set
+                .scroll_by(
+                    offset,
+                    limit,
+                    &WithPayloadInterface::Bool(false),
+                    &false.into(),
+                    None,
+                    None,
+                    false,
+                    None,
+                )
+                .await?;
+
+            offset = if batch.len() < limit {
+                None
+            } else {
+                batch.pop().map(|point| point.id)
+            };
+
+            let ids: Vec<PointIdType> = batch
+                .into_iter()
+                .map(|point| point.id)
+                .filter(|point_id| !hashring.get(point_id).contains(&shard_id))
+                .collect();
+
+            if !ids.is_empty() {
+                log::trace!(
+                    "Deleting {} migrated points from shard {shard_id} after resharding",
+                    ids.len(),
+                );
+
+                replica_set
+                    .update_with_consistency(
+                        CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+                            ids,
+                        }),
+                        true,
+                        WriteOrdering::default(),
+                    )
+                    .await?;
+            }
+
+            if offset.is_none() {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -9,6 +9,7 @@ use super::update_tracker::UpdateTracker;
 use crate::operations::types::{CollectionError, CollectionResult};
 use crate::shards::dummy_shard::DummyShard;
 use crate::shards::forward_proxy_shard::ForwardProxyShard;
+use crate::shards::local_shard::snapshot_manifest::SnapshotManifest;
 use crate::shards::local_shard::LocalShard;
 use crate::shards::proxy_shard::ProxyShard;
 use crate::shards::queue_proxy_shard::QueueProxyShard;
@@ -80,31 +81,32 @@ impl Shard {
         temp_path: &Path,
         target_path: &Path,
         save_wal: bool,
+        base: Option<&SnapshotManifest>,
     ) -> CollectionResult<()> {
         match self {
             Shard::Local(local_shard) => {
                 local_shard
-                    .create_snapshot(temp_path, target_path, save_wal)
+                    .create_snapshot(temp_path, target_path, save_wal, base)
                     .await
             }
             Shard::Proxy(proxy_shard) => {
                 proxy_shard
-                    .create_snapshot(temp_path, target_path, save_wal)
+                    .create_snapshot(temp_path, target_path, save_wal, base)
                     .await
             }
             Shard::ForwardProxy(proxy_shard) => {
                 proxy_shard
-                    .create_snapshot(temp_path, target_path, save_wal)
+                    .create_snapshot(temp_path, target_path, save_wal, base)
                     .await
             }
             Shard::QueueProxy(proxy_shard) => {
                 proxy_shard
-                    .create_snapshot(temp_path, target_path, save_wal)
+                    .create_snapshot(temp_path, target_path, save_wal, base)
                     .await
             }
             Shard::Dummy(dummy_shard) => {
                 dummy_shard
-                    .create_snapshot(temp_path, target_path, save_wal)
+                    .create_snapshot(temp_path, target_path, save_wal, base)
                     .await
             }
         }
@@ -4,7 +4,9 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use common::cpu::CpuBudget;
+use io::file_operations::{atomic_save_json, read_json};
 use itertools::Itertools;
+use parking_lot::Mutex;
 // TODO rename ReplicaShard to ReplicaSetShard
 use segment::types::ShardKey;
 use tar::Builder as TarBuilder;
@@ -13,16 +15,22 @@ use tokio::sync::RwLock;
 
 use super::replica_set::AbortShardTransfer;
 use super::transfer::transfer_tasks_pool::TransferTasksPool;
+use crate::collection::resharding::{
+    ReshardKey, ReshardingDirection, ReshardingStage, ReshardingState,
+};
+use crate::common::snapshot_encryption::decrypt_snapshot;
+use crate::common::snapshots_manager::SnapshotStorageManager;
 use crate::common::validate_snapshot_archive::validate_open_snapshot_archive;
 use crate::config::{CollectionConfig, ShardingMethod};
 use crate::hash_ring::HashRing;
 use crate::operations::shard_selector_internal::ShardSelectorInternal;
 use crate::operations::shared_storage_config::SharedStorageConfig;
-use crate::operations::snapshot_ops::SnapshotDescription;
+use crate::operations::snapshot_ops::{get_manifest_path, SnapshotDescription};
 use crate::operations::types::{CollectionError, CollectionResult, ShardTransferInfo};
 use crate::operations::{OperationToShard, SplitByShard};
 use crate::save_on_disk::SaveOnDisk;
 use crate::shards::channel_service::ChannelService;
+use crate::shards::local_shard::snapshot_manifest::SnapshotManifest;
 use crate::shards::local_shard::LocalShard;
 use crate::shards::replica_set::{ChangePeerState, ReplicaState, ShardReplicaSet}; // TODO rename ReplicaShard to ReplicaSetShard
 use crate::shards::shard::{PeerId, ShardId};
@@ -40,7 +48,9 @@ pub struct ShardHolder {
     shards: HashMap<ShardId, ShardReplicaSet>,
     pub(crate) shard_transfers: SaveOnDisk<HashSet<ShardTransfer>>,
     pub(crate) rings: HashMap<Option<ShardKey>, HashRing>,
-    resharding: Option<ShardId>,
+    resharding_state: Option<ReshardingState>,
+    /// Resharding transfers aborted through consensus, reported to the resharding driver
+    aborted_resharding_transfers: Mutex<HashSet<ShardTransferKey>>,
     key_mapping: SaveOnDisk<ShardKeyMapping>,
     // Duplicates the information from `key_mapping` for faster access
     // Do not require locking
@@ -50,7 +60,10 @@ pub struct ShardHolder {
 pub type LockedShardHolder = RwLock<ShardHolder>;
 
 impl ShardHolder {
-    pub fn new(collection_path: &Path, resharding: Option<ShardId>) -> CollectionResult<Self> {
+    pub fn new(
+        collection_path: &Path,
+        resharding_state: Option<ReshardingState>,
+    ) -> CollectionResult<Self> {
         let shard_transfers = SaveOnDisk::load_or_init(collection_path.join(SHARD_TRANSFERS_FILE))?;
 
         let key_mapping: SaveOnDisk<ShardKeyMapping> =
@@ -64,23 +77,18 @@ impl ShardHolder {
             }
         }
 
-        let mut rings = HashMap::from([(None, HashRing::single())]);
-
-        if let Some(shard_id) = resharding {
-            rings.insert(
-                shard_id_to_key_mapping.get(&shard_id).cloned(),
-                HashRing::resharding(shard_id),
-            );
-        }
-
-        Ok(Self {
+        let mut shard_holder = Self {
             shards: HashMap::new(),
             shard_transfers,
-            rings,
-            resharding,
+            rings: HashMap::new(),
+            resharding_state,
+            aborted_resharding_transfers: Default::default(),
             key_mapping,
             shard_id_to_key_mapping,
-        })
+        };
+        shard_holder.rebuild_rings();
+
+        Ok(shard_holder)
     }
 
     pub fn save_key_mapping_to_dir(&self, dir: &Path) -> CollectionResult<()> {
@@ -106,25 +114,36 @@ impl ShardHolder {
         Ok(())
     }
 
+    pub fn resharding_state(&self) -> Option<&ReshardingState> {
+        self.resharding_state.as_ref()
+    }
+
+    /// Overwrite resharding state, used when applying collection state from a snapshot
+    pub fn set_resharding_state(&mut self, resharding_state: Option<ReshardingState>) {
+        self.resharding_state = resharding_state;
+        self.rebuild_rings();
+    }
+
     pub fn start_resharding(
         &mut self,
-        shard_id: ShardId,
-        shard: ShardReplicaSet,
-        shard_key: Option<ShardKey>,
+        state: ReshardingState,
+        shard: Option<ShardReplicaSet>,
     ) -> Result<(), CollectionError> {
-        // TODO(resharding):
-        //
-        // `CollectionError::service_error` seems more fitting here... but if `start_resharding`
-        // returns `service_error` here, it will crash consensus thread.
-        //
-        // So it's seems less annoying to allow all of these errors be `bad_request`s for now, and
-        // (maybe) switch (some of) them to `service_error`s later.
-
-        let Some(ring) = self.rings.get_mut(&shard_key) else {
-            // TODO(resharding): `CollectionError::service_error`? 🤔
+        // `CollectionError::service_error` seems more fitting for some of these errors, but if
+        // `start_resharding` returns `service_error` here, it will crash consensus thread. So all
+        // of these errors are `bad_request`s.
+
+        let ReshardingState {
+            shard_id,
+            ref shard_key,
+            direction,
+            ..
+        } = state;
+
+        let Some(ring) = self.rings.get(shard_key) else {
             return Err(CollectionError::bad_request(format!(
                 "shard holder does not contain {} hashring",
-                if let Some(shard_key) = &shard_key {
+                if let Some(shard_key) = shard_key {
                     shard_key as &dyn fmt::Display
                 } else {
                     &"default"
@@ -132,94 +151,231 @@ impl ShardHolder {
             )));
         };
 
-        if ring.is_resharding() {
-            debug_assert!(
-                self.resharding.is_some(),
-                "shard holder contains resharding hashring, but resharding field is {:?}",
-                self.resharding
-            );
+        if let Some(resharding_state) = &self.resharding_state {
+            return Err(CollectionError::bad_request(format!(
+                "shard holder is already resharding: {resharding_state:?}"
+            )));
+        }
+
+        debug_assert!(
+            !ring.is_resharding(),
+            "shard holder contains resharding hashring, but resharding state is not set",
+        );
+
+        match (direction, shard) {
+            (ReshardingDirection::Up, Some(shard)) => {
+                if self.shards.contains_key(&shard_id) {
+                    return Err(CollectionError::bad_request(format!(
+                        "shard holder already contains shard {shard_id} replica set"
+                    )));
+                }
+
+                self.add_shard(shard_id, shard, shard_key.clone())?;
+            }
 
-            // TODO(resharding): `CollectionError::service_error`? 🤔
+            (ReshardingDirection::Up, None) => {
+                return Err(CollectionError::bad_request(format!(
+                    "replica set for new shard {shard_id} is not provided"
+                )));
+            }
+
+            (ReshardingDirection::Down, None) => {
+                if self.shard_id_to_key_mapping.get(&shard_id) != shard_key.as_ref()
+                    || !self.shards.contains_key(&shard_id)
+                {
+                    return Err(CollectionError::bad_request(format!(
+                        "shard holder does not contain shard {shard_id} with shard key {shard_key:?}"
+                    )));
+                }
+
+                if self.shard_ids_in_ring(shard_key).count() < 2 {
+                    return Err(CollectionError::bad_request(format!(
+                        "cannot remove shard {shard_id}, it is the last shard in its hashring"
+                    )));
+                }
+            }
+
+            (ReshardingDirection::Down, Some(_)) => {
+                return Err(CollectionError::bad_request(format!(
+                    "replica set must not be provided when removing shard {shard_id}"
+                )));
+            }
+        }
+
+        self.resharding_state = Some(state);
+        self.rebuild_rings();
+
+        Ok(())
+    }
+
+    /// Serve reads with the new hashring
+    ///
+    /// When resharding up, replicas of the new shard become active. They received all migrated
+    /// points, and all updates since.
+    pub fn commit_read_hashring(&mut self) -> Result<(), CollectionError> {
+        let Some(state) = &mut self.resharding_state else {
             return Err(CollectionError::bad_request(
-                "shard holder already contains resharding hashring".into(),
+                "shard holder is not resharding".to_string(),
             ));
+        };
+
+        if state.direction == ReshardingDirection::Up {
+            let Some(shard) = self.shards.get(&state.shard_id) else {
+                return Err(shard_not_found_error(state.shard_id));
+            };
+
+            for (peer_id, replica_state) in shard.peers() {
+                if replica_state == ReplicaState::Resharding {
+                    shard.set_replica_state(&peer_id, ReplicaState::Active)?;
+                }
+            }
         }
 
-        debug_assert!(
-            self.resharding.is_none(),
-            "shard holder does not contain resharding hashring, but resharding field is {:?}",
-            self.resharding
-        );
+        state.stage = ReshardingStage::ReadHashRingCommitted;
 
-        if self.shards.contains_key(&shard_id) {
-            // TODO(resharding): `CollectionError::service_error`? 🤔
-            return Err(CollectionError::bad_request(format!(
-                "shard holder already contains shard {shard_id} replica set"
-            )));
+        Ok(())
+    }
+
+    /// Route updates with the new hashring only
+    pub fn commit_write_hashring(&mut self) -> Result<(), CollectionError> {
+        let Some(state) = &mut self.resharding_state else {
+            return Err(CollectionError::bad_request(
+                "shard holder is not resharding".to_string(),
+            ));
+        };
+
+        state.stage = ReshardingStage::WriteHashRingCommitted;
+        self.rebuild_rings();
+
+        Ok(())
+    }
+
+    /// Complete resharding
+    ///
+    /// When resharding down, the removed shard is dropped.
+    pub async fn finish_resharding(&mut self) -> Result<(), CollectionError> {
+        let Some(state) = self.resharding_state.take() else {
+            return Err(CollectionError::bad_request(
+                "shard holder is not resharding".to_string(),
+            ));
+        };
+
+        if state.direction == ReshardingDirection::Down {
+            log::debug!("removing shard {} after resharding down", state.shard_id);
+            self.remove_shard_from_key_mapping(state.shard_id)?;
+            self.drop_and_remove_shard(state.shard_id).await?;
         }
 
-        ring.add_resharding(shard_id);
-        self.resharding = Some(shard_id);
-        self.add_shard(shard_id, shard, shard_key)?;
+        self.aborted_resharding_transfers.lock().clear();
+        self.rebuild_rings();
 
         Ok(())
     }
 
+    /// Abort resharding, reverting to the old hashring
+    ///
+    /// When resharding up, the new shard is dropped. Updates are applied to the old hashring until
+    /// the write hashring is committed, so no data is lost.
     pub async fn abort_resharding(
         &mut self,
-        shard_id: ShardId,
-        peer_id: PeerId,
-        shard_key: Option<ShardKey>,
+        key: ReshardKey,
         is_in_progress: bool,
     ) -> Result<(), CollectionError> {
-        let mut removed_resharding = false;
+        let ReshardKey {
+            direction,
+            peer_id,
+            shard_id,
+            ref shard_key,
+        } = key;
+
+        if is_in_progress {
+            self.resharding_state = None;
+        }
 
-        if let Some(ring) = self.rings.get_mut(&shard_key) {
-            log::debug!("removing peer {peer_id} from {shard_key:?} hashring");
-            removed_resharding = ring.remove_resharding(shard_id);
-        } else {
-            log::warn!(
-                "aborting resharding of shard {shard_id} ({peer_id}/{shard_key:?}), \
-                 but {shard_key:?} hashring does not exist"
+        // A new shard that never became part of the collection, all its replicas must be
+        // resharding (or dead) unless we're aborting the resharding in progress
+        let drop_shard = direction == ReshardingDirection::Up
+            && self.get_shard(&shard_id).map_or(false, |shard| {
+                is_in_progress
+                    || shard
+                        .peers()
+                        .values()
+                        .all(|state| matches!(state, ReplicaState::Resharding | ReplicaState::Dead))
+            });
+
+        if drop_shard {
+            log::debug!(
+                "removing shard {shard_id} replica set, because resharding {peer_id}/{shard_key:?} \
+                 is aborted",
             );
+            self.remove_shard_from_key_mapping(shard_id)?;
+            self.drop_and_remove_shard(shard_id).await?;
+        } else if direction == ReshardingDirection::Up && self.get_shard(&shard_id).is_some() {
+            return Err(CollectionError::bad_request(format!(
+                "cannot abort resharding of shard {shard_id} ({peer_id}/{shard_key:?}), \
+                 shard has non-resharding replicas"
+            )));
         }
 
-        if let Some(shard) = self.get_shard(&shard_id) {
-            match shard.peer_state(&peer_id) {
-                Some(ReplicaState::Resharding) => {
-                    log::debug!("removing peer {peer_id} from {shard_id} replica set");
-                    shard.remove_peer(peer_id).await?;
-                }
+        self.aborted_resharding_transfers.lock().clear();
+        self.rebuild_rings();
 
-                Some(ReplicaState::Dead) if is_in_progress || removed_resharding => {
-                    log::debug!("removing dead peer {peer_id} from {shard_id} replica set");
-                    shard.remove_peer(peer_id).await?;
-                }
+        Ok(())
+    }
 
-                Some(state) => {
-                    return Err(CollectionError::bad_request(format!(
-                        "peer {peer_id} is in {state:?} state"
-                    )));
-                }
+    /// Whether the given shard is excluded from reads due to resharding
+    ///
+    /// When resharding up, the new shard is only read from once the read hashring is committed.
+    /// When resharding down, the removed shard is not read from anymore at that point.
+    fn is_hidden_by_resharding(&self, shard_id: ShardId) -> bool {
+        let Some(state) = &self.resharding_state else {
+            return false;
+        };
 
-                None => {
-                    log::warn!(
-                        "aborting resharding of shard {shard_id} ({peer_id}/{shard_key:?}), \
-                         but peer {peer_id} does not exist in {shard_id} replica set"
-                    );
-                }
-            }
+        if state.shard_id != shard_id {
+            return false;
+        }
 
-            if shard.peers().is_empty() {
-                log::debug!("removing {shard_id} replica set, because replica set is empty");
-                self.drop_and_remove_shard(shard_id).await?;
-            }
-        } else {
-            log::warn!(
-                "aborting resharding of shard {shard_id} ({peer_id}/{shard_key:?}), \
-                 but shard holder does not contain {shard_id} replica set",
-            );
+        match state.direction {
+            ReshardingDirection::Up => state.stage < ReshardingStage::ReadHashRingCommitted,
+            ReshardingDirection::Down => state.stage >= ReshardingStage::ReadHashRingCommitted,
         }
+    }
+
+    /// Record resharding transfer aborted through consensus
+    pub fn register_aborted_resharding_transfer(&self, transfer: &ShardTransfer) {
+        if self.resharding_state.is_some() {
+            self.aborted_resharding_transfers
+                .lock()
+                .insert(transfer.key());
+        }
+    }
+
+    /// Check and forget whether the given resharding transfer was aborted
+    pub fn take_aborted_resharding_transfer(&self, key: &ShardTransferKey) -> bool {
+        self.aborted_resharding_transfers.lock().remove(key)
+    }
+
+    /// All shard IDs with the given shard key
+    pub(crate) fn shard_ids_in_ring<'a>(
+        &'a self,
+        shard_key: &'a Option<ShardKey>,
+    ) -> impl Iterator<Item = ShardId> + 'a {
+        self.shards.keys().copied().filter(move |shard_id| {
+            self.shard_id_to_key_mapping.get(shard_id) == shard_key.as_ref()
+        })
+    }
+
+    fn remove_shard_from_key_mapping(&mut self, shard_id: ShardId) -> CollectionResult<()> {
+        let Some(shard_key) = self.shard_id_to_key_mapping.remove(&shard_id) else {
+            return Ok(());
+        };
+
+        self.key_mapping.write_optional(|key_mapping| {
+            let mut key_mapping = key_mapping.clone();
+            let shard_ids = key_mapping.get_mut(&shard_key)?;
+            shard_ids.remove(&shard_id).then_some(key_mapping)
+        })?;
 
         Ok(())
     }
@@ -282,16 +438,35 @@ impl ShardHolder {
     }
 
     fn rebuild_rings(&mut self) {
-        // TODO(resharding): Correctly rebuild resharding hashrings!
-
         let mut rings = HashMap::from([(None, HashRing::single())]);
         let ids_to_key = self.get_shard_id_to_key_mapping();
+
+        // When resharding up, the new shard is added into the hashring with resharding state below
+        let resharding_up_shard = self
+            .resharding_state
+            .as_ref()
+            .filter(|state| state.direction == ReshardingDirection::Up)
+            .map(|state| state.shard_id);
+
         for shard_id in self.shards.keys() {
             let shard_key = ids_to_key.get(shard_id).cloned();
-            rings
-                .entry(shard_key)
-                .or_insert_with(HashRing::single)
-                .add(*shard_id);
+            let ring = rings.entry(shard_key).or_insert_with(HashRing::single);
+
+            if Some(*shard_id) != resharding_up_shard {
+                ring.add(*shard_id);
+            }
+        }
+
+        if let Some(state) = &self.resharding_state {
+            let ring = rings
+                .entry(state.shard_key.clone())
+                .or_insert_with(HashRing::single);
+
+            ring.start_resharding(state.shard_id, state.direction);
+
+            if state.stage >= ReshardingStage::WriteHashRingCommitted {
+                ring.commit_resharding();
+            }
         }
 
         self.rings = rings;
@@ -484,7 +659,7 @@ impl ShardHolder {
             }
             ShardSelectorInternal::All => {
                 for (&shard_id, shard) in self.shards.iter() {
-                    if self.resharding == Some(shard_id) {
+                    if self.is_hidden_by_resharding(shard_id) {
                         continue;
                     }
 
@@ -494,6 +669,10 @@ impl ShardHolder {
             }
             ShardSelectorInternal::ShardKey(shard_key) => {
                 for shard_id in self.get_shard_ids_by_key(shard_key)? {
+                    if self.is_hidden_by_resharding(shard_id) {
+                        continue;
+                    }
+
                     if let Some(replica_set) = self.shards.get(&shard_id) {
                         res.push((replica_set, Some(shard_key)));
                     } else {
@@ -504,6 +683,10 @@ impl ShardHolder {
             ShardSelectorInternal::ShardKeys(shard_keys) => {
                 for shard_key in shard_keys {
                     for shard_id in self.get_shard_ids_by_key(shard_key)? {
+                        if self.is_hidden_by_resharding(shard_id) {
+                            continue;
+                        }
+
                         if let Some(replica_set) = self.shards.get(&shard_id) {
                             res.push((replica_set, Some(shard_key)));
                         } else {
@@ -573,7 +756,19 @@ impl ShardHolder {
             .unwrap_or_default()
         {
             ShardingMethod::Auto => {
-                let ids_list = (0..shard_number).collect::<Vec<_>>();
+                let mut ids_list = (0..shard_number).collect::<Vec<_>>();
+
+                // New shard is not counted in shard number until resharding up is finished
+                if let Some(state) = self
+                    .resharding_state
+                    .as_ref()
+                    .filter(|state| state.direction == ReshardingDirection::Up)
+                {
+                    if !ids_list.contains(&state.shard_id) {
+                        ids_list.push(state.shard_id);
+                    }
+                }
+
                 let shard_id_to_key_mapping = HashMap::new();
                 (ids_list, shard_id_to_key_mapping)
             }
@@ -686,6 +881,9 @@ impl ShardHolder {
                 self.add_shard(shard_id, replica_set, shard_key).unwrap();
             }
         }
+
+        // Shards are added into hashrings one by one above, rebuild them to respect resharding
+        self.rebuild_rings();
     }
 
     pub async fn assert_shard_exists(&self, shard_id: ShardId) -> CollectionResult<()> {
@@ -826,10 +1024,11 @@ impl ShardHolder {
         snapshots_path: &Path,
         collection_name: &str,
         shard_id: ShardId,
+        base: Option<&str>,
         temp_dir: &Path,
     ) -> CollectionResult<SnapshotDescription> {
-        // - `snapshot_temp_dir`, `snapshot_target_dir` and `temp_file` are handled by `tempfile`
-        //   and would be deleted, if future is cancelled
+        // - `snapshot_temp_dir`, `snapshot_target_dir`, `temp_file` and `manifest_temp_path` are
+        //   handled by `tempfile` and would be deleted, if future is cancelled
 
         let shard = self
             .get_shard(&shard_id)
@@ -854,10 +1053,48 @@ impl ShardHolder {
             .prefix(&format!("{snapshot_file_name}-target-"))
             .tempdir_in(temp_dir)?;
 
+        let snapshot_manager = shard.get_snapshots_storage_manager()?;
+
+        let base_manifest = match base {
+            Some(base) => Some(
+                self.download_shard_snapshot_manifest(
+                    &snapshot_manager,
+                    snapshots_path,
+                    shard_id,
+                    base,
+                    temp_dir,
+                )
+                .await?
+                .into_base(base),
+            ),
+            None => None,
+        };
+
+        // Incremental snapshots carry the WAL tail, so they capture all operations
+        // accepted by the shard
         shard
-            .create_snapshot(snapshot_temp_dir.path(), snapshot_target_dir.path(), false)
+            .create_snapshot(
+                snapshot_temp_dir.path(),
+                snapshot_target_dir.path(),
+                base_manifest.is_some(),
+                base_manifest.as_ref(),
+            )
             .await?;
 
+        // Manifest is stored next to the snapshot, so it can be used as base without downloading
+        // the whole snapshot
+        let manifest_temp_path = match SnapshotManifest::load(snapshot_target_dir.path())? {
+            Some(manifest) => {
+                let manifest_temp_path = tempfile::Builder::new()
+                    .prefix(&format!("{snapshot_file_name}-manifest-"))
+                    .tempfile_in(temp_dir)?
+                    .into_temp_path();
+                atomic_save_json(&manifest_temp_path, &manifest)?;
+                Some(manifest_temp_path)
+            }
+            None => None,
+        };
+
         let snapshot_temp_dir_path = snapshot_temp_dir.path().to_path_buf();
         if let Err(err) = snapshot_temp_dir.close() {
             log::error!(
@@ -908,7 +1145,13 @@ impl ShardHolder {
         let snapshot_path =
             self.shard_snapshot_path_unchecked(snapshots_path, shard_id, snapshot_file_name)?;
 
-        let snapshot_manager = shard.get_snapshots_storage_manager()?;
+        // Store manifest first, to avoid making snapshot available without it
+        if let Some(manifest_temp_path) = &manifest_temp_path {
+            snapshot_manager
+                .store_file(manifest_temp_path, &get_manifest_path(&snapshot_path))
+                .await?;
+        }
+
         let snapshot_description = snapshot_manager
             .store_file(temp_file.path(), &snapshot_path)
             .await;
@@ -925,6 +1168,7 @@ impl ShardHolder {
     pub async fn restore_shard_snapshot(
         &self,
         snapshot_path: &Path,
+        snapshots_path: &Path,
         collection_name: &str,
         shard_id: ShardId,
         this_peer_id: PeerId,
@@ -942,7 +1186,18 @@ impl ShardHolder {
 
         let snapshot_file_name = snapshot_path.file_name().unwrap().to_string_lossy();
 
-        let snapshot_path = snapshot_path.to_path_buf();
+        // Snapshots encrypted at rest are decrypted before unpacking
+        let snapshot_manager = self
+            .get_shard(&shard_id)
+            .ok_or_else(|| shard_not_found_error(shard_id))?
+            .get_snapshots_storage_manager()?;
+        let decrypted_snapshot_path =
+            decrypt_snapshot(snapshot_manager.encryption(), snapshot_path, temp_dir).await?;
+
+        let snapshot_path = decrypted_snapshot_path
+            .as_deref()
+            .unwrap_or(snapshot_path)
+            .to_path_buf();
         let snapshot_temp_dir = tempfile::Builder::new()
             .prefix(&format!(
                 "{collection_name}-shard-{shard_id}-{snapshot_file_name}"
@@ -964,6 +1219,28 @@ impl ShardHolder {
                     tar.unpack(&snapshot_temp_dir)?;
                     drop(tar);
 
+                    Ok(())
+                },
+            )
+        };
+
+        task.await??;
+
+        // Incremental snapshot, extract segments referenced from its base snapshots
+        self.extract_referenced_segments(
+            snapshots_path,
+            shard_id,
+            snapshot_temp_dir.path(),
+            temp_dir,
+        )
+        .await?;
+
+        let task = {
+            let snapshot_temp_dir = snapshot_temp_dir.path().to_path_buf();
+
+            cancel::blocking::spawn_cancel_on_token(
+                cancel.child_token(),
+                move |cancel| -> CollectionResult<_> {
                     if cancel.is_cancelled() {
                         return Err(cancel::Error::Cancelled.into());
                     }
@@ -996,6 +1273,165 @@ impl ShardHolder {
         Ok(())
     }
 
+    /// Download manifest of a stored shard snapshot
+    async fn download_shard_snapshot_manifest(
+        &self,
+        snapshot_manager: &SnapshotStorageManager,
+        snapshots_path: &Path,
+        shard_id: ShardId,
+        snapshot_file_name: &str,
+        temp_dir: &Path,
+    ) -> CollectionResult<SnapshotManifest> {
+        let snapshot_path =
+            self.shard_snapshot_path_unchecked(snapshots_path, shard_id, snapshot_file_name)?;
+
+        let manifest_temp_path = tempfile::Builder::new()
+            .prefix(&format!("{snapshot_file_name}-manifest-"))
+            .tempfile_in(temp_dir)?
+            .into_temp_path();
+
+        snapshot_manager
+            .download_file(&get_manifest_path(&snapshot_path), &manifest_temp_path)
+            .await
+            .map_err(|err| {
+                CollectionError::bad_input(format!(
+                    "Can't get manifest of snapshot {snapshot_file_name} of shard {shard_id}: \
+                     {err}",
+                ))
+            })?;
+
+        Ok(read_json(&manifest_temp_path)?)
+    }
+
+    /// Check that no other snapshot of the shard references segments of the snapshot
+    /// `snapshot_file_name`, so that incremental snapshots stay restorable once it is deleted
+    ///
+    /// Snapshots without a manifest are skipped, they can't reference other snapshots.
+    /// Failing to read an existing manifest fails the check, so that a base snapshot is never
+    /// deleted while it might still be referenced.
+    pub async fn check_shard_snapshot_unreferenced(
+        &self,
+        snapshots_path: &Path,
+        shard_id: ShardId,
+        snapshot_file_name: &str,
+        temp_dir: &Path,
+    ) -> CollectionResult<()> {
+        let snapshot_manager = self
+            .get_shard(&shard_id)
+            .ok_or_else(|| shard_not_found_error(shard_id))?
+            .get_snapshots_storage_manager()?;
+
+        let stored_files: HashSet<_> = snapshot_manager
+            .list_files(&self.snapshots_path_for_shard_unchecked(snapshots_path, shard_id))
+            .await?
+            .into_iter()
+            .collect();
+
+        let mut referencing_snapshots = Vec::new();
+
+        for snapshot in self.list_shard_snapshots(snapshots_path, shard_id).await? {
+            if snapshot.name == snapshot_file_name {
+                continue;
+            }
+
+            let manifest_file_name = get_manifest_path(&snapshot.name);
+            if !stored_files.contains(manifest_file_name.to_string_lossy().as_ref()) {
+                continue;
+            }
+
+            let manifest = self
+                .download_shard_snapshot_manifest(
+                    &snapshot_manager,
+                    snapshots_path,
+                    shard_id,
+                    &snapshot.name,
+                    temp_dir,
+                )
+                .await?;
+
+            if manifest.referenced_snapshots().contains(snapshot_file_name) {
+                referencing_snapshots.push(snapshot.name);
+            }
+        }
+
+        if !referencing_snapshots.is_empty() {
+            return Err(CollectionError::bad_request(format!(
+                "Snapshot {snapshot_file_name} of shard {shard_id} is the base of incremental \
+                 snapshots {}, delete them first",
+                referencing_snapshots.join(", "),
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Extract segments referenced by an incremental shard snapshot unpacked at
+    /// `snapshot_shard_path` from the stored snapshots holding them
+    ///
+    /// Does nothing for a full snapshot.
+    async fn extract_referenced_segments(
+        &self,
+        snapshots_path: &Path,
+        shard_id: ShardId,
+        snapshot_shard_path: &Path,
+        temp_dir: &Path,
+    ) -> CollectionResult<()> {
+        let Some(manifest) = SnapshotManifest::load(snapshot_shard_path)? else {
+            return Ok(());
+        };
+
+        let referenced_snapshots = manifest.referenced_snapshots();
+        if referenced_snapshots.is_empty() {
+            return Ok(());
+        }
+
+        let snapshot_manager = self
+            .get_shard(&shard_id)
+            .ok_or_else(|| shard_not_found_error(shard_id))?
+            .get_snapshots_storage_manager()?;
+
+        let segments_path = LocalShard::segments_path(snapshot_shard_path);
+
+        for snapshot_file_name in referenced_snapshots {
+            log::debug!(
+                "Extracting segments of shard {shard_id} from snapshot {snapshot_file_name}"
+            );
+
+            let snapshot_path =
+                self.shard_snapshot_path_unchecked(snapshots_path, shard_id, snapshot_file_name)?;
+
+            let snapshot_temp_path = tempfile::Builder::new()
+                .prefix(&format!("{snapshot_file_name}-"))
+                .tempfile_in(temp_dir)?
+                .into_temp_path();
+
+            snapshot_manager
+                .download_file(&snapshot_path, &snapshot_temp_path)
+                .await
+                .map_err(|err| {
+                    CollectionError::bad_input(format!(
+                        "Can't get snapshot {snapshot_file_name} of shard {shard_id} referenced \
+                         by incremental snapshot: {err}",
+                    ))
+                })?;
+
+            let manifest = manifest.clone();
+            let snapshot_file_name = snapshot_file_name.to_string();
+            let segments_path = segments_path.clone();
+
+            tokio::task::spawn_blocking(move || {
+                manifest.extract_referenced(
+                    &snapshot_file_name,
+                    &snapshot_temp_path,
+                    &segments_path,
+                )
+            })
+            .await??;
+        }
+
+        Ok(())
+    }
+
     /// # Cancel safety
     ///
     /// This method is *not* cancel safe.
@@ -31,6 +31,11 @@ pub struct RemoteShardTelemetry {
 pub struct LocalShardTelemetry {
     pub variant_name: Option<String>,
     pub segments: Vec<SegmentTelemetry>,
+    /// Durations of searches in this shard
+    pub searches: OperationDurationStatistics,
+    /// Number of operations in the WAL after the last acknowledged one. Operations are acknowledged
+    /// once flushed into segments, unless still needed by shard transfers or WAL archiving
+    pub pending_wal_operations: usize,
     pub optimizations: OptimizerTelemetry,
 }
 
@@ -38,6 +43,8 @@ pub struct LocalShardTelemetry {
 pub struct OptimizerTelemetry {
     pub status: OptimizersStatus,
     pub optimizations: OperationDurationStatistics,
+    /// Number of points in segments optimized successfully since start
+    pub optimized_points: usize,
     pub log: Vec<TrackerTelemetry>,
 }
 
@@ -46,6 +53,7 @@ impl Anonymize for OptimizerTelemetry {
         Self {
             status: self.status.clone(),
             optimizations: self.optimizations.anonymize(),
+            optimized_points: self.optimized_points.anonymize(),
             log: self.log.anonymize(),
         }
     }
@@ -56,6 +64,8 @@ impl Anonymize for LocalShardTelemetry {
         LocalShardTelemetry {
             variant_name: self.variant_name.clone(),
             segments: self.segments.anonymize(),
+            searches: self.searches.anonymize(),
+            pending_wal_operations: self.pending_wal_operations.anonymize(),
             optimizations: self.optimizations.anonymize(),
         }
     }
@@ -79,7 +79,6 @@ pub async fn transfer_shard(
                 progress,
                 local_shard_id,
                 remote_shard,
-                collection_name,
             )
             .await?;
         }
@@ -143,7 +143,7 @@ pub fn validate_transfer(
         // Both shard IDs must share the same shard key
         let source_shard_key = shards_key_mapping
             .iter()
-            .find(|(_, shard_ids)| shard_ids.contains(&to_shard_id))
+            .find(|(_, shard_ids)| shard_ids.contains(&transfer.shard_id))
             .map(|(key, _)| key);
         let target_shard_key = shards_key_mapping
             .iter()
@@ -11,6 +11,7 @@ use super::remote_shard::RemoteShard;
 use super::replica_set::ReplicaState;
 use super::shard::{PeerId, ShardId};
 use super::CollectionId;
+use crate::collection::resharding::ReshardKey;
 use crate::operations::types::{CollectionError, CollectionResult};
 
 pub mod driver;
@@ -52,6 +53,11 @@ impl ShardTransfer {
             to: self.to,
         }
     }
+
+    /// Whether this transfer moves points into a different shard as part of resharding
+    pub fn is_resharding(&self) -> bool {
+        self.method == Some(ShardTransferMethod::ReshardingStreamRecords)
+    }
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
@@ -115,7 +121,7 @@ pub enum ShardTransferMethod {
     ReshardingStreamRecords,
 }
 
-/// Interface to consensus for shard transfer operations.
+/// Interface to consensus for shard transfer and resharding operations.
 #[async_trait]
 pub trait ShardTransferConsensus: Send + Sync {
     /// Get the current consensus commit and term state.
@@ -263,6 +269,52 @@ pub trait ShardTransferConsensus: Send + Sync {
         })
     }
 
+    /// Propose to start a shard transfer
+    ///
+    /// This internally awaits consensus to apply the operation on this node.
+    async fn start_shard_transfer(
+        &self,
+        transfer_config: ShardTransfer,
+        collection_name: CollectionId,
+    ) -> CollectionResult<()>;
+
+    /// Propose to switch reads to the new hashring during resharding
+    ///
+    /// This internally awaits consensus to apply the operation on this node.
+    async fn commit_read_hashring(
+        &self,
+        collection_name: CollectionId,
+        reshard_key: ReshardKey,
+    ) -> CollectionResult<()>;
+
+    /// Propose to switch updates to the new hashring during resharding
+    ///
+    /// This internally awaits consensus to apply the operation on this node.
+    async fn commit_write_hashring(
+        &self,
+        collection_name: CollectionId,
+        reshard_key: ReshardKey,
+    ) -> CollectionResult<()>;
+
+    /// Propose to finish resharding
+    ///
+    /// This internally awaits consensus to apply the operation on this node.
+    async fn finish_resharding(
+        &self,
+        collection_name: CollectionId,
+        reshard_key: ReshardKey,
+    ) -> CollectionResult<()>;
+
+    /// Propose to abort resharding
+    ///
+    /// This internally awaits consensus to apply the operation on this node.
+    async fn abort_resharding(
+        &self,
+        collection_name: CollectionId,
+        reshard_key: ReshardKey,
+        reason: &str,
+    ) -> CollectionResult<()>;
+
     /// Wait for all other peers to reach the current consensus
     ///
     /// This will take the current consensus state of this node. It then explicitly awaits on all
@@ -310,7 +362,7 @@ pub trait ShardTransferConsensus: Send + Sync {
 /// # Cancel safety
 ///
 /// This function is cancel safe.
-async fn await_consensus_sync(
+pub(crate) async fn await_consensus_sync(
     consensus: &dyn ShardTransferConsensus,
     channel_service: &ChannelService,
     this_peer_id: PeerId,
@@ -26,10 +26,8 @@ pub(super) async fn transfer_resharding_stream_records(
     progress: Arc<Mutex<TransferTaskProgress>>,
     shard_id: ShardId,
     remote_shard: RemoteShard,
-    collection_name: &str,
 ) -> CollectionResult<()> {
     let remote_peer_id = remote_shard.peer_id;
-    let hashring;
 
     log::debug!(
         "Starting shard {shard_id} transfer to peer {remote_peer_id} by reshard streaming records"
@@ -50,13 +48,24 @@ pub(super) async fn transfer_resharding_stream_records(
             .get_shard_id_to_key_mapping()
             .get(&shard_id)
             .cloned();
-        hashring = shard_holder.rings.get(&shard_key).cloned().ok_or_else(|| {
+        let mut hashring = shard_holder.rings.get(&shard_key).cloned().ok_or_else(|| {
             CollectionError::service_error(format!(
                 "Shard {shard_id} cannot be transferred for resharding, failed to get shard hash rings"
             ))
         })?;
 
-        replica_set.proxify_local(remote_shard.clone()).await?;
+        if !hashring.is_resharding() {
+            return Err(CollectionError::service_error(format!(
+                "Shard {shard_id} cannot be transferred for resharding, resharding is not in progress"
+            )));
+        }
+
+        // Only transfer and forward points that end up in the remote shard after resharding
+        hashring.commit_resharding();
+
+        replica_set
+            .proxify_local(remote_shard.clone(), Some(hashring))
+            .await?;
 
         let Some(count_result) = replica_set
             .count_local(Arc::new(CountRequestInternal {
@@ -91,7 +100,7 @@ pub(super) async fn transfer_resharding_stream_records(
         };
 
         offset = replica_set
-            .transfer_batch(offset, TRANSFER_BATCH_SIZE, Some(&hashring))
+            .transfer_batch(offset, TRANSFER_BATCH_SIZE)
             .await?;
 
         {
@@ -108,37 +117,8 @@ pub(super) async fn transfer_resharding_stream_records(
         }
     }
 
-    // Update cutoff point on remote shard, disallow recovery before our current last seen
-    {
-        let shard_holder = shard_holder.read().await;
-        let Some(replica_set) = shard_holder.get_shard(&shard_id) else {
-            // Forward proxy gone?!
-            // That would be a programming error.
-            return Err(CollectionError::service_error(format!(
-                "Shard {shard_id} is not found"
-            )));
-        };
-
-        let cutoff = replica_set.shard_recovery_point().await?;
-        let result = remote_shard
-            .update_shard_cutoff_point(collection_name, shard_id, &cutoff)
-            .await;
-
-        // Warn and ignore if remote shard is running an older version, error otherwise
-        // TODO: this is fragile, improve this with stricter matches/checks
-        match result {
-            // This string match is fragile but there does not seem to be a better way
-            Err(err)
-                if err.to_string().starts_with(
-                    "Service internal error: Tonic status error: status: Unimplemented",
-                ) =>
-            {
-                log::warn!("Cannot update cutoff point on remote shard because it is running an older version, ignoring: {err}");
-            }
-            Err(err) => return Err(err),
-            Ok(()) => {}
-        }
-    }
+    // Cutoff point is not updated on the remote shard, unlike regular stream records transfer.
+    // The remote is a different shard with its own clocks, our recovery point does not apply.
 
     log::debug!(
         "Ending shard {shard_id} transfer to peer {remote_peer_id} by reshard streaming records"
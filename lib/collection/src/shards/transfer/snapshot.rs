@@ -7,7 +7,7 @@ use tempfile::TempPath;
 
 use super::transfer_tasks_pool::TransferTaskProgress;
 use super::{ShardTransfer, ShardTransferConsensus};
-use crate::operations::snapshot_ops::{get_checksum_path, SnapshotPriority};
+use crate::operations::snapshot_ops::{get_checksum_path, get_manifest_path, SnapshotPriority};
 use crate::operations::types::{CollectionError, CollectionResult};
 use crate::shards::channel_service::ChannelService;
 use crate::shards::remote_shard::RemoteShard;
@@ -193,7 +193,7 @@ pub(super) async fn transfer_snapshot(
     // Create shard snapshot
     log::trace!("Creating snapshot of shard {shard_id} for shard snapshot transfer");
     let snapshot_description = shard_holder_read
-        .create_shard_snapshot(snapshots_path, collection_name, shard_id, temp_dir)
+        .create_shard_snapshot(snapshots_path, collection_name, shard_id, None, temp_dir)
         .await?;
 
     // TODO: If future is cancelled until `get_shard_snapshot_path` resolves, shard snapshot may not be cleaned up...
@@ -207,6 +207,10 @@ pub(super) async fn transfer_snapshot(
             ))
         })?;
     let snapshot_checksum_temp_path = TempPath::from_path(get_checksum_path(&snapshot_temp_path));
+    let snapshot_manifest_path = get_manifest_path(&snapshot_temp_path);
+    let _snapshot_manifest_checksum_temp_path =
+        TempPath::from_path(get_checksum_path(&snapshot_manifest_path));
+    let _snapshot_manifest_temp_path = TempPath::from_path(snapshot_manifest_path);
 
     // Recover shard snapshot on remote
     let mut shard_download_url = local_rest_address;
@@ -42,7 +42,9 @@ pub(super) async fn transfer_stream_records(
             )));
         };
 
-        replica_set.proxify_local(remote_shard.clone()).await?;
+        replica_set
+            .proxify_local(remote_shard.clone(), None)
+            .await?;
 
         let Some(count_result) = replica_set
             .count_local(Arc::new(CountRequestInternal {
@@ -77,7 +79,7 @@ pub(super) async fn transfer_stream_records(
         };
 
         offset = replica_set
-            .transfer_batch(offset, TRANSFER_BATCH_SIZE, None)
+            .transfer_batch(offset, TRANSFER_BATCH_SIZE)
             .await?;
 
         {
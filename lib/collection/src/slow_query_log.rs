@@ -0,0 +1,404 @@
+//! Log of slow read requests.
+//!
+//! Search, scroll and count requests taking longer than the slow query threshold are kept in a
+//! bounded in-memory log, with the sanitized request, the timings of each stage and shard, and
+//! the number of results. Query vectors are not logged. Optionally, slow queries are also
+//! appended to a file as JSON lines by a background thread, so requests never wait on the file.
+//!
+//! Requests forwarded from other peers are not logged, they are logged by the peer which
+//! received them from the client.
+
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{self, Write as _};
+use std::path::Path;
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::OnceLock;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use segment::problems::UnindexedField;
+use segment::types::{Filter, SearchParams, ShardKey, WithPayloadInterface, WithVector};
+use serde::Serialize;
+
+use crate::operations::query_enum::QueryEnum;
+use crate::operations::shard_selector_internal::ShardSelectorInternal;
+use crate::operations::types::CoreSearchRequest;
+use crate::shards::shard::ShardId;
+
+pub const DEFAULT_MAX_ENTRIES: usize = 100;
+
+/// Number of queries waiting to be written to the log file, further queries are not written
+const WRITER_QUEUE_SIZE: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SlowQueryOperation {
+    Search,
+    Scroll,
+    Count,
+}
+
+/// A single request, which took longer than the slow query threshold
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SlowQuery {
+    pub timestamp: DateTime<Utc>,
+    pub collection: String,
+    pub operation: SlowQueryOperation,
+    /// Request without query vectors
+    pub request: serde_json::Value,
+    pub duration_ms: f64,
+    pub stages: Vec<StageTiming>,
+    pub shards: Vec<ShardTiming>,
+    /// Number of returned points, or the number of counted points for count requests
+    pub result_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct StageTiming {
+    pub stage: &'static str,
+    pub duration_ms: f64,
+}
+
+/// Time it took a shard to complete a stage of the request
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ShardTiming {
+    pub stage: &'static str,
+    pub shard_id: ShardId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shard_key: Option<ShardKey>,
+    pub duration_ms: f64,
+}
+
+/// Search request without query vectors
+#[derive(Debug, Serialize)]
+struct SanitizedSearchRequest<'a> {
+    query: &'static str,
+    using: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filter: Option<&'a Filter>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    params: Option<&'a SearchParams>,
+    limit: usize,
+    offset: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    with_payload: Option<&'a WithPayloadInterface>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    with_vector: Option<&'a WithVector>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score_threshold: Option<f32>,
+}
+
+impl<'a> From<&'a CoreSearchRequest> for SanitizedSearchRequest<'a> {
+    fn from(request: &'a CoreSearchRequest) -> Self {
+        let query = match &request.query {
+            QueryEnum::Nearest(_) => "nearest",
+            QueryEnum::RecommendBestScore(_) => "recommend",
+            QueryEnum::Discover(_) => "discover",
+            QueryEnum::Context(_) => "context",
+        };
+
+        Self {
+            query,
+            using: request.query.get_vector_name(),
+            filter: request.filter.as_ref(),
+            params: request.params.as_ref(),
+            limit: request.limit,
+            offset: request.offset,
+            with_payload: request.with_payload.as_ref(),
+            with_vector: request.with_vector.as_ref(),
+            score_threshold: request.score_threshold,
+        }
+    }
+}
+
+/// Sanitized batch of search requests, to be logged
+pub fn search_batch_request(searches: &[CoreSearchRequest]) -> serde_json::Value {
+    let searches: Vec<_> = searches.iter().map(SanitizedSearchRequest::from).collect();
+    serde_json::to_value(searches).unwrap_or_default()
+}
+
+/// Timings of a read request, recorded into the slow query log once it is finished
+pub struct QueryTimings {
+    started: Instant,
+    /// Requests to a specific shard are forwarded from other peers, and are not logged
+    is_forwarded: bool,
+    stages: Mutex<Vec<StageTiming>>,
+    shards: Mutex<Vec<ShardTiming>>,
+}
+
+impl QueryTimings {
+    pub fn new(shard_selection: &ShardSelectorInternal) -> Self {
+        Self {
+            started: Instant::now(),
+            is_forwarded: shard_selection.is_shard_id(),
+            stages: Mutex::new(Vec::new()),
+            shards: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn add_stage(&self, stage: &'static str, duration: Duration) {
+        self.stages.lock().push(StageTiming {
+            stage,
+            duration_ms: duration_ms(duration),
+        });
+    }
+
+    pub fn add_shard(
+        &self,
+        stage: &'static str,
+        shard_id: ShardId,
+        shard_key: Option<ShardKey>,
+        duration: Duration,
+    ) {
+        self.shards.lock().push(ShardTiming {
+            stage,
+            shard_id,
+            shard_key,
+            duration_ms: duration_ms(duration),
+        });
+    }
+
+    /// Record the request into the slow query log, if it took longer than the threshold.
+    /// The request is only sanitized if it is logged.
+    pub fn finish(
+        self,
+        collection: &str,
+        operation: SlowQueryOperation,
+        request: impl FnOnce() -> serde_json::Value,
+        result_count: usize,
+    ) {
+        let duration = self.started.elapsed();
+        if self.is_forwarded || duration <= UnindexedField::slow_query_threshold() {
+            return;
+        }
+
+        slow_query_log().record(SlowQuery {
+            timestamp: Utc::now(),
+            collection: collection.to_string(),
+            operation,
+            request: request(),
+            duration_ms: duration_ms(duration),
+            stages: self.stages.into_inner(),
+            shards: self.shards.into_inner(),
+            result_count,
+        });
+    }
+}
+
+fn duration_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+pub struct SlowQueryLog {
+    max_entries: usize,
+    queries: Mutex<VecDeque<SlowQuery>>,
+    writer: Option<LogFileWriter>,
+}
+
+impl Default for SlowQueryLog {
+    fn default() -> Self {
+        Self {
+            max_entries: DEFAULT_MAX_ENTRIES,
+            queries: Mutex::new(VecDeque::new()),
+            writer: None,
+        }
+    }
+}
+
+impl SlowQueryLog {
+    pub fn new(max_entries: usize, log_file: Option<&Path>) -> io::Result<Self> {
+        let writer = log_file.map(LogFileWriter::open).transpose()?;
+        Ok(Self {
+            max_entries,
+            queries: Mutex::new(VecDeque::with_capacity(max_entries)),
+            writer,
+        })
+    }
+
+    /// Add the query to the log, evicting the oldest query if the log is full
+    ///
+    /// The query is written to the log file in the background. Errors writing the log file are
+    /// logged, and don't affect the request.
+    pub fn record(&self, query: SlowQuery) {
+        if let Some(writer) = &self.writer {
+            writer.send(query.clone());
+        }
+
+        if self.max_entries == 0 {
+            return;
+        }
+
+        let mut queries = self.queries.lock();
+        while queries.len() >= self.max_entries {
+            queries.pop_front();
+        }
+        queries.push_back(query);
+    }
+
+    /// All logged queries, from oldest to newest
+    pub fn queries(&self) -> Vec<SlowQuery> {
+        self.queries.lock().iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.queries.lock().clear();
+    }
+}
+
+/// Background thread appending queries to the log file
+struct LogFileWriter {
+    sender: Option<SyncSender<SlowQuery>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl LogFileWriter {
+    fn open(path: &Path) -> io::Result<Self> {
+        let mut file = open_log_file(path)?;
+        let (sender, receiver) = mpsc::sync_channel::<SlowQuery>(WRITER_QUEUE_SIZE);
+
+        let handle = thread::Builder::new()
+            .name("slow-query-log".to_string())
+            .spawn(move || {
+                for query in receiver {
+                    if let Err(err) = write_line(&mut file, &query) {
+                        log::error!("Failed to write slow query log: {err}");
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            sender: Some(sender),
+            handle: Some(handle),
+        })
+    }
+
+    /// Queue the query to be written, without blocking
+    fn send(&self, query: SlowQuery) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+
+        match sender.try_send(query) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                log::warn!("Slow query log file writer is falling behind, query is not written");
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                log::error!("Slow query log file writer is stopped, query is not written");
+            }
+        }
+    }
+}
+
+impl Drop for LogFileWriter {
+    /// Write all queued queries before closing the file
+    fn drop(&mut self) {
+        // Closing the channel stops the thread once the queue is drained
+        self.sender.take();
+
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                log::error!("Slow query log file writer panicked");
+            }
+        }
+    }
+}
+
+fn open_log_file(path: &Path) -> io::Result<File> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    File::options().create(true).append(true).open(path)
+}
+
+fn write_line(file: &mut File, query: &SlowQuery) -> io::Result<()> {
+    let mut line = serde_json::to_vec(query)?;
+    line.push(b'\n');
+    file.write_all(&line)
+}
+
+static SLOW_QUERY_LOG: OnceLock<SlowQueryLog> = OnceLock::new();
+
+/// Set up the slow query log, must be called before any query is logged
+pub fn init(log: SlowQueryLog) {
+    if SLOW_QUERY_LOG.set(log).is_err() {
+        log::warn!("Slow query log is already initialized");
+    }
+}
+
+fn slow_query_log() -> &'static SlowQueryLog {
+    SLOW_QUERY_LOG.get_or_init(SlowQueryLog::default)
+}
+
+/// All queries in the slow query log, from oldest to newest
+pub fn all_slow_queries() -> Vec<SlowQuery> {
+    slow_query_log().queries()
+}
+
+/// Clears the slow query log, the log file is kept
+pub fn clear() {
+    slow_query_log().clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slow_query(result_count: usize) -> SlowQuery {
+        SlowQuery {
+            timestamp: Utc::now(),
+            collection: "test".to_string(),
+            operation: SlowQueryOperation::Count,
+            request: serde_json::json!({ "exact": true }),
+            duration_ms: 2000.0,
+            stages: vec![StageTiming {
+                stage: "count",
+                duration_ms: 2000.0,
+            }],
+            shards: vec![ShardTiming {
+                stage: "count",
+                shard_id: 0,
+                shard_key: None,
+                duration_ms: 1999.0,
+            }],
+            result_count,
+        }
+    }
+
+    #[test]
+    fn test_slow_query_log() {
+        let dir = tempfile::Builder::new()
+            .prefix("slow_queries")
+            .tempdir()
+            .unwrap();
+        let log_file = dir.path().join("logs").join("slow_queries.log");
+
+        let log = SlowQueryLog::new(2, Some(&log_file)).unwrap();
+        for result_count in 0..3 {
+            log.record(slow_query(result_count));
+        }
+
+        // Only the latest queries are kept in memory
+        let counts: Vec<_> = log.queries().iter().map(|q| q.result_count).collect();
+        assert_eq!(counts, vec![1, 2]);
+
+        log.clear();
+        assert!(log.queries().is_empty());
+
+        // Dropping the log waits for the background writer to finish
+        drop(log);
+
+        // All queries are written to the file
+        let lines = fs::read_to_string(&log_file).unwrap();
+        assert_eq!(lines.lines().count(), 3);
+
+        let query: serde_json::Value = serde_json::from_str(lines.lines().next().unwrap()).unwrap();
+        assert_eq!(query["collection"], "test");
+        assert_eq!(query["operation"], "count");
+        assert_eq!(query["shards"][0]["shard_id"], 0);
+    }
+}
@@ -10,6 +10,7 @@ use itertools::Itertools;
 use log::{debug, error, info, trace, warn};
 use parking_lot::Mutex;
 use segment::common::operation_error::OperationResult;
+use segment::entry::entry_point::SegmentEntry;
 use segment::index::hnsw_index::num_rayon_threads;
 use segment::types::SeqNumberType;
 use tokio::runtime::Handle;
@@ -29,6 +30,7 @@ use crate::operations::types::{CollectionError, CollectionResult};
 use crate::operations::CollectionUpdateOperations;
 use crate::shards::local_shard::LocalShardClocks;
 use crate::wal::WalError;
+use crate::wal_archive::WalArchiver;
 use crate::wal_delta::LockedWal;
 
 /// Interval at which the optimizer worker cleans up old optimization handles
@@ -36,6 +38,11 @@ use crate::wal_delta::LockedWal;
 /// The longer the duration, the longer it  takes for panicked tasks to be reported.
 const OPTIMIZER_CLEANUP_INTERVAL: Duration = Duration::from_secs(5);
 
+/// Number of consecutive flushes WAL archiving may fail before WAL records are acknowledged anyway
+///
+/// Without a limit a broken archive would stop WAL truncation and grow the WAL without bound.
+const MAX_WAL_ARCHIVE_ATTEMPTS: usize = 10;
+
 pub type Optimizer = dyn SegmentOptimizer + Sync + Send;
 
 /// Information, required to perform operation and notify regarding the result
@@ -111,6 +118,8 @@ pub struct UpdateHandler {
     pub max_optimization_threads: Option<usize>,
     /// Highest and cutoff clocks for the shard WAL.
     clocks: LocalShardClocks,
+    /// Archives WAL records before they are acknowledged, if enabled
+    wal_archiver: Option<Arc<TokioMutex<WalArchiver>>>,
     shard_path: PathBuf,
     /// Whether we have ever triggered optimizers since starting.
     has_triggered_optimizers: Arc<AtomicBool>,
@@ -129,6 +138,7 @@ impl UpdateHandler {
         flush_interval_sec: u64,
         max_optimization_threads: Option<usize>,
         clocks: LocalShardClocks,
+        wal_archiver: Option<Arc<TokioMutex<WalArchiver>>>,
         shard_path: PathBuf,
     ) -> UpdateHandler {
         UpdateHandler {
@@ -148,6 +158,7 @@ impl UpdateHandler {
             optimization_handles: Arc::new(TokioMutex::new(vec![])),
             max_optimization_threads,
             clocks,
+            wal_archiver,
             shard_path,
             has_triggered_optimizers: Default::default(),
         }
@@ -181,6 +192,7 @@ impl UpdateHandler {
             self.flush_interval_sec,
             flush_rx,
             self.clocks.clone(),
+            self.wal_archiver.clone(),
             self.shard_path.clone(),
         )));
         self.flush_stop = Some(flush_tx);
@@ -313,6 +325,15 @@ impl UpdateHandler {
                             let tracker_handle = tracker.handle();
                             optimizers_log.lock().register(tracker);
 
+                            // Points to optimize, counted for the indexing throughput
+                            let points: usize = {
+                                let segments = segments.read();
+                                nsi.iter()
+                                    .filter_map(|id| segments.get(*id))
+                                    .map(|segment| segment.get().read().available_point_count())
+                                    .sum()
+                            };
+
                             // Optimize and handle result
                             match optimizer.as_ref().optimize(
                                 segments.clone(),
@@ -323,6 +344,9 @@ impl UpdateHandler {
                                 // Perform some actions when optimization if finished
                                 Ok(result) => {
                                     tracker_handle.update(TrackerStatus::Done);
+                                    if result {
+                                        optimizers_log.lock().add_optimized_points(points);
+                                    }
                                     callback(result);
                                     result
                                 }
@@ -628,8 +652,11 @@ impl UpdateHandler {
         flush_interval_sec: u64,
         mut stop_receiver: oneshot::Receiver<()>,
         clocks: LocalShardClocks,
+        wal_archiver: Option<Arc<TokioMutex<WalArchiver>>>,
         shard_path: PathBuf,
     ) {
+        let mut failed_archive_attempts = 0;
+
         loop {
             // Stop flush worker on signal or if sender was dropped
             // Even if timer did not finish
@@ -683,6 +710,30 @@ impl UpdateHandler {
                 segments.write().report_optimizer_error(err);
             }
 
+            // Archive records before they may be truncated, don't truncate if archiving fails,
+            // unless it keeps failing
+            if let Some(wal_archiver) = &wal_archiver {
+                match wal_archiver.lock().await.archive(&wal, ack).await {
+                    Ok(()) => failed_archive_attempts = 0,
+                    Err(err) if failed_archive_attempts + 1 < MAX_WAL_ARCHIVE_ATTEMPTS => {
+                        failed_archive_attempts += 1;
+                        log::warn!(
+                            "Failed to archive WAL records \
+                             (attempt {failed_archive_attempts}/{MAX_WAL_ARCHIVE_ATTEMPTS}): {err}",
+                        );
+                        continue;
+                    }
+                    Err(err) => {
+                        failed_archive_attempts = 0;
+                        log::error!(
+                            "Failed to archive WAL records {MAX_WAL_ARCHIVE_ATTEMPTS} times, \
+                             acknowledging them without archiving: {err}",
+                        );
+                        segments.write().report_optimizer_error(err);
+                    }
+                }
+            }
+
             if let Err(err) = wal.lock().ack(ack) {
                 log::warn!("Failed to acknowledge WAL version: {err}");
                 segments.write().report_optimizer_error(err);
@@ -255,6 +255,17 @@ impl<R: DeserializeOwned + Serialize + Debug> SerdeWal<R> {
         self.wal.last_index()
     }
 
+    /// Number of records after the last acknowledged one.
+    ///
+    /// The last acknowledged record itself is kept as `first_index` of the logical WAL, and
+    /// older records may still be held in closed segments, neither of them is counted.
+    pub fn unacknowledged_len(&self) -> u64 {
+        match self.first_index {
+            Some(last_acknowledged) => self.last_index().saturating_sub(last_acknowledged),
+            None => self.wal.num_entries(),
+        }
+    }
+
     pub fn segment_capacity(&self) -> usize {
         self.options.segment_capacity
     }
@@ -323,4 +334,30 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_unacknowledged_len() {
+        let dir = Builder::new().prefix("wal_test").tempdir().unwrap();
+        let wal_options = WalOptions {
+            segment_capacity: 32 * 1024 * 1024,
+            segment_queue_len: 0,
+        };
+
+        let mut serde_wal: SerdeWal<TestRecord> =
+            SerdeWal::new(dir.path().to_str().unwrap(), wal_options).unwrap();
+        assert_eq!(serde_wal.unacknowledged_len(), 0);
+
+        for data in 0..5 {
+            let record = TestRecord::Struct1(TestInternalStruct1 { data });
+            serde_wal.write(&record).expect("Can't write");
+        }
+        assert_eq!(serde_wal.unacknowledged_len(), 5);
+
+        // Acknowledged records are still in the open segment, but not counted
+        serde_wal.ack(2).unwrap();
+        assert_eq!(serde_wal.unacknowledged_len(), 2);
+
+        serde_wal.ack(4).unwrap();
+        assert_eq!(serde_wal.unacknowledged_len(), 0);
+    }
 }
@@ -0,0 +1,551 @@
+//! Archiving of WAL records for point-in-time recovery.
+//!
+//! When enabled, WAL records of a local shard are copied into the snapshot storage before they
+//! are truncated from the WAL. Records are stored in chunks, each holding a consecutive range of
+//! records.
+//!
+//! Chunks are grouped by timeline. A shard starts a new timeline whenever it is restored from a
+//! snapshot, so records written after the restore never mix with the history it was restored
+//! from. The restored shard remembers that history, which allows replaying its archived records
+//! on top of the snapshot up to a [`RecoveryTarget`].
+
+use std::collections::BTreeMap;
+use std::io::{BufReader, BufWriter, Write as _};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use io::file_operations::{atomic_save_json, read_json};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::common::file_utils::move_file;
+use crate::common::snapshots_manager::SnapshotStorageManager;
+use crate::operations::shared_storage_config::SharedStorageConfig;
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::operations::OperationWithClockTag;
+use crate::shards::shard::{PeerId, ShardId};
+use crate::shards::CollectionId;
+use crate::wal::SerdeWal;
+use crate::wal_delta::LockedWal;
+
+pub const WAL_ARCHIVE_STATE_FILE: &str = "wal_archive.json";
+
+const WAL_ARCHIVE_DIR: &str = "wal-archive";
+
+const CHUNK_EXTENSION: &str = "walchunk";
+
+/// Point up to which archived operations are replayed
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryTarget {
+    /// Replay operations received at or before this time
+    Timestamp(DateTime<Utc>),
+    /// Replay operations up to and including the operation with this clock tick
+    ClockTick(ClockTickTarget),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ClockTickTarget {
+    pub peer_id: PeerId,
+    pub clock_id: u32,
+    pub clock_tick: u64,
+}
+
+impl RecoveryTarget {
+    /// Whether the given operation is still replayed, and whether replaying stops after it
+    fn check(&self, operation: &OperationWithClockTag) -> (bool, bool) {
+        match self {
+            RecoveryTarget::Timestamp(timestamp) => {
+                // Operations received before timestamps were recorded are always replayed
+                let is_replayed = operation.timestamp.map_or(true, |operation_timestamp| {
+                    operation_timestamp <= timestamp.timestamp_millis().max(0) as u64
+                });
+                (is_replayed, !is_replayed)
+            }
+
+            RecoveryTarget::ClockTick(target) => {
+                let Some(clock_tag) = operation.clock_tag else {
+                    return (true, false);
+                };
+
+                if clock_tag.peer_id != target.peer_id || clock_tag.clock_id != target.clock_id {
+                    return (true, false);
+                }
+
+                (
+                    clock_tag.clock_tick <= target.clock_tick,
+                    clock_tag.clock_tick >= target.clock_tick,
+                )
+            }
+        }
+    }
+
+    /// Take operations in order, up to this recovery target
+    pub fn take_operations(
+        &self,
+        operations: impl IntoIterator<Item = OperationWithClockTag>,
+    ) -> Vec<OperationWithClockTag> {
+        let mut taken = Vec::new();
+
+        for operation in operations {
+            let (is_replayed, is_last) = self.check(&operation);
+
+            if is_replayed {
+                taken.push(operation);
+            }
+
+            if is_last {
+                break;
+            }
+        }
+
+        taken
+    }
+}
+
+/// Persisted archiving state of a local shard
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct WalArchiveState {
+    /// Timeline records of this shard are archived in
+    timeline: String,
+    /// Index of the next WAL record to archive, set once the shard is loaded
+    next_index: Option<u64>,
+    /// Timeline and WAL position this shard was restored from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    restored_from: Option<RestorePoint>,
+}
+
+/// Timeline and WAL position a shard was restored from
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RestorePoint {
+    pub timeline: String,
+    /// Index of the first WAL record not included in the restored snapshot, set once the shard
+    /// is loaded
+    pub next_index: Option<u64>,
+}
+
+impl WalArchiveState {
+    fn new(restored_from: Option<RestorePoint>) -> Self {
+        let timeline = format!(
+            "{}-{:08x}",
+            Utc::now().timestamp_millis(),
+            rand::random::<u32>(),
+        );
+
+        Self {
+            timeline,
+            next_index: None,
+            restored_from,
+        }
+    }
+
+    fn path(shard_path: &Path) -> PathBuf {
+        shard_path.join(WAL_ARCHIVE_STATE_FILE)
+    }
+
+    pub fn load(shard_path: &Path) -> CollectionResult<Option<Self>> {
+        let path = Self::path(shard_path);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        Ok(Some(read_json(&path)?))
+    }
+
+    fn save(&self, shard_path: &Path) -> CollectionResult<()> {
+        atomic_save_json(&Self::path(shard_path), self)?;
+        Ok(())
+    }
+
+    /// Copy archiving state from one shard path to another, such as into a snapshot
+    pub async fn copy_data(from: &Path, to: &Path) -> CollectionResult<()> {
+        let state_path = Self::path(from);
+
+        if state_path.exists() {
+            tokio::fs::copy(state_path, Self::path(to)).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Move archiving state from one shard path to another
+    pub async fn move_data(from: &Path, to: &Path) -> CollectionResult<()> {
+        let state_path = Self::path(from);
+
+        if state_path.exists() {
+            move_file(state_path, Self::path(to)).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Delete archiving state at the given shard path
+    pub async fn delete_data(shard_path: &Path) -> CollectionResult<()> {
+        let state_path = Self::path(shard_path);
+
+        if state_path.exists() {
+            tokio::fs::remove_file(state_path).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Start a new timeline for a shard just restored from a snapshot
+    ///
+    /// Does nothing if the snapshot has no archiving state.
+    pub fn fork(shard_path: &Path) -> CollectionResult<()> {
+        let Some(state) = Self::load(shard_path)? else {
+            return Ok(());
+        };
+
+        let restored_from = RestorePoint {
+            timeline: state.timeline,
+            next_index: None,
+        };
+
+        Self::new(Some(restored_from)).save(shard_path)
+    }
+}
+
+/// Archived WAL record
+#[derive(Debug, Deserialize, Serialize)]
+struct ArchivedRecord {
+    index: u64,
+    operation: OperationWithClockTag,
+}
+
+/// Archives WAL records of a local shard into the snapshot storage
+pub struct WalArchiver {
+    shard_path: PathBuf,
+    /// Directory holding all timelines of the shard in the snapshot storage
+    archive_path: PathBuf,
+    storage: SnapshotStorageManager,
+    state: WalArchiveState,
+}
+
+impl WalArchiver {
+    /// Create archiver for a local shard, if WAL archiving is enabled
+    pub fn new(
+        shared_storage_config: &SharedStorageConfig,
+        collection_id: &CollectionId,
+        shard_id: ShardId,
+        shard_path: &Path,
+        wal: &SerdeWal<OperationWithClockTag>,
+    ) -> CollectionResult<Option<Self>> {
+        if !shared_storage_config.snapshots_config.wal_archiving {
+            return Ok(None);
+        }
+
+        let storage = SnapshotStorageManager::new(shared_storage_config.snapshots_config.clone())?;
+
+        let archive_path = Path::new(&shared_storage_config.snapshots_path)
+            .join(collection_id)
+            .join(format!("shards/{shard_id}"))
+            .join(WAL_ARCHIVE_DIR);
+
+        let mut state =
+            WalArchiveState::load(shard_path)?.unwrap_or_else(|| WalArchiveState::new(None));
+
+        // Records already in the WAL are part of the data this timeline starts from
+        let next_index = wal.first_index() + wal.len(false);
+        state.next_index.get_or_insert(next_index);
+        if let Some(restored_from) = &mut state.restored_from {
+            restored_from.next_index.get_or_insert(next_index);
+        }
+        state.save(shard_path)?;
+
+        Ok(Some(Self {
+            shard_path: shard_path.to_path_buf(),
+            archive_path,
+            storage,
+            state,
+        }))
+    }
+
+    fn timeline_path(&self, timeline: &str) -> PathBuf {
+        self.archive_path.join(timeline)
+    }
+
+    /// Archive all WAL records up to and including `until_index`
+    pub async fn archive(&mut self, wal: &LockedWal, until_index: u64) -> CollectionResult<()> {
+        let records: Vec<_> = {
+            let wal = wal.lock();
+            let next_index = self.state.next_index.unwrap_or_default();
+            let first_index = wal.first_closed_index();
+
+            // Records were truncated from the WAL before they were archived, they are lost
+            if next_index < first_index {
+                log::warn!(
+                    "WAL records {next_index}..{first_index} were truncated before being archived, \
+                     archive of timeline {} has a gap and can't be replayed past it",
+                    self.state.timeline,
+                );
+            }
+
+            wal.read(next_index.max(first_index))
+                .take_while(|(index, _)| *index <= until_index)
+                .map(|(index, operation)| ArchivedRecord { index, operation })
+                .collect()
+        };
+
+        let (Some(first), Some(last)) = (records.first(), records.last()) else {
+            return Ok(());
+        };
+        let (first, last) = (first.index, last.index);
+
+        let chunk_file = tempfile::Builder::new()
+            .prefix("wal-archive-")
+            .tempfile_in(&self.shard_path)?;
+        let mut writer = BufWriter::new(chunk_file.as_file());
+        serde_cbor::to_writer(&mut writer, &records).map_err(|err| {
+            CollectionError::service_error(format!("Failed to serialize WAL chunk: {err}"))
+        })?;
+        writer.flush()?;
+        drop(writer);
+        let chunk_path = chunk_file.into_temp_path();
+
+        let target_path = self
+            .timeline_path(&self.state.timeline)
+            .join(format!("{first:020}-{last:020}.{CHUNK_EXTENSION}"));
+        self.storage.store_file(&chunk_path, &target_path).await?;
+
+        self.state.next_index = Some(last + 1);
+        self.state.save(&self.shard_path)?;
+
+        log::trace!(
+            "Archived WAL records {first}..={last} into {}",
+            target_path.display(),
+        );
+
+        Ok(())
+    }
+
+    /// Read archived operations following the snapshot this shard was restored from, in order
+    pub async fn read_restored_operations(
+        &self,
+        temp_dir: &Path,
+    ) -> CollectionResult<Vec<OperationWithClockTag>> {
+        let Some(RestorePoint {
+            timeline,
+            next_index: Some(next_index),
+        }) = &self.state.restored_from
+        else {
+            return Err(CollectionError::bad_request(
+                "shard was not restored from a snapshot with WAL archiving enabled".to_string(),
+            ));
+        };
+
+        let timeline_path = self.timeline_path(timeline);
+
+        let mut chunks: Vec<_> = self
+            .storage
+            .list_files(&timeline_path)
+            .await?
+            .into_iter()
+            .filter_map(|name| parse_chunk_name(&name).map(|range| (range, name)))
+            .filter(|((_, last), _)| last >= next_index)
+            .collect();
+        chunks.sort_unstable();
+
+        let download_dir = tempfile::Builder::new()
+            .prefix("wal-archive-")
+            .tempdir_in(temp_dir)?;
+
+        let mut records = BTreeMap::new();
+
+        for (_, name) in chunks {
+            let local_path = download_dir.path().join(&name);
+            self.storage
+                .download_file(&timeline_path.join(&name), &local_path)
+                .await?;
+
+            let chunk_file = std::fs::File::open(&local_path)?;
+            let chunk: Vec<ArchivedRecord> = serde_cbor::from_reader(BufReader::new(chunk_file))
+                .map_err(|err| {
+                    CollectionError::service_error(format!(
+                        "Failed to read WAL archive chunk {name}: {err}",
+                    ))
+                })?;
+            std::fs::remove_file(&local_path)?;
+
+            records.extend(
+                chunk
+                    .into_iter()
+                    .filter(|record| record.index >= *next_index)
+                    .map(|record| (record.index, record.operation)),
+            );
+        }
+
+        // Replaying past a gap would skip operations, which silently corrupts the shard
+        let mut expected_index = *next_index;
+        for &index in records.keys() {
+            if index != expected_index {
+                return Err(CollectionError::service_error(format!(
+                    "WAL archive of timeline {timeline} is missing records {expected_index}..{index}",
+                )));
+            }
+            expected_index += 1;
+        }
+
+        Ok(records.into_values().collect())
+    }
+}
+
+/// Parse range of WAL records from chunk file name
+fn parse_chunk_name(name: &str) -> Option<(u64, u64)> {
+    let range = name.strip_suffix(CHUNK_EXTENSION)?.strip_suffix('.')?;
+    let (first, last) = range.split_once('-')?;
+    Some((first.parse().ok()?, last.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use segment::data_types::vectors::VectorStruct;
+    use tempfile::Builder;
+    use wal::WalOptions;
+
+    use super::*;
+    use crate::common::snapshots_manager::SnapShotsConfig;
+    use crate::operations::point_ops::{
+        PointInsertOperationsInternal, PointOperations, PointStruct,
+    };
+    use crate::operations::{ClockTag, CollectionUpdateOperations};
+
+    fn mock_operation(id: u64, clock_tick: u64) -> OperationWithClockTag {
+        let operation = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+            PointInsertOperationsInternal::PointsList(vec![PointStruct {
+                id: id.into(),
+                vector: VectorStruct::from(vec![1.0, 2.0, 3.0]).into(),
+                payload: None,
+            }]),
+        ));
+        OperationWithClockTag::new(operation, Some(ClockTag::new(1, 0, clock_tick)))
+    }
+
+    #[test]
+    fn test_parse_chunk_name() {
+        assert_eq!(
+            parse_chunk_name("00000000000000000010-00000000000000000019.walchunk"),
+            Some((10, 19)),
+        );
+        assert_eq!(parse_chunk_name("00000000000000000010.walchunk"), None);
+        assert_eq!(parse_chunk_name("shard.snapshot"), None);
+    }
+
+    #[test]
+    fn test_recovery_target_clock_tick() {
+        let operations: Vec<_> = (0..5).map(|tick| mock_operation(tick, tick)).collect();
+
+        let target = RecoveryTarget::ClockTick(ClockTickTarget {
+            peer_id: 1,
+            clock_id: 0,
+            clock_tick: 2,
+        });
+        assert_eq!(target.take_operations(operations.clone()).len(), 3);
+
+        // Operations of other clocks do not stop replaying
+        let target = RecoveryTarget::ClockTick(ClockTickTarget {
+            peer_id: 2,
+            clock_id: 0,
+            clock_tick: 2,
+        });
+        assert_eq!(target.take_operations(operations).len(), 5);
+    }
+
+    #[test]
+    fn test_recovery_target_timestamp() {
+        let mut operations: Vec<_> = (0..5).map(|tick| mock_operation(tick, tick)).collect();
+        for (operation, timestamp) in operations.iter_mut().zip([1000, 2000, 3000, 4000, 5000]) {
+            operation.timestamp = Some(timestamp);
+        }
+
+        let target = RecoveryTarget::Timestamp(DateTime::from_timestamp_millis(3500).unwrap());
+        let taken = target.take_operations(operations);
+        assert_eq!(taken.len(), 3);
+        assert_eq!(taken.last().unwrap().timestamp, Some(3000));
+    }
+
+    #[tokio::test]
+    async fn test_archive_and_read_restored_operations() {
+        let storage_dir = Builder::new().prefix("storage").tempdir().unwrap();
+        let snapshots_dir = Builder::new().prefix("snapshots").tempdir().unwrap();
+        let temp_dir = Builder::new().prefix("temp").tempdir().unwrap();
+        let shard_dir = storage_dir.path().join("0");
+        let restored_dir = storage_dir.path().join("1");
+
+        let shared_storage_config = SharedStorageConfig {
+            snapshots_path: snapshots_dir.path().to_str().unwrap().to_string(),
+            snapshots_config: SnapShotsConfig {
+                wal_archiving: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let open_wal = |path: &Path| -> SerdeWal<OperationWithClockTag> {
+            std::fs::create_dir_all(path.join("wal")).unwrap();
+            let wal_options = WalOptions {
+                segment_capacity: 1024 * 1024,
+                segment_queue_len: 0,
+            };
+            SerdeWal::new(path.join("wal").to_str().unwrap(), wal_options).unwrap()
+        };
+
+        let wal = open_wal(&shard_dir);
+        let mut archiver = WalArchiver::new(
+            &shared_storage_config,
+            &"test".to_string(),
+            0,
+            &shard_dir,
+            &wal,
+        )
+        .unwrap()
+        .unwrap();
+        let wal: LockedWal = std::sync::Arc::new(parking_lot::Mutex::new(wal));
+
+        // Archive operations in two chunks, as if flushed twice
+        for tick in 0..6 {
+            wal.lock().write(&mock_operation(tick, tick)).unwrap();
+            if tick == 2 {
+                archiver.archive(&wal, tick).await.unwrap();
+            }
+        }
+        archiver.archive(&wal, 5).await.unwrap();
+
+        // Restore from a snapshot holding the first two operations
+        let mut restored_wal = open_wal(&restored_dir);
+        for tick in 0..2 {
+            restored_wal.write(&mock_operation(tick, tick)).unwrap();
+        }
+        std::fs::copy(
+            shard_dir.join(WAL_ARCHIVE_STATE_FILE),
+            restored_dir.join(WAL_ARCHIVE_STATE_FILE),
+        )
+        .unwrap();
+        WalArchiveState::fork(&restored_dir).unwrap();
+
+        let restored = WalArchiver::new(
+            &shared_storage_config,
+            &"test".to_string(),
+            0,
+            &restored_dir,
+            &restored_wal,
+        )
+        .unwrap()
+        .unwrap();
+        assert_ne!(restored.state.timeline, archiver.state.timeline);
+
+        let operations = restored
+            .read_restored_operations(temp_dir.path())
+            .await
+            .unwrap();
+
+        let ticks: Vec<_> = operations
+            .iter()
+            .map(|operation| operation.clock_tag.unwrap().clock_tick)
+            .collect();
+        assert_eq!(ticks, vec![2, 3, 4, 5]);
+    }
+}
@@ -2,6 +2,7 @@ use std::collections::{HashMap, HashSet};
 use std::fs::File;
 
 use api::rest::OrderByInterface;
+use collection::collection::resharding::{ReshardKey, ReshardingDirection};
 use collection::operations::payload_ops::{PayloadOps, SetPayloadOp};
 use collection::operations::point_ops::{Batch, PointOperations, PointStruct, WriteOrdering};
 use collection::operations::shard_selector_internal::ShardSelectorInternal;
@@ -184,6 +185,84 @@ async fn test_collection_search_with_payload_and_vector_with_shards(shard_number
     assert_eq!(count_res.count, 1);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_count_during_resharding() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+
+    let collection = simple_collection_fixture(collection_dir.path(), 1).await;
+
+    let insert_points = || {
+        CollectionUpdateOperations::PointOperation(
+            Batch {
+                ids: vec![0, 1, 2, 3, 4]
+                    .into_iter()
+                    .map(|x| x.into())
+                    .collect_vec(),
+                vectors: BatchVectorStruct::from(vec![
+                    vec![1.0, 0.0, 1.0, 1.0],
+                    vec![1.0, 0.0, 1.0, 0.0],
+                    vec![1.0, 1.0, 1.0, 1.0],
+                    vec![1.0, 1.0, 0.0, 1.0],
+                    vec![1.0, 0.0, 0.0, 0.0],
+                ])
+                .into(),
+                payloads: None,
+            }
+            .into(),
+        )
+    };
+
+    collection
+        .update_from_client_simple(insert_points(), true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let key = ReshardKey {
+        direction: ReshardingDirection::Up,
+        peer_id: 0,
+        shard_id: 1,
+        shard_key: None,
+    };
+    collection
+        .start_resharding(key.clone(), None)
+        .await
+        .unwrap();
+
+    // Points are migrated into the new shard, but not deleted from the old one yet
+    collection
+        .update_from_peer(insert_points().into(), 1, true, WriteOrdering::Weak)
+        .await
+        .unwrap();
+    collection.commit_read_hashring(key).await.unwrap();
+
+    for exact in [true, false] {
+        let count_request = CountRequestInternal {
+            filter: None,
+            exact,
+        };
+
+        let count_res = collection
+            .count(count_request, None, &ShardSelectorInternal::All)
+            .await
+            .unwrap();
+        assert_eq!(count_res.count, 5);
+    }
+
+    // Internal requests count the selected shard only
+    let count_res = collection
+        .count(
+            CountRequestInternal {
+                filter: None,
+                exact: true,
+            },
+            None,
+            &ShardSelectorInternal::ShardId(1),
+        )
+        .await
+        .unwrap();
+    assert_eq!(count_res.count, 5);
+}
+
 // FIXME: does not work
 #[tokio::test(flavor = "multi_thread")]
 async fn test_collection_loading() {
@@ -52,6 +52,7 @@ fn multi_vector_search_benchmark(c: &mut Criterion) {
         )]),
         sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        storage_format: Default::default(),
     };
 
     let mut segment = build_segment(segment_dir.path(), &segment_config, true).unwrap();
@@ -82,6 +82,26 @@ pub fn check_db_exists(path: &Path) -> bool {
     db_file.exists()
 }
 
+/// Check if a file belongs to a RocksDB database, by its name in the database directory
+pub fn is_db_file(file_name: &str) -> bool {
+    const DB_FILE_NAMES: [&str; 4] = ["CURRENT", "IDENTITY", "LOCK", "LOG"];
+    const DB_FILE_PREFIXES: [&str; 3] = ["LOG.old.", "MANIFEST-", "OPTIONS-"];
+    // Tables, WAL and temporary files are named by a file number
+    const DB_NUMBERED_FILE_EXTENSIONS: [&str; 4] = [".sst", ".log", ".blob", ".dbtmp"];
+
+    let is_numbered_file = DB_NUMBERED_FILE_EXTENSIONS.iter().any(|extension| {
+        file_name.strip_suffix(extension).is_some_and(|number| {
+            !number.is_empty() && number.bytes().all(|byte| byte.is_ascii_digit())
+        })
+    });
+
+    is_numbered_file
+        || DB_FILE_NAMES.contains(&file_name)
+        || DB_FILE_PREFIXES
+            .iter()
+            .any(|prefix| file_name.starts_with(prefix))
+}
+
 pub fn open_db_with_existing_cf(path: &Path) -> Result<Arc<RwLock<DB>>, rocksdb::Error> {
     let existing_column_families = if check_db_exists(path) {
         DB::list_cf(&db_options(), path)?
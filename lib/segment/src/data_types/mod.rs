@@ -4,6 +4,7 @@ pub mod named_vectors;
 pub mod order_by;
 pub mod primitive;
 pub mod query_context;
+pub mod query_explain;
 pub mod text_index;
 pub mod tiny_map;
 pub mod vectors;
@@ -13,6 +13,7 @@ use crate::common::operation_error::OperationError;
 use crate::spaces::metric::Metric;
 use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric, ManhattanMetric};
 use crate::types::{Distance, VectorDataConfig, VectorStorageDatatype};
+use crate::vector_storage::multi_dense::multi_vector_pooling::pool_multi_dense_vector;
 
 type CowKey<'a> = Cow<'a, str>;
 
@@ -294,11 +295,17 @@ impl<'a> NamedVectors<'a> {
                     std::mem::swap(&mut tmp_multi_vector, multi_vector);
                     let mut owned_multi_vector = tmp_multi_vector.to_owned();
                     let config = get_vector_data(name.as_ref());
-                    for dense_vector in owned_multi_vector.multi_vectors_mut() {
-                        let preprocessed_vector =
-                            Self::preprocess_dense_vector(dense_vector.to_vec(), config);
-                        // replace dense vector with preprocessed vector
-                        dense_vector.copy_from_slice(&preprocessed_vector);
+                    Self::preprocess_multi_vector(&mut owned_multi_vector, config);
+                    let pooling = config
+                        .multivec_config
+                        .and_then(|multivec_config| multivec_config.pooling);
+                    if let Some(pooling) = pooling {
+                        if owned_multi_vector.len() > pooling.max_vectors {
+                            owned_multi_vector =
+                                pool_multi_dense_vector(&owned_multi_vector, pooling.max_vectors);
+                            // pooled vectors are means, which need to be preprocessed again
+                            Self::preprocess_multi_vector(&mut owned_multi_vector, config);
+                        }
                     }
                     *multi_vector = CowMultiVector::Owned(owned_multi_vector);
                 }
@@ -306,6 +313,14 @@ impl<'a> NamedVectors<'a> {
         }
     }
 
+    fn preprocess_multi_vector(multi_vector: &mut MultiDenseVector, config: &VectorDataConfig) {
+        for dense_vector in multi_vector.multi_vectors_mut() {
+            let preprocessed_vector = Self::preprocess_dense_vector(dense_vector.to_vec(), config);
+            // replace dense vector with preprocessed vector
+            dense_vector.copy_from_slice(&preprocessed_vector);
+        }
+    }
+
     fn preprocess_dense_vector(
         dense_vector: DenseVector,
         config: &VectorDataConfig,
@@ -1,11 +1,14 @@
 use std::collections::HashMap;
 use std::ops::Deref;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use bitvec::prelude::BitSlice;
 use sparse::common::types::{DimId, DimWeight};
 
+use crate::data_types::query_explain::{
+    SearchExplainCollector, SegmentsExplainCollector, VectorSearchExplain,
+};
 use crate::data_types::tiny_map;
 
 #[derive(Debug)]
@@ -26,6 +29,9 @@ pub struct QueryContext {
     /// Required for processing sparse vector search with `idf-dot` similarity.
     #[allow(dead_code)]
     idf: tiny_map::TinyMap<String, HashMap<DimId, usize>>,
+
+    /// Collects how the query was executed in each segment, if the query is explained
+    explain: Option<Arc<SegmentsExplainCollector>>,
 }
 
 impl QueryContext {
@@ -35,11 +41,12 @@ impl QueryContext {
             search_optimized_threshold_kb,
             is_stopped: Arc::new(AtomicBool::new(false)),
             idf: tiny_map::TinyMap::new(),
+            explain: None,
         }
     }
 
     pub fn is_stopped(&self) -> bool {
-        self.is_stopped.load(std::sync::atomic::Ordering::Relaxed)
+        self.is_stopped.load(Ordering::Relaxed)
     }
 
     pub fn with_is_stopped(mut self, flag: Arc<AtomicBool>) -> Self {
@@ -47,6 +54,15 @@ impl QueryContext {
         self
     }
 
+    pub fn with_explain(mut self, explain: Arc<SegmentsExplainCollector>) -> Self {
+        self.explain = Some(explain);
+        self
+    }
+
+    pub fn explain(&self) -> Option<&SegmentsExplainCollector> {
+        self.explain.as_deref()
+    }
+
     pub fn available_point_count(&self) -> usize {
         self.available_point_count
     }
@@ -83,6 +99,7 @@ impl QueryContext {
         SegmentQueryContext {
             query_context: Some(self),
             deleted_points: None,
+            explain: None,
         }
     }
 }
@@ -98,10 +115,17 @@ impl Default for QueryContext {
 pub struct SegmentQueryContext<'a> {
     query_context: Option<&'a QueryContext>,
     deleted_points: Option<&'a BitSlice>,
+    explain: Option<&'a SearchExplainCollector>,
 }
 
 impl<'a> SegmentQueryContext<'a> {
     pub fn get_vector_context(&self, vector_name: &str) -> VectorQueryContext {
+        let explain_vector_name = if self.explain.is_some() {
+            vector_name.to_string()
+        } else {
+            String::new()
+        };
+
         if let Some(query_context) = self.query_context {
             VectorQueryContext {
                 available_point_count: query_context.available_point_count,
@@ -109,10 +133,15 @@ impl<'a> SegmentQueryContext<'a> {
                 is_stopped: Some(&query_context.is_stopped),
                 idf: query_context.idf.get(vector_name),
                 deleted_points: self.deleted_points,
+                explain: self.explain,
+                explain_vector_name,
+                scored_vectors: AtomicUsize::new(0),
             }
         } else {
             VectorQueryContext {
                 deleted_points: self.deleted_points,
+                explain: self.explain,
+                explain_vector_name,
                 ..Default::default()
             }
         }
@@ -122,6 +151,12 @@ impl<'a> SegmentQueryContext<'a> {
         self.deleted_points = Some(deleted_points);
         self
     }
+
+    /// Record how vector searches are executed into the given collector
+    pub fn with_explain(mut self, explain: &'a SearchExplainCollector) -> Self {
+        self.explain = Some(explain);
+        self
+    }
 }
 
 /// Query context related to a specific vector
@@ -139,6 +174,14 @@ pub struct VectorQueryContext<'a> {
     idf: Option<&'a HashMap<DimId, usize>>,
 
     deleted_points: Option<&'a BitSlice>,
+
+    /// Collects how searches were executed, if the query is explained
+    explain: Option<&'a SearchExplainCollector>,
+
+    explain_vector_name: String,
+
+    /// Number of vectors scored by the search, only counted if the query is explained
+    scored_vectors: AtomicUsize,
 }
 
 pub enum SimpleCow<'a, T> {
@@ -201,6 +244,35 @@ impl VectorQueryContext<'_> {
     pub fn is_require_idf(&self) -> bool {
         self.idf.is_some()
     }
+
+    /// Whether the query is explained, so searches should be recorded with `explain_search`
+    pub fn is_explained(&self) -> bool {
+        self.explain.is_some()
+    }
+
+    /// Counter of scored vectors, if the query is explained
+    pub fn scored_vectors_counter(&self) -> Option<&AtomicUsize> {
+        self.explain.map(|_| &self.scored_vectors)
+    }
+
+    pub fn add_scored_vectors(&self, count: usize) {
+        if let Some(counter) = self.scored_vectors_counter() {
+            counter.fetch_add(count, Ordering::Relaxed);
+        }
+    }
+
+    /// Take the number of vectors scored since the last call
+    pub fn take_scored_vectors(&self) -> usize {
+        self.scored_vectors.swap(0, Ordering::Relaxed)
+    }
+
+    /// Record how a search was executed, if the query is explained
+    pub fn explain_search(&self, mut explain: VectorSearchExplain) {
+        if let Some(collector) = self.explain {
+            explain.vector_name.clone_from(&self.explain_vector_name);
+            collector.lock().push(explain);
+        }
+    }
 }
 
 impl Default for VectorQueryContext<'_> {
@@ -211,6 +283,9 @@ impl Default for VectorQueryContext<'_> {
             is_stopped: None,
             idf: None,
             deleted_points: None,
+            explain: None,
+            explain_vector_name: String::new(),
+            scored_vectors: AtomicUsize::new(0),
         }
     }
 }
@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::entry::entry_point::SegmentEntry;
+use crate::index::field_index::CardinalityEstimation;
+use crate::json_path::{JsonPath, JsonPathInterface as _};
+use crate::types::{Condition, Filter, PayloadFieldSchema, PayloadKeyType, SegmentType};
+
+/// Collects how vector searches were executed in a segment, if the query is explained
+pub type SearchExplainCollector = Mutex<Vec<VectorSearchExplain>>;
+
+/// Collects how a request was executed in each segment of a shard, if the request is explained
+pub type SegmentsExplainCollector = Mutex<Vec<SegmentExplain>>;
+
+/// Strategy, which was chosen to search vectors in a segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchStrategy {
+    /// Exact search was requested, all vectors matching the filter are scored
+    Exact,
+    /// All vectors are scored, as there is no HNSW graph or there are too few vectors for it
+    FullScan,
+    /// HNSW graph traversal
+    Hnsw,
+    /// Points are selected with the payload index and scored one by one,
+    /// as the filter is too strict for HNSW
+    PayloadIndex,
+    /// Search over the inverted index of sparse vectors
+    Sparse,
+}
+
+/// Estimation of the number of points matching a filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct CardinalityExplain {
+    pub min: usize,
+    pub exp: usize,
+    pub max: usize,
+}
+
+impl From<&CardinalityEstimation> for CardinalityExplain {
+    fn from(estimation: &CardinalityEstimation) -> Self {
+        Self {
+            min: estimation.min,
+            exp: estimation.exp,
+            max: estimation.max,
+        }
+    }
+}
+
+/// How a batch of query vectors was searched in a vector index
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct VectorSearchExplain {
+    pub vector_name: String,
+    pub strategy: SearchStrategy,
+    /// Estimated number of vectors matching the filter, which the strategy was chosen by
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cardinality: Option<CardinalityExplain>,
+    /// Whether the estimation was inconclusive, and the strategy was chosen by checking a
+    /// sample of points against the filter
+    pub sampled_cardinality: bool,
+    /// Number of query vectors in the batch
+    pub query_vectors: usize,
+    /// Number of stored vectors, which were scored against the query vectors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vectors_scored: Option<usize>,
+    pub quantization: bool,
+    pub rescoring: bool,
+    pub duration_micros: u64,
+}
+
+impl VectorSearchExplain {
+    pub fn new(strategy: SearchStrategy, query_vectors: usize) -> Self {
+        Self {
+            vector_name: String::new(),
+            strategy,
+            cardinality: None,
+            sampled_cardinality: false,
+            query_vectors,
+            vectors_scored: None,
+            quantization: false,
+            rescoring: false,
+            duration_micros: 0,
+        }
+    }
+}
+
+/// Filter conditions split by whether a payload index of the segment can resolve them
+#[derive(Debug, Clone, Default, PartialEq, Serialize, JsonSchema)]
+pub struct FilterExplain {
+    /// Conditions resolved with a payload index or the ID tracker
+    pub indexed_conditions: Vec<Condition>,
+    /// Conditions checked against the raw payload of each candidate point
+    pub unindexed_conditions: Vec<Condition>,
+}
+
+impl FilterExplain {
+    pub fn new(
+        filter: &Filter,
+        indexed_fields: &HashMap<PayloadKeyType, PayloadFieldSchema>,
+    ) -> Self {
+        let mut explain = Self::default();
+        explain.add_filter(filter, indexed_fields);
+        explain
+    }
+
+    fn add_filter(
+        &mut self,
+        filter: &Filter,
+        indexed_fields: &HashMap<PayloadKeyType, PayloadFieldSchema>,
+    ) {
+        for condition in conditions(filter) {
+            match condition {
+                Condition::Filter(filter) => self.add_filter(filter, indexed_fields),
+                condition => {
+                    if is_indexed(condition, None, indexed_fields) {
+                        self.indexed_conditions.push(condition.clone());
+                    } else {
+                        self.unindexed_conditions.push(condition.clone());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Whether the condition can be resolved without reading the payload.
+/// Nested conditions are indexed, only if all of their inner conditions are.
+fn is_indexed(
+    condition: &Condition,
+    nested_path: Option<&JsonPath>,
+    indexed_fields: &HashMap<PayloadKeyType, PayloadFieldSchema>,
+) -> bool {
+    let is_key_indexed =
+        |key: &JsonPath| indexed_fields.contains_key(&JsonPath::extend_or_new(nested_path, key));
+
+    match condition {
+        Condition::Field(field) => is_key_indexed(&field.key),
+        Condition::IsEmpty(is_empty) => is_key_indexed(&is_empty.is_empty.key),
+        Condition::IsNull(is_null) => is_key_indexed(&is_null.is_null.key),
+        Condition::HasId(_) => true,
+        Condition::Nested(nested) => {
+            let full_path = JsonPath::extend_or_new(nested_path, &nested.array_key());
+            conditions(nested.filter())
+                .all(|condition| is_indexed(condition, Some(&full_path), indexed_fields))
+        }
+        Condition::Filter(filter) => {
+            conditions(filter).all(|condition| is_indexed(condition, nested_path, indexed_fields))
+        }
+    }
+}
+
+/// All conditions of the filter, regardless of the clause
+fn conditions(filter: &Filter) -> impl Iterator<Item = &Condition> {
+    let Filter {
+        should,
+        min_should,
+        must,
+        must_not,
+    } = filter;
+
+    should
+        .iter()
+        .flatten()
+        .chain(
+            min_should
+                .iter()
+                .flat_map(|min_should| &min_should.conditions),
+        )
+        .chain(must.iter().flatten())
+        .chain(must_not.iter().flatten())
+}
+
+/// Breakdown of how a request was executed in a single segment
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct SegmentExplain {
+    /// Name of the segment directory
+    pub segment: String,
+    pub segment_type: SegmentType,
+    /// Number of available points in the segment
+    pub points: usize,
+    /// Estimated number of points in the segment matching the filter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cardinality: Option<CardinalityExplain>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<FilterExplain>,
+    /// Vector searches in the segment, empty for scroll and count requests
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub searches: Vec<VectorSearchExplain>,
+    pub duration_micros: u64,
+}
+
+impl SegmentExplain {
+    pub fn new(segment: &dyn SegmentEntry, filter: Option<&Filter>) -> Self {
+        let segment_name = segment
+            .data_path()
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Self {
+            segment: segment_name,
+            segment_type: segment.segment_type(),
+            points: segment.available_point_count(),
+            cardinality: filter.map(|filter| {
+                CardinalityExplain::from(&segment.estimate_point_count(Some(filter)))
+            }),
+            filter: filter.map(|filter| FilterExplain::new(filter, &segment.get_indexed_fields())),
+            searches: Vec::new(),
+            duration_micros: 0,
+        }
+    }
+
+    /// Run `operation` on the segment, and record it into `collector`, if the request is explained
+    pub fn measure<T>(
+        collector: Option<&SegmentsExplainCollector>,
+        segment: &dyn SegmentEntry,
+        filter: Option<&Filter>,
+        operation: impl FnOnce() -> T,
+    ) -> T {
+        let Some(collector) = collector else {
+            return operation();
+        };
+
+        let started = Instant::now();
+        let result = operation();
+        let duration = started.elapsed();
+
+        let mut explain = Self::new(segment, filter);
+        explain.duration_micros = duration.as_micros() as u64;
+        collector.lock().push(explain);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::json_path::path;
+    use crate::types::{
+        FieldCondition, HasIdCondition, IsNullCondition, Match, PayloadField, PayloadSchemaType,
+        ValueVariants,
+    };
+
+    fn match_keyword(key: &str, value: &str) -> Condition {
+        Condition::Field(FieldCondition::new_match(
+            path(key),
+            Match::new_value(ValueVariants::Keyword(value.to_string())),
+        ))
+    }
+
+    #[test]
+    fn test_filter_explain() {
+        let indexed_fields = HashMap::from([
+            (
+                path("city"),
+                PayloadFieldSchema::FieldType(PayloadSchemaType::Keyword),
+            ),
+            (
+                path("items[].id"),
+                PayloadFieldSchema::FieldType(PayloadSchemaType::Keyword),
+            ),
+        ]);
+
+        let city = match_keyword("city", "Berlin");
+        let color = match_keyword("color", "red");
+        let has_id = Condition::HasId(HasIdCondition::from(HashSet::from([1.into(), 2.into()])));
+        let is_null = Condition::IsNull(IsNullCondition {
+            is_null: PayloadField { key: path("city") },
+        });
+        let indexed_nested =
+            Condition::new_nested(path("items"), Filter::new_must(match_keyword("id", "a")));
+        let unindexed_nested =
+            Condition::new_nested(path("items"), Filter::new_must(match_keyword("name", "b")));
+
+        let filter = Filter {
+            should: Some(vec![indexed_nested.clone(), unindexed_nested.clone()]),
+            min_should: None,
+            must: Some(vec![
+                city.clone(),
+                Condition::Filter(Filter::new_must_not(color.clone())),
+            ]),
+            must_not: Some(vec![has_id.clone(), is_null.clone()]),
+        };
+
+        let explain = FilterExplain::new(&filter, &indexed_fields);
+
+        assert_eq!(
+            explain.indexed_conditions,
+            vec![indexed_nested, city, has_id, is_null],
+        );
+        assert_eq!(explain.unindexed_conditions, vec![unindexed_nested, color]);
+    }
+}
@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use atomic_refcell::AtomicRefCell;
@@ -159,6 +159,10 @@ impl IdTracker for FixtureIdTracker {
     fn cleanup_versions(&mut self) -> OperationResult<()> {
         Ok(())
     }
+
+    fn files(&self) -> Vec<PathBuf> {
+        vec![]
+    }
 }
 
 /// Creates in-memory payload storage and fills it with random points
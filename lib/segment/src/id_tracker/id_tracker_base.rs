@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use bitvec::prelude::BitSlice;
 use common::types::PointOffsetType;
 use rand::rngs::StdRng;
@@ -141,6 +143,9 @@ pub trait IdTracker {
     /// It might happen that point doesn't have version due to un-flushed WAL.
     /// This method makes those points usable again.
     fn cleanup_versions(&mut self) -> OperationResult<()>;
+
+    /// Files which are used by this tracker, excluding RocksDB data
+    fn files(&self) -> Vec<PathBuf>;
 }
 
 pub type IdTrackerSS = dyn IdTracker + Sync + Send;
@@ -0,0 +1,533 @@
+use std::fs::create_dir_all;
+use std::mem::size_of_val;
+use std::path::{Path, PathBuf};
+
+use bitvec::prelude::BitSlice;
+use common::types::PointOffsetType;
+use memory::mmap_ops::{create_and_ensure_length, open_write_mmap};
+use uuid::Uuid;
+
+use crate::common::mmap_type::MmapSlice;
+use crate::common::operation_error::{OperationError, OperationResult};
+use crate::common::Flusher;
+use crate::id_tracker::IdTracker;
+use crate::types::{PointIdType, SeqNumberType};
+use crate::vector_storage::dense::dynamic_mmap_flags::DynamicMmapFlags;
+
+const INTERNAL_TO_EXTERNAL_FILE: &str = "internal_to_external.dat";
+const EXTERNAL_TO_INTERNAL_NUM_FILE: &str = "external_to_internal_num.dat";
+const EXTERNAL_TO_INTERNAL_UUID_FILE: &str = "external_to_internal_uuid.dat";
+const VERSIONS_FILE: &str = "versions.dat";
+const DELETED_DIR_PATH: &str = "deleted";
+
+const EXTERNAL_ID_NUM: u64 = 1;
+const EXTERNAL_ID_UUID: u64 = 2;
+
+/// Fixed size representation of an external point id, stored per internal id.
+///
+/// Zero `kind` means that there is no point with this internal id.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct StoredExternalId {
+    kind: u64,
+    id: [u8; 16],
+}
+
+impl From<PointIdType> for StoredExternalId {
+    fn from(point_id: PointIdType) -> Self {
+        match point_id {
+            PointIdType::NumId(idx) => {
+                let mut id = [0; 16];
+                id[..8].copy_from_slice(&idx.to_le_bytes());
+                StoredExternalId {
+                    kind: EXTERNAL_ID_NUM,
+                    id,
+                }
+            }
+            PointIdType::Uuid(uuid) => StoredExternalId {
+                kind: EXTERNAL_ID_UUID,
+                id: *uuid.as_bytes(),
+            },
+        }
+    }
+}
+
+impl StoredExternalId {
+    fn to_point_id(self) -> Option<PointIdType> {
+        match self.kind {
+            EXTERNAL_ID_NUM => {
+                let mut idx = [0; 8];
+                idx.copy_from_slice(&self.id[..8]);
+                Some(PointIdType::NumId(u64::from_le_bytes(idx)))
+            }
+            EXTERNAL_ID_UUID => Some(PointIdType::Uuid(Uuid::from_bytes(self.id))),
+            _ => None,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct NumIdMapping {
+    external_id: u64,
+    internal_id: PointOffsetType,
+    _padding: u32,
+}
+
+/// UUID is stored as big-endian bytes, so that byte-wise order matches the order of [`Uuid`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct UuidMapping {
+    external_id: [u8; 16],
+    internal_id: PointOffsetType,
+}
+
+/// Id tracker for segments which never receive new points.
+///
+/// All mappings are built once from another id tracker and stored in memory mapped files:
+///
+/// - sorted external id -> internal id mappings, separately for numeric ids and UUIDs
+/// - internal id -> external id array
+/// - internal id -> version array
+///
+/// Points can still be deleted, and their versions updated, in place. Nothing needs to be loaded
+/// into RAM on open, so startup time and memory don't depend on the number of points.
+pub struct ImmutableIdTracker {
+    internal_to_external: MmapSlice<StoredExternalId>,
+    external_to_internal_num: MmapSlice<NumIdMapping>,
+    external_to_internal_uuid: MmapSlice<UuidMapping>,
+    internal_to_version: MmapSlice<SeqNumberType>,
+    deleted: DynamicMmapFlags,
+    deleted_count: usize,
+    directory: PathBuf,
+}
+
+fn create_mmap_slice<T: Copy + 'static>(path: &Path, data: &[T]) -> OperationResult<()> {
+    create_and_ensure_length(path, size_of_val(data))?;
+    let mmap = open_write_mmap(path)?;
+    let mut slice = unsafe { MmapSlice::<T>::try_from(mmap)? };
+    slice.copy_from_slice(data);
+    slice.flusher()()?;
+    Ok(())
+}
+
+fn open_mmap_slice<T: 'static>(path: &Path) -> OperationResult<MmapSlice<T>> {
+    let mmap = open_write_mmap(path)?;
+    let slice = unsafe { MmapSlice::try_from(mmap)? };
+    Ok(slice)
+}
+
+impl ImmutableIdTracker {
+    /// Persist mappings and versions of all available points of `source` into `directory`.
+    ///
+    /// Points without a version are considered deleted, same as `cleanup_versions` would do.
+    pub fn create<T: IdTracker + ?Sized>(directory: &Path, source: &T) -> OperationResult<Self> {
+        create_dir_all(directory)?;
+
+        let total_point_count = source.total_point_count();
+        let mut internal_to_external = vec![StoredExternalId::default(); total_point_count];
+        let mut internal_to_version = vec![0; total_point_count];
+        let mut external_to_internal_num = Vec::new();
+        let mut external_to_internal_uuid = Vec::new();
+
+        let mut deleted = DynamicMmapFlags::open(&directory.join(DELETED_DIR_PATH))?;
+        deleted.set_len(total_point_count)?;
+
+        for internal_id in 0..total_point_count as PointOffsetType {
+            let external_id = source.external_id(internal_id);
+            let version = source.internal_version(internal_id);
+            let (Some(external_id), Some(version)) = (external_id, version) else {
+                deleted.set(internal_id, true);
+                continue;
+            };
+
+            internal_to_external[internal_id as usize] = external_id.into();
+            internal_to_version[internal_id as usize] = version;
+            match external_id {
+                PointIdType::NumId(idx) => external_to_internal_num.push(NumIdMapping {
+                    external_id: idx,
+                    internal_id,
+                    _padding: 0,
+                }),
+                PointIdType::Uuid(uuid) => external_to_internal_uuid.push(UuidMapping {
+                    external_id: *uuid.as_bytes(),
+                    internal_id,
+                }),
+            }
+        }
+
+        external_to_internal_num.sort_unstable_by_key(|mapping| mapping.external_id);
+        external_to_internal_uuid.sort_unstable_by_key(|mapping| mapping.external_id);
+
+        create_mmap_slice(
+            &directory.join(INTERNAL_TO_EXTERNAL_FILE),
+            &internal_to_external,
+        )?;
+        create_mmap_slice(
+            &directory.join(EXTERNAL_TO_INTERNAL_NUM_FILE),
+            &external_to_internal_num,
+        )?;
+        create_mmap_slice(
+            &directory.join(EXTERNAL_TO_INTERNAL_UUID_FILE),
+            &external_to_internal_uuid,
+        )?;
+        create_mmap_slice(&directory.join(VERSIONS_FILE), &internal_to_version)?;
+        deleted.flusher()()?;
+        drop(deleted);
+
+        Self::open(directory)
+    }
+
+    pub fn open(directory: &Path) -> OperationResult<Self> {
+        let internal_to_external: MmapSlice<StoredExternalId> =
+            open_mmap_slice(&directory.join(INTERNAL_TO_EXTERNAL_FILE))?;
+        let external_to_internal_num =
+            open_mmap_slice(&directory.join(EXTERNAL_TO_INTERNAL_NUM_FILE))?;
+        let external_to_internal_uuid =
+            open_mmap_slice(&directory.join(EXTERNAL_TO_INTERNAL_UUID_FILE))?;
+        let internal_to_version: MmapSlice<SeqNumberType> =
+            open_mmap_slice(&directory.join(VERSIONS_FILE))?;
+        let deleted = DynamicMmapFlags::open(&directory.join(DELETED_DIR_PATH))?;
+
+        if internal_to_version.len() != internal_to_external.len()
+            || deleted.len() != internal_to_external.len()
+        {
+            return Err(OperationError::service_error(format!(
+                "Inconsistent immutable id tracker in {}: {} points, {} versions, {} deleted flags",
+                directory.display(),
+                internal_to_external.len(),
+                internal_to_version.len(),
+                deleted.len(),
+            )));
+        }
+
+        let deleted_count = deleted.count_flags();
+
+        Ok(ImmutableIdTracker {
+            internal_to_external,
+            external_to_internal_num,
+            external_to_internal_uuid,
+            internal_to_version,
+            deleted,
+            deleted_count,
+            directory: directory.to_owned(),
+        })
+    }
+
+    fn iter_num_from(
+        &self,
+        start: usize,
+    ) -> impl Iterator<Item = (PointIdType, PointOffsetType)> + '_ {
+        self.external_to_internal_num[start..]
+            .iter()
+            .filter(|mapping| !self.deleted.get(mapping.internal_id))
+            .map(|mapping| (PointIdType::NumId(mapping.external_id), mapping.internal_id))
+    }
+
+    fn iter_uuid_from(
+        &self,
+        start: usize,
+    ) -> impl Iterator<Item = (PointIdType, PointOffsetType)> + '_ {
+        self.external_to_internal_uuid[start..]
+            .iter()
+            .filter(|mapping| !self.deleted.get(mapping.internal_id))
+            .map(|mapping| {
+                (
+                    PointIdType::Uuid(Uuid::from_bytes(mapping.external_id)),
+                    mapping.internal_id,
+                )
+            })
+    }
+}
+
+impl IdTracker for ImmutableIdTracker {
+    fn internal_version(&self, internal_id: PointOffsetType) -> Option<SeqNumberType> {
+        // Deleted points have no version
+        self.external_id(internal_id)?;
+        self.internal_to_version.get(internal_id as usize).copied()
+    }
+
+    fn set_internal_version(
+        &mut self,
+        internal_id: PointOffsetType,
+        version: SeqNumberType,
+    ) -> OperationResult<()> {
+        if self.external_id(internal_id).is_some() {
+            self.internal_to_version[internal_id as usize] = version;
+        }
+        Ok(())
+    }
+
+    fn internal_id(&self, external_id: PointIdType) -> Option<PointOffsetType> {
+        let internal_id = match external_id {
+            PointIdType::NumId(idx) => self
+                .external_to_internal_num
+                .binary_search_by_key(&idx, |mapping| mapping.external_id)
+                .ok()
+                .map(|pos| self.external_to_internal_num[pos].internal_id),
+            PointIdType::Uuid(uuid) => self
+                .external_to_internal_uuid
+                .binary_search_by_key(uuid.as_bytes(), |mapping| mapping.external_id)
+                .ok()
+                .map(|pos| self.external_to_internal_uuid[pos].internal_id),
+        }?;
+        (!self.is_deleted_point(internal_id)).then_some(internal_id)
+    }
+
+    fn external_id(&self, internal_id: PointOffsetType) -> Option<PointIdType> {
+        if self.is_deleted_point(internal_id) {
+            return None;
+        }
+        self.internal_to_external
+            .get(internal_id as usize)
+            .and_then(|stored| stored.to_point_id())
+    }
+
+    fn set_link(
+        &mut self,
+        external_id: PointIdType,
+        internal_id: PointOffsetType,
+    ) -> OperationResult<()> {
+        Err(OperationError::service_error(format!(
+            "Can't link point {external_id} to internal id {internal_id}, id tracker is immutable",
+        )))
+    }
+
+    fn drop(&mut self, external_id: PointIdType) -> OperationResult<()> {
+        if let Some(internal_id) = self.internal_id(external_id) {
+            let was_deleted = self.deleted.set(internal_id, true);
+            if !was_deleted {
+                self.deleted_count += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn iter_external(&self) -> Box<dyn Iterator<Item = PointIdType> + '_> {
+        // order is important here, we want to iterate over the u64 ids first
+        Box::new(
+            self.iter_num_from(0)
+                .chain(self.iter_uuid_from(0))
+                .map(|(external_id, _)| external_id),
+        )
+    }
+
+    fn iter_internal(&self) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
+        Box::new(
+            (0..self.internal_to_external.len() as PointOffsetType)
+                .filter(move |i| !self.deleted.get(*i)),
+        )
+    }
+
+    fn iter_from(
+        &self,
+        external_id: Option<PointIdType>,
+    ) -> Box<dyn Iterator<Item = (PointIdType, PointOffsetType)> + '_> {
+        match external_id {
+            None => Box::new(self.iter_num_from(0).chain(self.iter_uuid_from(0))),
+            Some(PointIdType::NumId(idx)) => {
+                // Because u64 keys are less that uuid key, we can just use the full iterator for uuid
+                let start = self
+                    .external_to_internal_num
+                    .partition_point(|mapping| mapping.external_id < idx);
+                Box::new(self.iter_num_from(start).chain(self.iter_uuid_from(0)))
+            }
+            Some(PointIdType::Uuid(uuid)) => {
+                // if offset is a uuid, we can only iterate over uuids
+                let start = self
+                    .external_to_internal_uuid
+                    .partition_point(|mapping| &mapping.external_id < uuid.as_bytes());
+                Box::new(self.iter_uuid_from(start))
+            }
+        }
+    }
+
+    /// Deleted flags are the only part of the mapping which may change.
+    fn mapping_flusher(&self) -> Flusher {
+        self.deleted.flusher()
+    }
+
+    fn versions_flusher(&self) -> Flusher {
+        self.internal_to_version.flusher()
+    }
+
+    fn total_point_count(&self) -> usize {
+        self.internal_to_external.len()
+    }
+
+    fn deleted_point_count(&self) -> usize {
+        self.deleted_count
+    }
+
+    fn deleted_point_bitslice(&self) -> &BitSlice {
+        self.deleted.get_bitslice()
+    }
+
+    fn is_deleted_point(&self, internal_id: PointOffsetType) -> bool {
+        if internal_id as usize >= self.internal_to_external.len() {
+            return true;
+        }
+        self.deleted.get(internal_id)
+    }
+
+    fn iter_ids(&self) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
+        self.iter_internal()
+    }
+
+    /// All points are guaranteed to have a version, points without one are dropped on creation.
+    fn cleanup_versions(&mut self) -> OperationResult<()> {
+        Ok(())
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        let directory = &self.directory;
+        let mut files = vec![
+            directory.join(INTERNAL_TO_EXTERNAL_FILE),
+            directory.join(EXTERNAL_TO_INTERNAL_NUM_FILE),
+            directory.join(EXTERNAL_TO_INTERNAL_UUID_FILE),
+            directory.join(VERSIONS_FILE),
+        ];
+        files.extend(self.deleted.files());
+        files
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use tempfile::Builder;
+
+    use super::*;
+    use crate::common::rocksdb_wrapper::{open_db, DB_VECTOR_CF};
+    use crate::id_tracker::simple_id_tracker::SimpleIdTracker;
+
+    fn make_source(dir: &Path, values: &[PointIdType]) -> SimpleIdTracker {
+        let db = open_db(dir, &[DB_VECTOR_CF]).unwrap();
+        let mut id_tracker = SimpleIdTracker::open(db).unwrap();
+        for (internal_id, external_id) in values.iter().enumerate() {
+            let internal_id = internal_id as PointOffsetType;
+            id_tracker.set_link(*external_id, internal_id).unwrap();
+            id_tracker
+                .set_internal_version(internal_id, internal_id as SeqNumberType * 10)
+                .unwrap();
+        }
+        id_tracker
+    }
+
+    fn sample_ids() -> Vec<PointIdType> {
+        vec![
+            100.into(),
+            PointIdType::Uuid(Uuid::from_u128(123_u128)),
+            PointIdType::Uuid(Uuid::from_u128(156_u128)),
+            150.into(),
+            120.into(),
+            PointIdType::Uuid(Uuid::from_u128(12_u128)),
+            180.into(),
+            110.into(),
+            115.into(),
+            PointIdType::Uuid(Uuid::from_u128(673_u128)),
+            190.into(),
+            177.into(),
+            PointIdType::Uuid(Uuid::from_u128(971_u128)),
+        ]
+    }
+
+    #[test]
+    fn test_same_as_source() {
+        let source_dir = Builder::new().prefix("source_dir").tempdir().unwrap();
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+
+        let values = sample_ids();
+        let mut source = make_source(source_dir.path(), &values);
+        source.drop(150.into()).unwrap();
+
+        let id_tracker = ImmutableIdTracker::create(dir.path(), &source).unwrap();
+
+        assert_eq!(id_tracker.total_point_count(), source.total_point_count());
+        assert_eq!(
+            id_tracker.available_point_count(),
+            source.available_point_count(),
+        );
+        assert_eq!(
+            id_tracker.iter_from(None).collect_vec(),
+            source.iter_from(None).collect_vec(),
+        );
+        assert_eq!(
+            id_tracker.iter_external().collect_vec(),
+            source.iter_external().collect_vec(),
+        );
+        assert_eq!(
+            id_tracker.iter_internal().collect_vec(),
+            source.iter_internal().collect_vec(),
+        );
+
+        for external_id in &values {
+            let internal_id = id_tracker.internal_id(*external_id);
+            assert_eq!(internal_id, source.internal_id(*external_id));
+            if let Some(internal_id) = internal_id {
+                assert_eq!(id_tracker.external_id(internal_id), Some(*external_id));
+                assert_eq!(
+                    id_tracker.internal_version(internal_id),
+                    source.internal_version(internal_id),
+                );
+            }
+        }
+        assert!(id_tracker.internal_id(150.into()).is_none());
+        assert!(id_tracker.internal_id(151.into()).is_none());
+
+        let offset = PointIdType::NumId(116);
+        assert_eq!(
+            id_tracker.iter_from(Some(offset)).collect_vec(),
+            source.iter_from(Some(offset)).collect_vec(),
+        );
+        let offset = PointIdType::Uuid(Uuid::from_u128(124_u128));
+        assert_eq!(
+            id_tracker.iter_from(Some(offset)).collect_vec(),
+            source.iter_from(Some(offset)).collect_vec(),
+        );
+    }
+
+    #[test]
+    fn test_delete_and_reopen() {
+        let source_dir = Builder::new().prefix("source_dir").tempdir().unwrap();
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+
+        let values = sample_ids();
+        let source = make_source(source_dir.path(), &values);
+
+        {
+            let mut id_tracker = ImmutableIdTracker::create(dir.path(), &source).unwrap();
+            let internal_id = id_tracker.internal_id(120.into()).unwrap();
+
+            id_tracker.drop(120.into()).unwrap();
+            id_tracker
+                .drop(PointIdType::Uuid(Uuid::from_u128(12_u128)))
+                .unwrap();
+            // Dropping twice must not change the count
+            id_tracker.drop(120.into()).unwrap();
+            id_tracker.set_internal_version(0, 1000).unwrap();
+
+            assert!(id_tracker.is_deleted_point(internal_id));
+            assert_eq!(id_tracker.internal_version(internal_id), None);
+            assert_eq!(id_tracker.deleted_point_count(), 2);
+            assert!(id_tracker.set_link(1000.into(), 0).is_err());
+
+            id_tracker.mapping_flusher()().unwrap();
+            id_tracker.versions_flusher()().unwrap();
+        }
+
+        let id_tracker = ImmutableIdTracker::open(dir.path()).unwrap();
+        assert_eq!(id_tracker.deleted_point_count(), 2);
+        assert_eq!(id_tracker.available_point_count(), values.len() - 2);
+        assert!(id_tracker.internal_id(120.into()).is_none());
+        assert!(id_tracker
+            .internal_id(PointIdType::Uuid(Uuid::from_u128(12_u128)))
+            .is_none());
+        assert_eq!(id_tracker.internal_version(0), Some(1000));
+        assert_eq!(
+            id_tracker.internal_version(values.len() as PointOffsetType),
+            None,
+        );
+        assert!(!id_tracker.iter_external().contains(&120.into()));
+    }
+}
@@ -3,6 +3,7 @@ use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::thread;
+use std::time::Instant;
 
 use atomic_refcell::AtomicRefCell;
 use bitvec::prelude::BitSlice;
@@ -24,6 +25,7 @@ use crate::common::operation_time_statistics::{
 };
 use crate::common::BYTES_IN_KB;
 use crate::data_types::query_context::VectorQueryContext;
+use crate::data_types::query_explain::{CardinalityExplain, SearchStrategy, VectorSearchExplain};
 use crate::data_types::vectors::{QueryVector, Vector, VectorRef};
 use crate::id_tracker::IdTrackerSS;
 use crate::index::hnsw_index::build_condition_checker::BuildConditionChecker;
@@ -289,12 +291,29 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
         let oversampled_top = Self::get_oversampled_top(quantized_vectors.as_ref(), params, top);
 
         let filter_context = filter.map(|f| payload_index.filter_context(f));
-        let points_scorer = FilteredScorer::new(raw_scorer.as_ref(), filter_context.as_deref());
+        let mut points_scorer = FilteredScorer::new(raw_scorer.as_ref(), filter_context.as_deref())
+            .with_scored_points_counter(vector_query_context.scored_vectors_counter());
+
+        // If the storage can estimate scores, traverse the graph with estimated scores to select
+        // candidates, and score only the candidates exactly
+        let candidates_factor = raw_scorer.candidates_factor();
+        let graph_top = match candidates_factor {
+            Some(candidates_factor) => {
+                points_scorer = points_scorer.with_estimated_scores();
+                oversampled_top.saturating_mul(candidates_factor)
+            }
+            None => oversampled_top,
+        };
 
         match &self.graph {
             Some(graph) => {
-                let search_result =
-                    graph.search(oversampled_top, ef, points_scorer, custom_entry_points);
+                let mut search_result =
+                    graph.search(graph_top, ef, points_scorer, custom_entry_points);
+                if candidates_factor.is_some() {
+                    vector_query_context.add_scored_vectors(search_result.len());
+                    let mut candidates = search_result.iter().map(|candidate| candidate.idx);
+                    search_result = raw_scorer.peek_top_iter(&mut candidates, oversampled_top);
+                }
                 self.postprocess_search_result(search_result, vector, params, top, &is_stopped)
             }
             None => Ok(Default::default()),
@@ -326,6 +345,70 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
             .collect()
     }
 
+    /// Search with a filter, choosing between the payload index and HNSW by the filter cardinality
+    fn search_vectors_filtered(
+        &self,
+        vectors: &[&QueryVector],
+        query_filter: &Filter,
+        top: usize,
+        params: Option<&SearchParams>,
+        query_context: &VectorQueryContext,
+        explain: &mut VectorSearchExplain,
+    ) -> OperationResult<Vec<Vec<ScoredPointOffset>>> {
+        let payload_index = self.payload_index.borrow();
+        let vector_storage = self.vector_storage.borrow();
+        let id_tracker = self.id_tracker.borrow();
+        let available_vector_count = vector_storage.available_vector_count();
+        let query_point_cardinality = payload_index.estimate_cardinality(query_filter);
+        let query_cardinality = adjust_to_available_vectors(
+            query_point_cardinality,
+            available_vector_count,
+            id_tracker.available_point_count(),
+        );
+        explain.cardinality = Some(CardinalityExplain::from(&query_cardinality));
+
+        if query_cardinality.max < self.config.full_scan_threshold {
+            // if cardinality is small - use plain index
+            let _timer = ScopeDurationMeasurer::new(&self.searches_telemetry.small_cardinality);
+            explain.strategy = SearchStrategy::PayloadIndex;
+            return self.search_vectors_plain(vectors, query_filter, top, params, query_context);
+        }
+
+        if query_cardinality.min > self.config.full_scan_threshold {
+            // if cardinality is high enough - use HNSW index
+            let _timer = ScopeDurationMeasurer::new(&self.searches_telemetry.large_cardinality);
+            explain.strategy = SearchStrategy::Hnsw;
+            return self.search_vectors_with_graph(
+                vectors,
+                Some(query_filter),
+                top,
+                params,
+                query_context,
+            );
+        }
+
+        let filter_context = payload_index.filter_context(query_filter);
+
+        // Fast cardinality estimation is not enough, do sample estimation of cardinality
+        explain.sampled_cardinality = true;
+        if sample_check_cardinality(
+            id_tracker.sample_ids(Some(vector_storage.deleted_vector_bitslice())),
+            |idx| filter_context.check(idx),
+            self.config.full_scan_threshold,
+            available_vector_count, // Check cardinality among available vectors
+        ) {
+            // if cardinality is high enough - use HNSW index
+            let _timer = ScopeDurationMeasurer::new(&self.searches_telemetry.large_cardinality);
+            explain.strategy = SearchStrategy::Hnsw;
+            self.search_vectors_with_graph(vectors, Some(query_filter), top, params, query_context)
+        } else {
+            // if cardinality is small - use plain index
+            let _timer = ScopeDurationMeasurer::new(&self.searches_telemetry.small_cardinality);
+            explain.strategy = SearchStrategy::PayloadIndex;
+            self.search_vectors_plain(vectors, query_filter, top, params, query_context)
+        }
+    }
+
     fn search_plain(
         &self,
         vector: &QueryVector,
@@ -354,8 +437,15 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
         )?;
         let oversampled_top = Self::get_oversampled_top(quantized_vectors.as_ref(), params, top);
 
-        let search_result =
-            raw_scorer.peek_top_iter(&mut filtered_points.iter().copied(), oversampled_top);
+        vector_query_context.add_scored_vectors(filtered_points.len());
+        let mut points = filtered_points.iter().copied();
+        // Candidates selected by estimated scores are only good enough for approximate search
+        let exact = params.map(|params| params.exact).unwrap_or(false);
+        let search_result = if exact {
+            raw_scorer.peek_top_iter(&mut points, oversampled_top)
+        } else {
+            raw_scorer.peek_top_iter_approximate(&mut points, oversampled_top)
+        };
 
         self.postprocess_search_result(search_result, vector, params, top, &is_stopped)
     }
@@ -427,6 +517,22 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
         quantized_storage.is_some() && !ignore_quantization
     }
 
+    fn is_rescored_search(
+        quantized_storage: Option<&QuantizedVectors>,
+        params: Option<&SearchParams>,
+    ) -> bool {
+        let quantization_enabled = Self::is_quantized_search(quantized_storage, params);
+
+        let default_rescoring = quantized_storage
+            .map(|q| q.default_rescoring())
+            .unwrap_or(false);
+        quantization_enabled
+            && params
+                .and_then(|p| p.quantization)
+                .and_then(|q| q.rescore)
+                .unwrap_or(default_rescoring)
+    }
+
     fn construct_search_scorer<'a>(
         vector: &QueryVector,
         vector_storage: &'a VectorStorageEnum,
@@ -484,17 +590,7 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
         let vector_storage = self.vector_storage.borrow();
         let quantized_vectors = self.quantized_vectors.borrow();
 
-        let quantization_enabled = Self::is_quantized_search(quantized_vectors.as_ref(), params);
-
-        let default_rescoring = quantized_vectors
-            .as_ref()
-            .map(|q| q.default_rescoring())
-            .unwrap_or(false);
-        let rescore = quantization_enabled
-            && params
-                .and_then(|p| p.quantization)
-                .and_then(|q| q.rescore)
-                .unwrap_or(default_rescoring);
+        let rescore = Self::is_rescored_search(quantized_vectors.as_ref(), params);
 
         let mut postprocess_result = if rescore {
             let raw_scorer = new_stoppable_raw_scorer(
@@ -534,7 +630,10 @@ impl<TGraphLinks: GraphLinks> VectorIndex for HNSWIndex<TGraphLinks> {
         query_context: &VectorQueryContext,
     ) -> OperationResult<Vec<Vec<ScoredPointOffset>>> {
         let exact = params.map(|params| params.exact).unwrap_or(false);
-        match filter {
+        let started = Instant::now();
+        let mut explain = VectorSearchExplain::new(SearchStrategy::Hnsw, vectors.len());
+
+        let result = match filter {
             None => {
                 let id_tracker = self.id_tracker.borrow();
                 let vector_storage = self.vector_storage.borrow();
@@ -553,6 +652,15 @@ impl<TGraphLinks: GraphLinks> VectorIndex for HNSWIndex<TGraphLinks> {
                     } else {
                         &self.searches_telemetry.unfiltered_plain
                     });
+                    explain.strategy = if exact {
+                        SearchStrategy::Exact
+                    } else {
+                        SearchStrategy::FullScan
+                    };
+                    query_context.add_scored_vectors(
+                        vector_storage.available_vector_count() * vectors.len(),
+                    );
+
                     let deleted_points = query_context
                         .deleted_points()
                         .unwrap_or(id_tracker.deleted_point_bitslice());
@@ -595,74 +703,43 @@ impl<TGraphLinks: GraphLinks> VectorIndex for HNSWIndex<TGraphLinks> {
                     });
                     let _timer =
                         ScopeDurationMeasurer::new(&self.searches_telemetry.exact_filtered);
-                    return self.search_vectors_plain(
+                    explain.strategy = SearchStrategy::Exact;
+                    self.search_vectors_plain(
                         vectors,
                         query_filter,
                         top,
                         exact_params.as_ref(),
                         query_context,
-                    );
-                }
-
-                let payload_index = self.payload_index.borrow();
-                let vector_storage = self.vector_storage.borrow();
-                let id_tracker = self.id_tracker.borrow();
-                let available_vector_count = vector_storage.available_vector_count();
-                let query_point_cardinality = payload_index.estimate_cardinality(query_filter);
-                let query_cardinality = adjust_to_available_vectors(
-                    query_point_cardinality,
-                    available_vector_count,
-                    id_tracker.available_point_count(),
-                );
-
-                if query_cardinality.max < self.config.full_scan_threshold {
-                    // if cardinality is small - use plain index
-                    let _timer =
-                        ScopeDurationMeasurer::new(&self.searches_telemetry.small_cardinality);
-                    return self.search_vectors_plain(
+                    )
+                } else {
+                    self.search_vectors_filtered(
                         vectors,
                         query_filter,
                         top,
                         params,
                         query_context,
-                    );
-                }
-
-                if query_cardinality.min > self.config.full_scan_threshold {
-                    // if cardinality is high enough - use HNSW index
-                    let _timer =
-                        ScopeDurationMeasurer::new(&self.searches_telemetry.large_cardinality);
-                    return self.search_vectors_with_graph(
-                        vectors,
-                        filter,
-                        top,
-                        params,
-                        query_context,
-                    );
+                        &mut explain,
+                    )
                 }
+            }
+        };
 
-                let filter_context = payload_index.filter_context(query_filter);
-
-                // Fast cardinality estimation is not enough, do sample estimation of cardinality
-                let id_tracker = self.id_tracker.borrow();
-                if sample_check_cardinality(
-                    id_tracker.sample_ids(Some(vector_storage.deleted_vector_bitslice())),
-                    |idx| filter_context.check(idx),
-                    self.config.full_scan_threshold,
-                    available_vector_count, // Check cardinality among available vectors
-                ) {
-                    // if cardinality is high enough - use HNSW index
-                    let _timer =
-                        ScopeDurationMeasurer::new(&self.searches_telemetry.large_cardinality);
-                    self.search_vectors_with_graph(vectors, filter, top, params, query_context)
-                } else {
-                    // if cardinality is small - use plain index
-                    let _timer =
-                        ScopeDurationMeasurer::new(&self.searches_telemetry.small_cardinality);
-                    self.search_vectors_plain(vectors, query_filter, top, params, query_context)
-                }
+        if query_context.is_explained() {
+            if matches!(
+                explain.strategy,
+                SearchStrategy::Hnsw | SearchStrategy::PayloadIndex
+            ) {
+                let quantized_vectors = self.quantized_vectors.borrow();
+                explain.quantization =
+                    Self::is_quantized_search(quantized_vectors.as_ref(), params);
+                explain.rescoring = Self::is_rescored_search(quantized_vectors.as_ref(), params);
             }
+            explain.vectors_scored = Some(query_context.take_scored_vectors());
+            explain.duration_micros = started.elapsed().as_micros() as u64;
+            query_context.explain_search(explain);
         }
+
+        result
     }
 
     fn build_index_with_progress(
@@ -1,3 +1,5 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
 use common::types::{PointOffsetType, ScoreType, ScoredPointOffset};
 
 use crate::payload_storage::FilterContext;
@@ -7,6 +9,9 @@ pub struct FilteredScorer<'a> {
     pub raw_scorer: &'a dyn RawScorer,
     pub filter_context: Option<&'a dyn FilterContext>,
     points_buffer: Vec<ScoredPointOffset>,
+    scored_points: Option<&'a AtomicUsize>,
+    /// Score points with estimated scores of the raw scorer, see `RawScorer::candidates_factor`
+    estimated: bool,
 }
 
 impl<'a> FilteredScorer<'a> {
@@ -18,6 +23,26 @@ impl<'a> FilteredScorer<'a> {
             raw_scorer,
             filter_context,
             points_buffer: Vec::new(),
+            scored_points: None,
+            estimated: false,
+        }
+    }
+
+    /// Count scored points into the given counter, used to explain searches
+    pub fn with_scored_points_counter(mut self, counter: Option<&'a AtomicUsize>) -> Self {
+        self.scored_points = counter;
+        self
+    }
+
+    /// Score points with estimated scores, which are cheaper but less precise
+    pub fn with_estimated_scores(mut self) -> Self {
+        self.estimated = true;
+        self
+    }
+
+    fn count_scored_points(&self, count: usize) {
+        if let Some(counter) = self.scored_points {
+            counter.fetch_add(count, Ordering::Relaxed);
         }
     }
 
@@ -66,14 +91,24 @@ impl<'a> FilteredScorer<'a> {
             self.points_buffer
                 .resize_with(limit, ScoredPointOffset::default);
         }
-        let count = self
-            .raw_scorer
-            .score_points(filtered_point_ids, &mut self.points_buffer);
+        self.count_scored_points(filtered_point_ids.len());
+        let count = if self.estimated {
+            self.raw_scorer
+                .score_points_estimate(filtered_point_ids, &mut self.points_buffer)
+        } else {
+            self.raw_scorer
+                .score_points(filtered_point_ids, &mut self.points_buffer)
+        };
         &self.points_buffer[0..count]
     }
 
     pub fn score_point(&self, point_id: PointOffsetType) -> ScoreType {
-        self.raw_scorer.score_point(point_id)
+        self.count_scored_points(1);
+        if self.estimated {
+            self.raw_scorer.score_point_estimate(point_id)
+        } else {
+            self.raw_scorer.score_point(point_id)
+        }
     }
 
     pub fn score_internal(&self, point_a: PointOffsetType, point_b: PointOffsetType) -> ScoreType {
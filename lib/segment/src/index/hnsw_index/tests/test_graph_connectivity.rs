@@ -50,6 +50,7 @@ fn test_graph_connectivity() {
             },
         )]),
         payload_storage_type: Default::default(),
+        storage_format: Default::default(),
         sparse_vector_data: Default::default(),
     };
 
@@ -3,6 +3,7 @@ use std::fs::create_dir_all;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
+use std::time::Instant;
 
 use atomic_refcell::AtomicRefCell;
 use common::cpu::CpuPermit;
@@ -16,6 +17,7 @@ use crate::common::operation_time_statistics::{
 };
 use crate::common::{Flusher, BYTES_IN_KB};
 use crate::data_types::query_context::VectorQueryContext;
+use crate::data_types::query_explain::{SearchStrategy, VectorSearchExplain};
 use crate::data_types::vectors::{QueryVector, VectorRef};
 use crate::id_tracker::IdTrackerSS;
 use crate::index::field_index::{CardinalityEstimation, PayloadBlockCondition};
@@ -268,14 +270,18 @@ impl VectorIndex for PlainIndex {
         }
 
         let is_stopped = query_context.is_stopped();
+        let started = Instant::now();
+        let mut explain = VectorSearchExplain::new(SearchStrategy::FullScan, vectors.len());
 
-        match filter {
+        let result = match filter {
             Some(filter) => {
                 let _timer = ScopeDurationMeasurer::new(&self.filtered_searches_telemetry);
                 let id_tracker = self.id_tracker.borrow();
                 let payload_index = self.payload_index.borrow();
                 let vector_storage = self.vector_storage.borrow();
                 let filtered_ids_vec = payload_index.query_points(filter);
+                explain.strategy = SearchStrategy::PayloadIndex;
+                explain.vectors_scored = Some(filtered_ids_vec.len() * vectors.len());
                 let deleted_points = query_context
                     .deleted_points()
                     .unwrap_or(id_tracker.deleted_point_bitslice());
@@ -301,6 +307,8 @@ impl VectorIndex for PlainIndex {
                 let deleted_points = query_context
                     .deleted_points()
                     .unwrap_or(id_tracker.deleted_point_bitslice());
+                explain.vectors_scored =
+                    Some(vector_storage.available_vector_count() * vectors.len());
                 vectors
                     .iter()
                     .map(|&vector| {
@@ -314,7 +322,14 @@ impl VectorIndex for PlainIndex {
                     })
                     .collect()
             }
+        };
+
+        if query_context.is_explained() {
+            explain.duration_micros = started.elapsed().as_micros() as u64;
+            query_context.explain_search(explain);
         }
+
+        result
     }
 
     fn build_index_with_progress(
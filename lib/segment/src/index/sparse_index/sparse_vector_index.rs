@@ -3,6 +3,7 @@ use std::fs::create_dir_all;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
+use std::time::Instant;
 
 use atomic_refcell::AtomicRefCell;
 use common::cpu::CpuPermit;
@@ -22,6 +23,7 @@ use super::sparse_index_config::SparseIndexType;
 use crate::common::operation_error::{check_process_stopped, OperationError, OperationResult};
 use crate::common::operation_time_statistics::ScopeDurationMeasurer;
 use crate::data_types::query_context::VectorQueryContext;
+use crate::data_types::query_explain::{CardinalityExplain, SearchStrategy, VectorSearchExplain};
 use crate::data_types::vectors::{QueryVector, Vector, VectorRef};
 use crate::id_tracker::IdTrackerSS;
 use crate::index::field_index::CardinalityEstimation;
@@ -446,6 +448,7 @@ impl<TInvertedIndex: InvertedIndex> VectorIndex for SparseVectorIndex<TInvertedI
         _params: Option<&SearchParams>,
         query_context: &VectorQueryContext,
     ) -> OperationResult<Vec<Vec<ScoredPointOffset>>> {
+        let started = Instant::now();
         let mut results = Vec::with_capacity(vectors.len());
         let mut prefiltered_points = None;
         for vector in vectors {
@@ -472,6 +475,31 @@ impl<TInvertedIndex: InvertedIndex> VectorIndex for SparseVectorIndex<TInvertedI
 
             results.push(search_results);
         }
+
+        if query_context.is_explained() {
+            let mut explain = VectorSearchExplain::new(SearchStrategy::Sparse, vectors.len());
+            let is_nearest = vectors
+                .iter()
+                .all(|vector| matches!(vector, QueryVector::Nearest(_)));
+            match filter {
+                Some(filter) => {
+                    let query_cardinality = self.get_query_cardinality(filter);
+                    let threshold = self
+                        .config
+                        .full_scan_threshold
+                        .unwrap_or(DEFAULT_SPARSE_FULL_SCAN_THRESHOLD);
+                    if !is_nearest || query_cardinality.max < threshold {
+                        explain.strategy = SearchStrategy::PayloadIndex;
+                    }
+                    explain.cardinality = Some(CardinalityExplain::from(&query_cardinality));
+                }
+                None if !is_nearest => explain.strategy = SearchStrategy::FullScan,
+                None => {}
+            }
+            explain.duration_micros = started.elapsed().as_micros() as u64;
+            query_context.explain_search(explain);
+        }
+
         Ok(results)
     }
 
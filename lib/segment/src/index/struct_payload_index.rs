@@ -12,7 +12,7 @@ use rocksdb::DB;
 use schemars::_serde_json::Value;
 
 use crate::common::operation_error::{OperationError, OperationResult};
-use crate::common::rocksdb_wrapper::open_db_with_existing_cf;
+use crate::common::rocksdb_wrapper::{check_db_exists, open_db_with_existing_cf};
 use crate::common::utils::IndexesMap;
 use crate::common::Flusher;
 use crate::id_tracker::IdTrackerSS;
@@ -51,7 +51,8 @@ pub struct StructPayloadIndex {
     path: PathBuf,
     /// Used to select unique point ids
     visited_pool: VisitedPool,
-    db: Arc<RwLock<DB>>,
+    /// Storage of field indexes, opened only once there is something to index
+    db: Option<Arc<RwLock<DB>>>,
 }
 
 impl StructPayloadIndex {
@@ -93,6 +94,27 @@ impl StructPayloadIndex {
         PayloadConfig::get_config_path(&self.path)
     }
 
+    fn open_database(path: &Path) -> OperationResult<Arc<RwLock<DB>>> {
+        open_db_with_existing_cf(path)
+            .map_err(|err| OperationError::service_error(format!("RocksDB open error: {err}")))
+    }
+
+    fn database(&self) -> OperationResult<Arc<RwLock<DB>>> {
+        self.db.clone().ok_or_else(|| {
+            OperationError::service_error(format!(
+                "Payload index database is not opened at {:?}",
+                self.path,
+            ))
+        })
+    }
+
+    fn ensure_database(&mut self) -> OperationResult<()> {
+        if self.db.is_none() {
+            self.db = Some(Self::open_database(&self.path)?);
+        }
+        Ok(())
+    }
+
     fn save_config(&self) -> OperationResult<()> {
         let config_path = self.config_path();
         self.config.save(&config_path)
@@ -115,7 +137,7 @@ impl StructPayloadIndex {
         payload_schema: PayloadFieldSchema,
         is_appendable: bool,
     ) -> OperationResult<Vec<FieldIndex>> {
-        let mut indexes = index_selector(field, &payload_schema, self.db.clone(), is_appendable);
+        let mut indexes = index_selector(field, &payload_schema, self.database()?, is_appendable);
 
         let mut is_loaded = true;
         for ref mut index in indexes.iter_mut() {
@@ -147,8 +169,12 @@ impl StructPayloadIndex {
             PayloadConfig::default()
         };
 
-        let db = open_db_with_existing_cf(path)
-            .map_err(|err| OperationError::service_error(format!("RocksDB open error: {err}")))?;
+        // Segments without indexed fields don't need RocksDB at all
+        let db = if check_db_exists(path) || !config.indexed_fields.is_empty() {
+            Some(Self::open_database(path)?)
+        } else {
+            None
+        };
 
         let mut index = StructPayloadIndex {
             payload,
@@ -176,7 +202,7 @@ impl StructPayloadIndex {
         payload_schema: PayloadFieldSchema,
     ) -> OperationResult<Vec<FieldIndex>> {
         let payload_storage = self.payload.borrow();
-        let mut field_indexes = index_selector(field, &payload_schema, self.db.clone(), true);
+        let mut field_indexes = index_selector(field, &payload_schema, self.database()?, true);
         for index in &field_indexes {
             index.recreate()?;
         }
@@ -208,6 +234,11 @@ impl StructPayloadIndex {
         self.id_tracker.borrow().available_point_count()
     }
 
+    /// Ratio of stale data in the payload storage, if the storage doesn't reclaim it on update
+    pub fn payload_storage_littered_ratio(&self) -> Option<f64> {
+        self.payload.borrow().littered_ratio()
+    }
+
     fn struct_filtered_context<'a>(&'a self, filter: &'a Filter) -> StructFilterContext<'a> {
         let estimator = |condition: &Condition| self.condition_cardinality(condition, None);
         let id_tracker = self.id_tracker.borrow();
@@ -352,6 +383,7 @@ impl PayloadIndex for StructPayloadIndex {
                 return Ok(());
             }
         }
+        self.ensure_database()?;
         self.build_and_save(field, payload_schema)?;
         self.save_config()?;
 
@@ -562,10 +594,15 @@ impl PayloadIndex for StructPayloadIndex {
     }
 
     fn take_database_snapshot(&self, path: &Path) -> OperationResult<()> {
-        crate::rocksdb_backup::create(&self.db.read(), path)
+        match &self.db {
+            Some(db) => crate::rocksdb_backup::create(&db.read(), path),
+            None => Ok(()),
+        }
     }
 
     fn files(&self) -> Vec<PathBuf> {
-        vec![self.config_path()]
+        let mut files = vec![self.config_path()];
+        files.extend(self.payload.borrow().files());
+        files
     }
 }
@@ -0,0 +1,479 @@
+use std::cmp::max;
+use std::fs::{create_dir_all, remove_file};
+use std::path::{Path, PathBuf};
+
+use common::types::PointOffsetType;
+use serde_json::Value;
+
+use crate::common::operation_error::{OperationError, OperationResult};
+use crate::common::Flusher;
+use crate::json_path::JsonPath;
+use crate::payload_storage::PayloadStorage;
+use crate::types::Payload;
+use crate::vector_storage::chunked_mmap_vectors::ChunkedMmapVectors;
+use crate::vector_storage::chunked_utils::{chunk_name, create_chunk, read_mmaps, MmapChunk};
+
+const PAGES_DIR_PATH: &str = "pages";
+const POINTERS_DIR_PATH: &str = "pointers";
+
+/// Payload values always occupy a whole number of blocks.
+const BLOCK_SIZE_BYTES: usize = 128;
+
+#[cfg(debug_assertions)]
+const PAGE_SIZE_BYTES: usize = 1024 * 1024; // 1Mb
+#[cfg(not(debug_assertions))]
+const PAGE_SIZE_BYTES: usize = 32 * 1024 * 1024; // 32Mb
+
+/// Location of a serialized payload value.
+///
+/// All-zero pointer (default value of the mmap file) means that there is no payload.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct PayloadPointer {
+    page_id: u32,
+    block_offset: u32,
+    /// Length of the serialized payload in bytes
+    length: u32,
+}
+
+impl PayloadPointer {
+    fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    fn blocks(&self) -> usize {
+        (self.length as usize).div_ceil(BLOCK_SIZE_BYTES)
+    }
+}
+
+/// Keeps track of how many blocks of each page are still referenced by a pointer.
+///
+/// Not persisted, as it is cheap to rebuild from the pointers on load.
+#[derive(Default)]
+struct FreeSpaceTracker {
+    /// Number of referenced blocks per page
+    used_blocks: Vec<usize>,
+    /// Number of blocks handed out per page, pages are only ever written at their end
+    allocated_blocks: Vec<usize>,
+}
+
+impl FreeSpaceTracker {
+    fn release(&mut self, pointer: &PayloadPointer) {
+        if pointer.is_empty() {
+            return;
+        }
+        let used = &mut self.used_blocks[pointer.page_id as usize];
+        *used = used.saturating_sub(pointer.blocks());
+    }
+
+    fn total_allocated(&self) -> usize {
+        self.allocated_blocks.iter().sum()
+    }
+
+    fn total_used(&self) -> usize {
+        self.used_blocks.iter().sum()
+    }
+}
+
+/// Payload storage based on append-only, memory mapped pages.
+///
+/// Each payload is serialized as CBOR and written to the end of the last page, taking a whole
+/// number of blocks and never crossing a page boundary. Updated or removed values leave their old
+/// blocks behind, which are tracked as free space. The space is reclaimed when the segment is
+/// rebuilt by the optimizer, which writes all payloads compactly into a new storage.
+///
+/// Because old blocks are never overwritten, a crash between writing a page and flushing the
+/// pointers can't corrupt a previously persisted payload.
+pub struct MmapPayloadStorage {
+    pages: Vec<MmapChunk<u8>>,
+    pointers: ChunkedMmapVectors<PayloadPointer>,
+    tracker: FreeSpaceTracker,
+    path: PathBuf,
+}
+
+impl MmapPayloadStorage {
+    pub fn open(path: &Path) -> OperationResult<Self> {
+        let pages_path = path.join(PAGES_DIR_PATH);
+        let pointers_path = path.join(POINTERS_DIR_PATH);
+        create_dir_all(&pages_path)?;
+
+        let pages: Vec<MmapChunk<u8>> = read_mmaps(&pages_path)?;
+        let pointers: ChunkedMmapVectors<PayloadPointer> =
+            ChunkedMmapVectors::open(&pointers_path, 1)?;
+
+        let mut tracker = FreeSpaceTracker {
+            used_blocks: vec![0; pages.len()],
+            allocated_blocks: pages
+                .iter()
+                .map(|page| page.len() / BLOCK_SIZE_BYTES)
+                .collect(),
+        };
+        // Last page may be partially filled, continue writing after the last referenced block
+        if let Some(last_allocated) = tracker.allocated_blocks.last_mut() {
+            *last_allocated = 0;
+        }
+        let last_page_id = pages.len().saturating_sub(1);
+        for point_id in 0..pointers.len() {
+            let Some(pointer) = pointers.get(point_id).and_then(|p| p.first()).copied() else {
+                continue;
+            };
+            if pointer.is_empty() {
+                continue;
+            }
+            let page_id = pointer.page_id as usize;
+            if page_id >= pages.len() {
+                return Err(OperationError::service_error(format!(
+                    "Payload of point {point_id} refers to missing page {page_id} in {}",
+                    pages_path.display(),
+                )));
+            }
+            tracker.used_blocks[page_id] += pointer.blocks();
+            if page_id == last_page_id {
+                tracker.allocated_blocks[page_id] = max(
+                    tracker.allocated_blocks[page_id],
+                    pointer.block_offset as usize + pointer.blocks(),
+                );
+            }
+        }
+
+        Ok(MmapPayloadStorage {
+            pages,
+            pointers,
+            tracker,
+            path: path.to_owned(),
+        })
+    }
+
+    fn pages_path(&self) -> PathBuf {
+        self.path.join(PAGES_DIR_PATH)
+    }
+
+    fn get_pointer(&self, point_id: PointOffsetType) -> Option<PayloadPointer> {
+        if point_id as usize >= self.pointers.len() {
+            return None;
+        }
+        self.pointers
+            .get(point_id)
+            .and_then(|pointer| pointer.first().copied())
+            .filter(|pointer| !pointer.is_empty())
+    }
+
+    /// Find space for a value of the given length at the end of the storage.
+    ///
+    /// Returns page id and block offset.
+    fn allocate(&mut self, length: usize) -> OperationResult<(usize, usize)> {
+        let blocks = length.div_ceil(BLOCK_SIZE_BYTES);
+
+        if let Some(last_page) = self.pages.last() {
+            let page_id = self.pages.len() - 1;
+            let page_blocks = last_page.len() / BLOCK_SIZE_BYTES;
+            let allocated = self.tracker.allocated_blocks[page_id];
+            if allocated + blocks <= page_blocks {
+                self.tracker.allocated_blocks[page_id] += blocks;
+                return Ok((page_id, allocated));
+            }
+            // Rest of the page is never going to be used
+            self.tracker.allocated_blocks[page_id] = page_blocks;
+        }
+
+        // Values larger than a page get a dedicated page
+        let page_size = max(PAGE_SIZE_BYTES, blocks * BLOCK_SIZE_BYTES);
+        let page_id = self.pages.len();
+        let page = create_chunk(&self.pages_path(), page_id, page_size)?;
+        self.pages.push(page);
+        self.tracker.used_blocks.push(0);
+        self.tracker.allocated_blocks.push(blocks);
+        Ok((page_id, 0))
+    }
+
+    pub fn read_payload(&self, point_id: PointOffsetType) -> OperationResult<Option<Payload>> {
+        let Some(pointer) = self.get_pointer(point_id) else {
+            return Ok(None);
+        };
+        let page = self.pages.get(pointer.page_id as usize).ok_or_else(|| {
+            OperationError::service_error(format!(
+                "Payload page {} of point {point_id} not found",
+                pointer.page_id,
+            ))
+        })?;
+        let start = pointer.block_offset as usize * BLOCK_SIZE_BYTES;
+        let end = start + pointer.length as usize;
+        if end > page.len() {
+            return Err(OperationError::service_error(format!(
+                "Payload of point {point_id} is out of page {} bounds",
+                pointer.page_id,
+            )));
+        }
+        let payload = serde_cbor::from_slice(&page[start..end])?;
+        Ok(Some(payload))
+    }
+
+    fn update_storage(
+        &mut self,
+        point_id: PointOffsetType,
+        payload: &Payload,
+    ) -> OperationResult<()> {
+        let bytes = serde_cbor::to_vec(payload)?;
+        let length = u32::try_from(bytes.len()).map_err(|_| {
+            OperationError::service_error(format!(
+                "Payload of point {point_id} is too large: {} bytes",
+                bytes.len(),
+            ))
+        })?;
+
+        let (page_id, block_offset) = self.allocate(bytes.len())?;
+        let start = block_offset * BLOCK_SIZE_BYTES;
+        self.pages[page_id][start..start + bytes.len()].copy_from_slice(&bytes);
+
+        let pointer = PayloadPointer {
+            page_id: page_id as u32,
+            block_offset: block_offset as u32,
+            length,
+        };
+        self.set_pointer(point_id, pointer)?;
+        self.tracker.used_blocks[page_id] += pointer.blocks();
+        Ok(())
+    }
+
+    fn remove_from_storage(&mut self, point_id: PointOffsetType) -> OperationResult<()> {
+        if self.get_pointer(point_id).is_some() {
+            self.set_pointer(point_id, PayloadPointer::default())?;
+        }
+        Ok(())
+    }
+
+    /// Replace pointer of the given point and release the blocks of the previous value.
+    fn set_pointer(
+        &mut self,
+        point_id: PointOffsetType,
+        pointer: PayloadPointer,
+    ) -> OperationResult<()> {
+        if let Some(previous) = self.get_pointer(point_id) {
+            self.tracker.release(&previous);
+        }
+        self.pointers.insert(point_id, &[pointer])
+    }
+
+    /// Ratio of the written space which is no longer referenced by any payload.
+    pub fn littered_ratio(&self) -> f64 {
+        let allocated = self.tracker.total_allocated();
+        if allocated == 0 {
+            return 0.0;
+        }
+        let free = allocated.saturating_sub(self.tracker.total_used());
+        free as f64 / allocated as f64
+    }
+
+    pub fn iter<F>(&self, mut callback: F) -> OperationResult<()>
+    where
+        F: FnMut(PointOffsetType, &Payload) -> OperationResult<bool>,
+    {
+        for point_id in 0..self.pointers.len() as PointOffsetType {
+            let Some(payload) = self.read_payload(point_id)? else {
+                continue;
+            };
+            let do_continue = callback(point_id, &payload)?;
+            if !do_continue {
+                return Ok(());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PayloadStorage for MmapPayloadStorage {
+    fn assign_all(&mut self, point_id: PointOffsetType, payload: &Payload) -> OperationResult<()> {
+        self.update_storage(point_id, payload)
+    }
+
+    fn assign(&mut self, point_id: PointOffsetType, payload: &Payload) -> OperationResult<()> {
+        let stored_payload = self.read_payload(point_id)?;
+        match stored_payload {
+            Some(mut point_payload) => {
+                point_payload.merge(payload);
+                self.update_storage(point_id, &point_payload)?
+            }
+            None => self.update_storage(point_id, payload)?,
+        }
+        Ok(())
+    }
+
+    fn assign_by_key(
+        &mut self,
+        point_id: PointOffsetType,
+        payload: &Payload,
+        key: &JsonPath,
+    ) -> OperationResult<()> {
+        let stored_payload = self.read_payload(point_id)?;
+        match stored_payload {
+            Some(mut point_payload) => {
+                point_payload.merge_by_key(payload, key)?;
+                self.update_storage(point_id, &point_payload)
+            }
+            None => {
+                let mut dest_payload = Payload::default();
+                dest_payload.merge_by_key(payload, key)?;
+                self.update_storage(point_id, &dest_payload)
+            }
+        }
+    }
+
+    fn payload(&self, point_id: PointOffsetType) -> OperationResult<Payload> {
+        let payload = self.read_payload(point_id)?;
+        match payload {
+            Some(payload) => Ok(payload),
+            None => Ok(Default::default()),
+        }
+    }
+
+    fn delete(&mut self, point_id: PointOffsetType, key: &JsonPath) -> OperationResult<Vec<Value>> {
+        let stored_payload = self.read_payload(point_id)?;
+
+        match stored_payload {
+            Some(mut payload) => {
+                let res = payload.remove(key);
+                if !res.is_empty() {
+                    self.update_storage(point_id, &payload)?;
+                }
+                Ok(res)
+            }
+            None => Ok(vec![]),
+        }
+    }
+
+    fn drop(&mut self, point_id: PointOffsetType) -> OperationResult<Option<Payload>> {
+        let payload = self.read_payload(point_id)?;
+        self.remove_from_storage(point_id)?;
+        Ok(payload)
+    }
+
+    fn wipe(&mut self) -> OperationResult<()> {
+        for point_id in 0..self.pointers.len() {
+            self.pointers
+                .insert(point_id, &[PayloadPointer::default()])?;
+        }
+
+        let pages_path = self.pages_path();
+        let pages_count = self.pages.len();
+        self.pages.clear();
+        for page_id in 0..pages_count {
+            remove_file(chunk_name(&pages_path, page_id))?;
+        }
+        self.tracker = FreeSpaceTracker::default();
+        Ok(())
+    }
+
+    fn flusher(&self) -> Flusher {
+        Box::new({
+            let pages_flushers: Vec<_> = self.pages.iter().map(|page| page.flusher()).collect();
+            let pointers_flusher = self.pointers.flusher();
+            move || {
+                // Flush values before pointers, so that persisted pointers never refer to
+                // unwritten data
+                for flusher in pages_flushers {
+                    flusher()?;
+                }
+                pointers_flusher()?;
+                Ok(())
+            }
+        })
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        let pages_path = self.pages_path();
+        let mut files: Vec<_> = (0..self.pages.len())
+            .map(|page_id| chunk_name(&pages_path, page_id))
+            .collect();
+        files.extend(self.pointers.files());
+        files
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::Builder;
+
+    use super::*;
+    use crate::json_path::path;
+
+    #[test]
+    fn test_mmap_payload_storage() {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+
+        let payload: Payload = serde_json::from_str(
+            r#"{
+                "name": "John Doe",
+                "age": 52,
+                "location": {
+                    "city": "Melbourne",
+                    "geo": {
+                        "lon": 144.9631,
+                        "lat": 37.8136
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        {
+            let mut storage = MmapPayloadStorage::open(dir.path()).unwrap();
+            storage.assign_all(100, &payload).unwrap();
+            storage.assign_all(5, &payload).unwrap();
+
+            let partial_payload: Payload = serde_json::from_str(r#"{ "age": 53 }"#).unwrap();
+            storage.assign(100, &partial_payload).unwrap();
+            storage.delete(100, &path("location.geo")).unwrap();
+            storage.drop(5).unwrap();
+
+            assert!(storage.littered_ratio() > 0.0);
+            storage.flusher()().unwrap();
+        }
+
+        let mut storage = MmapPayloadStorage::open(dir.path()).unwrap();
+        let res = storage.payload(100).unwrap();
+        assert_eq!(res.0.get("age"), Some(&Value::from(53)));
+        assert!(res.0.contains_key("location"));
+        assert!(res.0.contains_key("name"));
+        assert!(storage.payload(5).unwrap().is_empty());
+        assert!(storage.payload(1000).unwrap().is_empty());
+
+        let mut ids = vec![];
+        storage
+            .iter(|point_id, _| {
+                ids.push(point_id);
+                Ok(true)
+            })
+            .unwrap();
+        assert_eq!(ids, vec![100]);
+
+        // Appending after reopen must not overwrite existing values
+        storage.assign_all(7, &payload).unwrap();
+        assert_eq!(storage.payload(7).unwrap(), payload);
+        assert!(storage.payload(100).unwrap().0.contains_key("name"));
+
+        storage.wipe().unwrap();
+        assert!(storage.payload(100).unwrap().is_empty());
+        assert!(storage.payload(7).unwrap().is_empty());
+        storage.assign_all(100, &payload).unwrap();
+        assert_eq!(storage.payload(100).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_mmap_payload_storage_large_value() {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        let mut storage = MmapPayloadStorage::open(dir.path()).unwrap();
+
+        let small: Payload = serde_json::from_str(r#"{ "value": "small" }"#).unwrap();
+        let large_value = "x".repeat(PAGE_SIZE_BYTES * 2);
+        let large: Payload =
+            serde_json::from_value(serde_json::json!({ "value": large_value })).unwrap();
+
+        storage.assign_all(0, &small).unwrap();
+        storage.assign_all(1, &large).unwrap();
+        storage.assign_all(2, &small).unwrap();
+
+        assert_eq!(storage.payload(0).unwrap(), small);
+        assert_eq!(storage.payload(1).unwrap(), large);
+        assert_eq!(storage.payload(2).unwrap(), small);
+    }
+}
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use common::types::PointOffsetType;
 use serde_json::Value;
 
@@ -41,6 +43,9 @@ pub trait PayloadStorage {
 
     /// Return function that forces persistence of current storage state.
     fn flusher(&self) -> Flusher;
+
+    /// Files which are used by this storage, excluding RocksDB data
+    fn files(&self) -> Vec<PathBuf>;
 }
 
 pub trait ConditionChecker {
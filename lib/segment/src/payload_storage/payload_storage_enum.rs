@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use common::types::PointOffsetType;
 use serde_json::Value;
 
@@ -6,6 +8,7 @@ use crate::common::Flusher;
 use crate::json_path::JsonPath;
 #[cfg(feature = "testing")]
 use crate::payload_storage::in_memory_payload_storage::InMemoryPayloadStorage;
+use crate::payload_storage::mmap_payload_storage::MmapPayloadStorage;
 use crate::payload_storage::on_disk_payload_storage::OnDiskPayloadStorage;
 use crate::payload_storage::simple_payload_storage::SimplePayloadStorage;
 use crate::payload_storage::PayloadStorage;
@@ -16,6 +19,7 @@ pub enum PayloadStorageEnum {
     InMemoryPayloadStorage(InMemoryPayloadStorage),
     SimplePayloadStorage(SimplePayloadStorage),
     OnDiskPayloadStorage(OnDiskPayloadStorage),
+    MmapPayloadStorage(MmapPayloadStorage),
 }
 
 #[cfg(feature = "testing")]
@@ -37,6 +41,12 @@ impl From<OnDiskPayloadStorage> for PayloadStorageEnum {
     }
 }
 
+impl From<MmapPayloadStorage> for PayloadStorageEnum {
+    fn from(a: MmapPayloadStorage) -> Self {
+        PayloadStorageEnum::MmapPayloadStorage(a)
+    }
+}
+
 impl PayloadStorageEnum {
     pub fn iter<F>(&self, callback: F) -> OperationResult<()>
     where
@@ -47,6 +57,20 @@ impl PayloadStorageEnum {
             PayloadStorageEnum::InMemoryPayloadStorage(s) => s.iter(callback),
             PayloadStorageEnum::SimplePayloadStorage(s) => s.iter(callback),
             PayloadStorageEnum::OnDiskPayloadStorage(s) => s.iter(callback),
+            PayloadStorageEnum::MmapPayloadStorage(s) => s.iter(callback),
+        }
+    }
+
+    /// Ratio of the storage space occupied by stale payload values.
+    ///
+    /// Only defined for storages which don't reclaim space on update.
+    pub fn littered_ratio(&self) -> Option<f64> {
+        match self {
+            #[cfg(feature = "testing")]
+            PayloadStorageEnum::InMemoryPayloadStorage(_) => None,
+            PayloadStorageEnum::SimplePayloadStorage(_) => None,
+            PayloadStorageEnum::OnDiskPayloadStorage(_) => None,
+            PayloadStorageEnum::MmapPayloadStorage(s) => Some(s.littered_ratio()),
         }
     }
 }
@@ -58,6 +82,7 @@ impl PayloadStorage for PayloadStorageEnum {
             PayloadStorageEnum::InMemoryPayloadStorage(s) => s.assign(point_id, payload),
             PayloadStorageEnum::SimplePayloadStorage(s) => s.assign(point_id, payload),
             PayloadStorageEnum::OnDiskPayloadStorage(s) => s.assign(point_id, payload),
+            PayloadStorageEnum::MmapPayloadStorage(s) => s.assign(point_id, payload),
         }
     }
 
@@ -74,6 +99,7 @@ impl PayloadStorage for PayloadStorageEnum {
             }
             PayloadStorageEnum::SimplePayloadStorage(s) => s.assign_by_key(point_id, payload, key),
             PayloadStorageEnum::OnDiskPayloadStorage(s) => s.assign_by_key(point_id, payload, key),
+            PayloadStorageEnum::MmapPayloadStorage(s) => s.assign_by_key(point_id, payload, key),
         }
     }
 
@@ -83,6 +109,7 @@ impl PayloadStorage for PayloadStorageEnum {
             PayloadStorageEnum::InMemoryPayloadStorage(s) => s.payload(point_id),
             PayloadStorageEnum::SimplePayloadStorage(s) => s.payload(point_id),
             PayloadStorageEnum::OnDiskPayloadStorage(s) => s.payload(point_id),
+            PayloadStorageEnum::MmapPayloadStorage(s) => s.payload(point_id),
         }
     }
 
@@ -92,6 +119,7 @@ impl PayloadStorage for PayloadStorageEnum {
             PayloadStorageEnum::InMemoryPayloadStorage(s) => s.delete(point_id, key),
             PayloadStorageEnum::SimplePayloadStorage(s) => s.delete(point_id, key),
             PayloadStorageEnum::OnDiskPayloadStorage(s) => s.delete(point_id, key),
+            PayloadStorageEnum::MmapPayloadStorage(s) => s.delete(point_id, key),
         }
     }
 
@@ -101,6 +129,7 @@ impl PayloadStorage for PayloadStorageEnum {
             PayloadStorageEnum::InMemoryPayloadStorage(s) => s.drop(point_id),
             PayloadStorageEnum::SimplePayloadStorage(s) => s.drop(point_id),
             PayloadStorageEnum::OnDiskPayloadStorage(s) => s.drop(point_id),
+            PayloadStorageEnum::MmapPayloadStorage(s) => s.drop(point_id),
         }
     }
 
@@ -110,6 +139,7 @@ impl PayloadStorage for PayloadStorageEnum {
             PayloadStorageEnum::InMemoryPayloadStorage(s) => s.wipe(),
             PayloadStorageEnum::SimplePayloadStorage(s) => s.wipe(),
             PayloadStorageEnum::OnDiskPayloadStorage(s) => s.wipe(),
+            PayloadStorageEnum::MmapPayloadStorage(s) => s.wipe(),
         }
     }
 
@@ -119,6 +149,17 @@ impl PayloadStorage for PayloadStorageEnum {
             PayloadStorageEnum::InMemoryPayloadStorage(s) => s.flusher(),
             PayloadStorageEnum::SimplePayloadStorage(s) => s.flusher(),
             PayloadStorageEnum::OnDiskPayloadStorage(s) => s.flusher(),
+            PayloadStorageEnum::MmapPayloadStorage(s) => s.flusher(),
+        }
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        match self {
+            #[cfg(feature = "testing")]
+            PayloadStorageEnum::InMemoryPayloadStorage(s) => s.files(),
+            PayloadStorageEnum::SimplePayloadStorage(s) => s.files(),
+            PayloadStorageEnum::OnDiskPayloadStorage(s) => s.files(),
+            PayloadStorageEnum::MmapPayloadStorage(s) => s.files(),
         }
     }
 }
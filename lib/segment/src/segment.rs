@@ -91,7 +91,8 @@ pub struct Segment {
     /// Last unhandled error
     /// If not None, all update operations will be aborted until original operation is performed properly
     pub error_status: Option<SegmentFailedState>,
-    pub database: Arc<RwLock<DB>>,
+    /// Not available for segments stored in flat format
+    pub database: Option<Arc<RwLock<DB>>>,
     pub flush_thread: Mutex<Option<JoinHandle<OperationResult<SeqNumberType>>>>,
 }
 
@@ -225,7 +226,10 @@ impl Segment {
                         | VectorStorageEnum::DenseAppendableMemmapHalf(_) => {
                             Vector::from(vec![1.0; dim])
                         }
-                        VectorStorageEnum::SparseSimple(_) => Vector::from(SparseVector::default()),
+                        VectorStorageEnum::SparseSimple(_)
+                        | VectorStorageEnum::SparseAppendableMemmap(_) => {
+                            Vector::from(SparseVector::default())
+                        }
                         VectorStorageEnum::MultiDenseSimple(_)
                         | VectorStorageEnum::MultiDenseSimpleByte(_)
                         | VectorStorageEnum::MultiDenseSimpleHalf(_)
@@ -577,7 +581,10 @@ impl Segment {
             let db_backup_path = snapshot_path.join(DB_BACKUP_PATH);
             let payload_index_db_backup = snapshot_path.join(PAYLOAD_DB_BACKUP_PATH);
 
-            crate::rocksdb_backup::restore(&db_backup_path, &segment_path)?;
+            // Segments in flat format have no RocksDB
+            if db_backup_path.is_dir() {
+                crate::rocksdb_backup::restore(&db_backup_path, &segment_path)?;
+            }
 
             if payload_index_db_backup.is_dir() {
                 StructPayloadIndex::restore_database_snapshot(
@@ -1681,8 +1688,11 @@ impl SegmentEntry for Segment {
         let db_backup_path = temp_path.join(DB_BACKUP_PATH);
         let payload_index_db_backup_path = temp_path.join(PAYLOAD_DB_BACKUP_PATH);
 
-        {
-            let db = self.database.read();
+        // Backups are optional, make sure there is something to archive
+        std::fs::create_dir_all(&temp_path)?;
+
+        if let Some(database) = &self.database {
+            let db = database.read();
             crate::rocksdb_backup::create(&db, &db_backup_path)?;
         }
 
@@ -1753,6 +1763,15 @@ impl SegmentEntry for Segment {
             )?;
         }
 
+        for file in self.id_tracker.borrow().files() {
+            utils::tar::append_file_relative_to_base(
+                &mut builder,
+                &self.current_path,
+                &file,
+                &files,
+            )?;
+        }
+
         utils::tar::append_file(
             &mut builder,
             &self.current_path.join(SEGMENT_STATE_FILE),
@@ -1897,6 +1916,7 @@ mod tests {
             )]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            storage_format: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -1971,6 +1991,7 @@ mod tests {
             )]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            storage_format: Default::default(),
         };
 
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
@@ -2063,6 +2084,7 @@ mod tests {
             )]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            storage_format: Default::default(),
         };
 
         let mut segment = build_segment(segment_base_dir.path(), &config, true).unwrap();
@@ -2160,6 +2182,7 @@ mod tests {
             )]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            storage_format: Default::default(),
         };
 
         let mut segment = build_segment(segment_base_dir.path(), &config, true).unwrap();
@@ -2194,6 +2217,7 @@ mod tests {
             )]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            storage_format: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -2289,6 +2313,7 @@ mod tests {
             )]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            storage_format: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -2357,6 +2382,7 @@ mod tests {
             ]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            storage_format: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -2467,6 +2493,7 @@ mod tests {
             ]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            storage_format: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -2629,6 +2656,7 @@ mod tests {
             )]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            storage_format: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
         segment
@@ -6,15 +6,22 @@ use std::sync::Arc;
 
 use common::cpu::CpuPermit;
 
-use super::get_vector_storage_path;
+use super::{get_vector_storage_path, ID_TRACKER_PATH};
 use crate::common::error_logging::LogError;
 use crate::common::operation_error::{check_process_stopped, OperationError, OperationResult};
+use crate::common::rocksdb_wrapper::{
+    is_db_file, DatabaseColumnWrapper, DB_MAPPING_CF, DB_VERSIONS_CF,
+};
 use crate::entry::entry_point::SegmentEntry;
+use crate::id_tracker::immutable_id_tracker::ImmutableIdTracker;
+use crate::id_tracker::IdTracker;
 use crate::index::hnsw_index::num_rayon_threads;
 use crate::index::{PayloadIndex, VectorIndex};
 use crate::segment::Segment;
 use crate::segment_constructor::{build_segment, load_segment};
-use crate::types::{Indexes, PayloadFieldSchema, PayloadKeyType, SegmentConfig};
+use crate::types::{
+    Indexes, PayloadFieldSchema, PayloadKeyType, SegmentConfig, SegmentStorageFormat,
+};
 use crate::vector_storage::quantized::quantized_vectors::QuantizedVectors;
 use crate::vector_storage::VectorStorage;
 
@@ -24,6 +31,8 @@ pub struct SegmentBuilder {
     pub destination_path: PathBuf,
     pub temp_path: PathBuf,
     pub indexed_fields: HashMap<PayloadKeyType, PayloadFieldSchema>,
+    /// Config of the resulting segment
+    pub segment_config: SegmentConfig,
 }
 
 impl SegmentBuilder {
@@ -32,7 +41,12 @@ impl SegmentBuilder {
         temp_dir: &Path,
         segment_config: &SegmentConfig,
     ) -> OperationResult<Self> {
-        let segment = build_segment(temp_dir, segment_config, true)?;
+        // Flat segments are assembled with RocksDB and converted once built
+        let build_config = SegmentConfig {
+            storage_format: SegmentStorageFormat::RocksDb,
+            ..segment_config.clone()
+        };
+        let segment = build_segment(temp_dir, &build_config, true)?;
         let temp_path = segment.current_path.clone();
 
         let destination_path = segment_path.join(temp_path.file_name().unwrap());
@@ -42,6 +56,7 @@ impl SegmentBuilder {
             destination_path,
             temp_path,
             indexed_fields: Default::default(),
+            segment_config: segment_config.clone(),
         })
     }
 
@@ -214,6 +229,10 @@ impl SegmentBuilder {
 
             Self::update_quantization(&mut segment, stopped)?;
 
+            if !segment.is_appendable() {
+                Self::update_multi_vector_centroids(&segment, stopped)?;
+            }
+
             for vector_data in segment.vector_data.values_mut() {
                 vector_data
                     .vector_index
@@ -230,8 +249,26 @@ impl SegmentBuilder {
             drop(permit);
 
             segment.flush(true)?;
+
+            if !segment.is_appendable() {
+                Self::persist_immutable_id_tracker(&segment)?;
+            }
+
+            // Payload field indexes are only persisted in RocksDB, segments having them are kept
+            // in RocksDB format
+            let is_flat =
+                self.segment_config.storage_format.is_flat() && self.indexed_fields.is_empty();
+            if is_flat {
+                segment.segment_config = self.segment_config.clone();
+                segment.save_current_state()?;
+            }
+
             drop(segment);
             // Now segment is evicted from RAM
+
+            if is_flat {
+                Self::remove_database_files(&self.temp_path)?;
+            }
         }
 
         // Move fully constructed segment into collection directory and load back to RAM
@@ -247,6 +284,73 @@ impl SegmentBuilder {
         Ok(loaded_segment)
     }
 
+    /// Remove RocksDB files of the temporary segment, which is converted into flat format.
+    ///
+    /// RocksDB keeps all of its files in the root of the segment directory. Only files named like
+    /// RocksDB files are removed, everything else in the segment is kept.
+    fn remove_database_files(segment_path: &Path) -> OperationResult<()> {
+        for entry in std::fs::read_dir(segment_path)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+            let is_db_file = entry.file_name().to_str().is_some_and(is_db_file);
+            if is_db_file {
+                std::fs::remove_file(entry.path())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Store id mapping of a non-appendable segment in immutable mmap files.
+    ///
+    /// The mapping is then loaded from these files instead of RocksDB, which doesn't require
+    /// reading the whole mapping into memory.
+    fn persist_immutable_id_tracker(segment: &Segment) -> OperationResult<()> {
+        let id_tracker_path = segment.current_path.join(ID_TRACKER_PATH);
+        let immutable_id_tracker =
+            ImmutableIdTracker::create(&id_tracker_path, &*segment.id_tracker.borrow())?;
+        debug_assert_eq!(
+            immutable_id_tracker.available_point_count(),
+            segment.id_tracker.borrow().available_point_count(),
+        );
+        drop(immutable_id_tracker);
+
+        // Mapping is persisted in mmap files now, RocksDB copy is no longer needed
+        if let Some(database) = &segment.database {
+            DatabaseColumnWrapper::new(database.clone(), DB_MAPPING_CF).recreate_column_family()?;
+            DatabaseColumnWrapper::new(database.clone(), DB_VERSIONS_CF)
+                .recreate_column_family()?;
+        }
+        Ok(())
+    }
+
+    /// Build centroid index for multivectors, which have it configured
+    fn update_multi_vector_centroids(
+        segment: &Segment,
+        stopped: &AtomicBool,
+    ) -> OperationResult<()> {
+        for (vector_name, vector_data) in &segment.vector_data {
+            let centroids_config = segment
+                .config()
+                .vector_data
+                .get(vector_name)
+                .and_then(|config| config.multivec_config)
+                .and_then(|multivec_config| multivec_config.centroids);
+            let Some(centroids_config) = centroids_config else {
+                continue;
+            };
+            check_process_stopped(stopped)?;
+
+            let vector_storage_path = get_vector_storage_path(&segment.current_path, vector_name);
+            vector_data
+                .vector_storage
+                .borrow_mut()
+                .create_multi_vector_centroids(centroids_config, &vector_storage_path, stopped)?;
+        }
+        Ok(())
+    }
+
     fn update_quantization(segment: &mut Segment, stopped: &AtomicBool) -> OperationResult<()> {
         let config = segment.config().clone();
 
@@ -8,15 +8,17 @@ use std::sync::Arc;
 use atomic_refcell::AtomicRefCell;
 use io::storage_version::StorageVersion;
 use log::info;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
+use rocksdb::DB;
 use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::common::operation_error::{check_process_stopped, OperationError, OperationResult};
 use crate::common::rocksdb_wrapper::{open_db, DB_VECTOR_CF};
 use crate::data_types::vectors::DEFAULT_VECTOR_NAME;
+use crate::id_tracker::immutable_id_tracker::ImmutableIdTracker;
 use crate::id_tracker::simple_id_tracker::SimpleIdTracker;
-use crate::id_tracker::IdTracker;
+use crate::id_tracker::{IdTracker, IdTrackerSS};
 use crate::index::hnsw_index::graph_links::{GraphLinksMmap, GraphLinksRam};
 use crate::index::hnsw_index::hnsw::HNSWIndex;
 use crate::index::plain_payload_index::PlainIndex;
@@ -24,12 +26,14 @@ use crate::index::sparse_index::sparse_index_config::SparseIndexType;
 use crate::index::sparse_index::sparse_vector_index::SparseVectorIndex;
 use crate::index::struct_payload_index::StructPayloadIndex;
 use crate::index::VectorIndexEnum;
+use crate::payload_storage::mmap_payload_storage::MmapPayloadStorage;
 use crate::payload_storage::on_disk_payload_storage::OnDiskPayloadStorage;
 use crate::payload_storage::simple_payload_storage::SimplePayloadStorage;
+use crate::payload_storage::PayloadStorage;
 use crate::segment::{Segment, SegmentVersion, VectorData, SEGMENT_STATE_FILE};
 use crate::types::{
     Distance, Indexes, PayloadStorageType, SegmentConfig, SegmentState, SegmentType, SeqNumberType,
-    VectorStorageDatatype, VectorStorageType,
+    SparseVectorStorageType, VectorStorageDatatype, VectorStorageType,
 };
 use crate::vector_storage::dense::appendable_mmap_dense_vector_storage::{
     open_appendable_memmap_vector_storage, open_appendable_memmap_vector_storage_byte,
@@ -46,17 +50,21 @@ use crate::vector_storage::multi_dense::appendable_mmap_multi_dense_vector_stora
     open_appendable_memmap_multi_vector_storage, open_appendable_memmap_multi_vector_storage_byte,
     open_appendable_memmap_multi_vector_storage_half,
 };
+use crate::vector_storage::multi_dense::multi_vector_centroids::MultiVectorCentroids;
 use crate::vector_storage::multi_dense::simple_multi_dense_vector_storage::{
     open_simple_multi_dense_vector_storage, open_simple_multi_dense_vector_storage_byte,
     open_simple_multi_dense_vector_storage_half,
 };
 use crate::vector_storage::quantized::quantized_vectors::QuantizedVectors;
 use crate::vector_storage::simple_sparse_vector_storage::open_simple_sparse_vector_storage;
+use crate::vector_storage::sparse::appendable_mmap_sparse_vector_storage::open_appendable_memmap_sparse_vector_storage;
 use crate::vector_storage::VectorStorage;
 
 pub const PAYLOAD_INDEX_PATH: &str = "payload_index";
 pub const VECTOR_STORAGE_PATH: &str = "vector_storage";
 pub const VECTOR_INDEX_PATH: &str = "vector_index";
+pub const PAYLOAD_STORAGE_PATH: &str = "payload_storage";
+pub const ID_TRACKER_PATH: &str = "id_tracker";
 
 fn sp<T>(t: T) -> Arc<AtomicRefCell<T>> {
     Arc::new(AtomicRefCell::new(t))
@@ -77,6 +85,84 @@ pub fn get_vector_storage_path(segment_path: &Path, vector_name: &str) -> PathBu
     ))
 }
 
+/// Open mmap payload storage of the segment.
+///
+/// If the storage doesn't exist yet, payloads previously stored in RocksDB are migrated into it.
+/// The new storage is built in a temporary directory first, so an interrupted migration is simply
+/// restarted on the next load.
+fn open_mmap_payload_storage(
+    segment_path: &Path,
+    database: Arc<RwLock<DB>>,
+    stopped: &AtomicBool,
+) -> OperationResult<MmapPayloadStorage> {
+    let storage_path = segment_path.join(PAYLOAD_STORAGE_PATH);
+    if storage_path.exists() {
+        return MmapPayloadStorage::open(&storage_path);
+    }
+
+    let tmp_path = segment_path.join(format!("{PAYLOAD_STORAGE_PATH}.tmp"));
+    if tmp_path.exists() {
+        std::fs::remove_dir_all(&tmp_path)?;
+    }
+
+    let mut old_storage = OnDiskPayloadStorage::open(database)?;
+    let mut new_storage = MmapPayloadStorage::open(&tmp_path)?;
+    let mut migrated = 0;
+    old_storage.iter(|point_id, payload| {
+        check_process_stopped(stopped)?;
+        new_storage.assign_all(point_id, payload)?;
+        migrated += 1;
+        Ok(true)
+    })?;
+    new_storage.flusher()()?;
+    drop(new_storage);
+
+    std::fs::rename(&tmp_path, &storage_path)?;
+    // Payloads are safely persisted in the new storage, RocksDB copy is no longer needed
+    old_storage.wipe()?;
+    if migrated > 0 {
+        info!(
+            "Migrated {migrated} payloads of segment {} to mmap storage",
+            segment_path.display(),
+        );
+    }
+
+    MmapPayloadStorage::open(&storage_path)
+}
+
+/// Check that all components of a flat segment can be stored without RocksDB.
+pub fn check_flat_segment_config(config: &SegmentConfig) -> OperationResult<()> {
+    let error = |description: String| {
+        Err(OperationError::ValidationError {
+            description: format!("Flat segment format is not supported: {description}"),
+        })
+    };
+
+    if config.is_appendable() {
+        return error("segment is appendable".to_string());
+    }
+    if config.payload_storage_type != PayloadStorageType::Mmap {
+        return error(format!(
+            "payload storage {:?} requires RocksDB",
+            config.payload_storage_type,
+        ));
+    }
+    for (vector_name, vector_config) in &config.vector_data {
+        if vector_config.storage_type == VectorStorageType::Memory {
+            return error(format!("vector {vector_name} uses in-memory storage"));
+        }
+    }
+    for (vector_name, sparse_vector_config) in &config.sparse_vector_data {
+        if sparse_vector_config.storage_type != SparseVectorStorageType::Mmap {
+            return error(format!(
+                "sparse vector {vector_name} storage {:?} requires RocksDB",
+                sparse_vector_config.storage_type,
+            ));
+        }
+    }
+    Ok(())
+}
+
 pub fn get_vector_index_path(segment_path: &Path, vector_name: &str) -> PathBuf {
     segment_path.join(get_vector_name_with_prefix(VECTOR_INDEX_PATH, vector_name))
 }
@@ -87,38 +173,56 @@ fn create_segment(
     config: &SegmentConfig,
     stopped: &AtomicBool,
 ) -> OperationResult<Segment> {
-    let vector_db_names: Vec<String> = config
-        .vector_data
-        .keys()
-        .map(|vector_name| get_vector_name_with_prefix(DB_VECTOR_CF, vector_name))
-        .chain(
-            config
-                .sparse_vector_data
-                .keys()
-                .map(|vector_name| get_vector_name_with_prefix(DB_VECTOR_CF, vector_name)),
-        )
-        .collect();
-    let database = open_db(segment_path, &vector_db_names)
-        .map_err(|err| OperationError::service_error(format!("RocksDB open error: {err}")))?;
+    let database = if config.storage_format.is_flat() {
+        check_flat_segment_config(config)?;
+        None
+    } else {
+        let vector_db_names: Vec<String> = config
+            .vector_data
+            .keys()
+            .map(|vector_name| get_vector_name_with_prefix(DB_VECTOR_CF, vector_name))
+            .chain(
+                config
+                    .sparse_vector_data
+                    .keys()
+                    .map(|vector_name| get_vector_name_with_prefix(DB_VECTOR_CF, vector_name)),
+            )
+            .collect();
+        let database = open_db(segment_path, &vector_db_names)
+            .map_err(|err| OperationError::service_error(format!("RocksDB open error: {err}")))?;
+        Some(database)
+    };
+
+    // Flat segments are checked to not use any RocksDB based component
+    let require_database = || {
+        database.clone().ok_or_else(|| {
+            OperationError::service_error(format!(
+                "Segment {} is stored in flat format, RocksDB is not available",
+                segment_path.display(),
+            ))
+        })
+    };
 
     let payload_storage = match config.payload_storage_type {
-        PayloadStorageType::InMemory => sp(SimplePayloadStorage::open(database.clone())?.into()),
-        PayloadStorageType::OnDisk => sp(OnDiskPayloadStorage::open(database.clone())?.into()),
+        PayloadStorageType::InMemory => sp(SimplePayloadStorage::open(require_database()?)?.into()),
+        PayloadStorageType::OnDisk => sp(OnDiskPayloadStorage::open(require_database()?)?.into()),
+        PayloadStorageType::Mmap => match &database {
+            Some(database) => {
+                sp(open_mmap_payload_storage(segment_path, database.clone(), stopped)?.into())
+            }
+            None => sp(MmapPayloadStorage::open(&segment_path.join(PAYLOAD_STORAGE_PATH))?.into()),
+        },
     };
 
-    let id_tracker = sp(SimpleIdTracker::open(database.clone())?);
+    // Immutable id tracker is only created for segments produced by optimizers
+    let id_tracker_path = segment_path.join(ID_TRACKER_PATH);
+    let id_tracker: Arc<AtomicRefCell<IdTrackerSS>> = if id_tracker_path.exists() {
+        sp(ImmutableIdTracker::open(&id_tracker_path)?)
+    } else {
+        sp(SimpleIdTracker::open(require_database()?)?)
+    };
 
-    let appendable_flag = config
-        .vector_data
-        .values()
-        .map(|vector_config| vector_config.is_appendable())
-        .chain(
-            config
-                .sparse_vector_data
-                .values()
-                .map(|sparse_vector_config| sparse_vector_config.index.index_type.is_appendable()),
-        )
-        .all(|v| v);
+    let appendable_flag = config.is_appendable();
 
     let payload_index_path = segment_path.join(PAYLOAD_INDEX_PATH);
     let payload_index: Arc<AtomicRefCell<StructPayloadIndex>> = sp(StructPayloadIndex::open(
@@ -142,7 +246,7 @@ fn create_segment(
                 if let Some(multi_vec_config) = &vector_config.multivec_config {
                     match storage_element_type {
                         VectorStorageDatatype::Float32 => open_simple_multi_dense_vector_storage(
-                            database.clone(),
+                            require_database()?,
                             &db_column_name,
                             vector_config.size,
                             vector_config.distance,
@@ -151,7 +255,7 @@ fn create_segment(
                         )?,
                         VectorStorageDatatype::Uint8 => {
                             open_simple_multi_dense_vector_storage_byte(
-                                database.clone(),
+                                require_database()?,
                                 &db_column_name,
                                 vector_config.size,
                                 vector_config.distance,
@@ -161,7 +265,7 @@ fn create_segment(
                         }
                         VectorStorageDatatype::Float16 => {
                             open_simple_multi_dense_vector_storage_half(
-                                database.clone(),
+                                require_database()?,
                                 &db_column_name,
                                 vector_config.size,
                                 vector_config.distance,
@@ -173,21 +277,21 @@ fn create_segment(
                 } else {
                     match storage_element_type {
                         VectorStorageDatatype::Float32 => open_simple_dense_vector_storage(
-                            database.clone(),
+                            require_database()?,
                             &db_column_name,
                             vector_config.size,
                             vector_config.distance,
                             stopped,
                         )?,
                         VectorStorageDatatype::Uint8 => open_simple_dense_byte_vector_storage(
-                            database.clone(),
+                            require_database()?,
                             &db_column_name,
                             vector_config.size,
                             vector_config.distance,
                             stopped,
                         )?,
                         VectorStorageDatatype::Float16 => open_simple_dense_half_vector_storage(
-                            database.clone(),
+                            require_database()?,
                             &db_column_name,
                             vector_config.size,
                             vector_config.distance,
@@ -299,6 +403,17 @@ fn create_segment(
             }
         };
 
+        let centroids_config = vector_config
+            .multivec_config
+            .and_then(|multivec_config| multivec_config.centroids);
+        if let Some(centroids_config) = centroids_config {
+            if MultiVectorCentroids::exists(&vector_storage_path) {
+                vector_storage
+                    .borrow_mut()
+                    .load_multi_vector_centroids(centroids_config, &vector_storage_path)?;
+            }
+        }
+
         // Warn when number of points between ID tracker and storage differs
         let point_count = id_tracker.borrow().total_point_count();
         let vector_count = vector_storage.borrow().total_vector_count();
@@ -365,9 +480,15 @@ fn create_segment(
         let vector_storage_path = get_vector_storage_path(segment_path, vector_name);
         let vector_index_path = get_vector_index_path(segment_path, vector_name);
 
-        let db_column_name = get_vector_name_with_prefix(DB_VECTOR_CF, vector_name);
-        let vector_storage =
-            open_simple_sparse_vector_storage(database.clone(), &db_column_name, stopped)?;
+        let vector_storage = match sparse_vector_config.storage_type {
+            SparseVectorStorageType::OnDisk => {
+                let db_column_name = get_vector_name_with_prefix(DB_VECTOR_CF, vector_name);
+                open_simple_sparse_vector_storage(require_database()?, &db_column_name, stopped)?
+            }
+            SparseVectorStorageType::Mmap => {
+                open_appendable_memmap_sparse_vector_storage(&vector_storage_path, stopped)?
+            }
+        };
 
         // Warn when number of points between ID tracker and storage differs
         let point_count = id_tracker.borrow().total_point_count();
@@ -35,6 +35,7 @@ pub fn build_simple_segment(
             )]),
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            storage_format: Default::default(),
         },
         true,
     )
@@ -78,6 +79,7 @@ pub fn build_multivec_segment(
             vector_data: vectors_config,
             sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            storage_format: Default::default(),
         },
         true,
     )
@@ -125,6 +125,7 @@ impl Anonymize for SegmentConfig {
             vector_data: self.vector_data.anonymize(),
             sparse_vector_data: self.sparse_vector_data.anonymize(),
             payload_storage_type: self.payload_storage_type,
+            storage_format: self.storage_format,
         }
     }
 }
@@ -147,6 +148,7 @@ impl Anonymize for SparseVectorDataConfig {
     fn anonymize(&self) -> Self {
         SparseVectorDataConfig {
             index: self.index.anonymize(),
+            storage_type: self.storage_type,
         }
     }
 }
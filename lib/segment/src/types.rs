@@ -679,11 +679,36 @@ pub enum PayloadStorageType {
     InMemory,
     // Store payload on disk only, read each time it is requested
     OnDisk,
+    // Store payload in append-only memory mapped pages, read each time it is requested
+    Mmap,
 }
 
 impl PayloadStorageType {
     pub fn is_on_disk(&self) -> bool {
-        matches!(self, PayloadStorageType::OnDisk)
+        matches!(self, PayloadStorageType::OnDisk | PayloadStorageType::Mmap)
+    }
+}
+
+/// Defines how segment components are persisted
+#[derive(Default, Debug, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentStorageFormat {
+    /// Id mapping, payloads and in-memory vectors are stored in RocksDB
+    #[default]
+    RocksDb,
+    /// All components are stored in flat files, segment doesn't open RocksDB.
+    /// Only available for non-appendable segments with mmap-based storages and without payload
+    /// field indexes, which are stored in RocksDB.
+    Flat,
+}
+
+impl SegmentStorageFormat {
+    pub fn is_flat(&self) -> bool {
+        matches!(self, SegmentStorageFormat::Flat)
+    }
+
+    pub fn is_rocks_db(&self) -> bool {
+        matches!(self, SegmentStorageFormat::RocksDb)
     }
 }
 
@@ -697,6 +722,10 @@ pub struct SegmentConfig {
     pub sparse_vector_data: HashMap<String, SparseVectorDataConfig>,
     /// Defines payload storage type
     pub payload_storage_type: PayloadStorageType,
+    /// Defines how segment components are persisted
+    #[serde(default)]
+    #[serde(skip_serializing_if = "SegmentStorageFormat::is_rocks_db")]
+    pub storage_format: SegmentStorageFormat,
 }
 
 impl SegmentConfig {
@@ -747,6 +776,23 @@ impl SegmentConfig {
                 .all(|config| config.is_indexed())
     }
 
+    /// Check if it is possible to insert new points into segment with this config
+    ///
+    /// Requires all vector indexes and storages to support appending.
+    pub fn is_appendable(&self) -> bool {
+        self.vector_data
+            .values()
+            .map(|vector_config| vector_config.is_appendable())
+            .chain(
+                self.sparse_vector_data
+                    .values()
+                    .map(|sparse_vector_config| {
+                        sparse_vector_config.index.index_type.is_appendable()
+                    }),
+            )
+            .all(|v| v)
+    }
+
     /// Check if any vector storage is on-disk
     pub fn is_any_on_disk(&self) -> bool {
         self.vector_data
@@ -790,11 +836,62 @@ pub enum VectorStorageDatatype {
     Uint8,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Eq, PartialEq, Copy, Clone, Hash)]
+#[derive(
+    Debug, Default, Deserialize, Serialize, JsonSchema, Validate, Eq, PartialEq, Copy, Clone, Hash,
+)]
 #[serde(rename_all = "snake_case")]
 pub struct MultiVectorConfig {
     /// How to compare multivector points
     pub comparator: MultiVectorComparator,
+    /// Pool similar vectors of each multivector together on ingestion.
+    /// If none - all vectors are stored as is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate]
+    pub pooling: Option<MultiVectorPoolingConfig>,
+    /// Build centroid index for optimized segments, used to select candidates before exact comparison.
+    /// If none - all points are compared exactly.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate]
+    pub centroids: Option<MultiVectorCentroidsConfig>,
+}
+
+/// Hierarchical pooling of multivectors.
+///
+/// The most similar groups of vectors of a multivector, by average similarity, are repeatedly
+/// merged, until at most `max_vectors` groups remain. Each group is stored as the mean of its vectors.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Eq, PartialEq, Copy, Clone, Hash)]
+#[serde(rename_all = "snake_case")]
+pub struct MultiVectorPoolingConfig {
+    /// Maximal number of vectors to keep per multivector
+    #[validate(range(min = 1))]
+    pub max_vectors: usize,
+}
+
+/// Centroid-based candidate index for multivectors.
+///
+/// All vectors are clustered around centroids, and each stored vector is represented by its nearest centroid.
+/// Search estimates scores with centroids first and compares only the best candidates exactly.
+/// Centroid codes are kept on disk if the vector storage is on disk.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Eq, PartialEq, Copy, Clone, Hash)]
+#[serde(rename_all = "snake_case")]
+pub struct MultiVectorCentroidsConfig {
+    /// Number of centroids to cluster vectors around
+    #[validate(range(min = 1, max = 65536))]
+    pub num_centroids: usize,
+    /// Number of candidates compared exactly, as a multiple of the requested limit.
+    /// Default: 8
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub candidates_factor: Option<usize>,
+}
+
+impl MultiVectorCentroidsConfig {
+    pub const DEFAULT_CANDIDATES_FACTOR: usize = 8;
+
+    pub fn candidates_factor(&self) -> usize {
+        self.candidates_factor
+            .unwrap_or(Self::DEFAULT_CANDIDATES_FACTOR)
+    }
 }
 
 #[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Eq, PartialEq, Copy, Clone, Hash)]
@@ -854,12 +951,28 @@ impl VectorDataConfig {
     }
 }
 
+/// Storage types for sparse vectors
+#[derive(Default, Debug, Deserialize, Serialize, JsonSchema, Eq, PartialEq, Copy, Clone, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SparseVectorStorageType {
+    /// Storage on disk in RocksDB
+    #[default]
+    OnDisk,
+    /// Storage in chunked mmap files, appendable
+    ///
+    /// Does not share I/O with payload storage and avoids RocksDB compactions.
+    Mmap,
+}
+
 /// Config of single sparse vector data storage
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Validate)]
 #[serde(rename_all = "snake_case")]
 pub struct SparseVectorDataConfig {
     /// Sparse inverted index config
     pub index: SparseIndexConfig,
+    /// Type of storage this sparse vector uses
+    #[serde(default)]
+    pub storage_type: SparseVectorStorageType,
 }
 
 impl SparseVectorDataConfig {
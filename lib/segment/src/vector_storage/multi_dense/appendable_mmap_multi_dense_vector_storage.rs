@@ -18,6 +18,7 @@ use crate::data_types::vectors::{
 use crate::types::{Distance, MultiVectorConfig, VectorStorageDatatype};
 use crate::vector_storage::chunked_mmap_vectors::ChunkedMmapVectors;
 use crate::vector_storage::dense::dynamic_mmap_flags::DynamicMmapFlags;
+use crate::vector_storage::multi_dense::multi_vector_centroids::MultiVectorCentroids;
 use crate::vector_storage::{MultiVectorStorage, VectorStorage, VectorStorageEnum};
 
 const VECTORS_DIR_PATH: &str = "vectors";
@@ -38,6 +39,7 @@ pub struct AppendableMmapMultiDenseVectorStorage<T: PrimitiveVectorElement> {
     distance: Distance,
     multi_vector_config: MultiVectorConfig,
     deleted_count: usize,
+    centroids: Option<MultiVectorCentroids>,
 }
 
 pub fn open_appendable_memmap_multi_vector_storage(
@@ -111,6 +113,7 @@ pub fn open_appendable_memmap_multi_vector_storage_impl<T: PrimitiveVectorElemen
         distance,
         multi_vector_config,
         deleted_count,
+        centroids: None,
     })
 }
 
@@ -158,6 +161,14 @@ impl<T: PrimitiveVectorElement> MultiVectorStorage<T> for AppendableMmapMultiDen
     fn multi_vector_config(&self) -> &MultiVectorConfig {
         &self.multi_vector_config
     }
+
+    fn centroids(&self) -> Option<&MultiVectorCentroids> {
+        self.centroids.as_ref()
+    }
+
+    fn set_centroids(&mut self, centroids: Option<MultiVectorCentroids>) {
+        self.centroids = centroids;
+    }
 }
 
 impl<T: PrimitiveVectorElement> VectorStorage for AppendableMmapMultiDenseVectorStorage<T> {
@@ -231,6 +242,10 @@ impl<T: PrimitiveVectorElement> VectorStorage for AppendableMmapMultiDenseVector
         self.offsets.insert(key as usize, &[offset])?;
         self.set_deleted(key, false)?;
 
+        if let Some(centroids) = &mut self.centroids {
+            centroids.insert(key, multi_vector);
+        }
+
         Ok(())
     }
 
@@ -273,6 +288,9 @@ impl<T: PrimitiveVectorElement> VectorStorage for AppendableMmapMultiDenseVector
         let mut files = self.vectors.files();
         files.extend(self.offsets.files());
         files.extend(self.deleted.files());
+        if let Some(centroids) = &self.centroids {
+            files.extend(centroids.files());
+        }
         files
     }
 
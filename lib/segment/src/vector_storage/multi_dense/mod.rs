@@ -1,2 +1,4 @@
 pub mod appendable_mmap_multi_dense_vector_storage;
+pub mod multi_vector_centroids;
+pub mod multi_vector_pooling;
 pub mod simple_multi_dense_vector_storage;
@@ -0,0 +1,416 @@
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+
+use common::types::{PointOffsetType, ScoreType};
+use io::file_operations::{atomic_save_bin, read_bin};
+use memmap2::Mmap;
+use memory::mmap_ops::{open_read_mmap, transmute_from_u8_to_slice, transmute_to_u8_slice};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::common::operation_error::{check_process_stopped, OperationResult};
+use crate::data_types::primitive::PrimitiveVectorElement;
+use crate::data_types::vectors::{DenseVector, TypedMultiDenseVectorRef, VectorElementType};
+use crate::spaces::metric::Metric;
+use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric, ManhattanMetric};
+use crate::types::{Distance, MultiVectorCentroidsConfig};
+use crate::vector_storage::quantized::quantized_multivector_storage::MultivectorOffset;
+use crate::vector_storage::MultiVectorStorage;
+
+pub const CENTROIDS_FILE: &str = "centroids.bin";
+pub const CENTROID_CODES_FILE: &str = "centroid_codes.bin";
+pub const CENTROID_OFFSETS_FILE: &str = "centroid_offsets.bin";
+
+/// Maximal number of vectors used to train centroids
+const MAX_TRAINING_VECTORS: usize = 1 << 16;
+const KMEANS_ITERATIONS: usize = 10;
+/// Number of points encoded at once
+const ENCODING_BATCH_SIZE: usize = 1024;
+
+/// Trained centroids, persisted in [`CENTROIDS_FILE`]
+#[derive(Serialize, Deserialize)]
+struct CentroidsMeta {
+    config: MultiVectorCentroidsConfig,
+    distance: Distance,
+    dim: usize,
+    /// Flattened centroid vectors
+    centroids: Vec<VectorElementType>,
+}
+
+/// Ids of nearest centroids of all vectors, flattened, with the range of codes of each point
+///
+/// Kept in RAM or memory mapped, following the `on_disk` setting of the vector storage.
+enum CentroidCodes {
+    Ram {
+        codes: Vec<u16>,
+        offsets: Vec<MultivectorOffset>,
+    },
+    Mmap {
+        codes: Mmap,
+        offsets: Mmap,
+    },
+}
+
+impl CentroidCodes {
+    fn load(vector_storage_path: &Path, on_disk: bool) -> OperationResult<Self> {
+        let codes = open_read_mmap(&vector_storage_path.join(CENTROID_CODES_FILE))?;
+        let offsets = open_read_mmap(&vector_storage_path.join(CENTROID_OFFSETS_FILE))?;
+        let codes = Self::Mmap { codes, offsets };
+        Ok(if on_disk { codes } else { codes.to_ram() })
+    }
+
+    fn save(&self, vector_storage_path: &Path) -> OperationResult<()> {
+        save_slice(&vector_storage_path.join(CENTROID_CODES_FILE), self.codes())?;
+        save_slice(
+            &vector_storage_path.join(CENTROID_OFFSETS_FILE),
+            self.offsets(),
+        )?;
+        Ok(())
+    }
+
+    fn to_ram(&self) -> Self {
+        Self::Ram {
+            codes: self.codes().to_vec(),
+            offsets: self.offsets().to_vec(),
+        }
+    }
+
+    fn codes(&self) -> &[u16] {
+        match self {
+            Self::Ram { codes, .. } => codes,
+            Self::Mmap { codes, .. } => transmute_from_u8_to_slice(codes),
+        }
+    }
+
+    fn offsets(&self) -> &[MultivectorOffset] {
+        match self {
+            Self::Ram { offsets, .. } => offsets,
+            Self::Mmap { offsets, .. } => transmute_from_u8_to_slice(offsets),
+        }
+    }
+
+    fn get(&self, key: PointOffsetType) -> Option<&[u16]> {
+        let offset = self.offsets().get(key as usize)?;
+        let start = offset.offset as usize;
+        self.codes().get(start..start + offset.count as usize)
+    }
+}
+
+/// Centroid-based candidate index for multivectors, inspired by PLAID.
+///
+/// Vectors of all points are clustered with k-means, and each stored vector is replaced by the id
+/// of its nearest centroid. Score of a point is then estimated by comparing query vectors with
+/// centroids only, which is much cheaper than exact comparison with stored vectors.
+pub struct MultiVectorCentroids {
+    meta: CentroidsMeta,
+    codes: CentroidCodes,
+    /// Path of the vector storage, containing index files
+    path: PathBuf,
+}
+
+impl MultiVectorCentroids {
+    pub fn get_path(vector_storage_path: &Path) -> PathBuf {
+        vector_storage_path.join(CENTROIDS_FILE)
+    }
+
+    pub fn exists(vector_storage_path: &Path) -> bool {
+        Self::get_path(vector_storage_path).exists()
+    }
+
+    /// Train centroids on vectors of the given storage, encode all points and persist the index.
+    pub fn create<T: PrimitiveVectorElement>(
+        vector_storage: &impl MultiVectorStorage<T>,
+        config: MultiVectorCentroidsConfig,
+        vector_storage_path: &Path,
+        stopped: &AtomicBool,
+    ) -> OperationResult<Self> {
+        let distance = vector_storage.distance();
+        let dim = vector_storage.vector_dim();
+
+        let total_vectors = vector_storage.iterate_inner_vectors().count();
+        let step = total_vectors.div_ceil(MAX_TRAINING_VECTORS).max(1);
+        let training_vectors: Vec<DenseVector> = vector_storage
+            .iterate_inner_vectors()
+            .step_by(step)
+            .map(|vector| preprocess(distance, to_float(vector)))
+            .collect();
+
+        let centroids = train_centroids(
+            distance,
+            dim,
+            &training_vectors,
+            config.num_centroids,
+            stopped,
+        )?;
+
+        let meta = CentroidsMeta {
+            config,
+            distance,
+            dim,
+            centroids,
+        };
+
+        let point_count = vector_storage.total_vector_count() as PointOffsetType;
+        let mut codes = Vec::with_capacity(total_vectors);
+        let mut offsets = Vec::with_capacity(point_count as usize);
+        let mut batch_start = 0;
+        while batch_start < point_count {
+            check_process_stopped(stopped)?;
+            let batch_end = point_count.min(batch_start + ENCODING_BATCH_SIZE as PointOffsetType);
+            let batch: Vec<Vec<DenseVector>> = (batch_start..batch_end)
+                .map(|key| {
+                    vector_storage
+                        .get_multi(key)
+                        .multi_vectors()
+                        .map(|vector| preprocess(distance, to_float(vector)))
+                        .collect()
+                })
+                .collect();
+            let batch_codes: Vec<Vec<u16>> = batch
+                .par_iter()
+                .map(|vectors| meta.encode(vectors))
+                .collect();
+            for point_codes in batch_codes {
+                offsets.push(MultivectorOffset {
+                    offset: codes.len() as PointOffsetType,
+                    count: point_codes.len() as PointOffsetType,
+                });
+                codes.extend(point_codes);
+            }
+            batch_start = batch_end;
+        }
+
+        let codes = CentroidCodes::Ram { codes, offsets };
+        codes.save(vector_storage_path)?;
+        atomic_save_bin(&Self::get_path(vector_storage_path), &meta)?;
+
+        let codes = if vector_storage.is_on_disk() {
+            CentroidCodes::load(vector_storage_path, true)?
+        } else {
+            codes
+        };
+
+        Ok(Self {
+            meta,
+            codes,
+            path: vector_storage_path.to_path_buf(),
+        })
+    }
+
+    /// Load the index, codes are memory mapped if `on_disk` is set
+    pub fn load(vector_storage_path: &Path, on_disk: bool) -> OperationResult<Self> {
+        let meta: CentroidsMeta = read_bin(&Self::get_path(vector_storage_path))?;
+        let codes = CentroidCodes::load(vector_storage_path, on_disk)?;
+        Ok(Self {
+            meta,
+            codes,
+            path: vector_storage_path.to_path_buf(),
+        })
+    }
+
+    pub fn config(&self) -> &MultiVectorCentroidsConfig {
+        &self.meta.config
+    }
+
+    pub fn num_centroids(&self) -> usize {
+        self.meta.centroids.len() / self.meta.dim
+    }
+
+    /// Number of encoded points
+    pub fn point_count(&self) -> usize {
+        self.codes.offsets().len()
+    }
+
+    pub fn files(&self) -> Vec<PathBuf> {
+        vec![
+            Self::get_path(&self.path),
+            self.path.join(CENTROID_CODES_FILE),
+            self.path.join(CENTROID_OFFSETS_FILE),
+        ]
+    }
+
+    /// Encode vectors of a new point with the trained centroids.
+    ///
+    /// Not persisted, index is expected to be created for segments which don't receive new points.
+    pub fn insert<T: PrimitiveVectorElement>(
+        &mut self,
+        key: PointOffsetType,
+        multi_vector: TypedMultiDenseVectorRef<T>,
+    ) {
+        let vectors: Vec<DenseVector> = multi_vector
+            .multi_vectors()
+            .map(|vector| preprocess(self.meta.distance, to_float(vector)))
+            .collect();
+        let point_codes = self.meta.encode(&vectors);
+
+        if let CentroidCodes::Mmap { .. } = self.codes {
+            self.codes = self.codes.to_ram();
+        }
+        let CentroidCodes::Ram { codes, offsets } = &mut self.codes else {
+            unreachable!("centroid codes are loaded into RAM above");
+        };
+
+        // Previous codes of the point are left unused
+        let key = key as usize;
+        if offsets.len() <= key {
+            offsets.resize(key + 1, MultivectorOffset::default());
+        }
+        offsets[key] = MultivectorOffset {
+            offset: codes.len() as PointOffsetType,
+            count: point_codes.len() as PointOffsetType,
+        };
+        codes.extend(point_codes);
+    }
+
+    /// Similarities between each of the query vectors and each centroid
+    pub fn score_table<'a>(
+        &self,
+        query_vectors: impl Iterator<Item = &'a [VectorElementType]>,
+    ) -> Vec<ScoreType> {
+        let CentroidsMeta {
+            distance,
+            dim,
+            centroids,
+            ..
+        } = &self.meta;
+        query_vectors
+            .flat_map(|query_vector| {
+                let query_vector = preprocess(*distance, query_vector.to_vec());
+                centroids
+                    .chunks_exact(*dim)
+                    .map(|centroid| similarity(*distance, &query_vector, centroid))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Estimate MaxSim score of a stored point with scores of its centroids
+    pub fn estimate_score(&self, score_table: &[ScoreType], key: PointOffsetType) -> ScoreType {
+        let Some(codes) = self.codes.get(key) else {
+            return ScoreType::NEG_INFINITY;
+        };
+        score_table
+            .chunks_exact(self.num_centroids())
+            .map(|centroid_scores| {
+                codes
+                    .iter()
+                    .map(|&code| centroid_scores[code as usize])
+                    .fold(ScoreType::NEG_INFINITY, ScoreType::max)
+            })
+            .sum()
+    }
+}
+
+impl CentroidsMeta {
+    fn encode(&self, vectors: &[DenseVector]) -> Vec<u16> {
+        vectors
+            .iter()
+            .map(|vector| nearest_centroid(self.distance, self.dim, &self.centroids, vector) as u16)
+            .collect()
+    }
+}
+
+fn save_slice<T>(path: &Path, data: &[T]) -> OperationResult<()> {
+    let mut file = File::create(path)?;
+    file.write_all(transmute_to_u8_slice(data))?;
+    file.sync_all()?;
+    Ok(())
+}
+
+fn train_centroids(
+    distance: Distance,
+    dim: usize,
+    training_vectors: &[DenseVector],
+    num_centroids: usize,
+    stopped: &AtomicBool,
+) -> OperationResult<Vec<VectorElementType>> {
+    let num_centroids = num_centroids.min(training_vectors.len());
+    if num_centroids == 0 {
+        return Ok(Vec::new());
+    }
+
+    // Deterministic initialization with evenly spread training vectors
+    let mut centroids: Vec<VectorElementType> = (0..num_centroids)
+        .flat_map(|i| training_vectors[i * training_vectors.len() / num_centroids].iter())
+        .copied()
+        .collect();
+
+    for _ in 0..KMEANS_ITERATIONS {
+        check_process_stopped(stopped)?;
+
+        let assignments: Vec<usize> = training_vectors
+            .par_iter()
+            .map(|vector| nearest_centroid(distance, dim, &centroids, vector))
+            .collect();
+
+        let mut sums = vec![0.0; num_centroids * dim];
+        let mut counts = vec![0usize; num_centroids];
+        for (vector, &centroid) in training_vectors.iter().zip(&assignments) {
+            counts[centroid] += 1;
+            for (sum, value) in sums[centroid * dim..(centroid + 1) * dim]
+                .iter_mut()
+                .zip(vector)
+            {
+                *sum += value;
+            }
+        }
+
+        for (centroid, &count) in counts.iter().enumerate() {
+            // Keep previous position of centroids without assigned vectors
+            if count == 0 {
+                continue;
+            }
+            let mean = sums[centroid * dim..(centroid + 1) * dim]
+                .iter()
+                .map(|sum| sum / count as VectorElementType)
+                .collect();
+            centroids[centroid * dim..(centroid + 1) * dim]
+                .copy_from_slice(&preprocess(distance, mean));
+        }
+    }
+
+    Ok(centroids)
+}
+
+fn nearest_centroid(
+    distance: Distance,
+    dim: usize,
+    centroids: &[VectorElementType],
+    vector: &[VectorElementType],
+) -> usize {
+    let mut nearest = 0;
+    let mut best_similarity = ScoreType::NEG_INFINITY;
+    for (i, centroid) in centroids.chunks_exact(dim).enumerate() {
+        let similarity = similarity(distance, vector, centroid);
+        if similarity > best_similarity {
+            best_similarity = similarity;
+            nearest = i;
+        }
+    }
+    nearest
+}
+
+fn to_float<T: PrimitiveVectorElement>(vector: &[T]) -> DenseVector {
+    T::slice_to_float_cow(Cow::Borrowed(vector)).into_owned()
+}
+
+fn preprocess(distance: Distance, vector: DenseVector) -> DenseVector {
+    match distance {
+        Distance::Cosine => <CosineMetric as Metric<VectorElementType>>::preprocess(vector),
+        Distance::Euclid => <EuclidMetric as Metric<VectorElementType>>::preprocess(vector),
+        Distance::Dot => <DotProductMetric as Metric<VectorElementType>>::preprocess(vector),
+        Distance::Manhattan => <ManhattanMetric as Metric<VectorElementType>>::preprocess(vector),
+    }
+}
+
+fn similarity(distance: Distance, a: &[VectorElementType], b: &[VectorElementType]) -> ScoreType {
+    match distance {
+        Distance::Cosine => <CosineMetric as Metric<VectorElementType>>::similarity(a, b),
+        Distance::Euclid => <EuclidMetric as Metric<VectorElementType>>::similarity(a, b),
+        Distance::Dot => <DotProductMetric as Metric<VectorElementType>>::similarity(a, b),
+        Distance::Manhattan => <ManhattanMetric as Metric<VectorElementType>>::similarity(a, b),
+    }
+}
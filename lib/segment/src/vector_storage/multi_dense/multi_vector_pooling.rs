@@ -0,0 +1,188 @@
+use common::types::ScoreType;
+
+use crate::data_types::vectors::{MultiDenseVector, VectorElementType};
+
+/// Reduce the number of vectors in a multivector down to `max_vectors`.
+///
+/// Hierarchical agglomerative clustering with average linkage of cosine similarities: the two most
+/// similar clusters are merged, until at most `max_vectors` clusters remain. Each cluster is then
+/// replaced by the mean of its vectors. Order of the remaining vectors follows the order of the
+/// original ones.
+///
+/// Average linkage is reducible, so the full dendrogram is built with the nearest-neighbor chain
+/// algorithm, and cut by applying the most similar merges. It takes O(n²) time and memory for `n`
+/// vectors, which keeps pooling cheap enough for upserts.
+pub fn pool_multi_dense_vector(
+    multi_vector: &MultiDenseVector,
+    max_vectors: usize,
+) -> MultiDenseVector {
+    let count = multi_vector.len();
+    let max_vectors = max_vectors.max(1);
+    if count <= max_vectors {
+        return multi_vector.clone();
+    }
+
+    let vectors: Vec<&[VectorElementType]> = multi_vector.multi_vectors().collect();
+
+    // Similarity matrix between clusters, updated with merged clusters
+    let mut similarities = vec![0.0; count * count];
+    for i in 0..count {
+        for j in i + 1..count {
+            let similarity = cosine_similarity(vectors[i], vectors[j]);
+            similarities[i * count + j] = similarity;
+            similarities[j * count + i] = similarity;
+        }
+    }
+
+    let mut sizes = vec![1usize; count];
+    let mut active = vec![true; count];
+    // Merges of the dendrogram: merged clusters, represented by one of their vectors, and similarity
+    let mut merges: Vec<(usize, usize, ScoreType)> = Vec::with_capacity(count - 1);
+    let mut chain: Vec<usize> = Vec::with_capacity(count);
+
+    while merges.len() < count - 1 {
+        let Some(&last) = chain.last() else {
+            chain.extend((0..count).find(|&i| active[i]));
+            continue;
+        };
+
+        // Prefer the previous cluster in the chain on ties, so that the chain always terminates
+        let previous = chain.len().checked_sub(2).map(|i| chain[i]);
+        let mut nearest = previous;
+        let mut best = previous.map_or(ScoreType::NEG_INFINITY, |i| similarities[last * count + i]);
+        for other in (0..count).filter(|&other| active[other] && other != last) {
+            let similarity = similarities[last * count + other];
+            if similarity > best {
+                best = similarity;
+                nearest = Some(other);
+            }
+        }
+        let Some(nearest) = nearest else {
+            break;
+        };
+
+        if Some(nearest) != previous {
+            chain.push(nearest);
+            continue;
+        }
+
+        // Reciprocal nearest neighbors, merge them
+        chain.truncate(chain.len() - 2);
+        let (target, merged) = (last.min(nearest), last.max(nearest));
+        let (target_size, merged_size) = (sizes[target], sizes[merged]);
+        for other in (0..count).filter(|&other| active[other] && other != target) {
+            let similarity = (similarities[target * count + other] * target_size as ScoreType
+                + similarities[merged * count + other] * merged_size as ScoreType)
+                / (target_size + merged_size) as ScoreType;
+            similarities[target * count + other] = similarity;
+            similarities[other * count + target] = similarity;
+        }
+        sizes[target] += merged_size;
+        active[merged] = false;
+        merges.push((target, merged, best));
+    }
+
+    // Cut the dendrogram, applying the most similar merges. Sort is stable, so on ties a merge is
+    // applied before merges of the resulting cluster.
+    merges.sort_by(|a, b| b.2.total_cmp(&a.2));
+    let mut parents: Vec<usize> = (0..count).collect();
+    for &(a, b, _) in merges.iter().take(count - max_vectors) {
+        let (root_a, root_b) = (find_root(&mut parents, a), find_root(&mut parents, b));
+        // Root is the first vector of the cluster, to keep the original order
+        parents[root_a.max(root_b)] = root_a.min(root_b);
+    }
+
+    let dim = multi_vector.dim;
+    let mut sums = vec![0.0; count * dim];
+    let mut cluster_sizes = vec![0usize; count];
+    for (i, vector) in vectors.iter().enumerate() {
+        let root = find_root(&mut parents, i);
+        cluster_sizes[root] += 1;
+        for (sum, value) in sums[root * dim..(root + 1) * dim].iter_mut().zip(*vector) {
+            *sum += value;
+        }
+    }
+
+    let flattened_vectors = (0..count)
+        .filter(|&i| cluster_sizes[i] > 0)
+        .flat_map(|i| {
+            let size = cluster_sizes[i] as VectorElementType;
+            sums[i * dim..(i + 1) * dim]
+                .iter()
+                .map(move |value| value / size)
+        })
+        .collect();
+    MultiDenseVector::new(flattened_vectors, dim)
+}
+
+fn find_root(parents: &mut [usize], mut node: usize) -> usize {
+    while parents[node] != node {
+        parents[node] = parents[parents[node]];
+        node = parents[node];
+    }
+    node
+}
+
+fn cosine_similarity(a: &[VectorElementType], b: &[VectorElementType]) -> ScoreType {
+    let (mut dot, mut norm_a, mut norm_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pooling_merges_similar_vectors() {
+        let multi_vector = MultiDenseVector::new_unchecked(vec![
+            vec![1.0, 0.0],
+            vec![0.0, 1.0],
+            vec![0.9, 0.1],
+            vec![0.1, 0.9],
+            vec![-1.0, 0.0],
+        ]);
+
+        let pooled = pool_multi_dense_vector(&multi_vector, 3);
+        assert_eq!(pooled.len(), 3);
+
+        let expected = [[0.95, 0.05], [0.05, 0.95], [-1.0, 0.0]];
+        for (pooled, expected) in pooled.multi_vectors().zip(expected) {
+            for (value, expected) in pooled.iter().zip(expected) {
+                assert!(
+                    (value - expected).abs() < 1e-6,
+                    "{pooled:?} != {expected:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_pooling_long_multivector() {
+        let vectors = (0..300)
+            .map(|i| {
+                let angle = i as VectorElementType * 0.05;
+                vec![angle.cos(), angle.sin(), (i % 7) as VectorElementType * 0.1]
+            })
+            .collect();
+        let multi_vector = MultiDenseVector::new_unchecked(vectors);
+
+        let pooled = pool_multi_dense_vector(&multi_vector, 8);
+        assert_eq!(pooled.len(), 8);
+        assert_eq!(pooled.dim, 3);
+    }
+
+    #[test]
+    fn test_pooling_keeps_small_multivectors() {
+        let multi_vector = MultiDenseVector::new_unchecked(vec![vec![1.0, 2.0], vec![3.0, 4.0]]);
+        assert_eq!(pool_multi_dense_vector(&multi_vector, 2), multi_vector);
+        assert_eq!(pool_multi_dense_vector(&multi_vector, 1).len(), 1);
+    }
+}
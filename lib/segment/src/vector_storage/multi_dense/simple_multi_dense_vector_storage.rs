@@ -20,6 +20,7 @@ use crate::types::{Distance, MultiVectorConfig, VectorStorageDatatype};
 use crate::vector_storage::bitvec::bitvec_set_deleted;
 use crate::vector_storage::chunked_vectors::ChunkedVectors;
 use crate::vector_storage::common::StoredRecord;
+use crate::vector_storage::multi_dense::multi_vector_centroids::MultiVectorCentroids;
 use crate::vector_storage::{MultiVectorStorage, VectorStorage, VectorStorageEnum};
 
 type StoredMultiDenseVector<T> = StoredRecord<TypedMultiDenseVector<T>>;
@@ -46,6 +47,7 @@ pub struct SimpleMultiDenseVectorStorage<T: PrimitiveVectorElement> {
     deleted: BitVec,
     /// Current number of deleted vectors.
     deleted_count: usize,
+    centroids: Option<MultiVectorCentroids>,
 }
 
 pub fn open_simple_multi_dense_vector_storage(
@@ -172,6 +174,7 @@ fn open_simple_multi_dense_vector_storage_impl<T: PrimitiveVectorElement>(
         },
         deleted,
         deleted_count,
+        centroids: None,
     })
 }
 
@@ -262,6 +265,10 @@ impl<T: PrimitiveVectorElement> SimpleMultiDenseVectorStorage<T> {
 
         self.set_deleted(key, is_deleted);
         self.update_stored(key, is_deleted, Some(multi_vector))?;
+
+        if let Some(centroids) = &mut self.centroids {
+            centroids.insert(key, multi_vector);
+        }
         Ok(())
     }
 }
@@ -285,6 +292,14 @@ impl<T: PrimitiveVectorElement> MultiVectorStorage<T> for SimpleMultiDenseVector
     fn multi_vector_config(&self) -> &MultiVectorConfig {
         &self.multi_vector_config
     }
+
+    fn centroids(&self) -> Option<&MultiVectorCentroids> {
+        self.centroids.as_ref()
+    }
+
+    fn set_centroids(&mut self, centroids: Option<MultiVectorCentroids>) {
+        self.centroids = centroids;
+    }
 }
 
 impl<T: PrimitiveVectorElement> VectorStorage for SimpleMultiDenseVectorStorage<T> {
@@ -344,7 +359,10 @@ impl<T: PrimitiveVectorElement> VectorStorage for SimpleMultiDenseVectorStorage<
     }
 
     fn files(&self) -> Vec<std::path::PathBuf> {
-        vec![]
+        self.centroids
+            .as_ref()
+            .map(MultiVectorCentroids::files)
+            .unwrap_or_default()
     }
 
     fn delete_vector(&mut self, key: PointOffsetType) -> OperationResult<bool> {
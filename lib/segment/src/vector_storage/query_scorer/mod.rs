@@ -17,6 +17,17 @@ pub trait QueryScorer<TVector: ?Sized> {
     fn score(&self, v2: &TVector) -> ScoreType;
 
     fn score_internal(&self, point_a: PointOffsetType, point_b: PointOffsetType) -> ScoreType;
+
+    /// Number of candidates to select with `score_stored_estimate` per requested result,
+    /// if the scorer is able to estimate scores cheaper than `score_stored`
+    fn candidates_factor(&self) -> Option<usize> {
+        None
+    }
+
+    /// Estimation of `score_stored`, used to select candidates for exact scoring
+    fn score_stored_estimate(&self, idx: PointOffsetType) -> ScoreType {
+        self.score_stored(idx)
+    }
 }
 
 /// Colbert MaxSim metric, metric for multi-dense vectors
@@ -9,9 +9,16 @@ use crate::data_types::vectors::{
     DenseVector, MultiDenseVector, TypedMultiDenseVector, TypedMultiDenseVectorRef,
 };
 use crate::spaces::metric::Metric;
+use crate::vector_storage::multi_dense::multi_vector_centroids::MultiVectorCentroids;
 use crate::vector_storage::query_scorer::QueryScorer;
 use crate::vector_storage::MultiVectorStorage;
 
+/// Scores of query vectors against centroids of the storage
+struct CentroidScores<'a> {
+    centroids: &'a MultiVectorCentroids,
+    score_table: Vec<ScoreType>,
+}
+
 pub struct MultiMetricQueryScorer<
     'a,
     TElement: PrimitiveVectorElement,
@@ -20,6 +27,7 @@ pub struct MultiMetricQueryScorer<
 > {
     vector_storage: &'a TVectorStorage,
     query: TypedMultiDenseVector<TElement>,
+    centroid_scores: Option<CentroidScores<'a>>,
     metric: PhantomData<TMetric>,
 }
 
@@ -37,9 +45,17 @@ impl<
             .flat_map(|slice| TMetric::preprocess(slice.to_vec()))
             .collect();
         let preprocessed = MultiDenseVector::new(preprocessed, query.dim);
+        let centroid_scores = vector_storage
+            .centroids()
+            .filter(|centroids| centroids.num_centroids() > 0)
+            .map(|centroids| CentroidScores {
+                centroids,
+                score_table: centroids.score_table(preprocessed.multi_vectors()),
+            });
         Self {
             query: TElement::from_float_multivector(CowMultiVector::Owned(preprocessed)).to_owned(),
             vector_storage,
+            centroid_scores,
             metric: PhantomData,
         }
     }
@@ -86,4 +102,19 @@ impl<
         let v2 = self.vector_storage.get_multi(point_b);
         self.score_multi(v1, v2)
     }
+
+    fn candidates_factor(&self) -> Option<usize> {
+        self.centroid_scores
+            .as_ref()
+            .map(|centroid_scores| centroid_scores.centroids.config().candidates_factor())
+    }
+
+    fn score_stored_estimate(&self, idx: PointOffsetType) -> ScoreType {
+        match &self.centroid_scores {
+            Some(centroid_scores) => centroid_scores
+                .centroids
+                .estimate_score(&centroid_scores.score_table, idx),
+            None => self.score_stored(idx),
+        }
+    }
 }
@@ -82,6 +82,36 @@ pub trait RawScorer {
     ) -> Vec<ScoredPointOffset>;
 
     fn peek_top_all(&self, top: usize) -> Vec<ScoredPointOffset>;
+
+    /// Number of candidates to select by estimated scores per requested result,
+    /// if the storage is able to estimate scores cheaper than exact scoring
+    fn candidates_factor(&self) -> Option<usize> {
+        None
+    }
+
+    /// Same as `score_points`, but with estimated scores, see `candidates_factor`
+    fn score_points_estimate(
+        &self,
+        points: &[PointOffsetType],
+        scores: &mut [ScoredPointOffset],
+    ) -> usize {
+        self.score_points(points, scores)
+    }
+
+    /// Same as `score_point`, but with estimated score, see `candidates_factor`
+    fn score_point_estimate(&self, point: PointOffsetType) -> ScoreType {
+        self.score_point(point)
+    }
+
+    /// Same as `peek_top_iter`, but may select candidates by estimated scores first,
+    /// if the storage supports it. Results are approximate, must not be used for exact search.
+    fn peek_top_iter_approximate(
+        &self,
+        points: &mut dyn Iterator<Item = PointOffsetType>,
+        top: usize,
+    ) -> Vec<ScoredPointOffset> {
+        self.peek_top_iter(points, top)
+    }
 }
 
 pub struct RawScorerImpl<'a, TVector: ?Sized, TQueryScorer>
@@ -157,6 +187,9 @@ pub fn new_stoppable_raw_scorer<'a>(
         VectorStorageEnum::SparseSimple(vs) => {
             raw_sparse_scorer_impl(query, vs, point_deleted, is_stopped)
         }
+        VectorStorageEnum::SparseAppendableMemmap(vs) => {
+            raw_sparse_scorer_impl(query, vs.as_ref(), point_deleted, is_stopped)
+        }
         VectorStorageEnum::MultiDenseSimple(vs) => {
             raw_multi_scorer_impl(query, vs, point_deleted, is_stopped)
         }
@@ -815,6 +848,39 @@ fn new_multi_scorer_half_with_metric<
     }
 }
 
+impl<'a, TVector, TQueryScorer> RawScorerImpl<'a, TVector, TQueryScorer>
+where
+    TVector: ?Sized,
+    TQueryScorer: QueryScorer<TVector>,
+{
+    /// Two-stage search: select candidates by estimated scores, then score candidates exactly
+    fn peek_top_candidates(
+        &self,
+        points: &mut dyn Iterator<Item = PointOffsetType>,
+        top: usize,
+        candidates_factor: usize,
+    ) -> Vec<ScoredPointOffset> {
+        let estimations = points
+            .take_while(|_| !self.is_stopped.load(Ordering::Relaxed))
+            .filter(|point_id| self.check_vector(*point_id))
+            .map(|point_id| ScoredPointOffset {
+                idx: point_id,
+                score: self.query_scorer.score_stored_estimate(point_id),
+            });
+        let candidates =
+            peek_top_largest_iterable(estimations, top.saturating_mul(candidates_factor));
+
+        let scores = candidates
+            .into_iter()
+            .take_while(|_| !self.is_stopped.load(Ordering::Relaxed))
+            .map(|candidate| ScoredPointOffset {
+                idx: candidate.idx,
+                score: self.query_scorer.score_stored(candidate.idx),
+            });
+        peek_top_largest_iterable(scores, top)
+    }
+}
+
 impl<'a, TVector, TQueryScorer> RawScorer for RawScorerImpl<'a, TVector, TQueryScorer>
 where
     TVector: ?Sized,
@@ -899,6 +965,51 @@ where
             });
         peek_top_largest_iterable(scores, top)
     }
+
+    fn candidates_factor(&self) -> Option<usize> {
+        self.query_scorer.candidates_factor()
+    }
+
+    fn score_points_estimate(
+        &self,
+        points: &[PointOffsetType],
+        scores: &mut [ScoredPointOffset],
+    ) -> usize {
+        if self.is_stopped.load(Ordering::Relaxed) {
+            return 0;
+        }
+        let mut size: usize = 0;
+        for point_id in points.iter().copied() {
+            if !self.check_vector(point_id) {
+                continue;
+            }
+            scores[size] = ScoredPointOffset {
+                idx: point_id,
+                score: self.query_scorer.score_stored_estimate(point_id),
+            };
+
+            size += 1;
+            if size == scores.len() {
+                return size;
+            }
+        }
+        size
+    }
+
+    fn score_point_estimate(&self, point: PointOffsetType) -> ScoreType {
+        self.query_scorer.score_stored_estimate(point)
+    }
+
+    fn peek_top_iter_approximate(
+        &self,
+        points: &mut dyn Iterator<Item = PointOffsetType>,
+        top: usize,
+    ) -> Vec<ScoredPointOffset> {
+        match self.query_scorer.candidates_factor() {
+            Some(candidates_factor) => self.peek_top_candidates(points, top, candidates_factor),
+            None => self.peek_top_iter(points, top),
+        }
+    }
 }
 
 #[inline]
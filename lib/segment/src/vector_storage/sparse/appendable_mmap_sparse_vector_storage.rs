@@ -0,0 +1,303 @@
+use std::fs::create_dir_all;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use atomic_refcell::AtomicRefCell;
+use bitvec::prelude::BitSlice;
+use common::types::PointOffsetType;
+use sparse::common::sparse_vector::SparseVector;
+use sparse::common::types::{DimId, DimWeight};
+
+use crate::common::operation_error::{check_process_stopped, OperationError, OperationResult};
+use crate::common::Flusher;
+use crate::data_types::named_vectors::CowVector;
+use crate::data_types::vectors::VectorRef;
+use crate::types::{Distance, VectorStorageDatatype};
+use crate::vector_storage::chunked_mmap_vectors::ChunkedMmapVectors;
+use crate::vector_storage::dense::dynamic_mmap_flags::DynamicMmapFlags;
+use crate::vector_storage::simple_sparse_vector_storage::SPARSE_VECTOR_DISTANCE;
+use crate::vector_storage::{SparseVectorStorage, VectorStorage, VectorStorageEnum};
+
+const INDICES_DIR_PATH: &str = "indices";
+const VALUES_DIR_PATH: &str = "values";
+const OFFSETS_DIR_PATH: &str = "offsets";
+const DELETED_DIR_PATH: &str = "deleted";
+
+/// Location of a single variable-length sparse vector record.
+///
+/// `offset` points into both the `indices` and `values` chunks, which are always written together.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct SparseMmapOffset {
+    offset: u64,
+    count: u32,
+    capacity: u32,
+}
+
+/// Appendable sparse vector storage based on chunked mmap files.
+///
+/// Each vector is stored as a pair of `indices` and `values` records of the same length. The
+/// `offsets` file maps internal point ids to the location of these records. Updates are written
+/// in place if the new vector fits into the previously allocated capacity, and appended otherwise.
+pub struct AppendableMmapSparseVectorStorage {
+    indices: ChunkedMmapVectors<DimId>,
+    values: ChunkedMmapVectors<DimWeight>,
+    offsets: ChunkedMmapVectors<SparseMmapOffset>,
+    deleted: DynamicMmapFlags,
+    deleted_count: usize,
+    /// Total number of non-zero elements in all vectors. Used to estimate average vector size.
+    total_sparse_size: usize,
+}
+
+pub fn open_appendable_memmap_sparse_vector_storage(
+    path: &Path,
+    stopped: &AtomicBool,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let storage = open_appendable_memmap_sparse_vector_storage_impl(path, stopped)?;
+
+    Ok(Arc::new(AtomicRefCell::new(
+        VectorStorageEnum::SparseAppendableMemmap(Box::new(storage)),
+    )))
+}
+
+pub fn open_appendable_memmap_sparse_vector_storage_impl(
+    path: &Path,
+    stopped: &AtomicBool,
+) -> OperationResult<AppendableMmapSparseVectorStorage> {
+    create_dir_all(path)?;
+
+    let indices_path = path.join(INDICES_DIR_PATH);
+    let values_path = path.join(VALUES_DIR_PATH);
+    let offsets_path = path.join(OFFSETS_DIR_PATH);
+    let deleted_path = path.join(DELETED_DIR_PATH);
+
+    let indices = ChunkedMmapVectors::open(&indices_path, 1)?;
+    let values = ChunkedMmapVectors::open(&values_path, 1)?;
+    let offsets: ChunkedMmapVectors<SparseMmapOffset> = ChunkedMmapVectors::open(&offsets_path, 1)?;
+
+    let deleted: DynamicMmapFlags = DynamicMmapFlags::open(&deleted_path)?;
+    let deleted_count = deleted.count_flags();
+
+    let mut total_sparse_size = 0;
+    for key in 0..offsets.len() {
+        check_process_stopped(stopped)?;
+        if let Some(offset) = offsets.get(key).and_then(|x| x.first()) {
+            total_sparse_size += offset.count as usize;
+        }
+    }
+
+    Ok(AppendableMmapSparseVectorStorage {
+        indices,
+        values,
+        offsets,
+        deleted,
+        deleted_count,
+        total_sparse_size,
+    })
+}
+
+impl AppendableMmapSparseVectorStorage {
+    /// Set deleted flag for given key. Returns previous deleted state.
+    #[inline]
+    fn set_deleted(&mut self, key: PointOffsetType, deleted: bool) -> OperationResult<bool> {
+        if self.offsets.len() <= key as usize {
+            return Ok(false);
+        }
+
+        if self.deleted.len() <= key as usize {
+            self.deleted.set_len(key as usize + 1)?;
+        }
+        let previous = self.deleted.set(key, deleted);
+        if !previous && deleted {
+            self.deleted_count += 1;
+        } else if previous && !deleted {
+            self.deleted_count -= 1;
+        }
+        Ok(previous)
+    }
+
+    fn get_offset(&self, key: PointOffsetType) -> Option<SparseMmapOffset> {
+        if key as usize >= self.offsets.len() {
+            return None;
+        }
+        self.offsets
+            .get(key as usize)
+            .and_then(|offset| offset.first().copied())
+    }
+
+    /// Estimate average vector size based on total number of non-zero elements in all vectors.
+    pub fn get_average_dimension(&self) -> usize {
+        let total_vector_count = self.total_vector_count();
+        if total_vector_count == 0 {
+            // default dimension to play nice with optimizers
+            1
+        } else {
+            // multiply by 2 to account for indices & values
+            (self.total_sparse_size / total_vector_count) * 2
+        }
+    }
+}
+
+impl SparseVectorStorage for AppendableMmapSparseVectorStorage {
+    fn get_sparse(&self, key: PointOffsetType) -> OperationResult<SparseVector> {
+        let offset = self.get_offset(key).ok_or_else(|| {
+            OperationError::service_error(format!("Sparse vector offset {key} not found"))
+        })?;
+
+        if offset.count == 0 {
+            return Ok(SparseVector::default());
+        }
+
+        let count = offset.count as usize;
+        let indices = self.indices.get_many(offset.offset, count);
+        let values = self.values.get_many(offset.offset, count);
+        match (indices, values) {
+            (Some(indices), Some(values)) => Ok(SparseVector {
+                indices: indices.to_vec(),
+                values: values.to_vec(),
+            }),
+            _ => Err(OperationError::service_error(format!(
+                "Sparse vector {key} not found in mmap storage",
+            ))),
+        }
+    }
+}
+
+impl VectorStorage for AppendableMmapSparseVectorStorage {
+    fn vector_dim(&self) -> usize {
+        // estimate average vector size
+        self.get_average_dimension()
+    }
+
+    fn distance(&self) -> Distance {
+        SPARSE_VECTOR_DISTANCE
+    }
+
+    fn datatype(&self) -> VectorStorageDatatype {
+        VectorStorageDatatype::Float32
+    }
+
+    fn is_on_disk(&self) -> bool {
+        true
+    }
+
+    fn total_vector_count(&self) -> usize {
+        self.offsets.len()
+    }
+
+    fn get_vector(&self, key: PointOffsetType) -> CowVector {
+        let vector = self.get_vector_opt(key);
+        debug_assert!(vector.is_some());
+        vector.unwrap_or_else(CowVector::default_sparse)
+    }
+
+    fn get_vector_opt(&self, key: PointOffsetType) -> Option<CowVector> {
+        // ignore any error
+        self.get_sparse(key).ok().map(CowVector::from)
+    }
+
+    fn insert_vector(&mut self, key: PointOffsetType, vector: VectorRef) -> OperationResult<()> {
+        let vector: &SparseVector = vector.try_into()?;
+        debug_assert!(vector.is_sorted());
+        let count = vector.indices.len();
+
+        let previous = self.get_offset(key);
+        let mut offset = previous.unwrap_or_default();
+
+        if count > offset.capacity as usize {
+            // append vector to the end, records must not cross chunk boundaries
+            let mut new_key = self.indices.len();
+            let chunk_left_keys = self.indices.get_remaining_chunk_keys(new_key);
+            if count > chunk_left_keys {
+                new_key += chunk_left_keys;
+            }
+
+            offset = SparseMmapOffset {
+                offset: new_key as u64,
+                count: count as u32,
+                capacity: count as u32,
+            };
+        } else {
+            // use existing place to insert vector
+            offset.count = count as u32;
+        }
+
+        if count > 0 {
+            self.indices
+                .insert_many(offset.offset, &vector.indices, count)?;
+            self.values
+                .insert_many(offset.offset, &vector.values, count)?;
+        }
+        self.offsets.insert(key as usize, &[offset])?;
+        self.set_deleted(key, false)?;
+
+        let previous_count = previous.map_or(0, |previous| previous.count as usize);
+        self.total_sparse_size = self.total_sparse_size.saturating_sub(previous_count) + count;
+
+        Ok(())
+    }
+
+    fn update_from(
+        &mut self,
+        other: &VectorStorageEnum,
+        other_ids: &mut impl Iterator<Item = PointOffsetType>,
+        stopped: &AtomicBool,
+    ) -> OperationResult<Range<PointOffsetType>> {
+        let start_index = self.offsets.len() as PointOffsetType;
+        for point_id in other_ids {
+            check_process_stopped(stopped)?;
+            // Do not perform preprocessing - vectors should be already processed
+            let other_deleted = other.is_deleted_vector(point_id);
+            let other_vector = other.get_vector(point_id);
+            let other_vector: VectorRef = other_vector.as_vec_ref();
+            let new_id = self.offsets.len() as PointOffsetType;
+            self.insert_vector(new_id, other_vector)?;
+            self.set_deleted(new_id, other_deleted)?;
+        }
+        let end_index = self.offsets.len() as PointOffsetType;
+        Ok(start_index..end_index)
+    }
+
+    fn flusher(&self) -> Flusher {
+        Box::new({
+            let indices_flusher = self.indices.flusher();
+            let values_flusher = self.values.flusher();
+            let offsets_flusher = self.offsets.flusher();
+            let deleted_flusher = self.deleted.flusher();
+            move || {
+                indices_flusher()?;
+                values_flusher()?;
+                offsets_flusher()?;
+                deleted_flusher()?;
+                Ok(())
+            }
+        })
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        let mut files = self.indices.files();
+        files.extend(self.values.files());
+        files.extend(self.offsets.files());
+        files.extend(self.deleted.files());
+        files
+    }
+
+    fn delete_vector(&mut self, key: PointOffsetType) -> OperationResult<bool> {
+        let was_deleted = self.set_deleted(key, true)?;
+        Ok(!was_deleted && (key as usize) < self.offsets.len())
+    }
+
+    fn is_deleted_vector(&self, key: PointOffsetType) -> bool {
+        self.deleted.get(key)
+    }
+
+    fn deleted_vector_count(&self) -> usize {
+        self.deleted_count
+    }
+
+    fn deleted_vector_bitslice(&self) -> &BitSlice {
+        self.deleted.get_bitslice()
+    }
+}
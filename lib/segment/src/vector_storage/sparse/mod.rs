@@ -0,0 +1 @@
+pub mod appendable_mmap_sparse_vector_storage;
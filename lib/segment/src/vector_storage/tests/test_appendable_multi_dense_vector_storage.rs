@@ -76,6 +76,7 @@ fn do_test_delete_points(storage: Arc<AtomicRefCell<VectorStorageEnum>>) {
             VectorStorageEnum::DenseAppendableMemmapByte(_) => unreachable!(),
             VectorStorageEnum::DenseAppendableMemmapHalf(_) => unreachable!(),
             VectorStorageEnum::SparseSimple(_) => unreachable!(),
+            VectorStorageEnum::SparseAppendableMemmap(_) => unreachable!(),
             VectorStorageEnum::MultiDenseSimple(v) => {
                 for (orig, vec) in orig_iter.zip(v.iterate_inner_vectors()) {
                     assert_eq!(orig, vec);
@@ -12,6 +12,7 @@ use crate::fixtures::payload_context_fixture::FixtureIdTracker;
 use crate::id_tracker::IdTrackerSS;
 use crate::vector_storage::query::RecoQuery;
 use crate::vector_storage::simple_sparse_vector_storage::open_simple_sparse_vector_storage;
+use crate::vector_storage::sparse::appendable_mmap_sparse_vector_storage::open_appendable_memmap_sparse_vector_storage;
 use crate::vector_storage::{new_raw_scorer, VectorStorage, VectorStorageEnum};
 
 fn do_test_delete_points(storage: Arc<AtomicRefCell<VectorStorageEnum>>) {
@@ -237,3 +238,88 @@ fn test_update_from_delete_points_simple_sparse_vector_storage() {
     let _storage =
         open_simple_sparse_vector_storage(db, DB_VECTOR_CF, &AtomicBool::new(false)).unwrap();
 }
+
+#[test]
+fn test_delete_points_in_appendable_mmap_sparse_vector_storage() {
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+
+    {
+        let storage =
+            open_appendable_memmap_sparse_vector_storage(dir.path(), &AtomicBool::new(false))
+                .unwrap();
+        do_test_delete_points(storage.clone());
+        storage.borrow().flusher()().unwrap();
+    }
+    let storage =
+        open_appendable_memmap_sparse_vector_storage(dir.path(), &AtomicBool::new(false)).unwrap();
+    assert_eq!(
+        storage.borrow().deleted_vector_count(),
+        5,
+        "deleted flags must be persisted"
+    );
+}
+
+#[test]
+fn test_update_from_delete_points_appendable_mmap_sparse_vector_storage() {
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    {
+        let storage =
+            open_appendable_memmap_sparse_vector_storage(dir.path(), &AtomicBool::new(false))
+                .unwrap();
+        do_test_update_from_delete_points(storage.clone());
+        storage.borrow().flusher()().unwrap();
+    }
+
+    let _storage =
+        open_appendable_memmap_sparse_vector_storage(dir.path(), &AtomicBool::new(false)).unwrap();
+}
+
+#[test]
+fn test_overwrite_appendable_mmap_sparse_vector_storage() {
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+
+    let points: Vec<SparseVector> = vec![
+        vec![(0, 1.0), (2, 1.0), (3, 1.0)],
+        vec![(1, 2.0)],
+        vec![],
+        vec![(0, 1.0), (1, 1.0), (2, 1.0), (3, 1.0), (7, 3.0)],
+    ]
+    .into_iter()
+    .map(|v| v.try_into().unwrap())
+    .collect();
+
+    {
+        let storage =
+            open_appendable_memmap_sparse_vector_storage(dir.path(), &AtomicBool::new(false))
+                .unwrap();
+        let mut borrowed_storage = storage.borrow_mut();
+
+        for (i, vec) in points.iter().enumerate() {
+            borrowed_storage
+                .insert_vector(i as PointOffsetType, vec.into())
+                .unwrap();
+        }
+
+        // Shrink the first vector in place and grow the second one
+        borrowed_storage
+            .insert_vector(0, (&points[1]).into())
+            .unwrap();
+        borrowed_storage
+            .insert_vector(1, (&points[3]).into())
+            .unwrap();
+
+        borrowed_storage.flusher()().unwrap();
+    }
+
+    let storage =
+        open_appendable_memmap_sparse_vector_storage(dir.path(), &AtomicBool::new(false)).unwrap();
+    let borrowed_storage = storage.borrow();
+    assert_eq!(borrowed_storage.total_vector_count(), points.len());
+
+    let expected = [&points[1], &points[3], &points[2], &points[3]];
+    for (i, vec) in expected.into_iter().enumerate() {
+        let stored_vec = borrowed_storage.get_vector(i as PointOffsetType);
+        let sparse: &SparseVector = stored_vec.as_vec_ref().try_into().unwrap();
+        assert_eq!(sparse, vec);
+    }
+}
@@ -1,5 +1,5 @@
 use std::ops::Range;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 
 use bitvec::prelude::BitSlice;
@@ -9,8 +9,9 @@ use sparse::common::sparse_vector::SparseVector;
 use super::dense::memmap_dense_vector_storage::MemmapDenseVectorStorage;
 use super::dense::simple_dense_vector_storage::SimpleDenseVectorStorage;
 use super::multi_dense::appendable_mmap_multi_dense_vector_storage::AppendableMmapMultiDenseVectorStorage;
+use super::multi_dense::multi_vector_centroids::MultiVectorCentroids;
 use super::multi_dense::simple_multi_dense_vector_storage::SimpleMultiDenseVectorStorage;
-use crate::common::operation_error::OperationResult;
+use crate::common::operation_error::{OperationError, OperationResult};
 use crate::common::Flusher;
 use crate::data_types::named_vectors::CowVector;
 use crate::data_types::primitive::PrimitiveVectorElement;
@@ -18,9 +19,12 @@ use crate::data_types::vectors::{
     TypedMultiDenseVectorRef, VectorElementType, VectorElementTypeByte, VectorElementTypeHalf,
     VectorRef,
 };
-use crate::types::{Distance, MultiVectorConfig, VectorStorageDatatype};
+use crate::types::{
+    Distance, MultiVectorCentroidsConfig, MultiVectorConfig, VectorStorageDatatype,
+};
 use crate::vector_storage::dense::appendable_mmap_dense_vector_storage::AppendableMmapDenseVectorStorage;
 use crate::vector_storage::simple_sparse_vector_storage::SimpleSparseVectorStorage;
+use crate::vector_storage::sparse::appendable_mmap_sparse_vector_storage::AppendableMmapSparseVectorStorage;
 
 /// Trait for vector storage
 /// El - type of vector element, expected numerical type
@@ -116,6 +120,48 @@ pub trait MultiVectorStorage<T: PrimitiveVectorElement>: VectorStorage {
     fn get_multi(&self, key: PointOffsetType) -> TypedMultiDenseVectorRef<T>;
     fn iterate_inner_vectors(&self) -> impl Iterator<Item = &[T]> + Clone + Send;
     fn multi_vector_config(&self) -> &MultiVectorConfig;
+    /// Centroid index of stored vectors, if built
+    fn centroids(&self) -> Option<&MultiVectorCentroids>;
+    fn set_centroids(&mut self, centroids: Option<MultiVectorCentroids>);
+}
+
+fn create_centroids<T: PrimitiveVectorElement>(
+    vector_storage: &mut impl MultiVectorStorage<T>,
+    config: MultiVectorCentroidsConfig,
+    vector_storage_path: &Path,
+    stopped: &AtomicBool,
+) -> OperationResult<()> {
+    let centroids =
+        MultiVectorCentroids::create(&*vector_storage, config, vector_storage_path, stopped)?;
+    vector_storage.set_centroids(Some(centroids));
+    Ok(())
+}
+
+fn load_centroids<T: PrimitiveVectorElement>(
+    vector_storage: &mut impl MultiVectorStorage<T>,
+    config: MultiVectorCentroidsConfig,
+    vector_storage_path: &Path,
+) -> OperationResult<()> {
+    let centroids = MultiVectorCentroids::load(vector_storage_path, vector_storage.is_on_disk())?;
+    // Outdated index is ignored, it will be rebuilt by optimizer
+    if centroids.config() != &config {
+        log::warn!(
+            "Centroid index config doesn't match, ignoring index in {}",
+            vector_storage_path.display(),
+        );
+        return Ok(());
+    }
+    if centroids.point_count() != vector_storage.total_vector_count() {
+        log::warn!(
+            "Centroid index doesn't match vector storage ({} != {}), ignoring index in {}",
+            centroids.point_count(),
+            vector_storage.total_vector_count(),
+            vector_storage_path.display(),
+        );
+        return Ok(());
+    }
+    vector_storage.set_centroids(Some(centroids));
+    Ok(())
 }
 
 pub enum VectorStorageEnum {
@@ -129,6 +175,7 @@ pub enum VectorStorageEnum {
     DenseAppendableMemmapByte(Box<AppendableMmapDenseVectorStorage<VectorElementTypeByte>>),
     DenseAppendableMemmapHalf(Box<AppendableMmapDenseVectorStorage<VectorElementTypeHalf>>),
     SparseSimple(SimpleSparseVectorStorage),
+    SparseAppendableMemmap(Box<AppendableMmapSparseVectorStorage>),
     MultiDenseSimple(SimpleMultiDenseVectorStorage<VectorElementType>),
     MultiDenseSimpleByte(SimpleMultiDenseVectorStorage<VectorElementTypeByte>),
     MultiDenseSimpleHalf(SimpleMultiDenseVectorStorage<VectorElementTypeHalf>),
@@ -142,6 +189,89 @@ pub enum VectorStorageEnum {
 }
 
 impl VectorStorageEnum {
+    /// Build centroid index of multivectors and persist it in `vector_storage_path`
+    pub fn create_multi_vector_centroids(
+        &mut self,
+        config: MultiVectorCentroidsConfig,
+        vector_storage_path: &Path,
+        stopped: &AtomicBool,
+    ) -> OperationResult<()> {
+        match self {
+            VectorStorageEnum::DenseSimple(_)
+            | VectorStorageEnum::DenseSimpleByte(_)
+            | VectorStorageEnum::DenseSimpleHalf(_)
+            | VectorStorageEnum::DenseMemmap(_)
+            | VectorStorageEnum::DenseMemmapByte(_)
+            | VectorStorageEnum::DenseMemmapHalf(_)
+            | VectorStorageEnum::DenseAppendableMemmap(_)
+            | VectorStorageEnum::DenseAppendableMemmapByte(_)
+            | VectorStorageEnum::DenseAppendableMemmapHalf(_)
+            | VectorStorageEnum::SparseSimple(_)
+            | VectorStorageEnum::SparseAppendableMemmap(_) => Err(OperationError::service_error(
+                "Centroid index is only available for multivectors",
+            )),
+            VectorStorageEnum::MultiDenseSimple(s) => {
+                create_centroids(s, config, vector_storage_path, stopped)
+            }
+            VectorStorageEnum::MultiDenseSimpleByte(s) => {
+                create_centroids(s, config, vector_storage_path, stopped)
+            }
+            VectorStorageEnum::MultiDenseSimpleHalf(s) => {
+                create_centroids(s, config, vector_storage_path, stopped)
+            }
+            VectorStorageEnum::MultiDenseAppendableMemmap(s) => {
+                create_centroids(s.as_mut(), config, vector_storage_path, stopped)
+            }
+            VectorStorageEnum::MultiDenseAppendableMemmapByte(s) => {
+                create_centroids(s.as_mut(), config, vector_storage_path, stopped)
+            }
+            VectorStorageEnum::MultiDenseAppendableMemmapHalf(s) => {
+                create_centroids(s.as_mut(), config, vector_storage_path, stopped)
+            }
+        }
+    }
+
+    /// Load centroid index of multivectors from `vector_storage_path`
+    pub fn load_multi_vector_centroids(
+        &mut self,
+        config: MultiVectorCentroidsConfig,
+        vector_storage_path: &Path,
+    ) -> OperationResult<()> {
+        match self {
+            VectorStorageEnum::DenseSimple(_)
+            | VectorStorageEnum::DenseSimpleByte(_)
+            | VectorStorageEnum::DenseSimpleHalf(_)
+            | VectorStorageEnum::DenseMemmap(_)
+            | VectorStorageEnum::DenseMemmapByte(_)
+            | VectorStorageEnum::DenseMemmapHalf(_)
+            | VectorStorageEnum::DenseAppendableMemmap(_)
+            | VectorStorageEnum::DenseAppendableMemmapByte(_)
+            | VectorStorageEnum::DenseAppendableMemmapHalf(_)
+            | VectorStorageEnum::SparseSimple(_)
+            | VectorStorageEnum::SparseAppendableMemmap(_) => Err(OperationError::service_error(
+                "Centroid index is only available for multivectors",
+            )),
+            VectorStorageEnum::MultiDenseSimple(s) => {
+                load_centroids(s, config, vector_storage_path)
+            }
+            VectorStorageEnum::MultiDenseSimpleByte(s) => {
+                load_centroids(s, config, vector_storage_path)
+            }
+            VectorStorageEnum::MultiDenseSimpleHalf(s) => {
+                load_centroids(s, config, vector_storage_path)
+            }
+            VectorStorageEnum::MultiDenseAppendableMemmap(s) => {
+                load_centroids(s.as_mut(), config, vector_storage_path)
+            }
+            VectorStorageEnum::MultiDenseAppendableMemmapByte(s) => {
+                load_centroids(s.as_mut(), config, vector_storage_path)
+            }
+            VectorStorageEnum::MultiDenseAppendableMemmapHalf(s) => {
+                load_centroids(s.as_mut(), config, vector_storage_path)
+            }
+        }
+    }
+
     pub fn try_multi_vector_config(&self) -> Option<&MultiVectorConfig> {
         match self {
             VectorStorageEnum::DenseSimple(_) => None,
@@ -154,6 +284,7 @@ impl VectorStorageEnum {
             VectorStorageEnum::DenseAppendableMemmapByte(_) => None,
             VectorStorageEnum::DenseAppendableMemmapHalf(_) => None,
             VectorStorageEnum::SparseSimple(_) => None,
+            VectorStorageEnum::SparseAppendableMemmap(_) => None,
             VectorStorageEnum::MultiDenseSimple(s) => Some(s.multi_vector_config()),
             VectorStorageEnum::MultiDenseSimpleByte(s) => Some(s.multi_vector_config()),
             VectorStorageEnum::MultiDenseSimpleHalf(s) => Some(s.multi_vector_config()),
@@ -177,6 +308,7 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::DenseAppendableMemmapByte(v) => v.vector_dim(),
             VectorStorageEnum::DenseAppendableMemmapHalf(v) => v.vector_dim(),
             VectorStorageEnum::SparseSimple(v) => v.vector_dim(),
+            VectorStorageEnum::SparseAppendableMemmap(v) => v.vector_dim(),
             VectorStorageEnum::MultiDenseSimple(v) => v.vector_dim(),
             VectorStorageEnum::MultiDenseSimpleByte(v) => v.vector_dim(),
             VectorStorageEnum::MultiDenseSimpleHalf(v) => v.vector_dim(),
@@ -198,6 +330,7 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::DenseAppendableMemmapByte(v) => v.distance(),
             VectorStorageEnum::DenseAppendableMemmapHalf(v) => v.distance(),
             VectorStorageEnum::SparseSimple(v) => v.distance(),
+            VectorStorageEnum::SparseAppendableMemmap(v) => v.distance(),
             VectorStorageEnum::MultiDenseSimple(v) => v.distance(),
             VectorStorageEnum::MultiDenseSimpleByte(v) => v.distance(),
             VectorStorageEnum::MultiDenseSimpleHalf(v) => v.distance(),
@@ -219,6 +352,7 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::DenseAppendableMemmapByte(v) => v.datatype(),
             VectorStorageEnum::DenseAppendableMemmapHalf(v) => v.datatype(),
             VectorStorageEnum::SparseSimple(v) => v.datatype(),
+            VectorStorageEnum::SparseAppendableMemmap(v) => v.datatype(),
             VectorStorageEnum::MultiDenseSimple(v) => v.datatype(),
             VectorStorageEnum::MultiDenseSimpleByte(v) => v.datatype(),
             VectorStorageEnum::MultiDenseSimpleHalf(v) => v.datatype(),
@@ -240,6 +374,7 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::DenseAppendableMemmapByte(v) => v.is_on_disk(),
             VectorStorageEnum::DenseAppendableMemmapHalf(v) => v.is_on_disk(),
             VectorStorageEnum::SparseSimple(v) => v.is_on_disk(),
+            VectorStorageEnum::SparseAppendableMemmap(v) => v.is_on_disk(),
             VectorStorageEnum::MultiDenseSimple(v) => v.is_on_disk(),
             VectorStorageEnum::MultiDenseSimpleByte(v) => v.is_on_disk(),
             VectorStorageEnum::MultiDenseSimpleHalf(v) => v.is_on_disk(),
@@ -261,6 +396,7 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::DenseAppendableMemmapByte(v) => v.total_vector_count(),
             VectorStorageEnum::DenseAppendableMemmapHalf(v) => v.total_vector_count(),
             VectorStorageEnum::SparseSimple(v) => v.total_vector_count(),
+            VectorStorageEnum::SparseAppendableMemmap(v) => v.total_vector_count(),
             VectorStorageEnum::MultiDenseSimple(v) => v.total_vector_count(),
             VectorStorageEnum::MultiDenseSimpleByte(v) => v.total_vector_count(),
             VectorStorageEnum::MultiDenseSimpleHalf(v) => v.total_vector_count(),
@@ -282,6 +418,7 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::DenseAppendableMemmapByte(v) => v.get_vector(key),
             VectorStorageEnum::DenseAppendableMemmapHalf(v) => v.get_vector(key),
             VectorStorageEnum::SparseSimple(v) => v.get_vector(key),
+            VectorStorageEnum::SparseAppendableMemmap(v) => v.get_vector(key),
             VectorStorageEnum::MultiDenseSimple(v) => v.get_vector(key),
             VectorStorageEnum::MultiDenseSimpleByte(v) => v.get_vector(key),
             VectorStorageEnum::MultiDenseSimpleHalf(v) => v.get_vector(key),
@@ -303,6 +440,7 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::DenseAppendableMemmapByte(v) => v.get_vector_opt(key),
             VectorStorageEnum::DenseAppendableMemmapHalf(v) => v.get_vector_opt(key),
             VectorStorageEnum::SparseSimple(v) => v.get_vector_opt(key),
+            VectorStorageEnum::SparseAppendableMemmap(v) => v.get_vector_opt(key),
             VectorStorageEnum::MultiDenseSimple(v) => v.get_vector_opt(key),
             VectorStorageEnum::MultiDenseSimpleByte(v) => v.get_vector_opt(key),
             VectorStorageEnum::MultiDenseSimpleHalf(v) => v.get_vector_opt(key),
@@ -324,6 +462,7 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::DenseAppendableMemmapByte(v) => v.insert_vector(key, vector),
             VectorStorageEnum::DenseAppendableMemmapHalf(v) => v.insert_vector(key, vector),
             VectorStorageEnum::SparseSimple(v) => v.insert_vector(key, vector),
+            VectorStorageEnum::SparseAppendableMemmap(v) => v.insert_vector(key, vector),
             VectorStorageEnum::MultiDenseSimple(v) => v.insert_vector(key, vector),
             VectorStorageEnum::MultiDenseSimpleByte(v) => v.insert_vector(key, vector),
             VectorStorageEnum::MultiDenseSimpleHalf(v) => v.insert_vector(key, vector),
@@ -354,6 +493,9 @@ impl VectorStorage for VectorStorageEnum {
                 v.update_from(other, other_ids, stopped)
             }
             VectorStorageEnum::SparseSimple(v) => v.update_from(other, other_ids, stopped),
+            VectorStorageEnum::SparseAppendableMemmap(v) => {
+                v.update_from(other, other_ids, stopped)
+            }
             VectorStorageEnum::MultiDenseSimple(v) => v.update_from(other, other_ids, stopped),
             VectorStorageEnum::MultiDenseSimpleByte(v) => v.update_from(other, other_ids, stopped),
             VectorStorageEnum::MultiDenseSimpleHalf(v) => v.update_from(other, other_ids, stopped),
@@ -381,6 +523,7 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::DenseAppendableMemmapByte(v) => v.flusher(),
             VectorStorageEnum::DenseAppendableMemmapHalf(v) => v.flusher(),
             VectorStorageEnum::SparseSimple(v) => v.flusher(),
+            VectorStorageEnum::SparseAppendableMemmap(v) => v.flusher(),
             VectorStorageEnum::MultiDenseSimple(v) => v.flusher(),
             VectorStorageEnum::MultiDenseSimpleByte(v) => v.flusher(),
             VectorStorageEnum::MultiDenseSimpleHalf(v) => v.flusher(),
@@ -402,6 +545,7 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::DenseAppendableMemmapByte(v) => v.files(),
             VectorStorageEnum::DenseAppendableMemmapHalf(v) => v.files(),
             VectorStorageEnum::SparseSimple(v) => v.files(),
+            VectorStorageEnum::SparseAppendableMemmap(v) => v.files(),
             VectorStorageEnum::MultiDenseSimple(v) => v.files(),
             VectorStorageEnum::MultiDenseSimpleByte(v) => v.files(),
             VectorStorageEnum::MultiDenseSimpleHalf(v) => v.files(),
@@ -423,6 +567,7 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::DenseAppendableMemmapByte(v) => v.delete_vector(key),
             VectorStorageEnum::DenseAppendableMemmapHalf(v) => v.delete_vector(key),
             VectorStorageEnum::SparseSimple(v) => v.delete_vector(key),
+            VectorStorageEnum::SparseAppendableMemmap(v) => v.delete_vector(key),
             VectorStorageEnum::MultiDenseSimple(v) => v.delete_vector(key),
             VectorStorageEnum::MultiDenseSimpleByte(v) => v.delete_vector(key),
             VectorStorageEnum::MultiDenseSimpleHalf(v) => v.delete_vector(key),
@@ -444,6 +589,7 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::DenseAppendableMemmapByte(v) => v.is_deleted_vector(key),
             VectorStorageEnum::DenseAppendableMemmapHalf(v) => v.is_deleted_vector(key),
             VectorStorageEnum::SparseSimple(v) => v.is_deleted_vector(key),
+            VectorStorageEnum::SparseAppendableMemmap(v) => v.is_deleted_vector(key),
             VectorStorageEnum::MultiDenseSimple(v) => v.is_deleted_vector(key),
             VectorStorageEnum::MultiDenseSimpleByte(v) => v.is_deleted_vector(key),
             VectorStorageEnum::MultiDenseSimpleHalf(v) => v.is_deleted_vector(key),
@@ -465,6 +611,7 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::DenseAppendableMemmapByte(v) => v.deleted_vector_count(),
             VectorStorageEnum::DenseAppendableMemmapHalf(v) => v.deleted_vector_count(),
             VectorStorageEnum::SparseSimple(v) => v.deleted_vector_count(),
+            VectorStorageEnum::SparseAppendableMemmap(v) => v.deleted_vector_count(),
             VectorStorageEnum::MultiDenseSimple(v) => v.deleted_vector_count(),
             VectorStorageEnum::MultiDenseSimpleByte(v) => v.deleted_vector_count(),
             VectorStorageEnum::MultiDenseSimpleHalf(v) => v.deleted_vector_count(),
@@ -486,6 +633,7 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::DenseAppendableMemmapByte(v) => v.deleted_vector_bitslice(),
             VectorStorageEnum::DenseAppendableMemmapHalf(v) => v.deleted_vector_bitslice(),
             VectorStorageEnum::SparseSimple(v) => v.deleted_vector_bitslice(),
+            VectorStorageEnum::SparseAppendableMemmap(v) => v.deleted_vector_bitslice(),
             VectorStorageEnum::MultiDenseSimple(v) => v.deleted_vector_bitslice(),
             VectorStorageEnum::MultiDenseSimpleByte(v) => v.deleted_vector_bitslice(),
             VectorStorageEnum::MultiDenseSimpleHalf(v) => v.deleted_vector_bitslice(),
@@ -48,6 +48,7 @@ fn test_batch_and_single_request_equivalency() {
         )]),
         sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        storage_format: Default::default(),
     };
 
     let int_key = "int";
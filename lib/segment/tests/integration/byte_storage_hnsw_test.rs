@@ -134,6 +134,7 @@ fn test_byte_storage_hnsw(
         )]),
         sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        storage_format: Default::default(),
     };
     let config_byte = SegmentConfig {
         vector_data: HashMap::from([(
@@ -150,6 +151,7 @@ fn test_byte_storage_hnsw(
         )]),
         sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        storage_format: Default::default(),
     };
 
     let int_key = "int";
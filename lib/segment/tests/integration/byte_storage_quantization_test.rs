@@ -257,6 +257,7 @@ fn test_byte_storage_binary_quantization_hnsw(
         )]),
         sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        storage_format: Default::default(),
     };
 
     let int_key = "int";
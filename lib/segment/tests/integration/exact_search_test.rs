@@ -56,6 +56,7 @@ fn exact_search_test() {
         )]),
         sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        storage_format: Default::default(),
     };
 
     let int_key = "int";
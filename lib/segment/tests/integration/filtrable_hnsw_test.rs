@@ -118,6 +118,7 @@ fn _test_filterable_hnsw(
         )]),
         sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        storage_format: Default::default(),
     };
 
     let int_key = "int";
@@ -80,6 +80,7 @@ fn hnsw_discover_precision() {
             },
         )]),
         payload_storage_type: Default::default(),
+        storage_format: Default::default(),
         sparse_vector_data: Default::default(),
     };
 
@@ -192,6 +193,7 @@ fn filtered_hnsw_discover_precision() {
             },
         )]),
         payload_storage_type: Default::default(),
+        storage_format: Default::default(),
         sparse_vector_data: Default::default(),
     };
 
@@ -77,6 +77,7 @@ fn hnsw_quantized_search_test(
         )]),
         sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        storage_format: Default::default(),
     };
 
     let mut segment = build_segment(dir.path(), &config, true).unwrap();
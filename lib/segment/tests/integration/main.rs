@@ -11,6 +11,7 @@ pub mod filtrable_hnsw_test;
 pub mod fixtures;
 pub mod hnsw_discover_test;
 pub mod hnsw_quantized_search_test;
+mod multivector_centroids_test;
 mod multivector_filtrable_hnsw_test;
 mod multivector_hnsw_test;
 mod multivector_quantization_test;
@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+
+use common::cpu::CpuPermit;
+use itertools::Itertools;
+use rand::prelude::StdRng;
+use rand::{Rng, SeedableRng};
+use segment::data_types::vectors::{
+    only_default_multi_vector, QueryVector, Vector, DEFAULT_VECTOR_NAME,
+};
+use segment::entry::entry_point::SegmentEntry;
+use segment::fixtures::payload_fixtures::random_multi_vector;
+use segment::index::hnsw_index::num_rayon_threads;
+use segment::segment::Segment;
+use segment::segment_constructor::segment_builder::SegmentBuilder;
+use segment::segment_constructor::{build_segment, get_vector_storage_path, load_segment};
+use segment::types::{
+    Distance, HnswConfig, Indexes, MultiVectorCentroidsConfig, MultiVectorConfig,
+    MultiVectorPoolingConfig, SearchParams, SegmentConfig, SeqNumberType, VectorDataConfig,
+    VectorStorageType,
+};
+use segment::vector_storage::multi_dense::multi_vector_centroids::MultiVectorCentroids;
+use tempfile::Builder;
+
+fn multivector_segment_config(
+    storage_type: VectorStorageType,
+    multivec_config: MultiVectorConfig,
+) -> SegmentConfig {
+    SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: 8,
+                distance: Distance::Cosine,
+                storage_type,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                multivec_config: Some(multivec_config),
+                datatype: None,
+            },
+        )]),
+        sparse_vector_data: Default::default(),
+        payload_storage_type: Default::default(),
+        storage_format: Default::default(),
+    }
+}
+
+#[test]
+fn test_multivector_centroids_search() {
+    let stopped = AtomicBool::new(false);
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+
+    let vector_dim = 8;
+    let num_points = 500;
+    let top = 10;
+
+    // Candidates factor covers all points, so results must be the same as with exact search,
+    // also when the graph is traversed with estimated scores
+    let multivec_config = MultiVectorConfig {
+        centroids: Some(MultiVectorCentroidsConfig {
+            num_centroids: 16,
+            candidates_factor: Some(num_points),
+        }),
+        ..Default::default()
+    };
+
+    let mut segment = build_segment(
+        dir.path(),
+        &multivector_segment_config(VectorStorageType::Memory, multivec_config),
+        true,
+    )
+    .unwrap();
+    for n in 0..num_points as u64 {
+        let num_vectors = rnd.gen_range(1..=4);
+        let multi_vec = random_multi_vector(&mut rnd, vector_dim, num_vectors);
+        segment
+            .upsert_point(
+                n as SeqNumberType,
+                n.into(),
+                only_default_multi_vector(&multi_vec),
+            )
+            .unwrap();
+    }
+
+    let hnsw_config = HnswConfig {
+        m: 16,
+        ef_construct: 100,
+        full_scan_threshold: 0,
+        max_indexing_threads: 2,
+        on_disk: Some(false),
+        payload_m: None,
+    };
+
+    // Centroid codes are memory mapped with on disk storage, and kept in RAM otherwise
+    let built_segments = [
+        (VectorStorageType::Mmap, Indexes::Plain {}),
+        (VectorStorageType::Memory, Indexes::Plain {}),
+        (VectorStorageType::Mmap, Indexes::Hnsw(hnsw_config)),
+    ]
+    .map(|(storage_type, index)| {
+        let mut config = multivector_segment_config(storage_type, multivec_config);
+        config
+            .vector_data
+            .get_mut(DEFAULT_VECTOR_NAME)
+            .unwrap()
+            .index = index;
+
+        let mut builder = SegmentBuilder::new(dir.path(), temp_dir.path(), &config).unwrap();
+        builder.update_from(&segment, &stopped).unwrap();
+        let permit = CpuPermit::dummy(num_rayon_threads(0) as u32);
+        let built_segment = builder.build(permit, &stopped).unwrap();
+
+        let vector_storage_path =
+            get_vector_storage_path(&built_segment.current_path, DEFAULT_VECTOR_NAME);
+        assert!(MultiVectorCentroids::exists(&vector_storage_path));
+
+        let loaded_segment = load_segment(&built_segment.current_path, &stopped)
+            .unwrap()
+            .unwrap();
+        (built_segment, loaded_segment)
+    });
+
+    for _ in 0..10 {
+        let query: QueryVector = random_multi_vector(&mut rnd, vector_dim, 2).into();
+
+        let search = |segment: &Segment| {
+            segment
+                .search(
+                    DEFAULT_VECTOR_NAME,
+                    &query,
+                    &false.into(),
+                    &false.into(),
+                    None,
+                    top,
+                    None,
+                )
+                .unwrap()
+                .into_iter()
+                .map(|scored_point| scored_point.id)
+                .collect_vec()
+        };
+
+        let exact_result = search(&segment);
+        assert_eq!(exact_result.len(), top);
+        for (built_segment, loaded_segment) in &built_segments {
+            assert_eq!(search(built_segment), exact_result);
+            assert_eq!(search(loaded_segment), exact_result);
+        }
+    }
+}
+
+#[test]
+fn test_multivector_centroids_exact_search() {
+    let stopped = AtomicBool::new(false);
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+
+    let vector_dim = 8;
+    let num_points = 500;
+    let top = 10;
+
+    // Smallest candidates factor, so that candidate selection would miss exact results
+    let multivec_config = MultiVectorConfig {
+        centroids: Some(MultiVectorCentroidsConfig {
+            num_centroids: 4,
+            candidates_factor: Some(1),
+        }),
+        ..Default::default()
+    };
+
+    let mut segment = build_segment(
+        dir.path(),
+        &multivector_segment_config(VectorStorageType::Memory, multivec_config),
+        true,
+    )
+    .unwrap();
+    for n in 0..num_points as u64 {
+        let num_vectors = rnd.gen_range(1..=4);
+        let multi_vec = random_multi_vector(&mut rnd, vector_dim, num_vectors);
+        segment
+            .upsert_point(
+                n as SeqNumberType,
+                n.into(),
+                only_default_multi_vector(&multi_vec),
+            )
+            .unwrap();
+    }
+
+    let hnsw_config = HnswConfig {
+        m: 16,
+        ef_construct: 100,
+        full_scan_threshold: 0,
+        max_indexing_threads: 2,
+        on_disk: Some(false),
+        payload_m: None,
+    };
+
+    let built_segments = [Indexes::Plain {}, Indexes::Hnsw(hnsw_config)].map(|index| {
+        let mut config = multivector_segment_config(VectorStorageType::Mmap, multivec_config);
+        config
+            .vector_data
+            .get_mut(DEFAULT_VECTOR_NAME)
+            .unwrap()
+            .index = index;
+
+        let mut builder = SegmentBuilder::new(dir.path(), temp_dir.path(), &config).unwrap();
+        builder.update_from(&segment, &stopped).unwrap();
+        let permit = CpuPermit::dummy(num_rayon_threads(0) as u32);
+        let built_segment = builder.build(permit, &stopped).unwrap();
+
+        let vector_storage_path =
+            get_vector_storage_path(&built_segment.current_path, DEFAULT_VECTOR_NAME);
+        assert!(MultiVectorCentroids::exists(&vector_storage_path));
+        built_segment
+    });
+
+    let exact_params = SearchParams {
+        exact: true,
+        ..Default::default()
+    };
+
+    for _ in 0..10 {
+        let query: QueryVector = random_multi_vector(&mut rnd, vector_dim, 2).into();
+
+        let search = |segment: &Segment, params: Option<&SearchParams>| {
+            segment
+                .search(
+                    DEFAULT_VECTOR_NAME,
+                    &query,
+                    &false.into(),
+                    &false.into(),
+                    None,
+                    top,
+                    params,
+                )
+                .unwrap()
+                .into_iter()
+                .map(|scored_point| scored_point.id)
+                .collect_vec()
+        };
+
+        // Segment without centroids scores all points
+        let brute_force_result = search(&segment, None);
+        assert_eq!(brute_force_result.len(), top);
+        for built_segment in &built_segments {
+            assert_eq!(
+                search(built_segment, Some(&exact_params)),
+                brute_force_result,
+            );
+        }
+    }
+}
+
+#[test]
+fn test_multivector_pooling() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+    let multivec_config = MultiVectorConfig {
+        pooling: Some(MultiVectorPoolingConfig { max_vectors: 2 }),
+        ..Default::default()
+    };
+    let mut segment = build_segment(
+        dir.path(),
+        &multivector_segment_config(VectorStorageType::Memory, multivec_config),
+        true,
+    )
+    .unwrap();
+
+    let mut rnd = StdRng::seed_from_u64(42);
+    let long_vector = random_multi_vector(&mut rnd, 8, 5);
+    let short_vector = random_multi_vector(&mut rnd, 8, 2);
+    segment
+        .upsert_point(1, 1.into(), only_default_multi_vector(&long_vector))
+        .unwrap();
+    segment
+        .upsert_point(2, 2.into(), only_default_multi_vector(&short_vector))
+        .unwrap();
+
+    let stored_len = |point_id: u64| match segment
+        .vector(DEFAULT_VECTOR_NAME, point_id.into())
+        .unwrap()
+    {
+        Some(Vector::MultiDense(multi_vector)) => multi_vector.len(),
+        other => panic!("unexpected vector: {other:?}"),
+    };
+    assert_eq!(stored_len(1), 2);
+    assert_eq!(stored_len(2), 2);
+}
@@ -55,6 +55,7 @@ fn test_single_multi_and_dense_hnsw_equivalency() {
         )]),
         sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        storage_format: Default::default(),
     };
 
     let int_key = "int";
@@ -6,12 +6,17 @@ use std::time::{Duration, Instant};
 use common::cpu::CpuPermit;
 use itertools::Itertools;
 use segment::common::operation_error::OperationError;
+use segment::common::rocksdb_wrapper::is_db_file;
 use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
 use segment::entry::entry_point::SegmentEntry;
 use segment::index::hnsw_index::num_rayon_threads;
-use segment::segment::Segment;
+use segment::segment::{Segment, SEGMENT_STATE_FILE};
+use segment::segment_constructor::build_segment;
 use segment::segment_constructor::segment_builder::SegmentBuilder;
-use segment::types::{Indexes, SegmentConfig, VectorDataConfig, VectorStorageType};
+use segment::types::{
+    Condition, FieldCondition, Filter, Indexes, PayloadFieldSchema, PayloadSchemaType,
+    PayloadStorageType, SegmentConfig, SegmentStorageFormat, VectorDataConfig, VectorStorageType,
+};
 use tempfile::Builder;
 
 use crate::fixtures::segment::{build_segment_1, build_segment_2, empty_segment};
@@ -75,6 +80,140 @@ fn test_building_new_segment() {
     assert_eq!(merged_segment.point_version(3.into()), Some(100));
 }
 
+fn flat_segment_config(segment: &Segment, index: Indexes) -> SegmentConfig {
+    SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: segment.segment_config.vector_data[DEFAULT_VECTOR_NAME].size,
+                distance: segment.segment_config.vector_data[DEFAULT_VECTOR_NAME].distance,
+                storage_type: VectorStorageType::Mmap,
+                index,
+                quantization_config: None,
+                multivec_config: None,
+                datatype: None,
+            },
+        )]),
+        sparse_vector_data: Default::default(),
+        payload_storage_type: PayloadStorageType::Mmap,
+        storage_format: SegmentStorageFormat::Flat,
+    }
+}
+
+#[test]
+fn test_building_flat_segment() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+
+    let stopped = AtomicBool::new(false);
+
+    let segment1 = build_segment_1(dir.path());
+    let segment2 = build_segment_2(dir.path());
+
+    let segment_config = flat_segment_config(&segment1, Indexes::Hnsw(Default::default()));
+
+    let mut builder = SegmentBuilder::new(dir.path(), temp_dir.path(), &segment_config).unwrap();
+
+    builder.update_from(&segment1, &stopped).unwrap();
+    builder.update_from(&segment2, &stopped).unwrap();
+
+    let permit_cpu_count = num_rayon_threads(0);
+    let permit = CpuPermit::dummy(permit_cpu_count as u32);
+
+    let flat_segment: Segment = builder.build(permit, &stopped).unwrap();
+
+    assert!(flat_segment.database.is_none());
+    assert!(flat_segment.segment_config.storage_format.is_flat());
+    assert!(!flat_segment.current_path.join("CURRENT").exists());
+    // Only RocksDB files are removed from the segment directory
+    let file_names: Vec<_> = flat_segment
+        .current_path
+        .read_dir()
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    assert!(!file_names.iter().any(|file_name| is_db_file(file_name)));
+    assert!(file_names
+        .iter()
+        .any(|file_name| file_name == SEGMENT_STATE_FILE));
+
+    assert_eq!(
+        flat_segment.available_point_count(),
+        segment1
+            .iter_points()
+            .chain(segment2.iter_points())
+            .unique()
+            .count(),
+    );
+
+    for point_id in segment1.iter_points() {
+        assert_eq!(
+            flat_segment.vector(DEFAULT_VECTOR_NAME, point_id).unwrap(),
+            segment1.vector(DEFAULT_VECTOR_NAME, point_id).unwrap(),
+        );
+        assert_eq!(
+            flat_segment.payload(point_id).unwrap(),
+            segment1.payload(point_id).unwrap(),
+        );
+    }
+}
+
+#[test]
+fn test_flat_segment_with_payload_index_keeps_rocksdb() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+
+    let stopped = AtomicBool::new(false);
+
+    let segment1 = build_segment_1(dir.path());
+
+    let segment_config = flat_segment_config(&segment1, Indexes::Plain {});
+
+    let mut builder = SegmentBuilder::new(dir.path(), temp_dir.path(), &segment_config).unwrap();
+
+    builder.update_from(&segment1, &stopped).unwrap();
+    builder.indexed_fields.insert(
+        "color".parse().unwrap(),
+        PayloadFieldSchema::FieldType(PayloadSchemaType::Keyword),
+    );
+
+    let permit_cpu_count = num_rayon_threads(0);
+    let permit = CpuPermit::dummy(permit_cpu_count as u32);
+
+    let segment: Segment = builder.build(permit, &stopped).unwrap();
+
+    // Payload indexes are stored in RocksDB, so the segment is not converted into flat format
+    assert!(segment.segment_config.storage_format.is_rocks_db());
+    assert!(segment.database.is_some());
+    assert_eq!(segment.get_indexed_fields().len(), 1);
+
+    let filter = Filter::new_must(Condition::Field(FieldCondition::new_match(
+        "color".parse().unwrap(),
+        "red".to_owned().into(),
+    )));
+    assert_eq!(
+        segment.read_filtered(None, None, Some(&filter)).len(),
+        segment1.read_filtered(None, None, Some(&filter)).len(),
+    );
+}
+
+#[test]
+fn test_flat_segment_must_not_be_appendable() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+    let segment = build_segment_1(dir.path());
+    let mut segment_config = flat_segment_config(&segment, Indexes::Plain {});
+    segment_config.vector_data.values_mut().for_each(|config| {
+        config.storage_type = VectorStorageType::ChunkedMmap;
+    });
+
+    let result = build_segment(dir.path(), &segment_config, true);
+    assert!(matches!(
+        result,
+        Err(OperationError::ValidationError { .. })
+    ));
+}
+
 fn estimate_build_time(segment: &Segment, stop_delay_millis: u64) -> (u64, bool) {
     let stopped = Arc::new(AtomicBool::new(false));
 
@@ -96,6 +235,7 @@ fn estimate_build_time(segment: &Segment, stop_delay_millis: u64) -> (u64, bool)
         )]),
         sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        storage_format: Default::default(),
     };
 
     let mut builder = SegmentBuilder::new(dir.path(), temp_dir.path(), &segment_config).unwrap();
@@ -124,9 +124,11 @@ fn sparse_index_discover_test() {
                     full_scan_threshold: Some(DEFAULT_SPARSE_FULL_SCAN_THRESHOLD),
                     index_type: SparseIndexType::MutableRam,
                 },
+                storage_type: Default::default(),
             },
         )]),
         payload_storage_type: Default::default(),
+        storage_format: Default::default(),
     };
     let dense_config = SegmentConfig {
         vector_data: HashMap::from([(
@@ -142,6 +144,7 @@ fn sparse_index_discover_test() {
             },
         )]),
         payload_storage_type: Default::default(),
+        storage_format: Default::default(),
         sparse_vector_data: Default::default(),
     };
 
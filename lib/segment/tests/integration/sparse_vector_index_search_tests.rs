@@ -619,9 +619,11 @@ fn sparse_vector_index_persistence_test() {
                     full_scan_threshold: Some(DEFAULT_SPARSE_FULL_SCAN_THRESHOLD),
                     index_type: SparseIndexType::MutableRam,
                 },
+                storage_type: Default::default(),
             },
         )]),
         payload_storage_type: Default::default(),
+        storage_format: Default::default(),
     };
     let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -862,9 +864,11 @@ fn sparse_vector_test_large_index() {
                     full_scan_threshold: Some(DEFAULT_SPARSE_FULL_SCAN_THRESHOLD),
                     index_type: SparseIndexType::MutableRam,
                 },
+                storage_type: Default::default(),
             },
         )]),
         payload_storage_type: Default::default(),
+        storage_format: Default::default(),
     };
     let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
 
-use collection::config::{CollectionConfig, ShardingMethod};
+use collection::collection::resharding::ReshardKey;
+use collection::config::{CollectionConfig, PayloadStorage, ReplicaPlacement, ShardingMethod};
 use collection::operations::config_diff::{
     CollectionParamsDiff, HnswConfigDiff, OptimizersConfigDiff, QuantizationConfigDiff,
     WalConfigDiff,
@@ -13,11 +14,14 @@ use collection::shards::shard::{PeerId, ShardId, ShardsPlacement};
 use collection::shards::transfer::{ShardTransfer, ShardTransferKey, ShardTransferRestart};
 use collection::shards::{replica_set, CollectionId};
 use schemars::JsonSchema;
-use segment::types::{PayloadFieldSchema, PayloadKeyType, QuantizationConfig, ShardKey};
+use segment::types::{
+    PayloadFieldSchema, PayloadKeyType, QuantizationConfig, SegmentStorageFormat, ShardKey,
+};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
 use crate::content_manager::shard_distribution::ShardDistributionProposal;
+use crate::content_manager::token_revocation::{RevokedToken, TokenRevocation};
 
 // *Operation wrapper structure is only required for better OpenAPI generation
 
@@ -131,6 +135,11 @@ pub struct CreateCollection {
     #[serde(default)]
     #[validate(range(min = 1))]
     pub replication_factor: Option<u32>,
+    /// Constraint on the placement of shard replicas across peers.
+    /// For example, spread replicas of each shard across availability zones.
+    #[serde(default)]
+    #[validate]
+    pub replica_placement: Option<ReplicaPlacement>,
     /// Defines how many replicas should apply the operation for us to consider it successful.
     /// Increasing this number will make the collection more resilient to inconsistencies, but will
     /// also make it fail if not enough replicas are available.
@@ -144,6 +153,17 @@ pub struct CreateCollection {
     /// Note: those payload values that are involved in filtering and are indexed - remain in RAM.
     #[serde(default)]
     pub on_disk_payload: Option<bool>,
+    /// Type of storage for point payloads.
+    /// If not set, defined by `on_disk_payload`. If both are set, they must match.
+    #[serde(default)]
+    pub payload_storage: Option<PayloadStorage>,
+    /// Defines how optimized segments are persisted.
+    /// Flat format stores all segment components in plain files without RocksDB.
+    /// It is only applied to non-appendable segments with mmap payload and on-disk vector storages,
+    /// other segments keep using RocksDB. Payload indexes can't be created with flat format.
+    /// Requires `mmap` payload storage.
+    #[serde(default)]
+    pub storage_format: Option<SegmentStorageFormat>,
     /// Custom params for HNSW index. If none - values from service configuration file are used.
     #[validate]
     pub hnsw_config: Option<HnswConfigDiff>,
@@ -293,17 +313,13 @@ pub struct DeleteCollectionOperation(pub String);
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
 pub enum ReshardingOperation {
-    Start {
-        peer_id: PeerId,
-        shard_id: ShardId,
-        shard_key: Option<ShardKey>,
-    },
-
-    Abort {
-        peer_id: PeerId,
-        shard_id: ShardId,
-        shard_key: Option<ShardKey>,
-    },
+    Start(ReshardKey),
+    /// Serve reads with the new hashring
+    CommitRead(ReshardKey),
+    /// Route updates with the new hashring only
+    CommitWrite(ReshardKey),
+    Finish(ReshardKey),
+    Abort(ReshardKey),
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
@@ -390,7 +406,20 @@ pub enum CollectionMetaOperations {
     DropShardKey(DropShardKey),
     CreatePayloadIndex(CreatePayloadIndex),
     DropPayloadIndex(DropPayloadIndex),
-    Nop { token: usize }, // Empty operation
+    /// Revoke tokens, `timestamp` is the time of the operation to drop expired revocations at
+    RevokeToken {
+        revocation: TokenRevocation,
+        timestamp: u64,
+    },
+    /// Restore revoked tokens, `timestamp` is the time of the operation to drop expired
+    /// revocations at
+    RestoreToken {
+        token: RevokedToken,
+        timestamp: u64,
+    },
+    Nop {
+        token: usize,
+    }, // Empty operation
 }
 
 /// Use config of the existing collection to generate a create collection operation
@@ -402,8 +431,11 @@ impl From<CollectionConfig> for CreateCollection {
             shard_number: Some(value.params.shard_number.get()),
             sharding_method: value.params.sharding_method,
             replication_factor: Some(value.params.replication_factor.get()),
+            replica_placement: value.params.replica_placement,
             write_consistency_factor: Some(value.params.write_consistency_factor.get()),
             on_disk_payload: Some(value.params.on_disk_payload),
+            payload_storage: value.params.payload_storage,
+            storage_format: value.params.storage_format,
             hnsw_config: Some(value.hnsw_config.into()),
             wal_config: Some(value.wal_config.into()),
             optimizers_config: Some(value.optimizer_config.into()),
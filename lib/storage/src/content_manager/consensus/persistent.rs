@@ -1,4 +1,5 @@
 use std::cmp;
+use std::collections::BTreeMap;
 use std::fs::{create_dir_all, File};
 use std::io::BufWriter;
 use std::path::{Path, PathBuf};
@@ -186,11 +187,13 @@ impl Persistent {
         self.peer_metadata_by_id.read().clone()
     }
 
-    pub fn is_our_metadata_outdated(&self) -> bool {
+    pub fn is_our_metadata_outdated(&self, labels: &BTreeMap<String, String>) -> bool {
         self.peer_metadata_by_id
             .read()
             .get(&self.this_peer_id())
-            .map_or(true, |metadata| metadata.is_different_version())
+            .map_or(true, |metadata| {
+                metadata.is_different_version() || metadata.labels != *labels
+            })
     }
 
     pub fn this_peer_id(&self) -> PeerId {
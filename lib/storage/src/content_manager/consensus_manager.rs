@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
 use std::fmt::Display;
 use std::future::Future;
@@ -32,6 +32,7 @@ use crate::content_manager::consensus::consensus_wal::ConsensusOpWal;
 use crate::content_manager::consensus::entry_queue::EntryId;
 use crate::content_manager::consensus::operation_sender::OperationSender;
 use crate::content_manager::consensus::persistent::Persistent;
+use crate::content_manager::token_revocation::TokenRevocations;
 use crate::types::{
     ClusterInfo, ClusterStatus, ConsensusThreadStatus, MessageSendErrors, PeerAddressById,
     PeerInfo, PeerMetadataById, RaftInfo,
@@ -59,6 +60,8 @@ pub struct SnapshotData {
 pub struct CollectionsSnapshot {
     pub collections: HashMap<CollectionId, collection_state::State>,
     pub aliases: AliasMapping,
+    #[serde(default)]
+    pub token_revocations: TokenRevocations,
 }
 
 impl TryFrom<&[u8]> for SnapshotData {
@@ -100,6 +103,8 @@ pub struct ConsensusManager<C: CollectionContainer> {
     message_send_failures: RwLock<HashMap<String, MessageSendErrors>>,
     /// Last time we attempted to update the peer metadata
     next_peer_metadata_update_attempt: Mutex<Instant>,
+    /// Labels of this peer, announced to other peers in the peer metadata
+    peer_labels: BTreeMap<String, String>,
 }
 
 impl<C: CollectionContainer> ConsensusManager<C> {
@@ -123,9 +128,16 @@ impl<C: CollectionContainer> ConsensusManager<C> {
             }),
             message_send_failures: Default::default(),
             next_peer_metadata_update_attempt: Mutex::new(Instant::now()),
+            peer_labels: BTreeMap::new(),
         }
     }
 
+    /// Set labels of this peer, such as its availability zone
+    pub fn with_peer_labels(mut self, peer_labels: BTreeMap<String, String>) -> Self {
+        self.peer_labels = peer_labels;
+        self
+    }
+
     pub fn report_snapshot(
         &self,
         peer_id: u64,
@@ -200,14 +212,20 @@ impl<C: CollectionContainer> ConsensusManager<C> {
     pub fn cluster_status(&self) -> ClusterStatus {
         let persistent = self.persistent.read();
         let hard_state = &persistent.state.hard_state;
+        let peer_metadata_by_id = persistent.peer_metadata_by_id.read();
         let peers = persistent
             .peer_address_by_id()
             .into_iter()
             .map(|(peer_id, uri)| {
+                let labels = peer_metadata_by_id
+                    .get(&peer_id)
+                    .map(|metadata| metadata.labels.clone())
+                    .unwrap_or_default();
                 (
                     peer_id,
                     PeerInfo {
                         uri: uri.to_string(),
+                        labels,
                     },
                 )
             })
@@ -738,7 +756,11 @@ impl<C: CollectionContainer> ConsensusManager<C> {
             return Ok(());
         }
 
-        if !self.persistent.read().is_our_metadata_outdated() {
+        if !self
+            .persistent
+            .read()
+            .is_our_metadata_outdated(&self.peer_labels)
+        {
             return Ok(());
         }
 
@@ -747,7 +769,7 @@ impl<C: CollectionContainer> ConsensusManager<C> {
             .propose_sender
             .send(ConsensusOperations::UpdatePeerMetadata {
                 peer_id: self.this_peer_id(),
-                metadata: PeerMetadata::current(),
+                metadata: PeerMetadata::current(self.peer_labels.clone()),
             });
         if let Err(err) = result {
             log::error!("Failed to propose consensus peer metadata update for this peer: {err}");
@@ -1,4 +1,6 @@
-use collection::operations::conversions::sharding_method_from_proto;
+use collection::operations::conversions::{
+    payload_storage_from_proto, sharding_method_from_proto, storage_format_from_proto,
+};
 use collection::operations::types::SparseVectorsConfig;
 use tonic::Status;
 
@@ -9,6 +11,7 @@ use crate::content_manager::collection_meta_ops::{
     UpdateCollection, UpdateCollectionOperation,
 };
 use crate::content_manager::errors::StorageError;
+use crate::content_manager::rebalancer::PeerLoad;
 
 pub fn error_to_status(error: StorageError) -> tonic::Status {
     let error_code = match &error {
@@ -22,6 +25,7 @@ pub fn error_to_status(error: StorageError) -> tonic::Status {
         StorageError::ChecksumMismatch { .. } => tonic::Code::DataLoss,
         StorageError::Forbidden { .. } => tonic::Code::PermissionDenied,
         StorageError::PreconditionFailed { .. } => tonic::Code::FailedPrecondition,
+        StorageError::RateLimitExceeded { .. } => tonic::Code::ResourceExhausted,
     };
     tonic::Status::new(error_code, format!("{error}"))
 }
@@ -46,7 +50,16 @@ impl TryFrom<api::grpc::qdrant::CreateCollection> for CollectionMetaOperations {
                 optimizers_config: value.optimizers_config.map(|v| v.into()),
                 shard_number: value.shard_number,
                 on_disk_payload: value.on_disk_payload,
+                payload_storage: value
+                    .payload_storage
+                    .map(payload_storage_from_proto)
+                    .transpose()?,
+                storage_format: value
+                    .storage_format
+                    .map(storage_format_from_proto)
+                    .transpose()?,
                 replication_factor: value.replication_factor,
+                replica_placement: value.replica_placement.map(Into::into),
                 write_consistency_factor: value.write_consistency_factor,
                 init_from: value
                     .init_from_collection
@@ -166,3 +179,41 @@ impl TryFrom<api::grpc::qdrant::ChangeAliases> for CollectionMetaOperations {
         Ok(Self::ChangeAliases(ChangeAliasesOperation { actions }))
     }
 }
+
+impl From<PeerLoad> for api::grpc::qdrant::GetPeerLoadResponse {
+    fn from(value: PeerLoad) -> Self {
+        let PeerLoad {
+            free_disk_bytes,
+            shard_sizes,
+        } = value;
+        Self {
+            free_disk_bytes,
+            shards: shard_sizes
+                .into_iter()
+                .map(
+                    |((collection_name, shard_id), size_bytes)| api::grpc::qdrant::ShardLoad {
+                        collection_name,
+                        shard_id,
+                        size_bytes,
+                    },
+                )
+                .collect(),
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::GetPeerLoadResponse> for PeerLoad {
+    fn from(value: api::grpc::qdrant::GetPeerLoadResponse) -> Self {
+        let api::grpc::qdrant::GetPeerLoadResponse {
+            free_disk_bytes,
+            shards,
+        } = value;
+        Self {
+            free_disk_bytes,
+            shard_sizes: shards
+                .into_iter()
+                .map(|shard| ((shard.collection_name, shard.shard_id), shard.size_bytes))
+                .collect(),
+        }
+    }
+}
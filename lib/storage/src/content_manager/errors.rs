@@ -32,6 +32,8 @@ pub enum StorageError {
     Forbidden { description: String },
     #[error("Pre-condition failure: {description}")]
     PreconditionFailed { description: String }, // system is not in the state to perform the operation
+    #[error("Rate limit exceeded: {description}")]
+    RateLimitExceeded { description: String },
 }
 
 impl StorageError {
@@ -48,6 +50,12 @@ impl StorageError {
         }
     }
 
+    pub fn rate_limit_exceeded(description: impl Into<String>) -> StorageError {
+        StorageError::RateLimitExceeded {
+            description: description.into(),
+        }
+    }
+
     pub fn bad_input(description: impl Into<String>) -> StorageError {
         StorageError::BadInput {
             description: description.into(),
@@ -12,9 +12,13 @@ pub mod consensus_manager;
 pub mod conversions;
 mod data_transfer;
 pub mod errors;
+pub mod rebalancer;
+pub mod replica_repair;
 pub mod shard_distribution;
+pub mod snapshot_scheduler;
 pub mod snapshots;
 pub mod toc;
+pub mod token_revocation;
 
 pub mod consensus_ops {
     use collection::operations::types::PeerMetadata;
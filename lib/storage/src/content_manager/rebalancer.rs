@@ -0,0 +1,727 @@
+//! Planning of automatic shard moves across peers.
+//!
+//! The planner works on a snapshot of the shard placement and the load reported by each peer.
+//! It greedily picks shard moves, in this order of priority:
+//!
+//! 1. move shards away from peers running low on disk space
+//! 2. even out the number of shard replicas per peer
+//! 3. even out the total size of shard replicas per peer
+//!
+//! Executing the planned moves is up to the caller, see [`ShardMove::to_transfer`].
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use collection::collection_state;
+use collection::shards::peer_groups::PeerGroups;
+use collection::shards::replica_set::ReplicaState;
+use collection::shards::shard::{PeerId, ShardId};
+use collection::shards::transfer::{ShardTransfer, ShardTransferMethod};
+use collection::shards::CollectionId;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::types::{PeerMetadataById, RebalancerConfig};
+
+type ShardRef = (CollectionId, ShardId);
+
+/// Load reported by a single peer
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PeerLoad {
+    /// Free disk space in the storage directory, `None` if unknown
+    pub free_disk_bytes: Option<u64>,
+    /// Disk usage of local shards in bytes
+    pub shard_sizes: HashMap<ShardRef, u64>,
+}
+
+/// Shard placement of a single collection, as seen by the planner
+#[derive(Clone, Debug, Default)]
+pub struct CollectionPlacement {
+    pub shards: HashMap<ShardId, HashMap<PeerId, ReplicaState>>,
+    pub transfers: HashSet<ShardTransfer>,
+    pub is_resharding: bool,
+    pub replication_factor: u32,
+    /// Groups to spread shard replicas across, see [`PeerGroups`]
+    pub peer_groups: PeerGroups,
+}
+
+impl CollectionPlacement {
+    pub fn new(state: &collection_state::State, peer_metadata: &PeerMetadataById) -> Self {
+        Self {
+            shards: state
+                .shards
+                .iter()
+                .map(|(shard_id, info)| (*shard_id, info.replicas.clone()))
+                .collect(),
+            transfers: state.transfers.clone(),
+            is_resharding: state.resharding.is_some(),
+            replication_factor: state.config.params.replication_factor.get(),
+            peer_groups: PeerGroups::new(
+                state.config.params.replica_placement.as_ref(),
+                peer_metadata,
+            ),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ShardMoveReason {
+    /// Source peer is running low on disk space
+    DiskSpace,
+    /// Source peer holds more shard replicas than the target peer
+    ShardCount,
+    /// Source peer holds more data than the target peer
+    ShardSize,
+}
+
+/// A single planned move of a shard replica from one peer to another
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, JsonSchema)]
+pub struct ShardMove {
+    pub collection_name: CollectionId,
+    pub shard_id: ShardId,
+    pub from_peer_id: PeerId,
+    pub to_peer_id: PeerId,
+    /// Disk usage of the moved shard in bytes, if known
+    pub size_bytes: Option<u64>,
+    pub reason: ShardMoveReason,
+}
+
+impl ShardMove {
+    /// Shard transfer to execute this move
+    pub fn to_transfer(&self, method: Option<ShardTransferMethod>) -> ShardTransfer {
+        ShardTransfer {
+            shard_id: self.shard_id,
+            to_shard_id: None,
+            from: self.from_peer_id,
+            to: self.to_peer_id,
+            sync: false,
+            method,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, JsonSchema)]
+pub struct RebalancePlan {
+    /// Planned shard moves, in order of priority
+    pub moves: Vec<ShardMove>,
+}
+
+/// Limits for the number of concurrent shard transfers per collection on each peer
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TransferLimits {
+    pub incoming: Option<usize>,
+    pub outgoing: Option<usize>,
+}
+
+/// Plan shard moves to rebalance the given collections across peers
+///
+/// Only peers present in `loads` are considered, as both source and target of a move. Shards of
+/// collections that are being resharded, and shards that are already being transferred, are
+/// never moved.
+pub fn plan_rebalance(
+    collections: &HashMap<CollectionId, CollectionPlacement>,
+    loads: &HashMap<PeerId, PeerLoad>,
+    config: &RebalancerConfig,
+    limits: TransferLimits,
+) -> RebalancePlan {
+    let mut planner = Planner::new(collections, loads, config, limits);
+
+    while planner.moves.len() < config.max_moves {
+        let next_move = planner
+            .next_disk_space_move()
+            .or_else(|| planner.next_shard_count_move())
+            .or_else(|| planner.next_shard_size_move());
+
+        match next_move {
+            Some(shard_move) => planner.apply(shard_move),
+            None => break,
+        }
+    }
+
+    RebalancePlan {
+        moves: planner.moves,
+    }
+}
+
+struct Planner<'a> {
+    config: &'a RebalancerConfig,
+    limits: TransferLimits,
+    /// Shard replicas on each peer
+    replicas: BTreeMap<PeerId, BTreeSet<ShardRef>>,
+    /// Peers holding a replica of each shard, including peers without a reported load
+    shard_peers: HashMap<ShardRef, Vec<PeerId>>,
+    peer_groups: HashMap<CollectionId, PeerGroups>,
+    /// Known size of each shard, the largest size reported by any of its replicas
+    sizes: HashMap<ShardRef, u64>,
+    free_disk_bytes: HashMap<PeerId, Option<u64>>,
+    /// Incoming and outgoing transfers of each collection on each peer
+    transfer_io: HashMap<(CollectionId, PeerId), (usize, usize)>,
+    /// Shards which must not be moved
+    locked: HashSet<ShardRef>,
+    moves: Vec<ShardMove>,
+}
+
+impl<'a> Planner<'a> {
+    fn new(
+        collections: &HashMap<CollectionId, CollectionPlacement>,
+        loads: &HashMap<PeerId, PeerLoad>,
+        config: &'a RebalancerConfig,
+        limits: TransferLimits,
+    ) -> Self {
+        let mut replicas: BTreeMap<PeerId, BTreeSet<ShardRef>> = loads
+            .keys()
+            .map(|peer_id| (*peer_id, BTreeSet::new()))
+            .collect();
+        let mut sizes: HashMap<ShardRef, u64> = HashMap::new();
+        let mut transfer_io: HashMap<(CollectionId, PeerId), (usize, usize)> = HashMap::new();
+        let mut locked = HashSet::new();
+        let mut shard_peers = HashMap::new();
+        let mut peer_groups = HashMap::new();
+
+        for load in loads.values() {
+            for (shard, size) in &load.shard_sizes {
+                let known_size = sizes.entry(shard.clone()).or_default();
+                *known_size = (*known_size).max(*size);
+            }
+        }
+
+        for (collection_name, placement) in collections {
+            peer_groups.insert(collection_name.clone(), placement.peer_groups.clone());
+
+            for transfer in &placement.transfers {
+                transfer_io
+                    .entry((collection_name.clone(), transfer.to))
+                    .or_default()
+                    .0 += 1;
+                transfer_io
+                    .entry((collection_name.clone(), transfer.from))
+                    .or_default()
+                    .1 += 1;
+            }
+
+            let transferred_shards: HashSet<ShardId> = placement
+                .transfers
+                .iter()
+                .flat_map(|transfer| [Some(transfer.shard_id), transfer.to_shard_id])
+                .flatten()
+                .collect();
+
+            for (shard_id, peers) in &placement.shards {
+                let shard = (collection_name.clone(), *shard_id);
+
+                let all_active = peers.values().all(|state| *state == ReplicaState::Active);
+                if placement.is_resharding || transferred_shards.contains(shard_id) || !all_active {
+                    locked.insert(shard.clone());
+                }
+
+                for peer_id in peers.keys() {
+                    if let Some(peer_replicas) = replicas.get_mut(peer_id) {
+                        peer_replicas.insert(shard.clone());
+                    }
+                }
+
+                shard_peers.insert(shard, peers.keys().copied().collect());
+            }
+        }
+
+        let free_disk_bytes = loads
+            .iter()
+            .map(|(peer_id, load)| (*peer_id, load.free_disk_bytes))
+            .collect();
+
+        Self {
+            config,
+            limits,
+            replicas,
+            shard_peers,
+            peer_groups,
+            sizes,
+            free_disk_bytes,
+            transfer_io,
+            locked,
+            moves: Vec::new(),
+        }
+    }
+
+    fn min_free_disk_bytes(&self) -> u64 {
+        self.config.min_free_disk_mb.saturating_mul(1024 * 1024)
+    }
+
+    fn shard_size(&self, shard: &ShardRef) -> u64 {
+        self.sizes.get(shard).copied().unwrap_or(0)
+    }
+
+    fn peer_size(&self, peer_id: PeerId) -> u64 {
+        self.replicas[&peer_id]
+            .iter()
+            .map(|shard| self.shard_size(shard))
+            .sum()
+    }
+
+    fn peer_count(&self, peer_id: PeerId) -> usize {
+        self.replicas[&peer_id].len()
+    }
+
+    fn is_low_on_disk(&self, peer_id: PeerId) -> bool {
+        self.free_disk_bytes[&peer_id].map_or(false, |free| free < self.min_free_disk_bytes())
+    }
+
+    fn can_move(&self, shard: &ShardRef, from: PeerId, to: PeerId) -> bool {
+        if from == to || self.locked.contains(shard) {
+            return false;
+        }
+
+        if !self.replicas[&from].contains(shard) || self.replicas[&to].contains(shard) {
+            return false;
+        }
+
+        let (collection_name, _) = shard;
+
+        let (_, outgoing) = self
+            .transfer_io
+            .get(&(collection_name.clone(), from))
+            .copied()
+            .unwrap_or_default();
+        let (incoming, _) = self
+            .transfer_io
+            .get(&(collection_name.clone(), to))
+            .copied()
+            .unwrap_or_default();
+
+        if self
+            .limits
+            .outgoing
+            .map_or(false, |limit| outgoing >= limit)
+            || self
+                .limits
+                .incoming
+                .map_or(false, |limit| incoming >= limit)
+        {
+            return false;
+        }
+
+        // Keep replicas spread across peer groups
+        if let Some(peer_groups) = self.peer_groups.get(collection_name) {
+            let shard_peers = self.shard_peers.get(shard).map_or(&[][..], Vec::as_slice);
+            let peers: Vec<_> = self.replicas.keys().copied().collect();
+            if !peer_groups.allows_move(shard_peers, from, to, &peers) {
+                return false;
+            }
+        }
+
+        // Target must keep enough free disk space after receiving the shard
+        match self.free_disk_bytes[&to] {
+            Some(free) => free >= self.shard_size(shard) + self.min_free_disk_bytes(),
+            None => true,
+        }
+    }
+
+    fn shard_move(
+        &self,
+        shard: &ShardRef,
+        from: PeerId,
+        to: PeerId,
+        reason: ShardMoveReason,
+    ) -> ShardMove {
+        let (collection_name, shard_id) = shard.clone();
+        ShardMove {
+            collection_name,
+            shard_id,
+            from_peer_id: from,
+            to_peer_id: to,
+            size_bytes: self.sizes.get(shard).copied(),
+            reason,
+        }
+    }
+
+    /// Move the largest shard away from the peer with the least free disk space, onto the peer
+    /// with the most free disk space
+    fn next_disk_space_move(&self) -> Option<ShardMove> {
+        let mut sources: Vec<_> = self
+            .replicas
+            .keys()
+            .copied()
+            .filter(|peer_id| self.is_low_on_disk(*peer_id))
+            .collect();
+        sources.sort_by_key(|peer_id| self.free_disk_bytes[peer_id]);
+
+        let mut targets: Vec<_> = self
+            .replicas
+            .keys()
+            .copied()
+            .filter(|peer_id| !self.is_low_on_disk(*peer_id))
+            .collect();
+        // Unknown free disk space goes last
+        targets.sort_by_key(|peer_id| {
+            std::cmp::Reverse(
+                self.free_disk_bytes[peer_id].map_or(0, |free| free.saturating_add(1)),
+            )
+        });
+
+        for from in sources {
+            let mut shards: Vec<_> = self.replicas[&from].iter().collect();
+            shards.sort_by_key(|shard| std::cmp::Reverse(self.shard_size(shard)));
+
+            for shard in shards {
+                for &to in &targets {
+                    if self.can_move(shard, from, to) {
+                        return Some(self.shard_move(shard, from, to, ShardMoveReason::DiskSpace));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Move the smallest shard from a peer with the most replicas onto a peer with the least
+    /// replicas, if their replica counts differ by more than one
+    fn next_shard_count_move(&self) -> Option<ShardMove> {
+        let mut peers: Vec<_> = self.replicas.keys().copied().collect();
+        peers.sort_by_key(|peer_id| self.peer_count(*peer_id));
+
+        for &from in peers.iter().rev() {
+            for &to in &peers {
+                if self.peer_count(from) <= self.peer_count(to) + 1 {
+                    break;
+                }
+
+                let mut shards: Vec<_> = self.replicas[&from].iter().collect();
+                shards.sort_by_key(|shard| self.shard_size(shard));
+
+                if let Some(shard) = shards
+                    .into_iter()
+                    .find(|shard| self.can_move(shard, from, to))
+                {
+                    return Some(self.shard_move(shard, from, to, ShardMoveReason::ShardCount));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Move a shard from a peer with the most data onto a peer with the least data, if their
+    /// total shard sizes differ by more than the configured threshold
+    ///
+    /// Moves are only planned onto peers not holding more replicas than the source peer, so this
+    /// does not undo shard count balancing.
+    fn next_shard_size_move(&self) -> Option<ShardMove> {
+        let mut peers: Vec<_> = self.replicas.keys().copied().collect();
+        if peers.len() < 2 {
+            return None;
+        }
+
+        let peer_sizes: HashMap<PeerId, u64> = peers
+            .iter()
+            .map(|peer_id| (*peer_id, self.peer_size(*peer_id)))
+            .collect();
+        let mean_size = peer_sizes.values().sum::<u64>() as f64 / peers.len() as f64;
+        let threshold = mean_size * self.config.size_imbalance_threshold;
+
+        peers.sort_by_key(|peer_id| peer_sizes[peer_id]);
+
+        for &from in peers.iter().rev() {
+            for &to in &peers {
+                let difference = peer_sizes[&from].saturating_sub(peer_sizes[&to]);
+                if difference == 0 || difference as f64 <= threshold {
+                    break;
+                }
+
+                if self.peer_count(to) > self.peer_count(from) {
+                    continue;
+                }
+
+                // Any shard smaller than the difference reduces the imbalance, prefer the shard
+                // that brings both peers closest together
+                let shard = self.replicas[&from]
+                    .iter()
+                    .filter(|shard| {
+                        let size = self.shard_size(shard);
+                        size > 0 && size < difference
+                    })
+                    .filter(|shard| self.can_move(shard, from, to))
+                    .min_by_key(|shard| difference.abs_diff(2 * self.shard_size(shard)));
+
+                if let Some(shard) = shard {
+                    return Some(self.shard_move(shard, from, to, ShardMoveReason::ShardSize));
+                }
+            }
+        }
+
+        None
+    }
+
+    fn apply(&mut self, shard_move: ShardMove) {
+        let shard = (shard_move.collection_name.clone(), shard_move.shard_id);
+        let size = self.shard_size(&shard);
+
+        if let Some(replicas) = self.replicas.get_mut(&shard_move.from_peer_id) {
+            replicas.remove(&shard);
+        }
+        if let Some(replicas) = self.replicas.get_mut(&shard_move.to_peer_id) {
+            replicas.insert(shard.clone());
+        }
+
+        if let Some(shard_peers) = self.shard_peers.get_mut(&shard) {
+            shard_peers.retain(|peer_id| *peer_id != shard_move.from_peer_id);
+            shard_peers.push(shard_move.to_peer_id);
+        }
+
+        if let Some(Some(free)) = self.free_disk_bytes.get_mut(&shard_move.from_peer_id) {
+            *free += size;
+        }
+        if let Some(Some(free)) = self.free_disk_bytes.get_mut(&shard_move.to_peer_id) {
+            *free = free.saturating_sub(size);
+        }
+
+        self.transfer_io
+            .entry((shard_move.collection_name.clone(), shard_move.to_peer_id))
+            .or_default()
+            .0 += 1;
+        self.transfer_io
+            .entry((shard_move.collection_name.clone(), shard_move.from_peer_id))
+            .or_default()
+            .1 += 1;
+
+        self.locked.insert(shard);
+        self.moves.push(shard_move);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+
+    fn config() -> RebalancerConfig {
+        RebalancerConfig {
+            max_moves: 16,
+            ..Default::default()
+        }
+    }
+
+    /// Collection with one active replica per shard, placed on the given peers
+    fn placement(shard_peers: &[PeerId]) -> CollectionPlacement {
+        CollectionPlacement {
+            shards: shard_peers
+                .iter()
+                .enumerate()
+                .map(|(shard_id, peer_id)| {
+                    (
+                        shard_id as ShardId,
+                        HashMap::from([(*peer_id, ReplicaState::Active)]),
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn loads(peers: &[PeerId]) -> HashMap<PeerId, PeerLoad> {
+        peers
+            .iter()
+            .map(|peer_id| (*peer_id, PeerLoad::default()))
+            .collect()
+    }
+
+    #[test]
+    fn test_balance_shard_count_onto_new_peer() {
+        let collections = HashMap::from([("test".to_string(), placement(&[1, 1, 1, 1, 2, 2]))]);
+
+        let plan = plan_rebalance(
+            &collections,
+            &loads(&[1, 2, 3]),
+            &config(),
+            TransferLimits::default(),
+        );
+
+        assert_eq!(plan.moves.len(), 2);
+        for shard_move in &plan.moves {
+            assert_eq!(shard_move.to_peer_id, 3);
+            assert_eq!(shard_move.reason, ShardMoveReason::ShardCount);
+        }
+        assert!(plan
+            .moves
+            .iter()
+            .any(|shard_move| shard_move.from_peer_id == 1));
+    }
+
+    #[test]
+    fn test_balanced_cluster_is_left_alone() {
+        let collections = HashMap::from([("test".to_string(), placement(&[1, 2, 3, 1, 2]))]);
+
+        let plan = plan_rebalance(
+            &collections,
+            &loads(&[1, 2, 3]),
+            &config(),
+            TransferLimits::default(),
+        );
+
+        assert!(plan.moves.is_empty());
+    }
+
+    #[test]
+    fn test_respect_transfer_limits() {
+        let mut collection = placement(&[1, 1, 1, 1]);
+        let collections = HashMap::from([("test".to_string(), collection.clone())]);
+
+        let limits = TransferLimits {
+            incoming: Some(1),
+            outgoing: Some(1),
+        };
+
+        let plan = plan_rebalance(&collections, &loads(&[1, 2, 3]), &config(), limits);
+        assert_eq!(plan.moves.len(), 1);
+
+        // Ongoing transfer from peer 1 uses up the outgoing limit
+        collection.transfers.insert(ShardTransfer {
+            shard_id: 0,
+            to_shard_id: None,
+            from: 1,
+            to: 2,
+            sync: true,
+            method: None,
+        });
+        let collections = HashMap::from([("test".to_string(), collection)]);
+
+        let plan = plan_rebalance(&collections, &loads(&[1, 2, 3]), &config(), limits);
+        assert!(plan.moves.is_empty());
+    }
+
+    #[test]
+    fn test_skip_resharding_and_transferred_shards() {
+        let mut resharding = placement(&[1, 1, 1, 1]);
+        resharding.is_resharding = true;
+
+        let mut transferring = placement(&[1, 1]);
+        transferring.transfers.insert(ShardTransfer {
+            shard_id: 0,
+            to_shard_id: None,
+            from: 1,
+            to: 2,
+            sync: true,
+            method: None,
+        });
+
+        let collections = HashMap::from([
+            ("resharding".to_string(), resharding),
+            ("transferring".to_string(), transferring),
+        ]);
+
+        let plan = plan_rebalance(
+            &collections,
+            &loads(&[1, 2]),
+            &config(),
+            TransferLimits::default(),
+        );
+
+        assert_eq!(plan.moves.len(), 1);
+        assert_eq!(plan.moves[0].collection_name, "transferring");
+        assert_eq!(plan.moves[0].shard_id, 1);
+    }
+
+    #[test]
+    fn test_move_away_from_full_disk() {
+        let collections = HashMap::from([("test".to_string(), placement(&[1, 2]))]);
+
+        let mut loads = loads(&[1, 2, 3]);
+        loads.get_mut(&1).unwrap().free_disk_bytes = Some(10 * MB);
+        loads.get_mut(&2).unwrap().free_disk_bytes = Some(100 * 1024 * MB);
+        loads.get_mut(&3).unwrap().free_disk_bytes = Some(10 * 1024 * MB);
+        loads
+            .get_mut(&1)
+            .unwrap()
+            .shard_sizes
+            .insert(("test".to_string(), 0), 500 * MB);
+
+        let plan = plan_rebalance(&collections, &loads, &config(), TransferLimits::default());
+
+        assert_eq!(plan.moves[0].from_peer_id, 1);
+        assert_eq!(plan.moves[0].to_peer_id, 2);
+        assert_eq!(plan.moves[0].size_bytes, Some(500 * MB));
+        assert_eq!(plan.moves[0].reason, ShardMoveReason::DiskSpace);
+    }
+
+    #[test]
+    fn test_never_fill_target_disk() {
+        let collections = HashMap::from([("test".to_string(), placement(&[1, 1, 1, 1]))]);
+
+        let mut loads = loads(&[1, 2]);
+        loads.get_mut(&2).unwrap().free_disk_bytes = Some(512 * MB);
+
+        let plan = plan_rebalance(&collections, &loads, &config(), TransferLimits::default());
+
+        assert!(plan.moves.is_empty());
+    }
+
+    #[test]
+    fn test_balance_shard_size() {
+        let collections = HashMap::from([("test".to_string(), placement(&[1, 1, 2, 2]))]);
+
+        let mut loads = loads(&[1, 2]);
+        for (shard_id, size) in [(0, 100), (1, 100), (2, 10), (3, 10)] {
+            let peer_id = if shard_id < 2 { 1 } else { 2 };
+            loads
+                .get_mut(&peer_id)
+                .unwrap()
+                .shard_sizes
+                .insert(("test".to_string(), shard_id), size * MB);
+        }
+
+        let plan = plan_rebalance(&collections, &loads, &config(), TransferLimits::default());
+
+        // Large shard moves onto the peer with less data, then a small shard moves back to even
+        // out replica counts again
+        assert_eq!(plan.moves.len(), 2);
+        assert_eq!(plan.moves[0].from_peer_id, 1);
+        assert_eq!(plan.moves[0].to_peer_id, 2);
+        assert_eq!(plan.moves[0].size_bytes, Some(100 * MB));
+        assert_eq!(plan.moves[0].reason, ShardMoveReason::ShardSize);
+        assert_eq!(plan.moves[1].from_peer_id, 2);
+        assert_eq!(plan.moves[1].to_peer_id, 1);
+        assert_eq!(plan.moves[1].size_bytes, Some(10 * MB));
+        assert_eq!(plan.moves[1].reason, ShardMoveReason::ShardCount);
+    }
+
+    #[test]
+    fn test_keep_replicas_spread_across_groups() {
+        // Peers 1, 2 in zone `a`, peers 3, 4 in zone `b`
+        let peer_groups = PeerGroups::from_groups(HashMap::from([
+            (1, "a".to_string()),
+            (2, "a".to_string()),
+            (3, "b".to_string()),
+            (4, "b".to_string()),
+        ]));
+
+        let collection = CollectionPlacement {
+            shards: (0..4)
+                .map(|shard_id| {
+                    (
+                        shard_id,
+                        HashMap::from([(1, ReplicaState::Active), (3, ReplicaState::Active)]),
+                    )
+                })
+                .collect(),
+            peer_groups: peer_groups.clone(),
+            ..Default::default()
+        };
+        let collections = HashMap::from([("test".to_string(), collection)]);
+
+        let plan = plan_rebalance(
+            &collections,
+            &loads(&[1, 2, 3, 4]),
+            &config(),
+            TransferLimits::default(),
+        );
+
+        assert_eq!(plan.moves.len(), 4);
+        for shard_move in &plan.moves {
+            assert_eq!(
+                peer_groups.group(shard_move.from_peer_id),
+                peer_groups.group(shard_move.to_peer_id),
+            );
+        }
+    }
+}
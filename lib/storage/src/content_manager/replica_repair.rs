@@ -0,0 +1,498 @@
+//! Planning of automatic repair of under-replicated shards.
+//!
+//! A shard is under-replicated if it has less healthy replicas than the replication factor of its
+//! collection. All replicas are considered healthy, except replicas which stay `Dead` for longer
+//! than a grace period. This gives peers a chance to recover their replicas by themselves, before
+//! they are replaced by new replicas on other peers.
+//!
+//! For every under-replicated shard the planner decides how to repair it, see [`RepairDecision`].
+//! At most one new replica is planned per shard in a single round.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use collection::shards::replica_set::ReplicaState;
+use collection::shards::shard::{PeerId, ShardId};
+use collection::shards::transfer::{ShardTransfer, ShardTransferMethod};
+use collection::shards::CollectionId;
+
+use crate::content_manager::rebalancer::{CollectionPlacement, TransferLimits};
+
+/// Replica of a shard on a specific peer
+pub type ReplicaRef = (CollectionId, ShardId, PeerId);
+
+/// Keeps track of how long replicas are dead
+#[derive(Debug, Default)]
+pub struct DeadReplicaTracker {
+    dead_since: HashMap<ReplicaRef, Instant>,
+}
+
+impl DeadReplicaTracker {
+    /// Update tracked replicas with the current replica states
+    ///
+    /// Replicas which are not dead anymore, or which don't exist anymore, are forgotten.
+    pub fn update(
+        &mut self,
+        collections: &HashMap<CollectionId, CollectionPlacement>,
+        now: Instant,
+    ) {
+        let mut dead_since = HashMap::new();
+
+        for (collection_name, placement) in collections {
+            for (shard_id, peers) in &placement.shards {
+                for (peer_id, state) in peers {
+                    if *state != ReplicaState::Dead {
+                        continue;
+                    }
+
+                    let replica = (collection_name.clone(), *shard_id, *peer_id);
+                    let since = self.dead_since.get(&replica).copied().unwrap_or(now);
+                    dead_since.insert(replica, since);
+                }
+            }
+        }
+
+        self.dead_since = dead_since;
+    }
+
+    /// Replicas which are dead for at least the given grace period
+    pub fn lost_replicas(&self, grace: Duration, now: Instant) -> HashSet<ReplicaRef> {
+        self.dead_since
+            .iter()
+            .filter(|(_, since)| now.saturating_duration_since(**since) >= grace)
+            .map(|(replica, _)| replica.clone())
+            .collect()
+    }
+}
+
+/// Decision taken for an under-replicated shard
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RepairDecision {
+    /// Replicate the shard from an active replica onto a new peer
+    Replicate { from: PeerId, to: PeerId },
+    /// Shard is already being transferred, wait for the transfer to finish
+    TransferInProgress,
+    /// Collection is being resharded, wait for resharding to finish
+    Resharding,
+    /// Shard transfer limits are reached on the source or on all possible target peers
+    TransferLimitReached,
+    /// Shard has no active replica to replicate from
+    NoActiveReplica,
+    /// All available peers already hold a replica of the shard
+    NoTargetPeer,
+}
+
+/// An under-replicated shard and the decision taken to repair it
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShardRepair {
+    pub collection_name: CollectionId,
+    pub shard_id: ShardId,
+    pub healthy_replicas: usize,
+    pub replication_factor: u32,
+    pub decision: RepairDecision,
+}
+
+impl ShardRepair {
+    /// Shard transfer to execute the repair, if the shard can be repaired right now
+    pub fn to_transfer(&self, method: Option<ShardTransferMethod>) -> Option<ShardTransfer> {
+        match self.decision {
+            RepairDecision::Replicate { from, to } => Some(ShardTransfer {
+                shard_id: self.shard_id,
+                to_shard_id: None,
+                from,
+                to,
+                sync: true,
+                method,
+            }),
+            RepairDecision::TransferInProgress
+            | RepairDecision::Resharding
+            | RepairDecision::TransferLimitReached
+            | RepairDecision::NoActiveReplica
+            | RepairDecision::NoTargetPeer => None,
+        }
+    }
+}
+
+/// Find under-replicated shards of the given collections and decide how to repair them
+///
+/// Replicas listed in `lost_replicas` are not counted as healthy, see
+/// [`DeadReplicaTracker::lost_replicas`]. New replicas are only planned onto the given `peers`,
+/// preferring peers with the least replicas. Peers holding any dead replica are assumed to be
+/// unavailable, and never receive new replicas.
+///
+/// Returned repairs are sorted by collection name and shard ID.
+pub fn plan_replica_repair(
+    collections: &HashMap<CollectionId, CollectionPlacement>,
+    peers: &[PeerId],
+    lost_replicas: &HashSet<ReplicaRef>,
+    limits: TransferLimits,
+) -> Vec<ShardRepair> {
+    // Number of replicas on each available peer
+    let mut replica_count: BTreeMap<PeerId, usize> =
+        peers.iter().map(|peer_id| (*peer_id, 0)).collect();
+    let mut unavailable = HashSet::new();
+    // Incoming and outgoing transfers of each collection on each peer
+    let mut transfer_io: HashMap<(CollectionId, PeerId), (usize, usize)> = HashMap::new();
+
+    for (collection_name, placement) in collections {
+        for peers in placement.shards.values() {
+            for (peer_id, state) in peers {
+                if *state == ReplicaState::Dead {
+                    unavailable.insert(*peer_id);
+                }
+                if let Some(count) = replica_count.get_mut(peer_id) {
+                    *count += 1;
+                }
+            }
+        }
+
+        for transfer in &placement.transfers {
+            transfer_io
+                .entry((collection_name.clone(), transfer.to))
+                .or_default()
+                .0 += 1;
+            transfer_io
+                .entry((collection_name.clone(), transfer.from))
+                .or_default()
+                .1 += 1;
+        }
+    }
+
+    let mut collection_names: Vec<_> = collections.keys().collect();
+    collection_names.sort();
+
+    let mut repairs = Vec::new();
+
+    for collection_name in collection_names {
+        let placement = &collections[collection_name];
+
+        let transferred_shards: HashSet<ShardId> = placement
+            .transfers
+            .iter()
+            .flat_map(|transfer| [Some(transfer.shard_id), transfer.to_shard_id])
+            .flatten()
+            .collect();
+
+        let mut shard_ids: Vec<_> = placement.shards.keys().copied().collect();
+        shard_ids.sort_unstable();
+
+        for shard_id in shard_ids {
+            let shard_peers = &placement.shards[&shard_id];
+
+            let healthy_replicas: Vec<PeerId> = shard_peers
+                .keys()
+                .filter(|peer_id| {
+                    !lost_replicas.contains(&(collection_name.clone(), shard_id, **peer_id))
+                })
+                .copied()
+                .collect();
+
+            if healthy_replicas.len() >= placement.replication_factor as usize {
+                continue;
+            }
+
+            let decision = if placement.is_resharding {
+                RepairDecision::Resharding
+            } else if transferred_shards.contains(&shard_id) {
+                RepairDecision::TransferInProgress
+            } else {
+                let outgoing = |peer_id: PeerId| {
+                    transfer_io
+                        .get(&(collection_name.clone(), peer_id))
+                        .map_or(0, |(_, outgoing)| *outgoing)
+                };
+                let incoming = |peer_id: PeerId| {
+                    transfer_io
+                        .get(&(collection_name.clone(), peer_id))
+                        .map_or(0, |(incoming, _)| *incoming)
+                };
+
+                let source = shard_peers
+                    .iter()
+                    .filter(|(_, state)| **state == ReplicaState::Active)
+                    .map(|(peer_id, _)| *peer_id)
+                    .min_by_key(|peer_id| (outgoing(*peer_id), *peer_id));
+
+                let candidates: Vec<PeerId> = replica_count
+                    .keys()
+                    .filter(|peer_id| {
+                        !shard_peers.contains_key(peer_id) && !unavailable.contains(peer_id)
+                    })
+                    .copied()
+                    .collect();
+
+                // Keep replicas spread across peer groups, lost replicas do not count
+                let mut targets: Vec<_> = candidates
+                    .iter()
+                    .filter(|peer_id| {
+                        placement.peer_groups.allows_replica(
+                            &healthy_replicas,
+                            **peer_id,
+                            &candidates,
+                        )
+                    })
+                    .map(|peer_id| (replica_count[peer_id], *peer_id))
+                    .collect();
+                targets.sort_unstable();
+
+                let within_limits = |limit: Option<usize>, transfers: usize| {
+                    limit.map_or(true, |limit| transfers < limit)
+                };
+
+                match source {
+                    None => RepairDecision::NoActiveReplica,
+                    Some(_) if targets.is_empty() => RepairDecision::NoTargetPeer,
+                    Some(from) if !within_limits(limits.outgoing, outgoing(from)) => {
+                        RepairDecision::TransferLimitReached
+                    }
+                    Some(from) => targets
+                        .into_iter()
+                        .map(|(_, peer_id)| peer_id)
+                        .find(|to| within_limits(limits.incoming, incoming(*to)))
+                        .map_or(RepairDecision::TransferLimitReached, |to| {
+                            RepairDecision::Replicate { from, to }
+                        }),
+                }
+            };
+
+            if let RepairDecision::Replicate { from, to } = decision {
+                transfer_io
+                    .entry((collection_name.clone(), to))
+                    .or_default()
+                    .0 += 1;
+                transfer_io
+                    .entry((collection_name.clone(), from))
+                    .or_default()
+                    .1 += 1;
+                if let Some(count) = replica_count.get_mut(&to) {
+                    *count += 1;
+                }
+            }
+
+            repairs.push(ShardRepair {
+                collection_name: collection_name.clone(),
+                shard_id,
+                healthy_replicas: healthy_replicas.len(),
+                replication_factor: placement.replication_factor,
+                decision,
+            });
+        }
+    }
+
+    repairs
+}
+
+#[cfg(test)]
+mod tests {
+    use collection::shards::peer_groups::PeerGroups;
+
+    use super::*;
+
+    /// Collection with the given replicas of a single shard
+    fn placement(
+        replication_factor: u32,
+        replicas: &[(PeerId, ReplicaState)],
+    ) -> CollectionPlacement {
+        CollectionPlacement {
+            shards: HashMap::from([(0, replicas.iter().copied().collect())]),
+            replication_factor,
+            ..Default::default()
+        }
+    }
+
+    fn collections(placement: CollectionPlacement) -> HashMap<CollectionId, CollectionPlacement> {
+        HashMap::from([("test".to_string(), placement)])
+    }
+
+    #[test]
+    fn test_replicate_missing_replica() {
+        let collections = collections(placement(2, &[(1, ReplicaState::Active)]));
+
+        let repairs = plan_replica_repair(
+            &collections,
+            &[1, 2],
+            &HashSet::new(),
+            TransferLimits::default(),
+        );
+
+        assert_eq!(repairs.len(), 1);
+        assert_eq!(repairs[0].healthy_replicas, 1);
+        assert_eq!(
+            repairs[0].decision,
+            RepairDecision::Replicate { from: 1, to: 2 },
+        );
+
+        let transfer = repairs[0].to_transfer(None).unwrap();
+        assert!(transfer.sync);
+        assert_eq!((transfer.from, transfer.to), (1, 2));
+    }
+
+    #[test]
+    fn test_replace_lost_replica_in_same_group() {
+        let mut placement = placement(
+            3,
+            &[
+                (1, ReplicaState::Dead),
+                (3, ReplicaState::Active),
+                (5, ReplicaState::Active),
+            ],
+        );
+        // Peers 1, 6 in zone `a`, peers 2, 3 in zone `b`, peers 4, 5 in zone `c`
+        placement.peer_groups = PeerGroups::from_groups(HashMap::from([
+            (1, "a".to_string()),
+            (6, "a".to_string()),
+            (2, "b".to_string()),
+            (3, "b".to_string()),
+            (4, "c".to_string()),
+            (5, "c".to_string()),
+        ]));
+        let collections = collections(placement);
+        let lost = HashSet::from([("test".to_string(), 0, 1)]);
+
+        let repairs = plan_replica_repair(
+            &collections,
+            &[1, 2, 3, 4, 5, 6],
+            &lost,
+            TransferLimits::default(),
+        );
+
+        assert_eq!(repairs.len(), 1);
+        assert!(matches!(
+            repairs[0].decision,
+            RepairDecision::Replicate { to: 6, .. },
+        ));
+    }
+
+    #[test]
+    fn test_fully_replicated_shard_is_left_alone() {
+        let collections = collections(placement(
+            2,
+            &[(1, ReplicaState::Active), (2, ReplicaState::Partial)],
+        ));
+
+        let repairs = plan_replica_repair(
+            &collections,
+            &[1, 2, 3],
+            &HashSet::new(),
+            TransferLimits::default(),
+        );
+
+        assert!(repairs.is_empty());
+    }
+
+    #[test]
+    fn test_dead_replica_within_grace_period() {
+        let dead = collections(placement(
+            2,
+            &[(1, ReplicaState::Active), (2, ReplicaState::Dead)],
+        ));
+
+        let now = Instant::now();
+        let grace = Duration::from_secs(60);
+
+        let mut tracker = DeadReplicaTracker::default();
+        tracker.update(&dead, now);
+
+        let lost_replicas = tracker.lost_replicas(grace, now);
+        assert!(lost_replicas.is_empty());
+
+        let later = now + grace;
+        tracker.update(&dead, later);
+
+        let lost_replicas = tracker.lost_replicas(grace, later);
+        assert_eq!(lost_replicas, HashSet::from([("test".to_string(), 0, 2)]));
+
+        // Peer 2 holding the dead replica is never a target
+        let repairs =
+            plan_replica_repair(&dead, &[1, 2, 3], &lost_replicas, TransferLimits::default());
+        assert_eq!(
+            repairs[0].decision,
+            RepairDecision::Replicate { from: 1, to: 3 },
+        );
+
+        // Recovered replica is forgotten
+        let recovered = collections(placement(
+            2,
+            &[(1, ReplicaState::Active), (2, ReplicaState::Active)],
+        ));
+        tracker.update(&recovered, later);
+        assert!(tracker.lost_replicas(grace, later).is_empty());
+    }
+
+    #[test]
+    fn test_prefer_peer_with_least_replicas() {
+        let collections = HashMap::from([
+            (
+                "a".to_string(),
+                placement(1, &[(1, ReplicaState::Active), (2, ReplicaState::Active)]),
+            ),
+            ("b".to_string(), placement(2, &[(1, ReplicaState::Active)])),
+        ]);
+
+        let repairs = plan_replica_repair(
+            &collections,
+            &[1, 2, 3],
+            &HashSet::new(),
+            TransferLimits::default(),
+        );
+
+        assert_eq!(repairs.len(), 1);
+        assert_eq!(repairs[0].collection_name, "b");
+        assert_eq!(
+            repairs[0].decision,
+            RepairDecision::Replicate { from: 1, to: 3 },
+        );
+    }
+
+    #[test]
+    fn test_no_repair_possible() {
+        let repairs = plan_replica_repair(
+            &collections(placement(3, &[(1, ReplicaState::Active)])),
+            &[1],
+            &HashSet::new(),
+            TransferLimits::default(),
+        );
+        assert_eq!(repairs[0].decision, RepairDecision::NoTargetPeer);
+
+        let repairs = plan_replica_repair(
+            &collections(placement(2, &[(1, ReplicaState::Partial)])),
+            &[1, 2],
+            &HashSet::new(),
+            TransferLimits::default(),
+        );
+        assert_eq!(repairs[0].decision, RepairDecision::NoActiveReplica);
+
+        let mut resharding = placement(2, &[(1, ReplicaState::Active)]);
+        resharding.is_resharding = true;
+        let repairs = plan_replica_repair(
+            &collections(resharding),
+            &[1, 2],
+            &HashSet::new(),
+            TransferLimits::default(),
+        );
+        assert_eq!(repairs[0].decision, RepairDecision::Resharding);
+    }
+
+    #[test]
+    fn test_respect_transfer_limits() {
+        let mut collection = placement(2, &[(1, ReplicaState::Active)]);
+        collection
+            .shards
+            .insert(1, HashMap::from([(1, ReplicaState::Active)]));
+        let collections = collections(collection);
+
+        let limits = TransferLimits {
+            incoming: None,
+            outgoing: Some(1),
+        };
+
+        let repairs = plan_replica_repair(&collections, &[1, 2], &HashSet::new(), limits);
+
+        assert_eq!(repairs.len(), 2);
+        assert_eq!(
+            repairs[0].decision,
+            RepairDecision::Replicate { from: 1, to: 2 },
+        );
+        assert_eq!(repairs[1].decision, RepairDecision::TransferLimitReached);
+    }
+}
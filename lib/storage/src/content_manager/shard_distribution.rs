@@ -4,6 +4,7 @@ use std::iter::repeat_with;
 use std::num::NonZeroU32;
 
 use collection::shards::collection_shard_distribution::CollectionShardDistribution;
+use collection::shards::peer_groups::PeerGroups;
 use collection::shards::shard::{PeerId, ShardId};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -101,6 +102,47 @@ impl ShardDistributionProposal {
         Self { distribution }
     }
 
+    /// Builds a proposal for the distribution of shards, spreading replicas of each shard across
+    /// the given peer groups as evenly as possible.
+    /// Within that, replicas are allocated on peers with the least number of shards first.
+    pub fn new_spread(
+        shard_number: NonZeroU32,
+        replication_factor: NonZeroU32,
+        known_peers: &[PeerId],
+        peer_groups: &PeerGroups,
+    ) -> Self {
+        let mut peers: Vec<_> = known_peers
+            .iter()
+            .map(|peer| PeerShardCount::new(*peer))
+            .collect();
+
+        // There should not be more than 1 replica per peer
+        let replica_number = cmp::min(replication_factor.get() as usize, known_peers.len());
+
+        let distribution = (0..shard_number.get())
+            .map(|shard_id| {
+                let mut replicas = Vec::with_capacity(replica_number);
+
+                for _ in 0..replica_number {
+                    let peer = peers
+                        .iter_mut()
+                        .filter(|peer| !replicas.contains(&peer.peer_id))
+                        .min_by(|a, b| {
+                            let a_replicas = peer_groups.replicas_in_group(&replicas, a.peer_id);
+                            let b_replicas = peer_groups.replicas_in_group(&replicas, b.peer_id);
+                            a_replicas.cmp(&b_replicas).then_with(|| a.cmp(b))
+                        })
+                        .unwrap();
+                    replicas.push(peer.get_and_inc_shard_count());
+                }
+
+                (shard_id, replicas)
+            })
+            .collect();
+
+        Self { distribution }
+    }
+
     pub fn local_shards_for(&self, peer_id: PeerId) -> Vec<ShardId> {
         self.distribution
             .iter()
@@ -138,7 +180,7 @@ impl From<ShardDistributionProposal> for CollectionShardDistribution {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use std::collections::{HashMap, HashSet};
 
     use super::*;
 
@@ -209,4 +251,40 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_distribution_spread_across_groups() {
+        let known_peers = vec![1, 2, 3, 4, 5, 6];
+        let peer_groups = PeerGroups::from_groups(HashMap::from([
+            (1, "a".to_string()),
+            (2, "a".to_string()),
+            (3, "b".to_string()),
+            (4, "b".to_string()),
+            (5, "c".to_string()),
+            (6, "c".to_string()),
+        ]));
+
+        let distribution = ShardDistributionProposal::new_spread(
+            NonZeroU32::new(6).unwrap(),
+            NonZeroU32::new(3).unwrap(),
+            &known_peers,
+            &peer_groups,
+        );
+
+        let mut shard_counts: HashMap<PeerId, usize> = HashMap::new();
+        for (_shard_id, peers) in &distribution.distribution {
+            let groups: HashSet<_> = peers
+                .iter()
+                .map(|peer_id| peer_groups.group(*peer_id))
+                .collect();
+            assert_eq!(groups.len(), 3, "replicas must be in different groups");
+
+            for peer_id in peers {
+                *shard_counts.entry(*peer_id).or_default() += 1;
+            }
+        }
+
+        assert_eq!(shard_counts.len(), known_peers.len());
+        assert!(shard_counts.values().all(|count| *count == 3));
+    }
 }
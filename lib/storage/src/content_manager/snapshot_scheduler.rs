@@ -0,0 +1,367 @@
+//! Scheduled snapshots and their retention.
+//!
+//! Every schedule periodically creates a snapshot of a single collection, or a full storage
+//! snapshot. After each run, snapshots of the same kind are pruned according to the retention
+//! policy of the schedule, see [`expired_snapshots`].
+//!
+//! Only snapshots created by schedules of this peer are considered for retention. They are tagged
+//! with [`SCHEDULED_SNAPSHOT_TAG`] in their name. Snapshots created on request, shard snapshots,
+//! and snapshots of other collections or other peers stored in the same location, are never
+//! deleted.
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, NaiveDateTime, Utc};
+use collection::operations::snapshot_ops::SnapshotDescription;
+use collection::shards::shard::PeerId;
+use schemars::JsonSchema;
+use segment::common::anonymize::Anonymize;
+use serde::Serialize;
+
+use crate::content_manager::toc::FULL_SNAPSHOT_FILE_NAME;
+use crate::types::{SnapshotRetentionConfig, SnapshotScheduleConfig};
+
+/// Tag in the name of snapshots created by a schedule
+pub const SCHEDULED_SNAPSHOT_TAG: &str = "scheduled";
+
+/// Parse a cron expression, with seconds as the first field
+///
+/// For example `0 30 3 * * *` runs every day at 03:30 UTC.
+pub fn parse_schedule(expression: &str) -> Result<cron::Schedule, String> {
+    cron::Schedule::from_str(expression).map_err(|err| err.to_string())
+}
+
+/// Validate cron expression of a snapshot schedule
+pub fn validate_schedule(expression: &str) -> Result<(), validator::ValidationError> {
+    parse_schedule(expression).map(|_| ()).map_err(|err| {
+        let mut error = validator::ValidationError::new("invalid_cron_schedule");
+        error.add_param("message".into(), &err);
+        error
+    })
+}
+
+/// Status of a snapshot schedule, reported in telemetry
+#[derive(Serialize, Clone, Debug, JsonSchema)]
+pub struct SnapshotScheduleStatus {
+    /// Snapshotted collection, none for full storage snapshots
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
+    pub schedule: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_run: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_success: Option<DateTime<Utc>>,
+    /// Name of the last snapshot created by this schedule
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_snapshot: Option<String>,
+    /// Error of the last run, if it failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Number of runs failed in a row
+    pub consecutive_failures: usize,
+    /// Total number of snapshots deleted by the retention policy
+    pub deleted_snapshots: usize,
+}
+
+impl SnapshotScheduleStatus {
+    pub fn new(config: &SnapshotScheduleConfig) -> Self {
+        Self {
+            collection: config.collection.clone(),
+            schedule: config.schedule.clone(),
+            next_run: None,
+            last_run: None,
+            last_success: None,
+            last_snapshot: None,
+            last_error: None,
+            consecutive_failures: 0,
+            deleted_snapshots: 0,
+        }
+    }
+}
+
+impl Anonymize for SnapshotScheduleStatus {
+    fn anonymize(&self) -> Self {
+        Self {
+            collection: self.collection.anonymize(),
+            schedule: self.schedule.clone(),
+            next_run: self.next_run,
+            last_run: self.last_run.anonymize(),
+            last_success: self.last_success.anonymize(),
+            last_snapshot: self.last_snapshot.anonymize(),
+            last_error: self.last_error.anonymize(),
+            consecutive_failures: self.consecutive_failures,
+            deleted_snapshots: self.deleted_snapshots.anonymize(),
+        }
+    }
+}
+
+/// Whether the snapshot was created by a schedule of the given collection on the given peer
+///
+/// Scheduled collection snapshots are named `<collection>-<peer_id>-scheduled-<time>.snapshot`,
+/// scheduled full storage snapshots are named `full-snapshot-scheduled-<time>.snapshot`.
+pub fn is_scheduled_snapshot(
+    snapshot_name: &str,
+    collection_name: Option<&str>,
+    this_peer_id: PeerId,
+) -> bool {
+    let prefix = match collection_name {
+        Some(collection_name) => {
+            format!("{collection_name}-{this_peer_id}-{SCHEDULED_SNAPSHOT_TAG}-")
+        }
+        None => format!("{FULL_SNAPSHOT_FILE_NAME}-{SCHEDULED_SNAPSHOT_TAG}-"),
+    };
+
+    snapshot_name.starts_with(&prefix) && snapshot_name.ends_with(".snapshot")
+}
+
+/// Select snapshots to delete according to the retention policy
+///
+/// A snapshot is kept if it matches any of the rules:
+///
+/// - `keep_last`: it is one of the last N snapshots
+/// - `keep_daily`: it is the last snapshot of one of the last N days having snapshots
+/// - `keep_weekly`: it is the last snapshot of one of the last N ISO weeks having snapshots
+///
+/// If no rule is set, all snapshots are kept. Snapshots without creation time are always kept.
+pub fn expired_snapshots(
+    snapshots: &[SnapshotDescription],
+    retention: &SnapshotRetentionConfig,
+) -> Vec<String> {
+    if retention.is_keep_all() {
+        return Vec::new();
+    }
+
+    let mut dated: Vec<_> = snapshots
+        .iter()
+        .filter_map(|snapshot| Some((snapshot.creation_time?, snapshot.name.as_str())))
+        .collect();
+
+    // Newest first, name is a tie breaker for snapshots created within the same second
+    dated.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut keep = HashSet::new();
+
+    keep.extend(
+        dated
+            .iter()
+            .take(retention.keep_last.unwrap_or(0))
+            .map(|(_, name)| *name),
+    );
+
+    keep_last_per_period(&dated, retention.keep_daily, &mut keep, |time| time.date());
+    keep_last_per_period(&dated, retention.keep_weekly, &mut keep, |time| {
+        time.iso_week()
+    });
+
+    dated
+        .into_iter()
+        .filter(|(_, name)| !keep.contains(name))
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+/// Keep the newest snapshot in each of the `periods` most recent periods having snapshots
+///
+/// `snapshots` must be sorted newest first.
+fn keep_last_per_period<'a, P: Copy + PartialEq>(
+    snapshots: &[(NaiveDateTime, &'a str)],
+    periods: Option<usize>,
+    keep: &mut HashSet<&'a str>,
+    period_of: impl Fn(&NaiveDateTime) -> P,
+) {
+    let Some(periods) = periods else {
+        return;
+    };
+
+    let mut last_period = None;
+    let mut kept_periods = 0;
+
+    for (time, name) in snapshots {
+        if kept_periods >= periods {
+            break;
+        }
+
+        let period = period_of(time);
+        if last_period == Some(period) {
+            continue;
+        }
+
+        keep.insert(*name);
+        last_period = Some(period);
+        kept_periods += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(time: &str) -> SnapshotDescription {
+        let creation_time = NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M").unwrap();
+        SnapshotDescription {
+            name: format!(
+                "test-1-scheduled-{}.snapshot",
+                creation_time.format("%Y-%m-%d-%H-%M-%S")
+            ),
+            creation_time: Some(creation_time),
+            size: 0,
+            checksum: None,
+        }
+    }
+
+    fn names(snapshots: &[&SnapshotDescription]) -> Vec<String> {
+        let mut names: Vec<_> = snapshots.iter().map(|s| s.name.clone()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_parse_schedule() {
+        assert!(parse_schedule("0 30 3 * * *").is_ok());
+        assert!(parse_schedule("@daily").is_ok());
+        assert!(parse_schedule("30 3 * *").is_err());
+        assert!(validate_schedule("not a schedule").is_err());
+    }
+
+    #[test]
+    fn test_is_scheduled_snapshot() {
+        assert!(is_scheduled_snapshot(
+            "test-1-scheduled-2024-05-01-03-00-00.snapshot",
+            Some("test"),
+            1,
+        ));
+        // Created on request
+        assert!(!is_scheduled_snapshot(
+            "test-1-2024-05-01-03-00-00.snapshot",
+            Some("test"),
+            1,
+        ));
+        assert!(!is_scheduled_snapshot(
+            "test-2-scheduled-2024-05-01-03-00-00.snapshot",
+            Some("test"),
+            1,
+        ));
+        assert!(!is_scheduled_snapshot(
+            "test-shard-1-2024-05-01-03-00-00.snapshot",
+            Some("test"),
+            1,
+        ));
+        assert!(!is_scheduled_snapshot(
+            "test-1-scheduled-2024-05-01-03-00-00.snapshot.checksum",
+            Some("test"),
+            1,
+        ));
+        assert!(is_scheduled_snapshot(
+            "full-snapshot-scheduled-2024-05-01-03-00-00.snapshot",
+            None,
+            1,
+        ));
+        assert!(!is_scheduled_snapshot(
+            "full-snapshot-2024-05-01-03-00-00.snapshot",
+            None,
+            1,
+        ));
+    }
+
+    #[test]
+    fn test_keep_manual_snapshots() {
+        let mut manual = snapshot("2024-05-01 03:00");
+        manual.name = "test-1-2024-05-01-03-00-00.snapshot".to_string();
+
+        let snapshots = [
+            manual,
+            snapshot("2024-05-02 03:00"),
+            snapshot("2024-05-03 03:00"),
+        ];
+
+        let retention = SnapshotRetentionConfig {
+            keep_last: Some(1),
+            ..Default::default()
+        };
+
+        // Same selection as the scheduler does before applying the retention policy
+        let scheduled: Vec<_> = snapshots
+            .iter()
+            .filter(|snapshot| is_scheduled_snapshot(&snapshot.name, Some("test"), 1))
+            .cloned()
+            .collect();
+
+        let expired = expired_snapshots(&scheduled, &retention);
+        assert_eq!(expired, names(&[&snapshots[1]]));
+    }
+
+    #[test]
+    fn test_keep_all_without_rules() {
+        let snapshots = [snapshot("2024-05-01 03:00"), snapshot("2024-05-02 03:00")];
+
+        let expired = expired_snapshots(&snapshots, &SnapshotRetentionConfig::default());
+        assert!(expired.is_empty());
+    }
+
+    #[test]
+    fn test_keep_last() {
+        let snapshots = [
+            snapshot("2024-05-01 03:00"),
+            snapshot("2024-05-03 03:00"),
+            snapshot("2024-05-02 03:00"),
+        ];
+
+        let retention = SnapshotRetentionConfig {
+            keep_last: Some(2),
+            ..Default::default()
+        };
+
+        let mut expired = expired_snapshots(&snapshots, &retention);
+        expired.sort();
+        assert_eq!(expired, names(&[&snapshots[0]]));
+    }
+
+    #[test]
+    fn test_keep_daily_and_weekly() {
+        let snapshots = [
+            // Week of 2024-04-22
+            snapshot("2024-04-23 03:00"),
+            snapshot("2024-04-25 03:00"),
+            // Week of 2024-04-29
+            snapshot("2024-04-30 03:00"),
+            snapshot("2024-05-01 03:00"),
+            snapshot("2024-05-01 15:00"),
+            snapshot("2024-05-02 03:00"),
+            snapshot("2024-05-02 15:00"),
+        ];
+
+        let retention = SnapshotRetentionConfig {
+            keep_last: Some(1),
+            keep_daily: Some(2),
+            keep_weekly: Some(2),
+        };
+
+        let mut expired = expired_snapshots(&snapshots, &retention);
+        expired.sort();
+
+        // Kept: last of 05-02 (last, daily, weekly), last of 05-01 (daily),
+        // last of week 2024-04-22 (weekly)
+        assert_eq!(
+            expired,
+            names(&[&snapshots[0], &snapshots[2], &snapshots[3], &snapshots[5]]),
+        );
+    }
+
+    #[test]
+    fn test_keep_undated_snapshots() {
+        let mut undated = snapshot("2024-05-01 03:00");
+        undated.creation_time = None;
+
+        let snapshots = [undated, snapshot("2024-05-02 03:00")];
+
+        let retention = SnapshotRetentionConfig {
+            keep_last: Some(1),
+            ..Default::default()
+        };
+
+        assert!(expired_snapshots(&snapshots, &retention).is_empty());
+    }
+}
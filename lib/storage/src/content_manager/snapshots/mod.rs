@@ -13,7 +13,7 @@ use tokio::task::JoinHandle;
 
 use crate::content_manager::toc::FULL_SNAPSHOT_FILE_NAME;
 use crate::dispatcher::Dispatcher;
-use crate::rbac::{Access, AccessRequirements};
+use crate::rbac::{Access, AccessRequirements, OperationClass};
 use crate::{StorageError, TableOfContent};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -48,8 +48,13 @@ pub async fn do_delete_collection_snapshot(
     collection_name: &str,
     snapshot_name: &str,
 ) -> Result<JoinHandle<Result<bool, StorageError>>, StorageError> {
-    let collection_pass = access
-        .check_collection_access(collection_name, AccessRequirements::new().write().whole())?;
+    let collection_pass = access.check_collection_access(
+        collection_name,
+        AccessRequirements::new()
+            .write()
+            .whole()
+            .operations(&[OperationClass::Snapshot]),
+    )?;
     let toc = dispatcher.toc(&access);
     let snapshot_name = snapshot_name.to_string();
     let collection = toc.get_collection(&collection_pass).await?;
@@ -81,13 +86,15 @@ pub fn do_create_full_snapshot(
     access.check_global_access(AccessRequirements::new().manage())?;
     let toc = dispatcher.toc(&access).clone();
     Ok(tokio::spawn(async move {
-        _do_create_full_snapshot(&toc, access).await
+        _do_create_full_snapshot(&toc, access, None).await
     }))
 }
 
-async fn _do_create_full_snapshot(
+/// Create a full storage snapshot, with an optional `tag` in its name
+pub(crate) async fn _do_create_full_snapshot(
     toc: &TableOfContent,
     access: Access,
+    tag: Option<&str>,
 ) -> Result<SnapshotDescription, StorageError> {
     let snapshot_dir = Path::new(toc.snapshots_path()).to_path_buf();
 
@@ -99,7 +106,8 @@ async fn _do_create_full_snapshot(
     }
     let current_time = chrono::Utc::now().format("%Y-%m-%d-%H-%M-%S").to_string();
 
-    let snapshot_name = format!("{FULL_SNAPSHOT_FILE_NAME}-{current_time}.snapshot");
+    let tag = tag.map(|tag| format!("{tag}-")).unwrap_or_default();
+    let snapshot_name = format!("{FULL_SNAPSHOT_FILE_NAME}-{tag}{current_time}.snapshot");
 
     let collection_name_to_snapshot_path: HashMap<_, _> = created_snapshots
         .iter()
@@ -1,5 +1,6 @@
 use collection::collection::Collection;
 use collection::common::sha_256::{hash_file, hashes_equal};
+use collection::common::snapshot_encryption::decrypt_snapshot;
 use collection::config::CollectionConfig;
 use collection::operations::snapshot_ops::{SnapshotPriority, SnapshotRecover};
 use collection::shards::replica_set::ReplicaState;
@@ -104,6 +105,18 @@ async fn _do_recover_from_snapshot(
 
     log::debug!("Snapshot downloaded to {}", snapshot_path.display());
 
+    // Snapshots encrypted at rest are decrypted transparently, the checksum covers the ciphertext
+    let snapshot_manager = toc.get_snapshots_storage_manager()?;
+    let decrypted_snapshot_path = decrypt_snapshot(
+        snapshot_manager.encryption(),
+        &snapshot_path,
+        download_dir.path(),
+    )
+    .await?;
+    let snapshot_path = decrypted_snapshot_path
+        .as_ref()
+        .map_or(snapshot_path, |path| path.to_path_buf());
+
     let temp_storage_path = toc.optional_temp_or_storage_temp_path()?;
 
     let tmp_collection_dir = tempfile::Builder::new()
@@ -122,6 +122,7 @@ impl TableOfContent {
         consensus_manager::CollectionsSnapshot {
             collections,
             aliases: self.alias_persistence.read().await.state().clone(),
+            token_revocations: self.token_revocations.read().await.state().clone(),
         }
     }
 
@@ -240,6 +241,11 @@ impl TableOfContent {
                 .await
                 .apply_state(data.aliases)?;
 
+            self.token_revocations
+                .write()
+                .await
+                .apply_state(data.token_revocations)?;
+
             Ok(())
         })
     }
@@ -6,7 +6,7 @@ use collection::config::ShardingMethod;
 use collection::events::{CollectionDeletedEvent, IndexCreatedEvent};
 use collection::shards::collection_shard_distribution::CollectionShardDistribution;
 use collection::shards::replica_set::ReplicaState;
-use collection::shards::transfer::ShardTransfer;
+use collection::shards::transfer::{ShardTransfer, ShardTransferConsensus};
 use collection::shards::{transfer, CollectionId};
 use uuid::Uuid;
 
@@ -84,6 +84,23 @@ impl TableOfContent {
                 log::debug!("Set shard replica state {:?}", operation);
                 self.set_shard_replica_state(operation).await.map(|()| true)
             }
+            CollectionMetaOperations::RevokeToken {
+                revocation,
+                timestamp,
+            } => {
+                log::info!("Revoking token {revocation:?}");
+                self.token_revocations
+                    .write()
+                    .await
+                    .revoke(revocation, timestamp)
+            }
+            CollectionMetaOperations::RestoreToken { token, timestamp } => {
+                log::info!("Restoring revoked token {token:?}");
+                self.token_revocations
+                    .write()
+                    .await
+                    .restore(&token, timestamp)
+            }
             CollectionMetaOperations::Nop { .. } => Ok(true),
             CollectionMetaOperations::CreateShardKey(create_shard_key) => {
                 log::debug!("Create shard key {:?}", create_shard_key);
@@ -274,24 +291,30 @@ impl TableOfContent {
         let collection = self.get_collection_unchecked(&collection).await?;
 
         match operation {
-            ReshardingOperation::Start {
-                peer_id,
-                shard_id,
-                shard_key,
-            } => {
-                collection
-                    .start_resharding(peer_id, shard_id, shard_key)
-                    .await?;
+            ReshardingOperation::Start(key) => {
+                let consensus = self
+                    .shard_transfer_dispatcher
+                    .lock()
+                    .clone()
+                    .map(|consensus| Box::new(consensus) as Box<dyn ShardTransferConsensus>);
+
+                collection.start_resharding(key, consensus).await?;
             }
 
-            ReshardingOperation::Abort {
-                peer_id,
-                shard_id,
-                shard_key,
-            } => {
-                collection
-                    .abort_resharding(peer_id, shard_id, shard_key)
-                    .await?;
+            ReshardingOperation::CommitRead(key) => {
+                collection.commit_read_hashring(key).await?;
+            }
+
+            ReshardingOperation::CommitWrite(key) => {
+                collection.commit_write_hashring(key).await?;
+            }
+
+            ReshardingOperation::Finish(key) => {
+                collection.finish_resharding(key).await?;
+            }
+
+            ReshardingOperation::Abort(key) => {
+                collection.abort_resharding(key).await?;
             }
         }
 
@@ -2,7 +2,9 @@ use std::collections::BTreeMap;
 use std::num::NonZeroU32;
 
 use collection::collection::Collection;
-use collection::config::{self, CollectionConfig, CollectionParams, ShardingMethod};
+use collection::config::{
+    self, CollectionConfig, CollectionParams, PayloadStorage, ShardingMethod,
+};
 use collection::operations::config_diff::DiffConfig as _;
 use collection::operations::types::{
     check_sparse_compatible, CollectionResult, SparseVectorParams, VectorsConfig,
@@ -36,10 +38,13 @@ impl TableOfContent {
             shard_number,
             sharding_method,
             on_disk_payload,
+            payload_storage,
+            storage_format,
             hnsw_config: hnsw_config_diff,
             wal_config: wal_config_diff,
             optimizers_config: optimizers_config_diff,
             replication_factor,
+            replica_placement,
             write_consistency_factor,
             init_from,
             quantization_config,
@@ -63,6 +68,18 @@ impl TableOfContent {
             )));
         }
 
+        if let Some(payload_storage) = payload_storage {
+            payload_storage.check_on_disk_payload(on_disk_payload)?;
+        }
+
+        if storage_format.unwrap_or_default().is_flat()
+            && payload_storage != Some(PayloadStorage::Mmap)
+        {
+            return Err(StorageError::bad_input(
+                "Flat storage format requires `mmap` payload storage",
+            ));
+        }
+
         if let Some(init_from) = &init_from {
             self.check_collections_compatibility(&vectors, &sparse_vectors, &init_from.collection)
                 .await?;
@@ -138,12 +155,18 @@ impl TableOfContent {
                 description: "`shard_number` cannot be 0".to_string(),
             })?,
             sharding_method,
-            on_disk_payload: on_disk_payload.unwrap_or(self.storage_config.on_disk_payload),
+            on_disk_payload: payload_storage
+                .map(|payload_storage| payload_storage.is_on_disk())
+                .or(on_disk_payload)
+                .unwrap_or(self.storage_config.on_disk_payload),
+            payload_storage,
+            storage_format,
             replication_factor: NonZeroU32::new(replication_factor).ok_or(
                 StorageError::BadInput {
                     description: "`replication_factor` cannot be 0".to_string(),
                 },
             )?,
+            replica_placement,
             write_consistency_factor: NonZeroU32::new(write_consistency_factor).ok_or(
                 StorageError::BadInput {
                     description: "`write_consistency_factor` cannot be 0".to_string(),
@@ -5,6 +5,9 @@ mod create_collection;
 mod locks;
 mod point_ops;
 mod point_ops_internal;
+mod rebalance;
+mod replica_repair;
+mod snapshot_scheduler;
 mod snapshots;
 mod temp_directories;
 pub mod transfer;
@@ -25,6 +28,7 @@ use collection::collection::{Collection, RequestShardTransfer};
 use collection::config::{default_replication_factor, CollectionConfig};
 use collection::operations::types::*;
 use collection::shards::channel_service::ChannelService;
+use collection::shards::peer_groups::PeerGroups;
 use collection::shards::replica_set;
 use collection::shards::replica_set::{AbortShardTransfer, ReplicaState};
 use collection::shards::shard::{PeerId, ShardId};
@@ -45,6 +49,8 @@ use crate::content_manager::collections_ops::{Checker, Collections};
 use crate::content_manager::consensus::operation_sender::OperationSender;
 use crate::content_manager::errors::StorageError;
 use crate::content_manager::shard_distribution::ShardDistributionProposal;
+use crate::content_manager::snapshot_scheduler::SnapshotScheduleStatus;
+use crate::content_manager::token_revocation::{TokenRevocationPersistence, TokenRevocations};
 use crate::rbac::{Access, AccessRequirements, CollectionPass};
 use crate::types::{PeerAddressById, StorageConfig};
 use crate::ConsensusOperations;
@@ -66,6 +72,7 @@ pub struct TableOfContent {
     /// Assigns CPU permits to tasks to limit overall resource utilization.
     optimizer_cpu_budget: CpuBudget,
     alias_persistence: RwLock<AliasPersistence>,
+    token_revocations: RwLock<TokenRevocationPersistence>,
     pub this_peer_id: PeerId,
     channel_service: ChannelService,
     /// Backlink to the consensus, if none - single node mode
@@ -83,6 +90,8 @@ pub struct TableOfContent {
     collection_create_lock: Mutex<()>,
     /// Dispatcher for shard transfer to access consensus.
     shard_transfer_dispatcher: parking_lot::Mutex<Option<ShardTransferDispatcher>>,
+    /// Status of each configured snapshot schedule, in configuration order.
+    snapshot_schedule_statuses: parking_lot::Mutex<Vec<SnapshotScheduleStatus>>,
 }
 
 impl TableOfContent {
@@ -168,6 +177,9 @@ impl TableOfContent {
         let alias_path = Path::new(&storage_config.storage_path).join(ALIASES_PATH);
         let alias_persistence =
             AliasPersistence::open(alias_path).expect("Can't open database by the provided config");
+        let token_revocations =
+            TokenRevocationPersistence::open(Path::new(&storage_config.storage_path))
+                .expect("Can't open token revocations by the provided config");
 
         let rate_limiter = match storage_config.performance.update_rate_limit {
             Some(limit) => Some(Semaphore::new(limit)),
@@ -194,6 +206,7 @@ impl TableOfContent {
             general_runtime,
             optimizer_cpu_budget,
             alias_persistence: RwLock::new(alias_persistence),
+            token_revocations: RwLock::new(token_revocations),
             this_peer_id,
             channel_service,
             consensus_proposal_sender,
@@ -202,6 +215,13 @@ impl TableOfContent {
             update_rate_limiter: rate_limiter,
             collection_create_lock: Default::default(),
             shard_transfer_dispatcher: Default::default(),
+            snapshot_schedule_statuses: parking_lot::Mutex::new(
+                storage_config
+                    .snapshot_schedules
+                    .iter()
+                    .map(SnapshotScheduleStatus::new)
+                    .collect(),
+            ),
         }
     }
 
@@ -340,6 +360,28 @@ impl TableOfContent {
         Ok(aliases)
     }
 
+    /// List of revoked token IDs and subjects
+    pub async fn list_token_revocations(
+        &self,
+        access: &Access,
+    ) -> Result<TokenRevocations, StorageError> {
+        access.check_global_access(AccessRequirements::new().manage())?;
+        Ok(self.token_revocations.read().await.state().clone())
+    }
+
+    /// Check if a token with the given ID (`jti` claim) or subject (`sub` claim) is revoked
+    pub async fn is_token_revoked(&self, token_id: Option<&str>, subject: Option<&str>) -> bool {
+        if token_id.is_none() && subject.is_none() {
+            return false;
+        }
+
+        self.token_revocations
+            .read()
+            .await
+            .state()
+            .is_revoked(token_id, subject)
+    }
+
     pub async fn suggest_shard_distribution(
         &self,
         op: &CreateCollectionOperation,
@@ -365,8 +407,21 @@ impl TableOfContent {
             .and_then(NonZeroU32::new)
             .unwrap_or_else(default_replication_factor);
 
-        let shard_distribution =
-            ShardDistributionProposal::new(shard_number, replication_factor, &known_peers);
+        let shard_distribution = match &op.create_collection.replica_placement {
+            Some(replica_placement) => {
+                let peer_groups = PeerGroups::new(
+                    Some(replica_placement),
+                    &self.channel_service.id_to_metadata.read(),
+                );
+                ShardDistributionProposal::new_spread(
+                    shard_number,
+                    replication_factor,
+                    &known_peers,
+                    &peer_groups,
+                )
+            }
+            None => ShardDistributionProposal::new(shard_number, replication_factor, &known_peers),
+        };
 
         log::debug!(
             "Suggesting distribution for {} shards for collection '{}' among {} peers {:?}",
@@ -710,8 +765,24 @@ impl TableOfContent {
     }
 
     /// Insert dispatcher into table of contents for shard transfer.
+    ///
+    /// This also resumes resharding driven by this peer, the driver does not survive a restart.
     pub fn with_shard_transfer_dispatcher(&self, dispatcher: ShardTransferDispatcher) {
-        self.shard_transfer_dispatcher.lock().replace(dispatcher);
+        self.shard_transfer_dispatcher
+            .lock()
+            .replace(dispatcher.clone());
+
+        let collections = self.collections.clone();
+        self.general_runtime.spawn(async move {
+            for (collection_name, collection) in collections.read().await.iter() {
+                if let Err(err) = collection
+                    .resume_resharding(Box::new(dispatcher.clone()))
+                    .await
+                {
+                    log::error!("Failed to resume resharding of {collection_name}: {err}");
+                }
+            }
+        });
     }
 
     pub fn get_channel_service(&self) -> &ChannelService {
@@ -5,6 +5,7 @@ use collection::grouping::group_by::GroupRequest;
 use collection::grouping::GroupBy;
 use collection::operations::consistency_params::ReadConsistency;
 use collection::operations::point_ops::WriteOrdering;
+use collection::operations::query_explain::QueryExplain;
 use collection::operations::shard_selector_internal::ShardSelectorInternal;
 use collection::operations::types::*;
 use collection::operations::universal_query::collection_query::CollectionQueryRequest;
@@ -129,6 +130,25 @@ impl TableOfContent {
             .map_err(|err| err.into())
     }
 
+    /// Search, and explain how the request was executed in each shard and segment
+    pub async fn search_with_explain(
+        &self,
+        collection_name: &str,
+        mut request: CoreSearchRequest,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: ShardSelectorInternal,
+        access: Access,
+        timeout: Option<Duration>,
+    ) -> Result<(Vec<ScoredPoint>, QueryExplain), StorageError> {
+        let collection_pass = access.check_point_op(collection_name, &mut request)?;
+
+        let collection = self.get_collection(&collection_pass).await?;
+        collection
+            .search_with_explain(request, read_consistency, &shard_selection, timeout)
+            .await
+            .map_err(|err| err.into())
+    }
+
     /// Count points in the collection.
     ///
     /// # Arguments
@@ -158,6 +178,24 @@ impl TableOfContent {
             .map_err(|err| err.into())
     }
 
+    /// Count, and explain how the request was executed in each shard and segment
+    pub async fn count_with_explain(
+        &self,
+        collection_name: &str,
+        mut request: CountRequestInternal,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: ShardSelectorInternal,
+        access: Access,
+    ) -> Result<(CountResult, QueryExplain), StorageError> {
+        let collection_pass = access.check_point_op(collection_name, &mut request)?;
+
+        let collection = self.get_collection(&collection_pass).await?;
+        collection
+            .count_with_explain(request, read_consistency, &shard_selection)
+            .await
+            .map_err(|err| err.into())
+    }
+
     /// Return specific points by IDs
     ///
     /// # Arguments
@@ -294,6 +332,24 @@ impl TableOfContent {
             .map_err(|err| err.into())
     }
 
+    /// Scroll, and explain how the request was executed in each shard and segment
+    pub async fn scroll_with_explain(
+        &self,
+        collection_name: &str,
+        mut request: ScrollRequestInternal,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: ShardSelectorInternal,
+        access: Access,
+    ) -> Result<(ScrollResult, QueryExplain), StorageError> {
+        let collection_pass = access.check_point_op(collection_name, &mut request)?;
+
+        let collection = self.get_collection(&collection_pass).await?;
+        collection
+            .scroll_by_with_explain(request, read_consistency, &shard_selection)
+            .await
+            .map_err(|err| err.into())
+    }
+
     pub async fn query(
         &self,
         collection_name: &str,
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use api::grpc::qdrant::qdrant_internal_client::QdrantInternalClient;
+use api::grpc::qdrant::GetPeerLoadRequest;
+use collection::shards::shard::PeerId;
+use collection::shards::transfer::ShardTransferMethod;
+use collection::shards::CollectionId;
+use common::types::TelemetryDetail;
+use futures::future;
+use tonic::transport::Uri;
+
+use super::TableOfContent;
+use crate::content_manager::errors::StorageError;
+use crate::content_manager::rebalancer::{
+    self, CollectionPlacement, PeerLoad, RebalancePlan, TransferLimits,
+};
+use crate::types::{PeerAddressById, RebalancerConfig};
+
+impl TableOfContent {
+    pub fn rebalancer_config(&self) -> &RebalancerConfig {
+        &self.storage_config.rebalancer
+    }
+
+    /// Shard transfer method to use for rebalancing, `None` to choose automatically
+    pub fn rebalance_transfer_method(&self) -> Option<ShardTransferMethod> {
+        self.storage_config.shard_transfer_method
+    }
+
+    /// Free disk space of the storage directory and disk usage of local shards on this peer
+    pub async fn peer_load(&self) -> PeerLoad {
+        let storage_path = PathBuf::from(&self.storage_config.storage_path);
+        let free_disk_bytes =
+            match tokio::task::spawn_blocking(move || fs4::available_space(storage_path)).await {
+                Ok(Ok(free_disk_bytes)) => Some(free_disk_bytes),
+                Ok(Err(err)) => {
+                    log::debug!("Failed to get free disk space of storage directory: {err}");
+                    None
+                }
+                Err(err) => {
+                    log::debug!("Failed to join free disk space task: {err}");
+                    None
+                }
+            };
+
+        let mut shard_sizes = HashMap::new();
+
+        for collection in self.collections.read().await.values() {
+            let telemetry = collection
+                .get_telemetry_data(TelemetryDetail::default())
+                .await;
+
+            for shard in telemetry.shards {
+                let Some(local) = shard.local else {
+                    continue;
+                };
+
+                let size_bytes = local
+                    .segments
+                    .iter()
+                    .map(|segment| segment.info.disk_usage_bytes as u64)
+                    .sum();
+
+                shard_sizes.insert((telemetry.id.clone(), shard.id), size_bytes);
+            }
+        }
+
+        PeerLoad {
+            free_disk_bytes,
+            shard_sizes,
+        }
+    }
+
+    /// Plan shard moves to rebalance shards across the given peers, without executing them
+    ///
+    /// Peers failing to report their load are left out of the plan.
+    pub async fn plan_rebalance(&self, peer_address_by_id: &PeerAddressById) -> RebalancePlan {
+        let remote_loads = peer_address_by_id
+            .iter()
+            .filter(|(peer_id, _)| **peer_id != self.this_peer_id)
+            .map(|(peer_id, uri)| async move { (*peer_id, self.remote_peer_load(uri).await) });
+
+        let mut loads: HashMap<PeerId, PeerLoad> = HashMap::new();
+
+        for (peer_id, load) in future::join_all(remote_loads).await {
+            match load {
+                Ok(load) => {
+                    loads.insert(peer_id, load);
+                }
+                Err(err) => {
+                    log::warn!(
+                        "Not rebalancing shards of peer {peer_id}, failed to get its load: {err}"
+                    );
+                }
+            }
+        }
+
+        loads.insert(self.this_peer_id, self.peer_load().await);
+
+        let peer_metadata = self.channel_service.id_to_metadata.read().clone();
+
+        let mut collections: HashMap<CollectionId, CollectionPlacement> = HashMap::new();
+        for (collection_name, collection) in self.collections.read().await.iter() {
+            let state = collection.state().await;
+            collections.insert(
+                collection_name.clone(),
+                CollectionPlacement::new(&state, &peer_metadata),
+            );
+        }
+
+        let limits = TransferLimits {
+            incoming: self
+                .storage_config
+                .performance
+                .incoming_shard_transfers_limit,
+            outgoing: self
+                .storage_config
+                .performance
+                .outgoing_shard_transfers_limit,
+        };
+
+        rebalancer::plan_rebalance(
+            &collections,
+            &loads,
+            &self.storage_config.rebalancer,
+            limits,
+        )
+    }
+
+    async fn remote_peer_load(&self, uri: &Uri) -> Result<PeerLoad, StorageError> {
+        let response = self
+            .channel_service
+            .channel_pool
+            .with_channel(uri, |channel| async move {
+                let mut client = QdrantInternalClient::new(channel);
+                client.get_peer_load(GetPeerLoadRequest {}).await
+            })
+            .await
+            .map_err(|err| {
+                StorageError::service_error(format!("GetPeerLoad request failed: {err}"))
+            })?;
+
+        Ok(PeerLoad::from(response.into_inner()))
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use collection::shards::shard::PeerId;
+use collection::shards::transfer::ShardTransferMethod;
+use collection::shards::CollectionId;
+
+use super::TableOfContent;
+use crate::content_manager::rebalancer::{CollectionPlacement, TransferLimits};
+use crate::content_manager::replica_repair::{self, DeadReplicaTracker, ShardRepair};
+use crate::types::ReplicaRepairConfig;
+
+impl TableOfContent {
+    pub fn replica_repair_config(&self) -> &ReplicaRepairConfig {
+        &self.storage_config.replica_repair
+    }
+
+    /// Shard transfer method to use for repairing replicas, `None` to choose automatically
+    pub fn replica_repair_transfer_method(&self) -> Option<ShardTransferMethod> {
+        self.storage_config.shard_transfer_method
+    }
+
+    /// Find under-replicated shards and decide how to repair them, without executing anything
+    ///
+    /// The given tracker is updated with the current state of dead replicas, so it must be kept
+    /// across calls.
+    pub async fn plan_replica_repair(
+        &self,
+        peers: &[PeerId],
+        dead_replicas: &mut DeadReplicaTracker,
+    ) -> Vec<ShardRepair> {
+        let peer_metadata = self.channel_service.id_to_metadata.read().clone();
+
+        let mut collections: HashMap<CollectionId, CollectionPlacement> = HashMap::new();
+        for (collection_name, collection) in self.collections.read().await.iter() {
+            let state = collection.state().await;
+            collections.insert(
+                collection_name.clone(),
+                CollectionPlacement::new(&state, &peer_metadata),
+            );
+        }
+
+        let now = Instant::now();
+        dead_replicas.update(&collections, now);
+
+        let grace = Duration::from_secs(self.storage_config.replica_repair.dead_replica_grace_sec);
+        let lost_replicas = dead_replicas.lost_replicas(grace, now);
+
+        let limits = TransferLimits {
+            incoming: self
+                .storage_config
+                .performance
+                .incoming_shard_transfers_limit,
+            outgoing: self
+                .storage_config
+                .performance
+                .outgoing_shard_transfers_limit,
+        };
+
+        replica_repair::plan_replica_repair(&collections, peers, &lost_replicas, limits)
+    }
+}
@@ -0,0 +1,113 @@
+use std::path::Path;
+
+use collection::operations::snapshot_ops::SnapshotDescription;
+
+use super::TableOfContent;
+use crate::content_manager::errors::StorageError;
+use crate::content_manager::snapshot_scheduler::{
+    self, SnapshotScheduleStatus, SCHEDULED_SNAPSHOT_TAG,
+};
+use crate::content_manager::snapshots::_do_create_full_snapshot;
+use crate::rbac::{Access, AccessRequirements};
+use crate::types::SnapshotScheduleConfig;
+
+const SCHEDULER_ACCESS: Access = Access::full("Snapshot scheduler");
+
+impl TableOfContent {
+    pub fn snapshot_schedules(&self) -> &[SnapshotScheduleConfig] {
+        &self.storage_config.snapshot_schedules
+    }
+
+    /// Current status of all snapshot schedules, in configuration order
+    pub fn snapshot_schedule_statuses(&self) -> Vec<SnapshotScheduleStatus> {
+        self.snapshot_schedule_statuses.lock().clone()
+    }
+
+    pub fn update_snapshot_schedule_status(
+        &self,
+        index: usize,
+        update: impl FnOnce(&mut SnapshotScheduleStatus),
+    ) {
+        if let Some(status) = self.snapshot_schedule_statuses.lock().get_mut(index) {
+            update(status);
+        }
+    }
+
+    /// Create the snapshot of a schedule and delete snapshots expired by its retention policy
+    ///
+    /// Returns the created snapshot and the names of deleted snapshots.
+    pub async fn run_snapshot_schedule(
+        &self,
+        schedule: &SnapshotScheduleConfig,
+    ) -> Result<(SnapshotDescription, Vec<String>), StorageError> {
+        let snapshot = match &schedule.collection {
+            Some(collection_name) => {
+                let collection_pass = SCHEDULER_ACCESS.check_collection_access(
+                    collection_name,
+                    AccessRequirements::new().write().whole(),
+                )?;
+                self.create_tagged_snapshot(&collection_pass, Some(SCHEDULED_SNAPSHOT_TAG))
+                    .await?
+            }
+            None => {
+                _do_create_full_snapshot(self, SCHEDULER_ACCESS, Some(SCHEDULED_SNAPSHOT_TAG))
+                    .await?
+            }
+        };
+
+        let deleted = self.delete_expired_snapshots(schedule).await?;
+
+        Ok((snapshot, deleted))
+    }
+
+    async fn delete_expired_snapshots(
+        &self,
+        schedule: &SnapshotScheduleConfig,
+    ) -> Result<Vec<String>, StorageError> {
+        if schedule.retention.is_keep_all() {
+            return Ok(Vec::new());
+        }
+
+        let snapshot_manager = self.get_snapshots_storage_manager()?;
+
+        let snapshots_path = match &schedule.collection {
+            Some(collection_name) => self.snapshots_path_for_collection(collection_name),
+            None => Path::new(self.snapshots_path()).to_path_buf(),
+        };
+
+        let snapshots: Vec<_> = snapshot_manager
+            .list_snapshots(&snapshots_path)
+            .await?
+            .into_iter()
+            .filter(|snapshot| {
+                snapshot_scheduler::is_scheduled_snapshot(
+                    &snapshot.name,
+                    schedule.collection.as_deref(),
+                    self.this_peer_id,
+                )
+            })
+            .collect();
+
+        let expired = snapshot_scheduler::expired_snapshots(&snapshots, &schedule.retention);
+
+        for snapshot_name in &expired {
+            let snapshot_path = match &schedule.collection {
+                Some(_) => {
+                    snapshot_manager
+                        .get_snapshot_path(&snapshots_path, snapshot_name)
+                        .await?
+                }
+                None => {
+                    snapshot_manager
+                        .get_full_snapshot_path(self.snapshots_path(), snapshot_name)
+                        .await?
+                }
+            };
+
+            log::info!("Deleting expired snapshot {snapshot_path:?}");
+            snapshot_manager.delete_snapshot(&snapshot_path).await?;
+        }
+
+        Ok(expired)
+    }
+}
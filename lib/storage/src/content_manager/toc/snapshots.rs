@@ -55,13 +55,22 @@ impl TableOfContent {
     pub async fn create_snapshot<'a>(
         &self,
         collection: &CollectionPass<'a>,
+    ) -> Result<SnapshotDescription, StorageError> {
+        self.create_tagged_snapshot(collection, None).await
+    }
+
+    /// Create a collection snapshot with `tag` in its name, to tell it apart from other snapshots
+    pub async fn create_tagged_snapshot<'a>(
+        &self,
+        collection: &CollectionPass<'a>,
+        tag: Option<&str>,
     ) -> Result<SnapshotDescription, StorageError> {
         let collection = self.get_collection(collection).await?;
         // We want to use temp dir inside the temp_path (storage if not specified), because it is possible, that
         // snapshot directory is mounted as network share and multiple writes to it could be slow
         let temp_dir = self.optional_temp_or_storage_temp_path()?;
         Ok(collection
-            .create_snapshot(&temp_dir, self.this_peer_id)
+            .create_tagged_snapshot(&temp_dir, self.this_peer_id, tag)
             .await?)
     }
 
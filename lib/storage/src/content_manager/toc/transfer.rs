@@ -1,13 +1,14 @@
 use std::sync::Weak;
 
 use async_trait::async_trait;
+use collection::collection::resharding::ReshardKey;
 use collection::operations::types::{CollectionError, CollectionResult};
 use collection::shards::transfer::{ShardTransfer, ShardTransferConsensus};
 use collection::shards::CollectionId;
 
 use super::TableOfContent;
 use crate::content_manager::collection_meta_ops::{
-    CollectionMetaOperations, ShardTransferOperations,
+    CollectionMetaOperations, ReshardingOperation, ShardTransferOperations,
 };
 use crate::content_manager::consensus_manager::ConsensusStateRef;
 use crate::content_manager::consensus_ops::ConsensusOperations;
@@ -30,6 +31,26 @@ impl ShardTransferDispatcher {
             consensus_state,
         }
     }
+
+    /// Propose collection meta operation and await it to be applied on this node
+    async fn propose_with_await(
+        &self,
+        operation: CollectionMetaOperations,
+        description: &str,
+    ) -> CollectionResult<()> {
+        self.consensus_state
+            .propose_consensus_op_with_await(
+                ConsensusOperations::CollectionMeta(Box::new(operation)),
+                None,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|err| {
+                CollectionError::service_error(format!(
+                    "Failed to propose and confirm {description} operation through consensus: {err}"
+                ))
+            })
+    }
 }
 
 #[async_trait]
@@ -87,4 +108,82 @@ impl ShardTransferConsensus for ShardTransferDispatcher {
                 CollectionError::service_error(format!("Failed to propose and confirm shard transfer restart operation through consensus: {err}"))
             })
     }
+
+    async fn start_shard_transfer(
+        &self,
+        transfer_config: ShardTransfer,
+        collection_name: CollectionId,
+    ) -> CollectionResult<()> {
+        self.propose_with_await(
+            CollectionMetaOperations::TransferShard(
+                collection_name,
+                ShardTransferOperations::Start(transfer_config),
+            ),
+            "start shard transfer",
+        )
+        .await
+    }
+
+    async fn commit_read_hashring(
+        &self,
+        collection_name: CollectionId,
+        reshard_key: ReshardKey,
+    ) -> CollectionResult<()> {
+        self.propose_with_await(
+            CollectionMetaOperations::Resharding(
+                collection_name,
+                ReshardingOperation::CommitRead(reshard_key),
+            ),
+            "commit read hashring",
+        )
+        .await
+    }
+
+    async fn commit_write_hashring(
+        &self,
+        collection_name: CollectionId,
+        reshard_key: ReshardKey,
+    ) -> CollectionResult<()> {
+        self.propose_with_await(
+            CollectionMetaOperations::Resharding(
+                collection_name,
+                ReshardingOperation::CommitWrite(reshard_key),
+            ),
+            "commit write hashring",
+        )
+        .await
+    }
+
+    async fn finish_resharding(
+        &self,
+        collection_name: CollectionId,
+        reshard_key: ReshardKey,
+    ) -> CollectionResult<()> {
+        self.propose_with_await(
+            CollectionMetaOperations::Resharding(
+                collection_name,
+                ReshardingOperation::Finish(reshard_key),
+            ),
+            "finish resharding",
+        )
+        .await
+    }
+
+    async fn abort_resharding(
+        &self,
+        collection_name: CollectionId,
+        reshard_key: ReshardKey,
+        reason: &str,
+    ) -> CollectionResult<()> {
+        log::warn!("Aborting resharding {reshard_key} of {collection_name}: {reason}");
+
+        self.propose_with_await(
+            CollectionMetaOperations::Resharding(
+                collection_name,
+                ReshardingOperation::Abort(reshard_key),
+            ),
+            "abort resharding",
+        )
+        .await
+    }
 }
@@ -0,0 +1,266 @@
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use io::file_operations::{atomic_save_json, read_json};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::content_manager::errors::StorageError;
+
+pub const TOKEN_REVOCATIONS_FILE: &str = "token_revocations.json";
+
+/// Time to keep revocations of expired tokens, longer than the leeway of JWT `exp` validation
+const EXPIRED_REVOCATION_RETENTION_SEC: u64 = 60;
+
+/// Identifies revoked tokens, by the `jti` or `sub` claim of the token
+#[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq, Hash, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum RevokedToken {
+    /// Revoke a single token, by its ID
+    TokenId(String),
+    /// Revoke all tokens issued to the subject
+    Subject(String),
+}
+
+/// Revoke tokens until they expire
+#[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq, Hash, Clone)]
+pub struct TokenRevocation {
+    #[serde(flatten)]
+    pub token: RevokedToken,
+    /// Expiration time of the tokens (`exp` claim), in seconds since the Unix epoch.
+    /// The revocation is dropped once the tokens expire. If not set, it is kept until restored.
+    #[serde(default)]
+    pub exp: Option<u64>,
+}
+
+/// Revoked token IDs and subjects, with the expiration time of the tokens if known
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq, Default)]
+pub struct TokenRevocations {
+    #[serde(default)]
+    pub token_ids: BTreeMap<String, Option<u64>>,
+    #[serde(default)]
+    pub subjects: BTreeMap<String, Option<u64>>,
+}
+
+impl TokenRevocations {
+    pub fn is_empty(&self) -> bool {
+        self.token_ids.is_empty() && self.subjects.is_empty()
+    }
+
+    /// Check if a token with the given ID and subject is revoked
+    pub fn is_revoked(&self, token_id: Option<&str>, subject: Option<&str>) -> bool {
+        token_id.is_some_and(|token_id| self.token_ids.contains_key(token_id))
+            || subject.is_some_and(|subject| self.subjects.contains_key(subject))
+    }
+
+    /// Drop revocations of tokens which can't be used anymore at `now`, in seconds since the
+    /// Unix epoch. Returns false if nothing is dropped.
+    fn prune_expired(&mut self, now: u64) -> bool {
+        let len = self.token_ids.len() + self.subjects.len();
+
+        let is_usable = |exp: &Option<u64>| {
+            exp.map_or(true, |exp| {
+                exp.saturating_add(EXPIRED_REVOCATION_RETENTION_SEC) >= now
+            })
+        };
+        self.token_ids.retain(|_, exp| is_usable(exp));
+        self.subjects.retain(|_, exp| is_usable(exp));
+
+        self.token_ids.len() + self.subjects.len() != len
+    }
+}
+
+/// Persists revoked tokens. The data is assumed to be relatively small.
+/// - Reads are served from memory.
+/// - Writes are durably saved.
+/// - Revocations of expired tokens are dropped when changes are applied, at the time carried in
+///   the operation, so all peers drop the same revocations.
+#[derive(Debug)]
+pub struct TokenRevocationPersistence {
+    data_path: PathBuf,
+    revocations: TokenRevocations,
+}
+
+impl TokenRevocationPersistence {
+    pub fn open(dir_path: &Path) -> Result<Self, StorageError> {
+        if !dir_path.exists() {
+            fs::create_dir_all(dir_path)?;
+        }
+
+        let data_path = dir_path.join(TOKEN_REVOCATIONS_FILE);
+        let revocations = if data_path.exists() {
+            read_json(&data_path)?
+        } else {
+            TokenRevocations::default()
+        };
+
+        Ok(Self {
+            data_path,
+            revocations,
+        })
+    }
+
+    pub fn state(&self) -> &TokenRevocations {
+        &self.revocations
+    }
+
+    pub fn apply_state(&mut self, revocations: TokenRevocations) -> Result<(), StorageError> {
+        self.revocations = revocations;
+        Ok(atomic_save_json(&self.data_path, &self.revocations)?)
+    }
+
+    /// Revoke tokens at `now`, returns false if they are already revoked
+    pub fn revoke(&mut self, revocation: TokenRevocation, now: u64) -> Result<bool, StorageError> {
+        let TokenRevocation { token, exp } = revocation;
+
+        let entry = match token {
+            RevokedToken::TokenId(token_id) => self.revocations.token_ids.entry(token_id),
+            RevokedToken::Subject(subject) => self.revocations.subjects.entry(subject),
+        };
+
+        let inserted = match entry {
+            Entry::Vacant(entry) => {
+                entry.insert(exp);
+                true
+            }
+            Entry::Occupied(_) => false,
+        };
+
+        self.save_changes(inserted, now)?;
+        Ok(inserted)
+    }
+
+    /// Remove revocation of tokens at `now`, returns false if they are not revoked
+    pub fn restore(&mut self, token: &RevokedToken, now: u64) -> Result<bool, StorageError> {
+        let removed = match token {
+            RevokedToken::TokenId(token_id) => self.revocations.token_ids.remove(token_id),
+            RevokedToken::Subject(subject) => self.revocations.subjects.remove(subject),
+        }
+        .is_some();
+
+        self.save_changes(removed, now)?;
+        Ok(removed)
+    }
+
+    /// Drop revocations of tokens expired at `now`, and save if anything is changed
+    fn save_changes(&mut self, changed: bool, now: u64) -> Result<(), StorageError> {
+        let pruned = self.revocations.prune_expired(now);
+
+        if changed || pruned {
+            atomic_save_json(&self.data_path, &self.revocations)?;
+        }
+        Ok(())
+    }
+}
+
+/// Current time in seconds since the Unix epoch, to carry in revocation operations
+pub fn unix_now() -> u64 {
+    Utc::now().timestamp().max(0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_revocations() {
+        let dir = tempfile::Builder::new()
+            .prefix("revocations")
+            .tempdir()
+            .unwrap();
+
+        let mut persistence = TokenRevocationPersistence::open(dir.path()).unwrap();
+        assert!(persistence.state().is_empty());
+
+        let token_id = RevokedToken::TokenId("token".to_string());
+        let revocation = TokenRevocation {
+            token: token_id.clone(),
+            exp: None,
+        };
+        let now = unix_now();
+        assert!(persistence.revoke(revocation.clone(), now).unwrap());
+        assert!(!persistence.revoke(revocation, now).unwrap());
+        assert!(persistence
+            .revoke(
+                TokenRevocation {
+                    token: RevokedToken::Subject("user".to_string()),
+                    exp: Some(now + 3600),
+                },
+                now,
+            )
+            .unwrap());
+
+        let revocations = persistence.state();
+        assert!(revocations.is_revoked(Some("token"), None));
+        assert!(revocations.is_revoked(Some("other"), Some("user")));
+        assert!(!revocations.is_revoked(Some("other"), Some("other")));
+        assert!(!revocations.is_revoked(None, None));
+
+        // Persisted
+        let mut persistence = TokenRevocationPersistence::open(dir.path()).unwrap();
+        assert!(persistence.state().is_revoked(Some("token"), None));
+
+        assert!(persistence.restore(&token_id, now).unwrap());
+        assert!(!persistence.restore(&token_id, now).unwrap());
+        assert!(!persistence.state().is_revoked(Some("token"), None));
+    }
+
+    #[test]
+    fn test_expired_token_revocations() {
+        let dir = tempfile::Builder::new()
+            .prefix("revocations")
+            .tempdir()
+            .unwrap();
+
+        let mut persistence = TokenRevocationPersistence::open(dir.path()).unwrap();
+        let now = unix_now();
+
+        let mut revocations = TokenRevocations::default();
+        revocations
+            .token_ids
+            .insert("expired".to_string(), Some(now - 3600));
+        revocations
+            .token_ids
+            .insert("valid".to_string(), Some(now + 3600));
+        revocations.token_ids.insert("forever".to_string(), None);
+        revocations
+            .subjects
+            .insert("expired".to_string(), Some(now - 3600));
+        // Still within the leeway of `exp` validation
+        revocations
+            .subjects
+            .insert("recent".to_string(), Some(now - 1));
+
+        // State of other peers is applied as is
+        persistence.apply_state(revocations).unwrap();
+        assert!(persistence.state().is_revoked(Some("expired"), None));
+
+        // Expired revocations are dropped when changes are applied, at the time of the operation
+        persistence
+            .revoke(
+                TokenRevocation {
+                    token: RevokedToken::TokenId("other".to_string()),
+                    exp: None,
+                },
+                now,
+            )
+            .unwrap();
+
+        let revocations = persistence.state();
+        assert!(!revocations.is_revoked(Some("expired"), Some("expired")));
+        assert!(revocations.is_revoked(Some("valid"), None));
+        assert!(revocations.is_revoked(Some("forever"), None));
+        assert!(revocations.is_revoked(Some("other"), None));
+        assert!(revocations.is_revoked(None, Some("recent")));
+
+        // Later operations drop revocations expired since
+        persistence
+            .restore(&RevokedToken::TokenId("other".to_string()), now + 7200)
+            .unwrap();
+        assert!(!persistence.state().is_revoked(Some("valid"), None));
+        assert!(persistence.state().is_revoked(Some("forever"), None));
+    }
+}
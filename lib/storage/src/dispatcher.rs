@@ -134,6 +134,9 @@ impl Dispatcher {
                     })
                 }
 
+                // Sync nodes so that revoked tokens are rejected by all of them
+                CollectionMetaOperations::RevokeToken { .. } => true,
+
                 // TODO(resharding): Do we need/want to synchronize `Resharding` operations?
                 CollectionMetaOperations::Resharding(_, _) => false,
 
@@ -145,6 +148,7 @@ impl Dispatcher {
                 | CollectionMetaOperations::DropShardKey(_)
                 | CollectionMetaOperations::CreatePayloadIndex(_)
                 | CollectionMetaOperations::DropPayloadIndex(_)
+                | CollectionMetaOperations::RestoreToken { .. }
                 | CollectionMetaOperations::Nop { .. } => false,
             };
 
@@ -5,6 +5,8 @@ use issues::broker::Subscriber;
 use issues::Code;
 use segment::problems::UnindexedField;
 
+use crate::problems::{FailedScheduledSnapshot, UnderReplicatedShard};
+
 #[derive(Clone, Copy)]
 pub struct UnindexedFieldSubscriber;
 
@@ -39,3 +41,25 @@ impl Subscriber<IndexCreatedEvent> for UnindexedFieldSubscriber {
         ));
     }
 }
+
+#[derive(Clone, Copy)]
+pub struct UnderReplicatedShardSubscriber;
+
+impl Subscriber<CollectionDeletedEvent> for UnderReplicatedShardSubscriber {
+    fn notify(&self, event: Arc<CollectionDeletedEvent>) {
+        issues::solve_by_filter::<UnderReplicatedShard, _>(|code| {
+            UnderReplicatedShard::get_collection_name(code) == event.collection_id
+        });
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct FailedScheduledSnapshotSubscriber;
+
+impl Subscriber<CollectionDeletedEvent> for FailedScheduledSnapshotSubscriber {
+    fn notify(&self, event: Arc<CollectionDeletedEvent>) {
+        issues::solve_by_filter::<FailedScheduledSnapshot, _>(|code| {
+            FailedScheduledSnapshot::get_collection_name(code) == event.collection_id
+        });
+    }
+}
@@ -0,0 +1,78 @@
+use std::any::TypeId;
+
+use issues::{Code, Issue, Solution};
+
+/// Scheduled snapshot which failed to be created, or whose expired snapshots failed to be deleted
+#[derive(Debug)]
+pub struct FailedScheduledSnapshot {
+    /// Snapshotted collection, none for full storage snapshots
+    collection_name: Option<String>,
+    error: String,
+    consecutive_failures: usize,
+    instance_id: String,
+}
+
+impl FailedScheduledSnapshot {
+    pub fn new(
+        schedule_index: usize,
+        collection_name: Option<String>,
+        error: String,
+        consecutive_failures: usize,
+    ) -> Self {
+        let instance_id = Self::get_instance_id(schedule_index, collection_name.as_deref());
+        Self {
+            collection_name,
+            error,
+            consecutive_failures,
+            instance_id,
+        }
+    }
+
+    pub fn get_instance_id(schedule_index: usize, collection_name: Option<&str>) -> String {
+        match collection_name {
+            Some(collection_name) => format!("{collection_name}/{schedule_index}"),
+            None => format!("/{schedule_index}"),
+        }
+    }
+
+    pub fn get_collection_name(code: &Code) -> &str {
+        debug_assert!(code.issue_type == TypeId::of::<Self>());
+        // Code format is always the same, schedule index goes last
+        code.instance_id
+            .rsplit_once('/')
+            .map_or("", |(collection_name, _)| collection_name)
+    }
+
+    fn target(&self) -> String {
+        match &self.collection_name {
+            Some(collection_name) => format!("collection '{collection_name}'"),
+            None => "full storage".to_string(),
+        }
+    }
+}
+
+impl Issue for FailedScheduledSnapshot {
+    fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    fn name() -> &'static str {
+        "FAILED_SCHEDULED_SNAPSHOT"
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "Scheduled snapshot of {} failed {} time(s) in a row: {}",
+            self.target(),
+            self.consecutive_failures,
+            self.error,
+        )
+    }
+
+    fn solution(&self) -> Solution {
+        Solution::Refactor(format!(
+            "Check the snapshot storage and the logs of this peer, or fix `storage.snapshot_schedules` for {}",
+            self.target(),
+        ))
+    }
+}
@@ -0,0 +1,5 @@
+pub mod failed_scheduled_snapshot;
+pub mod under_replicated_shard;
+
+pub use failed_scheduled_snapshot::FailedScheduledSnapshot;
+pub use under_replicated_shard::UnderReplicatedShard;
@@ -0,0 +1,166 @@
+use std::any::TypeId;
+
+use collection::shards::shard::ShardId;
+use collection::shards::transfer::ShardTransferMethod;
+use http::header::CONTENT_TYPE;
+use http::{HeaderMap, HeaderValue, Method, Uri};
+use issues::{Action, Code, ImmediateSolution, Issue, Solution};
+
+use crate::content_manager::replica_repair::{RepairDecision, ShardRepair};
+
+/// Shard having less healthy replicas than the replication factor of its collection
+#[derive(Debug)]
+pub struct UnderReplicatedShard {
+    repair: ShardRepair,
+    /// Whether the shard is repaired automatically
+    auto_repair: bool,
+    method: Option<ShardTransferMethod>,
+    instance_id: String,
+}
+
+impl UnderReplicatedShard {
+    pub fn new(
+        repair: ShardRepair,
+        auto_repair: bool,
+        method: Option<ShardTransferMethod>,
+    ) -> Self {
+        let instance_id = Self::get_instance_id(&repair.collection_name, repair.shard_id);
+        Self {
+            repair,
+            auto_repair,
+            method,
+            instance_id,
+        }
+    }
+
+    pub fn get_instance_id(collection_name: &str, shard_id: ShardId) -> String {
+        format!("{collection_name}/{shard_id}")
+    }
+
+    pub fn get_collection_name(code: &Code) -> &str {
+        debug_assert!(code.issue_type == TypeId::of::<Self>());
+        // Code format is always the same, shard ID goes last
+        code.instance_id
+            .rsplit_once('/')
+            .map_or("", |(collection_name, _)| collection_name)
+    }
+
+    fn decision_description(&self) -> String {
+        match self.repair.decision {
+            RepairDecision::Replicate { from, to } if self.auto_repair => {
+                format!("Replicating it from peer {from} to peer {to}")
+            }
+            RepairDecision::Replicate { from, to } => format!(
+                "It can be replicated from peer {from} to peer {to}, automatic replica repair is disabled"
+            ),
+            RepairDecision::TransferInProgress => {
+                "Waiting for the ongoing shard transfer to finish".to_string()
+            }
+            RepairDecision::Resharding => {
+                "Waiting for resharding of the collection to finish".to_string()
+            }
+            RepairDecision::TransferLimitReached => {
+                "Waiting for shard transfers to finish, transfer limits are reached".to_string()
+            }
+            RepairDecision::NoActiveReplica => {
+                "It can't be replicated, there is no active replica".to_string()
+            }
+            RepairDecision::NoTargetPeer => {
+                "It can't be replicated, all available peers already hold a replica".to_string()
+            }
+        }
+    }
+}
+
+impl Issue for UnderReplicatedShard {
+    fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    fn name() -> &'static str {
+        "UNDER_REPLICATED_SHARD"
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "Shard {} of collection '{}' has {} healthy replicas, but the replication factor is {}. {}",
+            self.repair.shard_id,
+            self.repair.collection_name,
+            self.repair.healthy_replicas,
+            self.repair.replication_factor,
+            self.decision_description(),
+        )
+    }
+
+    fn solution(&self) -> Solution {
+        let collection_name = &self.repair.collection_name;
+
+        match self.repair.decision {
+            RepairDecision::Replicate { from, to } => {
+                let endpoint = match Uri::builder()
+                    .path_and_query(format!("/collections/{collection_name}/cluster").as_str())
+                    .build()
+                {
+                    Ok(uri) => uri,
+                    Err(e) => {
+                        log::trace!("Failed to build uri: {e}");
+                        return Solution::Refactor(format!(
+                            "Replicate shard {} of collection '{collection_name}' from peer {from} to peer {to}",
+                            self.repair.shard_id,
+                        ));
+                    }
+                };
+
+                let request_body = serde_json::json!({
+                    "replicate_shard": {
+                        "shard_id": self.repair.shard_id,
+                        "from_peer_id": from,
+                        "to_peer_id": to,
+                        "method": self.method,
+                    }
+                })
+                .as_object()
+                .unwrap()
+                .clone();
+
+                let headers = HeaderMap::from_iter([(
+                    CONTENT_TYPE,
+                    HeaderValue::from_static("application/json"),
+                )]);
+
+                let mut message = format!(
+                    "Replicate shard {} of collection '{collection_name}' from peer {from} to peer {to}",
+                    self.repair.shard_id,
+                );
+                if !self.auto_repair {
+                    message.push_str(
+                        ". Enable `storage.replica_repair` to repair shards automatically",
+                    );
+                }
+
+                Solution::Immediate(ImmediateSolution {
+                    message,
+                    action: Action {
+                        method: Method::POST,
+                        uri: endpoint,
+                        headers,
+                        body: Some(request_body),
+                    },
+                })
+            }
+            RepairDecision::TransferInProgress
+            | RepairDecision::Resharding
+            | RepairDecision::TransferLimitReached => Solution::Refactor(
+                "Wait for ongoing shard transfers to finish, the shard will be repaired afterwards"
+                    .to_string(),
+            ),
+            RepairDecision::NoActiveReplica => Solution::Refactor(format!(
+                "Recover shard {} of collection '{collection_name}' from a snapshot",
+                self.repair.shard_id,
+            )),
+            RepairDecision::NoTargetPeer => Solution::Refactor(format!(
+                "Add peers to the cluster, or lower the replication factor of collection '{collection_name}'"
+            )),
+        }
+    }
+}
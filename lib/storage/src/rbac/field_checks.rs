@@ -0,0 +1,292 @@
+//! Checks of payload fields and named vectors, for tokens restricted to some of them.
+//!
+//! Hidden payload fields and vectors are removed from results by rewriting `with_payload` and
+//! `with_vector` of requests. Filters on hidden payload fields are rejected, because they would
+//! reveal the hidden values.
+
+use collection::operations::payload_ops::{DeletePayloadOp, PayloadOps, SetPayloadOp};
+use collection::operations::point_ops::PointOperations;
+use collection::operations::vector_ops::{UpdateVectorsOp, VectorOperations};
+use collection::operations::CollectionUpdateOperations;
+use segment::data_types::vectors::DEFAULT_VECTOR_NAME;
+use segment::json_path::{JsonPath, JsonPathInterface as _};
+use segment::types::{
+    Condition, Filter, PayloadSelector, PayloadSelectorExclude, PayloadSelectorInclude,
+    WithPayloadInterface, WithVector,
+};
+
+use super::CollectionAccessView;
+use crate::content_manager::errors::StorageError;
+
+impl<'a> CollectionAccessView<'a> {
+    /// Check that the operation can't read or write hidden fields, because it works on whole
+    /// points, such as replacing all their payload.
+    pub(super) fn check_all_fields_access(&self) -> Result<(), StorageError> {
+        if self.hidden_payload.is_some() || self.vectors.is_some() {
+            return Err(StorageError::forbidden(format!(
+                "This operation is not allowed when payload fields or vectors of collection {} \
+                 are restricted",
+                self.collection,
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check that the payload field is not hidden, and doesn't contain hidden fields
+    pub(super) fn check_payload_field(&self, key: &JsonPath) -> Result<(), StorageError> {
+        let hidden = self
+            .hidden_payload
+            .unwrap_or_default()
+            .iter()
+            .find(|hidden| hidden.compatible(key));
+
+        match hidden {
+            Some(hidden) => Err(StorageError::forbidden(format!(
+                "Access to payload field {hidden} of collection {} is not allowed",
+                self.collection,
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    pub(super) fn check_vector_name(&self, name: &str) -> Result<(), StorageError> {
+        match self.vectors {
+            Some(vectors) if !vectors.iter().any(|vector| vector == name) => {
+                Err(StorageError::forbidden(format!(
+                    "Access to vector {name:?} of collection {} is not allowed",
+                    self.collection,
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check that the filter doesn't use hidden payload fields
+    pub(super) fn check_filter_fields(&self, filter: Option<&Filter>) -> Result<(), StorageError> {
+        let Some(filter) = filter else {
+            return Ok(());
+        };
+
+        if self.hidden_payload.is_none() {
+            return Ok(());
+        }
+
+        let Filter {
+            should,
+            min_should,
+            must,
+            must_not,
+        } = filter;
+
+        let conditions = should
+            .iter()
+            .flatten()
+            .chain(
+                min_should
+                    .iter()
+                    .flat_map(|min_should| &min_should.conditions),
+            )
+            .chain(must.iter().flatten())
+            .chain(must_not.iter().flatten());
+
+        for condition in conditions {
+            match condition {
+                Condition::Field(field) => self.check_payload_field(&field.key)?,
+                Condition::IsEmpty(is_empty) => self.check_payload_field(&is_empty.is_empty.key)?,
+                Condition::IsNull(is_null) => self.check_payload_field(&is_null.is_null.key)?,
+                // Fields of the nested filter are within the nested key
+                Condition::Nested(nested) => self.check_payload_field(&nested.nested.key)?,
+                Condition::HasId(_) => (),
+                Condition::Filter(filter) => self.check_filter_fields(Some(filter))?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Exclude hidden payload fields from results
+    ///
+    /// `default` is the payload selection of the request, if not specified.
+    pub(super) fn restrict_with_payload(
+        &self,
+        with_payload: &mut Option<WithPayloadInterface>,
+        default: bool,
+    ) -> Result<(), StorageError> {
+        if self.hidden_payload.is_none() {
+            return Ok(());
+        }
+
+        self.restrict_with_payload_interface(
+            with_payload.get_or_insert(WithPayloadInterface::Bool(default)),
+        )
+    }
+
+    pub(super) fn restrict_with_payload_interface(
+        &self,
+        with_payload: &mut WithPayloadInterface,
+    ) -> Result<(), StorageError> {
+        let Some(hidden_payload) = self.hidden_payload else {
+            return Ok(());
+        };
+
+        match with_payload {
+            WithPayloadInterface::Bool(false) => (),
+            WithPayloadInterface::Bool(true) => {
+                *with_payload = PayloadSelectorExclude::new(hidden_payload.to_vec()).into();
+            }
+            WithPayloadInterface::Selector(PayloadSelector::Exclude(PayloadSelectorExclude {
+                exclude,
+            })) => {
+                exclude.extend(hidden_payload.iter().cloned());
+            }
+            WithPayloadInterface::Fields(fields)
+            | WithPayloadInterface::Selector(PayloadSelector::Include(PayloadSelectorInclude {
+                include: fields,
+            })) => {
+                // Fields within hidden fields are not returned, while fields containing hidden
+                // fields can't be returned without them
+                for field in fields.iter() {
+                    for hidden in hidden_payload {
+                        if hidden.compatible(field) && !is_within(field, hidden) {
+                            return Err(StorageError::forbidden(format!(
+                                "Payload field {field} of collection {} contains hidden field \
+                                 {hidden}, select its other fields instead",
+                                self.collection,
+                            )));
+                        }
+                    }
+                }
+
+                fields
+                    .retain(|field| !hidden_payload.iter().any(|hidden| is_within(field, hidden)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Exclude hidden vectors from results
+    pub(super) fn restrict_with_vector(&self, with_vector: &mut WithVector) {
+        let Some(vectors) = self.vectors else {
+            return;
+        };
+
+        match with_vector {
+            WithVector::Bool(false) => (),
+            WithVector::Bool(true) => *with_vector = WithVector::Selector(vectors.to_vec()),
+            WithVector::Selector(names) => names.retain(|name| vectors.contains(name)),
+        }
+
+        if matches!(with_vector, WithVector::Selector(names) if names.is_empty()) {
+            *with_vector = WithVector::Bool(false);
+        }
+    }
+
+    pub(super) fn restrict_optional_with_vector(&self, with_vector: &mut Option<WithVector>) {
+        if let Some(with_vector) = with_vector {
+            self.restrict_with_vector(with_vector);
+        }
+    }
+
+    /// Check that the update doesn't write hidden payload fields or vectors
+    pub(super) fn check_update_fields(
+        &self,
+        operation: &CollectionUpdateOperations,
+    ) -> Result<(), StorageError> {
+        match operation {
+            CollectionUpdateOperations::PointOperation(op) => match op {
+                // Whole points are replaced
+                PointOperations::UpsertPoints(_) | PointOperations::SyncPoints(_) => {
+                    self.check_all_fields_access()?
+                }
+                PointOperations::DeletePoints { ids: _ } => (),
+                PointOperations::DeletePointsByFilter(filter) => {
+                    self.check_filter_fields(Some(filter))?
+                }
+            },
+
+            CollectionUpdateOperations::VectorOperation(op) => match op {
+                VectorOperations::UpdateVectors(UpdateVectorsOp { points }) => {
+                    for point in points {
+                        match &point.vector {
+                            api::rest::VectorStruct::Single(_) => {
+                                self.check_vector_name(DEFAULT_VECTOR_NAME)?
+                            }
+                            api::rest::VectorStruct::Multi(vectors) => {
+                                for name in vectors.keys() {
+                                    self.check_vector_name(name)?;
+                                }
+                            }
+                        }
+                    }
+                }
+                VectorOperations::DeleteVectors(_, names) => {
+                    for name in names {
+                        self.check_vector_name(name)?;
+                    }
+                }
+                VectorOperations::DeleteVectorsByFilter(filter, names) => {
+                    self.check_filter_fields(Some(filter))?;
+                    for name in names {
+                        self.check_vector_name(name)?;
+                    }
+                }
+            },
+
+            CollectionUpdateOperations::PayloadOperation(op) => {
+                let hidden_payload = self.hidden_payload.unwrap_or_default();
+
+                match op {
+                    PayloadOps::SetPayload(SetPayloadOp {
+                        payload,
+                        points: _,
+                        filter,
+                        key,
+                    }) => {
+                        self.check_filter_fields(filter.as_ref())?;
+                        let hidden = hidden_payload.iter().find(|hidden| {
+                            hidden.is_affected_by_value_set(&payload.0, key.as_ref())
+                        });
+                        if let Some(hidden) = hidden {
+                            return Err(StorageError::forbidden(format!(
+                                "Writing payload field {hidden} of collection {} is not allowed",
+                                self.collection,
+                            )));
+                        }
+                    }
+                    PayloadOps::DeletePayload(DeletePayloadOp {
+                        keys,
+                        points: _,
+                        filter,
+                    }) => {
+                        self.check_filter_fields(filter.as_ref())?;
+                        for key in keys {
+                            self.check_payload_field(key)?;
+                        }
+                    }
+                    // Whole payload is replaced
+                    PayloadOps::ClearPayload { points: _ } | PayloadOps::OverwritePayload(_) => {
+                        if self.hidden_payload.is_some() {
+                            self.check_all_fields_access()?;
+                        }
+                    }
+                    PayloadOps::ClearPayloadByFilter(filter) => {
+                        if self.hidden_payload.is_some() {
+                            self.check_all_fields_access()?;
+                        }
+                        self.check_filter_fields(Some(filter))?;
+                    }
+                }
+            }
+
+            CollectionUpdateOperations::FieldIndexOperation(_) => (),
+        }
+
+        Ok(())
+    }
+}
+
+/// Check if `field` is the same as `parent` or nested in it
+fn is_within(field: &JsonPath, parent: &JsonPath) -> bool {
+    field == parent || field.strip_prefix(parent).is_some()
+}
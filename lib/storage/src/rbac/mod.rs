@@ -8,6 +8,7 @@ use validator::{Validate, ValidateArgs, ValidationError, ValidationErrors};
 
 use crate::content_manager::errors::StorageError;
 
+mod field_checks;
 mod ops_checks;
 
 /// A structure that defines access rights.
@@ -26,6 +27,9 @@ pub struct CollectionAccessList(pub Vec<CollectionAccess>);
 #[derive(Serialize, Deserialize, Validate, PartialEq, Clone, Debug)]
 pub struct CollectionAccess {
     /// Collection names that are allowed to be accessed
+    ///
+    /// May contain `*` and `?` wildcards, such as `tenant-42-*`. An exact name takes precedence
+    /// over patterns, otherwise the first matching pattern is used.
     #[validate(custom(
         function = "validate_unique_collections",
         arg = "&'v_a mut HashSet<String>"
@@ -34,6 +38,21 @@ pub struct CollectionAccess {
 
     pub access: CollectionAccessMode,
 
+    /// Operations allowed on the collection.
+    /// If not specified, all operations of the access mode are allowed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operations: Option<Vec<OperationClass>>,
+
+    /// Payload fields hidden from the token.
+    /// They are excluded from results, and can't be used in filters or be written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hidden_payload: Option<Vec<JsonPath>>,
+
+    /// Named vectors visible to the token.
+    /// If specified, other vectors are excluded from results, and can't be written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vectors: Option<Vec<String>>,
+
     /// Payload constraints.
     /// An object where each key is a JSON path, and each value is JSON value.
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -62,6 +81,42 @@ pub enum CollectionAccessMode {
     ReadWrite,
 }
 
+/// Class of operations on a collection, to grant access to specific operations only.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Copy, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationClass {
+    /// Search, recommend, discover, query and count points
+    Search,
+
+    /// Retrieve and scroll points, and read vectors in results of any operation
+    Retrieve,
+
+    /// Insert and update points, vectors and payloads
+    Upsert,
+
+    /// Delete points, vectors and payloads
+    Delete,
+
+    /// Create and delete payload indexes
+    ManageIndexes,
+
+    /// Create, list, download and delete collection and shard snapshots
+    Snapshot,
+}
+
+impl std::fmt::Display for OperationClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            OperationClass::Search => "search",
+            OperationClass::Retrieve => "retrieve",
+            OperationClass::Upsert => "upsert",
+            OperationClass::Delete => "delete",
+            OperationClass::ManageIndexes => "manage_indexes",
+            OperationClass::Snapshot => "snapshot",
+        })
+    }
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
 pub struct PayloadConstraint(pub HashMap<JsonPath, ValueVariants>);
 
@@ -115,6 +170,11 @@ impl CollectionAccessList {
             .0
             .iter()
             .find(|collections| collections.collection == collection_name)
+            .or_else(|| {
+                self.0.iter().find(|collections| {
+                    matches_collection_pattern(&collections.collection, collection_name)
+                })
+            })
             .ok_or_else(|| {
                 StorageError::forbidden(format!(
                     "Access to collection {collection_name} is required"
@@ -123,15 +183,56 @@ impl CollectionAccessList {
         Ok(CollectionAccessView {
             collection: collection_name,
             access: access.access,
+            operations: access.operations.as_deref(),
+            hidden_payload: access.hidden_payload.as_deref(),
+            vectors: access.vectors.as_deref(),
             payload: &access.payload,
         })
     }
 }
 
+/// Check if a collection name matches a pattern with `*` and `?` wildcards
+///
+/// Wildcards can't clash with collection names, as they are not allowed in them.
+fn matches_collection_pattern(pattern: &str, collection_name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = collection_name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    // Positions in pattern and name after the last `*`, to retry matching with a longer span
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_n)) => {
+                    backtrack = Some((star_p, star_n + 1));
+                    p = star_p;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
 #[derive(Debug)]
 struct CollectionAccessView<'a> {
     pub collection: &'a str,
     pub access: CollectionAccessMode,
+    pub operations: Option<&'a [OperationClass]>,
+    pub hidden_payload: Option<&'a [JsonPath]>,
+    pub vectors: Option<&'a [String]>,
     pub payload: &'a Option<PayloadConstraint>,
 }
 
@@ -148,6 +249,7 @@ impl<'a> CollectionAccessView<'a> {
             write,
             manage,
             whole,
+            operations,
         } = requirements;
         if write {
             match self.access {
@@ -167,8 +269,18 @@ impl<'a> CollectionAccessView<'a> {
                 "Manage access for this operation is required",
             ));
         }
-        if whole && self.payload.is_some() {
-            return incompatible_with_payload_constraint(self.collection);
+        if whole {
+            self.check_whole_access()?;
+            // Whole collection access, such as snapshots, exposes hidden fields and vectors too
+            self.check_all_fields_access()?;
+        }
+        if let Some(allowed) = self.operations {
+            if let Some(operation) = operations.iter().find(|op| !allowed.contains(op)) {
+                return Err(StorageError::forbidden(format!(
+                    "Access to {operation} operations in collection {} is required",
+                    self.collection,
+                )));
+            }
         }
         Ok(())
     }
@@ -211,6 +323,8 @@ pub struct AccessRequirements {
     pub manage: bool,
     /// If true, the access should be not limited by a payload restrictions.
     pub whole: bool,
+    /// Operation classes to be allowed, if the access is limited to specific operations.
+    pub operations: &'static [OperationClass],
 }
 
 impl AccessRequirements {
@@ -238,6 +352,13 @@ impl AccessRequirements {
             ..*self
         }
     }
+
+    pub fn operations(&self, operations: &'static [OperationClass]) -> Self {
+        Self {
+            operations,
+            ..*self
+        }
+    }
 }
 
 impl GlobalAccessMode {
@@ -246,6 +367,7 @@ impl GlobalAccessMode {
             write,
             manage,
             whole: _,
+            operations: _,
         } = requirements;
         if write || manage {
             match self {
@@ -323,10 +445,42 @@ impl AccessCollectionBuilder {
             } else {
                 CollectionAccessMode::Read
             },
+            operations: None,
+            hidden_payload: None,
+            vectors: None,
             payload: (!whole).then(|| PayloadConstraint::new_test(name)),
         });
         self
     }
+
+    pub(self) fn add_operations(mut self, name: &str, operations: &[OperationClass]) -> Self {
+        self.0.push(CollectionAccess {
+            collection: name.to_string(),
+            access: CollectionAccessMode::ReadWrite,
+            operations: Some(operations.to_vec()),
+            hidden_payload: None,
+            vectors: None,
+            payload: None,
+        });
+        self
+    }
+
+    pub(self) fn add_fields(
+        mut self,
+        name: &str,
+        hidden_payload: &[&str],
+        vectors: &[&str],
+    ) -> Self {
+        self.0.push(CollectionAccess {
+            collection: name.to_string(),
+            access: CollectionAccessMode::ReadWrite,
+            operations: None,
+            hidden_payload: Some(hidden_payload.iter().map(|p| p.parse().unwrap()).collect()),
+            vectors: Some(vectors.iter().map(|v| v.to_string()).collect()),
+            payload: None,
+        });
+        self
+    }
 }
 
 #[cfg(test)]
@@ -2,6 +2,7 @@ use std::borrow::Cow;
 use std::collections::HashSet;
 use std::mem::take;
 
+use api::rest::OrderByInterface;
 use collection::grouping::group_by::{GroupRequest, SourceRequest};
 use collection::lookup::WithLookup;
 use collection::operations::payload_ops::{DeletePayloadOp, PayloadOps, SetPayloadOp};
@@ -16,11 +17,14 @@ use collection::operations::universal_query::collection_query::{
 };
 use collection::operations::vector_ops::VectorOperations;
 use collection::operations::CollectionUpdateOperations;
-use segment::types::{Condition, ExtendedPointId, FieldCondition, Filter, Match, Payload};
+use segment::json_path::JsonPath;
+use segment::types::{
+    Condition, ExtendedPointId, FieldCondition, Filter, Match, Payload, WithVector,
+};
 
 use super::{
     incompatible_with_payload_constraint, Access, AccessRequirements, CollectionAccessList,
-    CollectionAccessView, CollectionPass, PayloadConstraint,
+    CollectionAccessView, CollectionPass, OperationClass, PayloadConstraint,
 };
 use crate::content_manager::collection_meta_ops::CollectionMetaOperations;
 use crate::content_manager::errors::StorageError;
@@ -57,19 +61,27 @@ impl Access {
             | CollectionMetaOperations::TransferShard(_, _)
             | CollectionMetaOperations::SetShardReplicaState(_)
             | CollectionMetaOperations::CreateShardKey(_)
-            | CollectionMetaOperations::DropShardKey(_) => {
+            | CollectionMetaOperations::DropShardKey(_)
+            | CollectionMetaOperations::RevokeToken { .. }
+            | CollectionMetaOperations::RestoreToken { .. } => {
                 self.check_global_access(AccessRequirements::new().manage())?;
             }
             CollectionMetaOperations::CreatePayloadIndex(op) => {
                 self.check_collection_access(
                     &op.collection_name,
-                    AccessRequirements::new().write().whole(),
+                    AccessRequirements::new()
+                        .write()
+                        .whole()
+                        .operations(&[OperationClass::ManageIndexes]),
                 )?;
             }
             CollectionMetaOperations::DropPayloadIndex(op) => {
                 self.check_collection_access(
                     &op.collection_name,
-                    AccessRequirements::new().write().whole(),
+                    AccessRequirements::new()
+                        .write()
+                        .whole()
+                        .operations(&[OperationClass::ManageIndexes]),
                 )?;
             }
             CollectionMetaOperations::Nop { token: _ } => (),
@@ -101,10 +113,16 @@ impl CollectionAccessList {
         Ok(())
     }
 
-    fn check_with_lookup(&self, with_lookup: &Option<WithLookup>) -> Result<(), StorageError> {
+    fn check_with_lookup(&self, with_lookup: &mut Option<WithLookup>) -> Result<(), StorageError> {
         if let Some(with_lookup) = with_lookup {
-            self.find_view(&with_lookup.collection_name)?
-                .check_whole_access()?;
+            let view = self.find_view(&with_lookup.collection_name)?;
+            view.check_whole_access()?;
+            // Looked up points are retrieved by ID
+            view.meets_requirements(
+                AccessRequirements::new().operations(&[OperationClass::Retrieve]),
+            )?;
+            view.restrict_with_payload(&mut with_lookup.with_payload, false)?;
+            view.restrict_optional_with_vector(&mut with_lookup.with_vectors);
         }
         Ok(())
     }
@@ -162,6 +180,7 @@ impl CheckableCollectionOperation for RecommendRequestInternal {
             write: false,
             manage: false,
             whole: false,
+            operations: read_operations(self.with_vector.as_ref()),
         }
     }
 
@@ -177,6 +196,9 @@ impl CheckableCollectionOperation for RecommendRequestInternal {
             view.check_recommend_example(e)?;
         }
         access.check_lookup_from(&self.lookup_from)?;
+        view.check_filter_fields(self.filter.as_ref())?;
+        view.restrict_with_payload(&mut self.with_payload, false)?;
+        view.restrict_optional_with_vector(&mut self.with_vector);
         view.apply_filter(&mut self.filter);
         Ok(())
     }
@@ -188,14 +210,17 @@ impl CheckableCollectionOperation for PointRequestInternal {
             write: false,
             manage: false,
             whole: true,
+            operations: &[OperationClass::Retrieve],
         }
     }
 
     fn check_access(
         &mut self,
-        _view: CollectionAccessView<'_>,
+        view: CollectionAccessView<'_>,
         _access: &CollectionAccessList,
     ) -> Result<(), StorageError> {
+        view.restrict_with_payload(&mut self.with_payload, false)?;
+        view.restrict_with_vector(&mut self.with_vector);
         Ok(())
     }
 }
@@ -206,6 +231,7 @@ impl CheckableCollectionOperation for CoreSearchRequest {
             write: false,
             manage: false,
             whole: false,
+            operations: read_operations(self.with_vector.as_ref()),
         }
     }
 
@@ -214,6 +240,9 @@ impl CheckableCollectionOperation for CoreSearchRequest {
         view: CollectionAccessView<'_>,
         _access: &CollectionAccessList,
     ) -> Result<(), StorageError> {
+        view.check_filter_fields(self.filter.as_ref())?;
+        view.restrict_with_payload(&mut self.with_payload, false)?;
+        view.restrict_optional_with_vector(&mut self.with_vector);
         view.apply_filter(&mut self.filter);
         Ok(())
     }
@@ -225,6 +254,7 @@ impl CheckableCollectionOperation for CountRequestInternal {
             write: false,
             manage: false,
             whole: false,
+            operations: &[OperationClass::Search],
         }
     }
 
@@ -233,6 +263,7 @@ impl CheckableCollectionOperation for CountRequestInternal {
         view: CollectionAccessView<'_>,
         _access: &CollectionAccessList,
     ) -> Result<(), StorageError> {
+        view.check_filter_fields(self.filter.as_ref())?;
         view.apply_filter(&mut self.filter);
         Ok(())
     }
@@ -244,6 +275,10 @@ impl CheckableCollectionOperation for GroupRequest {
             write: false,
             manage: false,
             whole: false,
+            operations: read_operations(match &self.source {
+                SourceRequest::Search(s) => s.with_vector.as_ref(),
+                SourceRequest::Recommend(r) => r.with_vector.as_ref(),
+            }),
         }
     }
 
@@ -252,13 +287,18 @@ impl CheckableCollectionOperation for GroupRequest {
         view: CollectionAccessView<'_>,
         access: &CollectionAccessList,
     ) -> Result<(), StorageError> {
+        // Group values are returned with the results
+        view.check_payload_field(&self.group_by)?;
+        access.check_with_lookup(&mut self.with_lookup)?;
         match &mut self.source {
             SourceRequest::Search(s) => {
+                view.check_filter_fields(s.filter.as_ref())?;
+                view.restrict_with_payload(&mut s.with_payload, false)?;
+                view.restrict_optional_with_vector(&mut s.with_vector);
                 view.apply_filter(&mut s.filter);
             }
             SourceRequest::Recommend(r) => r.check_access(view, access)?,
         }
-        access.check_with_lookup(&self.with_lookup)?;
         Ok(())
     }
 }
@@ -269,6 +309,7 @@ impl CheckableCollectionOperation for DiscoverRequestInternal {
             write: false,
             manage: false,
             whole: false,
+            operations: read_operations(self.with_vector.as_ref()),
         }
     }
 
@@ -285,6 +326,9 @@ impl CheckableCollectionOperation for DiscoverRequestInternal {
             view.check_recommend_example(positive)?;
             view.check_recommend_example(negative)?;
         }
+        view.check_filter_fields(self.filter.as_ref())?;
+        view.restrict_with_payload(&mut self.with_payload, false)?;
+        view.restrict_optional_with_vector(&mut self.with_vector);
         view.apply_filter(&mut self.filter);
         access.check_lookup_from(&self.lookup_from)?;
         Ok(())
@@ -297,6 +341,7 @@ impl CheckableCollectionOperation for ScrollRequestInternal {
             write: false,
             manage: false,
             whole: false,
+            operations: &[OperationClass::Retrieve],
         }
     }
 
@@ -305,6 +350,13 @@ impl CheckableCollectionOperation for ScrollRequestInternal {
         view: CollectionAccessView<'_>,
         _access: &CollectionAccessList,
     ) -> Result<(), StorageError> {
+        view.check_filter_fields(self.filter.as_ref())?;
+        if let Some(order_by) = &self.order_by {
+            view.check_payload_field(order_by_key(order_by))?;
+        }
+        // Scroll returns payload by default
+        view.restrict_with_payload(&mut self.with_payload, true)?;
+        view.restrict_with_vector(&mut self.with_vector);
         view.apply_filter(&mut self.filter);
         Ok(())
     }
@@ -316,6 +368,7 @@ impl CheckableCollectionOperation for CollectionQueryRequest {
             write: false,
             manage: false,
             whole: false,
+            operations: read_operations(Some(&self.with_vector)),
         }
     }
 
@@ -324,10 +377,15 @@ impl CheckableCollectionOperation for CollectionQueryRequest {
         view: CollectionAccessView<'_>,
         access: &CollectionAccessList,
     ) -> Result<(), StorageError> {
+        view.check_filter_fields(self.filter.as_ref())?;
+        view.restrict_with_payload_interface(&mut self.with_payload)?;
+        view.restrict_with_vector(&mut self.with_vector);
         view.apply_filter(&mut self.filter);
 
-        if let Some(Query::Vector(vector_query)) = &self.query {
-            view.check_vector_query(vector_query)?
+        match &self.query {
+            Some(Query::Vector(vector_query)) => view.check_vector_query(vector_query)?,
+            Some(Query::OrderBy(order_by)) => view.check_payload_field(&order_by.key)?,
+            _ => (),
         }
 
         // TODO(universal-query): implement lookup_from
@@ -345,10 +403,13 @@ fn check_access_for_prefetch(
     view: &CollectionAccessView<'_>,
     _access: &CollectionAccessList, // TODO(universal_query): implement lookup_from
 ) -> Result<(), StorageError> {
+    view.check_filter_fields(prefetch.filter.as_ref())?;
     view.apply_filter(&mut prefetch.filter);
 
-    if let Some(Query::Vector(vector_query)) = &prefetch.query {
-        view.check_vector_query(vector_query)?
+    match &prefetch.query {
+        Some(Query::Vector(vector_query)) => view.check_vector_query(vector_query)?,
+        Some(Query::OrderBy(order_by)) => view.check_payload_field(&order_by.key)?,
+        _ => (),
     }
 
     // TODO(universal-query): implement lookup_from
@@ -363,19 +424,44 @@ fn check_access_for_prefetch(
 
 impl CheckableCollectionOperation for CollectionUpdateOperations {
     fn access_requirements(&self) -> AccessRequirements {
-        match self {
-            CollectionUpdateOperations::PointOperation(_)
-            | CollectionUpdateOperations::VectorOperation(_)
-            | CollectionUpdateOperations::PayloadOperation(_) => AccessRequirements {
-                write: true,
-                manage: false,
-                whole: false, // Checked in `check_access()`
+        const UPSERT: &[OperationClass] = &[OperationClass::Upsert];
+        const DELETE: &[OperationClass] = &[OperationClass::Delete];
+
+        let operations: &[OperationClass] = match self {
+            CollectionUpdateOperations::PointOperation(op) => match op {
+                PointOperations::UpsertPoints(_) => UPSERT,
+                PointOperations::DeletePoints { .. } | PointOperations::DeletePointsByFilter(_) => {
+                    DELETE
+                }
+                // Points missing from the batch are deleted
+                PointOperations::SyncPoints(_) => &[OperationClass::Upsert, OperationClass::Delete],
+            },
+            CollectionUpdateOperations::VectorOperation(op) => match op {
+                VectorOperations::UpdateVectors(_) => UPSERT,
+                VectorOperations::DeleteVectors(..)
+                | VectorOperations::DeleteVectorsByFilter(..) => DELETE,
             },
-            CollectionUpdateOperations::FieldIndexOperation(_) => AccessRequirements {
-                write: true,
-                manage: true,
-                whole: true,
+            CollectionUpdateOperations::PayloadOperation(op) => match op {
+                PayloadOps::SetPayload(_) | PayloadOps::OverwritePayload(_) => UPSERT,
+                PayloadOps::DeletePayload(_)
+                | PayloadOps::ClearPayload { .. }
+                | PayloadOps::ClearPayloadByFilter(_) => DELETE,
             },
+            CollectionUpdateOperations::FieldIndexOperation(_) => {
+                return AccessRequirements {
+                    write: true,
+                    manage: true,
+                    whole: true,
+                    operations: &[OperationClass::ManageIndexes],
+                };
+            }
+        };
+
+        AccessRequirements {
+            write: true,
+            manage: false,
+            whole: false, // Checked in `check_access()`
+            operations,
         }
     }
 
@@ -384,6 +470,8 @@ impl CheckableCollectionOperation for CollectionUpdateOperations {
         view: CollectionAccessView<'_>,
         _access: &CollectionAccessList,
     ) -> Result<(), StorageError> {
+        view.check_update_fields(self)?;
+
         match self {
             CollectionUpdateOperations::PointOperation(op) => match op {
                 PointOperations::UpsertPoints(_) => {
@@ -504,6 +592,22 @@ impl CheckableCollectionOperation for CollectionUpdateOperations {
     }
 }
 
+/// Operation classes of a search-like request, reading vectors requires retrieve access.
+fn read_operations(with_vector: Option<&WithVector>) -> &'static [OperationClass] {
+    if with_vector.is_some_and(WithVector::is_enabled) {
+        &[OperationClass::Search, OperationClass::Retrieve]
+    } else {
+        &[OperationClass::Search]
+    }
+}
+
+fn order_by_key(order_by: &OrderByInterface) -> &JsonPath {
+    match order_by {
+        OrderByInterface::Key(key) => key,
+        OrderByInterface::Struct(order_by) => &order_by.key,
+    }
+}
+
 /// Create a `must` filter from a list of point IDs.
 fn make_filter_from_ids(ids: Vec<ExtendedPointId>) -> Filter {
     let cond = ids.into_iter().collect::<HashSet<_>>().into();
@@ -553,6 +657,9 @@ mod tests {
         let list = CollectionAccessList(vec![CollectionAccess {
             collection: "col".to_string(),
             access: CollectionAccessMode::Read,
+            operations: None,
+            hidden_payload: None,
+            vectors: None,
             payload: Some(PayloadConstraint(HashMap::from([(
                 "field".parse().unwrap(),
                 ValueVariants::Integer(42),
@@ -603,6 +710,53 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn test_collection_patterns() {
+        let access = |collection: &str| CollectionAccess {
+            collection: collection.to_string(),
+            access: CollectionAccessMode::Read,
+            operations: None,
+            hidden_payload: None,
+            vectors: None,
+            payload: None,
+        };
+        let list = CollectionAccessList(vec![
+            access("tenant-42-*"),
+            CollectionAccess {
+                access: CollectionAccessMode::ReadWrite,
+                ..access("tenant-42-logs")
+            },
+            access("shard-?"),
+        ]);
+
+        let view = list.find_view("tenant-42-docs").unwrap();
+        assert_eq!(view.access, CollectionAccessMode::Read);
+
+        // Exact name takes precedence over patterns
+        let view = list.find_view("tenant-42-logs").unwrap();
+        assert_eq!(view.access, CollectionAccessMode::ReadWrite);
+
+        assert!(list.find_view("tenant-42-").is_ok());
+        assert!(list.find_view("shard-1").is_ok());
+        assert!(list.find_view("shard-10").is_err());
+        assert!(list.find_view("tenant-43-docs").is_err());
+        assert!(list.find_view("my-tenant-42-docs").is_err());
+    }
+
+    #[test]
+    fn test_matches_collection_pattern() {
+        use crate::rbac::matches_collection_pattern;
+
+        assert!(matches_collection_pattern("col", "col"));
+        assert!(!matches_collection_pattern("col", "col2"));
+        assert!(matches_collection_pattern("*", "anything"));
+        assert!(matches_collection_pattern("a*b*c", "a-b-b-c"));
+        assert!(!matches_collection_pattern("a*b*c", "a-b-b-c-d"));
+        assert!(matches_collection_pattern("*-logs", "tenant-logs-logs"));
+        assert!(matches_collection_pattern("t??t", "test"));
+        assert!(!matches_collection_pattern("t??t", "tet"));
+    }
 }
 
 #[cfg(test)]
@@ -625,7 +779,10 @@ mod tests_ops {
         FieldIndexOperationsDiscriminants,
     };
     use segment::data_types::vectors::NamedVectorStruct;
-    use segment::types::{PointIdType, SearchParams, WithPayloadInterface, WithVector};
+    use segment::types::{
+        PayloadSelectorExclude, PointIdType, SearchParams, ValueVariants, WithPayloadInterface,
+        WithVector,
+    };
     use strum::IntoEnumIterator as _;
 
     use super::*;
@@ -1315,4 +1472,210 @@ mod tests_ops {
             );
         }
     }
+
+    #[test]
+    fn test_operation_classes() {
+        let only = |operations: &[OperationClass]| -> Access {
+            AccessCollectionBuilder::new()
+                .add_operations("col", operations)
+                .into()
+        };
+
+        // Search, reading vectors also requires retrieve
+        let op = CountRequestInternal {
+            filter: None,
+            exact: false,
+        };
+        assert_allowed(&op, &only(&[OperationClass::Search]));
+        assert_forbidden(&op, &only(&[OperationClass::Retrieve]));
+
+        let op = CoreSearchRequest {
+            query: QueryEnum::Nearest(NamedVectorStruct::Default(vec![0.0, 1.0, 2.0])),
+            filter: None,
+            params: None,
+            limit: 100,
+            offset: 0,
+            with_payload: Some(WithPayloadInterface::Bool(true)),
+            with_vector: Some(WithVector::Bool(true)),
+            score_threshold: None,
+        };
+        assert_forbidden(&op, &only(&[OperationClass::Search]));
+        assert_allowed(
+            &op,
+            &only(&[OperationClass::Search, OperationClass::Retrieve]),
+        );
+        assert_allowed(
+            &CoreSearchRequest {
+                with_vector: None,
+                ..op.clone()
+            },
+            &only(&[OperationClass::Search]),
+        );
+
+        let op = PointRequestInternal {
+            ids: vec![PointIdType::NumId(12345)],
+            with_payload: None,
+            with_vector: WithVector::Bool(false),
+        };
+        assert_allowed(&op, &only(&[OperationClass::Retrieve]));
+        assert_forbidden(&op, &only(&[OperationClass::Search]));
+
+        // Upsert and delete
+        let upsert = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+            PointInsertOperationsInternal::PointsList(Vec::new()),
+        ));
+        let delete = CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+            ids: vec![ExtendedPointId::NumId(12345)],
+        });
+        let sync = CollectionUpdateOperations::PointOperation(PointOperations::SyncPoints(
+            PointSyncOperation {
+                from_id: None,
+                to_id: None,
+                points: Vec::new(),
+            },
+        ));
+
+        assert_allowed(&upsert, &only(&[OperationClass::Upsert]));
+        assert_forbidden(&delete, &only(&[OperationClass::Upsert]));
+        assert_forbidden(&sync, &only(&[OperationClass::Upsert]));
+
+        assert_allowed(&delete, &only(&[OperationClass::Delete]));
+        assert_forbidden(&upsert, &only(&[OperationClass::Delete]));
+
+        assert_allowed(
+            &sync,
+            &only(&[OperationClass::Upsert, OperationClass::Delete]),
+        );
+
+        // Global access is not restricted by operation classes
+        assert_allowed(&delete, &Access::Global(GlobalAccessMode::Manage));
+    }
+
+    #[test]
+    fn test_field_restrictions() {
+        let access: Access = AccessCollectionBuilder::new()
+            .add_fields("col", &["customer.email"], &["text"])
+            .into();
+
+        let match_field = |key: &str| {
+            Some(Filter::new_must(Condition::Field(
+                FieldCondition::new_match(
+                    key.parse().unwrap(),
+                    Match::new_value(ValueVariants::Keyword("value".to_string())),
+                ),
+            )))
+        };
+
+        // Hidden fields and vectors are excluded from results
+        let op = ScrollRequestInternal {
+            offset: None,
+            limit: Some(100),
+            filter: match_field("status"),
+            with_payload: None,
+            with_vector: WithVector::Bool(true),
+            order_by: None,
+        };
+        assert_allowed_rewrite(&op, &access, |op| {
+            op.with_payload =
+                Some(PayloadSelectorExclude::new(vec!["customer.email".parse().unwrap()]).into());
+            op.with_vector = WithVector::Selector(vec!["text".to_string()]);
+        });
+
+        assert_allowed_rewrite(
+            &ScrollRequestInternal {
+                with_payload: Some(WithPayloadInterface::Fields(vec![
+                    "customer.email.domain".parse().unwrap(),
+                    "status".parse().unwrap(),
+                ])),
+                with_vector: WithVector::Selector(vec!["image".to_string()]),
+                ..op.clone()
+            },
+            &access,
+            |op| {
+                op.with_payload = Some(WithPayloadInterface::Fields(vec!["status"
+                    .parse()
+                    .unwrap()]));
+                op.with_vector = WithVector::Bool(false);
+            },
+        );
+
+        // Fields containing hidden fields can't be selected
+        assert_forbidden(
+            &ScrollRequestInternal {
+                with_payload: Some(WithPayloadInterface::Fields(vec!["customer"
+                    .parse()
+                    .unwrap()])),
+                ..op.clone()
+            },
+            &access,
+        );
+
+        // Hidden fields can't be used in filters or ordering
+        for key in ["customer", "customer.email", "customer.email.domain"] {
+            assert_forbidden(
+                &ScrollRequestInternal {
+                    filter: match_field(key),
+                    ..op.clone()
+                },
+                &access,
+            );
+        }
+        assert_forbidden(
+            &ScrollRequestInternal {
+                order_by: Some(OrderByInterface::Key("customer.email".parse().unwrap())),
+                ..op.clone()
+            },
+            &access,
+        );
+
+        // Hidden fields and vectors can't be written
+        let set_payload = |payload: serde_json::Value| {
+            CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(SetPayloadOp {
+                payload: Payload::from(payload),
+                points: Some(vec![ExtendedPointId::NumId(12345)]),
+                filter: None,
+                key: None,
+            }))
+        };
+        assert_allowed(&set_payload(serde_json::json!({"status": "open"})), &access);
+        assert_forbidden(
+            &set_payload(serde_json::json!({"customer": {"email": "a@b.c"}})),
+            &access,
+        );
+
+        let delete_vectors = |name: &str| {
+            CollectionUpdateOperations::VectorOperation(VectorOperations::DeleteVectors(
+                PointIdsList {
+                    points: vec![ExtendedPointId::NumId(12345)],
+                    shard_key: None,
+                },
+                vec![name.to_string()],
+            ))
+        };
+        assert_allowed(&delete_vectors("text"), &access);
+        assert_forbidden(&delete_vectors("image"), &access);
+
+        let upsert = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+            PointInsertOperationsInternal::PointsList(Vec::new()),
+        ));
+        assert_forbidden(&upsert, &access);
+
+        // Snapshots contain hidden fields and vectors
+        let snapshot = AccessRequirements::new()
+            .whole()
+            .operations(&[OperationClass::Snapshot]);
+        assert!(access.check_collection_access("col", snapshot).is_err());
+
+        let vectors_only: Access = AccessCollectionBuilder::new()
+            .add_fields("col", &[], &["text"])
+            .into();
+        assert!(vectors_only
+            .check_collection_access("col", snapshot)
+            .is_err());
+
+        let unrestricted: Access = AccessCollectionBuilder::new().add("col", true, true).into();
+        assert!(unrestricted
+            .check_collection_access("col", snapshot)
+            .is_ok());
+    }
 }
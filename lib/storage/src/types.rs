@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::num::NonZeroUsize;
 use std::time::Duration;
 
@@ -93,6 +93,18 @@ pub struct StorageConfig {
     /// Default values for collections.
     #[serde(default)]
     pub collection: Option<CollectionConfigDefaults>,
+    /// Automatic rebalancing of shards across peers.
+    #[serde(default)]
+    #[validate]
+    pub rebalancer: RebalancerConfig,
+    /// Automatic repair of under-replicated shards.
+    #[serde(default)]
+    #[validate]
+    pub replica_repair: ReplicaRepairConfig,
+    /// Snapshots created periodically by the server.
+    #[serde(default)]
+    #[validate]
+    pub snapshot_schedules: Vec<SnapshotScheduleConfig>,
 }
 
 impl StorageConfig {
@@ -128,10 +140,153 @@ const fn default_mmap_advice() -> madvise::Advice {
     madvise::Advice::Random
 }
 
+/// Configuration of the automatic shard rebalancer
+///
+/// The rebalancer runs on the consensus leader only. It moves shard replicas between peers to
+/// even out shard counts and shard sizes, and to free up peers running low on disk space.
+#[derive(Debug, Deserialize, Serialize, Clone, Validate)]
+pub struct RebalancerConfig {
+    /// Whether to automatically move shards. Planned moves can always be inspected through the API.
+    #[serde(default)]
+    pub enabled: bool,
+    /// How often to check the shard distribution, in seconds.
+    #[serde(default = "default_rebalancer_interval_sec")]
+    #[validate(range(min = 1))]
+    pub interval_sec: u64,
+    /// Maximum number of shard moves planned in a single round.
+    /// Actual number of moves is also limited by `incoming_shard_transfers_limit` and
+    /// `outgoing_shard_transfers_limit`.
+    #[serde(default = "default_rebalancer_max_moves")]
+    #[validate(range(min = 1))]
+    pub max_moves: usize,
+    /// Move shards if the total size of shards on the largest peer exceeds the smallest peer by
+    /// more than this fraction of the average peer size.
+    #[serde(default = "default_rebalancer_size_imbalance_threshold")]
+    #[validate(range(min = 0.0))]
+    pub size_imbalance_threshold: f64,
+    /// Keep at least this much free disk space on peers, in megabytes.
+    /// Shards are moved away from peers below this threshold, and never onto them.
+    #[serde(default = "default_rebalancer_min_free_disk_mb")]
+    pub min_free_disk_mb: u64,
+}
+
+impl Default for RebalancerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_sec: default_rebalancer_interval_sec(),
+            max_moves: default_rebalancer_max_moves(),
+            size_imbalance_threshold: default_rebalancer_size_imbalance_threshold(),
+            min_free_disk_mb: default_rebalancer_min_free_disk_mb(),
+        }
+    }
+}
+
+const fn default_rebalancer_interval_sec() -> u64 {
+    60
+}
+
+const fn default_rebalancer_max_moves() -> usize {
+    4
+}
+
+const fn default_rebalancer_size_imbalance_threshold() -> f64 {
+    0.2
+}
+
+const fn default_rebalancer_min_free_disk_mb() -> u64 {
+    1024
+}
+
+/// Configuration of the automatic replica repair
+///
+/// Shards having less healthy replicas than the replication factor of their collection are
+/// reported in the issues dashboard. If enabled, the consensus leader also replicates them onto
+/// other peers.
+#[derive(Debug, Deserialize, Serialize, Clone, Validate)]
+pub struct ReplicaRepairConfig {
+    /// Whether to automatically replicate under-replicated shards.
+    #[serde(default)]
+    pub enabled: bool,
+    /// How often to check the replication of shards, in seconds.
+    #[serde(default = "default_replica_repair_interval_sec")]
+    #[validate(range(min = 1))]
+    pub interval_sec: u64,
+    /// Consider a dead replica lost only after it stays dead for this long, in seconds.
+    /// Gives the peer holding it a chance to recover the replica by itself.
+    #[serde(default = "default_replica_repair_dead_replica_grace_sec")]
+    pub dead_replica_grace_sec: u64,
+}
+
+impl Default for ReplicaRepairConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_sec: default_replica_repair_interval_sec(),
+            dead_replica_grace_sec: default_replica_repair_dead_replica_grace_sec(),
+        }
+    }
+}
+
+const fn default_replica_repair_interval_sec() -> u64 {
+    30
+}
+
+const fn default_replica_repair_dead_replica_grace_sec() -> u64 {
+    300
+}
+
+/// Configuration of a scheduled snapshot
+///
+/// Snapshots are created by every peer independently, each peer only snapshots its local shards.
+#[derive(Debug, Deserialize, Serialize, Clone, Validate)]
+pub struct SnapshotScheduleConfig {
+    /// Collection to snapshot. If not set, a full storage snapshot is created.
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub collection: Option<String>,
+    /// Cron expression in UTC, with seconds as the first field.
+    /// For example `0 30 3 * * *` runs every day at 03:30.
+    #[validate(custom = "crate::content_manager::snapshot_scheduler::validate_schedule")]
+    pub schedule: String,
+    /// Which snapshots to keep after each run. All snapshots are kept by default.
+    #[serde(default)]
+    #[validate]
+    pub retention: SnapshotRetentionConfig,
+}
+
+/// Retention policy of scheduled snapshots
+///
+/// A snapshot is kept if it matches any of the rules. If no rule is set, all snapshots are kept.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Validate)]
+pub struct SnapshotRetentionConfig {
+    /// Keep the last N snapshots.
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub keep_last: Option<usize>,
+    /// Keep the last snapshot of each of the last N days.
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub keep_daily: Option<usize>,
+    /// Keep the last snapshot of each of the last N weeks.
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub keep_weekly: Option<usize>,
+}
+
+impl SnapshotRetentionConfig {
+    pub fn is_keep_all(&self) -> bool {
+        self.keep_last.is_none() && self.keep_daily.is_none() && self.keep_weekly.is_none()
+    }
+}
+
 /// Information of a peer in the cluster
 #[derive(Debug, Serialize, JsonSchema, Clone)]
 pub struct PeerInfo {
     pub uri: String,
+    /// Labels of the peer, such as its availability zone
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
     // ToDo: How long ago was the last communication? In milliseconds
     // pub last_responded_millis: usize
 }
@@ -228,6 +383,7 @@ impl Anonymize for PeerInfo {
     fn anonymize(&self) -> Self {
         PeerInfo {
             uri: self.uri.anonymize(),
+            labels: self.labels.anonymize(),
         }
     }
 }
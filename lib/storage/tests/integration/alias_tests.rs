@@ -67,6 +67,9 @@ fn test_alias_operation() {
         // update_concurrency: None,
         shard_transfer_method: None,
         collection: None,
+        rebalancer: Default::default(),
+        replica_repair: Default::default(),
+        snapshot_schedules: Vec::new(),
     };
 
     let search_runtime = Runtime::new().unwrap();
@@ -106,7 +109,10 @@ fn test_alias_operation() {
                         optimizers_config: None,
                         shard_number: Some(1),
                         on_disk_payload: None,
+                        payload_storage: None,
+                        storage_format: None,
                         replication_factor: None,
+                        replica_placement: None,
                         write_consistency_factor: None,
                         init_from: None,
                         quantization_config: None,
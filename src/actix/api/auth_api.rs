@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+use actix_web::rt::time::Instant;
+use actix_web::{delete, get, put, web, Responder};
+use actix_web_validator::Query;
+use serde::Deserialize;
+use storage::content_manager::collection_meta_ops::CollectionMetaOperations;
+use storage::content_manager::token_revocation::{unix_now, RevokedToken, TokenRevocation};
+use storage::dispatcher::Dispatcher;
+use validator::Validate;
+
+use crate::actix::api::collections_api::WaitTimeout;
+use crate::actix::auth::ActixAccess;
+use crate::actix::helpers::process_response;
+
+#[derive(Debug, Deserialize, Validate)]
+struct RestoreTokenParam {
+    /// Restore tokens of the subject with this ID, instead of the token with this ID
+    #[serde(default)]
+    subject: bool,
+    #[validate(range(min = 1))]
+    timeout: Option<u64>,
+}
+
+#[get("/auth/revocations")]
+async fn get_token_revocations(
+    dispatcher: web::Data<Dispatcher>,
+    ActixAccess(access): ActixAccess,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = dispatcher
+        .toc(&access)
+        .list_token_revocations(&access)
+        .await;
+    process_response(response, timing)
+}
+
+#[put("/auth/revocations")]
+async fn revoke_token(
+    dispatcher: web::Data<Dispatcher>,
+    web::Json(revocation): web::Json<TokenRevocation>,
+    Query(query): Query<WaitTimeout>,
+    ActixAccess(access): ActixAccess,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = dispatcher
+        .submit_collection_meta_op(
+            CollectionMetaOperations::RevokeToken {
+                revocation,
+                timestamp: unix_now(),
+            },
+            access,
+            query.timeout(),
+        )
+        .await;
+    process_response(response, timing)
+}
+
+#[delete("/auth/revocations/{id}")]
+async fn restore_token(
+    dispatcher: web::Data<Dispatcher>,
+    path: web::Path<String>,
+    Query(query): Query<RestoreTokenParam>,
+    ActixAccess(access): ActixAccess,
+) -> impl Responder {
+    let timing = Instant::now();
+    let id = path.into_inner();
+    let token = if query.subject {
+        RevokedToken::Subject(id)
+    } else {
+        RevokedToken::TokenId(id)
+    };
+    let response = dispatcher
+        .submit_collection_meta_op(
+            CollectionMetaOperations::RestoreToken {
+                token,
+                timestamp: unix_now(),
+            },
+            access,
+            query.timeout.map(Duration::from_secs),
+        )
+        .await;
+    process_response(response, timing)
+}
+
+pub fn config_auth_api(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_token_revocations)
+        .service(revoke_token)
+        .service(restore_token);
+}
@@ -81,9 +81,31 @@ fn remove_peer(
     })
 }
 
+#[get("/cluster/rebalance")]
+fn rebalance_plan(
+    dispatcher: web::Data<Dispatcher>,
+    ActixAccess(access): ActixAccess,
+) -> impl Future<Output = HttpResponse> {
+    helpers::time(async move {
+        access.check_global_access(AccessRequirements::new().manage())?;
+
+        let Some(consensus_state) = dispatcher.consensus_state() else {
+            return Err(StorageError::BadRequest {
+                description: "Distributed mode disabled.".to_string(),
+            });
+        };
+
+        let toc = dispatcher.toc(&access);
+        Ok(toc
+            .plan_rebalance(&consensus_state.peer_address_by_id())
+            .await)
+    })
+}
+
 // Configure services
 pub fn config_cluster_api(cfg: &mut web::ServiceConfig) {
     cfg.service(cluster_status)
         .service(remove_peer)
-        .service(recover_current_peer);
+        .service(recover_current_peer)
+        .service(rebalance_plan);
 }
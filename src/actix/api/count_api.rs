@@ -8,7 +8,7 @@ use storage::dispatcher::Dispatcher;
 use super::CollectionPath;
 use crate::actix::api::read_params::ReadParams;
 use crate::actix::auth::ActixAccess;
-use crate::actix::helpers::process_response;
+use crate::actix::helpers::{process_response, process_response_with_explain};
 use crate::common::points::do_count_points;
 
 #[post("/collections/{name}/points/count")]
@@ -24,6 +24,7 @@ async fn count_points(
     let CountRequest {
         count_request,
         shard_key,
+        explain,
     } = request.into_inner();
 
     let shard_selector = match shard_key {
@@ -31,6 +32,21 @@ async fn count_points(
         Some(shard_keys) => ShardSelectorInternal::from(shard_keys),
     };
 
+    if explain.unwrap_or_default() {
+        let response = dispatcher
+            .toc(&access)
+            .count_with_explain(
+                &collection.name,
+                count_request,
+                params.consistency,
+                shard_selector,
+                access,
+            )
+            .await;
+
+        return process_response_with_explain(response, timing);
+    }
+
     let response = do_count_points(
         dispatcher.toc(&access),
         &collection.name,
@@ -9,8 +9,9 @@ use tokio::time::Instant;
 use crate::actix::api::read_params::ReadParams;
 use crate::actix::api::CollectionPath;
 use crate::actix::auth::ActixAccess;
-use crate::actix::helpers::process_response;
+use crate::actix::helpers::{process_response, process_response_error};
 use crate::common::points::do_discover_batch_points;
+use crate::common::rate_limiting::{RequestLimits, SearchSize};
 
 #[post("/collections/{name}/points/discover")]
 async fn discover_points(
@@ -18,6 +19,7 @@ async fn discover_points(
     collection: Path<CollectionPath>,
     request: Json<DiscoverRequest>,
     params: Query<ReadParams>,
+    limits: RequestLimits,
     ActixAccess(access): ActixAccess,
 ) -> impl Responder {
     let timing = Instant::now();
@@ -27,6 +29,11 @@ async fn discover_points(
         shard_key,
     } = request.into_inner();
 
+    if let Err(err) = limits.check_searches(&collection.name, [SearchSize::from(&discover_request)])
+    {
+        return process_response_error(err, timing);
+    }
+
     let shard_selection = match shard_key {
         None => ShardSelectorInternal::All,
         Some(shard_keys) => shard_keys.into(),
@@ -59,14 +66,24 @@ async fn discover_batch_points(
     collection: Path<CollectionPath>,
     request: Json<DiscoverRequestBatch>,
     params: Query<ReadParams>,
+    limits: RequestLimits,
     ActixAccess(access): ActixAccess,
 ) -> impl Responder {
     let timing = Instant::now();
 
+    let request = request.into_inner();
+    let searches = request
+        .searches
+        .iter()
+        .map(|search| SearchSize::from(&search.discover_request));
+    if let Err(err) = limits.check_searches(&collection.name, searches) {
+        return process_response_error(err, timing);
+    }
+
     let response = do_discover_batch_points(
         dispatcher.toc(&access),
         &collection.name,
-        request.into_inner(),
+        request,
         params.consistency,
         access,
         params.timeout(),
@@ -1,5 +1,5 @@
 use actix_web::{delete, get, web, Responder};
-use collection::operations::types::IssuesReport;
+use collection::operations::types::{IssuesReport, SlowQueriesReport};
 use storage::rbac::AccessRequirements;
 
 use crate::actix::auth::ActixAccess;
@@ -25,8 +25,31 @@ async fn clear_issues(ActixAccess(access): ActixAccess) -> impl Responder {
     .await
 }
 
+#[get("/slow_queries")]
+async fn get_slow_queries(ActixAccess(access): ActixAccess) -> impl Responder {
+    crate::actix::helpers::time(async move {
+        access.check_global_access(AccessRequirements::new().manage())?;
+        Ok(SlowQueriesReport {
+            queries: collection::slow_query_log::all_slow_queries(),
+        })
+    })
+    .await
+}
+
+#[delete("/slow_queries")]
+async fn clear_slow_queries(ActixAccess(access): ActixAccess) -> impl Responder {
+    crate::actix::helpers::time(async move {
+        access.check_global_access(AccessRequirements::new().manage())?;
+        collection::slow_query_log::clear();
+        Ok(true)
+    })
+    .await
+}
+
 // Configure services
 pub fn config_issues_api(cfg: &mut web::ServiceConfig) {
     cfg.service(get_issues);
     cfg.service(clear_issues);
+    cfg.service(get_slow_queries);
+    cfg.service(clear_slow_queries);
 }
@@ -18,7 +18,8 @@ use storage::rbac::Access;
 use super::read_params::ReadParams;
 use super::CollectionPath;
 use crate::actix::auth::ActixAccess;
-use crate::actix::helpers::process_response;
+use crate::actix::helpers::{process_response, process_response_error};
+use crate::common::rate_limiting::{RequestLimits, SearchSize};
 
 #[post("/collections/{name}/points/recommend")]
 async fn recommend_points(
@@ -26,6 +27,7 @@ async fn recommend_points(
     collection: Path<CollectionPath>,
     request: Json<RecommendRequest>,
     params: Query<ReadParams>,
+    limits: RequestLimits,
     ActixAccess(access): ActixAccess,
 ) -> impl Responder {
     let timing = Instant::now();
@@ -35,6 +37,12 @@ async fn recommend_points(
         shard_key,
     } = request.into_inner();
 
+    if let Err(err) =
+        limits.check_searches(&collection.name, [SearchSize::from(&recommend_request)])
+    {
+        return process_response_error(err, timing);
+    }
+
     let shard_selection = match shard_key {
         None => ShardSelectorInternal::All,
         Some(shard_keys) => shard_keys.into(),
@@ -92,14 +100,24 @@ async fn recommend_batch_points(
     collection: Path<CollectionPath>,
     request: Json<RecommendRequestBatch>,
     params: Query<ReadParams>,
+    limits: RequestLimits,
     ActixAccess(access): ActixAccess,
 ) -> impl Responder {
     let timing = Instant::now();
 
+    let request = request.into_inner();
+    let searches = request
+        .searches
+        .iter()
+        .map(|search| SearchSize::from(&search.recommend_request));
+    if let Err(err) = limits.check_searches(&collection.name, searches) {
+        return process_response_error(err, timing);
+    }
+
     let response = do_recommend_batch_points(
         dispatcher.toc(&access),
         &collection.name,
-        request.into_inner(),
+        request,
         params.consistency,
         access,
         params.timeout(),
@@ -126,6 +144,7 @@ async fn recommend_point_groups(
     collection: Path<CollectionPath>,
     request: Json<RecommendGroupsRequest>,
     params: Query<ReadParams>,
+    limits: RequestLimits,
     ActixAccess(access): ActixAccess,
 ) -> impl Responder {
     let timing = Instant::now();
@@ -135,6 +154,13 @@ async fn recommend_point_groups(
         shard_key,
     } = request.into_inner();
 
+    if let Err(err) = limits.check_searches(
+        &collection.name,
+        [SearchSize::from(&recommend_group_request)],
+    ) {
+        return process_response_error(err, timing);
+    }
+
     let shard_selection = match shard_key {
         None => ShardSelectorInternal::All,
         Some(shard_keys) => shard_keys.into(),
@@ -16,7 +16,7 @@ use validator::Validate;
 use super::read_params::ReadParams;
 use super::CollectionPath;
 use crate::actix::auth::ActixAccess;
-use crate::actix::helpers::{self, process_response};
+use crate::actix::helpers::{self, process_response, process_response_with_explain};
 use crate::common::points::do_get_points;
 
 #[derive(Deserialize, Validate)]
@@ -130,6 +130,7 @@ async fn scroll_points(
     let ScrollRequest {
         scroll_request,
         shard_key,
+        explain,
     } = request.into_inner();
 
     let shard_selection = match shard_key {
@@ -137,6 +138,21 @@ async fn scroll_points(
         Some(shard_keys) => ShardSelectorInternal::from(shard_keys),
     };
 
+    if explain.unwrap_or_default() {
+        let response = dispatcher
+            .toc(&access)
+            .scroll_with_explain(
+                &collection.name,
+                scroll_request,
+                params.consistency,
+                shard_selection,
+                access,
+            )
+            .await;
+
+        return process_response_with_explain(response, timing);
+    }
+
     let response = dispatcher
         .toc(&access)
         .scroll(
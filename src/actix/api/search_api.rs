@@ -6,15 +6,19 @@ use collection::operations::types::{
     CoreSearchRequest, SearchGroupsRequest, SearchRequest, SearchRequestBatch,
 };
 use itertools::Itertools;
+use storage::content_manager::errors::StorageError;
 use storage::dispatcher::Dispatcher;
 
 use super::read_params::ReadParams;
 use super::CollectionPath;
 use crate::actix::auth::ActixAccess;
-use crate::actix::helpers::process_response;
+use crate::actix::helpers::{
+    process_response, process_response_error, process_response_with_explain,
+};
 use crate::common::points::{
     do_core_search_points, do_search_batch_points, do_search_point_groups,
 };
+use crate::common::rate_limiting::{RequestLimits, SearchSize};
 
 #[post("/collections/{name}/points/search")]
 async fn search_points(
@@ -22,6 +26,7 @@ async fn search_points(
     collection: Path<CollectionPath>,
     request: Json<SearchRequest>,
     params: Query<ReadParams>,
+    limits: RequestLimits,
     ActixAccess(access): ActixAccess,
 ) -> impl Responder {
     let timing = Instant::now();
@@ -29,13 +34,41 @@ async fn search_points(
     let SearchRequest {
         search_request,
         shard_key,
+        explain,
     } = request.into_inner();
 
+    if let Err(err) = limits.check_searches(&collection.name, [SearchSize::from(&search_request)]) {
+        return process_response_error(err, timing);
+    }
+
     let shard_selection = match shard_key {
         None => ShardSelectorInternal::All,
         Some(shard_keys) => shard_keys.into(),
     };
 
+    if explain.unwrap_or_default() {
+        let response = dispatcher
+            .toc(&access)
+            .search_with_explain(
+                &collection.name,
+                search_request.into(),
+                params.consistency,
+                shard_selection,
+                access,
+                params.timeout(),
+            )
+            .await
+            .map(|(scored_points, explain)| {
+                let scored_points = scored_points
+                    .into_iter()
+                    .map(api::rest::ScoredPoint::from)
+                    .collect_vec();
+                (scored_points, explain)
+            });
+
+        return process_response_with_explain(response, timing);
+    }
+
     let response = do_core_search_points(
         dispatcher.toc(&access),
         &collection.name,
@@ -62,18 +95,30 @@ async fn batch_search_points(
     collection: Path<CollectionPath>,
     request: Json<SearchRequestBatch>,
     params: Query<ReadParams>,
+    limits: RequestLimits,
     ActixAccess(access): ActixAccess,
 ) -> impl Responder {
     let timing = Instant::now();
 
     let request = request.into_inner();
-    let requests = request
+
+    if request
+        .searches
+        .iter()
+        .any(|search| search.explain.unwrap_or_default())
+    {
+        let err = StorageError::bad_request("`explain` is not supported in batch search");
+        return process_response_error(err, timing);
+    }
+
+    let requests: Vec<_> = request
         .searches
         .into_iter()
         .map(|req| {
             let SearchRequest {
                 search_request,
                 shard_key,
+                explain: _,
             } = req;
             let shard_selection = match shard_key {
                 None => ShardSelectorInternal::All,
@@ -85,6 +130,13 @@ async fn batch_search_points(
         })
         .collect();
 
+    let searches = requests
+        .iter()
+        .map(|(request, _)| SearchSize::from(request));
+    if let Err(err) = limits.check_searches(&collection.name, searches) {
+        return process_response_error(err, timing);
+    }
+
     let response = do_search_batch_points(
         dispatcher.toc(&access),
         &collection.name,
@@ -115,6 +167,7 @@ async fn search_point_groups(
     collection: Path<CollectionPath>,
     request: Json<SearchGroupsRequest>,
     params: Query<ReadParams>,
+    limits: RequestLimits,
     ActixAccess(access): ActixAccess,
 ) -> impl Responder {
     let timing = Instant::now();
@@ -124,6 +177,12 @@ async fn search_point_groups(
         shard_key,
     } = request.into_inner();
 
+    if let Err(err) =
+        limits.check_searches(&collection.name, [SearchSize::from(&search_group_request)])
+    {
+        return process_response_error(err, timing);
+    }
+
     let shard_selection = match shard_key {
         None => ShardSelectorInternal::All,
         Some(shard_keys) => shard_keys.into(),
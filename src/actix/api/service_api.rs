@@ -75,11 +75,18 @@ async fn metrics(
 
     let anonymize = params.anonymize.unwrap_or(false);
     let telemetry_collector = telemetry_collector.lock().await;
+    let collection_metrics = telemetry_collector.collection_metrics_config().cloned();
+    // Collection metrics are based on full telemetry of collections
+    let level = if collection_metrics.is_some() {
+        DetailsLevel::Level2
+    } else {
+        DetailsLevel::Level1
+    };
     let telemetry_data = telemetry_collector
         .prepare_data(
             &access,
             TelemetryDetail {
-                level: DetailsLevel::Level1,
+                level,
                 histograms: true,
             },
         )
@@ -92,7 +99,7 @@ async fn metrics(
 
     HttpResponse::Ok()
         .content_type(ContentType::plaintext())
-        .body(MetricsData::from(telemetry_data).format_metrics())
+        .body(MetricsData::new(telemetry_data, collection_metrics.as_ref()).format_metrics())
 }
 
 #[post("/locks")]
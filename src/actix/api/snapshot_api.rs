@@ -5,6 +5,7 @@ use actix_multipart::form::MultipartForm;
 use actix_web::rt::time::Instant;
 use actix_web::{delete, get, post, put, web, HttpRequest, Responder, Result};
 use actix_web_validator as valid;
+use chrono::{DateTime, Utc};
 use collection::common::file_utils::move_file;
 use collection::common::sha_256::{hash_file, hashes_equal};
 use collection::common::snapshot_stream::SnapshotStream;
@@ -12,6 +13,7 @@ use collection::operations::snapshot_ops::{
     ShardSnapshotRecover, SnapshotPriority, SnapshotRecover,
 };
 use collection::shards::shard::ShardId;
+use collection::wal_archive::RecoveryTarget;
 use futures::{FutureExt as _, TryFutureExt as _};
 use reqwest::Url;
 use schemars::JsonSchema;
@@ -24,7 +26,7 @@ use storage::content_manager::snapshots::{
 };
 use storage::content_manager::toc::TableOfContent;
 use storage::dispatcher::Dispatcher;
-use storage::rbac::{Access, AccessRequirements};
+use storage::rbac::{Access, AccessRequirements, OperationClass};
 use uuid::Uuid;
 use validator::Validate;
 
@@ -53,11 +55,36 @@ pub struct SnapshotUploadingParam {
     pub checksum: Option<String>,
 }
 
+#[derive(Deserialize, Serialize, JsonSchema, Validate)]
+pub struct ShardSnapshotUploadingParam {
+    pub wait: Option<bool>,
+    pub priority: Option<SnapshotPriority>,
+
+    /// Optional SHA256 checksum to verify snapshot integrity before recovery.
+    #[serde(default)]
+    #[validate(custom = "::common::validation::validate_sha256_hash")]
+    pub checksum: Option<String>,
+
+    /// Replay operations from the WAL archive on top of the snapshot, up to this time.
+    #[serde(default)]
+    pub recover_until: Option<DateTime<Utc>>,
+}
+
 #[derive(Deserialize, Serialize, JsonSchema, Validate)]
 pub struct SnapshottingParam {
     pub wait: Option<bool>,
 }
 
+#[derive(Deserialize, Serialize, JsonSchema, Validate)]
+pub struct ShardSnapshottingParam {
+    pub wait: Option<bool>,
+
+    /// Name of a previous snapshot of the shard to create an incremental snapshot from.
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub base: Option<String>,
+}
+
 #[derive(MultipartForm)]
 pub struct SnapshottingForm {
     snapshot: TempFile,
@@ -131,8 +158,12 @@ pub async fn do_get_snapshot(
     collection_name: &str,
     snapshot_name: &str,
 ) -> Result<SnapshotStream, HttpError> {
-    let collection_pass =
-        access.check_collection_access(collection_name, AccessRequirements::new().whole())?;
+    let collection_pass = access.check_collection_access(
+        collection_name,
+        AccessRequirements::new()
+            .whole()
+            .operations(&[OperationClass::Snapshot]),
+    )?;
     let collection: tokio::sync::RwLockReadGuard<collection::collection::Collection> =
         toc.get_collection(&collection_pass).await?;
     let snapshot_storage_manager = collection.get_snapshots_storage_manager()?;
@@ -347,18 +378,20 @@ async fn list_shard_snapshots(
 async fn create_shard_snapshot(
     dispatcher: web::Data<Dispatcher>,
     path: web::Path<(String, ShardId)>,
-    query: web::Query<SnapshottingParam>,
+    query: valid::Query<ShardSnapshottingParam>,
     ActixAccess(access): ActixAccess,
 ) -> impl Responder {
     let (collection, shard) = path.into_inner();
+    let ShardSnapshottingParam { wait, base } = query.into_inner();
     let future = common::snapshots::create_shard_snapshot(
         dispatcher.toc(&access).clone(),
         access,
         collection,
         shard,
+        base,
     );
 
-    helpers::time_or_accept(future, query.wait.unwrap_or(true)).await
+    helpers::time_or_accept(future, wait.unwrap_or(true)).await
 }
 
 // TODO: `PUT` (same as `recover_from_snapshot`) or `POST`!?
@@ -384,6 +417,7 @@ async fn recover_shard_snapshot(
             request.checksum,
             http_client.as_ref().clone(),
             request.api_key,
+            request.recover_until,
         )
         .await?;
 
@@ -398,15 +432,16 @@ async fn recover_shard_snapshot(
 async fn upload_shard_snapshot(
     dispatcher: web::Data<Dispatcher>,
     path: web::Path<(String, ShardId)>,
-    query: web::Query<SnapshotUploadingParam>,
+    query: web::Query<ShardSnapshotUploadingParam>,
     MultipartForm(form): MultipartForm<SnapshottingForm>,
     ActixAccess(access): ActixAccess,
 ) -> impl Responder {
     let (collection, shard) = path.into_inner();
-    let SnapshotUploadingParam {
+    let ShardSnapshotUploadingParam {
         wait,
         priority,
         checksum,
+        recover_until,
     } = query.into_inner();
 
     // - `recover_shard_snapshot_impl` is *not* cancel safe
@@ -444,6 +479,7 @@ async fn upload_shard_snapshot(
             shard,
             form.snapshot.file.path(),
             priority.unwrap_or_default(),
+            recover_until.map(RecoveryTarget::Timestamp),
             cancel,
         )
         .await?;
@@ -463,8 +499,12 @@ async fn download_shard_snapshot(
     ActixAccess(access): ActixAccess,
 ) -> Result<impl Responder, HttpError> {
     let (collection, shard, snapshot) = path.into_inner();
-    let collection_pass =
-        access.check_collection_access(&collection, AccessRequirements::new().whole())?;
+    let collection_pass = access.check_collection_access(
+        &collection,
+        AccessRequirements::new()
+            .whole()
+            .operations(&[OperationClass::Snapshot]),
+    )?;
     let collection = dispatcher
         .toc(&access)
         .get_collection(&collection_pass)
@@ -12,12 +12,13 @@ use validator::Validate;
 
 use super::CollectionPath;
 use crate::actix::auth::ActixAccess;
-use crate::actix::helpers::process_response;
+use crate::actix::helpers::{process_response, process_response_error};
 use crate::common::points::{
     do_batch_update_points, do_clear_payload, do_create_index, do_delete_index, do_delete_payload,
     do_delete_points, do_delete_vectors, do_overwrite_payload, do_set_payload, do_update_vectors,
-    do_upsert_points, CreateFieldIndex, UpdateOperations,
+    do_upsert_points, payload_points_count, CreateFieldIndex, UpdateOperation, UpdateOperations,
 };
+use crate::common::rate_limiting::RequestLimits;
 
 #[derive(Deserialize, Validate)]
 struct FieldPath {
@@ -38,6 +39,7 @@ async fn upsert_points(
     collection: Path<CollectionPath>,
     operation: Json<PointInsertOperations>,
     params: Query<UpdateParam>,
+    limits: RequestLimits,
     ActixAccess(access): ActixAccess,
 ) -> impl Responder {
     let timing = Instant::now();
@@ -45,6 +47,14 @@ async fn upsert_points(
     let wait = params.wait.unwrap_or(false);
     let ordering = params.ordering.unwrap_or_default();
 
+    let points_count = operation.points_count();
+    if let Err(err) = limits
+        .check_writes(dispatcher.toc(&access), &collection.name, points_count)
+        .await
+    {
+        return process_response_error(err, timing);
+    }
+
     let response = do_upsert_points(
         dispatcher.toc(&access).clone(),
         collection.into_inner().name,
@@ -92,6 +102,7 @@ async fn update_vectors(
     collection: Path<CollectionPath>,
     operation: Json<UpdateVectors>,
     params: Query<UpdateParam>,
+    limits: RequestLimits,
     ActixAccess(access): ActixAccess,
 ) -> impl Responder {
     let timing = Instant::now();
@@ -99,6 +110,14 @@ async fn update_vectors(
     let wait = params.wait.unwrap_or(false);
     let ordering = params.ordering.unwrap_or_default();
 
+    let points_count = operation.points.len();
+    if let Err(err) = limits
+        .check_writes(dispatcher.toc(&access), &collection.name, points_count)
+        .await
+    {
+        return process_response_error(err, timing);
+    }
+
     let response = do_update_vectors(
         dispatcher.toc(&access).clone(),
         collection.into_inner().name,
@@ -146,6 +165,7 @@ async fn set_payload(
     collection: Path<CollectionPath>,
     operation: Json<SetPayload>,
     params: Query<UpdateParam>,
+    limits: RequestLimits,
     ActixAccess(access): ActixAccess,
 ) -> impl Responder {
     let timing = Instant::now();
@@ -153,6 +173,14 @@ async fn set_payload(
     let wait = params.wait.unwrap_or(false);
     let ordering = params.ordering.unwrap_or_default();
 
+    let points_count = payload_points_count(&operation);
+    if let Err(err) = limits
+        .check_writes(dispatcher.toc(&access), &collection.name, points_count)
+        .await
+    {
+        return process_response_error(err, timing);
+    }
+
     let response = do_set_payload(
         dispatcher.toc(&access).clone(),
         collection.into_inner().name,
@@ -173,6 +201,7 @@ async fn overwrite_payload(
     collection: Path<CollectionPath>,
     operation: Json<SetPayload>,
     params: Query<UpdateParam>,
+    limits: RequestLimits,
     ActixAccess(access): ActixAccess,
 ) -> impl Responder {
     let timing = Instant::now();
@@ -180,6 +209,14 @@ async fn overwrite_payload(
     let wait = params.wait.unwrap_or(false);
     let ordering = params.ordering.unwrap_or_default();
 
+    let points_count = payload_points_count(&operation);
+    if let Err(err) = limits
+        .check_writes(dispatcher.toc(&access), &collection.name, points_count)
+        .await
+    {
+        return process_response_error(err, timing);
+    }
+
     let response = do_overwrite_payload(
         dispatcher.toc(&access).clone(),
         collection.into_inner().name,
@@ -254,6 +291,7 @@ async fn update_batch(
     collection: Path<CollectionPath>,
     operations: Json<UpdateOperations>,
     params: Query<UpdateParam>,
+    limits: RequestLimits,
     ActixAccess(access): ActixAccess,
 ) -> impl Responder {
     let timing = Instant::now();
@@ -261,6 +299,18 @@ async fn update_batch(
     let wait = params.wait.unwrap_or(false);
     let ordering = params.ordering.unwrap_or_default();
 
+    let points_count = operations
+        .operations
+        .iter()
+        .map(UpdateOperation::written_points_count)
+        .sum();
+    if let Err(err) = limits
+        .check_writes(dispatcher.toc(&access), &collection.name, points_count)
+        .await
+    {
+        return process_response_error(err, timing);
+    }
+
     let response = do_batch_update_points(
         dispatcher.toc(&access).clone(),
         collection.into_inner().name,
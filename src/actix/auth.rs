@@ -4,23 +4,31 @@ use std::sync::Arc;
 
 use actix_web::body::{BoxBody, EitherBody};
 use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
-use actix_web::{Error, FromRequest, HttpMessage, HttpResponse, ResponseError};
+use actix_web::{web, Error, FromRequest, HttpMessage, HttpResponse, ResponseError};
 use futures_util::future::LocalBoxFuture;
 use storage::rbac::Access;
 
 use super::helpers::HttpError;
-use crate::common::auth::{AuthError, AuthKeys};
+use crate::common::audit::{self, Api, AuditLogger, AuditedRequest};
+use crate::common::auth::{AuthError, AuthKeys, Credentials};
+use crate::common::rate_limiting::{RateLimiter, RequestLimits};
 
 pub struct Auth {
     auth_keys: AuthKeys,
     whitelist: Vec<WhitelistItem>,
+    audit_logger: Option<Arc<AuditLogger>>,
 }
 
 impl Auth {
-    pub fn new(auth_keys: AuthKeys, whitelist: Vec<WhitelistItem>) -> Self {
+    pub fn new(
+        auth_keys: AuthKeys,
+        whitelist: Vec<WhitelistItem>,
+        audit_logger: Option<Arc<AuditLogger>>,
+    ) -> Self {
         Self {
             auth_keys,
             whitelist,
+            audit_logger,
         }
     }
 }
@@ -42,6 +50,7 @@ where
         ready(Ok(AuthMiddleware {
             auth_keys: Arc::new(self.auth_keys.clone()),
             whitelist: self.whitelist.clone(),
+            audit_logger: self.audit_logger.clone(),
             service: Arc::new(service),
         }))
     }
@@ -85,6 +94,7 @@ pub struct AuthMiddleware<S> {
     auth_keys: Arc<AuthKeys>,
     /// List of items whitelisted from authentication.
     whitelist: Vec<WhitelistItem>,
+    audit_logger: Option<Arc<AuditLogger>>,
     service: Arc<S>,
 }
 
@@ -92,6 +102,29 @@ impl<S> AuthMiddleware<S> {
     pub fn is_path_whitelisted(&self, path: &str) -> bool {
         self.whitelist.iter().any(|item| item.matches(path))
     }
+
+    /// Start auditing the request, if its operation is audited
+    fn audit_request(&self, req: &ServiceRequest) -> Option<(Arc<AuditLogger>, AuditedRequest)> {
+        let audit_logger = self.audit_logger.as_ref()?;
+
+        let method = req.method().as_str();
+        let pattern = req.match_pattern();
+        let pattern = pattern.as_deref().unwrap_or(req.path());
+
+        let kind = audit::rest_operation_kind(method, pattern);
+        if !audit_logger.is_audited(kind) {
+            return None;
+        }
+
+        let audited_request = AuditedRequest::new(
+            Api::Rest,
+            req.peer_addr().map(|addr| addr.ip()),
+            audit::rest_collection(pattern, req.path()).map(ToString::to_string),
+            kind,
+            format!("{method} {pattern}"),
+        );
+        Some((audit_logger.clone(), audited_request))
+    }
 }
 
 impl<S, B> Service<ServiceRequest> for AuthMiddleware<S>
@@ -114,20 +147,23 @@ where
             return Box::pin(self.service.call(req));
         }
 
+        let audit = self.audit_request(&req);
         let auth_keys = self.auth_keys.clone();
         let service = self.service.clone();
         Box::pin(async move {
-            match auth_keys
+            let (result, credentials) = match auth_keys
                 .validate_request(|key| req.headers().get(key).and_then(|val| val.to_str().ok()))
                 .await
             {
-                Ok(access) => {
+                Ok((access, credentials)) => {
                     let _previous = req.extensions_mut().insert::<Access>(access);
                     debug_assert!(
                         _previous.is_none(),
                         "Previous access object should not exist in the request"
                     );
-                    service.call(req).await
+                    req.extensions_mut()
+                        .insert::<Credentials>(credentials.clone());
+                    (service.call(req).await, Some(credentials))
                 }
                 Err(e) => {
                     let resp = match e {
@@ -135,9 +171,24 @@ where
                         AuthError::Forbidden(e) => HttpResponse::Forbidden().body(e),
                         AuthError::StorageError(e) => HttpError::from(e).error_response(),
                     };
-                    Ok(req.into_response(resp).map_into_right_body())
+                    (Ok(req.into_response(resp).map_into_right_body()), None)
                 }
+            };
+
+            if let Some((audit_logger, audited_request)) = audit {
+                let status = match &result {
+                    Ok(response) => response.status(),
+                    Err(err) => err.as_response_error().status_code(),
+                };
+                audited_request.finish(
+                    &audit_logger,
+                    credentials.as_ref(),
+                    status.as_u16().to_string(),
+                    status.is_success(),
+                );
             }
+
+            result
         })
     }
 }
@@ -158,3 +209,19 @@ impl FromRequest for ActixAccess {
         ready(Ok(ActixAccess(access)))
     }
 }
+
+impl FromRequest for RequestLimits {
+    type Error = Infallible;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(
+        req: &actix_web::HttpRequest,
+        _payload: &mut actix_web::dev::Payload,
+    ) -> Self::Future {
+        let rate_limiter = req
+            .app_data::<web::Data<Option<Arc<RateLimiter>>>>()
+            .and_then(|rate_limiter| rate_limiter.get_ref().clone());
+        let limits = RequestLimits::new(rate_limiter, req.extensions().get::<Credentials>());
+        ready(Ok(limits))
+    }
+}
@@ -4,6 +4,7 @@ use std::future::Future;
 use actix_web::rt::time::Instant;
 use actix_web::{http, HttpResponse, ResponseError};
 use api::grpc::models::{ApiResponse, ApiStatus};
+use collection::operations::query_explain::QueryExplain;
 use collection::operations::types::CollectionError;
 use serde::Serialize;
 use storage::content_manager::errors::StorageError;
@@ -31,6 +32,34 @@ where
     }
 }
 
+/// Response of an explained request, with the breakdown next to the result
+#[derive(Serialize)]
+struct ExplainedApiResponse<D> {
+    #[serde(flatten)]
+    response: ApiResponse<D>,
+    explain: QueryExplain,
+}
+
+pub fn process_response_with_explain<D>(
+    response: Result<(D, QueryExplain), StorageError>,
+    timing: Instant,
+) -> HttpResponse
+where
+    D: Serialize,
+{
+    match response {
+        Ok((res, explain)) => HttpResponse::Ok().json(ExplainedApiResponse {
+            response: ApiResponse {
+                result: Some(res),
+                status: ApiStatus::Ok,
+                time: timing.elapsed().as_secs_f64(),
+            },
+            explain,
+        }),
+        Err(err) => process_response_error(err, timing),
+    }
+}
+
 pub fn process_response_error(err: StorageError, timing: Instant) -> HttpResponse {
     if let StorageError::ServiceError {
         description,
@@ -158,6 +187,7 @@ impl ResponseError for HttpError {
             StorageError::ChecksumMismatch { .. } => http::StatusCode::BAD_REQUEST,
             StorageError::Forbidden { .. } => http::StatusCode::FORBIDDEN,
             StorageError::PreconditionFailed { .. } => http::StatusCode::INTERNAL_SERVER_ERROR,
+            StorageError::RateLimitExceeded { .. } => http::StatusCode::TOO_MANY_REQUESTS,
         }
     }
 }
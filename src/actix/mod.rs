@@ -5,6 +5,7 @@ mod auth;
 mod certificate_helpers;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod helpers;
+mod trace_context;
 
 use std::io;
 use std::path::Path;
@@ -21,6 +22,7 @@ use collection::operations::validation;
 use storage::dispatcher::Dispatcher;
 use storage::rbac::Access;
 
+use crate::actix::api::auth_api::config_auth_api;
 use crate::actix::api::cluster_api::config_cluster_api;
 use crate::actix::api::collections_api::config_collections_api;
 use crate::actix::api::count_api::count_points;
@@ -34,9 +36,11 @@ use crate::actix::api::shards_api::config_shards_api;
 use crate::actix::api::snapshot_api::config_snapshots_api;
 use crate::actix::api::update_api::config_update_api;
 use crate::actix::auth::{Auth, WhitelistItem};
+use crate::common::audit::AuditLogger;
 use crate::common::auth::AuthKeys;
 use crate::common::health;
 use crate::common::http_client::HttpClient;
+use crate::common::rate_limiting::RateLimiter;
 use crate::common::telemetry::TelemetryCollector;
 use crate::settings::{max_web_workers, Settings};
 use crate::tracing::LoggerHandle;
@@ -56,12 +60,11 @@ pub fn init(
     health_checker: Option<Arc<health::HealthChecker>>,
     settings: Settings,
     logger_handle: LoggerHandle,
+    auth_keys: Option<AuthKeys>,
+    audit_logger: Option<Arc<AuditLogger>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
 ) -> io::Result<()> {
     actix_web::rt::System::new().block_on(async {
-        let auth_keys = AuthKeys::try_create(
-            &settings.service,
-            dispatcher.toc(&Access::full("For JWT validation")).clone(),
-        );
         let upload_dir = dispatcher
             .toc(&Access::full("For upload dir"))
             .upload_dir()
@@ -76,6 +79,7 @@ pub fn init(
         let logger_handle_data = web::Data::new(logger_handle);
         let http_client = web::Data::new(HttpClient::from_settings(&settings)?);
         let health_checker = web::Data::new(health_checker);
+        let rate_limiter = web::Data::new(rate_limiter);
         let static_folder = settings
             .service
             .static_content_dir
@@ -130,7 +134,13 @@ pub fn init(
                 // api_key middleware
                 // note: the last call to `wrap()` or `wrap_fn()` is executed first
                 .wrap(ConditionEx::from_option(auth_keys.as_ref().map(
-                    |auth_keys| Auth::new(auth_keys.clone(), api_key_whitelist.clone()),
+                    |auth_keys| {
+                        Auth::new(
+                            auth_keys.clone(),
+                            api_key_whitelist.clone(),
+                            audit_logger.clone(),
+                        )
+                    },
                 )))
                 .wrap(Condition::new(settings.service.enable_cors, cors))
                 .wrap(
@@ -146,11 +156,16 @@ pub fn init(
                 .wrap(actix_telemetry::ActixTelemetryTransform::new(
                     actix_telemetry_collector.clone(),
                 ))
+                .wrap(Condition::new(
+                    settings.logger.otlp.is_enabled(),
+                    trace_context::TraceContextTransform,
+                ))
                 .app_data(dispatcher_data.clone())
                 .app_data(telemetry_collector_data.clone())
                 .app_data(logger_handle_data.clone())
                 .app_data(http_client.clone())
                 .app_data(health_checker.clone())
+                .app_data(rate_limiter.clone())
                 .app_data(validate_path_config)
                 .app_data(validate_query_config)
                 .app_data(validate_json_config)
@@ -167,6 +182,7 @@ pub fn init(
                 .configure(config_discovery_api)
                 .configure(config_shards_api)
                 .configure(config_issues_api)
+                .configure(config_auth_api)
                 // Ordering of services is important for correct path pattern matching
                 // See: <https://github.com/qdrant/qdrant/issues/3543>
                 .service(scroll_points)
@@ -0,0 +1,60 @@
+use std::future::{ready, Ready};
+
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::Error;
+use futures_util::future::LocalBoxFuture;
+use tracing::Instrument as _;
+
+use crate::tracing::otlp;
+
+pub struct TraceContextService<S> {
+    service: S,
+}
+
+pub struct TraceContextTransform;
+
+/// Actix trace context service. It runs every request in a span, which continues the trace of
+/// the W3C `traceparent` header.
+impl<S, B> Service<ServiceRequest> for TraceContextService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_web::dev::forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let match_pattern = request
+            .match_pattern()
+            .unwrap_or_else(|| "unknown".to_owned());
+        let name = format!("{} {}", request.method(), match_pattern);
+        let span = otlp::server_span("http", &name, |key| {
+            request.headers().get(key)?.to_str().ok()
+        });
+
+        let future = span.in_scope(|| self.service.call(request));
+        Box::pin(future.instrument(span))
+    }
+}
+
+/// Actix trace context transform. It's a builder for an actix service
+impl<S, B> Transform<S, ServiceRequest> for TraceContextTransform
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = TraceContextService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(TraceContextService { service }))
+    }
+}
@@ -0,0 +1,555 @@
+//! Audit log of authenticated operations.
+//!
+//! Audited requests are written to the log file as JSON lines, with the credentials they were
+//! authenticated with, the client address, the collection, the operation, its result and
+//! duration. Mutating, snapshot and cluster operations are always audited, reads only if
+//! configured. Events are written by a background thread, so requests never wait on the file.
+//! The log file is rotated when it reaches the configured size.
+
+use std::fs::{self, File};
+use std::io::{self, Write as _};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::common::auth::Credentials;
+use crate::settings::AuditConfig;
+
+/// Number of events waiting to be written to the log file, further events are not written
+const WRITER_QUEUE_SIZE: usize = 4096;
+
+/// Kind of an operation, determines if it is audited
+#[derive(Serialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationKind {
+    Read,
+    Write,
+    Snapshot,
+    Cluster,
+}
+
+#[derive(Serialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Api {
+    Rest,
+    Grpc,
+}
+
+/// A single line of the audit log
+#[derive(Serialize, Debug)]
+pub struct AuditEvent<'a> {
+    pub timestamp: DateTime<Utc>,
+    pub api: Api,
+    /// Credentials of the request, none if authentication failed
+    pub credentials: Option<&'a Credentials>,
+    pub client: Option<IpAddr>,
+    pub collection: Option<&'a str>,
+    pub kind: OperationKind,
+    /// REST method and route, or gRPC method
+    pub operation: &'a str,
+    /// HTTP status code, or gRPC status code
+    pub status: String,
+    pub success: bool,
+    pub duration_ms: f64,
+}
+
+pub struct AuditLogger {
+    config: AuditConfig,
+    sender: Option<SyncSender<Vec<u8>>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+struct AuditFile {
+    file: File,
+    /// Current size of the file
+    size: u64,
+}
+
+impl AuditLogger {
+    pub fn new(config: AuditConfig) -> io::Result<Self> {
+        let mut file = AuditFile::open(Path::new(&config.log_file))?;
+        let (sender, receiver) = mpsc::sync_channel::<Vec<u8>>(WRITER_QUEUE_SIZE);
+
+        let writer_config = config.clone();
+        let handle = thread::Builder::new()
+            .name("audit-log".to_string())
+            .spawn(move || {
+                for line in receiver {
+                    if let Err(err) = file.write_line(&line, &writer_config) {
+                        log::error!(
+                            "Failed to write audit log {}: {err}",
+                            writer_config.log_file,
+                        );
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            config,
+            sender: Some(sender),
+            handle: Some(handle),
+        })
+    }
+
+    /// Check if operations of the given kind are audited
+    pub fn is_audited(&self, kind: OperationKind) -> bool {
+        kind != OperationKind::Read || self.config.log_reads
+    }
+
+    /// Queue the event to be written to the log, without blocking
+    ///
+    /// Errors are logged, and don't affect the request.
+    pub fn log(&self, event: &AuditEvent) {
+        let mut line = match serde_json::to_vec(event) {
+            Ok(line) => line,
+            Err(err) => {
+                log::error!("Failed to serialize audit event: {err}");
+                return;
+            }
+        };
+        line.push(b'\n');
+
+        let Some(sender) = &self.sender else {
+            return;
+        };
+
+        match sender.try_send(line) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                log::error!("Audit log writer is falling behind, event is not written");
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                log::error!("Audit log writer is stopped, event is not written");
+            }
+        }
+    }
+}
+
+impl Drop for AuditLogger {
+    /// Write all queued events before closing the file
+    fn drop(&mut self) {
+        // Closing the channel stops the thread once the queue is drained
+        self.sender.take();
+
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                log::error!("Audit log writer panicked");
+            }
+        }
+    }
+}
+
+impl AuditFile {
+    fn open(path: &Path) -> io::Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+
+        let file = File::options().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { file, size })
+    }
+
+    /// Append the line to the log, rotating the log file if it is full
+    fn write_line(&mut self, line: &[u8], config: &AuditConfig) -> io::Result<()> {
+        let path = Path::new(&config.log_file);
+
+        let max_size = config.max_file_size_mb * 1024 * 1024;
+        if self.size > 0 && self.size + line.len() as u64 > max_size {
+            rotate(path, config.max_files)?;
+            *self = Self::open(path)?;
+        }
+
+        self.file.write_all(line)?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+}
+
+/// Rotate log files: `audit.log` becomes `audit.log.1`, `audit.log.1` becomes `audit.log.2`, etc.
+/// Only `max_files` rotated files are kept.
+fn rotate(path: &Path, max_files: usize) -> io::Result<()> {
+    if max_files == 0 {
+        return fs::remove_file(path);
+    }
+
+    for index in (1..max_files).rev() {
+        match fs::rename(rotated_path(path, index), rotated_path(path, index + 1)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => (),
+        }
+    }
+
+    fs::rename(path, rotated_path(path, 1))
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(format!(".{index}"));
+    PathBuf::from(path)
+}
+
+/// Request being audited, logged once its result is known
+pub struct AuditedRequest {
+    api: Api,
+    client: Option<IpAddr>,
+    collection: Option<String>,
+    kind: OperationKind,
+    operation: String,
+    start: Instant,
+}
+
+impl AuditedRequest {
+    pub fn new(
+        api: Api,
+        client: Option<IpAddr>,
+        collection: Option<String>,
+        kind: OperationKind,
+        operation: String,
+    ) -> Self {
+        Self {
+            api,
+            client,
+            collection,
+            kind,
+            operation,
+            start: Instant::now(),
+        }
+    }
+
+    pub fn set_collection(&mut self, collection: Option<String>) {
+        self.collection = collection;
+    }
+
+    pub fn finish(
+        self,
+        logger: &AuditLogger,
+        credentials: Option<&Credentials>,
+        status: String,
+        success: bool,
+    ) {
+        logger.log(&AuditEvent {
+            timestamp: Utc::now(),
+            api: self.api,
+            credentials,
+            client: self.client,
+            collection: self.collection.as_deref(),
+            kind: self.kind,
+            operation: &self.operation,
+            status,
+            success,
+            duration_ms: self.start.elapsed().as_secs_f64() * 1000.0,
+        });
+    }
+}
+
+/// Kind of a REST operation, by its method and route pattern
+pub fn rest_operation_kind(method: &str, pattern: &str) -> OperationKind {
+    if pattern.contains("/snapshots") {
+        return OperationKind::Snapshot;
+    }
+
+    if matches!(method, "GET" | "HEAD" | "OPTIONS") {
+        return OperationKind::Read;
+    }
+
+    if pattern.starts_with("/cluster")
+        || pattern.ends_with("/cluster")
+        || pattern.contains("/shards")
+    {
+        return OperationKind::Cluster;
+    }
+
+    // Reads with a request body
+    const READ_ROUTES: [&str; 7] = [
+        "/points/search",
+        "/points/recommend",
+        "/points/discover",
+        "/points/query",
+        "/points/scroll",
+        "/points/count",
+        "/facet",
+    ];
+
+    if method == "POST"
+        && (pattern.ends_with("/points") || READ_ROUTES.iter().any(|r| pattern.contains(r)))
+    {
+        return OperationKind::Read;
+    }
+
+    OperationKind::Write
+}
+
+/// Collection of a REST operation, if its route has one
+pub fn rest_collection<'a>(pattern: &str, path: &'a str) -> Option<&'a str> {
+    let mut pattern = pattern.split('/').skip(1);
+    if pattern.next()? != "collections" || !pattern.next()?.starts_with('{') {
+        return None;
+    }
+    path.split('/').nth(2)
+}
+
+/// Kind of a gRPC operation, by its path, such as `/qdrant.Points/Upsert`
+pub fn grpc_operation_kind(path: &str) -> OperationKind {
+    let (service, method) = split_grpc_path(path);
+
+    match service {
+        "qdrant.Snapshots" => OperationKind::Snapshot,
+        "qdrant.Collections" => match method {
+            "Get"
+            | "List"
+            | "ListCollectionAliases"
+            | "ListAliases"
+            | "CollectionClusterInfo"
+            | "CollectionExists" => OperationKind::Read,
+            "UpdateCollectionClusterSetup" | "CreateShardKey" | "DeleteShardKey" => {
+                OperationKind::Cluster
+            }
+            _ => OperationKind::Write,
+        },
+        "qdrant.Points" => {
+            const READ_METHODS: [&str; 8] = [
+                "Get",
+                "Search",
+                "Recommend",
+                "Discover",
+                "Query",
+                "Scroll",
+                "Count",
+                "Facet",
+            ];
+            if READ_METHODS.iter().any(|m| method.starts_with(m)) {
+                OperationKind::Read
+            } else {
+                OperationKind::Write
+            }
+        }
+        "qdrant.Qdrant" | "grpc.health.v1.Health" => OperationKind::Read,
+        _ if service.starts_with("grpc.reflection.") => OperationKind::Read,
+        _ => OperationKind::Write,
+    }
+}
+
+/// Check if the request message of a gRPC method has the collection name as its first field
+pub fn grpc_has_collection(path: &str) -> bool {
+    match split_grpc_path(path) {
+        ("qdrant.Points", _) => true,
+        ("qdrant.Collections", method) => {
+            !matches!(method, "List" | "ListAliases" | "UpdateAliases")
+        }
+        ("qdrant.Snapshots", method) => !method.ends_with("Full"),
+        _ => false,
+    }
+}
+
+/// Leading bytes of a gRPC request body, which are enough to read a collection name of up to
+/// 255 bytes: message header, field tag and a two byte length
+pub const GRPC_COLLECTION_PREFIX_LEN: usize = 5 + 1 + 2 + 255;
+
+/// Read the collection name from the body of a gRPC request
+///
+/// The body is a length-prefixed message, and the collection name must be its first field.
+/// Compressed messages are not supported.
+pub fn grpc_collection(body: &[u8]) -> Option<&str> {
+    let (&compressed, body) = body.split_first()?;
+    if compressed != 0 {
+        return None;
+    }
+
+    // Skip message length, field 1 with wire type 2 (length-delimited) is tagged as 0x0A
+    let (&tag, mut message) = body.get(4..)?.split_first()?;
+    if tag != 0x0A {
+        return None;
+    }
+
+    let mut length = 0usize;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = message.split_first()?;
+        message = rest;
+        length |= usize::from(byte & 0x7F) << shift;
+        if byte & 0x80 == 0 {
+            return std::str::from_utf8(message.get(..length)?).ok();
+        }
+    }
+
+    None
+}
+
+fn split_grpc_path(path: &str) -> (&str, &str) {
+    let path = path.strip_prefix('/').unwrap_or(path);
+    path.split_once('/').unwrap_or((path, ""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rest_operation_kind() {
+        let kind = rest_operation_kind;
+        assert_eq!(
+            kind("PUT", "/collections/{name}/points"),
+            OperationKind::Write
+        );
+        assert_eq!(
+            kind("POST", "/collections/{name}/points"),
+            OperationKind::Read
+        );
+        assert_eq!(
+            kind("POST", "/collections/{name}/points/batch"),
+            OperationKind::Write
+        );
+        assert_eq!(
+            kind("POST", "/collections/{name}/points/delete"),
+            OperationKind::Write
+        );
+        assert_eq!(
+            kind("POST", "/collections/{name}/points/search/batch"),
+            OperationKind::Read
+        );
+        assert_eq!(kind("GET", "/collections/{name}"), OperationKind::Read);
+        assert_eq!(kind("DELETE", "/collections/{name}"), OperationKind::Write);
+        assert_eq!(
+            kind("GET", "/snapshots/{snapshot_name}"),
+            OperationKind::Snapshot
+        );
+        assert_eq!(
+            kind("POST", "/collections/{name}/cluster"),
+            OperationKind::Cluster
+        );
+        assert_eq!(
+            kind("DELETE", "/cluster/peer/{peer_id}"),
+            OperationKind::Cluster
+        );
+        assert_eq!(kind("GET", "/cluster"), OperationKind::Read);
+    }
+
+    #[test]
+    fn test_rest_collection() {
+        assert_eq!(
+            rest_collection(
+                "/collections/{name}/points/delete",
+                "/collections/test/points/delete"
+            ),
+            Some("test"),
+        );
+        assert_eq!(
+            rest_collection("/collections/aliases", "/collections/aliases"),
+            None
+        );
+        assert_eq!(rest_collection("/snapshots", "/snapshots"), None);
+    }
+
+    #[test]
+    fn test_grpc_operation_kind() {
+        assert_eq!(
+            grpc_operation_kind("/qdrant.Points/Upsert"),
+            OperationKind::Write
+        );
+        assert_eq!(
+            grpc_operation_kind("/qdrant.Points/SearchBatch"),
+            OperationKind::Read
+        );
+        assert_eq!(
+            grpc_operation_kind("/qdrant.Points/DeleteVectors"),
+            OperationKind::Write
+        );
+        assert_eq!(
+            grpc_operation_kind("/qdrant.Collections/Delete"),
+            OperationKind::Write
+        );
+        assert_eq!(
+            grpc_operation_kind("/qdrant.Collections/CreateShardKey"),
+            OperationKind::Cluster,
+        );
+        assert_eq!(
+            grpc_operation_kind("/qdrant.Snapshots/List"),
+            OperationKind::Snapshot
+        );
+
+        assert!(grpc_has_collection("/qdrant.Snapshots/Create"));
+        assert!(!grpc_has_collection("/qdrant.Snapshots/CreateFull"));
+        assert!(!grpc_has_collection("/qdrant.Collections/UpdateAliases"));
+    }
+
+    #[test]
+    fn test_grpc_collection() {
+        // Uncompressed message with `collection_name: "test"` and another field
+        let body = [0, 0, 0, 0, 8, 0x0A, 4, b't', b'e', b's', b't', 0x10, 1];
+        assert_eq!(grpc_collection(&body), Some("test"));
+
+        // Compressed
+        let mut compressed = body;
+        compressed[0] = 1;
+        assert_eq!(grpc_collection(&compressed), None);
+
+        // First field is not a string
+        assert_eq!(grpc_collection(&[0, 0, 0, 0, 2, 0x10, 1]), None);
+
+        // Truncated
+        assert_eq!(grpc_collection(&body[..8]), None);
+
+        // Longest collection name fits into the prefix read from the body
+        let name = "x".repeat(255);
+        let mut body = vec![0, 0, 0, 1, 3, 0x0A, 0xFF, 0x01];
+        body.extend_from_slice(name.as_bytes());
+        body.extend_from_slice(&[0x10, 1]);
+        assert_eq!(
+            grpc_collection(&body[..GRPC_COLLECTION_PREFIX_LEN]),
+            Some(name.as_str()),
+        );
+    }
+
+    #[test]
+    fn test_rotation() {
+        let dir = tempfile::Builder::new().prefix("audit").tempdir().unwrap();
+        let log_file = dir.path().join("audit.log");
+
+        let logger = AuditLogger::new(AuditConfig {
+            log_file: log_file.to_str().unwrap().to_string(),
+            max_file_size_mb: 1,
+            max_files: 2,
+            log_reads: false,
+        })
+        .unwrap();
+
+        let operation = "x".repeat(400 * 1024);
+        let event = AuditEvent {
+            timestamp: Utc::now(),
+            api: Api::Rest,
+            credentials: Some(&Credentials::ApiKey),
+            client: None,
+            collection: Some("test"),
+            kind: OperationKind::Write,
+            operation: &operation,
+            status: "200".to_string(),
+            success: true,
+            duration_ms: 1.0,
+        };
+
+        // Two events per file
+        for _ in 0..7 {
+            logger.log(&event);
+        }
+
+        // Dropping the logger waits for the background writer to finish
+        drop(logger);
+
+        let lines = |path: PathBuf| fs::read_to_string(path).unwrap().lines().count();
+        assert_eq!(lines(log_file.clone()), 1);
+        assert_eq!(lines(rotated_path(&log_file, 1)), 2);
+        assert_eq!(lines(rotated_path(&log_file, 2)), 2);
+        assert!(!rotated_path(&log_file, 3).exists());
+
+        let line = fs::read_to_string(&log_file).unwrap();
+        let event: serde_json::Value = serde_json::from_str(line.trim()).unwrap();
+        assert_eq!(event["credentials"]["type"], "api_key");
+        assert_eq!(event["collection"], "test");
+        assert_eq!(event["kind"], "write");
+    }
+}
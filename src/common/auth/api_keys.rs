@@ -0,0 +1,203 @@
+use std::sync::Arc;
+use std::time::Duration;
+use std::{fs, io};
+
+use super::jwt_parser::JwtParser;
+use super::public_keys::{self, PublicKeys};
+use super::reloading::Reloading;
+use crate::common::strings::ct_eq;
+use crate::settings::ServiceConfig;
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// API keys, and the JWT parser verifying tokens with them
+#[derive(Clone)]
+pub struct ApiKeySet {
+    /// A key allowing Read or Write operations
+    read_write: Option<String>,
+
+    /// A key allowing Read operations
+    read_only: Option<String>,
+
+    /// A JWT parser, based on the read_write key and configured public keys
+    jwt_parser: Option<JwtParser>,
+}
+
+impl ApiKeySet {
+    pub fn is_empty(&self) -> bool {
+        self.read_write.is_none() && self.read_only.is_none() && self.jwt_parser.is_none()
+    }
+
+    pub fn jwt_parser(&self) -> Option<&JwtParser> {
+        self.jwt_parser.as_ref()
+    }
+
+    /// Check if a key is allowed to read
+    #[inline]
+    pub fn can_read(&self, key: &str) -> bool {
+        self.read_only
+            .as_ref()
+            .map(|ro_key| ct_eq(ro_key, key))
+            .unwrap_or_default()
+    }
+
+    /// Check if a key is allowed to write
+    #[inline]
+    pub fn can_write(&self, key: &str) -> bool {
+        self.read_write
+            .as_ref()
+            .map(|rw_key| ct_eq(rw_key, key))
+            .unwrap_or_default()
+    }
+}
+
+/// A set of API keys, periodically reloaded from the key files if configured
+pub struct ApiKeys {
+    /// Current keys
+    keys: Reloading<ApiKeySet>,
+}
+
+impl ApiKeys {
+    /// Only keys read from key files are reloaded. Keys set in the config are kept until restart,
+    /// and so is whether any keys are configured at all.
+    pub fn new(service_config: &ServiceConfig) -> Result<Self> {
+        let jwt_rbac = service_config.jwt_rbac.unwrap_or_default();
+
+        // Public keys of the JWT parser, these are reloaded on their own
+        let public_keys = if jwt_rbac {
+            service_config
+                .jwt_public_keys
+                .clone()
+                .map(PublicKeys::new)
+                .transpose()?
+                .map(Arc::new)
+        } else {
+            None
+        };
+
+        let read_write = KeySource::new(&service_config.api_key_file, &service_config.api_key);
+        let read_only = KeySource::new(
+            &service_config.read_only_api_key_file,
+            &service_config.read_only_api_key,
+        );
+
+        // Nothing to reload if no key is read from a file
+        let reload_interval = match service_config.api_key_reload_interval_sec {
+            Some(seconds) if seconds > 0 && (read_write.is_file() || read_only.is_file()) => {
+                Some(Duration::from_secs(seconds))
+            }
+            _ => None,
+        };
+
+        let keys = load_api_keys(&read_write, &read_only, jwt_rbac, public_keys.clone())?;
+
+        let keys = Reloading::new("API keys", keys, reload_interval, move || {
+            load_api_keys(&read_write, &read_only, jwt_rbac, public_keys.clone())
+        })
+        .map_err(Error::ReloadThread)?;
+
+        Ok(Self { keys })
+    }
+
+    /// Get current keys
+    ///
+    /// Keys are reloaded in the background when a reload interval is configured.
+    /// If reloading fails, an error is logged and the old keys are persisted.
+    pub fn get(&self) -> Arc<ApiKeySet> {
+        self.keys.get()
+    }
+}
+
+/// Where an API key is loaded from
+enum KeySource {
+    /// Key file, read again on every reload
+    File(String),
+    /// Key set in the config, if any
+    Config(Option<String>),
+}
+
+impl KeySource {
+    /// The key file takes precedence over the key set in the config
+    fn new(file: &Option<String>, key: &Option<String>) -> Self {
+        match file {
+            Some(path) => KeySource::File(path.clone()),
+            None => KeySource::Config(key.clone()),
+        }
+    }
+
+    fn is_file(&self) -> bool {
+        matches!(self, KeySource::File(_))
+    }
+
+    fn load(&self) -> Result<Option<String>> {
+        match self {
+            KeySource::File(path) => read_key_file(path).map(Some),
+            KeySource::Config(key) => Ok(key.clone()),
+        }
+    }
+}
+
+/// Load keys from their sources
+fn load_api_keys(
+    read_write: &KeySource,
+    read_only: &KeySource,
+    jwt_rbac: bool,
+    public_keys: Option<Arc<PublicKeys>>,
+) -> Result<ApiKeySet> {
+    let read_write = read_write.load()?;
+    let read_only = read_only.load()?;
+
+    let jwt_parser = (jwt_rbac && (read_write.is_some() || public_keys.is_some()))
+        .then(|| JwtParser::with_shared_keys(read_write.as_deref(), public_keys));
+
+    Ok(ApiKeySet {
+        read_write,
+        read_only,
+        jwt_parser,
+    })
+}
+
+/// Read a key from a file, such as a mounted secret, ignoring surrounding whitespace
+fn read_key_file(path: &str) -> Result<String> {
+    let key = fs::read_to_string(path).map_err(|err| Error::ReadFile(err, path.to_string()))?;
+    let key = key.trim();
+
+    if key.is_empty() {
+        return Err(Error::EmptyFile(path.to_string()));
+    }
+
+    Ok(key.to_string())
+}
+
+/// API key errors.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("API key file could not be read: {1}")]
+    ReadFile(#[source] io::Error, String),
+    #[error("API key file is empty: {0}")]
+    EmptyFile(String),
+    #[error("failed to start reloading API keys")]
+    ReloadThread(#[source] io::Error),
+    #[error(transparent)]
+    PublicKeys(#[from] public_keys::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_key_file() {
+        let dir = tempfile::Builder::new().prefix("keys").tempdir().unwrap();
+
+        let path = dir.path().join("api_key");
+        let path = path.to_str().unwrap();
+        assert!(matches!(read_key_file(path), Err(Error::ReadFile(..))));
+
+        fs::write(path, " \n").unwrap();
+        assert!(matches!(read_key_file(path), Err(Error::EmptyFile(_))));
+
+        fs::write(path, "secret\n").unwrap();
+        assert_eq!(read_key_file(path).unwrap(), "secret");
+    }
+}
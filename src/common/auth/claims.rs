@@ -6,6 +6,14 @@ use validator::{Validate, ValidationErrors};
 
 #[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
 pub struct Claims {
+    /// Subject of the token, such as a user or service name. Recorded in the audit log.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+
+    /// Token ID, to revoke the token without revoking other tokens of the subject
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+
     /// Expiration time (seconds since UNIX epoch)
     pub exp: Option<u64>,
 
@@ -3,36 +3,52 @@ use std::sync::Arc;
 use collection::operations::shard_selector_internal::ShardSelectorInternal;
 use collection::operations::types::ScrollRequestInternal;
 use segment::types::{WithPayloadInterface, WithVector};
+use serde::Serialize;
 use storage::content_manager::errors::StorageError;
 use storage::content_manager::toc::TableOfContent;
 use storage::rbac::Access;
 
+use self::api_keys::ApiKeys;
 use self::claims::{Claims, ValueExists};
-use self::jwt_parser::JwtParser;
-use super::strings::ct_eq;
 use crate::settings::ServiceConfig;
 
+pub mod api_keys;
 pub mod claims;
 pub mod jwt_parser;
+pub mod public_keys;
+pub mod reloading;
 
 pub const HTTP_HEADER_API_KEY: &str = "api-key";
 
 /// The API keys used for auth
 #[derive(Clone)]
 pub struct AuthKeys {
-    /// A key allowing Read or Write operations
-    read_write: Option<String>,
-
-    /// A key allowing Read operations
-    read_only: Option<String>,
-
-    /// A JWT parser, based on the read_write key
-    jwt_parser: Option<JwtParser>,
+    /// API keys and JWT parser, reloaded periodically if configured
+    api_keys: Arc<ApiKeys>,
 
     /// Table of content, needed to do stateful validation of JWT
     toc: Arc<TableOfContent>,
 }
 
+/// Credentials a request is authenticated with
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Credentials {
+    ApiKey,
+    ReadOnlyApiKey,
+    Jwt { claims: Claims },
+}
+
+impl Credentials {
+    /// Subject of the JWT, if any
+    pub fn subject(&self) -> Option<&str> {
+        match self {
+            Credentials::ApiKey | Credentials::ReadOnlyApiKey => None,
+            Credentials::Jwt { claims } => claims.sub.as_deref(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum AuthError {
     Unauthorized(String),
@@ -41,40 +57,33 @@ pub enum AuthError {
 }
 
 impl AuthKeys {
-    fn get_jwt_parser(service_config: &ServiceConfig) -> Option<JwtParser> {
-        if service_config.jwt_rbac.unwrap_or_default() {
-            service_config
-                .api_key
-                .as_ref()
-                .map(|secret| JwtParser::new(secret))
-        } else {
-            None
-        }
-    }
-
     /// Defines the auth scheme given the service config
     ///
     /// Returns None if no scheme is specified.
-    pub fn try_create(service_config: &ServiceConfig, toc: Arc<TableOfContent>) -> Option<Self> {
-        match (
-            service_config.api_key.clone(),
-            service_config.read_only_api_key.clone(),
-        ) {
-            (None, None) => None,
-            (read_write, read_only) => Some(Self {
-                read_write,
-                read_only,
-                jwt_parser: Self::get_jwt_parser(service_config),
-                toc,
-            }),
+    /// Fails if configured API key files or JWT public keys can't be loaded.
+    pub fn try_create(
+        service_config: &ServiceConfig,
+        toc: Arc<TableOfContent>,
+    ) -> Result<Option<Self>, api_keys::Error> {
+        let api_keys = ApiKeys::new(service_config)?;
+
+        if api_keys.get().is_empty() {
+            return Ok(None);
         }
+
+        Ok(Some(Self {
+            api_keys: Arc::new(api_keys),
+            toc,
+        }))
     }
 
     /// Validate that the specified request is allowed for given keys.
+    ///
+    /// Returns the access of the request, and the credentials it is authenticated with.
     pub async fn validate_request<'a>(
         &self,
         get_header: impl Fn(&'a str) -> Option<&'a str>,
-    ) -> Result<Access, AuthError> {
+    ) -> Result<(Access, Credentials), AuthError> {
         let Some(key) = get_header(HTTP_HEADER_API_KEY)
             .or_else(|| get_header("authorization").and_then(|v| v.strip_prefix("Bearer ")))
         else {
@@ -83,26 +92,41 @@ impl AuthKeys {
             ));
         };
 
-        if self.can_write(key) {
-            return Ok(Access::full("Read-write access by key"));
+        let api_keys = self.api_keys.get();
+
+        if api_keys.can_write(key) {
+            return Ok((
+                Access::full("Read-write access by key"),
+                Credentials::ApiKey,
+            ));
         }
 
-        if self.can_read(key) {
-            return Ok(Access::full_ro("Read-only access by key"));
+        if api_keys.can_read(key) {
+            return Ok((
+                Access::full_ro("Read-only access by key"),
+                Credentials::ReadOnlyApiKey,
+            ));
         }
 
-        if let Some(claims) = self.jwt_parser.as_ref().and_then(|p| p.decode(key)) {
-            let Claims {
-                exp: _, // already validated on decoding
-                access,
-                value_exists,
-            } = claims?;
+        if let Some(claims) = api_keys.jwt_parser().and_then(|p| p.decode(key)) {
+            let claims = claims?;
+
+            if self
+                .toc
+                .is_token_revoked(claims.jti.as_deref(), claims.sub.as_deref())
+                .await
+            {
+                return Err(AuthError::Unauthorized(
+                    "Token has been revoked".to_string(),
+                ));
+            }
 
-            if let Some(value_exists) = value_exists {
-                self.validate_value_exists(&value_exists).await?;
+            if let Some(value_exists) = &claims.value_exists {
+                self.validate_value_exists(value_exists).await?;
             }
 
-            return Ok(access);
+            let access = claims.access.clone();
+            return Ok((access, Credentials::Jwt { claims }));
         }
 
         Err(AuthError::Unauthorized(
@@ -145,22 +169,4 @@ impl AuthKeys {
 
         Ok(())
     }
-
-    /// Check if a key is allowed to read
-    #[inline]
-    fn can_read(&self, key: &str) -> bool {
-        self.read_only
-            .as_ref()
-            .map(|ro_key| ct_eq(ro_key, key))
-            .unwrap_or_default()
-    }
-
-    /// Check if a key is allowed to write
-    #[inline]
-    fn can_write(&self, key: &str) -> bool {
-        self.read_write
-            .as_ref()
-            .map(|rw_key| ct_eq(rw_key, key))
-            .unwrap_or_default()
-    }
 }
@@ -0,0 +1,201 @@
+use std::str::FromStr;
+use std::time::Duration;
+use std::{fs, io};
+
+use jsonwebtoken::jwk::{AlgorithmParameters, EllipticCurve, Jwk, JwkSet, PublicKeyUse};
+use jsonwebtoken::{Algorithm, DecodingKey};
+
+use super::reloading::Reloading;
+use crate::settings::JwtPublicKeysConfig;
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Algorithms of RSA keys
+const RSA_ALGORITHMS: [Algorithm; 6] = [
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+];
+
+/// Public key to verify JWT signatures
+#[derive(Clone)]
+pub struct PublicKey {
+    /// Key ID, matched against the `kid` header of the token
+    kid: Option<String>,
+
+    /// Algorithms the key is allowed to verify
+    algorithms: Vec<Algorithm>,
+
+    key: DecodingKey,
+}
+
+impl PublicKey {
+    pub fn key(&self) -> &DecodingKey {
+        &self.key
+    }
+
+    /// Check if the key can verify a token with the given algorithm and key ID
+    ///
+    /// Keys without an ID match any token, and tokens without an ID match any key.
+    fn matches(&self, algorithm: Algorithm, kid: Option<&str>) -> bool {
+        if !self.algorithms.contains(&algorithm) {
+            return false;
+        }
+
+        match (self.kid.as_deref(), kid) {
+            (Some(key_kid), Some(kid)) => key_kid == kid,
+            _ => true,
+        }
+    }
+}
+
+/// A periodically reloaded set of public keys, loaded from PEM and JWKS files
+pub struct PublicKeys {
+    /// Current keys
+    keys: Reloading<Vec<PublicKey>>,
+}
+
+impl PublicKeys {
+    pub fn new(config: JwtPublicKeysConfig) -> Result<Self> {
+        let keys = load_public_keys(&config)?;
+
+        let reload_interval = match config.reload_interval_sec {
+            None | Some(0) => None,
+            Some(seconds) => Some(Duration::from_secs(seconds)),
+        };
+
+        let keys = Reloading::new("JWT public keys", keys, reload_interval, move || {
+            load_public_keys(&config)
+        })
+        .map_err(Error::ReloadThread)?;
+
+        Ok(Self { keys })
+    }
+
+    /// Find keys to verify a token with the given algorithm and key ID
+    ///
+    /// Keys are reloaded in the background when a reload interval is configured.
+    /// If reloading fails, an error is logged and the old keys are persisted.
+    pub fn find(&self, algorithm: Algorithm, kid: Option<&str>) -> Vec<PublicKey> {
+        self.keys
+            .get()
+            .iter()
+            .filter(|key| key.matches(algorithm, kid))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Load public keys from the configured PEM and JWKS files
+fn load_public_keys(config: &JwtPublicKeysConfig) -> Result<Vec<PublicKey>> {
+    let mut keys = Vec::new();
+
+    for pem in &config.pem_files {
+        let data = fs::read(&pem.path).map_err(|err| Error::ReadFile(err, pem.path.clone()))?;
+
+        let key = match pem.algorithm {
+            Algorithm::RS256
+            | Algorithm::RS384
+            | Algorithm::RS512
+            | Algorithm::PS256
+            | Algorithm::PS384
+            | Algorithm::PS512 => DecodingKey::from_rsa_pem(&data),
+            Algorithm::ES256 | Algorithm::ES384 => DecodingKey::from_ec_pem(&data),
+            Algorithm::EdDSA => DecodingKey::from_ed_pem(&data),
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => {
+                return Err(Error::SymmetricAlgorithm(pem.path.clone()));
+            }
+        }
+        .map_err(|err| Error::InvalidKey(err, pem.path.clone()))?;
+
+        keys.push(PublicKey {
+            kid: pem.kid.clone(),
+            algorithms: vec![pem.algorithm],
+            key,
+        });
+    }
+
+    if let Some(path) = &config.jwks_file {
+        let data = fs::read(path).map_err(|err| Error::ReadFile(err, path.clone()))?;
+        let jwks: JwkSet =
+            serde_json::from_slice(&data).map_err(|err| Error::InvalidJwks(err, path.clone()))?;
+
+        for jwk in &jwks.keys {
+            let Some(algorithms) = jwk_algorithms(jwk) else {
+                log::warn!(
+                    "Skipping unsupported key {:?} in JWKS file {path}",
+                    jwk.common.key_id,
+                );
+                continue;
+            };
+
+            let key =
+                DecodingKey::from_jwk(jwk).map_err(|err| Error::InvalidKey(err, path.clone()))?;
+
+            keys.push(PublicKey {
+                kid: jwk.common.key_id.clone(),
+                algorithms,
+                key,
+            });
+        }
+    }
+
+    if keys.is_empty() {
+        return Err(Error::NoKeys);
+    }
+
+    Ok(keys)
+}
+
+/// Algorithms a JWK is allowed to verify, or None if it can't be used to verify signatures
+fn jwk_algorithms(jwk: &Jwk) -> Option<Vec<Algorithm>> {
+    if matches!(
+        jwk.common.public_key_use,
+        Some(PublicKeyUse::Encryption | PublicKeyUse::Other(_)),
+    ) {
+        return None;
+    }
+
+    let key_algorithms = match &jwk.algorithm {
+        AlgorithmParameters::RSA(_) => RSA_ALGORITHMS.to_vec(),
+        AlgorithmParameters::EllipticCurve(params) => match params.curve {
+            EllipticCurve::P256 => vec![Algorithm::ES256],
+            EllipticCurve::P384 => vec![Algorithm::ES384],
+            EllipticCurve::P521 | EllipticCurve::Ed25519 => return None,
+        },
+        AlgorithmParameters::OctetKeyPair(params) if params.curve == EllipticCurve::Ed25519 => {
+            vec![Algorithm::EdDSA]
+        }
+        // Symmetric keys are not public keys
+        AlgorithmParameters::OctetKeyPair(_) | AlgorithmParameters::OctetKey(_) => return None,
+    };
+
+    // Restrict to the declared algorithm of the key, if any
+    match jwk.common.key_algorithm {
+        None => Some(key_algorithms),
+        Some(key_algorithm) => {
+            let algorithm = Algorithm::from_str(&key_algorithm.to_string()).ok()?;
+            key_algorithms.contains(&algorithm).then(|| vec![algorithm])
+        }
+    }
+}
+
+/// JWT public key errors.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("JWT public key file could not be read: {1}")]
+    ReadFile(#[source] io::Error, String),
+    #[error("invalid JWT public key in {1}")]
+    InvalidKey(#[source] jsonwebtoken::errors::Error, String),
+    #[error("invalid JWKS file: {1}")]
+    InvalidJwks(#[source] serde_json::Error, String),
+    #[error("symmetric algorithm configured for JWT public key {0}")]
+    SymmetricAlgorithm(String),
+    #[error("no JWT public keys found")]
+    NoKeys,
+    #[error("failed to start reloading JWT public keys")]
+    ReloadThread(#[source] io::Error),
+}
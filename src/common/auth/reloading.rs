@@ -0,0 +1,122 @@
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use std::{fmt, io};
+
+use parking_lot::RwLock;
+
+/// A value reloaded periodically by a background thread, such as keys loaded from files
+///
+/// Readers always get the cached value, and never wait for a reload.
+/// If reloading fails, an error is logged and the current value is kept until the next reload.
+pub struct Reloading<T> {
+    value: Arc<RwLock<Arc<T>>>,
+    /// Closing the channel stops the reload thread
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl<T: Send + Sync + 'static> Reloading<T> {
+    /// Hold `value`, replacing it with the result of `load` every `interval`, if configured
+    ///
+    /// `name` describes the value in logs.
+    pub fn new<E: fmt::Display>(
+        name: &'static str,
+        value: T,
+        interval: Option<Duration>,
+        mut load: impl FnMut() -> Result<T, E> + Send + 'static,
+    ) -> io::Result<Self> {
+        let value = Arc::new(RwLock::new(Arc::new(value)));
+
+        let Some(interval) = interval else {
+            return Ok(Self {
+                value,
+                stop: None,
+                handle: None,
+            });
+        };
+
+        let (stop, stopped) = mpsc::channel::<()>();
+
+        let reloaded_value = value.clone();
+        let handle = thread::Builder::new()
+            .name(format!("reload {name}"))
+            .spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                    match load() {
+                        Ok(new_value) => *reloaded_value.write() = Arc::new(new_value),
+                        Err(err) => log::error!("Failed to reload {name}, keeping current: {err}"),
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            value,
+            stop: Some(stop),
+            handle: Some(handle),
+        })
+    }
+
+    /// Get the current value
+    pub fn get(&self) -> Arc<T> {
+        self.value.read().clone()
+    }
+}
+
+impl<T> Drop for Reloading<T> {
+    fn drop(&mut self) {
+        self.stop.take();
+
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                log::error!("Reload thread panicked");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn test_reloading() {
+        let loads = Arc::new(AtomicUsize::new(0));
+
+        let reloading = Reloading::new("test value", 0, Some(Duration::from_millis(10)), {
+            let loads = loads.clone();
+            move || match loads.fetch_add(1, Ordering::SeqCst) {
+                // Failed reload keeps the current value
+                0 => Err("failed"),
+                n => Ok(n),
+            }
+        })
+        .unwrap();
+
+        assert_eq!(*reloading.get(), 0);
+
+        while loads.load(Ordering::SeqCst) < 3 {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(*reloading.get() >= 1);
+
+        drop(reloading);
+        let stopped_loads = loads.load(Ordering::SeqCst);
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(loads.load(Ordering::SeqCst), stopped_loads);
+    }
+
+    #[test]
+    fn test_not_reloading_without_interval() {
+        let reloading = Reloading::new("test value", 1, None, || -> Result<_, &str> {
+            panic!("value must not be reloaded")
+        })
+        .unwrap();
+
+        assert_eq!(*reloading.get(), 1);
+        assert!(reloading.handle.is_none());
+    }
+}
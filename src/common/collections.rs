@@ -1,8 +1,11 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Duration;
 
 use api::grpc::models::{CollectionDescription, CollectionsResponse};
 use api::grpc::qdrant::CollectionExists;
+use collection::collection::resharding::{ReshardKey, ReshardingDirection, ReshardingStage};
+use collection::collection_state;
 use collection::config::ShardingMethod;
 use collection::operations::cluster_ops::{
     AbortTransferOperation, ClusterOperations, DropReplicaOperation, MoveShardOperation,
@@ -13,6 +16,7 @@ use collection::operations::snapshot_ops::SnapshotDescription;
 use collection::operations::types::{
     AliasDescription, CollectionClusterInfo, CollectionInfo, CollectionsAliasesResponse,
 };
+use collection::shards::peer_groups::PeerGroups;
 use collection::shards::replica_set;
 use collection::shards::shard::{PeerId, ShardId, ShardsPlacement};
 use collection::shards::transfer::{ShardTransfer, ShardTransferKey, ShardTransferRestart};
@@ -24,9 +28,11 @@ use storage::content_manager::collection_meta_ops::{
     ShardTransferOperations, UpdateCollectionOperation,
 };
 use storage::content_manager::errors::StorageError;
+use storage::content_manager::shard_distribution::ShardDistributionProposal;
 use storage::content_manager::toc::TableOfContent;
 use storage::dispatcher::Dispatcher;
-use storage::rbac::{Access, AccessRequirements};
+use storage::rbac::{Access, AccessRequirements, OperationClass};
+use storage::types::PeerMetadataById;
 use tokio::task::JoinHandle;
 
 pub async fn do_collection_exists(
@@ -153,8 +159,12 @@ pub async fn do_list_snapshots(
     access: Access,
     collection_name: &str,
 ) -> Result<Vec<SnapshotDescription>, StorageError> {
-    let collection_pass =
-        access.check_collection_access(collection_name, AccessRequirements::new().whole())?;
+    let collection_pass = access.check_collection_access(
+        collection_name,
+        AccessRequirements::new()
+            .whole()
+            .operations(&[OperationClass::Snapshot]),
+    )?;
     Ok(toc
         .get_collection(&collection_pass)
         .await?
@@ -168,13 +178,56 @@ pub fn do_create_snapshot(
     collection_name: &str,
 ) -> Result<JoinHandle<Result<SnapshotDescription, StorageError>>, StorageError> {
     let collection_pass = access
-        .check_collection_access(collection_name, AccessRequirements::new().write().whole())?
+        .check_collection_access(
+            collection_name,
+            AccessRequirements::new()
+                .write()
+                .whole()
+                .operations(&[OperationClass::Snapshot]),
+        )?
         .into_static();
     Ok(tokio::spawn(async move {
         toc.create_snapshot(&collection_pass).await
     }))
 }
 
+/// Validate that a new replica of the shard on peer `to`, replacing the replica on peer `from` if
+/// any, respects the replica placement constraint of the collection
+fn validate_replica_placement(
+    state: &collection_state::State,
+    peer_metadata: &PeerMetadataById,
+    peers: &[PeerId],
+    shard_id: ShardId,
+    from: Option<PeerId>,
+    to: PeerId,
+) -> Result<(), StorageError> {
+    let Some(replica_placement) = &state.config.params.replica_placement else {
+        return Ok(());
+    };
+
+    let peer_groups = PeerGroups::new(Some(replica_placement), peer_metadata);
+    let replicas: Vec<_> = state
+        .shards
+        .get(&shard_id)
+        .map(|shard_info| shard_info.replicas.keys().copied().collect())
+        .unwrap_or_default();
+
+    let is_allowed = match from {
+        Some(from) => peer_groups.allows_move(&replicas, from, to, peers),
+        None => peer_groups.allows_replica(&replicas, to, peers),
+    };
+
+    if !is_allowed {
+        return Err(StorageError::bad_request(format!(
+            "Replica of shard {shard_id} on peer {to} does not respect the replica placement of the collection, \
+             replicas must be spread across peers by label '{}'",
+            replica_placement.spread_by_label,
+        )));
+    }
+
+    Ok(())
+}
+
 pub async fn do_get_collection_cluster(
     toc: &TableOfContent,
     access: Access,
@@ -252,6 +305,15 @@ pub async fn do_update_collection_cluster(
             validate_peer_exists(move_shard.to_peer_id)?;
             validate_peer_exists(move_shard.from_peer_id)?;
 
+            validate_replica_placement(
+                &collection.state().await,
+                &consensus_state.peer_metadata_by_id(),
+                &get_all_peer_ids(),
+                move_shard.shard_id,
+                Some(move_shard.from_peer_id),
+                move_shard.to_peer_id,
+            )?;
+
             // submit operation to consensus
             dispatcher
                 .submit_collection_meta_op(
@@ -288,6 +350,15 @@ pub async fn do_update_collection_cluster(
             // validate source peer exists
             validate_peer_exists(replicate_shard.from_peer_id)?;
 
+            validate_replica_placement(
+                &collection.state().await,
+                &consensus_state.peer_metadata_by_id(),
+                &get_all_peer_ids(),
+                replicate_shard.shard_id,
+                None,
+                replicate_shard.to_peer_id,
+            )?;
+
             // submit operation to consensus
             dispatcher
                 .submit_collection_meta_op(
@@ -387,12 +458,10 @@ pub async fn do_update_collection_cluster(
 
             let shard_number = create_sharding_key
                 .shards_number
-                .unwrap_or(state.config.params.shard_number)
-                .get() as usize;
+                .unwrap_or(state.config.params.shard_number);
             let replication_factor = create_sharding_key
                 .replication_factor
-                .unwrap_or(state.config.params.replication_factor)
-                .get() as usize;
+                .unwrap_or(state.config.params.replication_factor);
 
             let shard_keys_mapping = state.shards_key_mapping;
             if shard_keys_mapping.contains_key(&create_sharding_key.shard_key) {
@@ -422,8 +491,29 @@ pub async fn do_update_collection_cluster(
                 get_all_peer_ids()
             };
 
-            let exact_placement =
-                generate_even_placement(peers_pool, shard_number, replication_factor);
+            let exact_placement = match &state.config.params.replica_placement {
+                Some(replica_placement) => {
+                    let peer_groups = PeerGroups::new(
+                        Some(replica_placement),
+                        &consensus_state.peer_metadata_by_id(),
+                    );
+                    ShardDistributionProposal::new_spread(
+                        shard_number,
+                        replication_factor,
+                        &peers_pool,
+                        &peer_groups,
+                    )
+                    .distribution
+                    .into_iter()
+                    .map(|(_, peers)| peers)
+                    .collect()
+                }
+                None => generate_even_placement(
+                    peers_pool,
+                    shard_number.get() as usize,
+                    replication_factor.get() as usize,
+                ),
+            };
 
             dispatcher
                 .submit_collection_meta_op(
@@ -515,62 +605,144 @@ pub async fn do_update_collection_cluster(
                 .await
         }
         ClusterOperations::StartResharding(op) => {
-            let StartResharding { peer_id, shard_key } = op.start_resharding;
+            let StartResharding {
+                direction,
+                peer_id,
+                shard_key,
+            } = op.start_resharding;
 
-            let peer_id = match peer_id {
-                Some(peer_id) => {
-                    validate_peer_exists(peer_id)?;
-                    peer_id
-                }
+            let collection_state = collection.state().await;
 
+            if let Some(resharding) = &collection_state.resharding {
+                return Err(StorageError::bad_request(format!(
+                    "resharding {resharding:?} is already in progress \
+                     for collection {collection_name}"
+                )));
+            }
+
+            // Shards in the hashring being resharded
+            let ring_shard_ids: Vec<ShardId> = match &shard_key {
+                Some(shard_key) => {
+                    let Some(shard_ids) = collection_state.shards_key_mapping.get(shard_key) else {
+                        return Err(StorageError::bad_request(format!(
+                            "sharding key {shard_key} does not exists for collection {collection_name}"
+                        )));
+                    };
+                    shard_ids.iter().copied().sorted().collect()
+                }
                 None => {
-                    // TODO(resharding): Select `peer_id` for resharding in a more reasonable way!?
-                    consensus_state
-                        .persistent
-                        .read()
-                        .peer_address_by_id
-                        .read()
+                    let keyed_shard_ids: HashSet<ShardId> = collection_state
+                        .shards_key_mapping
+                        .values()
+                        .flatten()
+                        .copied()
+                        .collect();
+                    collection_state
+                        .shards
                         .keys()
                         .copied()
-                        .next()
-                        .unwrap()
+                        .filter(|shard_id| !keyed_shard_ids.contains(shard_id))
+                        .sorted()
+                        .collect()
                 }
             };
 
-            let collection_state = collection.state().await;
+            let (peer_id, shard_id) = match direction {
+                ReshardingDirection::Up => {
+                    let peer_id = match peer_id {
+                        Some(peer_id) => {
+                            validate_peer_exists(peer_id)?;
+                            peer_id
+                        }
+
+                        // Select peer holding the least shards of this collection
+                        None => {
+                            let peers: Vec<PeerId> = consensus_state
+                                .persistent
+                                .read()
+                                .peer_address_by_id
+                                .read()
+                                .keys()
+                                .copied()
+                                .sorted()
+                                .collect();
+
+                            peers
+                                .into_iter()
+                                .min_by_key(|peer_id| {
+                                    collection_state
+                                        .shards
+                                        .values()
+                                        .filter(|shard| shard.replicas.contains_key(peer_id))
+                                        .count()
+                                })
+                                .ok_or_else(|| {
+                                    StorageError::service_error("no peers available for resharding")
+                                })?
+                        }
+                    };
+
+                    // New shard gets the next free shard ID of the collection
+                    let shard_id = collection_state
+                        .shards
+                        .keys()
+                        .copied()
+                        .max()
+                        .map_or(0, |id| id + 1);
 
-            // TODO(resharding): Select `shard_id` for resharding in a more reasonable way?..
-            let shard_id = collection_state
-                .shards
-                .keys()
-                .copied()
-                .max()
-                .map_or(0, |id| id + 1);
-
-            if let Some(shard_key) = &shard_key {
-                if !collection_state.shards_key_mapping.contains_key(shard_key) {
-                    return Err(StorageError::bad_request(format!(
-                        "sharding key {shard_key} does not exists for collection {collection_name}"
-                    )));
+                    (peer_id, shard_id)
                 }
-            }
 
-            if let Some(resharding) = &collection_state.resharding {
-                return Err(StorageError::bad_request(format!(
-                    "resharding {resharding:?} is already in progress \
-                     for collection {collection_name}"
-                )));
-            }
+                ReshardingDirection::Down => {
+                    if ring_shard_ids.len() < 2 {
+                        return Err(StorageError::bad_request(format!(
+                            "can't reshard down collection {collection_name}, \
+                             it must have at least two shards"
+                        )));
+                    }
+
+                    // Remove the last shard
+                    let shard_id = *ring_shard_ids.last().unwrap();
+
+                    let active_peers: Vec<PeerId> = collection_state.shards[&shard_id]
+                        .replicas
+                        .iter()
+                        .filter(|(_, state)| **state == replica_set::ReplicaState::Active)
+                        .map(|(peer_id, _)| *peer_id)
+                        .sorted()
+                        .collect();
+
+                    // Resharding down is driven by a peer holding the removed shard
+                    let peer_id = match peer_id {
+                        Some(peer_id) if active_peers.contains(&peer_id) => peer_id,
+                        Some(peer_id) => {
+                            return Err(StorageError::bad_request(format!(
+                                "peer {peer_id} has no active replica of shard {shard_id}, \
+                                 which is removed when resharding down"
+                            )));
+                        }
+                        None => active_peers.first().copied().ok_or_else(|| {
+                            StorageError::bad_request(format!(
+                                "shard {shard_id} has no active replica, \
+                                 which is required for resharding down"
+                            ))
+                        })?,
+                    };
+
+                    (peer_id, shard_id)
+                }
+            };
 
             dispatcher
                 .submit_collection_meta_op(
                     CollectionMetaOperations::Resharding(
                         collection_name.clone(),
-                        ReshardingOperation::Start {
+                        ReshardingOperation::Start(ReshardKey {
+                            direction,
                             peer_id,
                             shard_id,
                             shard_key,
-                        },
+                        }),
                     ),
                     access,
                     wait_timeout,
@@ -584,15 +756,19 @@ pub async fn do_update_collection_cluster(
                 )));
             };
 
+            if state.stage >= ReshardingStage::WriteHashRingCommitted {
+                return Err(StorageError::bad_request(format!(
+                    "resharding of collection {collection_name} can't be aborted \
+                     in {:?} stage anymore",
+                    state.stage,
+                )));
+            }
+
             dispatcher
                 .submit_collection_meta_op(
                     CollectionMetaOperations::Resharding(
                         collection_name.clone(),
-                        ReshardingOperation::Abort {
-                            peer_id: state.peer_id,
-                            shard_id: state.shard_id,
-                            shard_key: state.shard_key.clone(),
-                        },
+                        ReshardingOperation::Abort(state.key()),
                     ),
                     access,
                     wait_timeout,
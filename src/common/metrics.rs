@@ -1,3 +1,5 @@
+use collection::collection_manager::optimizers::TrackerStatus;
+use collection::shards::telemetry::LocalShardTelemetry;
 use prometheus::proto::{Counter, Gauge, LabelPair, Metric, MetricFamily, MetricType};
 use prometheus::TextEncoder;
 use segment::common::operation_time_statistics::OperationDurationStatistics;
@@ -11,6 +13,7 @@ use crate::common::telemetry_ops::collections_telemetry::{
 use crate::common::telemetry_ops::requests_telemetry::{
     GrpcTelemetry, RequestsTelemetry, WebApiTelemetry,
 };
+use crate::settings::CollectionMetricsConfig;
 
 /// Whitelist for REST endpoints in metrics output.
 ///
@@ -55,6 +58,21 @@ pub struct MetricsData {
 }
 
 impl MetricsData {
+    /// Build metrics, with labelled metrics of each collection if configured.
+    ///
+    /// Collection metrics require full collection telemetry, of at least `Level2`.
+    pub fn new(
+        telemetry_data: TelemetryData,
+        collection_metrics: Option<&CollectionMetricsConfig>,
+    ) -> Self {
+        let mut metrics = vec![];
+        telemetry_data.add_metrics(&mut metrics);
+        if let Some(config) = collection_metrics {
+            add_collection_metrics(&telemetry_data.collections, config, &mut metrics);
+        }
+        Self { metrics }
+    }
+
     pub fn format_metrics(&self) -> String {
         TextEncoder::new().encode_to_string(&self.metrics).unwrap()
     }
@@ -62,9 +80,7 @@ impl MetricsData {
 
 impl From<TelemetryData> for MetricsData {
     fn from(telemetry_data: TelemetryData) -> Self {
-        let mut metrics = vec![];
-        telemetry_data.add_metrics(&mut metrics);
-        Self { metrics }
+        Self::new(telemetry_data, None)
     }
 }
 
@@ -244,6 +260,200 @@ impl MetricsProvider for GrpcTelemetry {
     }
 }
 
+/// Add labelled metrics of each collection, or of each local shard of a collection.
+///
+/// Only the first `max_collections` collections by name are included, to limit cardinality.
+fn add_collection_metrics(
+    collections: &CollectionsTelemetry,
+    config: &CollectionMetricsConfig,
+    metrics: &mut Vec<MetricFamily>,
+) {
+    let mut collections: Vec<_> = collections
+        .collections
+        .iter()
+        .flatten()
+        .filter_map(|collection| match collection {
+            CollectionTelemetryEnum::Full(collection) => Some(collection),
+            CollectionTelemetryEnum::Aggregated(_) => None,
+        })
+        .collect();
+    collections.sort_unstable_by(|a, b| a.id.cmp(&b.id));
+    collections.truncate(config.max_collections);
+
+    let mut builder = CollectionMetricsBuilder::default();
+    for collection in collections {
+        let local_shards = collection
+            .shards
+            .iter()
+            .filter_map(|shard| Some((shard.id, shard.local.as_ref()?)));
+
+        if config.per_shard {
+            for (shard_id, shard) in local_shards {
+                builder.add(
+                    [shard],
+                    &[
+                        ("collection", &collection.id),
+                        ("shard", &shard_id.to_string()),
+                    ],
+                );
+            }
+        } else {
+            builder.add(
+                local_shards.map(|(_, shard)| shard),
+                &[("collection", &collection.id)],
+            );
+        }
+    }
+    builder.build(metrics);
+}
+
+/// A helper struct to build a vector of [`MetricFamily`] out of the telemetry of local shards,
+/// labelled by collection and optionally shard.
+#[derive(Default)]
+struct CollectionMetricsBuilder {
+    points: Vec<Metric>,
+    vectors: Vec<Metric>,
+    indexed_vectors: Vec<Metric>,
+    segments: Vec<Metric>,
+    ram_usage_bytes: Vec<Metric>,
+    disk_usage_bytes: Vec<Metric>,
+    pending_wal_operations: Vec<Metric>,
+    running_optimizations: Vec<Metric>,
+    optimized_points: Vec<Metric>,
+    searches: OperationDurationMetricsBuilder,
+    optimizations: OperationDurationMetricsBuilder,
+}
+
+impl CollectionMetricsBuilder {
+    /// Add metrics for the sum of the provided local shards.
+    pub fn add<'a>(
+        &mut self,
+        shards: impl IntoIterator<Item = &'a LocalShardTelemetry>,
+        labels: &[(&str, &str)],
+    ) {
+        let mut points = 0;
+        let mut vectors = 0;
+        let mut indexed_vectors = 0;
+        let mut segments = 0;
+        let mut ram_usage_bytes = 0;
+        let mut disk_usage_bytes = 0;
+        let mut pending_wal_operations = 0;
+        let mut running_optimizations = 0;
+        let mut optimized_points = 0;
+        let mut searches = OperationDurationStatistics::default();
+        let mut optimizations = OperationDurationStatistics::default();
+
+        for shard in shards {
+            for segment in &shard.segments {
+                points += segment.info.num_points;
+                vectors += segment.info.num_vectors;
+                indexed_vectors += segment.info.num_indexed_vectors;
+                ram_usage_bytes += segment.info.ram_usage_bytes;
+                disk_usage_bytes += segment.info.disk_usage_bytes;
+            }
+            segments += shard.segments.len();
+            pending_wal_operations += shard.pending_wal_operations;
+            running_optimizations += shard
+                .optimizations
+                .log
+                .iter()
+                .filter(|tracker| tracker.status == TrackerStatus::Optimizing)
+                .count();
+            optimized_points += shard.optimizations.optimized_points;
+            searches = searches + shard.searches.clone();
+            optimizations = optimizations + shard.optimizations.optimizations.clone();
+        }
+
+        self.points.push(gauge(points as f64, labels));
+        self.vectors.push(gauge(vectors as f64, labels));
+        self.indexed_vectors
+            .push(gauge(indexed_vectors as f64, labels));
+        self.segments.push(gauge(segments as f64, labels));
+        self.ram_usage_bytes
+            .push(gauge(ram_usage_bytes as f64, labels));
+        self.disk_usage_bytes
+            .push(gauge(disk_usage_bytes as f64, labels));
+        self.pending_wal_operations
+            .push(gauge(pending_wal_operations as f64, labels));
+        self.running_optimizations
+            .push(gauge(running_optimizations as f64, labels));
+        self.optimized_points
+            .push(counter(optimized_points as f64, labels));
+        self.searches.add(&searches, labels, true);
+        self.optimizations.add(&optimizations, labels, true);
+    }
+
+    /// Build metrics and add them to the provided vector.
+    pub fn build(self, metrics: &mut Vec<MetricFamily>) {
+        if self.points.is_empty() {
+            return;
+        }
+
+        metrics.push(metric_family(
+            "collection_points",
+            "number of points in collection",
+            MetricType::GAUGE,
+            self.points,
+        ));
+        metrics.push(metric_family(
+            "collection_vectors",
+            "number of vectors in collection",
+            MetricType::GAUGE,
+            self.vectors,
+        ));
+        metrics.push(metric_family(
+            "collection_indexed_vectors",
+            "number of indexed vectors in collection",
+            MetricType::GAUGE,
+            self.indexed_vectors,
+        ));
+        metrics.push(metric_family(
+            "collection_segments",
+            "number of segments in collection",
+            MetricType::GAUGE,
+            self.segments,
+        ));
+        metrics.push(metric_family(
+            "collection_ram_usage_bytes",
+            "estimated RAM usage of collection",
+            MetricType::GAUGE,
+            self.ram_usage_bytes,
+        ));
+        metrics.push(metric_family(
+            "collection_disk_usage_bytes",
+            "disk usage of collection",
+            MetricType::GAUGE,
+            self.disk_usage_bytes,
+        ));
+        metrics.push(metric_family(
+            "collection_pending_wal_operations",
+            "number of operations in WAL, not yet acknowledged after flushing into segments",
+            MetricType::GAUGE,
+            self.pending_wal_operations,
+        ));
+        metrics.push(metric_family(
+            "collection_running_optimizations",
+            "number of running optimizations",
+            MetricType::GAUGE,
+            self.running_optimizations,
+        ));
+        metrics.push(metric_family(
+            "collection_optimized_points_total",
+            "number of points in segments optimized and indexed, its rate is the indexing throughput",
+            MetricType::COUNTER,
+            self.optimized_points,
+        ));
+        self.searches
+            .build_named("collection_searches", "searches", "search", metrics);
+        self.optimizations.build_named(
+            "collection_optimizations",
+            "optimizations",
+            "optimization",
+            metrics,
+        );
+    }
+}
+
 /// A helper struct to build a vector of [`MetricFamily`] out of a collection of
 /// [`OperationDurationStatistics`].
 #[derive(Default)]
@@ -299,50 +509,67 @@ impl OperationDurationMetricsBuilder {
 
     /// Build metrics and add them to the provided vector.
     pub fn build(self, prefix: &str, metrics: &mut Vec<MetricFamily>) {
+        self.build_named(
+            &format!("{prefix}_responses"),
+            "responses",
+            "response",
+            metrics,
+        );
+    }
+
+    /// Build metrics named after `name`, describing operations as `plural` and `singular`, and
+    /// add them to the provided vector.
+    pub fn build_named(
+        self,
+        name: &str,
+        plural: &str,
+        singular: &str,
+        metrics: &mut Vec<MetricFamily>,
+    ) {
         if !self.total.is_empty() {
             metrics.push(metric_family(
-                &format!("{prefix}_responses_total"),
-                "total number of responses",
+                &format!("{name}_total"),
+                &format!("total number of {plural}"),
                 MetricType::COUNTER,
                 self.total,
             ));
         }
         if !self.fail_total.is_empty() {
             metrics.push(metric_family(
-                &format!("{prefix}_responses_fail_total"),
-                "total number of failed responses",
+                &format!("{name}_fail_total"),
+                &format!("total number of failed {plural}"),
                 MetricType::COUNTER,
                 self.fail_total,
             ));
         }
         if !self.avg_secs.is_empty() {
             metrics.push(metric_family(
-                &format!("{prefix}_responses_avg_duration_seconds"),
-                "average response duration",
+                &format!("{name}_avg_duration_seconds"),
+                &format!("average {singular} duration"),
                 MetricType::GAUGE,
                 self.avg_secs,
             ));
         }
         if !self.min_secs.is_empty() {
             metrics.push(metric_family(
-                &format!("{prefix}_responses_min_duration_seconds"),
-                "minimum response duration",
+                &format!("{name}_min_duration_seconds"),
+                &format!("minimum {singular} duration"),
                 MetricType::GAUGE,
                 self.min_secs,
             ));
         }
         if !self.max_secs.is_empty() {
             metrics.push(metric_family(
-                &format!("{prefix}_responses_max_duration_seconds"),
-                "maximum response duration",
+                &format!("{name}_max_duration_seconds"),
+                &format!("maximum {singular} duration"),
                 MetricType::GAUGE,
                 self.max_secs,
             ));
         }
         if !self.duration_histogram_secs.is_empty() {
             metrics.push(metric_family(
-                &format!("{prefix}_responses_duration_seconds"),
-                "response duration histogram",
+                &format!("{name}_duration_seconds"),
+                &format!("{singular} duration histogram"),
                 MetricType::HISTOGRAM,
                 self.duration_histogram_secs,
             ));
@@ -431,4 +658,46 @@ mod tests {
             "GRPC_ENDPOINT_WHITELIST must be sorted in code to allow binary search"
         );
     }
+
+    #[test]
+    fn test_collection_metrics() {
+        use collection::shards::telemetry::LocalShardTelemetry;
+
+        use super::CollectionMetricsBuilder;
+
+        let shard = LocalShardTelemetry {
+            variant_name: None,
+            segments: vec![],
+            searches: Default::default(),
+            pending_wal_operations: 3,
+            optimizations: Default::default(),
+        };
+
+        let mut builder = CollectionMetricsBuilder::default();
+        builder.add([&shard, &shard], &[("collection", "a")]);
+        builder.add(Vec::<&LocalShardTelemetry>::new(), &[("collection", "b")]);
+
+        let mut metrics = vec![];
+        builder.build(&mut metrics);
+
+        let pending = metrics
+            .iter()
+            .find(|family| family.get_name() == "collection_pending_wal_operations")
+            .unwrap();
+        let values: Vec<_> = pending
+            .get_metric()
+            .iter()
+            .map(|metric| {
+                (
+                    metric.get_label()[0].get_value().to_string(),
+                    metric.get_gauge().get_value(),
+                )
+            })
+            .collect();
+        assert_eq!(values, vec![("a".to_string(), 6.0), ("b".to_string(), 0.0)]);
+
+        assert!(metrics
+            .iter()
+            .any(|family| family.get_name() == "collection_searches_duration_seconds"));
+    }
 }
@@ -10,6 +10,13 @@ pub mod http_client;
 pub mod metrics;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod points;
+pub mod rate_limiting;
+#[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
+pub mod rebalancer;
+#[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
+pub mod replica_repair;
+#[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
+pub mod snapshot_scheduler;
 pub mod snapshots;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod stacktrace;
@@ -19,6 +26,7 @@ pub mod telemetry_ops;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod telemetry_reporting;
 
+pub mod audit;
 pub mod auth;
 
 pub mod strings;
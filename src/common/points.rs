@@ -112,6 +112,27 @@ pub struct UpdateOperations {
     pub operations: Vec<UpdateOperation>,
 }
 
+impl UpdateOperation {
+    /// Number of points the operation adds data to, deletes don't count
+    pub fn written_points_count(&self) -> usize {
+        match self {
+            UpdateOperation::Upsert(op) => op.upsert.points_count(),
+            UpdateOperation::SetPayload(op) => payload_points_count(&op.set_payload),
+            UpdateOperation::OverwritePayload(op) => payload_points_count(&op.overwrite_payload),
+            UpdateOperation::UpdateVectors(op) => op.update_vectors.points.len(),
+            UpdateOperation::Delete(_)
+            | UpdateOperation::DeletePayload(_)
+            | UpdateOperation::ClearPayload(_)
+            | UpdateOperation::DeleteVectors(_) => 0,
+        }
+    }
+}
+
+/// Number of points a payload is set to, an update by filter counts as a single point
+pub fn payload_points_count(operation: &SetPayload) -> usize {
+    operation.points.as_ref().map_or(0, Vec::len) + usize::from(operation.filter.is_some())
+}
+
 impl Validate for UpdateOperation {
     fn validate(&self) -> Result<(), validator::ValidationErrors> {
         match self {
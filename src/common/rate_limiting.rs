@@ -0,0 +1,657 @@
+//! Request rate limits and storage quotas, per JWT subject and per collection.
+//!
+//! Throughput is limited with token buckets, holding up to a second of throughput. A request
+//! larger than that is allowed once its bucket is full, and delays the following requests instead.
+//! Buckets refilled to their capacity are dropped periodically, they are the same as new buckets.
+//!
+//! Writes adding data to a collection are limited, and rejected once the collection reached its
+//! quota. Deletes are not limited, so that clients can always free up space.
+//!
+//! Limits are enforced by each peer on its own: buckets count requests received by the peer, and
+//! quotas count points and disk usage of the shards stored on the peer. In a cluster, a client
+//! sending requests to several peers gets up to the limit from each of them.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use api::grpc::qdrant as grpc;
+use collection::operations::types::{
+    CoreSearchRequest, DiscoverRequestInternal, RecommendGroupsRequestInternal,
+    RecommendRequestInternal, SearchGroupsRequestInternal, SearchRequestInternal,
+};
+use common::types::TelemetryDetail;
+use parking_lot::Mutex;
+use segment::types::SearchParams;
+use storage::content_manager::errors::StorageError;
+use storage::content_manager::toc::TableOfContent;
+use storage::rbac::{Access, AccessRequirements};
+
+use super::auth::Credentials;
+use crate::settings::{RateLimitsConfig, ThroughputLimits};
+
+/// How long the usage of a collection is cached for, when checking quotas
+const COLLECTION_USAGE_TTL: Duration = Duration::from_secs(5);
+
+/// How often idle buckets are dropped
+const BUCKET_EVICTION_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Rate limits and quotas, shared by the REST and gRPC APIs
+pub struct RateLimiter {
+    config: RateLimitsConfig,
+
+    buckets: Mutex<Buckets>,
+
+    /// Cached usage of collections, by collection name
+    collection_usage: Mutex<HashMap<String, CollectionUsage>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitsConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(Buckets::new(Instant::now())),
+            collection_usage: Default::default(),
+        }
+    }
+
+    /// Take `amount` from all buckets with the given keys, or from none of them
+    fn acquire(&self, keys: &[(BucketKey, f64)], amount: usize) -> Result<(), StorageError> {
+        if keys.is_empty() || amount == 0 {
+            return Ok(());
+        }
+
+        let amount = amount as f64;
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock();
+        buckets.evict_idle(now);
+        let buckets = &mut buckets.buckets;
+
+        for (key, rate) in keys {
+            let bucket = buckets
+                .entry(key.clone())
+                .or_insert_with(|| TokenBucket::new(*rate, now));
+            bucket.refill(now);
+
+            if !bucket.can_take(amount) {
+                return Err(StorageError::rate_limit_exceeded(format!(
+                    "{key} is limited to {rate} per second",
+                )));
+            }
+        }
+
+        for (key, _) in keys {
+            if let Some(bucket) = buckets.get_mut(key) {
+                bucket.take(amount);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Throughput limit of the collection, the limit set for the collection overrides the default
+    fn collection_rate(
+        &self,
+        collection: &str,
+        rate: impl Fn(&ThroughputLimits) -> Option<f64>,
+    ) -> Option<f64> {
+        self.config
+            .collections
+            .0
+            .get(collection)
+            .and_then(|limits| rate(&limits.throughput))
+            .or_else(|| rate(&self.config.per_collection))
+    }
+
+    /// Reject writes to collections which reached their quota
+    async fn check_quota(
+        &self,
+        toc: &TableOfContent,
+        collection: &str,
+    ) -> Result<(), StorageError> {
+        let limits = self.config.collections.0.get(collection);
+        let max_points = limits
+            .and_then(|limits| limits.max_points)
+            .or(self.config.max_collection_points);
+        let max_disk_bytes = limits
+            .and_then(|limits| limits.max_disk_bytes)
+            .or(self.config.max_collection_disk_bytes);
+
+        if max_points.is_none() && max_disk_bytes.is_none() {
+            return Ok(());
+        }
+
+        let usage = self.collection_usage(toc, collection).await?;
+
+        if let Some(max_points) = max_points {
+            if usage.points >= max_points {
+                return Err(StorageError::rate_limit_exceeded(format!(
+                    "Collection {collection} reached its quota of {max_points} points on this peer",
+                )));
+            }
+        }
+
+        if let Some(max_disk_bytes) = max_disk_bytes {
+            if usage.disk_bytes >= max_disk_bytes {
+                return Err(StorageError::rate_limit_exceeded(format!(
+                    "Collection {collection} reached its quota of {max_disk_bytes} bytes on this peer",
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn collection_usage(
+        &self,
+        toc: &TableOfContent,
+        collection_name: &str,
+    ) -> Result<CollectionUsage, StorageError> {
+        let cached = self.collection_usage.lock().get(collection_name).copied();
+        if let Some(usage) = cached.filter(|usage| !usage.is_expired()) {
+            return Ok(usage);
+        }
+
+        let collection_pass = Access::full("For collection quota")
+            .check_collection_access(collection_name, AccessRequirements::new())?;
+        let collection = toc.get_collection(&collection_pass).await?;
+
+        // Usage of the shards of this peer, as only they are on its disk
+        let mut usage = CollectionUsage {
+            points: 0,
+            disk_bytes: 0,
+            last_update: Instant::now(),
+        };
+
+        let telemetry = collection
+            .get_telemetry_data(TelemetryDetail::default())
+            .await;
+        let segments = telemetry
+            .shards
+            .iter()
+            .filter_map(|shard| shard.local.as_ref())
+            .flat_map(|local| &local.segments);
+
+        for segment in segments {
+            usage.points += segment.info.num_points;
+            usage.disk_bytes += segment.info.disk_usage_bytes;
+        }
+
+        let mut collection_usage = self.collection_usage.lock();
+        collection_usage.retain(|_, usage| !usage.is_expired());
+        collection_usage.insert(collection_name.to_string(), usage);
+
+        Ok(usage)
+    }
+}
+
+/// Limits applying to a request, by its JWT subject
+#[derive(Clone, Default)]
+pub struct RequestLimits {
+    rate_limiter: Option<Arc<RateLimiter>>,
+
+    /// Subject of the JWT the request is authenticated with
+    subject: Option<String>,
+}
+
+impl RequestLimits {
+    pub fn new(rate_limiter: Option<Arc<RateLimiter>>, credentials: Option<&Credentials>) -> Self {
+        Self {
+            rate_limiter,
+            subject: credentials
+                .and_then(Credentials::subject)
+                .map(ToString::to_string),
+        }
+    }
+
+    /// Check the size of searches to the collection, and their rate
+    pub fn check_searches(
+        &self,
+        collection: &str,
+        searches: impl IntoIterator<Item = SearchSize>,
+    ) -> Result<(), StorageError> {
+        let Some(rate_limiter) = &self.rate_limiter else {
+            return Ok(());
+        };
+
+        let config = &rate_limiter.config;
+        let mut count = 0;
+
+        for search in searches {
+            if let Some(max_limit) = config.max_limit {
+                if search.limit > max_limit {
+                    return Err(StorageError::bad_request(format!(
+                        "Search limit {} is larger than the maximum {max_limit}",
+                        search.limit,
+                    )));
+                }
+            }
+
+            if let (Some(max_ef), Some(ef)) = (config.max_ef, search.ef) {
+                if ef > max_ef {
+                    return Err(StorageError::bad_request(format!(
+                        "Search param hnsw_ef {ef} is larger than the maximum {max_ef}",
+                    )));
+                }
+            }
+
+            count += 1;
+        }
+
+        let keys = self.bucket_keys(rate_limiter, collection, Throughput::Searches, |limits| {
+            limits.searches_per_sec
+        });
+        rate_limiter.acquire(&keys, count)
+    }
+
+    /// Check the rate of points written to the collection, and its quota
+    ///
+    /// Applies to all writes adding data: upserts, vector updates and payload updates.
+    pub async fn check_writes(
+        &self,
+        toc: &TableOfContent,
+        collection: &str,
+        points: usize,
+    ) -> Result<(), StorageError> {
+        let Some(rate_limiter) = &self.rate_limiter else {
+            return Ok(());
+        };
+
+        if points == 0 {
+            return Ok(());
+        }
+
+        rate_limiter.check_quota(toc, collection).await?;
+
+        let keys = self.bucket_keys(
+            rate_limiter,
+            collection,
+            Throughput::WrittenPoints,
+            |limits| limits.written_points_per_sec,
+        );
+        rate_limiter.acquire(&keys, points)
+    }
+
+    /// Buckets of the subject and the collection, with their configured rates
+    fn bucket_keys(
+        &self,
+        rate_limiter: &RateLimiter,
+        collection: &str,
+        throughput: Throughput,
+        rate: impl Fn(&ThroughputLimits) -> Option<f64>,
+    ) -> Vec<(BucketKey, f64)> {
+        let config = &rate_limiter.config;
+        let mut keys = Vec::with_capacity(2);
+
+        if let (Some(subject), Some(rate)) = (&self.subject, rate(&config.per_subject)) {
+            keys.push((BucketKey::Subject(subject.clone(), throughput), rate));
+        }
+
+        if let Some(rate) = rate_limiter.collection_rate(collection, &rate) {
+            keys.push((
+                BucketKey::Collection(collection.to_string(), throughput),
+                rate,
+            ));
+        }
+
+        keys
+    }
+}
+
+/// Result size and params of a search, checked against the configured maximums
+#[derive(Clone, Copy, Debug)]
+pub struct SearchSize {
+    pub limit: usize,
+    pub ef: Option<usize>,
+}
+
+impl SearchSize {
+    fn new(limit: usize, params: Option<&SearchParams>) -> Self {
+        Self {
+            limit,
+            ef: params.and_then(|params| params.hnsw_ef),
+        }
+    }
+
+    fn from_grpc(limit: u64, params: Option<&grpc::SearchParams>) -> Self {
+        Self {
+            limit: limit as usize,
+            ef: params
+                .and_then(|params| params.hnsw_ef)
+                .map(|ef| ef as usize),
+        }
+    }
+}
+
+impl From<&SearchRequestInternal> for SearchSize {
+    fn from(request: &SearchRequestInternal) -> Self {
+        Self::new(request.limit, request.params.as_ref())
+    }
+}
+
+impl From<&CoreSearchRequest> for SearchSize {
+    fn from(request: &CoreSearchRequest) -> Self {
+        Self::new(request.limit, request.params.as_ref())
+    }
+}
+
+impl From<&RecommendRequestInternal> for SearchSize {
+    fn from(request: &RecommendRequestInternal) -> Self {
+        Self::new(request.limit, request.params.as_ref())
+    }
+}
+
+impl From<&DiscoverRequestInternal> for SearchSize {
+    fn from(request: &DiscoverRequestInternal) -> Self {
+        Self::new(request.limit, request.params.as_ref())
+    }
+}
+
+impl From<&SearchGroupsRequestInternal> for SearchSize {
+    fn from(request: &SearchGroupsRequestInternal) -> Self {
+        let group_request = &request.group_request;
+        let limit = group_request.limit as usize * group_request.group_size as usize;
+        Self::new(limit, request.params.as_ref())
+    }
+}
+
+impl From<&RecommendGroupsRequestInternal> for SearchSize {
+    fn from(request: &RecommendGroupsRequestInternal) -> Self {
+        let group_request = &request.group_request;
+        let limit = group_request.limit as usize * group_request.group_size as usize;
+        Self::new(limit, request.params.as_ref())
+    }
+}
+
+impl From<&grpc::SearchPoints> for SearchSize {
+    fn from(request: &grpc::SearchPoints) -> Self {
+        Self::from_grpc(request.limit, request.params.as_ref())
+    }
+}
+
+impl From<&grpc::RecommendPoints> for SearchSize {
+    fn from(request: &grpc::RecommendPoints) -> Self {
+        Self::from_grpc(request.limit, request.params.as_ref())
+    }
+}
+
+impl From<&grpc::DiscoverPoints> for SearchSize {
+    fn from(request: &grpc::DiscoverPoints) -> Self {
+        Self::from_grpc(request.limit, request.params.as_ref())
+    }
+}
+
+impl From<&grpc::SearchPointGroups> for SearchSize {
+    fn from(request: &grpc::SearchPointGroups) -> Self {
+        let limit = u64::from(request.limit) * u64::from(request.group_size);
+        Self::from_grpc(limit, request.params.as_ref())
+    }
+}
+
+impl From<&grpc::RecommendPointGroups> for SearchSize {
+    fn from(request: &grpc::RecommendPointGroups) -> Self {
+        let limit = u64::from(request.limit) * u64::from(request.group_size);
+        Self::from_grpc(limit, request.params.as_ref())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum Throughput {
+    Searches,
+    WrittenPoints,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+enum BucketKey {
+    Subject(String, Throughput),
+    Collection(String, Throughput),
+}
+
+impl fmt::Display for BucketKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (scope, name, throughput) = match self {
+            BucketKey::Subject(name, throughput) => ("subject", name, throughput),
+            BucketKey::Collection(name, throughput) => ("collection", name, throughput),
+        };
+
+        let throughput = match throughput {
+            Throughput::Searches => "searches",
+            Throughput::WrittenPoints => "written points",
+        };
+
+        write!(f, "Rate of {throughput} of {scope} {name}")
+    }
+}
+
+struct Buckets {
+    buckets: HashMap<BucketKey, TokenBucket>,
+
+    /// Last time idle buckets were dropped
+    last_eviction: Instant,
+}
+
+impl Buckets {
+    fn new(now: Instant) -> Self {
+        Self {
+            buckets: HashMap::new(),
+            last_eviction: now,
+        }
+    }
+
+    /// Periodically drop buckets which are full again, they don't limit anything
+    fn evict_idle(&mut self, now: Instant) {
+        if now.saturating_duration_since(self.last_eviction) < BUCKET_EVICTION_INTERVAL {
+            return;
+        }
+
+        self.buckets.retain(|_, bucket| {
+            bucket.refill(now);
+            !bucket.is_full()
+        });
+        self.last_eviction = now;
+    }
+}
+
+struct TokenBucket {
+    /// Tokens added per second
+    rate: f64,
+
+    /// Available tokens, negative after taking more than available
+    tokens: f64,
+
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64, now: Instant) -> Self {
+        let mut bucket = Self {
+            rate,
+            tokens: 0.0,
+            last_refill: now,
+        };
+        bucket.tokens = bucket.capacity();
+        bucket
+    }
+
+    /// A second of throughput, and at least a single request
+    fn capacity(&self) -> f64 {
+        self.rate.max(1.0)
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.capacity());
+        self.last_refill = now;
+    }
+
+    fn is_full(&self) -> bool {
+        self.tokens >= self.capacity()
+    }
+
+    /// Amounts larger than the capacity can be taken from a full bucket
+    fn can_take(&self, amount: f64) -> bool {
+        self.tokens >= amount.min(self.capacity())
+    }
+
+    fn take(&mut self, amount: f64) {
+        self.tokens -= amount;
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct CollectionUsage {
+    points: usize,
+    disk_bytes: usize,
+    last_update: Instant,
+}
+
+impl CollectionUsage {
+    fn is_expired(&self) -> bool {
+        self.last_update.elapsed() >= COLLECTION_USAGE_TTL
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::settings::{CollectionRateLimits, CollectionsRateLimits};
+
+    fn rate_limiter(
+        per_subject: ThroughputLimits,
+        per_collection: ThroughputLimits,
+    ) -> RateLimiter {
+        RateLimiter::new(RateLimitsConfig {
+            per_subject,
+            per_collection,
+            max_limit: Some(100),
+            max_ef: Some(200),
+            max_collection_points: None,
+            max_collection_disk_bytes: None,
+            collections: CollectionsRateLimits(BTreeMap::from([(
+                "limited".to_string(),
+                CollectionRateLimits {
+                    throughput: ThroughputLimits {
+                        searches_per_sec: Some(1.0),
+                        written_points_per_sec: None,
+                    },
+                    max_points: None,
+                    max_disk_bytes: None,
+                },
+            )])),
+        })
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(10.0, now);
+
+        assert!(bucket.can_take(10.0));
+        bucket.take(10.0);
+        assert!(!bucket.can_take(1.0));
+
+        bucket.refill(now + Duration::from_millis(500));
+        assert!(bucket.can_take(5.0));
+        assert!(!bucket.can_take(6.0));
+
+        // Larger than capacity with a full bucket, then in debt
+        bucket.refill(now + Duration::from_secs(10));
+        assert!(bucket.can_take(25.0));
+        bucket.take(25.0);
+        bucket.refill(now + Duration::from_secs(11));
+        assert!(!bucket.can_take(1.0));
+        bucket.refill(now + Duration::from_millis(12_600));
+        assert!(bucket.can_take(1.0));
+    }
+
+    #[test]
+    fn test_bucket_eviction() {
+        let now = Instant::now();
+        let mut buckets = Buckets::new(now);
+
+        let idle = BucketKey::Collection("idle".to_string(), Throughput::Searches);
+        let busy = BucketKey::Collection("busy".to_string(), Throughput::Searches);
+        buckets
+            .buckets
+            .insert(idle.clone(), TokenBucket::new(10.0, now));
+        let mut busy_bucket = TokenBucket::new(1.0, now);
+        busy_bucket.take(100.0);
+        buckets.buckets.insert(busy.clone(), busy_bucket);
+
+        // Not evicted before the interval
+        buckets.evict_idle(now + Duration::from_secs(1));
+        assert_eq!(buckets.buckets.len(), 2);
+
+        // Full buckets are evicted, buckets in debt are kept
+        buckets.evict_idle(now + BUCKET_EVICTION_INTERVAL);
+        assert!(!buckets.buckets.contains_key(&idle));
+        assert!(buckets.buckets.contains_key(&busy));
+
+        buckets.evict_idle(now + BUCKET_EVICTION_INTERVAL * 3);
+        assert!(buckets.buckets.is_empty());
+    }
+
+    #[test]
+    fn test_search_limits() {
+        let rate_limiter = Arc::new(rate_limiter(
+            ThroughputLimits {
+                searches_per_sec: Some(3.0),
+                written_points_per_sec: None,
+            },
+            ThroughputLimits {
+                searches_per_sec: Some(5.0),
+                written_points_per_sec: None,
+            },
+        ));
+
+        let limits = RequestLimits {
+            rate_limiter: Some(rate_limiter.clone()),
+            subject: Some("tenant".to_string()),
+        };
+        let search = SearchSize {
+            limit: 10,
+            ef: Some(100),
+        };
+
+        // Maximum limit and ef
+        let too_large = SearchSize {
+            limit: 101,
+            ef: None,
+        };
+        let err = limits.check_searches("a", [too_large]).unwrap_err();
+        assert!(matches!(err, StorageError::BadRequest { .. }));
+        let too_large = SearchSize {
+            limit: 10,
+            ef: Some(201),
+        };
+        let err = limits.check_searches("a", [too_large]).unwrap_err();
+        assert!(matches!(err, StorageError::BadRequest { .. }));
+
+        // Rate of the subject
+        assert!(limits.check_searches("a", [search, search]).is_ok());
+        assert!(limits.check_searches("b", [search]).is_ok());
+        let err = limits.check_searches("b", [search]).unwrap_err();
+        assert!(matches!(err, StorageError::RateLimitExceeded { .. }));
+
+        // Rate of the collection, over all subjects
+        let anonymous = RequestLimits {
+            rate_limiter: Some(rate_limiter),
+            subject: None,
+        };
+        assert!(anonymous.check_searches("a", [search; 3]).is_ok());
+        assert!(anonymous.check_searches("a", [search]).is_err());
+        assert!(anonymous.check_searches("c", [search; 5]).is_ok());
+
+        // Rate of a collection with its own limit
+        assert!(anonymous.check_searches("limited", [search]).is_ok());
+        assert!(anonymous.check_searches("limited", [search]).is_err());
+
+        // No limits
+        assert!(RequestLimits::default()
+            .check_searches("c", [too_large; 10])
+            .is_ok());
+    }
+}
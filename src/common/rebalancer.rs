@@ -0,0 +1,98 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use storage::content_manager::collection_meta_ops::{
+    CollectionMetaOperations, ShardTransferOperations,
+};
+use storage::content_manager::consensus_manager::ConsensusStateRef;
+use storage::content_manager::consensus_ops::ConsensusOperations;
+use storage::content_manager::errors::StorageError;
+use storage::content_manager::toc::TableOfContent;
+use storage::types::{ClusterStatus, StateRole};
+use tokio::runtime;
+use tokio::time::{self, MissedTickBehavior};
+
+/// Spawn task automatically rebalancing shards across peers, if enabled in the configuration
+///
+/// The task periodically plans shard moves and proposes them as shard transfers through
+/// consensus. Only the consensus leader rebalances shards, so that moves are planned from a
+/// single point of view.
+pub fn spawn(
+    toc: Arc<TableOfContent>,
+    consensus_state: ConsensusStateRef,
+    runtime: runtime::Handle,
+) {
+    if !toc.rebalancer_config().enabled {
+        log::debug!("Automatic shard rebalancing disabled");
+        return;
+    }
+
+    let task = runtime.spawn(run(toc, consensus_state));
+    drop(task); // drop `JoinFuture` explicitly to make clippy happy
+}
+
+async fn run(toc: Arc<TableOfContent>, consensus_state: ConsensusStateRef) {
+    let interval_sec = toc.rebalancer_config().interval_sec;
+
+    log::info!("Automatic shard rebalancing enabled, checking every {interval_sec} seconds");
+
+    let mut interval = time::interval(Duration::from_secs(interval_sec));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+
+        if !is_leader(&consensus_state) {
+            continue;
+        }
+
+        if let Err(err) = rebalance(&toc, &consensus_state).await {
+            log::warn!("Failed to rebalance shards: {err}");
+        }
+    }
+}
+
+pub fn is_leader(consensus_state: &ConsensusStateRef) -> bool {
+    if !consensus_state.is_leader_established.check_ready() {
+        return false;
+    }
+
+    match consensus_state.cluster_status() {
+        ClusterStatus::Enabled(info) => info.raft_info.role == Some(StateRole::Leader),
+        ClusterStatus::Disabled => false,
+    }
+}
+
+async fn rebalance(
+    toc: &TableOfContent,
+    consensus_state: &ConsensusStateRef,
+) -> Result<(), StorageError> {
+    let plan = toc
+        .plan_rebalance(&consensus_state.peer_address_by_id())
+        .await;
+
+    let method = toc.rebalance_transfer_method();
+
+    for shard_move in plan.moves {
+        log::info!(
+            "Rebalancing shards, moving shard {}:{} from peer {} to peer {} ({:?})",
+            shard_move.collection_name,
+            shard_move.shard_id,
+            shard_move.from_peer_id,
+            shard_move.to_peer_id,
+            shard_move.reason,
+        );
+
+        let operation =
+            ConsensusOperations::CollectionMeta(Box::new(CollectionMetaOperations::TransferShard(
+                shard_move.collection_name.clone(),
+                ShardTransferOperations::Start(shard_move.to_transfer(method)),
+            )));
+
+        consensus_state
+            .propose_consensus_op_with_await(operation, None)
+            .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use collection::shards::shard::ShardId;
+use collection::shards::CollectionId;
+use issues::{Code, Issue as _};
+use storage::content_manager::collection_meta_ops::{
+    CollectionMetaOperations, ShardTransferOperations,
+};
+use storage::content_manager::consensus_manager::ConsensusStateRef;
+use storage::content_manager::consensus_ops::ConsensusOperations;
+use storage::content_manager::replica_repair::{DeadReplicaTracker, RepairDecision, ShardRepair};
+use storage::content_manager::toc::TableOfContent;
+use storage::problems::UnderReplicatedShard;
+use tokio::runtime;
+use tokio::time::{self, MissedTickBehavior};
+
+use super::rebalancer::is_leader;
+
+/// Spawn task watching the replication of shards
+///
+/// The task periodically looks for shards having less healthy replicas than the replication
+/// factor of their collection, and reports them in the issues dashboard. If automatic replica
+/// repair is enabled, the consensus leader also proposes shard transfers to replicate them onto
+/// other peers.
+pub fn spawn(
+    toc: Arc<TableOfContent>,
+    consensus_state: ConsensusStateRef,
+    runtime: runtime::Handle,
+) {
+    let task = runtime.spawn(run(toc, consensus_state));
+    drop(task); // drop `JoinFuture` explicitly to make clippy happy
+}
+
+async fn run(toc: Arc<TableOfContent>, consensus_state: ConsensusStateRef) {
+    let config = toc.replica_repair_config().clone();
+
+    if config.enabled {
+        log::info!(
+            "Automatic replica repair enabled, checking every {} seconds",
+            config.interval_sec,
+        );
+    }
+
+    let mut interval = time::interval(Duration::from_secs(config.interval_sec));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let mut dead_replicas = DeadReplicaTracker::default();
+    let mut reported = HashMap::new();
+
+    loop {
+        interval.tick().await;
+
+        if !consensus_state.is_leader_established.check_ready() {
+            continue;
+        }
+
+        let peers: Vec<_> = consensus_state.peer_address_by_id().into_keys().collect();
+
+        let repairs = toc.plan_replica_repair(&peers, &mut dead_replicas).await;
+
+        report_issues(&repairs, &mut reported, &toc);
+
+        if config.enabled && is_leader(&consensus_state) {
+            repair(&repairs, &toc, &consensus_state).await;
+        }
+    }
+}
+
+/// Report under-replicated shards in the issues dashboard, solve issues of repaired shards
+///
+/// Issues are only replaced if the decision taken for the shard changes.
+fn report_issues(
+    repairs: &[ShardRepair],
+    reported: &mut HashMap<(CollectionId, ShardId), RepairDecision>,
+    toc: &TableOfContent,
+) {
+    let auto_repair = toc.replica_repair_config().enabled;
+    let method = toc.replica_repair_transfer_method();
+
+    let mut current = HashMap::new();
+
+    for repair in repairs {
+        let key = (repair.collection_name.clone(), repair.shard_id);
+        let instance_id = UnderReplicatedShard::get_instance_id(&key.0, key.1);
+
+        if reported.get(&key) != Some(&repair.decision) {
+            issues::solve(Code::new::<UnderReplicatedShard>(instance_id));
+            UnderReplicatedShard::new(repair.clone(), auto_repair, method).submit();
+        }
+
+        current.insert(key, repair.decision);
+    }
+
+    for (collection_name, shard_id) in reported.keys() {
+        if !current.contains_key(&(collection_name.clone(), *shard_id)) {
+            issues::solve(Code::new::<UnderReplicatedShard>(
+                UnderReplicatedShard::get_instance_id(collection_name, *shard_id),
+            ));
+        }
+    }
+
+    *reported = current;
+}
+
+async fn repair(
+    repairs: &[ShardRepair],
+    toc: &TableOfContent,
+    consensus_state: &ConsensusStateRef,
+) {
+    let method = toc.replica_repair_transfer_method();
+
+    for repair in repairs {
+        let Some(transfer) = repair.to_transfer(method) else {
+            continue;
+        };
+
+        log::info!(
+            "Repairing under-replicated shard {}:{} ({}/{} healthy replicas), replicating from peer {} to peer {}",
+            repair.collection_name,
+            repair.shard_id,
+            repair.healthy_replicas,
+            repair.replication_factor,
+            transfer.from,
+            transfer.to,
+        );
+
+        let operation =
+            ConsensusOperations::CollectionMeta(Box::new(CollectionMetaOperations::TransferShard(
+                repair.collection_name.clone(),
+                ShardTransferOperations::Start(transfer),
+            )));
+
+        if let Err(err) = consensus_state
+            .propose_consensus_op_with_await(operation, None)
+            .await
+        {
+            log::warn!(
+                "Failed to repair shard {}:{}: {err}",
+                repair.collection_name,
+                repair.shard_id,
+            );
+        }
+    }
+}
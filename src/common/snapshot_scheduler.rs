@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use issues::{Code, Issue as _};
+use storage::content_manager::snapshot_scheduler;
+use storage::content_manager::toc::TableOfContent;
+use storage::problems::FailedScheduledSnapshot;
+use storage::types::SnapshotScheduleConfig;
+use tokio::runtime;
+
+/// Spawn a task for each configured snapshot schedule
+///
+/// Every task creates snapshots according to its cron schedule, and deletes snapshots expired by
+/// its retention policy. Status of the schedules is exposed in telemetry, failures are reported
+/// in the issues dashboard.
+pub fn spawn(toc: Arc<TableOfContent>, runtime: runtime::Handle) {
+    for (index, schedule) in toc.snapshot_schedules().iter().enumerate() {
+        let task = runtime.spawn(run(toc.clone(), index, schedule.clone()));
+        drop(task); // drop `JoinFuture` explicitly to make clippy happy
+    }
+}
+
+async fn run(toc: Arc<TableOfContent>, index: usize, schedule: SnapshotScheduleConfig) {
+    // Schedule is validated when loading the configuration
+    let cron = match snapshot_scheduler::parse_schedule(&schedule.schedule) {
+        Ok(cron) => cron,
+        Err(err) => {
+            log::error!("Invalid snapshot schedule {:?}: {err}", schedule.schedule);
+            return;
+        }
+    };
+
+    log::info!(
+        "Scheduled snapshots of {} with schedule {:?}",
+        schedule.collection.as_deref().unwrap_or("full storage"),
+        schedule.schedule,
+    );
+
+    let instance_id =
+        FailedScheduledSnapshot::get_instance_id(index, schedule.collection.as_deref());
+
+    loop {
+        let Some(next_run) = cron.upcoming(Utc).next() else {
+            log::info!(
+                "Snapshot schedule {:?} has no upcoming runs, stopping",
+                schedule.schedule,
+            );
+            toc.update_snapshot_schedule_status(index, |status| status.next_run = None);
+            return;
+        };
+
+        toc.update_snapshot_schedule_status(index, |status| status.next_run = Some(next_run));
+
+        let delay = (next_run - Utc::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(delay).await;
+
+        let started = Utc::now();
+
+        match toc.run_snapshot_schedule(&schedule).await {
+            Ok((snapshot, deleted)) => {
+                log::info!(
+                    "Created scheduled snapshot {}, deleted {} expired snapshots",
+                    snapshot.name,
+                    deleted.len(),
+                );
+
+                toc.update_snapshot_schedule_status(index, |status| {
+                    status.last_run = Some(started);
+                    status.last_success = Some(started);
+                    status.last_snapshot = Some(snapshot.name);
+                    status.last_error = None;
+                    status.consecutive_failures = 0;
+                    status.deleted_snapshots += deleted.len();
+                });
+
+                issues::solve(Code::new::<FailedScheduledSnapshot>(instance_id.clone()));
+            }
+            Err(err) => {
+                log::error!(
+                    "Scheduled snapshot of {} failed: {err}",
+                    schedule.collection.as_deref().unwrap_or("full storage"),
+                );
+
+                let mut consecutive_failures = 0;
+                toc.update_snapshot_schedule_status(index, |status| {
+                    status.last_run = Some(started);
+                    status.last_error = Some(err.to_string());
+                    status.consecutive_failures += 1;
+                    consecutive_failures = status.consecutive_failures;
+                });
+
+                // Replace the previous issue to update failure count and error
+                issues::solve(Code::new::<FailedScheduledSnapshot>(instance_id.clone()));
+                FailedScheduledSnapshot::new(
+                    index,
+                    schedule.collection.clone(),
+                    err.to_string(),
+                    consecutive_failures,
+                )
+                .submit();
+            }
+        }
+    }
+}
@@ -9,10 +9,11 @@ use collection::operations::snapshot_ops::{
 };
 use collection::shards::replica_set::ReplicaState;
 use collection::shards::shard::ShardId;
+use collection::wal_archive::RecoveryTarget;
 use storage::content_manager::errors::StorageError;
 use storage::content_manager::snapshots;
 use storage::content_manager::toc::TableOfContent;
-use storage::rbac::{Access, AccessRequirements};
+use storage::rbac::{Access, AccessRequirements, OperationClass};
 
 use super::http_client::HttpClient;
 
@@ -24,13 +25,23 @@ pub async fn create_shard_snapshot(
     access: Access,
     collection_name: String,
     shard_id: ShardId,
+    base: Option<String>,
 ) -> Result<SnapshotDescription, StorageError> {
-    let collection_pass = access
-        .check_collection_access(&collection_name, AccessRequirements::new().write().whole())?;
+    let collection_pass = access.check_collection_access(
+        &collection_name,
+        AccessRequirements::new()
+            .write()
+            .whole()
+            .operations(&[OperationClass::Snapshot]),
+    )?;
     let collection = toc.get_collection(&collection_pass).await?;
 
     let snapshot = collection
-        .create_shard_snapshot(shard_id, &toc.optional_temp_or_snapshot_temp_path()?)
+        .create_shard_snapshot(
+            shard_id,
+            base.as_deref(),
+            &toc.optional_temp_or_snapshot_temp_path()?,
+        )
         .await?;
 
     Ok(snapshot)
@@ -45,8 +56,12 @@ pub async fn list_shard_snapshots(
     collection_name: String,
     shard_id: ShardId,
 ) -> Result<Vec<SnapshotDescription>, StorageError> {
-    let collection_pass =
-        access.check_collection_access(&collection_name, AccessRequirements::new().whole())?;
+    let collection_pass = access.check_collection_access(
+        &collection_name,
+        AccessRequirements::new()
+            .whole()
+            .operations(&[OperationClass::Snapshot]),
+    )?;
     let collection = toc.get_collection(&collection_pass).await?;
     let snapshots = collection.list_shard_snapshots(shard_id).await?;
     Ok(snapshots)
@@ -62,8 +77,13 @@ pub async fn delete_shard_snapshot(
     shard_id: ShardId,
     snapshot_name: String,
 ) -> Result<(), StorageError> {
-    let collection_pass = access
-        .check_collection_access(&collection_name, AccessRequirements::new().write().whole())?;
+    let collection_pass = access.check_collection_access(
+        &collection_name,
+        AccessRequirements::new()
+            .write()
+            .whole()
+            .operations(&[OperationClass::Snapshot]),
+    )?;
     let collection = toc.get_collection(&collection_pass).await?;
     let snapshot_manager = collection.get_snapshots_storage_manager()?;
     let snapshot_path = snapshot_manager
@@ -71,12 +91,21 @@ pub async fn delete_shard_snapshot(
             collection.shards_holder(),
             shard_id,
             collection.snapshots_path(),
-            snapshot_name,
+            &snapshot_name,
         )
         .await?;
 
     check_shard_snapshot_file_exists(&snapshot_path)?;
 
+    // Base snapshots are needed to restore incremental snapshots referencing them
+    collection
+        .check_shard_snapshot_unreferenced(
+            shard_id,
+            &snapshot_name,
+            &toc.optional_temp_or_snapshot_temp_path()?,
+        )
+        .await?;
+
     let _task = tokio::spawn(async move { snapshot_manager.delete_snapshot(&snapshot_path).await });
 
     Ok(())
@@ -96,6 +125,7 @@ pub async fn recover_shard_snapshot(
     checksum: Option<String>,
     client: HttpClient,
     api_key: Option<String>,
+    recover_until: Option<RecoveryTarget>,
 ) -> Result<(), StorageError> {
     let collection_pass = access
         .check_global_access(AccessRequirements::new().manage())?
@@ -176,6 +206,7 @@ pub async fn recover_shard_snapshot(
             shard_id,
             &snapshot_path,
             snapshot_priority,
+            recover_until,
             cancel,
         )
         .await;
@@ -203,6 +234,7 @@ pub async fn recover_shard_snapshot_impl(
     shard: ShardId,
     snapshot_path: &std::path::Path,
     priority: SnapshotPriority,
+    recover_until: Option<RecoveryTarget>,
     cancel: cancel::CancellationToken,
 ) -> Result<(), StorageError> {
     // `Collection::restore_shard_snapshot` and `activate_shard` calls *have to* be executed as a
@@ -210,6 +242,18 @@ pub async fn recover_shard_snapshot_impl(
     //
     // It is *possible* to make this function to be cancel safe, but it is *extremely tedious* to do so
 
+    // Replayed operations only exist on this replica, it must be the source of truth
+    if recover_until.is_some()
+        && matches!(
+            priority,
+            SnapshotPriority::Replica | SnapshotPriority::ShardTransfer,
+        )
+    {
+        return Err(StorageError::bad_request(format!(
+            "Can't replay archived operations with {priority:?} snapshot priority",
+        )));
+    }
+
     // `Collection::restore_shard_snapshot` is *not* cancel safe
     // (see `ShardReplicaSet::restore_local_replica_from`)
     collection
@@ -223,6 +267,22 @@ pub async fn recover_shard_snapshot_impl(
         )
         .await?;
 
+    // Replay archived operations before the shard is activated
+    if let Some(recover_until) = &recover_until {
+        let replayed = collection
+            .replay_wal_archive(
+                shard,
+                recover_until,
+                &toc.optional_temp_or_snapshot_temp_path()?,
+            )
+            .await?;
+
+        log::info!(
+            "Replayed {replayed} archived operations on shard {shard} of collection {}",
+            collection.name(),
+        );
+    }
+
     let state = collection.state().await;
     let shard_info = state.shards.get(&shard).unwrap(); // TODO: Handle `unwrap`?..
 
@@ -239,12 +299,13 @@ pub async fn recover_shard_snapshot_impl(
         snapshots::recover::activate_shard(toc, collection, toc.this_peer_id, &shard).await?;
     } else {
         match priority {
-            SnapshotPriority::NoSync => {
+            SnapshotPriority::NoSync if recover_until.is_none() => {
                 snapshots::recover::activate_shard(toc, collection, toc.this_peer_id, &shard)
                     .await?;
             }
 
-            SnapshotPriority::Snapshot => {
+            // Other replicas don't have replayed operations, they are synchronized from this one
+            SnapshotPriority::NoSync | SnapshotPriority::Snapshot => {
                 snapshots::recover::activate_shard(toc, collection, toc.this_peer_id, &shard)
                     .await?;
 
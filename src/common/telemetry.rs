@@ -15,7 +15,8 @@ use crate::common::telemetry_ops::collections_telemetry::CollectionsTelemetry;
 use crate::common::telemetry_ops::requests_telemetry::{
     ActixTelemetryCollector, RequestsTelemetry, TonicTelemetryCollector,
 };
-use crate::settings::Settings;
+use crate::common::telemetry_ops::snapshots_telemetry::SnapshotsTelemetry;
+use crate::settings::{CollectionMetricsConfig, Settings};
 
 pub struct TelemetryCollector {
     process_id: Uuid,
@@ -34,6 +35,7 @@ pub struct TelemetryData {
     pub(crate) collections: CollectionsTelemetry,
     pub(crate) cluster: ClusterTelemetry,
     pub(crate) requests: RequestsTelemetry,
+    pub(crate) snapshots: SnapshotsTelemetry,
 }
 
 impl Anonymize for TelemetryData {
@@ -44,6 +46,7 @@ impl Anonymize for TelemetryData {
             collections: self.collections.anonymize(),
             cluster: self.cluster.anonymize(),
             requests: self.requests.anonymize(),
+            snapshots: self.snapshots.anonymize(),
         }
     }
 }
@@ -72,6 +75,10 @@ impl TelemetryCollector {
         }
     }
 
+    pub fn collection_metrics_config(&self) -> Option<&CollectionMetricsConfig> {
+        self.settings.service.collection_metrics.as_ref()
+    }
+
     pub async fn prepare_data(&self, access: &Access, detail: TelemetryDetail) -> TelemetryData {
         TelemetryData {
             id: self.process_id.to_string(),
@@ -84,6 +91,7 @@ impl TelemetryCollector {
                 &self.tonic_telemetry_collector.lock(),
                 detail,
             ),
+            snapshots: SnapshotsTelemetry::collect(detail, access, self.dispatcher.toc(access)),
         }
     }
 }
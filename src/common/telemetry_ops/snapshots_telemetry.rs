@@ -0,0 +1,48 @@
+use common::types::{DetailsLevel, TelemetryDetail};
+use schemars::JsonSchema;
+use segment::common::anonymize::Anonymize;
+use serde::Serialize;
+use storage::content_manager::snapshot_scheduler::SnapshotScheduleStatus;
+use storage::content_manager::toc::TableOfContent;
+use storage::rbac::{Access, AccessRequirements};
+
+#[derive(Serialize, Clone, Debug, JsonSchema)]
+pub struct SnapshotsTelemetry {
+    /// Status of scheduled snapshots
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub schedules: Vec<SnapshotScheduleStatus>,
+}
+
+impl SnapshotsTelemetry {
+    pub fn collect(
+        detail: TelemetryDetail,
+        access: &Access,
+        toc: &TableOfContent,
+    ) -> SnapshotsTelemetry {
+        let schedules = if detail.level >= DetailsLevel::Level1 {
+            toc.snapshot_schedule_statuses()
+                .into_iter()
+                .filter(|status| match &status.collection {
+                    Some(collection_name) => access
+                        .check_collection_access(collection_name, AccessRequirements::new())
+                        .is_ok(),
+                    None => access
+                        .check_global_access(AccessRequirements::new())
+                        .is_ok(),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        SnapshotsTelemetry { schedules }
+    }
+}
+
+impl Anonymize for SnapshotsTelemetry {
+    fn anonymize(&self) -> Self {
+        SnapshotsTelemetry {
+            schedules: self.schedules.anonymize(),
+        }
+    }
+}
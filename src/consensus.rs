@@ -1342,7 +1342,10 @@ mod tests {
                             optimizers_config: None,
                             shard_number: Some(2),
                             on_disk_payload: None,
+                            payload_storage: None,
+                            storage_format: None,
                             replication_factor: None,
+                            replica_placement: None,
                             write_consistency_factor: None,
                             init_from: None,
                             quantization_config: None,
@@ -2,7 +2,9 @@ use std::time::Duration;
 
 use collection::events::{CollectionDeletedEvent, IndexCreatedEvent, SlowQueryEvent};
 use segment::problems::unindexed_field;
-use storage::issues_subscribers::UnindexedFieldSubscriber;
+use storage::issues_subscribers::{
+    FailedScheduledSnapshotSubscriber, UnderReplicatedShardSubscriber, UnindexedFieldSubscriber,
+};
 
 use crate::settings::Settings;
 
@@ -17,4 +19,12 @@ pub fn setup_subscribers(settings: &Settings) {
     issues::broker::add_subscriber::<SlowQueryEvent>(Box::new(unindexed_subscriber));
     issues::broker::add_subscriber::<IndexCreatedEvent>(Box::new(unindexed_subscriber));
     issues::broker::add_subscriber::<CollectionDeletedEvent>(Box::new(unindexed_subscriber));
+
+    issues::broker::add_subscriber::<CollectionDeletedEvent>(Box::new(
+        UnderReplicatedShardSubscriber,
+    ));
+
+    issues::broker::add_subscriber::<CollectionDeletedEvent>(Box::new(
+        FailedScheduledSnapshotSubscriber,
+    ));
 }
@@ -14,6 +14,7 @@ mod tonic;
 mod tracing;
 
 use std::io::Error;
+use std::path::Path;
 use std::sync::Arc;
 use std::thread;
 use std::thread::JoinHandle;
@@ -21,9 +22,12 @@ use std::time::Duration;
 
 use ::common::cpu::{get_cpu_budget, CpuBudget};
 use ::tonic::transport::Uri;
+use anyhow::Context as _;
 use api::grpc::transport_channel_pool::TransportChannelPool;
 use clap::Parser;
+use collection::common::snapshot_encryption::SnapshotEncryption;
 use collection::shards::channel_service::ChannelService;
+use collection::slow_query_log::SlowQueryLog;
 use consensus::Consensus;
 use slog::Drain;
 use startup::setup_panic_hook;
@@ -40,10 +44,13 @@ use storage::rbac::Access;
 ))]
 use tikv_jemallocator::Jemalloc;
 
+use crate::common::audit::AuditLogger;
+use crate::common::auth::AuthKeys;
 use crate::common::helpers::{
     create_general_purpose_runtime, create_search_runtime, create_update_runtime,
     load_tls_client_config,
 };
+use crate::common::rate_limiting::RateLimiter;
 use crate::common::telemetry::TelemetryCollector;
 use crate::common::telemetry_reporting::TelemetryReporter;
 use crate::greeting::welcome;
@@ -173,6 +180,15 @@ fn main() -> anyhow::Result<()> {
 
     let temp_path = settings.storage.temp_path.as_deref();
 
+    // Keys to decrypt snapshots encrypted at rest
+    let snapshot_encryption = settings
+        .storage
+        .snapshots_config
+        .encryption
+        .as_ref()
+        .map(SnapshotEncryption::new)
+        .transpose()?;
+
     let restored_collections = if let Some(full_snapshot) = args.storage_snapshot {
         recover_full_snapshot(
             temp_path,
@@ -181,6 +197,7 @@ fn main() -> anyhow::Result<()> {
             args.force_snapshot,
             persistent_consensus_state.this_peer_id(),
             is_distributed_deployment,
+            snapshot_encryption.as_ref(),
         )
     } else if let Some(snapshots) = args.snapshot {
         // recover from snapshots
@@ -191,6 +208,7 @@ fn main() -> anyhow::Result<()> {
             &settings.storage.storage_path,
             persistent_consensus_state.this_peer_id(),
             is_distributed_deployment,
+            snapshot_encryption.as_ref(),
         )
     } else {
         vec![]
@@ -287,6 +305,7 @@ fn main() -> anyhow::Result<()> {
             propose_operation_sender.unwrap(),
             storage_path,
         )
+        .with_peer_labels(settings.cluster.peer_labels())
         .into();
         let is_new_deployment = consensus_state.is_new_deployment();
 
@@ -333,6 +352,18 @@ fn main() -> anyhow::Result<()> {
 
         handles.push(handle);
 
+        common::rebalancer::spawn(
+            toc_arc.clone(),
+            consensus_state.clone(),
+            runtime_handle.clone(),
+        );
+
+        common::replica_repair::spawn(
+            toc_arc.clone(),
+            consensus_state.clone(),
+            runtime_handle.clone(),
+        );
+
         let toc_arc_clone = toc_arc.clone();
         let consensus_state_clone = consensus_state.clone();
         let _cancel_transfer_handle = runtime_handle.spawn(async move {
@@ -402,6 +433,39 @@ fn main() -> anyhow::Result<()> {
     // Setup subscribers to listen for issue-able events
     issues_setup::setup_subscribers(&settings);
 
+    // Slow query log, uses the slow query threshold set up with the subscribers
+    let slow_query_log = &settings.service.slow_query_log;
+    let slow_query_log = SlowQueryLog::new(
+        slow_query_log.max_entries,
+        slow_query_log.log_file.as_deref().map(Path::new),
+    )
+    .context("failed to open slow query log")?;
+    collection::slow_query_log::init(slow_query_log);
+
+    // Scheduled snapshots, created by every peer independently
+    common::snapshot_scheduler::spawn(toc_arc.clone(), runtime_handle.clone());
+
+    // Audit log, shared by REST and gRPC servers
+    let audit_logger = settings
+        .service
+        .audit
+        .clone()
+        .map(AuditLogger::new)
+        .transpose()
+        .context("failed to open audit log")?
+        .map(Arc::new);
+
+    // Rate limits, shared by REST and gRPC servers
+    let rate_limiter = settings
+        .service
+        .rate_limits
+        .clone()
+        .map(|config| Arc::new(RateLimiter::new(config)));
+
+    // Auth keys, shared by REST and gRPC servers to load and reload keys only once
+    let auth_keys = AuthKeys::try_create(&settings.service, toc_arc.clone())
+        .context("failed to load auth keys")?;
+
     // Helper to better log start errors
     let log_err_if_any = |server_name, result| match result {
         Err(err) => {
@@ -419,6 +483,9 @@ fn main() -> anyhow::Result<()> {
     {
         let dispatcher_arc = dispatcher_arc.clone();
         let settings = settings.clone();
+        let audit_logger = audit_logger.clone();
+        let rate_limiter = rate_limiter.clone();
+        let auth_keys = auth_keys.clone();
         let handle = thread::Builder::new()
             .name("web".to_string())
             .spawn(move || {
@@ -430,6 +497,9 @@ fn main() -> anyhow::Result<()> {
                         health_checker,
                         settings,
                         logger_handle,
+                        auth_keys,
+                        audit_logger,
+                        rate_limiter,
                     ),
                 )
             })
@@ -454,6 +524,9 @@ fn main() -> anyhow::Result<()> {
                         settings,
                         grpc_port,
                         runtime_handle,
+                        auth_keys,
+                        audit_logger,
+                        rate_limiter,
                     ),
                 )
             })
@@ -509,5 +582,6 @@ fn main() -> anyhow::Result<()> {
     }
     drop(toc_arc);
     drop(settings);
+    tracing::otlp::shutdown();
     Ok(())
 }
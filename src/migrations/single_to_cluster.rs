@@ -50,6 +50,7 @@ pub async fn handle_existing_collections(
                 shard_number: Some(shards_number),
                 sharding_method,
                 replication_factor: Some(collection_state.config.params.replication_factor.get()),
+                replica_placement: collection_state.config.params.replica_placement,
                 write_consistency_factor: Some(
                     collection_state
                         .config
@@ -58,6 +59,8 @@ pub async fn handle_existing_collections(
                         .get(),
                 ),
                 on_disk_payload: Some(collection_state.config.params.on_disk_payload),
+                payload_storage: collection_state.config.params.payload_storage,
+                storage_format: collection_state.config.params.storage_format,
                 hnsw_config: Some(collection_state.config.hnsw_config.into()),
                 wal_config: Some(collection_state.config.wal_config.into()),
                 optimizers_config: Some(collection_state.config.optimizer_config.into()),
@@ -21,6 +21,8 @@ use serde::Serialize;
 use storage::content_manager::collection_meta_ops::{
     ChangeAliasesOperation, CreateCollection, UpdateCollection,
 };
+use storage::content_manager::rebalancer::RebalancePlan;
+use storage::content_manager::token_revocation::{TokenRevocation, TokenRevocations};
 use storage::types::ClusterStatus;
 
 use crate::common::helpers::LocksOption;
@@ -81,6 +83,9 @@ struct AllDefinitions {
     bb: DiscoverRequestBatch,
     bc: VersionInfo,
     bd: CollectionExistence,
+    be: RebalancePlan,
+    bf: TokenRevocation,
+    bg: TokenRevocations,
 }
 
 fn save_schema<T: JsonSchema>() {
@@ -1,13 +1,15 @@
+use std::collections::BTreeMap;
 use std::{env, io};
 
 use api::grpc::transport_channel_pool::{
     DEFAULT_CONNECT_TIMEOUT, DEFAULT_GRPC_TIMEOUT, DEFAULT_POOL_SIZE,
 };
+use collection::operations::types::ZONE_LABEL;
 use collection::operations::validation;
 use config::{Config, ConfigError, Environment, File, FileFormat, Source};
 use serde::Deserialize;
 use storage::types::StorageConfig;
-use validator::Validate;
+use validator::{Validate, ValidationErrors};
 
 use crate::tracing;
 
@@ -29,8 +31,33 @@ pub struct ServiceConfig {
     pub verify_https_client_certificate: bool,
     pub api_key: Option<String>,
     pub read_only_api_key: Option<String>,
+    /// File to read the read-write API key from, instead of `api_key`
+    #[serde(default)]
+    pub api_key_file: Option<String>,
+    /// File to read the read-only API key from, instead of `read_only_api_key`
+    #[serde(default)]
+    pub read_only_api_key_file: Option<String>,
+    /// Interval to reload API keys from the key files. Disabled if 0 or not set
+    #[serde(default)]
+    pub api_key_reload_interval_sec: Option<u64>,
     #[serde(default)]
     pub jwt_rbac: Option<bool>,
+    /// Public keys to verify JWTs signed with asymmetric algorithms, such as RS256
+    #[serde(default)]
+    #[validate]
+    pub jwt_public_keys: Option<JwtPublicKeysConfig>,
+    /// Audit log of authenticated operations
+    #[serde(default)]
+    #[validate]
+    pub audit: Option<AuditConfig>,
+    /// Request rate limits and storage quotas, per JWT subject and per collection
+    #[serde(default)]
+    #[validate]
+    pub rate_limits: Option<RateLimitsConfig>,
+    /// Export labelled metrics of each collection in the `/metrics` endpoint
+    #[serde(default)]
+    #[validate]
+    pub collection_metrics: Option<CollectionMetricsConfig>,
 
     /// Directory where static files are served from.
     /// For example, the Web-UI should be placed here.
@@ -44,6 +71,154 @@ pub struct ServiceConfig {
 
     /// How much time is considered too long for a query to execute.
     pub slow_query_secs: Option<f32>,
+    /// Log of search, scroll and count requests taking longer than `slow_query_secs`
+    #[serde(default)]
+    #[validate]
+    pub slow_query_log: SlowQueryLogConfig,
+}
+
+#[derive(Debug, Deserialize, Clone, Validate)]
+pub struct JwtPublicKeysConfig {
+    /// Public keys in PEM format
+    #[serde(default)]
+    pub pem_files: Vec<JwtPemKeyConfig>,
+    /// JSON Web Key Set file, as published by identity providers
+    #[serde(default)]
+    pub jwks_file: Option<String>,
+    /// Interval to reload keys from files, to pick up rotated keys. Disabled if 0 or null
+    #[serde(default = "default_jwt_keys_reload_interval_sec")]
+    pub reload_interval_sec: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct JwtPemKeyConfig {
+    pub path: String,
+    /// Signing algorithm of the key, such as RS256, ES256 or EdDSA
+    pub algorithm: jsonwebtoken::Algorithm,
+    /// Key ID, matched against the `kid` header of tokens
+    #[serde(default)]
+    pub kid: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Validate)]
+pub struct AuditConfig {
+    /// Path of the audit log file, rotated files get a numeric suffix
+    #[validate(length(min = 1))]
+    pub log_file: String,
+    /// Rotate the log file when it reaches this size
+    #[serde(default = "default_audit_max_file_size_mb")]
+    #[validate(range(min = 1))]
+    pub max_file_size_mb: u64,
+    /// Number of rotated log files to keep
+    #[serde(default = "default_audit_max_files")]
+    pub max_files: usize,
+    /// Also record read operations, such as search and retrieve
+    #[serde(default)]
+    pub log_reads: bool,
+}
+
+#[derive(Debug, Deserialize, Clone, Default, Validate)]
+pub struct RateLimitsConfig {
+    /// Throughput limits of each JWT subject, over all collections
+    #[serde(default)]
+    #[validate]
+    pub per_subject: ThroughputLimits,
+    /// Throughput limits of each collection, over all clients, unless set for the collection
+    #[serde(default)]
+    #[validate]
+    pub per_collection: ThroughputLimits,
+    /// Maximum `limit` of a search, or `limit` x `group_size` of a group search
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub max_limit: Option<usize>,
+    /// Maximum `hnsw_ef` param of a search
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub max_ef: Option<usize>,
+    /// Maximum number of points of each collection on this peer, unless set for the collection.
+    /// Writes are rejected once it is reached.
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub max_collection_points: Option<usize>,
+    /// Maximum size on disk of each collection on this peer, unless set for the collection.
+    /// Writes are rejected once it is reached.
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub max_collection_disk_bytes: Option<usize>,
+    /// Limits and quotas of specific collections, by name, overriding the defaults above
+    #[serde(default)]
+    #[validate]
+    pub collections: CollectionsRateLimits,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct CollectionsRateLimits(pub BTreeMap<String, CollectionRateLimits>);
+
+impl Validate for CollectionsRateLimits {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        common::validation::validate_iter(self.0.values())
+    }
+}
+
+/// Limits and quotas of a collection, fields which are not set fall back to the defaults
+#[derive(Debug, Deserialize, Clone, Default, Validate)]
+pub struct CollectionRateLimits {
+    #[serde(flatten)]
+    #[validate]
+    pub throughput: ThroughputLimits,
+    /// Maximum number of points of the collection on this peer
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub max_points: Option<usize>,
+    /// Maximum size on disk of the collection on this peer
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub max_disk_bytes: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default, Validate)]
+pub struct ThroughputLimits {
+    /// Search, recommend and discover requests per second, each request of a batch counts
+    #[serde(default)]
+    #[validate(range(min = 0.001))]
+    pub searches_per_sec: Option<f64>,
+    /// Points written per second, by upserts, vector updates and payload updates.
+    /// A payload update by filter counts as a single point.
+    #[serde(default)]
+    #[validate(range(min = 0.001))]
+    pub written_points_per_sec: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Clone, Validate)]
+pub struct CollectionMetricsConfig {
+    /// Also label metrics with the shard ID, exporting a series for each local shard
+    #[serde(default)]
+    pub per_shard: bool,
+    /// Maximum number of collections to export metrics for, to limit cardinality.
+    /// Collections are selected by name, in lexicographical order.
+    #[serde(default = "default_max_metrics_collections")]
+    #[validate(range(min = 1))]
+    pub max_collections: usize,
+}
+
+#[derive(Debug, Deserialize, Clone, Validate)]
+pub struct SlowQueryLogConfig {
+    /// Number of latest slow queries to keep in memory, and serve in the `/slow_queries` API
+    #[serde(default = "default_slow_query_log_max_entries")]
+    pub max_entries: usize,
+    /// Also append slow queries to this file, as JSON lines
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub log_file: Option<String>,
+}
+
+impl Default for SlowQueryLogConfig {
+    fn default() -> Self {
+        SlowQueryLogConfig {
+            max_entries: default_slow_query_log_max_entries(),
+            log_file: None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, Default, Validate)]
@@ -61,6 +236,25 @@ pub struct ClusterConfig {
     #[serde(default)]
     #[validate]
     pub consensus: ConsensusConfig,
+    /// Labels of this peer, such as its availability zone. Announced to other peers, and used to
+    /// spread shard replicas of collections with a replica placement constraint.
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// Availability zone of this peer, shorthand for the `zone` label.
+    /// Reads are routed to replicas in the same zone first.
+    #[serde(default)]
+    pub zone: Option<String>,
+}
+
+impl ClusterConfig {
+    /// Labels of this peer, including the configured zone
+    pub fn peer_labels(&self) -> BTreeMap<String, String> {
+        let mut labels = self.labels.clone();
+        if let Some(zone) = &self.zone {
+            labels.insert(ZONE_LABEL.to_string(), zone.clone());
+        }
+        labels
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, Validate)]
@@ -291,6 +485,27 @@ const fn default_tls_cert_ttl() -> Option<u64> {
     Some(3600)
 }
 
+const fn default_jwt_keys_reload_interval_sec() -> Option<u64> {
+    // Default five minutes
+    Some(300)
+}
+
+const fn default_max_metrics_collections() -> usize {
+    100
+}
+
+const fn default_audit_max_file_size_mb() -> u64 {
+    100
+}
+
+const fn default_audit_max_files() -> usize {
+    10
+}
+
+const fn default_slow_query_log_max_entries() -> usize {
+    collection::slow_query_log::DEFAULT_MAX_ENTRIES
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -2,12 +2,14 @@ use std::fs::{self, remove_dir_all, rename};
 use std::path::{Path, PathBuf};
 
 use collection::collection::Collection;
+use collection::common::snapshot_encryption::{self, SnapshotEncryption};
 use collection::common::validate_snapshot_archive::validate_open_snapshot_archive;
 use collection::shards::shard::PeerId;
 use log::info;
 use storage::content_manager::alias_mapping::AliasPersistence;
 use storage::content_manager::snapshots::SnapshotConfig;
 use storage::content_manager::toc::{ALIASES_PATH, COLLECTIONS_DIR};
+use tempfile::TempPath;
 
 /// Recover snapshots from the given arguments
 ///
@@ -15,6 +17,7 @@ use storage::content_manager::toc::{ALIASES_PATH, COLLECTIONS_DIR};
 ///
 /// * `mapping` - `[ <path>:<collection_name> ]`
 /// * `force` - if true, allow to overwrite collections from snapshots
+/// * `encryption` - keys to decrypt snapshots encrypted at rest
 ///
 /// # Returns
 ///
@@ -26,6 +29,7 @@ pub fn recover_snapshots(
     storage_dir: &str,
     this_peer_id: PeerId,
     is_distributed: bool,
+    encryption: Option<&SnapshotEncryption>,
 ) -> Vec<String> {
     let collection_dir_path = Path::new(storage_dir).join(COLLECTIONS_DIR);
     let mut recovered_collections: Vec<String> = vec![];
@@ -62,8 +66,13 @@ pub fn recover_snapshots(
         let collection_temp_path = temp_dir
             .map(PathBuf::from)
             .unwrap_or_else(|| collection_path.with_extension("tmp"));
-        if let Err(err) = Collection::restore_snapshot(
+        let decrypted_snapshot_path = decrypt_if_encrypted(
+            encryption,
             snapshot_path,
+            &collection_path.with_extension("decrypted"),
+        );
+        if let Err(err) = Collection::restore_snapshot(
+            decrypted_snapshot_path.as_deref().unwrap_or(snapshot_path),
             &collection_temp_path,
             this_peer_id,
             is_distributed,
@@ -88,15 +97,28 @@ pub fn recover_full_snapshot(
     force: bool,
     this_peer_id: PeerId,
     is_distributed: bool,
+    encryption: Option<&SnapshotEncryption>,
 ) -> Vec<String> {
     let snapshot_temp_path = temp_dir
         .map(PathBuf::from)
         .unwrap_or_else(|| Path::new(storage_dir).join("snapshots_recovery_tmp"));
     fs::create_dir_all(&snapshot_temp_path).unwrap();
 
+    let decrypted_snapshot_path = decrypt_if_encrypted(
+        encryption,
+        Path::new(snapshot_path),
+        &snapshot_temp_path.with_extension("decrypted"),
+    );
+
     // Un-tar snapshot into temporary directory
-    let mut ar = validate_open_snapshot_archive(snapshot_path).unwrap();
+    let mut ar = validate_open_snapshot_archive(
+        decrypted_snapshot_path
+            .as_deref()
+            .unwrap_or(Path::new(snapshot_path)),
+    )
+    .unwrap();
     ar.unpack(&snapshot_temp_path).unwrap();
+    drop(decrypted_snapshot_path);
 
     // Read configuration file with snapshot-to-collection mapping
     let config_path = snapshot_temp_path.join("config.json");
@@ -123,6 +145,7 @@ pub fn recover_full_snapshot(
         storage_dir,
         this_peer_id,
         is_distributed,
+        encryption,
     );
 
     let alias_path = Path::new(storage_dir).join(ALIASES_PATH);
@@ -139,3 +162,35 @@ pub fn recover_full_snapshot(
     remove_dir_all(&snapshot_temp_path).unwrap();
     recovered_collection
 }
+
+/// Decrypt the snapshot into `decrypted_path`, if it is encrypted
+///
+/// Returns the path of the decrypted snapshot, which is deleted once dropped.
+fn decrypt_if_encrypted(
+    encryption: Option<&SnapshotEncryption>,
+    snapshot_path: &Path,
+    decrypted_path: &Path,
+) -> Option<TempPath> {
+    if !snapshot_encryption::is_encrypted(snapshot_path).unwrap() {
+        return None;
+    }
+
+    let encryption = encryption.unwrap_or_else(|| {
+        panic!(
+            "Snapshot {} is encrypted, but no snapshot encryption keys are configured",
+            snapshot_path.display(),
+        )
+    });
+
+    info!("Decrypting snapshot {}", snapshot_path.display());
+
+    let decrypted_path = TempPath::from_path(decrypted_path);
+    if let Err(err) = encryption.decrypt_file(snapshot_path, &decrypted_path) {
+        panic!(
+            "Failed to decrypt snapshot {}: {err}",
+            snapshot_path.display(),
+        );
+    }
+
+    Some(decrypted_path)
+}
@@ -1,19 +1,23 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use api::grpc::qdrant::points_selector::PointsSelectorOneOf;
 use api::grpc::qdrant::points_server::Points;
+use api::grpc::qdrant::points_update_operation::Operation;
 use api::grpc::qdrant::{
     ClearPayloadPoints, CountPoints, CountResponse, CreateFieldIndexCollection,
     DeleteFieldIndexCollection, DeletePayloadPoints, DeletePointVectors, DeletePoints,
     DiscoverBatchPoints, DiscoverBatchResponse, DiscoverPoints, DiscoverResponse, GetPoints,
-    GetResponse, PointsOperationResponse, RecommendBatchPoints, RecommendBatchResponse,
-    RecommendGroupsResponse, RecommendPointGroups, RecommendPoints, RecommendResponse,
-    ScrollPoints, ScrollResponse, SearchBatchPoints, SearchBatchResponse, SearchGroupsResponse,
-    SearchPointGroups, SearchPoints, SearchResponse, SetPayloadPoints, UpdateBatchPoints,
-    UpdateBatchResponse, UpdatePointVectors, UpsertPoints,
+    GetResponse, PointsOperationResponse, PointsSelector, RecommendBatchPoints,
+    RecommendBatchResponse, RecommendGroupsResponse, RecommendPointGroups, RecommendPoints,
+    RecommendResponse, ScrollPoints, ScrollResponse, SearchBatchPoints, SearchBatchResponse,
+    SearchGroupsResponse, SearchPointGroups, SearchPoints, SearchResponse, SetPayloadPoints,
+    UpdateBatchPoints, UpdateBatchResponse, UpdatePointVectors, UpsertPoints,
 };
 use collection::operations::types::CoreSearchRequest;
+use storage::content_manager::conversions::error_to_status;
 use storage::dispatcher::Dispatcher;
+use storage::rbac::Access;
 use tonic::{Request, Response, Status};
 
 use super::points_common::{
@@ -21,20 +25,60 @@ use super::points_common::{
     update_vectors,
 };
 use super::validate;
+use crate::common::rate_limiting::{RateLimiter, SearchSize};
 use crate::tonic::api::points_common::{
     clear_payload, convert_shard_selector_for_read, core_search_batch, count, create_field_index,
     delete, delete_field_index, delete_payload, get, overwrite_payload, recommend, recommend_batch,
     scroll, search, set_payload, upsert,
 };
-use crate::tonic::auth::extract_access;
+use crate::tonic::auth::{extract_access, extract_limits};
 
 pub struct PointsService {
     dispatcher: Arc<Dispatcher>,
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl PointsService {
-    pub fn new(dispatcher: Arc<Dispatcher>) -> Self {
-        Self { dispatcher }
+    pub fn new(dispatcher: Arc<Dispatcher>, rate_limiter: Option<Arc<RateLimiter>>) -> Self {
+        Self {
+            dispatcher,
+            rate_limiter,
+        }
+    }
+
+    /// Check limits of searches to the collection
+    fn check_searches<R>(
+        &self,
+        request: &Request<R>,
+        collection: &str,
+        searches: impl IntoIterator<Item = SearchSize>,
+    ) -> Result<(), Status> {
+        extract_limits(&self.rate_limiter, request)
+            .check_searches(collection, searches)
+            .map_err(error_to_status)
+    }
+
+    /// Check limits of points written to the collection
+    async fn check_writes<R>(
+        &self,
+        request: &Request<R>,
+        collection: &str,
+        points_count: usize,
+    ) -> Result<(), Status> {
+        let toc = self.dispatcher.toc(&Access::full("For collection quota"));
+        extract_limits(&self.rate_limiter, request)
+            .check_writes(toc, collection, points_count)
+            .await
+            .map_err(error_to_status)
+    }
+}
+
+/// Number of points selected by the selector, a filter counts as a single point
+fn selected_points_count(selector: Option<&PointsSelector>) -> usize {
+    match selector.and_then(|selector| selector.points_selector_one_of.as_ref()) {
+        Some(PointsSelectorOneOf::Points(points)) => points.ids.len(),
+        Some(PointsSelectorOneOf::Filter(_)) => 1,
+        None => 0,
     }
 }
 
@@ -46,6 +90,10 @@ impl Points for PointsService {
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
 
+        let points_count = request.get_ref().points.len();
+        self.check_writes(&request, &request.get_ref().collection_name, points_count)
+            .await?;
+
         let access = extract_access(&mut request);
 
         upsert(
@@ -98,6 +146,10 @@ impl Points for PointsService {
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
 
+        let points_count = request.get_ref().points.len();
+        self.check_writes(&request, &request.get_ref().collection_name, points_count)
+            .await?;
+
         let access = extract_access(&mut request);
 
         update_vectors(
@@ -136,6 +188,10 @@ impl Points for PointsService {
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
 
+        let points_count = selected_points_count(request.get_ref().points_selector.as_ref());
+        self.check_writes(&request, &request.get_ref().collection_name, points_count)
+            .await?;
+
         let access = extract_access(&mut request);
 
         set_payload(
@@ -155,6 +211,10 @@ impl Points for PointsService {
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
 
+        let points_count = selected_points_count(request.get_ref().points_selector.as_ref());
+        self.check_writes(&request, &request.get_ref().collection_name, points_count)
+            .await?;
+
         let access = extract_access(&mut request);
 
         overwrite_payload(
@@ -212,6 +272,31 @@ impl Points for PointsService {
     ) -> Result<Response<UpdateBatchResponse>, Status> {
         validate(request.get_ref())?;
 
+        let points_count = request
+            .get_ref()
+            .operations
+            .iter()
+            .filter_map(|operation| operation.operation.as_ref())
+            .map(|operation| match operation {
+                Operation::Upsert(upsert) => upsert.points.len(),
+                Operation::SetPayload(set_payload) => {
+                    selected_points_count(set_payload.points_selector.as_ref())
+                }
+                Operation::OverwritePayload(overwrite_payload) => {
+                    selected_points_count(overwrite_payload.points_selector.as_ref())
+                }
+                Operation::UpdateVectors(update_vectors) => update_vectors.points.len(),
+                Operation::DeleteDeprecated(_)
+                | Operation::DeletePayload(_)
+                | Operation::ClearPayloadDeprecated(_)
+                | Operation::DeleteVectors(_)
+                | Operation::DeletePoints(_)
+                | Operation::ClearPayload(_) => 0,
+            })
+            .sum();
+        self.check_writes(&request, &request.get_ref().collection_name, points_count)
+            .await?;
+
         let access = extract_access(&mut request);
 
         update_batch(
@@ -267,6 +352,11 @@ impl Points for PointsService {
         mut request: Request<SearchPoints>,
     ) -> Result<Response<SearchResponse>, Status> {
         validate(request.get_ref())?;
+        self.check_searches(
+            &request,
+            &request.get_ref().collection_name,
+            [SearchSize::from(request.get_ref())],
+        )?;
         let access = extract_access(&mut request);
         search(
             self.dispatcher.toc(&access),
@@ -283,6 +373,9 @@ impl Points for PointsService {
     ) -> Result<Response<SearchBatchResponse>, Status> {
         validate(request.get_ref())?;
 
+        let searches = request.get_ref().search_points.iter().map(SearchSize::from);
+        self.check_searches(&request, &request.get_ref().collection_name, searches)?;
+
         let access = extract_access(&mut request);
 
         let SearchBatchPoints {
@@ -321,6 +414,11 @@ impl Points for PointsService {
         mut request: Request<SearchPointGroups>,
     ) -> Result<Response<SearchGroupsResponse>, Status> {
         validate(request.get_ref())?;
+        self.check_searches(
+            &request,
+            &request.get_ref().collection_name,
+            [SearchSize::from(request.get_ref())],
+        )?;
         let access = extract_access(&mut request);
         search_groups(
             self.dispatcher.toc(&access),
@@ -353,6 +451,11 @@ impl Points for PointsService {
         mut request: Request<RecommendPoints>,
     ) -> Result<Response<RecommendResponse>, Status> {
         validate(request.get_ref())?;
+        self.check_searches(
+            &request,
+            &request.get_ref().collection_name,
+            [SearchSize::from(request.get_ref())],
+        )?;
         let access = extract_access(&mut request);
         recommend(self.dispatcher.toc(&access), request.into_inner(), access).await
     }
@@ -362,6 +465,12 @@ impl Points for PointsService {
         mut request: Request<RecommendBatchPoints>,
     ) -> Result<Response<RecommendBatchResponse>, Status> {
         validate(request.get_ref())?;
+        let searches = request
+            .get_ref()
+            .recommend_points
+            .iter()
+            .map(SearchSize::from);
+        self.check_searches(&request, &request.get_ref().collection_name, searches)?;
         let access = extract_access(&mut request);
         let RecommendBatchPoints {
             collection_name,
@@ -386,6 +495,12 @@ impl Points for PointsService {
     ) -> Result<Response<RecommendGroupsResponse>, Status> {
         validate(request.get_ref())?;
 
+        self.check_searches(
+            &request,
+            &request.get_ref().collection_name,
+            [SearchSize::from(request.get_ref())],
+        )?;
+
         let access = extract_access(&mut request);
 
         recommend_groups(self.dispatcher.toc(&access), request.into_inner(), access).await
@@ -397,6 +512,12 @@ impl Points for PointsService {
     ) -> Result<Response<DiscoverResponse>, Status> {
         validate(request.get_ref())?;
 
+        self.check_searches(
+            &request,
+            &request.get_ref().collection_name,
+            [SearchSize::from(request.get_ref())],
+        )?;
+
         let access = extract_access(&mut request);
 
         discover(self.dispatcher.toc(&access), request.into_inner(), access).await
@@ -408,6 +529,13 @@ impl Points for PointsService {
     ) -> Result<Response<DiscoverBatchResponse>, Status> {
         validate(request.get_ref())?;
 
+        let searches = request
+            .get_ref()
+            .discover_points
+            .iter()
+            .map(SearchSize::from);
+        self.check_searches(&request, &request.get_ref().collection_name, searches)?;
+
         let access = extract_access(&mut request);
 
         let DiscoverBatchPoints {
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 use std::time::Instant;
 
+use api::grpc::conversions::try_date_time_from_proto;
 use api::grpc::qdrant::shard_snapshots_server::ShardSnapshots;
 use api::grpc::qdrant::snapshots_server::Snapshots;
 use api::grpc::qdrant::{
@@ -10,6 +11,7 @@ use api::grpc::qdrant::{
     ListShardSnapshotsRequest, ListSnapshotsRequest, ListSnapshotsResponse,
     RecoverShardSnapshotRequest, RecoverSnapshotResponse,
 };
+use collection::wal_archive::RecoveryTarget;
 use storage::content_manager::conversions::error_to_status;
 use storage::content_manager::snapshots::{
     do_create_full_snapshot, do_delete_collection_snapshot, do_delete_full_snapshot,
@@ -192,6 +194,7 @@ impl ShardSnapshots for ShardSnapshotsService {
             access,
             request.collection_name,
             request.shard_id,
+            None,
         )
         .await
         .map_err(error_to_status)?;
@@ -262,6 +265,12 @@ impl ShardSnapshots for ShardSnapshotsService {
 
         let timing = Instant::now();
 
+        let recover_until = request
+            .recover_until
+            .map(try_date_time_from_proto)
+            .transpose()?
+            .map(|date_time| RecoveryTarget::Timestamp(date_time.0));
+
         common::snapshots::recover_shard_snapshot(
             self.toc.clone(),
             access,
@@ -272,6 +281,7 @@ impl ShardSnapshots for ShardSnapshotsService {
             request.checksum,
             self.http_client.clone(),
             request.api_key,
+            recover_until,
         )
         .await
         .map_err(error_to_status)?;
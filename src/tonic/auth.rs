@@ -2,25 +2,35 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use futures::future::BoxFuture;
+use futures::{stream, StreamExt as _};
 use storage::content_manager::conversions::error_to_status;
 use storage::rbac::Access;
 use tonic::body::BoxBody;
-use tonic::Status;
+use tonic::codegen::Body as _;
+use tonic::transport::server::{TcpConnectInfo, TlsConnectInfo};
+use tonic::transport::Body;
+use tonic::{Code, Status};
 use tower::{Layer, Service};
 
-use crate::common::auth::{AuthError, AuthKeys};
+use crate::common::audit::{self, Api, AuditLogger, AuditedRequest};
+use crate::common::auth::{AuthError, AuthKeys, Credentials};
+use crate::common::rate_limiting::{RateLimiter, RequestLimits};
 
-type Request = tonic::codegen::http::Request<tonic::transport::Body>;
+type Request = tonic::codegen::http::Request<Body>;
 type Response = tonic::codegen::http::Response<BoxBody>;
 
 #[derive(Clone)]
 pub struct AuthMiddleware<S> {
     auth_keys: Arc<AuthKeys>,
+    audit_logger: Option<Arc<AuditLogger>>,
     service: S,
 }
 
-async fn check(auth_keys: Arc<AuthKeys>, mut req: Request) -> Result<Request, Status> {
-    let access = auth_keys
+async fn check(
+    auth_keys: Arc<AuthKeys>,
+    mut req: Request,
+) -> Result<(Request, Credentials), Status> {
+    let (access, credentials) = auth_keys
         .validate_request(|key| req.headers().get(key).and_then(|val| val.to_str().ok()))
         .await
         .map_err(|e| match e {
@@ -34,8 +44,67 @@ async fn check(auth_keys: Arc<AuthKeys>, mut req: Request) -> Result<Request, St
         _previous.is_none(),
         "Previous access object should not exist in the request"
     );
+    req.extensions_mut()
+        .insert::<Credentials>(credentials.clone());
 
-    Ok(req)
+    Ok((req, credentials))
+}
+
+/// Start auditing the request, if its operation is audited
+///
+/// The collection name is not known yet, it is read from the body once the request is
+/// authenticated, see [`read_collection`].
+fn audit_request(audit_logger: &AuditLogger, req: &Request) -> Option<AuditedRequest> {
+    let path = req.uri().path().to_string();
+
+    let kind = audit::grpc_operation_kind(&path);
+    if !audit_logger.is_audited(kind) {
+        return None;
+    }
+
+    let client = req
+        .extensions()
+        .get::<TcpConnectInfo>()
+        .or_else(|| {
+            req.extensions()
+                .get::<TlsConnectInfo<TcpConnectInfo>>()
+                .map(|info| info.get_ref())
+        })
+        .and_then(|info| info.remote_addr())
+        .map(|addr| addr.ip());
+
+    Some(AuditedRequest::new(Api::Grpc, client, None, kind, path))
+}
+
+/// Read the collection name of an audited request from its body
+///
+/// Only the leading bytes of the body are buffered, the rest is streamed through untouched.
+async fn read_collection(
+    req: Request,
+    audited_request: &mut AuditedRequest,
+) -> Result<Request, Status> {
+    if !audit::grpc_has_collection(req.uri().path()) {
+        return Ok(req);
+    }
+
+    let (parts, mut body) = req.into_parts();
+
+    let mut chunks = Vec::new();
+    let mut prefix = Vec::with_capacity(audit::GRPC_COLLECTION_PREFIX_LEN);
+    while prefix.len() < audit::GRPC_COLLECTION_PREFIX_LEN {
+        let Some(chunk) = body.data().await else {
+            break;
+        };
+        let chunk = chunk.map_err(|err| Status::internal(err.to_string()))?;
+        let missing = audit::GRPC_COLLECTION_PREFIX_LEN - prefix.len();
+        prefix.extend_from_slice(&chunk[..chunk.len().min(missing)]);
+        chunks.push(Ok(chunk));
+    }
+
+    audited_request.set_collection(audit::grpc_collection(&prefix).map(ToString::to_string));
+
+    let body = Body::wrap_stream(stream::iter(chunks).chain(body));
+    Ok(Request::from_parts(parts, body))
 }
 
 impl<S> Service<Request> for AuthMiddleware<S>
@@ -53,12 +122,45 @@ where
 
     fn call(&mut self, request: Request) -> Self::Future {
         let auth_keys = self.auth_keys.clone();
+        let audit_logger = self.audit_logger.clone();
         let mut service = self.service.clone();
         Box::pin(async move {
-            match check(auth_keys, request).await {
-                Ok(req) => service.call(req).await,
-                Err(e) => Ok(e.to_http()),
+            let mut audit = audit_logger
+                .as_deref()
+                .and_then(|audit_logger| audit_request(audit_logger, &request));
+
+            let (result, credentials) = match check(auth_keys, request).await {
+                Ok((request, credentials)) => {
+                    // Body is only read for authenticated requests
+                    let request = match &mut audit {
+                        Some(audited_request) => read_collection(request, audited_request).await,
+                        None => Ok(request),
+                    };
+                    let result = match request {
+                        Ok(request) => service.call(request).await,
+                        Err(e) => Ok(e.to_http()),
+                    };
+                    (result, Some(credentials))
+                }
+                Err(e) => (Ok(e.to_http()), None),
+            };
+
+            if let (Some(audit_logger), Some(audited_request)) = (audit_logger, audit) {
+                // Successful responses carry the status in trailers, which are not inspected
+                let code = match &result {
+                    Ok(response) => Status::from_header_map(response.headers())
+                        .map_or(Code::Ok, |status| status.code()),
+                    Err(_) => Code::Unknown,
+                };
+                audited_request.finish(
+                    &audit_logger,
+                    credentials.as_ref(),
+                    format!("{code:?}"),
+                    code == Code::Ok,
+                );
             }
+
+            result
         })
     }
 }
@@ -66,12 +168,14 @@ where
 #[derive(Clone)]
 pub struct AuthLayer {
     auth_keys: Arc<AuthKeys>,
+    audit_logger: Option<Arc<AuditLogger>>,
 }
 
 impl AuthLayer {
-    pub fn new(auth_keys: AuthKeys) -> Self {
+    pub fn new(auth_keys: AuthKeys, audit_logger: Option<Arc<AuditLogger>>) -> Self {
         Self {
             auth_keys: Arc::new(auth_keys),
+            audit_logger,
         }
     }
 }
@@ -82,6 +186,7 @@ impl<S> Layer<S> for AuthLayer {
     fn layer(&self, service: S) -> Self::Service {
         Self::Service {
             auth_keys: self.auth_keys.clone(),
+            audit_logger: self.audit_logger.clone(),
             service,
         }
     }
@@ -92,3 +197,10 @@ pub fn extract_access<R>(req: &mut tonic::Request<R>) -> Access {
         Access::full("All requests have full by default access when API key is not configured")
     })
 }
+
+pub fn extract_limits<R>(
+    rate_limiter: &Option<Arc<RateLimiter>>,
+    req: &tonic::Request<R>,
+) -> RequestLimits {
+    RequestLimits::new(rate_limiter.clone(), req.extensions().get::<Credentials>())
+}
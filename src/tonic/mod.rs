@@ -24,23 +24,25 @@ use ::api::grpc::qdrant::qdrant_server::{Qdrant, QdrantServer};
 use ::api::grpc::qdrant::shard_snapshots_server::ShardSnapshotsServer;
 use ::api::grpc::qdrant::snapshots_server::SnapshotsServer;
 use ::api::grpc::qdrant::{
-    GetConsensusCommitRequest, GetConsensusCommitResponse, HealthCheckReply, HealthCheckRequest,
-    WaitOnConsensusCommitRequest, WaitOnConsensusCommitResponse,
+    GetConsensusCommitRequest, GetConsensusCommitResponse, GetPeerLoadRequest, GetPeerLoadResponse,
+    HealthCheckReply, HealthCheckRequest, WaitOnConsensusCommitRequest,
+    WaitOnConsensusCommitResponse,
 };
 use ::api::grpc::QDRANT_DESCRIPTOR_SET;
 use storage::content_manager::consensus_manager::ConsensusStateRef;
 use storage::content_manager::toc::TableOfContent;
 use storage::dispatcher::Dispatcher;
-use storage::rbac::Access;
 use tokio::runtime::Handle;
 use tokio::signal;
 use tonic::codec::CompressionEncoding;
 use tonic::transport::{Server, ServerTlsConfig};
 use tonic::{Request, Response, Status};
 
+use crate::common::audit::AuditLogger;
 use crate::common::auth::AuthKeys;
 use crate::common::helpers;
 use crate::common::http_client::HttpClient;
+use crate::common::rate_limiting::RateLimiter;
 use crate::common::telemetry_ops::requests_telemetry::TonicTelemetryCollector;
 use crate::settings::Settings;
 use crate::tonic::api::collections_api::CollectionsService;
@@ -85,13 +87,20 @@ pub struct QdrantInternalService {
     settings: Settings,
     /// Consensus state
     consensus_state: ConsensusStateRef,
+    /// Table of contents
+    toc: Arc<TableOfContent>,
 }
 
 impl QdrantInternalService {
-    fn new(settings: Settings, consensus_state: ConsensusStateRef) -> Self {
+    fn new(
+        settings: Settings,
+        consensus_state: ConsensusStateRef,
+        toc: Arc<TableOfContent>,
+    ) -> Self {
         Self {
             settings,
             consensus_state,
+            toc,
         }
     }
 }
@@ -124,6 +133,14 @@ impl QdrantInternal for QdrantInternalService {
             .is_ok();
         Ok(Response::new(WaitOnConsensusCommitResponse { ok }))
     }
+
+    async fn get_peer_load(
+        &self,
+        _: tonic::Request<GetPeerLoadRequest>,
+    ) -> Result<Response<GetPeerLoadResponse>, Status> {
+        let load = self.toc.peer_load().await;
+        Ok(Response::new(load.into()))
+    }
 }
 
 #[cfg(not(unix))]
@@ -143,12 +160,29 @@ async fn wait_stop_signal(for_what: &str) {
     }
 }
 
+/// Span of an incoming request, continuing the trace of the caller
+///
+/// Frequent Raft messages are not traced.
+fn trace_span(request: &tonic::codegen::http::Request<()>) -> tracing::Span {
+    let path = request.uri().path();
+    if path.starts_with("/qdrant.Raft/") {
+        return tracing::Span::none();
+    }
+
+    crate::tracing::otlp::server_span("grpc", path, |key| {
+        request.headers().get(key)?.to_str().ok()
+    })
+}
+
 pub fn init(
     dispatcher: Arc<Dispatcher>,
     telemetry_collector: Arc<parking_lot::Mutex<TonicTelemetryCollector>>,
     settings: Settings,
     grpc_port: u16,
     runtime: Handle,
+    auth_keys: Option<AuthKeys>,
+    audit_logger: Option<Arc<AuditLogger>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
 ) -> io::Result<()> {
     runtime.block_on(async {
         let socket =
@@ -157,7 +191,7 @@ pub fn init(
         let qdrant_service = QdrantService::default();
         let health_service = HealthService::default();
         let collections_service = CollectionsService::new(dispatcher.clone());
-        let points_service = PointsService::new(dispatcher.clone());
+        let points_service = PointsService::new(dispatcher.clone(), rate_limiter);
         let snapshot_service = SnapshotsService::new(dispatcher.clone());
 
         // Only advertise the public services. By default, all services in QDRANT_DESCRIPTOR_SET
@@ -188,21 +222,19 @@ pub fn init(
             log::info!("TLS disabled for gRPC API");
         }
 
+        if settings.logger.otlp.is_enabled() {
+            server = server.trace_fn(trace_span);
+        }
+
+        let auth_layer = auth_keys.map(|auth_keys| auth::AuthLayer::new(auth_keys, audit_logger));
+
         // The stack of middleware that our service will be wrapped in
         let middleware_layer = tower::ServiceBuilder::new()
             .layer(logging::LoggingMiddlewareLayer::new())
             .layer(tonic_telemetry::TonicTelemetryLayer::new(
                 telemetry_collector,
             ))
-            .option_layer({
-                AuthKeys::try_create(
-                    &settings.service,
-                    dispatcher
-                        .toc(&Access::full("For tonic auth middleware"))
-                        .clone(),
-                )
-                .map(auth::AuthLayer::new)
-            })
+            .option_layer(auth_layer)
             .into_inner();
 
         server
@@ -265,6 +297,7 @@ pub fn init_internal(
     use crate::tonic::api::raft_api::RaftService;
 
     let http_client = HttpClient::from_settings(&settings)?;
+    let trace_requests = settings.logger.otlp.is_enabled();
 
     runtime
         .block_on(async {
@@ -272,7 +305,7 @@ pub fn init_internal(
 
             let qdrant_service = QdrantService::default();
             let qdrant_internal_service =
-                QdrantInternalService::new(settings, consensus_state.clone());
+                QdrantInternalService::new(settings, consensus_state.clone(), toc.clone());
             let collections_internal_service = CollectionsInternalService::new(toc.clone());
             let points_internal_service = PointsInternalService::new(toc.clone());
             let shard_snapshots_service = ShardSnapshotsService::new(toc.clone(), http_client);
@@ -298,6 +331,10 @@ pub fn init_internal(
                 log::info!("TLS disabled for internal gRPC API");
             };
 
+            if trace_requests {
+                server = server.trace_fn(trace_span);
+            }
+
             // The stack of middleware that our service will be wrapped in
             let middleware_layer = tower::ServiceBuilder::new()
                 .layer(logging::LoggingMiddlewareLayer::new())
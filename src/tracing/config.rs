@@ -12,6 +12,8 @@ pub struct LoggerConfig {
     pub default: default::Config,
     #[serde(default)]
     pub on_disk: on_disk::Config,
+    #[serde(default)]
+    pub otlp: otlp::Config,
 }
 
 impl LoggerConfig {
@@ -32,6 +34,7 @@ impl LoggerConfig {
     pub fn merge(&mut self, other: Self) {
         self.default.merge(other.default);
         self.on_disk.merge(other.on_disk);
+        self.otlp.merge(other.otlp);
     }
 }
 
@@ -4,6 +4,7 @@ pub mod config;
 pub mod default;
 pub mod handle;
 pub mod on_disk;
+pub mod otlp;
 
 #[cfg(test)]
 mod test;
@@ -50,6 +51,16 @@ pub fn setup(mut config: config::LoggerConfig) -> anyhow::Result<LoggerHandle> {
     let (default_logger, default_logger_handle) = reload::Layer::new(default_logger);
     let reg = reg.with(default_logger);
 
+    // Use `opentelemetry` feature to export spans to an OTLP collector
+    #[cfg(feature = "opentelemetry")]
+    let otlp_logger = otlp::new_logger(&mut config.otlp);
+
+    #[cfg(not(feature = "opentelemetry"))]
+    if config.otlp.enabled.unwrap_or_default() {
+        eprintln!("OTLP trace export requires the `opentelemetry` feature!");
+        config.otlp.enabled = Some(false);
+    }
+
     let logger_handle = LoggerHandle::new(config, default_logger_handle, on_disk_logger_handle);
 
     // Use `console` or `console-subscriber` feature to enable `console-subscriber`
@@ -77,6 +88,9 @@ pub fn setup(mut config: config::LoggerConfig) -> anyhow::Result<LoggerHandle> {
         ),
     );
 
+    #[cfg(feature = "opentelemetry")]
+    let reg = reg.with(otlp_logger);
+
     tracing::subscriber::set_global_default(reg)?;
     tracing_log::LogTracer::init()?;
 
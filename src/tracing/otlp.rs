@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "opentelemetry")]
+use tracing_subscriber::prelude::*;
+#[cfg(feature = "opentelemetry")]
+use tracing_subscriber::{filter, registry};
+
+#[cfg(feature = "opentelemetry")]
+use super::*;
+
+const DEFAULT_SERVICE_NAME: &str = "qdrant";
+
+const DEFAULT_GRPC_ENDPOINT: &str = "http://localhost:4317";
+
+const DEFAULT_HTTP_ENDPOINT: &str = "http://localhost:4318";
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    pub enabled: Option<bool>,
+    pub protocol: Option<Protocol>,
+    pub endpoint: Option<String>,
+    pub service_name: Option<String>,
+    pub log_level: Option<String>,
+}
+
+impl Config {
+    pub fn merge(&mut self, other: Self) {
+        self.enabled = other.enabled.or(self.enabled.take());
+        self.protocol = other.protocol.or(self.protocol.take());
+        self.endpoint = other.endpoint.or(self.endpoint.take());
+        self.service_name = other.service_name.or(self.service_name.take());
+        self.log_level = other.log_level.or(self.log_level.take());
+    }
+
+    /// Whether spans are exported, which requires the `opentelemetry` feature
+    pub fn is_enabled(&self) -> bool {
+        cfg!(feature = "opentelemetry") && self.enabled.unwrap_or_default()
+    }
+
+    pub fn endpoint(&self) -> &str {
+        match (&self.endpoint, self.protocol.unwrap_or_default()) {
+            (Some(endpoint), _) => endpoint,
+            (None, Protocol::Grpc) => DEFAULT_GRPC_ENDPOINT,
+            (None, Protocol::Http) => DEFAULT_HTTP_ENDPOINT,
+        }
+    }
+
+    pub fn service_name(&self) -> &str {
+        self.service_name.as_deref().unwrap_or(DEFAULT_SERVICE_NAME)
+    }
+}
+
+/// Protocol to export spans to the OTLP collector with
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    #[default]
+    Grpc,
+    Http,
+}
+
+#[cfg(feature = "opentelemetry")]
+#[rustfmt::skip] // `rustfmt` formats this into unreadable single line
+pub type Logger<S> = filter::Filtered<
+    Option<Layer<S>>,
+    filter::EnvFilter,
+    S,
+>;
+
+#[cfg(feature = "opentelemetry")]
+pub type Layer<S> = tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry::sdk::trace::Tracer>;
+
+/// Runtime of the batch exporter, so spans are exported independently of the service runtimes,
+/// which are not yet created when logging is set up
+#[cfg(feature = "opentelemetry")]
+static RUNTIME: std::sync::OnceLock<tokio::runtime::Runtime> = std::sync::OnceLock::new();
+
+#[cfg(feature = "opentelemetry")]
+pub fn new_logger<S>(config: &mut Config) -> Logger<S>
+where
+    S: tracing::Subscriber + for<'span> registry::LookupSpan<'span>,
+{
+    let layer = match new_layer(config) {
+        Ok(layer) => layer,
+        Err(err) => {
+            eprintln!(
+                "failed to enable OTLP trace export to {}: {err}",
+                config.endpoint(),
+            );
+
+            config.enabled = Some(false);
+            None
+        }
+    };
+
+    let filter = new_filter(config);
+    layer.with_filter(filter)
+}
+
+#[cfg(feature = "opentelemetry")]
+pub fn new_layer<S>(config: &Config) -> anyhow::Result<Option<Layer<S>>>
+where
+    S: tracing::Subscriber + for<'span> registry::LookupSpan<'span>,
+{
+    use opentelemetry_otlp::WithExportConfig as _;
+
+    if !config.is_enabled() {
+        return Ok(None);
+    }
+
+    // Read and write W3C `traceparent` and `tracestate` headers
+    opentelemetry::global::set_text_map_propagator(
+        opentelemetry::sdk::propagation::TraceContextPropagator::new(),
+    );
+
+    let exporter: opentelemetry_otlp::SpanExporterBuilder =
+        match config.protocol.unwrap_or_default() {
+            Protocol::Grpc => opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(config.endpoint())
+                .into(),
+            Protocol::Http => opentelemetry_otlp::new_exporter()
+                .http()
+                .with_endpoint(config.endpoint())
+                .into(),
+        };
+
+    let resource = opentelemetry::sdk::Resource::new([opentelemetry::KeyValue::new(
+        "service.name",
+        config.service_name().to_string(),
+    )]);
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("otlp-exporter")
+        .enable_all()
+        .build()?;
+    let runtime = RUNTIME.get_or_init(|| runtime);
+    let _guard = runtime.enter();
+
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(exporter)
+        .with_trace_config(opentelemetry::sdk::trace::config().with_resource(resource))
+        .install_batch(opentelemetry::runtime::Tokio)?;
+
+    let layer = tracing_opentelemetry::layer().with_tracer(tracer);
+
+    Ok(Some(layer))
+}
+
+#[cfg(feature = "opentelemetry")]
+pub fn new_filter(config: &Config) -> filter::EnvFilter {
+    filter(config.log_level.as_deref().unwrap_or(""))
+}
+
+/// Export remaining spans, before the process exits
+pub fn shutdown() {
+    #[cfg(feature = "opentelemetry")]
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+/// Create a span for an incoming request, continuing the trace of the W3C `traceparent` header
+///
+/// The header is read with `header`, if spans are exported.
+pub fn server_span<'a>(
+    protocol: &'static str,
+    name: &str,
+    header: impl Fn(&str) -> Option<&'a str>,
+) -> tracing::Span {
+    let span = tracing::info_span!("request", otel.name = name, otel.kind = "server", protocol,);
+
+    #[cfg(feature = "opentelemetry")]
+    {
+        use tracing_opentelemetry::OpenTelemetrySpanExt as _;
+
+        let headers: std::collections::HashMap<String, String> =
+            opentelemetry::global::get_text_map_propagator(|propagator| {
+                propagator
+                    .fields()
+                    .filter_map(|field| Some((field.to_string(), header(field)?.to_string())))
+                    .collect()
+            });
+
+        let context = opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.extract(&headers)
+        });
+
+        span.set_parent(context);
+    }
+
+    #[cfg(not(feature = "opentelemetry"))]
+    let _ = header;
+
+    span
+}
@@ -16,6 +16,14 @@ fn deseriailze_logger_config() {
             "log_file": "/logs/qdrant",
             "log_level": "tracing",
             "span_events": ["new", "close"],
+        },
+
+        "otlp": {
+            "enabled": true,
+            "protocol": "http",
+            "endpoint": "http://collector:4318",
+            "service_name": "qdrant-node",
+            "log_level": "debug",
         }
     });
 
@@ -40,6 +48,14 @@ fn deseriailze_logger_config() {
                 config::SpanEvent::Close,
             ])),
         },
+
+        otlp: otlp::Config {
+            enabled: Some(true),
+            protocol: Some(otlp::Protocol::Http),
+            endpoint: Some("http://collector:4318".into()),
+            service_name: Some("qdrant-node".into()),
+            log_level: Some("debug".into()),
+        },
     };
 
     assert_eq!(config, expected);
@@ -57,6 +73,12 @@ fn deserialize_config_with_empty_on_disk() {
     assert_eq!(config, LoggerConfig::default());
 }
 
+#[test]
+fn deserialize_config_with_empty_otlp() {
+    let config = deserialize_config(json!({ "otlp": {} }));
+    assert_eq!(config, LoggerConfig::default());
+}
+
 #[test]
 fn deseriailze_config_with_explicit_nulls() {
     let json = json!({
@@ -69,6 +91,14 @@ fn deseriailze_config_with_explicit_nulls() {
             "log_file": null,
             "log_level": null,
             "span_events": null,
+        },
+
+        "otlp": {
+            "enabled": null,
+            "protocol": null,
+            "endpoint": null,
+            "service_name": null,
+            "log_level": null,
         }
     });
 